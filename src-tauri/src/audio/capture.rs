@@ -1,4 +1,8 @@
+use crate::audio::processing;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Shared audio buffer that the cpal callback writes to.
 /// This is Send+Sync and can be stored in Tauri state.
@@ -27,3 +31,392 @@ impl AudioBuffer {
         std::mem::take(&mut *buf)
     }
 }
+
+/// The input device went away during capture.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceChange {
+    pub error: String,
+    /// Device capture continues on, or `None` while no input could be opened.
+    pub device: Option<String>,
+}
+
+/// The input a capture runs on.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureInfo {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub device: String,
+    /// A Bluetooth headset in its low-rate hands-free (HFP) profile.
+    pub degraded: bool,
+}
+
+/// Name fragments of Bluetooth headsets, for platforms that don't report
+/// how a device is connected. A plain "headset" may well be wired or USB.
+const BLUETOOTH_HINTS: [&str; 6] = [
+    "bluetooth",
+    "airpods",
+    "hands-free",
+    "handsfree",
+    "buds",
+    "hfp",
+];
+/// Name fragments of microphones built into the computer.
+const BUILT_IN_HINTS: [&str; 5] = [
+    "built-in",
+    "macbook",
+    "internal",
+    "microphone array",
+    "integrated",
+];
+/// HFP capture runs at 8, 16 or (AirPods) 24 kHz.
+const HFP_MAX_RATE: u32 = 24_000;
+
+/// Whether an input is a Bluetooth device: by its transport type where the
+/// platform reports one, otherwise by its name.
+fn looks_bluetooth(name: &str) -> bool {
+    crate::audio::transport::is_bluetooth(name).unwrap_or_else(|| {
+        let name = name.to_lowercase();
+        BLUETOOTH_HINTS.iter().any(|hint| name.contains(hint))
+    })
+}
+
+/// Whether an input is a Bluetooth headset that dropped to the hands-free
+/// profile, where recognition quality suffers.
+pub fn is_degraded_bluetooth(name: &str, sample_rate: u32) -> bool {
+    sample_rate <= HFP_MAX_RATE && looks_bluetooth(name)
+}
+
+/// Index of the input to use instead of a Bluetooth default: a built-in
+/// microphone if one is recognised, else any non-Bluetooth input.
+fn built_in_choice(names: &[String]) -> Option<usize> {
+    let is_built_in = |name: &String| {
+        let name = name.to_lowercase();
+        BUILT_IN_HINTS.iter().any(|hint| name.contains(hint))
+    };
+    names
+        .iter()
+        .position(|n| is_built_in(n) && !looks_bluetooth(n))
+        .or_else(|| names.iter().position(|n| !looks_bluetooth(n)))
+}
+
+/// How often the capture thread checks for stop and stream errors.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Delay between attempts to reopen an input after a failed failover.
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Open the default input device and start writing interleaved f32 samples
+/// into `samples` on a dedicated thread, which keeps the stream alive until
+/// `stop` receives (or is dropped). Blocks until the stream is running.
+///
+/// With `prefer_built_in`, a Bluetooth default input is skipped for the
+/// built-in microphone, so the headset stays in its high-quality output
+/// profile instead of switching to hands-free.
+///
+/// If the device disconnects mid-capture the thread fails over to the
+/// current default input, converting to the original rate and channel count
+/// so the buffer stays consistent, and reports it through `on_change`.
+pub fn start(
+    samples: Arc<Mutex<Vec<f32>>>,
+    stop: Receiver<()>,
+    prefer_built_in: bool,
+    on_change: impl Fn(DeviceChange) + Send + 'static,
+) -> Result<CaptureInfo, String> {
+    // Channel for the capture thread to report init success/failure
+    let (init_tx, init_rx) = std::sync::mpsc::channel::<Result<CaptureInfo, String>>();
+
+    std::thread::spawn(move || {
+        let (err_tx, err_rx) = std::sync::mpsc::channel::<String>();
+
+        let (stream, info) = match open_input(&samples, None, prefer_built_in, err_tx.clone()) {
+            Ok(opened) => opened,
+            Err(e) => {
+                let _ = init_tx.send(Err(e));
+                return;
+            }
+        };
+        let (sample_rate, channels) = (info.sample_rate, info.channels);
+
+        // Signal success
+        let _ = init_tx.send(Ok(info));
+
+        // Keep the stream alive on this thread until the stop signal
+        let mut stream = Some(stream);
+        // Error that ended the last stream, until another input opens
+        let mut lost: Option<String> = None;
+        let mut reported_lost = false;
+        let mut last_attempt: Option<Instant> = None;
+        loop {
+            match stop.recv_timeout(POLL_INTERVAL) {
+                Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+
+            if let Some(error) = err_rx.try_iter().last() {
+                log::warn!("Input device lost: {}", error);
+                // Drop the dead stream before opening another
+                stream = None;
+                lost = Some(error);
+                reported_lost = false;
+                last_attempt = None;
+            }
+            if stream.is_some() || last_attempt.is_some_and(|t| t.elapsed() < RETRY_INTERVAL) {
+                continue;
+            }
+
+            last_attempt = Some(Instant::now());
+            let target = Some((sample_rate, channels));
+            match open_input(&samples, target, prefer_built_in, err_tx.clone()) {
+                Ok((new_stream, info)) => {
+                    log::info!("Capture continues on input device '{}'", info.device);
+                    stream = Some(new_stream);
+                    on_change(DeviceChange {
+                        error: lost.take().unwrap_or_default(),
+                        device: Some(info.device),
+                    });
+                }
+                Err(e) => {
+                    log::warn!("No input device to fail over to: {}", e);
+                    if !reported_lost {
+                        reported_lost = true;
+                        on_change(DeviceChange {
+                            error: lost.clone().unwrap_or_default(),
+                            device: None,
+                        });
+                    }
+                }
+            }
+        }
+        // Stream drops here, stopping capture
+    });
+
+    // Wait for capture thread initialization
+    init_rx
+        .recv()
+        .map_err(|_| "Capture thread died before initialization".to_string())
+        .and_then(|r| r.map_err(|e| format!("Audio init failed: {}", e)))
+}
+
+/// Pick the input to record from: the default device, unless it's a
+/// Bluetooth headset and `prefer_built_in` is set.
+fn select_input(host: &cpal::Host, prefer_built_in: bool) -> Result<cpal::Device, String> {
+    let default = host
+        .default_input_device()
+        .ok_or_else(|| "No input device available".to_string())?;
+    let default_name = default.name().unwrap_or_default();
+    if !prefer_built_in || !looks_bluetooth(&default_name) {
+        return Ok(default);
+    }
+
+    let mut devices: Vec<cpal::Device> = match host.input_devices() {
+        Ok(devices) => devices.collect(),
+        Err(e) => {
+            log::warn!("Failed to list input devices: {}", e);
+            return Ok(default);
+        }
+    };
+    let names: Vec<String> = devices
+        .iter()
+        .map(|d| d.name().unwrap_or_default())
+        .collect();
+    match built_in_choice(&names) {
+        Some(index) => {
+            log::info!(
+                "Using '{}' instead of Bluetooth input '{}'",
+                names[index],
+                default_name
+            );
+            Ok(devices.swap_remove(index))
+        }
+        None => Ok(default),
+    }
+}
+
+/// Open and start an input (see `select_input`). With a `target` (rate,
+/// channels) the samples are converted to that format; otherwise the
+/// device's own format is used.
+fn open_input(
+    samples: &Arc<Mutex<Vec<f32>>>,
+    target: Option<(u32, u16)>,
+    prefer_built_in: bool,
+    err_tx: Sender<String>,
+) -> Result<(cpal::Stream, CaptureInfo), String> {
+    let host = cpal::default_host();
+    let device = select_input(&host, prefer_built_in)?;
+    let name = device.name().unwrap_or_else(|_| "Unknown device".into());
+
+    let supported_config = device
+        .default_input_config()
+        .map_err(|e| format!("Failed to get input config: {}", e))?;
+
+    let sample_format = supported_config.sample_format();
+    let config: cpal::StreamConfig = supported_config.into();
+    let sample_rate = config.sample_rate.0;
+    let channels = config.channels;
+    let (to_rate, to_channels) = target.unwrap_or((sample_rate, channels));
+
+    let samples = samples.clone();
+    let sink = move |data: &[f32]| {
+        if let Ok(mut buf) = samples.lock() {
+            if (sample_rate, channels) == (to_rate, to_channels) {
+                buf.extend_from_slice(data);
+            } else {
+                buf.extend(adapt_format(
+                    data,
+                    channels,
+                    sample_rate,
+                    to_channels,
+                    to_rate,
+                ));
+            }
+        }
+    };
+
+    let err_fn = move |err: cpal::StreamError| {
+        log::error!("Audio capture error: {}", err);
+        // Only a vanished device ends the stream; other errors are transient
+        if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+            let _ = err_tx.send(err.to_string());
+        }
+    };
+
+    let stream = match sample_format {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| sink(data),
+            err_fn,
+            None,
+        ),
+        cpal::SampleFormat::I16 => build_converting::<i16>(&device, &config, sink, err_fn),
+        cpal::SampleFormat::U16 => build_converting::<u16>(&device, &config, sink, err_fn),
+        cpal::SampleFormat::I32 => build_converting::<i32>(&device, &config, sink, err_fn),
+        cpal::SampleFormat::I8 => build_converting::<i8>(&device, &config, sink, err_fn),
+        cpal::SampleFormat::F64 => build_converting::<f64>(&device, &config, sink, err_fn),
+        _ => {
+            return Err(format!("Unsupported sample format: {:?}", sample_format));
+        }
+    }
+    .map_err(|e| format!("Failed to build stream: {}", e))?;
+
+    stream
+        .play()
+        .map_err(|e| format!("Failed to start stream: {}", e))?;
+
+    Ok((
+        stream,
+        CaptureInfo {
+            sample_rate,
+            channels,
+            degraded: is_degraded_bluetooth(&name, sample_rate),
+            device: name,
+        },
+    ))
+}
+
+/// Build an input stream for a non-f32 sample format, converting each
+/// callback's data to f32 before handing it to `sink`.
+fn build_converting<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    sink: impl Fn(&[f32]) + Send + 'static,
+    err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: cpal::SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| sink(&to_f32(data)),
+        err_fn,
+        None,
+    )
+}
+
+/// Convert samples of any cpal format to f32 in [-1, 1].
+fn to_f32<T>(data: &[T]) -> Vec<f32>
+where
+    T: cpal::Sample,
+    f32: cpal::FromSample<T>,
+{
+    data.iter().map(|&s| s.to_sample::<f32>()).collect()
+}
+
+/// Convert interleaved samples to another channel count and rate, so audio
+/// from a failover device matches the format the buffer was started with.
+fn adapt_format(
+    data: &[f32],
+    from_channels: u16,
+    from_rate: u32,
+    to_channels: u16,
+    to_rate: u32,
+) -> Vec<f32> {
+    let mono = processing::to_mono(data, from_channels);
+    let resampled = processing::resample(&mono, from_rate, to_rate);
+    resampled
+        .iter()
+        .flat_map(|&s| std::iter::repeat(s).take(to_channels as usize))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_sample_formats_to_f32() {
+        let close = |a: &[f32], b: &[f32]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 0.01);
+        assert!(close(
+            &to_f32(&[0i16, i16::MAX, i16::MIN]),
+            &[0.0, 1.0, -1.0]
+        ));
+        assert!(close(&to_f32(&[32768u16, u16::MAX, 0]), &[0.0, 1.0, -1.0]));
+        assert!(close(
+            &to_f32(&[0i32, i32::MAX, i32::MIN]),
+            &[0.0, 1.0, -1.0]
+        ));
+        assert!(close(&to_f32(&[0i8, i8::MAX, i8::MIN]), &[0.0, 1.0, -1.0]));
+        assert!(close(&to_f32(&[0.0f64, 0.5, -0.5]), &[0.0, 0.5, -0.5]));
+    }
+
+    #[test]
+    fn detects_bluetooth_hands_free_inputs() {
+        assert!(is_degraded_bluetooth("AirPods Pro", 24_000));
+        assert!(is_degraded_bluetooth(
+            "WH-1000XM4 Hands-Free AG Audio",
+            16_000
+        ));
+        assert!(!is_degraded_bluetooth("AirPods Pro", 48_000));
+        assert!(!is_degraded_bluetooth("MacBook Pro Microphone", 16_000));
+        assert!(!is_degraded_bluetooth("USB Headset", 16_000));
+    }
+
+    #[test]
+    fn prefers_built_in_over_other_inputs() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            built_in_choice(&names(&["AirPods", "USB Mic", "MacBook Air Microphone"])),
+            Some(2)
+        );
+        assert_eq!(built_in_choice(&names(&["AirPods", "USB Mic"])), Some(1));
+        assert_eq!(
+            built_in_choice(&names(&["AirPods", "Bluetooth Headset"])),
+            None
+        );
+    }
+
+    #[test]
+    fn adapt_format_converts_channels_and_rate() {
+        // Mono 16 kHz → stereo 32 kHz: twice the frames, two samples each
+        let out = adapt_format(&[0.5; 160], 1, 16000, 2, 32000);
+        assert_eq!(out.len(), 640);
+        assert!(out.iter().all(|&s| (s - 0.5).abs() < 1e-6));
+
+        // Stereo → mono at the same rate averages the channels
+        let out = adapt_format(&[0.2, 0.4, 0.6, 0.8], 2, 48000, 1, 48000);
+        assert_eq!(out.len(), 2);
+        assert!((out[0] - 0.3).abs() < 1e-6);
+        assert!((out[1] - 0.7).abs() < 1e-6);
+    }
+}
@@ -1,3 +1,7 @@
 pub mod capture;
 pub mod processing;
+pub mod silence;
+pub mod spectrum;
+pub mod transport;
 pub mod wav;
+pub mod waveform;
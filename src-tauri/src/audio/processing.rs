@@ -60,6 +60,64 @@ pub fn rms_energy(samples: &[f32]) -> f32 {
     (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
 }
 
+/// Split audio into speech chunks at pauses of at least `min_silence_ms`,
+/// cutting in the middle of each pause. Chunks shorter than `min_chunk_ms`
+/// are merged into a neighbour. The ranges cover the whole input.
+pub fn split_on_silence(
+    samples: &[f32],
+    sample_rate: u32,
+    min_silence_ms: u32,
+    min_chunk_ms: u32,
+) -> Vec<std::ops::Range<usize>> {
+    if samples.is_empty() {
+        return vec![];
+    }
+    const FRAME_MS: u32 = 30;
+    let frame = (sample_rate * FRAME_MS / 1000).max(1) as usize;
+    let min_silent_frames = (min_silence_ms / FRAME_MS).max(1) as usize;
+    let min_len = (sample_rate as u64 * min_chunk_ms as u64 / 1000) as usize;
+
+    let mut cuts = Vec::new();
+    let mut run_start = None;
+    for (i, chunk) in samples.chunks(frame).enumerate() {
+        let silent = rms_energy(chunk) < 0.01;
+        match (silent, run_start) {
+            (true, None) => run_start = Some(i),
+            (false, Some(start)) => {
+                // Leading silence isn't a boundary between chunks
+                if start > 0 && i - start >= min_silent_frames {
+                    cuts.push((start + (i - start) / 2) * frame);
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+
+    split_at(&cuts, samples.len(), min_len)
+}
+
+/// Split `0..len` at the sorted offsets in `cuts`, merging chunks shorter
+/// than `min_len` samples into a neighbour.
+pub fn split_at(cuts: &[usize], len: usize, min_len: usize) -> Vec<std::ops::Range<usize>> {
+    if len == 0 {
+        return vec![];
+    }
+    let bounds: Vec<usize> = std::iter::once(0)
+        .chain(cuts.iter().copied().filter(|&cut| cut > 0 && cut < len))
+        .chain(std::iter::once(len))
+        .collect();
+    let mut chunks: Vec<std::ops::Range<usize>> = Vec::new();
+    for pair in bounds.windows(2) {
+        let range = pair[0]..pair[1];
+        match chunks.last_mut() {
+            Some(last) if last.len() < min_len || range.len() < min_len => last.end = range.end,
+            _ => chunks.push(range),
+        }
+    }
+    chunks
+}
+
 /// Full preprocessing pipeline: multi-channel -> mono -> 16kHz -> normalize.
 pub fn preprocess(samples: &[f32], channels: u16, sample_rate: u32) -> Vec<f32> {
     let mono = to_mono(samples, channels);
@@ -250,4 +308,64 @@ mod tests {
         let peak = result.iter().map(|s| s.abs()).fold(0.0f32, f32::max);
         assert!((peak - 0.95).abs() < 1e-5);
     }
+
+    // ── split_on_silence ─────────────────────────────────────
+
+    fn speech_with_pauses(parts: &[(bool, usize)]) -> Vec<f32> {
+        parts
+            .iter()
+            .flat_map(|&(speech, ms)| {
+                (0..ms * 16).map(move |i| {
+                    if speech {
+                        (i as f32 * 0.3).sin() * 0.5
+                    } else {
+                        0.0
+                    }
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn split_on_silence_cuts_long_pauses() {
+        let audio = speech_with_pauses(&[(true, 3000), (false, 600), (true, 3000)]);
+        let chunks = split_on_silence(&audio, 16000, 400, 1000);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].start, 0);
+        assert_eq!(chunks[1].end, audio.len());
+        assert_eq!(chunks[0].end, chunks[1].start);
+        // The cut falls inside the pause
+        assert!(chunks[0].end > 3000 * 16 && chunks[0].end < 3600 * 16);
+    }
+
+    #[test]
+    fn split_at_merges_short_chunks() {
+        assert_eq!(split_at(&[100, 150, 400], 500, 80), vec![0..150, 150..400, 400..500]);
+        assert_eq!(split_at(&[100, 150, 400], 500, 120), vec![0..150, 150..500]);
+        assert!(split_at(&[], 0, 10).is_empty());
+    }
+
+    #[test]
+    fn split_on_silence_keeps_short_pauses_and_chunks() {
+        let audio = speech_with_pauses(&[(true, 3000), (false, 150), (true, 3000)]);
+        assert_eq!(
+            split_on_silence(&audio, 16000, 400, 1000),
+            vec![0..audio.len()]
+        );
+
+        // A short burst between pauses joins the chunk before it
+        let audio = speech_with_pauses(&[
+            (false, 500),
+            (true, 3000),
+            (false, 600),
+            (true, 300),
+            (false, 600),
+            (true, 3000),
+        ]);
+        let chunks = split_on_silence(&audio, 16000, 400, 1000);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].len() > 3900 * 16);
+
+        assert!(split_on_silence(&[], 16000, 400, 1000).is_empty());
+    }
 }
@@ -0,0 +1,86 @@
+//! Trailing-silence detection for hands-free recording. Fed with the RMS
+//! readings of the level meter thread. Silence only counts once something
+//! was said, so pausing before speaking doesn't end the recording.
+
+use std::time::Duration;
+
+/// RMS level (of the raw capture) below which the input counts as silence.
+pub const SILENCE_LEVEL: f32 = 0.015;
+
+#[derive(Debug, Clone)]
+pub struct SilenceDetector {
+    timeout: Duration,
+    silent_for: Duration,
+    heard_speech: bool,
+}
+
+impl SilenceDetector {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            silent_for: Duration::ZERO,
+            heard_speech: false,
+        }
+    }
+
+    /// Feed one level reading covering `elapsed`. Returns true once the input
+    /// has stayed below `SILENCE_LEVEL` for the whole timeout after speech.
+    pub fn update(&mut self, level: f32, elapsed: Duration) -> bool {
+        if level >= SILENCE_LEVEL {
+            self.heard_speech = true;
+            self.silent_for = Duration::ZERO;
+            return false;
+        }
+        if !self.heard_speech {
+            return false;
+        }
+        self.silent_for += elapsed;
+        self.silent_for >= self.timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn triggers_after_timeout_of_silence() {
+        let mut detector = SilenceDetector::new(Duration::from_millis(300));
+        assert!(!detector.update(0.2, TICK));
+        assert!(!detector.update(0.0, TICK));
+        assert!(!detector.update(0.0, TICK));
+        assert!(detector.update(0.0, TICK));
+    }
+
+    #[test]
+    fn speech_resets_the_timer() {
+        let mut detector = SilenceDetector::new(Duration::from_millis(300));
+        assert!(!detector.update(0.0, TICK));
+        assert!(!detector.update(0.0, TICK));
+        assert!(!detector.update(0.2, TICK));
+        assert!(!detector.update(0.0, TICK));
+        assert!(!detector.update(0.0, TICK));
+        assert!(detector.update(0.0, TICK));
+    }
+
+    #[test]
+    fn quiet_noise_counts_as_silence() {
+        let mut detector = SilenceDetector::new(TICK);
+        assert!(!detector.update(0.2, TICK));
+        assert!(detector.update(SILENCE_LEVEL / 2.0, TICK));
+    }
+
+    #[test]
+    fn silence_before_speech_is_ignored() {
+        let mut detector = SilenceDetector::new(Duration::from_millis(300));
+        for _ in 0..10 {
+            assert!(!detector.update(0.0, TICK));
+        }
+        assert!(!detector.update(0.2, TICK));
+        assert!(!detector.update(0.0, TICK));
+        assert!(!detector.update(0.0, TICK));
+        assert!(detector.update(0.0, TICK));
+    }
+}
@@ -0,0 +1,127 @@
+//! Band energies for the recording bar's spectrum visualizer, computed by
+//! the level meter thread from the most recent capture samples.
+
+/// Samples (mono frames) per FFT; must be a power of two.
+pub const FFT_SIZE: usize = 1024;
+/// Number of bands in each `audio-spectrum` event.
+pub const BANDS: usize = 16;
+
+const MIN_FREQ: f32 = 80.0;
+const MAX_FREQ: f32 = 8000.0;
+/// Band energy in dB that maps to 0.0 and 1.0 respectively.
+const FLOOR_DB: f32 = -70.0;
+const CEIL_DB: f32 = -10.0;
+
+/// In-place iterative radix-2 FFT over separate real/imaginary buffers.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    debug_assert!(n.is_power_of_two() && im.len() == n);
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Log-spaced band energies of the last `FFT_SIZE` mono samples, each
+/// scaled to 0.0..=1.0. Shorter input is zero-padded at the front.
+pub fn band_levels(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let mut re = vec![0.0f32; FFT_SIZE];
+    let mut im = vec![0.0f32; FFT_SIZE];
+    let tail = &samples[samples.len().saturating_sub(FFT_SIZE)..];
+    let offset = FFT_SIZE - tail.len();
+    for (i, &s) in tail.iter().enumerate() {
+        // Hann window against spectral leakage
+        let n = offset + i;
+        let w = 0.5 - 0.5 * (2.0 * std::f32::consts::PI * n as f32 / (FFT_SIZE - 1) as f32).cos();
+        re[n] = s * w;
+    }
+    fft(&mut re, &mut im);
+
+    let bin_hz = sample_rate as f32 / FFT_SIZE as f32;
+    let max_freq = MAX_FREQ.min(sample_rate as f32 / 2.0);
+    let ratio = (max_freq / MIN_FREQ).powf(1.0 / BANDS as f32);
+
+    (0..BANDS)
+        .map(|band| {
+            let low = MIN_FREQ * ratio.powi(band as i32);
+            let high = low * ratio;
+            let first = ((low / bin_hz) as usize).max(1);
+            let last = ((high / bin_hz).ceil() as usize).clamp(first + 1, FFT_SIZE / 2);
+            let power = (first..last)
+                .map(|k| re[k] * re[k] + im[k] * im[k])
+                .fold(0.0f32, f32::max);
+            // Normalize by the window's coherent gain so a full-scale sine is ~0 dB
+            let amplitude = power.sqrt() / (FFT_SIZE as f32 / 4.0);
+            let db = 20.0 * amplitude.max(1e-9).log10();
+            ((db - FLOOR_DB) / (CEIL_DB - FLOOR_DB)).clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn silence_has_no_energy() {
+        let levels = band_levels(&[0.0; 2048], 48000);
+        assert_eq!(levels.len(), BANDS);
+        assert!(levels.iter().all(|&l| l == 0.0));
+        assert_eq!(band_levels(&[], 48000), vec![0.0; BANDS]);
+    }
+
+    #[test]
+    fn sine_peaks_in_its_band() {
+        let rate = 48000;
+        let low = band_levels(&sine(150.0, rate, 2048), rate);
+        let high = band_levels(&sine(4000.0, rate, 2048), rate);
+
+        let loudest = |levels: &[f32]| {
+            levels
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(i, _)| i)
+                .unwrap()
+        };
+        assert!(loudest(&low) < 4, "{:?}", low);
+        assert!(loudest(&high) > BANDS / 2, "{:?}", high);
+        assert!(high[loudest(&high)] > 0.8);
+    }
+}
@@ -0,0 +1,118 @@
+//! How an input device is connected, where the platform reports it.
+//!
+//! macOS asks CoreAudio for the transport type of the device with the name
+//! cpal reports. Other platforms can't tell and return `None`, leaving
+//! callers to guess from the name.
+
+/// Whether the input named `name` is connected over Bluetooth, or `None`
+/// when that's unknown.
+pub fn is_bluetooth(name: &str) -> Option<bool> {
+    platform::is_bluetooth(name)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use core_foundation::base::TCFType;
+    use core_foundation::string::{CFString, CFStringRef};
+    use std::ffi::c_void;
+
+    const fn code(id: &[u8; 4]) -> u32 {
+        u32::from_be_bytes(*id)
+    }
+
+    const SYSTEM_OBJECT: u32 = 1;
+    const SCOPE_GLOBAL: u32 = code(b"glob");
+    const ELEMENT_MAIN: u32 = 0;
+    const DEVICES: u32 = code(b"dev#");
+    /// The name cpal reports for a device.
+    const DEVICE_NAME: u32 = code(b"name");
+    const TRANSPORT_TYPE: u32 = code(b"tran");
+    /// Classic Bluetooth and Bluetooth LE.
+    const BLUETOOTH: [u32; 2] = [code(b"blue"), code(b"blea")];
+
+    #[repr(C)]
+    struct PropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+
+    #[link(name = "CoreAudio", kind = "framework")]
+    extern "C" {
+        fn AudioObjectGetPropertyDataSize(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            size: *mut u32,
+        ) -> i32;
+        fn AudioObjectGetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            size: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+    }
+
+    fn address(selector: u32) -> PropertyAddress {
+        PropertyAddress {
+            selector,
+            scope: SCOPE_GLOBAL,
+            element: ELEMENT_MAIN,
+        }
+    }
+
+    /// Read a fixed-size property of an audio object into `value`.
+    unsafe fn read_property<T>(object: u32, selector: u32, value: &mut T) -> bool {
+        let mut size = std::mem::size_of::<T>() as u32;
+        let data = value as *mut T as *mut c_void;
+        AudioObjectGetPropertyData(object, &address(selector), 0, std::ptr::null(), &mut size, data)
+            == 0
+    }
+
+    fn devices() -> Vec<u32> {
+        let address = address(DEVICES);
+        let mut size = 0u32;
+        unsafe {
+            let null = std::ptr::null();
+            if AudioObjectGetPropertyDataSize(SYSTEM_OBJECT, &address, 0, null, &mut size) != 0 {
+                return vec![];
+            }
+            let mut ids = vec![0u32; size as usize / std::mem::size_of::<u32>()];
+            let data = ids.as_mut_ptr() as *mut c_void;
+            if AudioObjectGetPropertyData(SYSTEM_OBJECT, &address, 0, null, &mut size, data) != 0 {
+                return vec![];
+            }
+            ids.truncate(size as usize / std::mem::size_of::<u32>());
+            ids
+        }
+    }
+
+    fn device_name(device: u32) -> Option<String> {
+        let mut name: CFStringRef = std::ptr::null();
+        unsafe {
+            if !read_property(device, DEVICE_NAME, &mut name) || name.is_null() {
+                return None;
+            }
+            Some(CFString::wrap_under_create_rule(name).to_string())
+        }
+    }
+
+    pub fn is_bluetooth(name: &str) -> Option<bool> {
+        let device = devices()
+            .into_iter()
+            .find(|&device| device_name(device).as_deref() == Some(name))?;
+        let mut transport = 0u32;
+        unsafe { read_property(device, TRANSPORT_TYPE, &mut transport) }
+            .then(|| BLUETOOTH.contains(&transport))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    pub fn is_bluetooth(_name: &str) -> Option<bool> {
+        None
+    }
+}
@@ -3,8 +3,18 @@ use std::io::Cursor;
 use std::path::Path;
 
 pub fn save_wav(samples: &[f32], sample_rate: u32, path: &Path) -> anyhow::Result<()> {
+    save_wav_channels(samples, sample_rate, 1, path)
+}
+
+/// Save interleaved samples with the given channel count.
+pub fn save_wav_channels(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+    path: &Path,
+) -> anyhow::Result<()> {
     let spec = WavSpec {
-        channels: 1,
+        channels,
         sample_rate,
         bits_per_sample: 32,
         sample_format: SampleFormat::Float,
@@ -35,6 +45,26 @@ pub fn encode_wav_to_bytes(samples: &[f32], sample_rate: u32) -> anyhow::Result<
     Ok(cursor.into_inner())
 }
 
+/// Mono 16-bit PCM WAV, half the size of the float encoding. Used for
+/// cloud uploads, where the extra precision buys nothing.
+pub fn encode_wav_pcm16_to_bytes(samples: &[f32], sample_rate: u32) -> anyhow::Result<Vec<u8>> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut cursor = Cursor::new(Vec::with_capacity(44 + samples.len() * 2));
+    {
+        let mut writer = WavWriter::new(&mut cursor, spec)?;
+        for &sample in samples {
+            writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+        }
+        writer.finalize()?;
+    }
+    Ok(cursor.into_inner())
+}
+
 pub fn read_wav_file(path: &Path) -> anyhow::Result<(Vec<f32>, u32, u16)> {
     let reader = hound::WavReader::open(path)?;
     let spec = reader.spec();
@@ -88,6 +118,24 @@ mod tests {
         std::fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn save_wav_channels_keeps_stereo() {
+        // Interleaved L/R pairs
+        let samples: Vec<f32> = test_samples().iter().flat_map(|&s| [s, -s]).collect();
+        let dir = std::env::temp_dir().join("sobotta_test_wav_stereo");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stereo.wav");
+
+        save_wav_channels(&samples, 48000, 2, &path).unwrap();
+        let (read_samples, rate, channels) = read_wav_file(&path).unwrap();
+
+        assert_eq!(rate, 48000);
+        assert_eq!(channels, 2);
+        assert_eq!(read_samples, samples);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn encode_wav_to_bytes_produces_valid_wav() {
         let samples = test_samples();
@@ -175,4 +223,27 @@ mod tests {
 
         std::fs::remove_dir_all(&dir).ok();
     }
+
+    #[test]
+    fn encode_pcm16_halves_size_and_roundtrips() {
+        let samples = test_samples();
+        let float_bytes = encode_wav_to_bytes(&samples, 16000).unwrap();
+        let bytes = encode_wav_pcm16_to_bytes(&samples, 16000).unwrap();
+        assert_eq!(bytes.len(), 44 + samples.len() * 2);
+        assert!(bytes.len() < float_bytes.len());
+
+        let dir = std::env::temp_dir().join("sobotta_test_wav_pcm16");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pcm16.wav");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let (read_samples, rate, channels) = read_wav_file(&path).unwrap();
+        assert_eq!((rate, channels), (16000, 1));
+        assert_eq!(read_samples.len(), samples.len());
+        for (a, b) in samples.iter().zip(read_samples.iter()) {
+            assert!((a - b).abs() < 1e-3, "sample mismatch: {} vs {}", a, b);
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
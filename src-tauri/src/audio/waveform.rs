@@ -0,0 +1,107 @@
+use serde::Serialize;
+
+/// Min/max amplitude of one waveform bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Peak {
+    pub min: f32,
+    pub max: f32,
+}
+
+/// Downsampled peak data for rendering a waveform.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaveformPeaks {
+    pub peaks: Vec<Peak>,
+    pub duration_ms: u64,
+}
+
+/// Split mono samples into `buckets` equal slices and return the min/max of each.
+/// Returns fewer buckets than requested when there are fewer samples than buckets.
+pub fn compute_peaks(samples: &[f32], buckets: usize) -> Vec<Peak> {
+    if samples.is_empty() || buckets == 0 {
+        return Vec::new();
+    }
+    let buckets = buckets.min(samples.len());
+
+    (0..buckets)
+        .map(|i| {
+            let start = i * samples.len() / buckets;
+            let end = (i + 1) * samples.len() / buckets;
+            samples[start..end].iter().fold(
+                Peak {
+                    min: f32::MAX,
+                    max: f32::MIN,
+                },
+                |acc, &s| Peak {
+                    min: acc.min.min(s),
+                    max: acc.max.max(s),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Compute peaks for interleaved audio at any rate/channel count.
+pub fn waveform_peaks(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+    buckets: usize,
+) -> WaveformPeaks {
+    let mono = super::processing::to_mono(samples, channels);
+    let duration_ms = if sample_rate == 0 {
+        0
+    } else {
+        mono.len() as u64 * 1000 / sample_rate as u64
+    };
+    WaveformPeaks {
+        peaks: compute_peaks(&mono, buckets),
+        duration_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_peaks_empty_input() {
+        assert!(compute_peaks(&[], 10).is_empty());
+        assert!(compute_peaks(&[0.5], 0).is_empty());
+    }
+
+    #[test]
+    fn compute_peaks_min_max_per_bucket() {
+        let samples = vec![0.1, -0.5, 0.3, 0.9, -0.2, 0.0];
+        let peaks = compute_peaks(&samples, 2);
+        assert_eq!(peaks.len(), 2);
+        assert_eq!(peaks[0], Peak { min: -0.5, max: 0.3 });
+        assert_eq!(peaks[1], Peak { min: -0.2, max: 0.9 });
+    }
+
+    #[test]
+    fn compute_peaks_caps_bucket_count() {
+        let peaks = compute_peaks(&[0.1, 0.2, 0.3], 100);
+        assert_eq!(peaks.len(), 3);
+        assert_eq!(peaks[1], Peak { min: 0.2, max: 0.2 });
+    }
+
+    #[test]
+    fn compute_peaks_uneven_split_covers_all_samples() {
+        let samples: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        let peaks = compute_peaks(&samples, 3);
+        assert_eq!(peaks.len(), 3);
+        assert_eq!(peaks[0].min, 0.0);
+        assert_eq!(peaks[2].max, 9.0);
+    }
+
+    #[test]
+    fn waveform_peaks_stereo_duration() {
+        // 1 second of stereo at 16 kHz
+        let samples = vec![0.25f32; 32000];
+        let w = waveform_peaks(&samples, 16000, 2, 50);
+        assert_eq!(w.duration_ms, 1000);
+        assert_eq!(w.peaks.len(), 50);
+        assert!((w.peaks[0].max - 0.25).abs() < 1e-6);
+    }
+}
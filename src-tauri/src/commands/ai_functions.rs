@@ -1,7 +1,16 @@
+use crate::commands::error::CommandError;
+use crate::commands::recording::RecordingState;
 use crate::db;
-use crate::llm::{self, LlmConfig, LlmProviderType};
+use crate::db::settings::AppSettings;
+use crate::llm::{self, ollama::OllamaOptions, ChatMessage, LlmConfig, LlmProviderType};
 use crate::rules;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+use tauri_plugin_clipboard_manager::ClipboardExt;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -12,53 +21,96 @@ pub struct AiFunction {
     pub provider: String,
     pub model: Option<String>,
     pub is_builtin: bool,
+    /// What happens with the output after a dictation.
+    #[serde(default)]
+    pub output_action: OutputAction,
+    /// Times the function has been run.
+    #[serde(default)]
+    pub use_count: u32,
+    #[serde(default)]
+    pub last_used_at: Option<String>,
+}
+
+/// What happens with an AI function's output after a dictation, serialized
+/// as `{ "type": "paste" }` or `{ "type": "webhook", "url": "..." }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OutputAction {
+    /// Paste into the focused app.
+    #[default]
+    Paste,
+    /// Only copy to the clipboard.
+    Copy,
+    /// Paste, then put back what was on the clipboard before.
+    PasteKeepClipboard,
+    /// Show in a preview window to review before using it.
+    Preview,
+    /// POST `{ functionId, text }` as JSON to `url`.
+    Webhook { url: String },
 }
 
 pub fn builtin_functions() -> Vec<AiFunction> {
     vec![
-        AiFunction {
-            id: "email".into(),
-            name: "Professional Email".into(),
-            prompt: "Rewrite the following as a professional email. Include a greeting and sign-off. Keep it concise.".into(),
-            provider: "default".into(),
-            model: None,
-            is_builtin: true,
-        },
-        AiFunction {
-            id: "code-prompt".into(),
-            name: "Code Prompt".into(),
-            prompt: "Convert the following spoken description into a clear, well-structured code prompt or specification.".into(),
-            provider: "default".into(),
-            model: None,
-            is_builtin: true,
-        },
-        AiFunction {
-            id: "summarize".into(),
-            name: "Summarize".into(),
-            prompt: "Summarize the following text concisely, capturing the key points.".into(),
-            provider: "default".into(),
-            model: None,
-            is_builtin: true,
-        },
-        AiFunction {
-            id: "casual".into(),
-            name: "Casual Rewrite".into(),
-            prompt: "Rewrite the following text in a casual, friendly tone.".into(),
-            provider: "default".into(),
-            model: None,
-            is_builtin: true,
-        },
-        AiFunction {
-            id: "translate".into(),
-            name: "Translate to English".into(),
-            prompt: "Translate the following text to English. If it is already in English, improve clarity.".into(),
-            provider: "default".into(),
-            model: None,
-            is_builtin: true,
-        },
+        builtin(
+            "email",
+            "Professional Email",
+            "Rewrite the following as a professional email. Include a greeting and sign-off. Keep it concise.",
+        ),
+        builtin(
+            "code-prompt",
+            "Code Prompt",
+            "Convert the following spoken description into a clear, well-structured code prompt or specification.",
+        ),
+        builtin(
+            "summarize",
+            "Summarize",
+            "Summarize the following text concisely, capturing the key points.",
+        ),
+        builtin(
+            "casual",
+            "Casual Rewrite",
+            "Rewrite the following text in a casual, friendly tone.",
+        ),
+        builtin(
+            "translate",
+            "Translate to English",
+            "Translate the following text to English. If it is already in English, improve clarity.",
+        ),
+        builtin("grammar", "Fix Grammar", rules::grammar::GRAMMAR_SYSTEM_PROMPT),
+        builtin(
+            "bullets",
+            "Bullet Points",
+            "Convert the following text into a concise bullet-point list. One idea per bullet, no introduction or closing.",
+        ),
+        builtin(
+            "meeting-notes",
+            "Meeting Notes",
+            "Turn the following dictation into structured meeting notes with a short summary, key decisions, and a list of action items (with owners and due dates when mentioned).",
+        ),
+        builtin(
+            "slack",
+            "Slack Message",
+            "Rewrite the following as a short, friendly Slack message. Keep it conversational and skimmable; no email-style greeting or sign-off.",
+        ),
     ]
 }
 
+/// A built-in function with the default provider, pasting its output.
+fn builtin(id: &str, name: &str, prompt: &str) -> AiFunction {
+    AiFunction {
+        id: id.into(),
+        name: name.into(),
+        prompt: prompt.into(),
+        provider: "default".into(),
+        model: None,
+        is_builtin: true,
+        output_action: OutputAction::Paste,
+        use_count: 0,
+        last_used_at: None,
+    }
+}
+
+/// Built-in then custom functions, each with its run count and last use.
 #[tauri::command]
 pub fn list_ai_functions() -> Result<Vec<AiFunction>, String> {
     let mut functions = builtin_functions();
@@ -73,10 +125,26 @@ pub fn list_ai_functions() -> Result<Vec<AiFunction>, String> {
                 provider: item.provider,
                 model: item.model,
                 is_builtin: false,
+                output_action: OutputAction::Paste,
+                use_count: 0,
+                last_used_at: None,
             });
         }
     }
 
+    let mut usage = db::ai_functions::usage().unwrap_or_default();
+    let mut actions = db::ai_functions::output_actions().unwrap_or_default();
+    for function in &mut functions {
+        if let Some(action) = actions.remove(&function.id) {
+            // Unknown actions from a newer version fall back to pasting
+            function.output_action = serde_json::from_str(&action).unwrap_or_default();
+        }
+        if let Some(used) = usage.remove(&function.id) {
+            function.use_count = used.count;
+            function.last_used_at = used.last_used_at;
+        }
+    }
+
     Ok(functions)
 }
 
@@ -89,66 +157,444 @@ fn parse_provider_type(s: &str) -> LlmProviderType {
     }
 }
 
+/// Prepend the user's global context (name, style, sign-off) to a function's
+/// system prompt. A blank prefix leaves the prompt unchanged.
+fn compose_system_prompt(prefix: Option<&str>, prompt: &str) -> String {
+    match prefix.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(prefix) => format!("{}\n\n{}", prefix, prompt),
+        None => prompt.to_string(),
+    }
+}
+
+/// Combine the dictated text with optional reference material (the email
+/// being replied to, a selected paragraph, ...) into the user message.
+fn compose_user_message(context: Option<&str>, text: &str) -> String {
+    match context.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(context) => format!(
+            "Context (for reference, do not repeat it):\n\"\"\"\n{}\n\"\"\"\n\nDictated text:\n{}",
+            context, text
+        ),
+        None => text.to_string(),
+    }
+}
+
+/// Fetch extra context for the AI function: `"clipboard"` reads the current
+/// clipboard, `"selection"` copies the focused app's selected text.
+async fn read_context(app: &AppHandle, source: Option<&str>) -> Option<String> {
+    match source {
+        Some("clipboard") => app.clipboard().read_text().ok(),
+        Some("selection") => match crate::system::selection::read_selected_text().await {
+            Ok(selected) => selected,
+            Err(e) => {
+                log::warn!("execute_ai_function: failed to read selection: {}", e);
+                None
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Run `function_id` over `text` with the LLM configuration resolved from
+/// the function and the saved settings, see `resolve_llm_configs`.
 #[tauri::command]
 pub async fn execute_ai_function(
+    app: AppHandle,
     text: String,
     function_id: String,
-    llm_provider: String,
-    llm_api_key: String,
-    llm_model: String,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
+    log::info!("execute_ai_function: function={}", function_id);
+
+    let settings = db::settings::load();
+    let context = read_context(&app, Some(&settings.ai_context_source)).await;
+    run_ai_function(&app, &function_id, &text, context.as_deref(), &settings).await
+}
+
+/// The last AI function exchange, kept so a follow-up instruction ("make it
+/// shorter") continues the conversation instead of starting over.
+#[derive(Debug, Clone)]
+struct Conversation {
+    /// The function that started the conversation; follow-ups use its LLM.
+    function: AiFunction,
+    system_prompt: String,
+    messages: Vec<ChatMessage>,
+}
+
+impl Conversation {
+    /// The conversation with `instruction` appended as the next user turn.
+    fn follow_up(&self, instruction: &str) -> Self {
+        let mut next = self.clone();
+        next.messages.push(ChatMessage::user(instruction));
+        next
+    }
+}
+
+/// Holds the most recent `Conversation`; each AI function run replaces it
+/// and each refinement extends it.
+pub struct AiConversation {
+    last: Mutex<Option<Conversation>>,
+}
+
+impl AiConversation {
+    pub fn new() -> Self {
+        Self {
+            last: Mutex::new(None),
+        }
+    }
+
+    fn set(&self, conversation: Conversation) {
+        *self.last.lock().unwrap() = Some(conversation);
+    }
+
+    fn get(&self) -> Option<Conversation> {
+        self.last.lock().unwrap().clone()
+    }
+}
+
+/// Lets `cancel_ai_function` stop the AI requests in flight.
+pub struct AiCancel {
+    cancelled: Notify,
+    running: AtomicUsize,
+}
+
+impl AiCancel {
+    pub fn new() -> Self {
+        Self {
+            cancelled: Notify::new(),
+            running: AtomicUsize::new(0),
+        }
+    }
+
+    /// Run `future` unless `cancel` is called before it finishes. Dropping
+    /// the future closes its connection, so the provider stops generating.
+    async fn run<T>(&self, future: impl Future<Output = T>) -> Result<T, CommandError> {
+        self.running.fetch_add(1, Ordering::SeqCst);
+        let result = tokio::select! {
+            value = future => Ok(value),
+            _ = self.cancelled.notified() => Err(CommandError::AiCancelled),
+        };
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        result
+    }
+
+    /// Stop every running request. Returns whether any was running.
+    fn cancel(&self) -> bool {
+        self.cancelled.notify_waiters();
+        self.running.load(Ordering::SeqCst) > 0
+    }
+}
+
+/// Abort the running AI function or refinement mid-stream; it then fails
+/// with the `cancelled` error code. Returns false if nothing was running.
+#[tauri::command]
+pub fn cancel_ai_function(app: AppHandle) -> bool {
+    let cancelled = app.state::<AiCancel>().cancel();
+    log::info!("cancel_ai_function: cancelled={}", cancelled);
+    cancelled
+}
+
+/// Payload of `ai-output-delta`: the next piece of a reply being streamed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AiOutputDelta<'a> {
+    function_id: &'a str,
+    delta: &'a str,
+}
+
+/// Get a reply from `configs`, emitting `ai-output-delta` as it streams in.
+/// Stops early with `AiCancelled` when `cancel_ai_function` is called.
+async fn stream_reply(
+    app: &AppHandle,
+    function_id: &str,
+    configs: &[LlmConfig],
+    system_prompt: &str,
+    messages: &[ChatMessage],
+) -> Result<String, CommandError> {
+    let on_delta = |delta: &str| {
+        let _ = app.emit("ai-output-delta", AiOutputDelta { function_id, delta });
+    };
+    let provider = llm::create_provider_chain(configs);
+    app.state::<AiCancel>()
+        .run(provider.chat_stream(system_prompt, messages, &on_delta))
+        .await?
+        .map_err(|e| CommandError::AiFailed(e.to_string()))
+}
+
+/// Send `instruction` as a follow-up to the last AI function output, with
+/// the earlier exchange as context, and return the revised text.
+#[tauri::command]
+pub async fn refine_last_output(
+    app: AppHandle,
+    instruction: String,
+) -> Result<String, CommandError> {
+    let instruction = instruction.trim();
+    if instruction.is_empty() {
+        return Err("Say how the output should change".into());
+    }
+    let state = app.state::<AiConversation>();
+    let mut conversation = state
+        .get()
+        .ok_or(CommandError::NoAiOutput)?
+        .follow_up(instruction);
     log::info!(
-        "execute_ai_function: function={}, provider={}, model={}",
-        function_id, llm_provider, llm_model
+        "refine_last_output: function={}, turns={}",
+        conversation.function.id,
+        conversation.messages.len()
     );
 
+    let configs = resolve_llm_configs(
+        &conversation.function,
+        &db::settings::load(),
+        db::settings::keychain_api_key,
+    )?;
+    let result = stream_reply(
+        &app,
+        &conversation.function.id,
+        &configs,
+        &conversation.system_prompt,
+        &conversation.messages,
+    )
+    .await
+    .map_err(|e| match e {
+        CommandError::AiFailed(e) => CommandError::AiFailed(format!("Refinement failed: {}", e)),
+        e => e,
+    })?;
+    if result.trim().is_empty() {
+        return Err(CommandError::AiFailed(
+            "Refinement returned empty response".to_string(),
+        ));
+    }
+
+    conversation.messages.push(ChatMessage::assistant(result.clone()));
+    state.set(conversation);
+    Ok(result)
+}
+
+/// LLM configs to run `function` with, in the order to try them: the
+/// function's own provider and model when it names one, otherwise the
+/// selected LLM provider, followed by the configured fallbacks. Models and
+/// options come from each provider's settings; a key missing there is
+/// looked up with `keychain`.
+fn resolve_llm_configs(
+    function: &AiFunction,
+    settings: &AppSettings,
+    keychain: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<LlmConfig>, CommandError> {
+    let own_provider = !matches!(function.provider.as_str(), "" | "default");
+    let primary = if own_provider {
+        function.provider.as_str()
+    } else {
+        settings.llm_provider.as_str()
+    };
+    let model = function
+        .model
+        .clone()
+        .filter(|m| own_provider && !m.trim().is_empty());
+
+    let config = provider_config(primary, model, settings, &keychain)
+        .ok_or_else(|| CommandError::ApiKeyMissing(format!("AI functions with {}", primary)))?;
+    let fallbacks = settings
+        .llm_fallback_providers
+        .iter()
+        .filter(|p| p.as_str() != primary)
+        .filter_map(|p| provider_config(p, None, settings, &keychain));
+    Ok(std::iter::once(config).chain(fallbacks).collect())
+}
+
+/// `LlmConfig` for `provider` from its saved settings, with `model` in
+/// place of the saved one if given and the provider's default model when
+/// neither is set. `None` if a cloud provider has no API key; Ollama
+/// without a saved config runs at its local default URL.
+fn provider_config(
+    provider: &str,
+    model: Option<String>,
+    settings: &AppSettings,
+    keychain: &impl Fn(&str) -> Option<String>,
+) -> Option<LlmConfig> {
+    let saved = settings.provider_configs.get(provider).cloned().unwrap_or_default();
+    let (api_key, ollama) = if provider == "ollama" {
+        let options = OllamaOptions {
+            num_ctx: saved.num_ctx,
+            temperature: saved.temperature,
+            keep_alive: saved
+                .keep_alive
+                .as_deref()
+                .map(str::trim)
+                .filter(|k| !k.is_empty())
+                .map(String::from),
+        };
+        (String::new(), Some(options))
+    } else {
+        let key = settings.api_key(provider).or_else(|| keychain(provider))?;
+        (key, None)
+    };
+    let mut model = model.unwrap_or(saved.model);
+    if model.trim().is_empty() {
+        model = parse_provider_type(provider).default_model().to_string();
+    }
+    Some(llm_config(
+        provider,
+        api_key,
+        model,
+        saved.base_url,
+        saved.timeout_secs,
+        ollama,
+    ))
+}
+
+/// Build an `LlmConfig` from the frontend's per-provider settings.
+pub(crate) fn llm_config(
+    provider: &str,
+    api_key: String,
+    model: String,
+    base_url: Option<String>,
+    timeout_secs: Option<u64>,
+    ollama: Option<OllamaOptions>,
+) -> LlmConfig {
+    LlmConfig {
+        provider: parse_provider_type(provider),
+        api_key: if api_key.is_empty() {
+            None
+        } else {
+            Some(api_key)
+        },
+        model,
+        base_url: base_url.filter(|u| !u.trim().is_empty()),
+        timeout_secs,
+        ollama,
+    }
+}
+
+/// How long AI outputs stay cached; 0 turns the cache off. Incognito
+/// sessions neither read nor write it, so nothing of theirs is saved.
+fn cache_ttl_secs(settings: &AppSettings, incognito: bool) -> u64 {
+    if incognito {
+        return 0;
+    }
+    settings.ai_cache_ttl_hours.saturating_mul(3600)
+}
+
+/// Run an AI function over `text` and return the LLM's output, trying each
+/// provider from `resolve_llm_configs` until one succeeds. The exchange is
+/// kept in `AiConversation` for `refine_last_output`.
+async fn run_ai_function(
+    app: &AppHandle,
+    function_id: &str,
+    text: &str,
+    context: Option<&str>,
+    settings: &AppSettings,
+) -> Result<String, CommandError> {
     // Search built-in functions first, then custom ones from DB
     let all_functions = list_ai_functions()?;
     let func = all_functions
         .iter()
         .find(|f| f.id == function_id)
-        .ok_or("AI function not found")?;
+        .ok_or(CommandError::AiFunctionNotFound)?;
+    let configs = resolve_llm_configs(func, settings, db::settings::keychain_api_key)?;
 
-    let has_key = !llm_api_key.is_empty();
     log::info!(
-        "execute_ai_function: found function '{}', has_api_key={}",
-        func.name, has_key
+        "run_ai_function: found function '{}', provider={:?}, model={}, providers={}",
+        func.name,
+        configs[0].provider,
+        configs[0].model,
+        configs.len()
     );
 
-    let config = LlmConfig {
-        provider: parse_provider_type(&llm_provider),
-        api_key: if llm_api_key.is_empty() {
+    let system_prompt = compose_system_prompt(Some(&settings.system_prompt_prefix), &func.prompt);
+    let mut messages = vec![ChatMessage::user(compose_user_message(context, text))];
+
+    let incognito = app.state::<RecordingState>().is_incognito();
+    let ttl_secs = cache_ttl_secs(settings, incognito);
+    let model = format!("{}/{}", configs[0].provider.id(), configs[0].model);
+    let cache_key = db::ai_cache::key(function_id, &model, &system_prompt, &messages[0].content);
+    let cached = if ttl_secs > 0 {
+        db::ai_cache::get(&cache_key, ttl_secs).unwrap_or_else(|e| {
+            log::warn!("Failed to read AI cache: {}", e);
             None
-        } else {
-            Some(llm_api_key)
-        },
-        model: llm_model,
-        base_url: None,
+        })
+    } else {
+        None
     };
 
-    let provider = llm::create_provider(&config);
-    let result = provider
-        .complete(&func.prompt, &text)
-        .await
-        .map_err(|e| {
-            log::error!("execute_ai_function: LLM call failed: {}", e);
-            format!("AI function failed: {}", e)
-        })?;
+    let result = match cached {
+        Some(output) => {
+            log::info!("run_ai_function: cache hit for '{}'", func.name);
+            let _ = app.emit(
+                "ai-output-delta",
+                AiOutputDelta {
+                    function_id,
+                    delta: &output,
+                },
+            );
+            output
+        }
+        None => {
+            let result = stream_reply(app, function_id, &configs, &system_prompt, &messages)
+                .await
+                .map_err(|e| match e {
+                    CommandError::AiFailed(e) => {
+                        log::error!("run_ai_function: LLM call failed: {}", e);
+                        CommandError::AiFailed(format!("AI function failed: {}", e))
+                    }
+                    e => e,
+                })?;
 
-    if result.is_empty() {
-        log::warn!("execute_ai_function: LLM returned empty response");
-        return Err("AI function returned empty response".to_string());
+            if result.is_empty() {
+                log::warn!("run_ai_function: LLM returned empty response");
+                return Err(CommandError::AiFailed(
+                    "AI function returned empty response".to_string(),
+                ));
+            }
+            if ttl_secs > 0 {
+                let cached = db::ai_cache::put(&cache_key, function_id, &model, &result, ttl_secs);
+                if let Err(e) = cached {
+                    log::warn!("Failed to cache AI output: {}", e);
+                }
+            }
+            result
+        }
+    };
+
+    log::info!("run_ai_function: success, result={} chars", result.len());
+    messages.push(ChatMessage::assistant(result.clone()));
+    app.state::<AiConversation>().set(Conversation {
+        function: func.clone(),
+        system_prompt,
+        messages,
+    });
+    // Like the cache, use counts aren't kept for incognito sessions
+    if !incognito {
+        if let Err(e) = db::ai_functions::record_use(function_id) {
+            log::warn!("Failed to record AI function use: {}", e);
+        }
     }
+    Ok(result)
+}
 
-    log::info!(
-        "execute_ai_function: success, result={} chars",
-        result.len()
-    );
+/// Rewrite hotkey: copy the focused app's selection, run `function_id` on it
+/// and paste the result over the selection. No dictation involved.
+#[tauri::command]
+pub async fn rewrite_selection(
+    app: AppHandle,
+    function_id: String,
+) -> Result<String, CommandError> {
+    log::info!("rewrite_selection: function={}", function_id);
+
+    let selected = crate::system::selection::read_selected_text()
+        .await
+        .map_err(|e| format!("Failed to read selection: {}", e))?
+        .ok_or(CommandError::NoTextSelected)?;
+
+    let settings = db::settings::load();
+    let result = run_ai_function(&app, &function_id, &selected, None, &settings).await?;
+
+    crate::commands::clipboard::paste_text(app, result.clone(), None).await?;
     Ok(result)
 }
 
 #[tauri::command]
-pub fn save_ai_function(function: AiFunction) -> Result<(), String> {
+pub fn save_ai_function(app: AppHandle, function: AiFunction) -> Result<(), String> {
+    // Checked up front so a bad webhook URL doesn't leave a half-saved function
+    let action = output_action_json(&function.output_action)?;
     let item = db::ai_functions::AiFunctionRow {
         id: function.id,
         name: function.name,
@@ -157,25 +603,195 @@ pub fn save_ai_function(function: AiFunction) -> Result<(), String> {
         model: function.model,
         is_builtin: false,
     };
-    db::ai_functions::insert(&item).map_err(|e| e.to_string())
+    db::ai_functions::insert(&item).map_err(|e| e.to_string())?;
+    db::ai_functions::set_output_action(&item.id, &action).map_err(|e| e.to_string())?;
+    crate::system::tray::refresh_ai_functions(&app);
+    Ok(())
 }
 
+/// Set what happens with a function's output; works for built-in functions,
+/// whose other fields can't be changed.
 #[tauri::command]
-pub fn delete_ai_function(function_id: String) -> Result<(), String> {
-    db::ai_functions::delete(&function_id).map_err(|e| e.to_string())
+pub fn set_ai_function_output(function_id: String, action: OutputAction) -> Result<(), String> {
+    let json = output_action_json(&action)?;
+    db::ai_functions::set_output_action(&function_id, &json).map_err(|e| e.to_string())
 }
 
-/// Apply text processing rules (regex-based). Called from frontend pipeline.
+/// Validate an output action and serialize it for storage.
+fn output_action_json(action: &OutputAction) -> Result<String, String> {
+    if let OutputAction::Webhook { url } = action {
+        let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err("Webhook URL must start with http:// or https://".into());
+        }
+    }
+    serde_json::to_string(action).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_ai_function(app: AppHandle, function_id: String) -> Result<(), String> {
+    db::ai_functions::delete(&function_id).map_err(|e| e.to_string())?;
+    crate::system::tray::refresh_ai_functions(&app);
+    Ok(())
+}
+
+/// Drop all cached AI function outputs, returning how many were removed.
 #[tauri::command]
-pub fn apply_rules(text: String, enabled_rule_ids: Vec<String>) -> Result<String, String> {
-    let all_rules = rules::builtin_rules();
-    let active_rules: Vec<rules::Rule> = all_rules
+pub async fn clear_ai_cache() -> Result<usize, String> {
+    db::run_blocking(db::ai_cache::clear)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Identifies a shared AI function file.
+const LIBRARY_FORMAT: &str = "sobottaai-ai-functions";
+/// Version of the sharing format this build writes; newer files are rejected.
+const LIBRARY_VERSION: u32 = 1;
+
+/// Portable file of AI functions for sharing between users:
+/// `{ "format": "sobottaai-ai-functions", "version": 1, "functions": [...] }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiFunctionLibrary {
+    pub format: String,
+    pub version: u32,
+    pub functions: Vec<SharedAiFunction>,
+}
+
+/// One function in a library. Ids aren't shared; importing assigns new ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedAiFunction {
+    pub name: String,
+    pub prompt: String,
+    /// Provider the author wrote it for, e.g. "anthropic"; absent when any works.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Model the author wrote it for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl AiFunctionLibrary {
+    fn new(functions: &[AiFunction]) -> Self {
+        Self {
+            format: LIBRARY_FORMAT.into(),
+            version: LIBRARY_VERSION,
+            functions: functions
+                .iter()
+                .map(|f| SharedAiFunction {
+                    name: f.name.clone(),
+                    prompt: f.prompt.clone(),
+                    provider: Some(f.provider.clone()).filter(|p| p != "default"),
+                    model: f.model.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn parse(json: &str) -> Result<Self, String> {
+        let library: Self =
+            serde_json::from_str(json).map_err(|e| format!("Not an AI function file: {}", e))?;
+        if library.format != LIBRARY_FORMAT {
+            return Err(format!("Not an AI function file (format '{}')", library.format));
+        }
+        if library.version > LIBRARY_VERSION {
+            return Err(format!(
+                "AI function file version {} needs a newer version of SobottaAI",
+                library.version
+            ));
+        }
+        Ok(library)
+    }
+
+    /// Functions to add, skipping empty ones and any with the same name and
+    /// prompt as one in `existing`.
+    fn new_functions(self, existing: &[AiFunction]) -> Vec<AiFunction> {
+        let mut seen: Vec<(String, String)> = existing
+            .iter()
+            .map(|f| (f.name.clone(), f.prompt.clone()))
+            .collect();
+        let mut added = Vec::new();
+        for shared in self.functions {
+            let (name, prompt) = (shared.name.trim(), shared.prompt.trim());
+            if name.is_empty() || prompt.is_empty() {
+                continue;
+            }
+            let key = (name.to_string(), prompt.to_string());
+            if seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+            added.push(AiFunction {
+                id: format!("custom-{}", uuid::Uuid::new_v4()),
+                name: name.to_string(),
+                prompt: prompt.to_string(),
+                provider: shared.provider.unwrap_or_else(|| "default".into()),
+                model: shared.model,
+                is_builtin: false,
+                output_action: OutputAction::Paste,
+                use_count: 0,
+                last_used_at: None,
+            });
+        }
+        added
+    }
+}
+
+/// Write custom AI functions to `path` in the sharing format; `ids` limits
+/// the export to those functions. Returns how many were written.
+#[tauri::command]
+pub fn export_ai_functions(path: String, ids: Option<Vec<String>>) -> Result<usize, String> {
+    let functions: Vec<AiFunction> = list_ai_functions()?
         .into_iter()
-        .map(|mut r| {
-            r.enabled = enabled_rule_ids.contains(&r.id);
-            r
-        })
+        .filter(|f| !f.is_builtin)
+        .filter(|f| ids.as_ref().map_or(true, |ids| ids.contains(&f.id)))
         .collect();
+    let json = serde_json::to_string_pretty(&AiFunctionLibrary::new(&functions))
+        .map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    log::info!("Exported {} AI functions to {}", functions.len(), path);
+    Ok(functions.len())
+}
+
+/// Add the functions in a shared file at `path` as custom functions,
+/// skipping ones already present. Returns the functions added.
+#[tauri::command]
+pub fn import_ai_functions(app: AppHandle, path: String) -> Result<Vec<AiFunction>, String> {
+    let json = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let added = AiFunctionLibrary::parse(&json)?.new_functions(&list_ai_functions()?);
+    for function in &added {
+        db::ai_functions::insert(&db::ai_functions::AiFunctionRow {
+            id: function.id.clone(),
+            name: function.name.clone(),
+            prompt: function.prompt.clone(),
+            provider: function.provider.clone(),
+            model: function.model.clone(),
+            is_builtin: false,
+        })
+        .map_err(|e| e.to_string())?;
+    }
+    if !added.is_empty() {
+        crate::system::tray::refresh_ai_functions(&app);
+    }
+    log::info!("Imported {} AI functions from {}", added.len(), path);
+    Ok(added)
+}
+
+/// Apply text processing rules (regex-based) with their saved enabled state
+/// and order. `enabled_rule_ids` overrides which rules run, in built-in order.
+#[tauri::command]
+pub fn apply_rules(text: String, enabled_rule_ids: Option<Vec<String>>) -> Result<String, String> {
+    let active_rules: Vec<rules::Rule> = match enabled_rule_ids {
+        Some(ids) => rules::builtin_rules()
+            .into_iter()
+            .map(|mut r| {
+                r.enabled = ids.contains(&r.id);
+                r
+            })
+            .collect(),
+        None => db::rules::load().map_err(|e| e.to_string())?,
+    };
 
     Ok(rules::apply_regex_rules(&text, &active_rules))
 }
@@ -187,9 +803,9 @@ mod tests {
     // ── builtin_functions ────────────────────────────────────
 
     #[test]
-    fn builtin_functions_returns_five() {
+    fn builtin_functions_returns_nine() {
         let funcs = builtin_functions();
-        assert_eq!(funcs.len(), 5);
+        assert_eq!(funcs.len(), 9);
     }
 
     #[test]
@@ -232,6 +848,19 @@ mod tests {
         assert!(ids.contains(&"summarize"));
         assert!(ids.contains(&"casual"));
         assert!(ids.contains(&"translate"));
+        assert!(ids.contains(&"grammar"));
+        assert!(ids.contains(&"bullets"));
+        assert!(ids.contains(&"meeting-notes"));
+        assert!(ids.contains(&"slack"));
+    }
+
+    #[test]
+    fn grammar_builtin_uses_grammar_prompt() {
+        let grammar = builtin_functions()
+            .into_iter()
+            .find(|f| f.id == "grammar")
+            .unwrap();
+        assert_eq!(grammar.prompt, rules::grammar::GRAMMAR_SYSTEM_PROMPT);
     }
 
     // ── parse_provider_type ──────────────────────────────────
@@ -271,11 +900,141 @@ mod tests {
         assert!(matches!(parse_provider_type("default"), LlmProviderType::OpenAI));
     }
 
+    // ── compose_system_prompt ────────────────────────────────
+
+    #[test]
+    fn compose_system_prompt_without_prefix() {
+        assert_eq!(compose_system_prompt(None, "Summarize."), "Summarize.");
+    }
+
+    #[test]
+    fn compose_system_prompt_blank_prefix_ignored() {
+        assert_eq!(compose_system_prompt(Some("  \n "), "Summarize."), "Summarize.");
+    }
+
+    #[test]
+    fn compose_system_prompt_prepends_prefix() {
+        assert_eq!(
+            compose_system_prompt(Some("My name is Ada. Sign off with 'Best, Ada'.\n"), "Write an email."),
+            "My name is Ada. Sign off with 'Best, Ada'.\n\nWrite an email."
+        );
+    }
+
+    // ── compose_user_message ─────────────────────────────────
+
+    #[test]
+    fn compose_user_message_without_context() {
+        assert_eq!(compose_user_message(None, "sounds good"), "sounds good");
+        assert_eq!(compose_user_message(Some("   "), "sounds good"), "sounds good");
+    }
+
+    #[test]
+    fn compose_user_message_includes_context() {
+        let msg = compose_user_message(Some("Can we meet Friday?"), "yes friday works");
+        assert!(msg.contains("Can we meet Friday?"));
+        assert!(msg.ends_with("Dictated text:\nyes friday works"));
+    }
+
+    // ── resolve_llm_configs ──────────────────────────────────
+
+    fn settings(json: serde_json::Value) -> AppSettings {
+        AppSettings::from_map(json.as_object().unwrap())
+    }
+
+    fn summarize() -> AiFunction {
+        builtin_functions()
+            .into_iter()
+            .find(|f| f.id == "summarize")
+            .unwrap()
+    }
+
+    #[test]
+    fn resolve_uses_selected_provider_then_fallbacks() {
+        let settings = settings(serde_json::json!({
+            "llmProvider": "ollama",
+            "llmFallbackProviders": ["ollama", "anthropic", "groq", "openai"],
+            "providerConfigs": {
+                "ollama": { "apiKey": "", "model": "llama3.2", "keepAlive": " ", "numCtx": 8192 },
+                "groq": { "apiKey": "gsk_x", "model": "llama-3.3-70b-versatile" },
+                "openai": { "apiKey": "sk-x", "model": "gpt-4o-mini", "timeoutSecs": 45 }
+            }
+        }));
+        let configs = resolve_llm_configs(&summarize(), &settings, |_| None).unwrap();
+        let models: Vec<&str> = configs.iter().map(|c| c.model.as_str()).collect();
+        // Anthropic has no key and is skipped
+        assert_eq!(models, ["llama3.2", "llama-3.3-70b-versatile", "gpt-4o-mini"]);
+        assert!(configs[0].api_key.is_none());
+        let ollama = configs[0].ollama.as_ref().unwrap();
+        assert_eq!(ollama.num_ctx, Some(8192));
+        assert!(ollama.keep_alive.is_none());
+        assert_eq!(configs[2].timeout_secs, Some(45));
+    }
+
+    #[test]
+    fn resolve_prefers_function_provider_and_model() {
+        let settings = settings(serde_json::json!({
+            "llmProvider": "openai",
+            "providerConfigs": {
+                "openai": { "apiKey": "sk-x", "model": "gpt-4o-mini" },
+                "anthropic": { "apiKey": "sk-ant", "model": "claude-3-5-haiku-latest" }
+            }
+        }));
+        let function = AiFunction {
+            provider: "anthropic".into(),
+            model: Some("claude-sonnet-4-0".into()),
+            ..summarize()
+        };
+        let configs = resolve_llm_configs(&function, &settings, |_| None).unwrap();
+        assert!(matches!(configs[0].provider, LlmProviderType::Anthropic));
+        assert_eq!(configs[0].model, "claude-sonnet-4-0");
+        assert_eq!(configs[0].api_key.as_deref(), Some("sk-ant"));
+    }
+
+    #[test]
+    fn resolve_falls_back_to_keychain_key() {
+        let settings = settings(serde_json::json!({
+            "llmProvider": "groq",
+            "providerConfigs": { "groq": { "apiKey": "", "model": "llama-3.3-70b-versatile" } }
+        }));
+        let keychain = |provider: &str| (provider == "groq").then(|| "gsk_keychain".to_string());
+        let configs = resolve_llm_configs(&summarize(), &settings, keychain).unwrap();
+        assert_eq!(configs[0].api_key.as_deref(), Some("gsk_keychain"));
+
+        let err = resolve_llm_configs(&summarize(), &settings, |_| None).unwrap_err();
+        assert_eq!(err.code(), "apiKeyMissing");
+    }
+
+    #[test]
+    fn resolve_defaults_unconfigured_ollama_and_blank_models() {
+        let settings = settings(serde_json::json!({
+            "llmProvider": "ollama",
+            "llmFallbackProviders": ["openai"],
+            "providerConfigs": { "openai": { "apiKey": "sk-x", "model": " " } }
+        }));
+        let configs = resolve_llm_configs(&summarize(), &settings, |_| None).unwrap();
+        assert!(matches!(configs[0].provider, LlmProviderType::Ollama));
+        assert_eq!(configs[0].model, "llama3.2");
+        assert!(configs[0].base_url.is_none());
+        assert_eq!(configs[1].model, "gpt-4o-mini");
+    }
+
+    // ── cache_ttl_secs ───────────────────────────────────────
+
+    #[test]
+    fn incognito_skips_the_ai_cache() {
+        let settings = AppSettings {
+            ai_cache_ttl_hours: 24,
+            ..AppSettings::default()
+        };
+        assert_eq!(cache_ttl_secs(&settings, false), 24 * 3600);
+        assert_eq!(cache_ttl_secs(&settings, true), 0);
+    }
+
     // ── apply_rules (command) ────────────────────────────────
 
     #[test]
     fn apply_rules_no_enabled_ids() {
-        let result = apply_rules("um hello world".into(), vec![]).unwrap();
+        let result = apply_rules("um hello world".into(), Some(vec![])).unwrap();
         assert_eq!(result, "um hello world"); // nothing enabled → no changes
     }
 
@@ -283,7 +1042,7 @@ mod tests {
     fn apply_rules_with_filler_removal() {
         let result = apply_rules(
             "um so like I think".into(),
-            vec!["remove-fillers".into()],
+            Some(vec!["remove-fillers".into()]),
         )
         .unwrap();
         assert_eq!(result, "I think");
@@ -293,7 +1052,7 @@ mod tests {
     fn apply_rules_with_punctuation() {
         let result = apply_rules(
             "hello world".into(),
-            vec!["smart-punctuation".into()],
+            Some(vec!["smart-punctuation".into()]),
         )
         .unwrap();
         assert_eq!(result, "Hello world.");
@@ -303,7 +1062,7 @@ mod tests {
     fn apply_rules_both_filler_and_punctuation() {
         let result = apply_rules(
             "um hello world".into(),
-            vec!["remove-fillers".into(), "smart-punctuation".into()],
+            Some(vec!["remove-fillers".into(), "smart-punctuation".into()]),
         )
         .unwrap();
         assert_eq!(result, "Hello world.");
@@ -313,7 +1072,7 @@ mod tests {
     fn apply_rules_unknown_rule_id_ignored() {
         let result = apply_rules(
             "hello world".into(),
-            vec!["nonexistent-rule".into()],
+            Some(vec!["nonexistent-rule".into()]),
         )
         .unwrap();
         assert_eq!(result, "hello world");
@@ -330,6 +1089,9 @@ mod tests {
             provider: "openai".into(),
             model: Some("gpt-4".into()),
             is_builtin: false,
+            output_action: OutputAction::Paste,
+            use_count: 0,
+            last_used_at: None,
         };
         let json = serde_json::to_string(&func).unwrap();
         let deserialized: AiFunction = serde_json::from_str(&json).unwrap();
@@ -349,8 +1111,135 @@ mod tests {
             provider: "openai".into(),
             model: None,
             is_builtin: true,
+            output_action: OutputAction::Paste,
+            use_count: 0,
+            last_used_at: None,
         };
         let json = serde_json::to_string(&func).unwrap();
         assert!(json.contains("\"isBuiltin\""));
     }
+
+    #[test]
+    fn output_action_serializes_with_type_tag() {
+        let webhook = OutputAction::Webhook {
+            url: "https://example.com/hook".into(),
+        };
+        assert_eq!(
+            serde_json::to_value(&webhook).unwrap(),
+            serde_json::json!({ "type": "webhook", "url": "https://example.com/hook" })
+        );
+        let action: OutputAction =
+            serde_json::from_str(r#"{"type":"pasteKeepClipboard"}"#).unwrap();
+        assert_eq!(action, OutputAction::PasteKeepClipboard);
+
+        // Functions saved before output actions existed paste
+        let func: AiFunction = serde_json::from_str(
+            r#"{"id":"f","name":"F","prompt":"p","provider":"default","isBuiltin":false}"#,
+        )
+        .unwrap();
+        assert_eq!(func.output_action, OutputAction::Paste);
+    }
+
+    #[test]
+    fn webhook_output_needs_http_url() {
+        let webhook = |url: &str| OutputAction::Webhook { url: url.into() };
+        assert!(set_ai_function_output("f".into(), webhook("ftp://example.com")).is_err());
+        assert!(set_ai_function_output("f".into(), webhook("not a url")).is_err());
+    }
+
+    // ── Conversation ─────────────────────────────────────────
+
+    #[test]
+    fn follow_up_appends_instruction_to_last_exchange() {
+        let state = AiConversation::new();
+        assert!(state.get().is_none());
+        state.set(Conversation {
+            function: builtin_functions().remove(0),
+            system_prompt: "Rewrite as an email.".into(),
+            messages: vec![
+                ChatMessage::user("send the report friday"),
+                ChatMessage::assistant("Hi team, I'll send the report on Friday."),
+            ],
+        });
+
+        let next = state.get().unwrap().follow_up("make it more formal");
+        assert_eq!(next.system_prompt, "Rewrite as an email.");
+        assert_eq!(next.messages.len(), 3);
+        assert_eq!(next.messages[2], ChatMessage::user("make it more formal"));
+        // The stored exchange only grows once the reply arrives
+        assert_eq!(state.get().unwrap().messages.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn cancel_stops_running_requests() {
+        let cancel = std::sync::Arc::new(AiCancel::new());
+        assert!(!cancel.cancel());
+
+        let running = cancel.clone();
+        let task = tokio::spawn(async move { running.run(std::future::pending::<()>()).await });
+        tokio::task::yield_now().await;
+        assert!(cancel.cancel());
+        assert!(matches!(task.await.unwrap(), Err(CommandError::AiCancelled)));
+
+        assert_eq!(cancel.run(async { 42 }).await.unwrap(), 42);
+    }
+
+    // ── Sharing format ───────────────────────────────────────
+
+    fn custom(name: &str, prompt: &str, provider: &str) -> AiFunction {
+        AiFunction {
+            id: format!("custom-{}", name),
+            name: name.into(),
+            prompt: prompt.into(),
+            provider: provider.into(),
+            model: None,
+            is_builtin: false,
+            output_action: OutputAction::Paste,
+            use_count: 0,
+            last_used_at: None,
+        }
+    }
+
+    #[test]
+    fn library_round_trips_without_ids() {
+        let functions = vec![
+            custom("Tweet", "Rewrite as a tweet.", "default"),
+            custom("Haiku", "Rewrite as a haiku.", "anthropic"),
+        ];
+        let json = serde_json::to_string(&AiFunctionLibrary::new(&functions)).unwrap();
+        assert!(json.contains("\"format\":\"sobottaai-ai-functions\""));
+        assert!(json.contains("\"version\":1"));
+        assert!(!json.contains("custom-Tweet"));
+
+        let added = AiFunctionLibrary::parse(&json).unwrap().new_functions(&[]);
+        assert_eq!(added.len(), 2);
+        assert_eq!(added[0].provider, "default");
+        assert_eq!(added[1].provider, "anthropic");
+        assert!(added.iter().all(|f| f.id.starts_with("custom-") && !f.is_builtin));
+    }
+
+    #[test]
+    fn library_import_skips_existing_and_duplicate_functions() {
+        let json = r#"{"format":"sobottaai-ai-functions","version":1,"functions":[
+            {"name":"Tweet","prompt":"Rewrite as a tweet."},
+            {"name":"Tweet","prompt":"Rewrite as a tweet."},
+            {"name":" ","prompt":"No name"},
+            {"name":"Pirate","prompt":"Talk like a pirate."}
+        ]}"#;
+        let existing = vec![custom("Pirate", "Talk like a pirate.", "default")];
+        let added = AiFunctionLibrary::parse(json).unwrap().new_functions(&existing);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].name, "Tweet");
+    }
+
+    #[test]
+    fn library_rejects_other_formats_and_newer_versions() {
+        assert!(AiFunctionLibrary::parse("[]").is_err());
+        assert!(AiFunctionLibrary::parse(r#"{"format":"other","version":1,"functions":[]}"#)
+            .is_err());
+        let newer = r#"{"format":"sobottaai-ai-functions","version":2,"functions":[]}"#;
+        assert!(AiFunctionLibrary::parse(newer)
+            .unwrap_err()
+            .contains("newer version"));
+    }
 }
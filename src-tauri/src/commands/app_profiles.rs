@@ -0,0 +1,43 @@
+use crate::db;
+use crate::db::app_profiles::{self, AppProfile};
+
+const NEWLINE_MODES: &[&str] = &["keep", "shiftEnter", "space"];
+
+#[tauri::command]
+pub async fn list_app_profiles() -> Result<Vec<AppProfile>, String> {
+    db::run_blocking(app_profiles::list)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create (empty `id`) or update an app profile. Returns the saved profile.
+#[tauri::command]
+pub async fn save_app_profile(mut profile: AppProfile) -> Result<AppProfile, String> {
+    profile.app_name = profile.app_name.trim().to_string();
+    if profile.app_name.is_empty() {
+        return Err("App name is required".into());
+    }
+    if !NEWLINE_MODES.contains(&profile.newline_mode.as_str()) {
+        return Err(format!("Unknown newline mode: {}", profile.newline_mode));
+    }
+    if profile.id.is_empty() {
+        profile.id = uuid::Uuid::new_v4().to_string();
+    }
+    let id = profile.id.clone();
+    db::run_blocking(move || {
+        app_profiles::upsert(&profile)?;
+        app_profiles::list()?
+            .into_iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| anyhow::anyhow!("App profile not found: {}", id))
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_app_profile(id: String) -> Result<(), String> {
+    db::run_blocking(move || app_profiles::delete(&id))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,7 +1,14 @@
 use crate::audio::{processing, wav};
+use crate::commands::jobs::JobManager;
 use crate::commands::recording::{self, RecordingState};
-use std::path::PathBuf;
-use tauri::State;
+use crate::commands::transcription;
+use crate::stt::queue::Priority;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use uuid::Uuid;
 
 #[tauri::command]
@@ -9,15 +16,188 @@ pub async fn import_audio_file(
     state: State<'_, RecordingState>,
     path: String,
 ) -> Result<String, String> {
-    let path = PathBuf::from(path);
+    import(&state, &PathBuf::from(path))
+}
+
+/// Audio files the app can import.
+const AUDIO_EXTENSIONS: &[&str] = &["wav"];
+
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImportStage {
+    Importing,
+    /// Imported; the transcription runs as job `job_id` and reports through
+    /// `job-updated`.
+    Transcribing,
+    Failed,
+}
+
+/// Progress of a file dropped on the main window, emitted as `audio-import`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioImportEvent {
+    pub path: String,
+    pub stage: ImportStage,
+    pub session_id: Option<String>,
+    pub job_id: Option<String>,
+    pub model_id: Option<String>,
+    pub error: Option<String>,
+}
+
+impl AudioImportEvent {
+    fn new(path: &Path, stage: ImportStage) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            stage,
+            session_id: None,
+            job_id: None,
+            model_id: None,
+            error: None,
+        }
+    }
+
+    fn failed(path: &Path, error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::new(path, ImportStage::Failed)
+        }
+    }
+}
+
+/// Import and transcribe files dropped on the main window, one job each,
+/// with the saved model and language. The frontend saves the results to
+/// history when the jobs finish.
+pub fn import_dropped(app: &AppHandle, paths: Vec<PathBuf>) {
+    for path in paths {
+        if !is_audio_file(&path) {
+            log::info!("Ignoring dropped file {:?}: not a WAV file", path);
+            let event = AudioImportEvent::failed(&path, "Only WAV files can be imported".into());
+            let _ = app.emit("audio-import", event);
+            continue;
+        }
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let event = match start_import(&app, &path).await {
+                Ok(event) => event,
+                Err(e) => {
+                    log::error!("Import of dropped file {:?} failed: {}", path, e);
+                    AudioImportEvent::failed(&path, e)
+                }
+            };
+            let _ = app.emit("audio-import", event);
+        });
+    }
+}
 
+async fn start_import(app: &AppHandle, path: &Path) -> Result<AudioImportEvent, String> {
+    log::info!("Importing dropped file {:?}", path);
+    let _ = app.emit(
+        "audio-import",
+        AudioImportEvent::new(path, ImportStage::Importing),
+    );
+
+    let import_path = path.to_path_buf();
+    let state_app = app.clone();
+    let session_id = tokio::task::spawn_blocking(move || {
+        import(&state_app.state::<RecordingState>(), &import_path)
+    })
+    .await
+    .map_err(|e| format!("Import task panicked: {}", e))??;
+
+    let settings = crate::db::settings::load();
+    let model_id = settings.selected_model.clone();
+    let api_key = transcription::cloud_api_key(&settings, &model_id);
+    let job_id = transcription::transcribe_file(
+        app.clone(),
+        app.state::<JobManager>(),
+        session_id.clone(),
+        None,
+        None,
+        api_key,
+        None,
+        None,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(AudioImportEvent {
+        session_id: Some(session_id),
+        job_id: Some(job_id),
+        model_id: Some(model_id),
+        ..AudioImportEvent::new(path, ImportStage::Transcribing)
+    })
+}
+
+/// Load a WAV file into a new session (16 kHz mono) and return its id.
+pub fn import(state: &RecordingState, path: &Path) -> Result<String, String> {
     let (samples, sample_rate, channels) =
-        wav::read_wav_file(&path).map_err(|e| e.to_string())?;
+        wav::read_wav_file(path).map_err(|e| e.to_string())?;
 
     let processed = processing::preprocess(&samples, channels, sample_rate);
 
     let session_id = Uuid::new_v4().to_string();
-    recording::insert_session_audio(&state, &session_id, processed);
+    recording::insert_session_audio(state, &session_id, processed);
 
     Ok(session_id)
 }
+
+/// Import and transcribe a file with the saved model and language, then copy
+/// the text to the clipboard. Used by the tray without the main window.
+pub async fn transcribe_to_clipboard(app: &AppHandle, path: &Path) -> Result<String, String> {
+    let settings = crate::db::settings::load();
+    let model_id = settings.selected_model.clone();
+    let api_key = transcription::cloud_api_key(&settings, &model_id);
+
+    let session_id = import(&app.state::<RecordingState>(), path)?;
+    let result = transcription::run_transcription(
+        app,
+        &session_id,
+        None,
+        &model_id,
+        settings.language(),
+        api_key,
+        None,
+        None,
+        Priority::Background,
+        Arc::new(AtomicBool::new(false)),
+    )
+    .await;
+    recording::take_session_audio(&app.state::<RecordingState>(), &session_id);
+
+    let text = result.map_err(|e| e.to_string())?.text.trim().to_string();
+    if text.is_empty() {
+        return Err("No speech found in the file".into());
+    }
+    app.clipboard()
+        .write_text(&text)
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_wav_files_are_imported() {
+        assert!(is_audio_file(Path::new("/tmp/memo.wav")));
+        assert!(is_audio_file(Path::new("Memo.WAV")));
+        assert!(!is_audio_file(Path::new("/tmp/notes.txt")));
+        assert!(!is_audio_file(Path::new("/tmp/wav")));
+    }
+
+    #[test]
+    fn import_event_serializes_for_the_frontend() {
+        let event = AudioImportEvent::failed(Path::new("/tmp/a.mp3"), "nope".into());
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["stage"], "failed");
+        assert_eq!(json["path"], "/tmp/a.mp3");
+        assert!(json["jobId"].is_null());
+    }
+}
@@ -1,24 +1,322 @@
-use crate::system::paste;
-use tauri::AppHandle;
+use crate::commands::error::CommandError;
+use crate::commands::jobs::JobManager;
+use crate::db::history::{self, HistoryItem};
+use crate::system::paste_format::{self, PasteFormat};
+use crate::system::{app_paste, paste, secure_input, tray};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_clipboard_manager::ClipboardExt;
+use unicode_segmentation::UnicodeSegmentation;
 
+/// How many pasted outputs are kept for `get_recent_outputs` and the tray.
+pub const RECENT_OUTPUT_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentOutput {
+    pub text: String,
+    pub pasted_at: String,
+}
+
+/// The last few pasted outputs, newest first, so text can be recovered after
+/// the clipboard was overwritten. Kept in memory only.
+pub struct RecentOutputs {
+    outputs: Mutex<VecDeque<RecentOutput>>,
+}
+
+impl RecentOutputs {
+    pub fn new() -> Self {
+        Self {
+            outputs: Mutex::new(VecDeque::with_capacity(RECENT_OUTPUT_LIMIT)),
+        }
+    }
+
+    /// Remember `text` as the newest output; pasting the same text again
+    /// moves it to the front.
+    fn push(&self, text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+        let mut outputs = self.outputs.lock().unwrap();
+        outputs.retain(|o| o.text != text);
+        outputs.push_front(RecentOutput {
+            text: text.to_string(),
+            pasted_at: chrono::Utc::now().to_rfc3339(),
+        });
+        outputs.truncate(RECENT_OUTPUT_LIMIT);
+    }
+
+    pub fn list(&self) -> Vec<RecentOutput> {
+        self.outputs.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// The text at `index`, newest first.
+    pub fn get(&self, index: usize) -> Option<String> {
+        self.outputs.lock().unwrap().get(index).map(|o| o.text.clone())
+    }
+}
+
+/// How much the last `paste_text` inserted and where, so `undo_last_paste`
+/// can take it back. Kept in memory only, for the current session.
+pub struct LastPaste {
+    paste: Mutex<Option<PastedText>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PastedText {
+    /// Characters inserted, counting each typed line break as one.
+    chars: usize,
+    /// The app it went to, when the platform can tell.
+    app: Option<String>,
+}
+
+impl LastPaste {
+    pub fn new() -> Self {
+        Self {
+            paste: Mutex::new(None),
+        }
+    }
+}
+
+/// Recently pasted outputs, newest first.
 #[tauri::command]
-pub async fn paste_text(app: AppHandle, text: String) -> Result<(), String> {
-    log::info!("paste_text: writing to clipboard ({} chars)", text.len());
+pub fn get_recent_outputs(app: AppHandle) -> Vec<RecentOutput> {
+    app.state::<RecentOutputs>().list()
+}
+
+/// Paste `text` into the focused app. Resolves to whether the target was a
+/// password field, which only happens when `secureFieldPaste` is "warn";
+/// otherwise such a paste is refused with `SecureField`. `format` ("plain",
+/// "markdown" or "rich") defaults to the saved paste format. The app
+/// profile of the app in front adjusts line breaks.
+#[tauri::command]
+pub async fn paste_text(
+    app: AppHandle,
+    text: String,
+    format: Option<String>,
+) -> Result<bool, CommandError> {
+    let settings = crate::db::settings::load();
+    let format = PasteFormat::parse(format.as_deref().unwrap_or(&settings.paste_format))?;
+    let secure = tokio::task::spawn_blocking(secure_input::focused_field_is_secure)
+        .await
+        .unwrap_or(false);
+    if secure && settings.secure_field_paste != "warn" {
+        log::warn!("paste_text: refused, the focused field is a password field");
+        return Err(CommandError::SecureField);
+    }
+
+    let target = tokio::task::spawn_blocking(app_paste::frontmost_app)
+        .await
+        .ok()
+        .flatten();
+    let pieces = match target.as_deref().and_then(app_paste::profile_for) {
+        Some(profile) => app_paste::pieces(&text, &profile),
+        None => vec![text.clone()],
+    };
+    // Forget the previous paste, so a failed one isn't undone in its place
+    app.state::<LastPaste>().paste.lock().unwrap().take();
+    // What goes into a password field may be a secret, so it isn't kept
+    if !secure {
+        app.state::<RecentOutputs>().push(&text);
+        tray::refresh_recent_outputs(&app);
+    }
+
+    if settings.paste_delay_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(settings.paste_delay_ms)).await;
+    }
+
+    let chars = inserted_chars(&pieces, format);
+    for (i, piece) in pieces.into_iter().enumerate() {
+        if i > 0 {
+            tokio::task::spawn_blocking(paste::simulate_shift_enter)
+                .await
+                .map_err(|e| format!("Paste task panicked: {}", e))?
+                .map_err(|e| format!("Paste failed: {}", e))?;
+        }
+        if piece.is_empty() {
+            continue;
+        }
 
-    app.clipboard()
-        .write_text(&text)
-        .map_err(|e| e.to_string())?;
+        log::info!(
+            "paste_text: writing to clipboard ({} chars, {:?})",
+            piece.len(),
+            format
+        );
 
-    log::info!("paste_text: clipboard written, simulating paste keystroke");
+        let written = match format {
+            PasteFormat::Markdown | PasteFormat::Plain => {
+                app.clipboard().write_text(pasted_text(&piece, format))
+            }
+            PasteFormat::Rich => app.clipboard().write_html(
+                paste_format::to_html(&piece),
+                Some(pasted_text(&piece, format)),
+            ),
+        };
+        written.map_err(|e| e.to_string())?;
 
-    tokio::task::spawn_blocking(move || {
-        paste::simulate_paste()
-    })
-    .await
-    .map_err(|e| format!("Paste task panicked: {}", e))?
-    .map_err(|e| format!("Paste failed: {}", e))?;
+        log::info!("paste_text: clipboard written, simulating paste keystroke");
 
+        tokio::task::spawn_blocking(move || {
+            paste::simulate_paste()
+        })
+        .await
+        .map_err(|e| format!("Paste task panicked: {}", e))?
+        .map_err(|e| format!("Paste failed: {}", e))?;
+    }
+
+    *app.state::<LastPaste>().paste.lock().unwrap() = Some(PastedText { chars, app: target });
     log::info!("paste_text: done");
+    Ok(secure)
+}
+
+/// The text a piece puts in the target app: Markdown as written, otherwise
+/// the plain text (rich pastes render it with formatting).
+fn pasted_text(piece: &str, format: PasteFormat) -> String {
+    match format {
+        PasteFormat::Markdown => piece.to_string(),
+        PasteFormat::Plain | PasteFormat::Rich => paste_format::to_plain(piece),
+    }
+}
+
+/// Characters `paste_text` inserts for `pieces`, including the Shift+Enter
+/// typed between them. Counted as graphemes, since one Backspace deletes an
+/// emoji or an accented letter however many code points it has.
+fn inserted_chars(pieces: &[String], format: PasteFormat) -> usize {
+    let text: usize = pieces
+        .iter()
+        .map(|piece| pasted_text(piece, format).graphemes(true).count())
+        .sum();
+    text + pieces.len().saturating_sub(1)
+}
+
+/// Take back the last paste of this session by selecting it with
+/// Shift+Left and deleting it, for when the transcription came out wrong.
+/// Refused when another app is in front than the one pasted into, or when
+/// that app couldn't be told. Each paste can be undone once.
+#[tauri::command]
+pub async fn undo_last_paste(app: AppHandle) -> Result<(), CommandError> {
+    let last = app.state::<LastPaste>().paste.lock().unwrap().take();
+    let Some(last) = last else {
+        return Err(CommandError::NothingToUndo);
+    };
+    let front = tokio::task::spawn_blocking(app_paste::frontmost_app)
+        .await
+        .ok()
+        .flatten();
+    if last.app.is_none() || front != last.app {
+        log::warn!(
+            "undo_last_paste: pasted into {:?} but {:?} is in front",
+            last.app,
+            front
+        );
+        *app.state::<LastPaste>().paste.lock().unwrap() = Some(last);
+        return Err(CommandError::PasteTargetChanged);
+    }
+
+    log::info!("undo_last_paste: deleting {} chars", last.chars);
+    tokio::task::spawn_blocking(move || paste::simulate_delete_back(last.chars))
+        .await
+        .map_err(|e| format!("Undo task panicked: {}", e))?
+        .map_err(|e| format!("Undo failed: {}", e))?;
     Ok(())
 }
+
+
+/// Text to paste again for the paste-last hotkey: the newest finished
+/// transcription, taken from history when it was saved (so AI and rules
+/// output is used), otherwise the raw transcript (e.g. incognito). Falls back
+/// to the newest history item after a restart.
+pub fn last_transcript(app: &AppHandle) -> Option<String> {
+    let paste_source = crate::db::settings::load().paste_source;
+    match app.state::<JobManager>().last_completed() {
+        Some(job) => match history::get(&job.session_id).ok().flatten() {
+            Some(item) => Some(history_text(&item, &paste_source)),
+            None => job.result.map(|r| r.text),
+        },
+        None => history::list(1, 0)
+            .ok()?
+            .first()
+            .map(|item| history_text(item, &paste_source)),
+    }
+}
+
+/// The stage of a history item that the pipeline would have pasted.
+fn history_text(item: &HistoryItem, paste_source: &str) -> String {
+    match paste_source {
+        "verbatim" => None,
+        "rules" => item.rules_text.clone(),
+        _ => item.processed_text.clone().or_else(|| item.rules_text.clone()),
+    }
+    .unwrap_or_else(|| item.transcript.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> HistoryItem {
+        HistoryItem {
+            id: "h".into(),
+            audio_path: None,
+            transcript: "um hello world".into(),
+            processed_text: Some("Hello, world!".into()),
+            rules_text: Some("hello world".into()),
+            model_id: "whisper-base".into(),
+            language: None,
+            ai_function: None,
+            duration_ms: None,
+            metrics: None,
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn history_text_follows_paste_source() {
+        assert_eq!(history_text(&item(), "processed"), "Hello, world!");
+        assert_eq!(history_text(&item(), "rules"), "hello world");
+        assert_eq!(history_text(&item(), "verbatim"), "um hello world");
+    }
+
+    #[test]
+    fn recent_outputs_keep_the_newest_without_duplicates() {
+        let recent = RecentOutputs::new();
+        for i in 0..RECENT_OUTPUT_LIMIT + 2 {
+            recent.push(&format!("text {}", i));
+        }
+        recent.push("text 5");
+        recent.push("  ");
+        let texts: Vec<String> = recent.list().into_iter().map(|o| o.text).collect();
+        assert_eq!(texts.len(), RECENT_OUTPUT_LIMIT);
+        assert_eq!(texts[0], "text 5");
+        assert_eq!(texts[1], format!("text {}", RECENT_OUTPUT_LIMIT + 1));
+        assert_eq!(texts.iter().filter(|t| *t == "text 5").count(), 1);
+        assert!(!texts.contains(&"text 0".to_string()));
+        assert_eq!(recent.get(0).as_deref(), Some("text 5"));
+        assert_eq!(recent.get(RECENT_OUTPUT_LIMIT), None);
+    }
+
+    #[test]
+    fn inserted_chars_count_line_breaks_and_formatting() {
+        let pieces = vec!["**Hi**".to_string(), "there".to_string()];
+        assert_eq!(inserted_chars(&pieces, PasteFormat::Markdown), 6 + 1 + 5);
+        assert_eq!(inserted_chars(&pieces, PasteFormat::Plain), 2 + 1 + 5);
+        assert_eq!(inserted_chars(&[String::new()], PasteFormat::Plain), 0);
+        // A flag, a family emoji and a decomposed é are one Backspace each
+        let pieces = vec!["\u{1F1E9}\u{1F1EA} \u{1F468}\u{200D}\u{1F469} e\u{301}".to_string()];
+        assert_eq!(inserted_chars(&pieces, PasteFormat::Plain), 5);
+    }
+
+    #[test]
+    fn history_text_falls_back_to_transcript() {
+        let bare = HistoryItem {
+            processed_text: None,
+            rules_text: None,
+            ..item()
+        };
+        assert_eq!(history_text(&bare, "processed"), "um hello world");
+        assert_eq!(history_text(&bare, "rules"), "um hello world");
+    }
+}
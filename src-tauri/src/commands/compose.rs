@@ -0,0 +1,413 @@
+//! Compose mode: successive dictations are appended to one growing document
+//! instead of being pasted one at a time. Finishing runs the AI function
+//! once over the whole text and saves a single history item.
+
+use crate::audio::wav;
+use crate::commands::error::CommandError;
+use crate::commands::recording::{self, RecordingState};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+/// Sample rate of the session WAVs that get merged.
+const SAMPLE_RATE: u32 = 16000;
+
+/// One dictation added to the document, after rules were applied.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposePart {
+    pub session_id: String,
+    /// Raw engine output.
+    pub transcript: String,
+    /// Text after rules, which is what the document shows.
+    pub rules_text: String,
+    pub model_id: String,
+    pub language: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+struct ComposeSession {
+    id: String,
+    parts: Vec<ComposePart>,
+}
+
+impl ComposeSession {
+    fn text(&self) -> String {
+        join_parts(self.parts.iter().map(|p| p.rules_text.as_str()))
+    }
+
+    fn transcript(&self) -> String {
+        join_parts(self.parts.iter().map(|p| p.transcript.as_str()))
+    }
+
+    fn status(&self) -> ComposeStatus {
+        ComposeStatus {
+            id: self.id.clone(),
+            parts: self.parts.len(),
+            text: self.text(),
+            duration_ms: self.parts.iter().filter_map(|p| p.duration_ms).sum(),
+        }
+    }
+}
+
+/// Join dictations into running text, one space between them.
+fn join_parts<'a>(parts: impl Iterator<Item = &'a str>) -> String {
+    parts
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The document so far, sent to the frontend as `compose-changed`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposeStatus {
+    pub id: String,
+    /// Number of dictations appended.
+    pub parts: usize,
+    pub text: String,
+    pub duration_ms: i64,
+}
+
+/// The finished document at each pipeline stage, so the frontend can paste
+/// the one its paste source setting picks.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposeResult {
+    pub transcript: String,
+    pub rules_text: String,
+    pub processed_text: Option<String>,
+}
+
+pub struct ComposeState {
+    session: Mutex<Option<ComposeSession>>,
+}
+
+impl ComposeState {
+    pub fn new() -> Self {
+        Self {
+            session: Mutex::new(None),
+        }
+    }
+
+    /// Parts in the active compose session, or `None` when not composing.
+    pub fn parts(&self) -> Option<usize> {
+        self.session.lock().unwrap().as_ref().map(|s| s.parts.len())
+    }
+}
+
+/// Tell the tray and windows that the compose session changed.
+fn notify(app: &AppHandle, status: Option<&ComposeStatus>) {
+    crate::system::tray::set_compose_status(app, status.map(|s| s.parts));
+    let _ = app.emit("compose-changed", status);
+}
+
+/// Start collecting dictations into one document. Returns the existing
+/// session if one is already active.
+#[tauri::command]
+pub fn start_compose(app: AppHandle) -> ComposeStatus {
+    let status = {
+        let state = app.state::<ComposeState>();
+        let mut session = state.session.lock().unwrap();
+        session
+            .get_or_insert_with(|| {
+                log::info!("Compose session started");
+                ComposeSession {
+                    id: Uuid::new_v4().to_string(),
+                    parts: Vec::new(),
+                }
+            })
+            .status()
+    };
+    notify(&app, Some(&status));
+    status
+}
+
+/// The active compose session, if any.
+#[tauri::command]
+pub fn get_compose(app: AppHandle) -> Option<ComposeStatus> {
+    let state = app.state::<ComposeState>();
+    let session = state.session.lock().unwrap();
+    session.as_ref().map(ComposeSession::status)
+}
+
+/// Append a dictation to the active compose session. Returns `None` when
+/// not composing, in which case the caller pastes the dictation as usual.
+#[tauri::command]
+pub fn compose_append(app: AppHandle, part: ComposePart) -> Option<ComposeStatus> {
+    let status = {
+        let state = app.state::<ComposeState>();
+        let mut session = state.session.lock().unwrap();
+        let session = session.as_mut()?;
+        session.parts.push(part);
+        session.status()
+    };
+    log::info!("Compose: appended part {}", status.parts);
+    notify(&app, Some(&status));
+    Some(status)
+}
+
+/// Finish the compose session: run `ai_function` once over the whole
+/// document and save it as a single history item with the parts' audio
+/// merged. The session is taken up front, so dictations made while the AI
+/// function runs are pasted as usual and a second finish finds nothing. On
+/// an AI error the session is put back so it can be finished again.
+#[tauri::command]
+pub async fn finish_compose(
+    app: AppHandle,
+    ai_function: Option<String>,
+    keep_rules_text: bool,
+) -> Result<ComposeResult, CommandError> {
+    let session = app
+        .state::<ComposeState>()
+        .session
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| CommandError::Other("Not composing".into()))?;
+    notify(&app, None);
+    let rules_text = session.text();
+    if rules_text.is_empty() {
+        discard_parts(&app, session).await?;
+        return Err(CommandError::NoAudio);
+    }
+
+    let processed_text = match &ai_function {
+        Some(function_id) => {
+            let processed = crate::commands::ai_functions::execute_ai_function(
+                app.clone(),
+                rules_text.clone(),
+                function_id.clone(),
+            )
+            .await;
+            match processed {
+                Ok(text) => Some(text),
+                Err(e) => {
+                    put_back(&app, session);
+                    return Err(e);
+                }
+            }
+        }
+        None => None,
+    };
+
+    let first = &session.parts[0];
+    let transcript = session.transcript();
+    let session_ids: Vec<String> = session.parts.iter().map(|p| p.session_id.clone()).collect();
+    if let Ok(audio_dir) = crate::system::storage::audio_dir(&app) {
+        let id = session.id.clone();
+        let merged =
+            tokio::task::spawn_blocking(move || merge_audio(&audio_dir, &id, &session_ids))
+                .await
+                .map_err(|e| e.to_string())?;
+        if let Err(e) = merged {
+            log::warn!("Failed to merge compose audio: {}", e);
+        }
+    }
+    crate::commands::history::save_history_item(
+        app.clone(),
+        app.state::<RecordingState>(),
+        session.id.clone(),
+        transcript.clone(),
+        processed_text.clone(),
+        keep_rules_text.then(|| rules_text.clone()),
+        first.model_id.clone(),
+        first.language.clone(),
+        ai_function,
+        Some(session.status().duration_ms),
+        None,
+    )
+    .await?;
+    log::info!(
+        "Compose session finished with {} parts",
+        session.parts.len()
+    );
+
+    Ok(ComposeResult {
+        transcript,
+        rules_text,
+        processed_text,
+    })
+}
+
+/// Restore a session taken by a finish that failed. If a new session was
+/// started in the meantime, its parts go after the restored ones.
+fn put_back(app: &AppHandle, mut session: ComposeSession) {
+    let status = {
+        let state = app.state::<ComposeState>();
+        let mut slot = state.session.lock().unwrap();
+        if let Some(newer) = slot.take() {
+            session.parts.extend(newer.parts);
+        }
+        slot.insert(session).status()
+    };
+    notify(app, Some(&status));
+}
+
+/// Drop the compose session and the audio of its parts.
+#[tauri::command]
+pub async fn discard_compose(app: AppHandle) -> Result<(), String> {
+    let session = app.state::<ComposeState>().session.lock().unwrap().take();
+    notify(&app, None);
+    let Some(session) = session else {
+        return Ok(());
+    };
+    discard_parts(&app, session).await
+}
+
+async fn discard_parts(app: &AppHandle, session: ComposeSession) -> Result<(), String> {
+    log::info!("Compose session discarded ({} parts)", session.parts.len());
+    for part in session.parts {
+        recording::discard_session(app.clone(), app.state::<RecordingState>(), part.session_id)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Join the parts' 16 kHz mono WAVs into `{id}.wav` and their kept original
+/// captures into `{id}`'s original, removing the part files. Parts without
+/// audio (incognito, or originals not kept) are skipped. Originals recorded
+/// in different formats (the input device changed) are left as they are.
+fn merge_audio(audio_dir: &Path, id: &str, session_ids: &[String]) -> anyhow::Result<()> {
+    let parts: Vec<PathBuf> = session_ids
+        .iter()
+        .map(|session_id| audio_dir.join(format!("{}.wav", session_id)))
+        .collect();
+    concat_wavs(
+        &parts,
+        &audio_dir.join(format!("{}.wav", id)),
+        Some((SAMPLE_RATE, 1)),
+    )?;
+
+    let originals: Vec<PathBuf> = session_ids
+        .iter()
+        .map(|session_id| recording::original_wav_path(audio_dir, session_id))
+        .collect();
+    concat_wavs(&originals, &recording::original_wav_path(audio_dir, id), None)
+}
+
+/// Write the existing files among `paths` one after another into `dest`,
+/// then remove them. All must share one (sample rate, channels) format,
+/// `format` when given; otherwise nothing is written or removed.
+fn concat_wavs(
+    paths: &[PathBuf],
+    dest: &Path,
+    mut format: Option<(u32, u16)>,
+) -> anyhow::Result<()> {
+    let mut samples = Vec::new();
+    let mut merged = Vec::new();
+    for path in paths {
+        if !path.exists() {
+            continue;
+        }
+        let (part, sample_rate, channels) = wav::read_wav_file(path)?;
+        let expected = *format.get_or_insert((sample_rate, channels));
+        anyhow::ensure!(
+            expected == (sample_rate, channels),
+            "Unexpected format in {:?}: {} Hz, {} channels",
+            path,
+            sample_rate,
+            channels
+        );
+        samples.extend(part);
+        merged.push(path);
+    }
+    let Some((sample_rate, channels)) = format.filter(|_| !merged.is_empty()) else {
+        return Ok(());
+    };
+
+    wav::save_wav_channels(&samples, sample_rate, channels, dest)?;
+    for path in merged {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(session_id: &str, text: &str) -> ComposePart {
+        ComposePart {
+            session_id: session_id.into(),
+            transcript: text.to_lowercase(),
+            rules_text: text.into(),
+            model_id: "whisper-base".into(),
+            language: None,
+            duration_ms: Some(1500),
+        }
+    }
+
+    #[test]
+    fn session_joins_parts_into_one_document() {
+        let session = ComposeSession {
+            id: "c".into(),
+            parts: vec![
+                part("a", "Hi team,"),
+                part("b", "  "),
+                part("c", "the report is ready. "),
+            ],
+        };
+        assert_eq!(session.text(), "Hi team, the report is ready.");
+        assert_eq!(session.transcript(), "hi team, the report is ready.");
+        let status = session.status();
+        assert_eq!(status.parts, 3);
+        assert_eq!(status.duration_ms, 4500);
+    }
+
+    #[test]
+    fn merge_audio_concatenates_and_removes_parts() {
+        let dir = std::env::temp_dir().join(format!("sobottaai-compose-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        wav::save_wav(&[0.5; 160], SAMPLE_RATE, &dir.join("a.wav")).unwrap();
+        wav::save_wav(&[-0.5; 320], SAMPLE_RATE, &dir.join("b.wav")).unwrap();
+        let ids = ["a".to_string(), "missing".to_string(), "b".to_string()];
+
+        merge_audio(&dir, "doc", &ids).unwrap();
+
+        let (samples, sample_rate, channels) = wav::read_wav_file(&dir.join("doc.wav")).unwrap();
+        assert_eq!(
+            (samples.len(), sample_rate, channels),
+            (480, SAMPLE_RATE, 1)
+        );
+        assert!(samples[0] > 0.0 && samples[479] < 0.0);
+        assert!(!dir.join("a.wav").exists() && !dir.join("b.wav").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn merge_audio_merges_the_originals() {
+        let dir = std::env::temp_dir().join(format!("sobottaai-compose-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = |id: &str| recording::original_wav_path(&dir, id);
+        wav::save_wav_channels(&[0.5; 960], 48000, 2, &original("a")).unwrap();
+        wav::save_wav_channels(&[-0.5; 480], 48000, 2, &original("b")).unwrap();
+        let ids = ["a".to_string(), "b".to_string()];
+
+        merge_audio(&dir, "doc", &ids).unwrap();
+
+        let (samples, sample_rate, channels) = wav::read_wav_file(&original("doc")).unwrap();
+        assert_eq!((samples.len(), sample_rate, channels), (1440, 48000, 2));
+        assert!(!original("a").exists() && !original("b").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn merge_audio_keeps_originals_it_cant_join() {
+        let dir = std::env::temp_dir().join(format!("sobottaai-compose-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = |id: &str| recording::original_wav_path(&dir, id);
+        wav::save_wav_channels(&[0.5; 960], 48000, 2, &original("a")).unwrap();
+        wav::save_wav_channels(&[-0.5; 441], 44100, 1, &original("b")).unwrap();
+        let ids = ["a".to_string(), "b".to_string()];
+
+        assert!(merge_audio(&dir, "doc", &ids).is_err());
+        assert!(original("a").exists() && original("b").exists());
+        assert!(!original("doc").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::db;
+use crate::db::device_bindings::{self, DeviceBinding};
+use crate::system::hid_input::{self, HidDeviceInfo};
+use crate::system::hotkey::HotkeyAction;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// How long `capture_hid_button` waits for a press.
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[tauri::command]
+pub async fn list_device_bindings() -> Result<Vec<DeviceBinding>, String> {
+    db::run_blocking(device_bindings::list)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create (empty `id`) or update a device binding and restart listening.
+/// Returns the saved binding.
+#[tauri::command]
+pub async fn save_device_binding(
+    app: AppHandle,
+    mut binding: DeviceBinding,
+) -> Result<DeviceBinding, String> {
+    if HotkeyAction::from_id(&binding.action).is_none() {
+        return Err(format!("Unknown action: {}", binding.action));
+    }
+    binding.name = binding.name.trim().to_string();
+    if binding.id.is_empty() {
+        binding.id = uuid::Uuid::new_v4().to_string();
+    }
+    let id = binding.id.clone();
+    let saved = db::run_blocking(move || {
+        device_bindings::upsert(&binding)?;
+        device_bindings::list()?
+            .into_iter()
+            .find(|b| b.id == id)
+            .ok_or_else(|| anyhow::anyhow!("Device binding not found: {}", id))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    hid_input::restart(&app)?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_device_binding(app: AppHandle, id: String) -> Result<(), String> {
+    db::run_blocking(move || device_bindings::delete(&id))
+        .await
+        .map_err(|e| e.to_string())?;
+    hid_input::restart(&app)
+}
+
+/// Connected HID devices, e.g. foot pedals.
+#[tauri::command]
+pub async fn list_hid_devices() -> Result<Vec<HidDeviceInfo>, String> {
+    tokio::task::spawn_blocking(hid_input::list_devices)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Wait up to 10 seconds for a button press on the device. Resolves to the
+/// button, or `null` when none was pressed.
+#[tauri::command]
+pub async fn capture_hid_button(vendor_id: u16, product_id: u16) -> Result<Option<u32>, String> {
+    tokio::task::spawn_blocking(move || {
+        hid_input::capture_button(vendor_id, product_id, CAPTURE_TIMEOUT)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
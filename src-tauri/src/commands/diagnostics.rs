@@ -0,0 +1,260 @@
+use crate::commands::jobs::JobManager;
+use crate::models;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+use zip::write::SimpleFileOptions;
+
+/// Only the newest log files go into the bundle, each cut to its tail.
+const MAX_LOG_FILES: usize = 5;
+const MAX_LOG_BYTES: u64 = 2 * 1024 * 1024;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SystemInfo {
+    app_version: String,
+    os: &'static str,
+    os_family: &'static str,
+    arch: &'static str,
+    cpu_count: usize,
+    process_memory_bytes: Option<u64>,
+    free_disk_bytes: Option<u64>,
+    models: Vec<ModelSummary>,
+    last_failed_job: Option<Value>,
+    created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelSummary {
+    id: String,
+    downloaded: bool,
+    size_bytes: u64,
+}
+
+/// Zip recent logs, system info, the model list and redacted settings for a
+/// bug report. With `include_failed_audio`, the WAV of the most recent failed
+/// transcription is added too. Written to `dest`, or the Downloads folder.
+/// Returns the path of the bundle.
+#[tauri::command]
+pub async fn collect_diagnostics(
+    app: AppHandle,
+    job_manager: State<'_, JobManager>,
+    dest: Option<String>,
+    include_failed_audio: Option<bool>,
+) -> Result<String, String> {
+    let storage_dir = crate::system::storage::root(&app)?;
+    let log_dir = app.path().app_log_dir().map_err(|e| e.to_string())?;
+    let dest = match dest {
+        Some(dest) => PathBuf::from(dest),
+        None => app
+            .path()
+            .download_dir()
+            .map_err(|e| e.to_string())?
+            .join(format!(
+                "sobottaai-diagnostics-{}.zip",
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            )),
+    };
+
+    let last_failed = job_manager.last_failed();
+    let failed_audio = if include_failed_audio.unwrap_or(false) {
+        last_failed
+            .as_ref()
+            .map(|job| storage_dir.join("audio").join(format!("{}.wav", job.session_id)))
+            .filter(|path| path.exists())
+    } else {
+        None
+    };
+
+    let usage = models::disk_usage(&storage_dir, &models::full_catalog());
+    let info = SystemInfo {
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS,
+        os_family: std::env::consts::FAMILY,
+        arch: std::env::consts::ARCH,
+        cpu_count: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        process_memory_bytes: memory_stats::memory_stats().map(|m| m.physical_mem as u64),
+        free_disk_bytes: fs4::available_space(&storage_dir).ok(),
+        models: models::full_catalog()
+            .iter()
+            .map(|m| ModelSummary {
+                id: m.id.clone(),
+                downloaded: models::is_model_downloaded(&storage_dir, m),
+                size_bytes: usage
+                    .iter()
+                    .find(|u| u.model_id == m.id)
+                    .map_or(0, |u| u.size_bytes),
+            })
+            .collect(),
+        last_failed_job: last_failed.and_then(|job| serde_json::to_value(job).ok()),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let settings = crate::db::settings::get_all()
+        .map(|map| redact(Value::Object(map)))
+        .unwrap_or(Value::Null);
+
+    tokio::task::spawn_blocking(move || {
+        write_bundle(&dest, &info, &settings, &log_dir, failed_audio.as_deref())?;
+        log::info!("Diagnostics bundle written to {:?}", dest);
+        Ok::<_, anyhow::Error>(dest.to_string_lossy().into_owned())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+fn write_bundle(
+    dest: &Path,
+    info: &SystemInfo,
+    settings: &Value,
+    log_dir: &Path,
+    failed_audio: Option<&Path>,
+) -> anyhow::Result<()> {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(dest)?);
+    let options = SimpleFileOptions::default();
+
+    zip.start_file("system.json", options)?;
+    zip.write_all(&serde_json::to_vec_pretty(info)?)?;
+
+    zip.start_file("settings.json", options)?;
+    zip.write_all(&serde_json::to_vec_pretty(settings)?)?;
+
+    for path in recent_logs(log_dir) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        zip.start_file(format!("logs/{}", name), options)?;
+        zip.write_all(&read_tail(&path, MAX_LOG_BYTES)?)?;
+    }
+
+    if let Some(path) = failed_audio {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        zip.start_file(format!("audio/{}", name), options)?;
+        std::io::copy(&mut std::fs::File::open(path)?, &mut zip)?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+/// The newest `MAX_LOG_FILES` files in the log directory.
+fn recent_logs(log_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(log_dir) else {
+        return vec![];
+    };
+    let mut logs: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter_map(|e| {
+            let meta = e.metadata().ok().filter(|m| m.is_file())?;
+            Some((meta.modified().ok()?, e.path()))
+        })
+        .collect();
+    logs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    logs.into_iter()
+        .take(MAX_LOG_FILES)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// Last `max_bytes` of a file.
+fn read_tail(path: &Path, max_bytes: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    if len > max_bytes {
+        file.seek(SeekFrom::Start(len - max_bytes))?;
+    }
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// Blank out API keys anywhere in the settings tree.
+fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let secret = key.to_lowercase().ends_with("apikey")
+                        && value.as_str().is_some_and(|s| !s.is_empty());
+                    let value = if secret {
+                        Value::String("[redacted]".into())
+                    } else {
+                        redact(value)
+                    };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_hides_api_keys_only() {
+        let settings = serde_json::json!({
+            "defaultHotkey": "Alt+Space",
+            "providerConfigs": {
+                "openai": { "apiKey": "sk-secret", "model": "gpt-4o-mini" },
+                "ollama": { "apiKey": "", "model": "llama3.2" }
+            },
+            "sttApiKey": "gsk-secret"
+        });
+        let redacted = redact(settings);
+        assert_eq!(redacted["defaultHotkey"], "Alt+Space");
+        assert_eq!(redacted["providerConfigs"]["openai"]["apiKey"], "[redacted]");
+        assert_eq!(redacted["providerConfigs"]["openai"]["model"], "gpt-4o-mini");
+        assert_eq!(redacted["providerConfigs"]["ollama"]["apiKey"], "");
+        assert_eq!(redacted["sttApiKey"], "[redacted]");
+    }
+
+    #[test]
+    fn read_tail_keeps_end_of_file() {
+        let path = std::env::temp_dir().join("sobotta_test_diag_tail.log");
+        std::fs::write(&path, b"0123456789").unwrap();
+        assert_eq!(read_tail(&path, 4).unwrap(), b"6789");
+        assert_eq!(read_tail(&path, 100).unwrap(), b"0123456789");
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn write_bundle_contains_expected_entries() {
+        let dir = std::env::temp_dir().join("sobotta_test_diag_bundle");
+        std::fs::remove_dir_all(&dir).ok();
+        let log_dir = dir.join("logs");
+        std::fs::create_dir_all(&log_dir).unwrap();
+        std::fs::write(log_dir.join("sobottaai.log"), b"started").unwrap();
+        let audio = dir.join("session.wav");
+        std::fs::write(&audio, b"RIFF").unwrap();
+
+        let info = SystemInfo {
+            app_version: "0.0.0".into(),
+            os: "test",
+            os_family: "test",
+            arch: "test",
+            cpu_count: 1,
+            process_memory_bytes: None,
+            free_disk_bytes: None,
+            models: vec![],
+            last_failed_job: None,
+            created_at: String::new(),
+        };
+        let dest = dir.join("bundle.zip");
+        write_bundle(&dest, &info, &Value::Null, &log_dir, Some(&audio)).unwrap();
+
+        let archive = zip::ZipArchive::new(std::fs::File::open(&dest).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["audio/session.wav", "logs/sobottaai.log", "settings.json", "system.json"]
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
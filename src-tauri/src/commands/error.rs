@@ -0,0 +1,170 @@
+//! Error type for commands, sent to the frontend as `{ code, message }` so
+//! it can tell a missing model from a denied microphone or a bad API key.
+
+use crate::stt::cloud_http::{CloudSttError, ErrorKind};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum CommandError {
+    #[error("Recording already in progress")]
+    AlreadyRecording,
+    #[error("No active recording")]
+    NotRecording,
+    #[error(
+        "Microphone access denied — allow it in System Settings → Privacy & Security → Microphone"
+    )]
+    MicPermissionDenied,
+    /// The input device couldn't be opened or started.
+    #[error("{0}")]
+    AudioDevice(String),
+    #[error("No audio data captured")]
+    NoAudio,
+    #[error("Unknown model: {0}")]
+    UnknownModel(String),
+    #[error("Model '{0}' is not downloaded")]
+    ModelNotDownloaded(String),
+    #[error("{model} can't transcribe '{language}'; choose another language or model")]
+    UnsupportedLanguage { model: String, language: String },
+    #[error("{0}")]
+    InsufficientDiskSpace(String),
+    #[error("{0}")]
+    DownloadFailed(String),
+    /// What the key is needed for, e.g. "cloud Groq transcription".
+    #[error("API key required for {0}")]
+    ApiKeyMissing(String),
+    #[error("{0}")]
+    ApiKeyInvalid(String),
+    #[error("{0}")]
+    RateLimited(String),
+    #[error("{0}")]
+    Timeout(String),
+    #[error("{0}")]
+    Network(String),
+    #[error("Transcription cancelled")]
+    Cancelled,
+    #[error("Not pasted: the focused field is a password field")]
+    SecureField,
+    #[error("No text selected")]
+    NoTextSelected,
+    #[error("AI function not found")]
+    AiFunctionNotFound,
+    #[error("No AI output to refine yet")]
+    NoAiOutput,
+    #[error("Nothing to undo")]
+    NothingToUndo,
+    #[error("Not undone: the app that was pasted into is no longer in front")]
+    PasteTargetChanged,
+    #[error("AI function cancelled")]
+    AiCancelled,
+    #[error("{0}")]
+    AiFailed(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl CommandError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::AlreadyRecording => "alreadyRecording",
+            Self::NotRecording => "notRecording",
+            Self::MicPermissionDenied => "micPermissionDenied",
+            Self::AudioDevice(_) => "audioDevice",
+            Self::NoAudio => "noAudio",
+            Self::UnknownModel(_) => "unknownModel",
+            Self::ModelNotDownloaded(_) => "modelNotDownloaded",
+            Self::UnsupportedLanguage { .. } => "unsupportedLanguage",
+            Self::InsufficientDiskSpace(_) => "insufficientDiskSpace",
+            Self::DownloadFailed(_) => "downloadFailed",
+            Self::ApiKeyMissing(_) => "apiKeyMissing",
+            Self::ApiKeyInvalid(_) => "apiKeyInvalid",
+            Self::RateLimited(_) => "rateLimited",
+            Self::Timeout(_) => "timeout",
+            Self::Network(_) => "network",
+            Self::Cancelled | Self::AiCancelled => "cancelled",
+            Self::SecureField => "secureField",
+            Self::NoTextSelected => "noTextSelected",
+            Self::AiFunctionNotFound => "aiFunctionNotFound",
+            Self::NoAiOutput => "noAiOutput",
+            Self::NothingToUndo => "nothingToUndo",
+            Self::PasteTargetChanged => "pasteTargetChanged",
+            Self::AiFailed(_) => "aiFailed",
+            Self::Other(_) => "other",
+        }
+    }
+
+    /// Categorize a failed cloud transcription, prefixing the message with
+    /// `context`. Errors other than `CloudSttError` become `Other`.
+    pub fn cloud_stt(error: anyhow::Error, context: &str) -> Self {
+        let message = format!("{}: {}", context, error);
+        match error.downcast_ref::<CloudSttError>().map(|e| e.kind) {
+            Some(ErrorKind::Auth) => Self::ApiKeyInvalid(message),
+            Some(ErrorKind::RateLimited) => Self::RateLimited(message),
+            Some(ErrorKind::Timeout) => Self::Timeout(message),
+            Some(ErrorKind::Network) => Self::Network(message),
+            Some(ErrorKind::Server | ErrorKind::Rejected) | None => Self::Other(message),
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::Other(message.to_string())
+    }
+}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CommandError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_as_code_and_message() {
+        let json = serde_json::to_value(CommandError::ModelNotDownloaded("base".into())).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "code": "modelNotDownloaded",
+                "message": "Model 'base' is not downloaded",
+            })
+        );
+    }
+
+    #[test]
+    fn plain_strings_become_other() {
+        let err: CommandError = "Session not found".into();
+        assert_eq!(err.code(), "other");
+        assert_eq!(err.to_string(), "Session not found");
+    }
+
+    #[test]
+    fn cloud_stt_errors_keep_their_category() {
+        let err = CloudSttError {
+            kind: ErrorKind::Auth,
+            provider: "Groq",
+            message: "401 Unauthorized".into(),
+        };
+        let err = CommandError::cloud_stt(err.into(), "Cloud Groq transcription failed");
+        assert_eq!(err.code(), "apiKeyInvalid");
+        assert_eq!(
+            err.to_string(),
+            "Cloud Groq transcription failed: Groq rejected the API key: 401 Unauthorized"
+        );
+
+        let err = CommandError::cloud_stt(anyhow::anyhow!("bad json"), "Failed");
+        assert_eq!(err.code(), "other");
+    }
+}
@@ -1,56 +1,106 @@
-use crate::db::history::{self, HistoryItem};
-use tauri::{AppHandle, Manager};
+use crate::audio::waveform::{self, WaveformPeaks};
+use crate::audio::wav;
+use crate::commands::recording::{self, RecordingState};
+use crate::db;
+use crate::db::corrections;
+use crate::db::history::{self, HistoryFilter, HistoryItem};
+use crate::stt::InferenceMetrics;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
-pub async fn get_history(limit: usize, offset: usize) -> Result<Vec<HistoryItem>, String> {
-    tokio::task::spawn_blocking(move || history::list(limit, offset))
+pub async fn get_history(
+    limit: usize,
+    offset: usize,
+    filter: Option<HistoryFilter>,
+) -> Result<Vec<HistoryItem>, String> {
+    let filter = filter.unwrap_or_default();
+    db::run_blocking(move || history::list_filtered(&filter, limit, offset))
         .await
-        .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn search_history(query: String) -> Result<Vec<HistoryItem>, String> {
-    tokio::task::spawn_blocking(move || history::search(&query))
+pub async fn search_history(
+    query: String,
+    filter: Option<HistoryFilter>,
+) -> Result<Vec<HistoryItem>, String> {
+    let filter = HistoryFilter {
+        query: Some(query),
+        ..filter.unwrap_or_default()
+    };
+    db::run_blocking(move || history::list_filtered(&filter, 100, 0))
         .await
-        .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn get_history_item(id: String) -> Result<Option<HistoryItem>, String> {
-    tokio::task::spawn_blocking(move || history::get(&id))
+    db::run_blocking(move || history::get(&id))
         .await
-        .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())
 }
 
+/// Delete a history item and its session audio, so the WAV isn't later
+/// taken for an unfinished recording and recovered.
 #[tauri::command]
-pub async fn delete_history_item(id: String) -> Result<(), String> {
-    tokio::task::spawn_blocking(move || history::delete(&id))
+pub async fn delete_history_item(app: AppHandle, id: String) -> Result<(), String> {
+    let session_id = id.clone();
+    db::run_blocking(move || history::delete(&id))
+        .await
+        .map_err(|e| e.to_string())?;
+    // Ids become file names, so only session ids (UUIDs) are looked up
+    if uuid::Uuid::parse_str(&session_id).is_err() {
+        return Ok(());
+    }
+    let audio_dir = crate::system::storage::audio_dir(&app)?;
+    tokio::task::spawn_blocking(move || recording::remove_session_files(&audio_dir, &session_id))
         .await
         .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())
 }
 
+/// Save the user's edit of a verbatim transcript, remembering which words
+/// they corrected so `suggest_vocabulary` can learn from them.
+#[tauri::command]
+pub async fn edit_history_transcript(id: String, transcript: String) -> Result<(), String> {
+    db::run_blocking(move || {
+        let item = history::get(&id)?
+            .ok_or_else(|| anyhow::anyhow!("History item not found: {}", id))?;
+        let fixes = corrections::word_corrections(&item.transcript, &transcript);
+        if !fixes.is_empty() {
+            log::info!("Recording {} corrections from edited transcript", fixes.len());
+            corrections::record(&id, &fixes)?;
+        }
+        history::update_transcript(&id, &transcript)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub async fn save_history_item(
     app: AppHandle,
+    recording_state: State<'_, RecordingState>,
     session_id: String,
     transcript: String,
     processed_text: Option<String>,
+    rules_text: Option<String>,
     model_id: String,
     language: Option<String>,
     ai_function: Option<String>,
     duration_ms: Option<i64>,
+    metrics: Option<InferenceMetrics>,
 ) -> Result<(), String> {
+    if recording_state.is_incognito() {
+        log::info!("Incognito mode: not saving history for session {}", session_id);
+        return Ok(());
+    }
+
     // Check if WAV file exists for this session
-    let audio_path = app
-        .path()
-        .app_data_dir()
+    let audio_path = crate::system::storage::audio_dir(&app)
         .ok()
-        .map(|dir| dir.join("audio").join(format!("{}.wav", session_id)))
+        .map(|dir| dir.join(format!("{}.wav", session_id)))
         .filter(|p| p.exists())
         .map(|p| p.to_string_lossy().to_string());
 
@@ -59,15 +109,55 @@ pub async fn save_history_item(
         audio_path,
         transcript,
         processed_text,
+        rules_text,
         model_id,
         language,
         ai_function,
         duration_ms,
+        metrics,
         created_at: chrono::Utc::now().to_rfc3339(),
     };
 
-    tokio::task::spawn_blocking(move || history::insert(&item))
+    db::run_blocking(move || history::insert(&item))
         .await
-        .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())
 }
+
+/// Compute min/max peak buckets for a session's audio so the UI can draw a
+/// waveform. Looks in in-memory session audio first, then the history entry's
+/// WAV, then the default `audio/{id}.wav` location.
+#[tauri::command]
+pub async fn get_waveform_peaks(
+    app: AppHandle,
+    recording_state: State<'_, RecordingState>,
+    id: String,
+    buckets: usize,
+) -> Result<WaveformPeaks, String> {
+    if let Some(samples) = recording::get_session_audio(&recording_state, &id) {
+        return Ok(waveform::waveform_peaks(&samples, 16000, 1, buckets));
+    }
+
+    let default_path = crate::system::storage::audio_dir(&app)
+        .ok()
+        .map(|dir| dir.join(format!("{}.wav", id)));
+
+    tokio::task::spawn_blocking(move || {
+        let path = history::get(&id)?
+            .and_then(|item| item.audio_path)
+            .map(std::path::PathBuf::from)
+            .filter(|p| p.exists())
+            .or(default_path.filter(|p| p.exists()))
+            .ok_or_else(|| anyhow::anyhow!("No audio found for {}", id))?;
+
+        let (samples, sample_rate, channels) = wav::read_wav_file(&path)?;
+        Ok::<_, anyhow::Error>(waveform::waveform_peaks(
+            &samples,
+            sample_rate,
+            channels,
+            buckets,
+        ))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
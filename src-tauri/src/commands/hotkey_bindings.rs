@@ -0,0 +1,61 @@
+use crate::db::hotkey_bindings::{self, HotkeyBinding};
+use crate::system::hotkey::{self, HotkeyAction};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn list_hotkey_bindings() -> Result<Vec<HotkeyBinding>, String> {
+    tokio::task::spawn_blocking(hotkey_bindings::list)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Create (empty `id`) or update a binding and register its hotkey. Returns
+/// the saved binding.
+#[tauri::command]
+pub fn save_hotkey_binding(
+    app: AppHandle,
+    mut binding: HotkeyBinding,
+) -> Result<HotkeyBinding, String> {
+    let shortcut = hotkey::parse_hotkey(&binding.hotkey)?;
+    if binding.id.is_empty() {
+        binding.id = uuid::Uuid::new_v4().to_string();
+    }
+
+    let action = HotkeyAction::Binding(binding.id.clone());
+    let previous = hotkey_bindings::get(&binding.id).map_err(|e| e.to_string())?;
+    if let Err(e) = hotkey::set_shortcut(&app, action.clone(), Some(shortcut)) {
+        // Keep the old hotkey working
+        if let Some(previous) = previous {
+            restore(&app, action, &previous.hotkey);
+        }
+        return Err(e);
+    }
+
+    if let Err(e) = hotkey_bindings::upsert(&binding) {
+        match previous {
+            Some(previous) => restore(&app, action, &previous.hotkey),
+            None => {
+                let _ = hotkey::set_shortcut(&app, action, None);
+            }
+        }
+        return Err(e.to_string());
+    }
+
+    log::info!("Hotkey binding saved: {}", binding.hotkey);
+    Ok(binding)
+}
+
+#[tauri::command]
+pub fn delete_hotkey_binding(app: AppHandle, id: String) -> Result<(), String> {
+    hotkey::set_shortcut(&app, HotkeyAction::Binding(id.clone()), None)?;
+    hotkey_bindings::delete(&id).map_err(|e| e.to_string())
+}
+
+fn restore(app: &AppHandle, action: HotkeyAction, hotkey: &str) {
+    let restored = hotkey::parse_hotkey(hotkey)
+        .and_then(|shortcut| hotkey::set_shortcut(app, action, Some(shortcut)));
+    if let Err(e) = restored {
+        log::warn!("Failed to restore hotkey binding {}: {}", hotkey, e);
+    }
+}
@@ -0,0 +1,317 @@
+use crate::commands::error::CommandError;
+use crate::stt::TranscriptionResult;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+/// Finished jobs kept around for `get_job_status` before being pruned.
+const MAX_FINISHED_JOBS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// Snapshot of a background job, emitted as `job-updated` on every change.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfo {
+    pub id: String,
+    pub session_id: String,
+    pub status: JobStatus,
+    /// 0.0–1.0 where the engine reports it, otherwise only 0 or 1.
+    pub progress: f32,
+    pub result: Option<TranscriptionResult>,
+    pub error: Option<CommandError>,
+    pub created_at: String,
+}
+
+struct JobEntry {
+    info: JobInfo,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Tracks transcription jobs so several workflows (live dictation, import
+/// queue) can run side by side and be watched or cancelled by id.
+pub struct JobManager {
+    jobs: Mutex<HashMap<String, JobEntry>>,
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a new queued job. Returns its info and the cancellation flag
+    /// the worker should poll.
+    pub fn create(&self, session_id: &str) -> (JobInfo, Arc<AtomicBool>) {
+        let info = JobInfo {
+            id: Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            status: JobStatus::Queued,
+            progress: 0.0,
+            result: None,
+            error: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let mut jobs = self.jobs.lock().unwrap();
+        Self::prune(&mut jobs);
+        jobs.insert(
+            info.id.clone(),
+            JobEntry {
+                info: info.clone(),
+                cancelled: cancelled.clone(),
+            },
+        );
+        (info, cancelled)
+    }
+
+    pub fn get(&self, id: &str) -> Option<JobInfo> {
+        self.jobs.lock().unwrap().get(id).map(|e| e.info.clone())
+    }
+
+    /// Apply `f` to a job unless it has already finished (e.g. was cancelled
+    /// while the engine was still running). Returns the updated snapshot.
+    pub fn update(&self, id: &str, f: impl FnOnce(&mut JobInfo)) -> Option<JobInfo> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let entry = jobs.get_mut(id)?;
+        if entry.info.status.is_finished() {
+            return None;
+        }
+        f(&mut entry.info);
+        Some(entry.info.clone())
+    }
+
+    /// The most recently created job that failed.
+    pub fn last_failed(&self) -> Option<JobInfo> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|e| e.info.status == JobStatus::Failed)
+            .max_by(|a, b| a.info.created_at.cmp(&b.info.created_at))
+            .map(|e| e.info.clone())
+    }
+
+    /// The most recently created job that completed with non-empty text.
+    pub fn last_completed(&self) -> Option<JobInfo> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|e| e.info.status == JobStatus::Completed)
+            .filter(|e| e.info.result.as_ref().is_some_and(|r| !r.text.trim().is_empty()))
+            .max_by(|a, b| a.info.created_at.cmp(&b.info.created_at))
+            .map(|e| e.info.clone())
+    }
+
+    /// Drop the transcripts of a session's jobs, e.g. a dictation that went
+    /// into a password field, so the paste-last hotkey can't paste it again.
+    pub fn forget_results(&self, session_id: &str) {
+        for entry in self.jobs.lock().unwrap().values_mut() {
+            if entry.info.session_id == session_id {
+                entry.info.result = None;
+            }
+        }
+    }
+
+    /// Mark a job cancelled. Returns the snapshot if the job was still active.
+    pub fn cancel(&self, id: &str) -> Option<JobInfo> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let entry = jobs.get_mut(id)?;
+        if entry.info.status.is_finished() {
+            return None;
+        }
+        entry.cancelled.store(true, Ordering::SeqCst);
+        entry.info.status = JobStatus::Cancelled;
+        Some(entry.info.clone())
+    }
+
+    /// Drop the oldest finished jobs beyond `MAX_FINISHED_JOBS`.
+    fn prune(jobs: &mut HashMap<String, JobEntry>) {
+        let mut finished: Vec<(String, String)> = jobs
+            .values()
+            .filter(|e| e.info.status.is_finished())
+            .map(|e| (e.info.created_at.clone(), e.info.id.clone()))
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+        finished.sort();
+        let excess = finished.len() - MAX_FINISHED_JOBS;
+        for (_, id) in finished.into_iter().take(excess) {
+            jobs.remove(&id);
+        }
+    }
+}
+
+/// Emit the latest job snapshot to the frontend.
+pub fn emit_job(app: &AppHandle, info: &JobInfo) {
+    let _ = app.emit("job-updated", info);
+}
+
+#[tauri::command]
+pub fn get_job_status(
+    job_manager: State<'_, JobManager>,
+    job_id: String,
+) -> Result<Option<JobInfo>, String> {
+    Ok(job_manager.get(&job_id))
+}
+
+#[tauri::command]
+pub fn cancel_job(
+    app: AppHandle,
+    job_manager: State<'_, JobManager>,
+    job_id: String,
+) -> Result<bool, String> {
+    match job_manager.cancel(&job_id) {
+        Some(info) => {
+            log::info!("Cancelled job {}", job_id);
+            emit_job(&app, &info);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_starts_queued() {
+        let mgr = JobManager::new();
+        let (info, cancelled) = mgr.create("session-1");
+        assert_eq!(info.status, JobStatus::Queued);
+        assert_eq!(info.session_id, "session-1");
+        assert!(!cancelled.load(Ordering::SeqCst));
+        assert!(mgr.get(&info.id).is_some());
+    }
+
+    #[test]
+    fn update_changes_active_job() {
+        let mgr = JobManager::new();
+        let (info, _) = mgr.create("s");
+        let updated = mgr
+            .update(&info.id, |j| j.status = JobStatus::Running)
+            .unwrap();
+        assert_eq!(updated.status, JobStatus::Running);
+    }
+
+    #[test]
+    fn cancel_sets_flag_and_blocks_later_updates() {
+        let mgr = JobManager::new();
+        let (info, cancelled) = mgr.create("s");
+        assert!(mgr.cancel(&info.id).is_some());
+        assert!(cancelled.load(Ordering::SeqCst));
+
+        // A worker finishing after cancellation must not overwrite the status
+        assert!(mgr
+            .update(&info.id, |j| j.status = JobStatus::Completed)
+            .is_none());
+        assert_eq!(mgr.get(&info.id).unwrap().status, JobStatus::Cancelled);
+
+        // Cancelling twice is a no-op
+        assert!(mgr.cancel(&info.id).is_none());
+    }
+
+    #[test]
+    fn last_failed_returns_failed_job() {
+        let mgr = JobManager::new();
+        assert!(mgr.last_failed().is_none());
+        let (ok, _) = mgr.create("ok");
+        mgr.update(&ok.id, |j| j.status = JobStatus::Completed);
+        let (bad, _) = mgr.create("bad");
+        mgr.update(&bad.id, |j| j.status = JobStatus::Failed);
+        assert_eq!(mgr.last_failed().unwrap().session_id, "bad");
+    }
+
+    #[test]
+    fn last_completed_skips_empty_results() {
+        let mgr = JobManager::new();
+        let (spoken, _) = mgr.create("spoken");
+        mgr.update(&spoken.id, |j| {
+            j.status = JobStatus::Completed;
+            j.result = Some(TranscriptionResult {
+                text: "hello".into(),
+                language: None,
+                segments: vec![],
+                duration_ms: 0,
+                metrics: None,
+            });
+        });
+        let (silent, _) = mgr.create("silent");
+        mgr.update(&silent.id, |j| {
+            j.status = JobStatus::Completed;
+            j.result = Some(TranscriptionResult {
+                text: " ".into(),
+                language: None,
+                segments: vec![],
+                duration_ms: 0,
+                metrics: None,
+            });
+        });
+        assert_eq!(mgr.last_completed().unwrap().session_id, "spoken");
+    }
+
+    #[test]
+    fn forgotten_results_are_not_last_completed() {
+        let mgr = JobManager::new();
+        let (secret, _) = mgr.create("secret");
+        mgr.update(&secret.id, |j| {
+            j.status = JobStatus::Completed;
+            j.result = Some(TranscriptionResult {
+                text: "hunter2".into(),
+                language: None,
+                segments: vec![],
+                duration_ms: 0,
+                metrics: None,
+            });
+        });
+        mgr.forget_results("secret");
+        assert!(mgr.get(&secret.id).unwrap().result.is_none());
+        assert!(mgr.last_completed().is_none());
+    }
+
+    #[test]
+    fn unknown_job_returns_none() {
+        let mgr = JobManager::new();
+        assert!(mgr.get("missing").is_none());
+        assert!(mgr.cancel("missing").is_none());
+    }
+
+    #[test]
+    fn finished_jobs_are_pruned() {
+        let mgr = JobManager::new();
+        for _ in 0..MAX_FINISHED_JOBS + 5 {
+            let (info, _) = mgr.create("s");
+            mgr.update(&info.id, |j| j.status = JobStatus::Completed);
+        }
+        // One more create triggers pruning
+        let (active, _) = mgr.create("s");
+        let jobs = mgr.jobs.lock().unwrap();
+        assert!(jobs.len() <= MAX_FINISHED_JOBS + 1);
+        assert!(jobs.contains_key(&active.id));
+    }
+}
@@ -1,9 +1,24 @@
 pub mod ai_functions;
+pub mod app_profiles;
 pub mod audio_import;
 pub mod clipboard;
+pub mod compose;
+pub mod device_bindings;
+pub mod diagnostics;
+pub mod error;
 pub mod history;
+pub mod hotkey_bindings;
+pub mod jobs;
 pub mod models;
+pub mod notes;
+pub mod output;
+pub mod permissions;
+pub mod pipelines;
+pub mod providers;
 pub mod recording;
+pub mod rules;
 pub mod settings;
 pub mod transcription;
+pub mod updater;
 pub mod vocabulary;
+pub mod watch_folders;
@@ -1,8 +1,15 @@
+use crate::audio::{processing, wav};
+use crate::commands::error::CommandError;
 use crate::commands::transcription::SttManager;
 use crate::models;
-use crate::models::ModelInfo;
+use crate::models::whisper_models;
+use crate::models::{Engine, ModelInfo};
+use crate::stt::queue::Priority;
+use crate::stt::{benchmark, punctuation, vad, TranscriptionOptions};
 use futures_util::StreamExt;
 use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use tauri::{AppHandle, Emitter, Manager, State};
 
 #[derive(Serialize)]
@@ -13,6 +20,25 @@ pub struct ModelStatus {
     pub downloaded: bool,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    pub model_id: String,
+    pub audio_duration_ms: u64,
+    /// Time to load the model; near zero if it was already cached.
+    pub load_ms: u64,
+    pub inference_ms: u64,
+    /// Inference time / audio duration; below 1.0 is faster than real time.
+    pub real_time_factor: f64,
+    /// Process resident memory after inference, if the OS reports it.
+    pub memory_bytes: Option<u64>,
+    /// Growth in resident memory across load + inference.
+    pub memory_delta_bytes: Option<i64>,
+    pub transcript: String,
+    /// Word error rate against `reference_text`, when one was given.
+    pub word_error_rate: Option<f32>,
+}
+
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct DownloadProgressEvent {
@@ -27,13 +53,13 @@ struct DownloadProgressEvent {
 
 #[tauri::command]
 pub async fn list_models(app: AppHandle) -> Result<Vec<ModelStatus>, String> {
-    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let storage_dir = crate::system::storage::root(&app)?;
     let catalog = models::full_catalog();
 
     let statuses: Vec<ModelStatus> = catalog
         .into_iter()
         .map(|info| {
-            let downloaded = models::is_model_downloaded(&app_data_dir, &info);
+            let downloaded = models::is_model_downloaded(&storage_dir, &info);
             ModelStatus { info, downloaded }
         })
         .collect();
@@ -42,20 +68,35 @@ pub async fn list_models(app: AppHandle) -> Result<Vec<ModelStatus>, String> {
 }
 
 #[tauri::command]
-pub async fn download_model(app: AppHandle, model_id: String) -> Result<(), String> {
-    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+pub async fn download_model(app: AppHandle, model_id: String) -> Result<(), CommandError> {
+    let storage_dir = crate::system::storage::root(&app)?;
     let catalog = models::full_catalog();
     let model = catalog
         .iter()
         .find(|m| m.id == model_id)
-        .ok_or("Model not found")?
+        .ok_or_else(|| CommandError::UnknownModel(model_id.clone()))?
         .clone();
 
-    let model_dir = models::model_path(&app_data_dir, &model_id);
+    let model_dir = models::model_path(&storage_dir, &model_id);
     std::fs::create_dir_all(&model_dir).map_err(|e| e.to_string())?;
 
+    // GGML models also get their CoreML encoder (macOS only)
+    let encoder = (model.engine == Engine::Whisper)
+        .then(|| whisper_models::coreml_encoder(&model.id))
+        .flatten()
+        .filter(|encoder| !model_dir.join(&encoder.dir).exists());
+
+    // Refuse up front rather than failing midway with a write error
+    let needed = models::remaining_download_bytes(&storage_dir, &model)
+        + encoder.as_ref().map_or(0, |encoder| encoder.size_bytes);
+    match fs4::available_space(&model_dir) {
+        Ok(available) => models::check_disk_space(&model.name, needed, available)
+            .map_err(CommandError::InsufficientDiskSpace)?,
+        Err(e) => log::warn!("Could not query free disk space: {}", e),
+    }
+
     let client = reqwest::Client::new();
-    let file_count = model.download_urls.len();
+    let file_count = model.download_urls.len() + usize::from(encoder.is_some());
 
     for (i, url) in model.download_urls.iter().enumerate() {
         let filename = &model.files[i];
@@ -69,52 +110,58 @@ pub async fn download_model(app: AppHandle, model_id: String) -> Result<(), Stri
 
         log::info!("Downloading {} -> {:?}", url, file_path);
 
-        let response = client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| format!("Download request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!(
-                "Download failed with status: {}",
-                response.status()
-            ));
+        // Write to a .part file and rename when complete, so an interrupted
+        // download never leaves a truncated file that looks finished.
+        let part_path = model_dir.join(format!("{}.part", filename));
+        let progress = |bytes_downloaded: u64, total_bytes: u64| {
+            emit_download_progress(
+                &app,
+                &model_id,
+                (i, file_count),
+                filename,
+                bytes_downloaded,
+                total_bytes,
+            );
+        };
+
+        if let Err(e) = download_file(&client, url, &part_path, progress).await {
+            let _ = std::fs::remove_file(&part_path);
+            return Err(CommandError::DownloadFailed(e));
         }
+        std::fs::rename(&part_path, &file_path)
+            .map_err(|e| format!("Failed to finalize file: {}", e))?;
+    }
 
-        let total_bytes = response.content_length().unwrap_or(0);
-        let mut bytes_downloaded: u64 = 0;
-
-        let mut file = std::fs::File::create(&file_path)
-            .map_err(|e| format!("Failed to create file: {}", e))?;
-
-        let mut stream = response.bytes_stream();
-
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|e| format!("Download stream error: {}", e))?;
-            std::io::Write::write_all(&mut file, &chunk)
-                .map_err(|e| format!("Failed to write file: {}", e))?;
-
-            bytes_downloaded += chunk.len() as u64;
-
-            let percentage = if total_bytes > 0 {
-                (bytes_downloaded as f64 / total_bytes as f64) * 100.0
-            } else {
-                0.0
-            };
-
-            let _ = app.emit(
-                "model-download-progress",
-                DownloadProgressEvent {
-                    model_id: model_id.clone(),
-                    file_index: i,
-                    file_count,
-                    file_name: filename.clone(),
-                    bytes_downloaded,
-                    total_bytes,
-                    percentage,
-                },
+    // The model works without its encoder, so a failure here isn't fatal
+    if let Some(encoder) = encoder {
+        let zip_path = model_dir.join(format!("{}.zip.part", encoder.dir));
+        log::info!("Downloading CoreML encoder {} -> {:?}", encoder.url, zip_path);
+        let progress = |bytes_downloaded: u64, total_bytes: u64| {
+            emit_download_progress(
+                &app,
+                &model_id,
+                (file_count - 1, file_count),
+                &encoder.dir,
+                bytes_downloaded,
+                total_bytes,
             );
+        };
+        let installed = match download_file(&client, &encoder.url, &zip_path, progress).await {
+            Ok(()) => {
+                let (zip, dir) = (zip_path.clone(), model_dir.clone());
+                tokio::task::spawn_blocking(move || {
+                    whisper_models::install_coreml_encoder(&zip, &dir, &encoder)
+                        .map_err(|e| e.to_string())
+                })
+                .await
+                .unwrap_or_else(|e| Err(e.to_string()))
+            }
+            Err(e) => Err(e),
+        };
+        let _ = std::fs::remove_file(&zip_path);
+        match installed {
+            Ok(()) => log::info!("CoreML encoder for {} installed", model_id),
+            Err(e) => log::warn!("CoreML encoder for {} not installed: {}", model_id, e),
         }
     }
 
@@ -122,14 +169,155 @@ pub async fn download_model(app: AppHandle, model_id: String) -> Result<(), Stri
     Ok(())
 }
 
+fn emit_download_progress(
+    app: &AppHandle,
+    model_id: &str,
+    (file_index, file_count): (usize, usize),
+    file_name: &str,
+    bytes_downloaded: u64,
+    total_bytes: u64,
+) {
+    let percentage = if total_bytes > 0 {
+        (bytes_downloaded as f64 / total_bytes as f64) * 100.0
+    } else {
+        0.0
+    };
+    let _ = app.emit(
+        "model-download-progress",
+        DownloadProgressEvent {
+            model_id: model_id.to_string(),
+            file_index,
+            file_count,
+            file_name: file_name.to_string(),
+            bytes_downloaded,
+            total_bytes,
+            percentage,
+        },
+    );
+}
+
+/// Stream `url` into `dest`, reporting (downloaded, total) bytes per chunk.
+async fn download_file(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    progress: impl Fn(u64, u64),
+) -> Result<(), String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Download request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Download failed with status: {}",
+            response.status()
+        ));
+    }
+
+    let total_bytes = response.content_length().unwrap_or(0);
+    let mut bytes_downloaded: u64 = 0;
+
+    let mut file =
+        std::fs::File::create(dest).map_err(|e| format!("Failed to create file: {}", e))?;
+
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Download stream error: {}", e))?;
+        std::io::Write::write_all(&mut file, &chunk)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+
+        bytes_downloaded += chunk.len() as u64;
+        progress(bytes_downloaded, total_bytes);
+    }
+
+    Ok(())
+}
+
+/// Whether the Silero VAD model used to pre-segment Whisper input is on disk.
+#[tauri::command]
+pub fn is_vad_model_downloaded(app: AppHandle) -> Result<bool, String> {
+    let storage_dir = crate::system::storage::root(&app)?;
+    Ok(vad::model_path(&storage_dir).exists())
+}
+
+/// Download the Silero VAD model, reporting `model-download-progress` like
+/// a catalog model.
+#[tauri::command]
+pub async fn download_vad_model(app: AppHandle) -> Result<(), CommandError> {
+    let storage_dir = crate::system::storage::root(&app)?;
+    let path = vad::model_path(&storage_dir);
+    download_auxiliary_model(&app, models::VAD_MODEL_ID, vad::MODEL_URL, &path).await?;
+    log::info!("VAD model downloaded to {:?}", path);
+    Ok(())
+}
+
+/// Whether the punctuation model used after Parakeet is on disk.
+#[tauri::command]
+pub fn is_punctuation_model_downloaded(app: AppHandle) -> Result<bool, String> {
+    let storage_dir = crate::system::storage::root(&app)?;
+    Ok(punctuation::model_path(&storage_dir).exists())
+}
+
+/// Download the punctuation model, reporting `model-download-progress` like
+/// a catalog model.
+#[tauri::command]
+pub async fn download_punctuation_model(app: AppHandle) -> Result<(), CommandError> {
+    let storage_dir = crate::system::storage::root(&app)?;
+    let path = punctuation::model_path(&storage_dir);
+    let url = punctuation::MODEL_URL;
+    download_auxiliary_model(&app, models::PUNCTUATION_MODEL_ID, url, &path).await?;
+    log::info!("Punctuation model downloaded to {:?}", path);
+    Ok(())
+}
+
+/// Download the single file of a model outside the catalog to `path`,
+/// unless it's already there.
+async fn download_auxiliary_model(
+    app: &AppHandle,
+    model_id: &str,
+    url: &str,
+    path: &Path,
+) -> Result<(), CommandError> {
+    if path.exists() {
+        return Ok(());
+    }
+    let model_dir = path.parent().ok_or("Invalid model path")?;
+    std::fs::create_dir_all(model_dir).map_err(|e| e.to_string())?;
+
+    let file_name = path
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let part_path = model_dir.join(format!("{}.part", file_name));
+    let progress = |bytes_downloaded: u64, total_bytes: u64| {
+        emit_download_progress(
+            app,
+            model_id,
+            (0, 1),
+            &file_name,
+            bytes_downloaded,
+            total_bytes,
+        );
+    };
+    if let Err(e) = download_file(&reqwest::Client::new(), url, &part_path, progress).await {
+        let _ = std::fs::remove_file(&part_path);
+        return Err(CommandError::DownloadFailed(e));
+    }
+    std::fs::rename(&part_path, path).map_err(|e| format!("Failed to finalize file: {}", e))?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn delete_model(
     app: AppHandle,
     stt_manager: State<'_, SttManager>,
     model_id: String,
-) -> Result<(), String> {
-    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-    let model_dir = models::model_path(&app_data_dir, &model_id);
+) -> Result<(), CommandError> {
+    let storage_dir = crate::system::storage::root(&app)?;
+    let model_dir = models::model_path(&storage_dir, &model_id);
 
     // Evict cached engine before deleting files
     stt_manager.evict(&model_id);
@@ -141,3 +329,140 @@ pub async fn delete_model(
     log::info!("Model {} deleted", model_id);
     Ok(())
 }
+
+fn resident_memory() -> Option<u64> {
+    memory_stats::memory_stats().map(|m| m.physical_mem as u64)
+}
+
+/// Run a reference WAV clip through a local model and report speed, memory
+/// and (given the clip's reference text) a rough word error rate.
+#[tauri::command]
+pub async fn benchmark_model(
+    app: AppHandle,
+    model_id: String,
+    audio_path: String,
+    reference_text: Option<String>,
+    language: Option<String>,
+) -> Result<BenchmarkResult, CommandError> {
+    let storage_dir = crate::system::storage::root(&app)?;
+    let options = TranscriptionOptions {
+        language,
+        vocabulary: vec![],
+        initial_prompt: None,
+        decoding: crate::db::settings::load().decoding_options,
+        code_switching: false,
+        code_switch_languages: vec![],
+        code_switch_cuts: vec![],
+    };
+
+    // Reading the clip and loading the model both block, so they run off the
+    // async runtime along with the transcription
+    let load_model_id = model_id.clone();
+    let (result, audio_duration_ms, load_ms, inference_ms, memory_before) =
+        tokio::task::spawn_blocking(move || -> Result<_, CommandError> {
+            let (samples, sample_rate, channels) =
+                wav::read_wav_file(&PathBuf::from(&audio_path)).map_err(|e| e.to_string())?;
+            let audio = processing::preprocess(&samples, channels, sample_rate);
+            let audio_duration_ms = (audio.len() as u64 * 1000) / 16000;
+            if audio_duration_ms == 0 {
+                return Err("Reference clip contains no audio".into());
+            }
+
+            let memory_before = resident_memory();
+            let stt_manager = app.state::<SttManager>();
+            let load_start = Instant::now();
+            let engine = stt_manager.get_or_load(&load_model_id, &storage_dir)?;
+            let load_ms = load_start.elapsed().as_millis() as u64;
+
+            let _turn = stt_manager.queue.acquire(Priority::Background);
+            let start = Instant::now();
+            let result = engine.transcribe(&audio, &options);
+            let inference_ms = start.elapsed().as_millis() as u64;
+            Ok((result, audio_duration_ms, load_ms, inference_ms, memory_before))
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+    let result = result.map_err(|e| format!("Benchmark transcription failed: {}", e))?;
+
+    let memory_after = resident_memory();
+    let word_error_rate = reference_text
+        .as_deref()
+        .and_then(|reference| benchmark::word_error_rate(reference, &result.text));
+
+    log::info!(
+        "Benchmark {}: audio={}ms load={}ms inference={}ms",
+        model_id,
+        audio_duration_ms,
+        load_ms,
+        inference_ms
+    );
+
+    Ok(BenchmarkResult {
+        model_id,
+        audio_duration_ms,
+        load_ms,
+        inference_ms,
+        real_time_factor: inference_ms as f64 / audio_duration_ms as f64,
+        memory_bytes: memory_after,
+        memory_delta_bytes: memory_before
+            .zip(memory_after)
+            .map(|(before, after)| after as i64 - before as i64),
+        transcript: result.text,
+        word_error_rate,
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageReport {
+    pub entries: Vec<models::ModelDiskUsage>,
+    pub total_bytes: u64,
+    /// Bytes reclaimed when `delete_orphaned` was set.
+    pub freed_bytes: u64,
+}
+
+/// Report the size of each model directory, including orphaned ones left by
+/// renamed or removed catalog entries. With `delete_orphaned`, remove those
+/// first and report what remains.
+#[tauri::command]
+pub async fn model_disk_usage(
+    app: AppHandle,
+    delete_orphaned: Option<bool>,
+) -> Result<DiskUsageReport, String> {
+    let storage_dir = crate::system::storage::root(&app)?;
+    let catalog = models::full_catalog();
+
+    tokio::task::spawn_blocking(move || {
+        let mut freed_bytes = 0;
+        if delete_orphaned.unwrap_or(false) {
+            for entry in models::disk_usage(&storage_dir, &catalog) {
+                if !entry.orphaned {
+                    continue;
+                }
+                let path = models::model_path(&storage_dir, &entry.model_id);
+                let removed = if path.is_dir() {
+                    std::fs::remove_dir_all(&path)
+                } else {
+                    std::fs::remove_file(&path)
+                };
+                match removed {
+                    Ok(()) => {
+                        log::info!("Removed orphaned model entry {:?}", path);
+                        freed_bytes += entry.size_bytes;
+                    }
+                    Err(e) => log::warn!("Failed to remove {:?}: {}", path, e),
+                }
+            }
+        }
+
+        let entries = models::disk_usage(&storage_dir, &catalog);
+        let total_bytes = entries.iter().map(|e| e.size_bytes).sum();
+        DiskUsageReport {
+            entries,
+            total_bytes,
+            freed_bytes,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,117 @@
+use crate::db;
+use crate::db::notes::{self, Note};
+use std::path::Path;
+
+#[tauri::command]
+pub async fn list_notes() -> Result<Vec<Note>, String> {
+    db::run_blocking(notes::list)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create a note, optionally starting with the given recordings in order.
+#[tauri::command]
+pub async fn create_note(
+    title: String,
+    recording_ids: Option<Vec<String>>,
+) -> Result<Note, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    db::run_blocking(move || {
+        notes::create(&id, title.trim())?;
+        for recording_id in recording_ids.unwrap_or_default() {
+            notes::attach(&id, &recording_id)?;
+        }
+        notes::get(&id)?.ok_or_else(|| anyhow::anyhow!("Note not found: {}", id))
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rename_note(id: String, title: String) -> Result<(), String> {
+    db::run_blocking(move || notes::rename(&id, title.trim()))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a note; its recordings stay in history.
+#[tauri::command]
+pub async fn delete_note(id: String) -> Result<(), String> {
+    db::run_blocking(move || notes::delete(&id))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Add a history recording to the end of a note.
+#[tauri::command]
+pub async fn attach_to_note(note_id: String, recording_id: String) -> Result<(), String> {
+    db::run_blocking(move || notes::attach(&note_id, &recording_id))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn detach_from_note(note_id: String, recording_id: String) -> Result<(), String> {
+    db::run_blocking(move || notes::detach(&note_id, &recording_id))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Reorder a note's snippets; `recording_ids` lists all of them in the new order.
+#[tauri::command]
+pub async fn reorder_note(id: String, recording_ids: Vec<String>) -> Result<(), String> {
+    db::run_blocking(move || notes::reorder(&id, &recording_ids))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The note's snippets merged into one text.
+#[tauri::command]
+pub async fn get_note_text(id: String) -> Result<String, String> {
+    db::run_blocking(move || notes::merged_text(&id))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Write the note's merged text to `path`. Markdown files get the title as
+/// a heading.
+#[tauri::command]
+pub async fn export_note(id: String, path: String) -> Result<(), String> {
+    db::run_blocking(move || {
+        let note = notes::get(&id)?.ok_or_else(|| anyhow::anyhow!("Note not found: {}", id))?;
+        let text = notes::merged_text(&id)?;
+        std::fs::write(&path, export_contents(&note.title, &text, Path::new(&path)))?;
+        log::info!("Exported note {} to {}", id, path);
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+fn export_contents(title: &str, text: &str, path: &Path) -> String {
+    let markdown = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md"));
+    if markdown {
+        format!("# {}\n\n{}\n", title, text)
+    } else {
+        format!("{}\n", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_export_gets_title_heading() {
+        assert_eq!(
+            export_contents("Ideas", "One.\n\nTwo.", Path::new("/tmp/ideas.MD")),
+            "# Ideas\n\nOne.\n\nTwo.\n"
+        );
+        assert_eq!(
+            export_contents("Ideas", "One.", Path::new("/tmp/ideas.txt")),
+            "One.\n"
+        );
+    }
+}
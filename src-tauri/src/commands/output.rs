@@ -0,0 +1,179 @@
+//! Delivers AI function output the way the function is set up to: paste,
+//! copy, paste and restore the clipboard, preview window or webhook. The
+//! paste mode setting turns pasting into copying or a confirmation preview.
+
+use crate::commands::ai_functions::{list_ai_functions, OutputAction};
+use crate::commands::clipboard::paste_text;
+use crate::commands::error::CommandError;
+use crate::system::focus_target;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Time the target app gets to read the pasted text before the previous
+/// clipboard contents are put back.
+const CLIPBOARD_RESTORE_DELAY: Duration = Duration::from_millis(500);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(15);
+const PREVIEW_WINDOW: &str = "output-preview";
+/// Time the previous app gets to take focus back from the preview window.
+const FOCUS_RETURN_DELAY: Duration = Duration::from_millis(200);
+
+/// Text shown in the preview window.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputPreview {
+    pub function_id: Option<String>,
+    pub text: String,
+}
+
+pub struct PreviewState {
+    preview: Mutex<Option<OutputPreview>>,
+}
+
+impl PreviewState {
+    pub fn new() -> Self {
+        Self {
+            preview: Mutex::new(None),
+        }
+    }
+}
+
+/// Hand `text` to the output action of `function_id`; without a function it
+/// is pasted, subject to the paste mode. Resolves to whether it was pasted
+/// into a password field, like `paste_text`.
+#[tauri::command]
+pub async fn deliver_output(
+    app: AppHandle,
+    function_id: Option<String>,
+    text: String,
+) -> Result<bool, CommandError> {
+    let action = match &function_id {
+        Some(id) => list_ai_functions()?
+            .into_iter()
+            .find(|f| &f.id == id)
+            .map(|f| f.output_action)
+            .unwrap_or_default(),
+        None => OutputAction::Paste,
+    };
+    // A function's own copy, preview or webhook action stands
+    let settings = crate::db::settings::load();
+    let action = match (action, settings.paste_mode.as_str()) {
+        (OutputAction::Paste | OutputAction::PasteKeepClipboard, "copy") => OutputAction::Copy,
+        (OutputAction::Paste | OutputAction::PasteKeepClipboard, "confirm") => {
+            OutputAction::Preview
+        }
+        (action, _) => action,
+    };
+
+    if settings.lock_paste_target
+        && matches!(action, OutputAction::Paste | OutputAction::PasteKeepClipboard)
+    {
+        focus_target::restore(&app).await;
+    }
+
+    match action {
+        OutputAction::Paste => paste_text(app, text, None).await,
+        OutputAction::Copy => {
+            app.clipboard()
+                .write_text(&text)
+                .map_err(|e| e.to_string())?;
+            log::info!("deliver_output: copied {} chars", text.len());
+            Ok(false)
+        }
+        OutputAction::PasteKeepClipboard => {
+            let previous = app.clipboard().read_text().ok();
+            // Restored even when the paste fails, which can leave the text
+            // on the clipboard
+            let result = paste_text(app.clone(), text, None).await;
+            if let Some(previous) = previous {
+                tokio::time::sleep(CLIPBOARD_RESTORE_DELAY).await;
+                let _ = app.clipboard().write_text(previous);
+            }
+            result
+        }
+        OutputAction::Preview => {
+            show_preview(&app, OutputPreview { function_id, text })?;
+            Ok(false)
+        }
+        OutputAction::Webhook { url } => {
+            send_webhook(&url, function_id.as_deref(), &text).await?;
+            Ok(false)
+        }
+    }
+}
+
+/// The text last sent to the preview window.
+#[tauri::command]
+pub fn get_output_preview(app: AppHandle) -> Option<OutputPreview> {
+    app.state::<PreviewState>().preview.lock().unwrap().clone()
+}
+
+/// Paste the text confirmed in the preview window into the app that was in
+/// front before it, closing the window.
+#[tauri::command]
+pub async fn paste_output_preview(app: AppHandle, text: String) -> Result<bool, CommandError> {
+    if let Some(window) = app.get_webview_window(PREVIEW_WINDOW) {
+        let _ = window.close();
+    }
+    // Hiding the app hands focus back to the previous one
+    #[cfg(target_os = "macos")]
+    let _ = app.hide();
+    tokio::time::sleep(FOCUS_RETURN_DELAY).await;
+    focus_target::restore(&app).await;
+    paste_text(app, text, None).await
+}
+
+/// Open (or update) the preview window. A window that's already open gets
+/// the new text as an `output-preview` event.
+fn show_preview(app: &AppHandle, preview: OutputPreview) -> Result<(), String> {
+    *app.state::<PreviewState>().preview.lock().unwrap() = Some(preview.clone());
+
+    if let Some(window) = app.get_webview_window(PREVIEW_WINDOW) {
+        let _ = window.emit("output-preview", &preview);
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+    WebviewWindowBuilder::new(
+        app,
+        PREVIEW_WINDOW,
+        WebviewUrl::App("/output-preview".into()),
+    )
+    .title("AI Output")
+    .inner_size(520.0, 420.0)
+    .always_on_top(true)
+    .build()
+    .map_err(|e| e.to_string())?;
+    log::info!("deliver_output: opened preview window");
+    Ok(())
+}
+
+async fn send_webhook(
+    url: &str,
+    function_id: Option<&str>,
+    text: &str,
+) -> Result<(), CommandError> {
+    let response = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?
+        .post(url)
+        .json(&serde_json::json!({ "functionId": function_id, "text": text }))
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                CommandError::Timeout(format!("Webhook timed out: {}", e))
+            } else {
+                CommandError::Network(format!("Webhook failed: {}", e))
+            }
+        })?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(CommandError::Other(format!("Webhook returned {}", status)));
+    }
+    log::info!("deliver_output: webhook accepted ({})", status);
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use crate::system::permissions::{self, Permissions};
+
+/// Current microphone and accessibility permission state.
+#[tauri::command]
+pub fn check_permissions() -> Result<Permissions, String> {
+    Ok(permissions::check())
+}
+
+/// Ask the OS for missing permissions (or open System Settings for ones the
+/// user already denied). Poll `check_permissions` to see the outcome.
+#[tauri::command]
+pub async fn request_permissions() -> Result<Permissions, String> {
+    tokio::task::spawn_blocking(permissions::request)
+        .await
+        .map_err(|e| e.to_string())
+}
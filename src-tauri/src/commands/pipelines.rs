@@ -0,0 +1,284 @@
+//! Post-processing pipelines. A pipeline runs its steps (rules, vocabulary
+//! replacement, AI functions, redaction) in the order the user set up;
+//! without one, enabled rules run first and then the selected AI function.
+
+use crate::commands::ai_functions::{execute_ai_function, list_ai_functions};
+use crate::commands::error::CommandError;
+use crate::db;
+use crate::db::pipelines::{self, Pipeline, PipelineStep};
+use crate::rules;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Text after each stage of a pipeline run.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineOutput {
+    pub text: String,
+    /// Text before the first AI function step.
+    pub rules_text: String,
+    /// Final text when an AI function step ran.
+    pub processed_text: Option<String>,
+    /// The last AI function that ran.
+    pub ai_function: Option<String>,
+    /// A failed AI function step; the step is skipped and the rest still run.
+    pub ai_error: Option<CommandError>,
+}
+
+#[tauri::command]
+pub async fn list_pipelines() -> Result<Vec<Pipeline>, String> {
+    db::run_blocking(pipelines::list)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create (empty `id`) or update a pipeline. Returns the saved pipeline.
+#[tauri::command]
+pub async fn save_pipeline(mut pipeline: Pipeline) -> Result<Pipeline, String> {
+    pipeline.name = pipeline.name.trim().to_string();
+    let function_ids: Vec<String> = list_ai_functions()?.into_iter().map(|f| f.id).collect();
+    let rule_ids: Vec<String> = db::run_blocking(db::rules::list)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    validate(&pipeline, &rule_ids, &function_ids)?;
+    if pipeline.id.is_empty() {
+        pipeline.id = uuid::Uuid::new_v4().to_string();
+    }
+    db::run_blocking(move || {
+        pipelines::upsert(&pipeline)?;
+        pipelines::get(&pipeline.id)?
+            .ok_or_else(|| anyhow::anyhow!("Pipeline not found: {}", pipeline.id))
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Delete a pipeline; hotkey presets using it go back to the default.
+#[tauri::command]
+pub async fn delete_pipeline(id: String) -> Result<(), String> {
+    db::run_blocking(move || pipelines::delete(&id))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run `text` through the pipeline `pipeline_id`, or through the saved
+/// enabled rules and `ai_function` when there is none. `skip_ai` leaves out
+/// AI function steps, e.g. while composing a document.
+#[tauri::command]
+pub async fn run_pipeline(
+    app: AppHandle,
+    text: String,
+    pipeline_id: Option<String>,
+    ai_function: Option<String>,
+    skip_ai: Option<bool>,
+) -> Result<PipelineOutput, CommandError> {
+    let steps = match pipeline_id {
+        Some(id) => {
+            db::run_blocking(move || {
+                pipelines::get(&id)?.ok_or_else(|| anyhow::anyhow!("Pipeline not found: {}", id))
+            })
+            .await
+            .map_err(|e| e.to_string())?
+            .steps
+        }
+        None => {
+            let enabled_rule_ids = db::run_blocking(db::rules::enabled_ids)
+                .await
+                .map_err(|e| e.to_string())?;
+            default_steps(enabled_rule_ids, ai_function)
+        }
+    };
+    let skip_ai = skip_ai.unwrap_or(false);
+    log::info!("run_pipeline: {} steps (skip AI: {})", steps.len(), skip_ai);
+
+    let all_rules = if steps
+        .iter()
+        .any(|s| matches!(s, PipelineStep::Rules { .. }))
+    {
+        db::run_blocking(db::rules::load)
+            .await
+            .map_err(|e| e.to_string())?
+    } else {
+        vec![]
+    };
+
+    let mut text = text;
+    let mut rules_text = None;
+    let mut ran_ai = None;
+    let mut ai_error = None;
+    for step in &steps {
+        match step {
+            PipelineStep::Rules { rule_ids } => {
+                text = apply_rules_in_order(&text, rule_ids, &all_rules)
+            }
+            PipelineStep::Vocabulary => {
+                let replacements = db::run_blocking(db::vocabulary::replacements)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                text = rules::vocabulary::apply_replacements(&text, &replacements);
+            }
+            PipelineStep::Redact => text = rules::redact::redact(&text),
+            PipelineStep::AiFunction { .. } if skip_ai => {}
+            PipelineStep::AiFunction { function_id } => {
+                rules_text.get_or_insert_with(|| text.clone());
+                let _ = app.emit("pipeline-state", "ai-processing");
+                match execute_ai_function(app.clone(), text.clone(), function_id.clone()).await {
+                    Ok(processed) => {
+                        text = processed;
+                        ran_ai = Some(function_id.clone());
+                    }
+                    Err(CommandError::ApiKeyMissing(what)) => {
+                        log::warn!(
+                            "run_pipeline: skipped {}: API key required for {}",
+                            function_id,
+                            what
+                        );
+                    }
+                    Err(CommandError::AiCancelled) => {
+                        log::info!("run_pipeline: {} cancelled", function_id);
+                    }
+                    Err(e) => {
+                        log::error!("run_pipeline: {} failed: {}", function_id, e);
+                        ai_error = Some(e);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(PipelineOutput {
+        rules_text: rules_text.unwrap_or_else(|| text.clone()),
+        processed_text: ran_ai.as_ref().map(|_| text.clone()),
+        ai_function: ran_ai,
+        ai_error,
+        text,
+    })
+}
+
+/// The order used without a pipeline: enabled rules, then the AI function.
+fn default_steps(enabled_rule_ids: Vec<String>, ai_function: Option<String>) -> Vec<PipelineStep> {
+    let mut steps = vec![];
+    if !enabled_rule_ids.is_empty() {
+        steps.push(PipelineStep::Rules {
+            rule_ids: enabled_rule_ids,
+        });
+    }
+    if let Some(function_id) = ai_function {
+        steps.push(PipelineStep::AiFunction { function_id });
+    }
+    steps
+}
+
+/// Apply the rules in `rule_ids`, in that order, looked up in `all_rules`.
+fn apply_rules_in_order(text: &str, rule_ids: &[String], all_rules: &[rules::Rule]) -> String {
+    let ordered: Vec<rules::Rule> = rule_ids
+        .iter()
+        .filter_map(|id| all_rules.iter().find(|r| &r.id == id))
+        .map(|r| rules::Rule {
+            enabled: true,
+            ..r.clone()
+        })
+        .collect();
+    rules::apply_regex_rules(text, &ordered)
+}
+
+fn validate(
+    pipeline: &Pipeline,
+    rule_ids: &[String],
+    function_ids: &[String],
+) -> Result<(), String> {
+    if pipeline.name.is_empty() {
+        return Err("Pipeline name is required".into());
+    }
+    for step in &pipeline.steps {
+        match step {
+            PipelineStep::Rules {
+                rule_ids: step_rule_ids,
+            } => {
+                if let Some(unknown) = step_rule_ids.iter().find(|id| !rule_ids.contains(id)) {
+                    return Err(format!("Unknown rule: {}", unknown));
+                }
+            }
+            PipelineStep::AiFunction { function_id } if !function_ids.contains(function_id) => {
+                return Err(format!("Unknown AI function: {}", function_id));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_steps_run_rules_before_ai_function() {
+        assert_eq!(
+            default_steps(vec!["remove-fillers".into()], Some("email".into())),
+            vec![
+                PipelineStep::Rules {
+                    rule_ids: vec!["remove-fillers".into()]
+                },
+                PipelineStep::AiFunction {
+                    function_id: "email".into()
+                },
+            ]
+        );
+        assert!(default_steps(vec![], None).is_empty());
+    }
+
+    #[test]
+    fn rules_step_keeps_its_order() {
+        let fillers_first = [
+            "remove-fillers".to_string(),
+            "smart-punctuation".to_string(),
+        ];
+        let builtin = rules::builtin_rules();
+        assert_eq!(
+            apply_rules_in_order("um hello", &fillers_first, &builtin),
+            "Hello."
+        );
+        let punctuation_first = [
+            "smart-punctuation".to_string(),
+            "remove-fillers".to_string(),
+        ];
+        assert_eq!(
+            apply_rules_in_order("um hello", &punctuation_first, &builtin),
+            "hello."
+        );
+    }
+
+    #[test]
+    fn validate_rejects_unknown_steps() {
+        let mut pipeline = Pipeline {
+            id: String::new(),
+            name: "Notes".into(),
+            steps: vec![PipelineStep::AiFunction {
+                function_id: "missing".into(),
+            }],
+            created_at: String::new(),
+        };
+        let rule_ids = vec!["smart-punctuation".to_string()];
+        let functions = vec!["email".to_string()];
+        assert!(validate(&pipeline, &rule_ids, &functions).is_err());
+
+        pipeline.steps = vec![
+            PipelineStep::Rules {
+                rule_ids: vec!["smart-punctuation".into()],
+            },
+            PipelineStep::AiFunction {
+                function_id: "email".into(),
+            },
+            PipelineStep::Vocabulary,
+            PipelineStep::Redact,
+        ];
+        assert!(validate(&pipeline, &rule_ids, &functions).is_ok());
+
+        pipeline.name.clear();
+        assert!(validate(&pipeline, &rule_ids, &functions).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::commands::ai_functions::llm_config;
+use crate::llm::{self, ollama, ollama::OllamaOptions};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Outcome of a provider connectivity check, shown next to the key input.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderTestResult {
+    pub ok: bool,
+    pub latency_ms: u64,
+    pub message: String,
+}
+
+impl ProviderTestResult {
+    fn from_result(start: Instant, result: anyhow::Result<String>) -> Self {
+        let latency_ms = start.elapsed().as_millis() as u64;
+        match result {
+            Ok(message) => Self {
+                ok: true,
+                latency_ms,
+                message,
+            },
+            Err(e) => Self {
+                ok: false,
+                latency_ms,
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+/// Models installed on the Ollama server at `base_url` (default
+/// localhost:11434), for the provider settings dropdown.
+#[tauri::command]
+pub async fn list_ollama_models(base_url: Option<String>) -> Result<Vec<String>, String> {
+    ollama::list_models(base_url.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Send a one-word completion to check the key, model and endpoint.
+#[tauri::command]
+pub async fn test_llm_provider(
+    provider: String,
+    api_key: String,
+    model: String,
+    base_url: Option<String>,
+    timeout_secs: Option<u64>,
+    ollama_options: Option<OllamaOptions>,
+) -> Result<ProviderTestResult, String> {
+    log::info!("test_llm_provider: provider={}, model={}", provider, model);
+
+    let config = llm_config(
+        &provider,
+        api_key,
+        model,
+        base_url,
+        timeout_secs,
+        ollama_options,
+    );
+
+    let start = Instant::now();
+    let result = llm::create_provider(&config)
+        .complete("Reply with the single word OK.", "ping")
+        .await
+        .map(|reply| format!("Connected — model replied \"{}\"", reply.trim()));
+    Ok(ProviderTestResult::from_result(start, result))
+}
+
+/// Check a cloud STT key by listing the provider's models, which is free,
+/// and confirm `model` is available to that key. For OpenAI, `base_url`
+/// checks a self-hosted OpenAI-compatible server instead, with that server's
+/// own optional key; no Authorization header is sent without one.
+#[tauri::command]
+pub async fn test_stt_provider(
+    provider: String,
+    api_key: String,
+    model: Option<String>,
+    base_url: Option<String>,
+) -> Result<ProviderTestResult, String> {
+    log::info!("test_stt_provider: provider={}", provider);
+
+    let base_url = base_url
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty());
+    let (url, default_model) = match (provider.as_str(), &base_url) {
+        ("openai", Some(base)) => (format!("{}/models", base), "whisper-1"),
+        ("openai", None) => (
+            format!("{}/models", crate::stt::cloud_openai::DEFAULT_BASE_URL),
+            "whisper-1",
+        ),
+        ("groq", _) => (
+            "https://api.groq.com/openai/v1/models".to_string(),
+            "whisper-large-v3-turbo",
+        ),
+        (other, _) => return Err(format!("Unknown STT provider: {}", other)),
+    };
+    let model = model
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| default_model.to_string());
+
+    let start = Instant::now();
+    let key_required = base_url.is_none() || provider != "openai";
+    let result = check_models_endpoint(&url, &api_key, &model, key_required).await;
+    Ok(ProviderTestResult::from_result(start, result))
+}
+
+async fn check_models_endpoint(
+    url: &str,
+    api_key: &str,
+    model: &str,
+    key_required: bool,
+) -> anyhow::Result<String> {
+    if api_key.is_empty() && key_required {
+        anyhow::bail!("API key is empty");
+    }
+
+    let mut request = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()?
+        .get(url);
+    if !api_key.is_empty() {
+        request = request.header("Authorization", format!("Bearer {}", api_key));
+    }
+    let resp = request.send().await?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        anyhow::bail!("API error {}: {}", status, body);
+    }
+
+    let body: serde_json::Value = resp.json().await?;
+    if has_model(&body, model) {
+        Ok(format!("Connected — {} available", model))
+    } else {
+        anyhow::bail!("Key is valid but model {} is not available", model)
+    }
+}
+
+/// True if an OpenAI-style `/models` response lists `model`.
+fn has_model(body: &serde_json::Value, model: &str) -> bool {
+    body["data"]
+        .as_array()
+        .is_some_and(|models| models.iter().any(|m| m["id"].as_str() == Some(model)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn has_model_finds_listed_model() {
+        let body = json!({ "data": [{ "id": "gpt-4o" }, { "id": "whisper-1" }] });
+        assert!(has_model(&body, "whisper-1"));
+        assert!(!has_model(&body, "whisper-large-v3"));
+    }
+
+    #[test]
+    fn has_model_handles_malformed_body() {
+        assert!(!has_model(&json!({}), "whisper-1"));
+        assert!(!has_model(&json!({ "data": "nope" }), "whisper-1"));
+    }
+
+    #[test]
+    fn test_result_reports_error_message() {
+        let result =
+            ProviderTestResult::from_result(Instant::now(), Err(anyhow::anyhow!("401 Unauthorized")));
+        assert!(!result.ok);
+        assert_eq!(result.message, "401 Unauthorized");
+    }
+}
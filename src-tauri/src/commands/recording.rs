@@ -1,20 +1,41 @@
-use crate::audio::capture::AudioBuffer;
-use crate::audio::{processing, wav};
+use crate::audio::capture::{self, AudioBuffer};
+use crate::audio::silence::SilenceDetector;
+use crate::audio::{processing, spectrum, wav};
+use crate::commands::error::CommandError;
+use crate::system::focus_target;
+use crate::system::permissions::{self, PermissionState};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use std::time::{Duration, Instant};
+use tauri::{
+    AppHandle, Emitter, Manager, Monitor, PhysicalPosition, State, WebviewUrl, WebviewWindowBuilder,
+};
 use uuid::Uuid;
 
+/// Session audio not released by the frontend is dropped after this long.
+const SESSION_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Audio of a completed recording or import, kept until transcribed.
+struct SessionAudio {
+    samples: Vec<f32>,
+    created_at: Instant,
+}
+
 /// Active recording session data (Send+Sync safe).
 pub struct RecordingState {
     /// The shared audio buffer being written to by the capture thread.
     buffer: Mutex<Option<AudioBuffer>>,
     /// Completed audio sessions keyed by session ID (16kHz mono f32).
-    sessions: Mutex<HashMap<String, Vec<f32>>>,
+    sessions: Mutex<HashMap<String, SessionAudio>>,
     /// Signal to stop the capture thread.
     stop_signal: Mutex<Option<std::sync::mpsc::Sender<()>>>,
     /// Signal to stop the audio level meter thread.
     level_stop: Mutex<Option<std::sync::mpsc::Sender<()>>>,
+    /// Signal to stop the live partial transcript thread.
+    partial_stop: Mutex<Option<std::sync::mpsc::Sender<()>>>,
+    /// Incognito mode: no WAV files are written and history saves are skipped.
+    incognito: Mutex<bool>,
 }
 
 impl RecordingState {
@@ -24,6 +45,8 @@ impl RecordingState {
             sessions: Mutex::new(HashMap::new()),
             stop_signal: Mutex::new(None),
             level_stop: Mutex::new(None),
+            partial_stop: Mutex::new(None),
+            incognito: Mutex::new(false),
         }
     }
 
@@ -31,6 +54,14 @@ impl RecordingState {
     pub fn is_recording(&self) -> bool {
         self.stop_signal.lock().unwrap().is_some()
     }
+
+    pub fn is_incognito(&self) -> bool {
+        *self.incognito.lock().unwrap()
+    }
+
+    pub fn set_incognito(&self, enabled: bool) {
+        *self.incognito.lock().unwrap() = enabled;
+    }
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -41,127 +72,83 @@ pub struct StopResult {
     pub sample_count: usize,
 }
 
+/// Payload of `recording-too-short`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TooShort {
+    pub duration_ms: u64,
+    pub min_duration_ms: u64,
+}
+
+/// Trailing silence that ends a recording, when the hands-free mode is on.
+fn hands_free_timeout(app: &AppHandle) -> Option<Duration> {
+    let mode = app
+        .state::<crate::system::hotkey::HotkeyModeState>()
+        .mode
+        .lock()
+        .unwrap()
+        .clone();
+    if mode != "hands-free" {
+        return None;
+    }
+    let secs = crate::db::settings::load().hands_free_silence_secs;
+    // Clamped, since a huge saved value would overflow a Duration
+    Some(Duration::from_secs_f64(secs.clamp(0.5, 60.0)))
+}
+
 #[tauri::command]
-pub fn start_recording(app: AppHandle, state: State<'_, RecordingState>) -> Result<(), String> {
+pub fn start_recording(
+    app: AppHandle,
+    state: State<'_, RecordingState>,
+) -> Result<(), CommandError> {
     // Prevent double-start
     if state.stop_signal.lock().unwrap().is_some() {
-        return Err("Recording already in progress".into());
+        return Err(CommandError::AlreadyRecording);
+    }
+
+    // A denied microphone records silence rather than failing, so check first
+    if matches!(
+        permissions::check().microphone,
+        PermissionState::Denied | PermissionState::Restricted
+    ) {
+        return Err(CommandError::MicPermissionDenied);
     }
 
     // Create shared buffer — the capture thread will write samples to this Arc
     let shared_buffer = AudioBuffer::new(0, 0);
-    let thread_buffer = shared_buffer.clone();
 
     let (stop_tx, stop_rx) = std::sync::mpsc::channel::<()>();
     *state.stop_signal.lock().unwrap() = Some(stop_tx);
 
-    // Channel for the capture thread to report init success/failure
-    let (init_tx, init_rx) = std::sync::mpsc::channel::<Result<(u32, u16), String>>();
-
-    std::thread::spawn(move || {
-        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-
-        let host = cpal::default_host();
-        let device = match host.default_input_device() {
-            Some(d) => d,
-            None => {
-                let _ = init_tx.send(Err("No input device available".into()));
-                return;
-            }
-        };
-
-        let supported_config = match device.default_input_config() {
-            Ok(c) => c,
-            Err(e) => {
-                let _ = init_tx.send(Err(format!("Failed to get input config: {}", e)));
-                return;
-            }
-        };
-
-        let sample_format = supported_config.sample_format();
-        let config: cpal::StreamConfig = supported_config.into();
-        let sample_rate = config.sample_rate.0;
-        let channels = config.channels;
-
-        let samples_arc = thread_buffer.samples.clone();
-
-        let err_fn = |err: cpal::StreamError| {
-            log::error!("Audio capture error: {}", err);
-        };
-
-        let stream = match sample_format {
-            cpal::SampleFormat::F32 => device.build_input_stream(
-                &config,
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    if let Ok(mut buf) = samples_arc.lock() {
-                        buf.extend_from_slice(data);
-                    }
-                },
-                err_fn,
-                None,
-            ),
-            cpal::SampleFormat::I16 => {
-                let samples_arc = thread_buffer.samples.clone();
-                device.build_input_stream(
-                    &config,
-                    move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                        if let Ok(mut buf) = samples_arc.lock() {
-                            buf.extend(data.iter().map(|&s| s as f32 / i16::MAX as f32));
-                        }
-                    },
-                    err_fn,
-                    None,
-                )
-            }
-            _ => {
-                let _ = init_tx.send(Err(format!(
-                    "Unsupported sample format: {:?}",
-                    sample_format
-                )));
-                return;
-            }
-        };
-
-        let stream = match stream {
-            Ok(s) => s,
-            Err(e) => {
-                let _ = init_tx.send(Err(format!("Failed to build stream: {}", e)));
-                return;
-            }
-        };
-
-        if let Err(e) = stream.play() {
-            let _ = init_tx.send(Err(format!("Failed to start stream: {}", e)));
-            return;
-        }
-
-        // Signal success
-        let _ = init_tx.send(Ok((sample_rate, channels)));
-
-        // Block until stop signal — stream stays alive on this thread
-        let _ = stop_rx.recv();
-        // Stream drops here, stopping capture
-    });
-
-    // Wait for capture thread initialization
-    let init_result = init_rx
-        .recv()
-        .map_err(|_| "Capture thread died before initialization".to_string())
-        .and_then(|r| r.map_err(|e| format!("Audio init failed: {}", e)));
+    let settings = crate::db::settings::load();
+    if settings.lock_paste_target {
+        focus_target::lock(&app);
+    }
+    let prefer_built_in = settings.prefer_built_in_mic;
+    let change_app = app.clone();
+    let init_result = capture::start(
+        shared_buffer.samples.clone(),
+        stop_rx,
+        prefer_built_in,
+        move |change| {
+            let _ = change_app.emit("recording-device-changed", change);
+        },
+    );
 
-    let (sample_rate, channels) = match init_result {
-        Ok(v) => v,
+    let info = match init_result {
+        Ok(info) => info,
         Err(e) => {
             // Clean up stop_signal so is_recording() returns false
             // and the app doesn't get stuck in a bad state
             *state.stop_signal.lock().unwrap() = None;
             *state.level_stop.lock().unwrap() = None;
-            return Err(e);
+            return Err(CommandError::AudioDevice(e));
         }
     };
 
     // Clone the samples Arc for the level meter before moving into state
     let level_samples = shared_buffer.samples.clone();
+    let partial_samples = shared_buffer.samples.clone();
 
     // Store the buffer (with correct metadata) in state.
     // shared_buffer.samples is the same Arc the capture thread writes to.
@@ -169,21 +156,36 @@ pub fn start_recording(app: AppHandle, state: State<'_, RecordingState>) -> Resu
         let mut buf_lock = state.buffer.lock().unwrap();
         *buf_lock = Some(AudioBuffer {
             samples: shared_buffer.samples,
-            sample_rate,
-            channels,
+            sample_rate: info.sample_rate,
+            channels: info.channels,
         });
     }
 
     // Emit event so frontend knows recording started
     let _ = app.emit("recording-started", ());
+    if info.degraded {
+        log::warn!(
+            "Bluetooth input '{}' is in hands-free mode ({}Hz)",
+            info.device,
+            info.sample_rate
+        );
+        let _ = app.emit("recording-input-degraded", &info);
+    }
 
-    // Spawn audio level meter thread — emits RMS level ~16 times per second
+    // Spawn audio level meter thread — emits RMS level ~16 times per second,
+    // or ~30 with the spectrum visualizer on, which also gets band levels.
+    // In hands-free mode it also ends the recording after trailing silence.
     {
         let (level_tx, level_rx) = std::sync::mpsc::channel::<()>();
         *state.level_stop.lock().unwrap() = Some(level_tx);
 
+        let mut silence = hands_free_timeout(&app).map(SilenceDetector::new);
+        let with_spectrum = settings.spectrum_visualizer;
+        let tick = Duration::from_millis(if with_spectrum { 33 } else { 60 });
+        let (sample_rate, channels) = (info.sample_rate, info.channels);
         let level_app = app.clone();
         std::thread::spawn(move || {
+            let mut last_tick = Instant::now();
             loop {
                 if level_rx.try_recv().is_ok() {
                     break;
@@ -204,15 +206,54 @@ pub fn start_recording(app: AppHandle, state: State<'_, RecordingState>) -> Resu
                 };
 
                 let _ = level_app.emit("audio-level", level);
-                std::thread::sleep(std::time::Duration::from_millis(60));
+
+                if with_spectrum {
+                    let recent = {
+                        let buf = level_samples.lock().unwrap();
+                        let window = spectrum::FFT_SIZE * channels.max(1) as usize;
+                        buf[buf.len().saturating_sub(window)..].to_vec()
+                    };
+                    let mono = processing::to_mono(&recent, channels);
+                    let bands = spectrum::band_levels(&mono, sample_rate);
+                    let _ = level_app.emit("audio-spectrum", bands);
+                }
+
+                if let Some(detector) = silence.as_mut() {
+                    let now = Instant::now();
+                    if detector.update(level, now - last_tick) {
+                        log::info!("Hands-free: trailing silence, stopping recording");
+                        let rec_state = level_app.state::<RecordingState>();
+                        // Fails harmlessly if the hotkey stopped it first
+                        if let Err(e) = stop_recording(level_app.clone(), rec_state) {
+                            log::warn!("Hands-free auto-stop failed: {}", e);
+                        }
+                        break;
+                    }
+                    last_tick = now;
+                }
+                std::thread::sleep(tick);
             }
         });
     }
 
+    // Rolling transcript of the recording so far, shown in the recording bar
+    if settings.live_transcript {
+        let (partial_tx, partial_rx) = std::sync::mpsc::channel::<()>();
+        *state.partial_stop.lock().unwrap() = Some(partial_tx);
+        crate::commands::transcription::spawn_partials(
+            app.clone(),
+            partial_samples,
+            info.sample_rate,
+            info.channels,
+            partial_rx,
+        );
+    }
+
     log::info!(
-        "Recording started: {}Hz, {} channels",
-        sample_rate,
-        channels
+        "Recording started on '{}': {}Hz, {} channels",
+        info.device,
+        info.sample_rate,
+        info.channels
     );
     Ok(())
 }
@@ -221,11 +262,14 @@ pub fn start_recording(app: AppHandle, state: State<'_, RecordingState>) -> Resu
 pub fn stop_recording(
     app: AppHandle,
     state: State<'_, RecordingState>,
-) -> Result<StopResult, String> {
-    // Stop the audio level meter thread
+) -> Result<StopResult, CommandError> {
+    // Stop the audio level meter and partial transcript threads
     if let Some(tx) = state.level_stop.lock().unwrap().take() {
         let _ = tx.send(());
     }
+    if let Some(tx) = state.partial_stop.lock().unwrap().take() {
+        let _ = tx.send(());
+    }
 
     // Signal the capture thread to stop
     let had_signal = state.stop_signal.lock().unwrap().take().map(|tx| {
@@ -233,7 +277,7 @@ pub fn stop_recording(
     });
 
     if had_signal.is_none() {
-        return Err("No active recording".into());
+        return Err(CommandError::NotRecording);
     }
 
     // Small delay for the stream callback to flush
@@ -251,24 +295,60 @@ pub fn stop_recording(
     let channels = buffer.channels;
 
     if raw_samples.is_empty() {
-        return Err("No audio data captured".into());
+        return Err(CommandError::NoAudio);
     }
 
     // Preprocess: multi-channel → mono → 16kHz
     let processed = processing::preprocess(&raw_samples, channels, sample_rate);
     let sample_count = processed.len();
     let duration_ms = (sample_count as f64 / 16000.0 * 1000.0) as u64;
+    let settings = crate::db::settings::load();
+
+    // Accidental hotkey taps: drop the audio instead of transcribing it. The
+    // empty session id resets the frontend like a failed start does.
+    if duration_ms < settings.min_recording_ms {
+        log::info!(
+            "Recording too short ({}ms < {}ms), discarding",
+            duration_ms,
+            settings.min_recording_ms
+        );
+        let _ = app.emit(
+            "recording-too-short",
+            TooShort {
+                duration_ms,
+                min_duration_ms: settings.min_recording_ms,
+            },
+        );
+        let result = StopResult {
+            session_id: String::new(),
+            duration_ms,
+            sample_count,
+        };
+        let _ = app.emit("recording-stopped", result.clone());
+        let _ = hide_recording_bar(app);
+        return Ok(result);
+    }
 
     let session_id = Uuid::new_v4().to_string();
 
-    // Save WAV file for history playback
-    if let Ok(app_data_dir) = app.path().app_data_dir() {
-        let audio_dir = app_data_dir.join("audio");
+    // Save WAV file for history playback (never in incognito mode)
+    if state.is_incognito() {
+        log::info!("Incognito mode: not saving WAV for session {}", session_id);
+    } else if let Ok(audio_dir) = crate::system::storage::audio_dir(&app) {
         if std::fs::create_dir_all(&audio_dir).is_ok() {
             let wav_path = audio_dir.join(format!("{}.wav", session_id));
             if let Err(e) = wav::save_wav(&processed, 16000, &wav_path) {
                 log::warn!("Failed to save WAV: {}", e);
             }
+            // Raw capture for archiving / re-transcribing with better models later
+            if settings.keep_original_audio {
+                let original_path = original_wav_path(&audio_dir, &session_id);
+                if let Err(e) =
+                    wav::save_wav_channels(&raw_samples, sample_rate, channels, &original_path)
+                {
+                    log::warn!("Failed to save original WAV: {}", e);
+                }
+            }
         }
     }
 
@@ -285,11 +365,7 @@ pub fn stop_recording(
     );
 
     // Store processed audio in session map for transcription
-    state
-        .sessions
-        .lock()
-        .unwrap()
-        .insert(session_id.clone(), processed);
+    insert_session_audio(&state, &session_id, processed);
 
     let result = StopResult {
         session_id: session_id.clone(),
@@ -303,6 +379,210 @@ pub fn stop_recording(
     Ok(result)
 }
 
+/// Default length of a microphone test.
+const MIC_TEST_MS: u64 = 3000;
+/// RMS below this is too quiet for reliable transcription.
+const MIC_QUIET_RMS: f32 = 0.01;
+/// Samples at or above this magnitude count as clipped.
+const MIC_CLIP_LEVEL: f32 = 0.99;
+/// Fraction of clipped samples above which the input is distorted.
+const MIC_CLIP_RATIO: f32 = 0.001;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MicVerdict {
+    TooQuiet,
+    Clipping,
+    Ok,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MicTestResult {
+    pub rms: f32,
+    pub peak: f32,
+    /// Fraction of samples at full scale.
+    pub clipped_ratio: f32,
+    pub verdict: MicVerdict,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub duration_ms: u64,
+}
+
+impl MicTestResult {
+    /// Level statistics and verdict for a raw capture.
+    fn analyze(samples: &[f32], sample_rate: u32, channels: u16, duration_ms: u64) -> Self {
+        let rms = processing::rms_energy(samples);
+        let peak = samples.iter().map(|s| s.abs()).fold(0.0f32, f32::max);
+        let clipped = samples.iter().filter(|s| s.abs() >= MIC_CLIP_LEVEL).count();
+        let clipped_ratio = if samples.is_empty() {
+            0.0
+        } else {
+            clipped as f32 / samples.len() as f32
+        };
+
+        let verdict = if clipped_ratio > MIC_CLIP_RATIO {
+            MicVerdict::Clipping
+        } else if rms < MIC_QUIET_RMS {
+            MicVerdict::TooQuiet
+        } else {
+            MicVerdict::Ok
+        };
+        Self {
+            rms,
+            peak,
+            clipped_ratio,
+            verdict,
+            sample_rate,
+            channels,
+            duration_ms,
+        }
+    }
+}
+
+/// Record a short clip from the default input and report its levels, so
+/// setup problems show up before real dictation. With `emit_levels`, live
+/// RMS readings are sent as `mic-test-level` events. Nothing is saved.
+#[tauri::command]
+pub async fn test_microphone(
+    app: AppHandle,
+    duration_ms: Option<u64>,
+    emit_levels: Option<bool>,
+) -> Result<MicTestResult, CommandError> {
+    if app.state::<RecordingState>().is_recording() {
+        return Err(CommandError::AlreadyRecording);
+    }
+    let duration = Duration::from_millis(duration_ms.unwrap_or(MIC_TEST_MS).clamp(500, 10_000));
+    let emit_levels = emit_levels.unwrap_or(false);
+
+    tokio::task::spawn_blocking(move || {
+        let buffer = AudioBuffer::new(0, 0);
+        let (stop_tx, stop_rx) = std::sync::mpsc::channel::<()>();
+        let prefer_built_in = crate::db::settings::load().prefer_built_in_mic;
+        let info = capture::start(buffer.samples.clone(), stop_rx, prefer_built_in, |_| {})
+            .map_err(CommandError::AudioDevice)?;
+
+        let started = Instant::now();
+        while started.elapsed() < duration {
+            std::thread::sleep(Duration::from_millis(60));
+            if emit_levels {
+                let level = {
+                    let buf = buffer.samples.lock().unwrap();
+                    let window = 4096.min(buf.len());
+                    processing::rms_energy(&buf[buf.len() - window..])
+                };
+                let _ = app.emit("mic-test-level", level);
+            }
+        }
+        let _ = stop_tx.send(());
+
+        let result = MicTestResult::analyze(
+            &buffer.take(),
+            info.sample_rate,
+            info.channels,
+            duration.as_millis() as u64,
+        );
+        log::info!(
+            "Microphone test: rms={:.4}, peak={:.4}, clipped={:.4}, verdict={:?}",
+            result.rms,
+            result.peak,
+            result.clipped_ratio,
+            result.verdict
+        );
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+const BAR_WIDTH: f64 = 250.0;
+const BAR_HEIGHT: f64 = 44.0;
+const BAR_MARGIN: f64 = 32.0;
+
+/// Placements accepted for the recording bar.
+pub const BAR_POSITIONS: [&str; 6] = [
+    "bottom-center",
+    "top-center",
+    "bottom-left",
+    "bottom-right",
+    "top-left",
+    "top-right",
+];
+
+/// A display the recording bar can be pinned to.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub primary: bool,
+}
+
+/// Top-left corner of the bar for `position` inside a monitor's work area.
+/// Everything is in physical pixels; the bar size and margin are scaled.
+fn bar_origin(position: &str, area: (i32, i32, u32, u32), scale: f64) -> (i32, i32) {
+    let (x, y, w, h) = area;
+    let bar_w = (BAR_WIDTH * scale).round() as i32;
+    let bar_h = (BAR_HEIGHT * scale).round() as i32;
+    let margin = (BAR_MARGIN * scale).round() as i32;
+    let (w, h) = (w as i32, h as i32);
+
+    let (vertical, horizontal) = position.split_once('-').unwrap_or(("bottom", "center"));
+    let left = match horizontal {
+        "left" => x + margin,
+        "right" => x + w - bar_w - margin,
+        _ => x + (w - bar_w) / 2,
+    };
+    let top = match vertical {
+        "top" => y + margin,
+        _ => y + h - bar_h - margin,
+    };
+    (left, top)
+}
+
+/// The monitor chosen in settings: "primary", "cursor" (the display under
+/// the mouse) or a monitor name. Falls back to the primary monitor.
+fn bar_monitor(app: &AppHandle, choice: &str) -> Option<Monitor> {
+    let chosen = match choice {
+        "primary" => None,
+        "cursor" => app
+            .cursor_position()
+            .ok()
+            .and_then(|pos| app.monitor_from_point(pos.x, pos.y).ok().flatten()),
+        name => app.available_monitors().ok().and_then(|monitors| {
+            monitors
+                .into_iter()
+                .find(|m| m.name().is_some_and(|n| n == name))
+        }),
+    };
+    chosen.or_else(|| app.primary_monitor().ok().flatten())
+}
+
+/// Move the recording bar to its configured monitor and placement.
+fn position_recording_bar(app: &AppHandle, position: &str, monitor: &str) {
+    let Some(window) = app.get_webview_window("recording-bar") else {
+        return;
+    };
+    let Some(monitor) = bar_monitor(app, monitor) else {
+        return;
+    };
+    let area = monitor.work_area();
+    let (x, y) = bar_origin(
+        position,
+        (
+            area.position.x,
+            area.position.y,
+            area.size.width,
+            area.size.height,
+        ),
+        monitor.scale_factor(),
+    );
+    if let Err(e) = window.set_position(PhysicalPosition::new(x, y)) {
+        log::warn!("Failed to position recording bar: {}", e);
+    }
+}
+
 /// Create the recording bar window (hidden) at startup so showing it later
 /// doesn't activate the app or steal focus.
 pub fn create_recording_bar(app: &AppHandle) -> Result<(), String> {
@@ -310,17 +590,13 @@ pub fn create_recording_bar(app: &AppHandle) -> Result<(), String> {
         return Ok(());
     }
 
-    let bar_width = 250.0_f64;
-    let bar_height = 44.0_f64;
-    let margin_bottom = 32.0_f64;
-
-    let mut builder = WebviewWindowBuilder::new(
+    let window = WebviewWindowBuilder::new(
         app,
         "recording-bar",
         WebviewUrl::App("/recording-bar".into()),
     )
     .title("Recording")
-    .inner_size(bar_width, bar_height)
+    .inner_size(BAR_WIDTH, BAR_HEIGHT)
     .always_on_top(true)
     .decorations(false)
     .transparent(true)
@@ -328,22 +604,17 @@ pub fn create_recording_bar(app: &AppHandle) -> Result<(), String> {
     .resizable(false)
     .skip_taskbar(true)
     .focused(false)
-    .visible(false);
+    .visible(false)
+    .center()
+    .build()
+    .map_err(|e| e.to_string())?;
 
-    // Position at bottom-center of primary monitor
-    if let Some(monitor) = app.primary_monitor().ok().flatten() {
-        let screen_size = monitor.size();
-        let scale = monitor.scale_factor();
-        let logical_w = screen_size.width as f64 / scale;
-        let logical_h = screen_size.height as f64 / scale;
-        let x = (logical_w - bar_width) / 2.0;
-        let y = logical_h - bar_height - margin_bottom;
-        builder = builder.position(x, y);
-    } else {
-        builder = builder.center();
-    }
-
-    let window = builder.build().map_err(|e| e.to_string())?;
+    let settings = crate::db::settings::load();
+    position_recording_bar(
+        app,
+        &settings.recording_bar_position,
+        &settings.recording_bar_monitor,
+    );
 
     // Start hidden
     let _ = window.hide();
@@ -355,11 +626,61 @@ pub fn create_recording_bar(app: &AppHandle) -> Result<(), String> {
 pub fn show_recording_bar(app: AppHandle) -> Result<(), String> {
     log::info!("show_recording_bar: called");
     if let Some(window) = app.get_webview_window("recording-bar") {
+        // Re-placed on every show so "cursor" follows the active display
+        let settings = crate::db::settings::load();
+        position_recording_bar(
+            &app,
+            &settings.recording_bar_position,
+            &settings.recording_bar_monitor,
+        );
         let _ = window.show();
     }
     Ok(())
 }
 
+/// Change where the recording bar appears and move it there right away.
+/// `monitor` is "primary", "cursor" or a name from `list_monitors`.
+#[tauri::command]
+pub fn set_recording_bar_position(
+    app: AppHandle,
+    position: String,
+    monitor: String,
+) -> Result<(), String> {
+    if !BAR_POSITIONS.contains(&position.as_str()) {
+        return Err(format!("Unknown recording bar position '{}'", position));
+    }
+    let mut settings = serde_json::Map::new();
+    settings.insert("recordingBarPosition".into(), position.clone().into());
+    settings.insert("recordingBarMonitor".into(), monitor.clone().into());
+    crate::db::settings::save_all(&settings).map_err(|e| e.to_string())?;
+
+    position_recording_bar(&app, &position, &monitor);
+    log::info!("Recording bar moved to {} on '{}'", position, monitor);
+    Ok(())
+}
+
+/// Connected displays, for choosing where the recording bar appears.
+#[tauri::command]
+pub fn list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    let primary = app
+        .primary_monitor()
+        .map_err(|e| e.to_string())?
+        .and_then(|m| m.name().cloned());
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    Ok(monitors
+        .into_iter()
+        .filter_map(|m| {
+            let name = m.name()?.clone();
+            Some(MonitorInfo {
+                primary: primary.as_ref() == Some(&name),
+                width: m.size().width,
+                height: m.size().height,
+                name,
+            })
+        })
+        .collect())
+}
+
 #[tauri::command]
 pub fn hide_recording_bar(app: AppHandle) -> Result<(), String> {
     log::info!("hide_recording_bar: called");
@@ -371,19 +692,316 @@ pub fn hide_recording_bar(app: AppHandle) -> Result<(), String> {
 
 /// Get audio samples for a session (used by transcription commands).
 pub fn get_session_audio(state: &RecordingState, session_id: &str) -> Option<Vec<f32>> {
-    state.sessions.lock().unwrap().get(session_id).cloned()
+    state
+        .sessions
+        .lock()
+        .unwrap()
+        .get(session_id)
+        .map(|s| s.samples.clone())
 }
 
-/// Insert audio samples for a session (used by audio import).
+/// Insert audio samples for a session (recording stop, audio import). Expired
+/// sessions are dropped at the same time.
 pub fn insert_session_audio(state: &RecordingState, session_id: &str, samples: Vec<f32>) {
+    let mut sessions = state.sessions.lock().unwrap();
+    prune_expired(&mut sessions, Instant::now(), SESSION_TTL);
+    sessions.insert(
+        session_id.to_string(),
+        SessionAudio {
+            samples,
+            created_at: Instant::now(),
+        },
+    );
+}
+
+/// Remove and return audio samples for a session (frees memory after transcription).
+pub fn take_session_audio(state: &RecordingState, session_id: &str) -> Option<Vec<f32>> {
     state
         .sessions
         .lock()
         .unwrap()
-        .insert(session_id.to_string(), samples);
+        .remove(session_id)
+        .map(|s| s.samples)
 }
 
-/// Remove and return audio samples for a session (frees memory after transcription).
-pub fn take_session_audio(state: &RecordingState, session_id: &str) -> Option<Vec<f32>> {
-    state.sessions.lock().unwrap().remove(session_id)
+/// Drop sessions older than `ttl`, in case the frontend never released them
+/// (window reloaded mid-transcription, failed pipeline).
+fn prune_expired(sessions: &mut HashMap<String, SessionAudio>, now: Instant, ttl: Duration) {
+    let before = sessions.len();
+    sessions.retain(|_, s| now.duration_since(s.created_at) < ttl);
+    let dropped = before - sessions.len();
+    if dropped > 0 {
+        log::info!("Dropped {} expired audio session(s)", dropped);
+    }
+}
+
+/// Free a session's in-memory audio once the pipeline is done with it.
+/// Returns whether the session existed.
+#[tauri::command]
+pub fn release_session(state: State<'_, RecordingState>, session_id: String) -> bool {
+    take_session_audio(&state, &session_id).is_some()
+}
+
+/// Where the full-quality capture of a session is kept when
+/// `keepOriginalAudio` is on.
+pub fn original_wav_path(audio_dir: &Path, session_id: &str) -> PathBuf {
+    audio_dir.join(format!("{}.original.wav", session_id))
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredSession {
+    pub session_id: String,
+    pub duration_ms: u64,
+}
+
+/// Session WAVs in `audio_dir` with no history row — the app quit (or crashed)
+/// between `stop_recording` and saving the transcript. Oldest first.
+pub fn find_unfinished(
+    audio_dir: &Path,
+    has_history: impl Fn(&str) -> bool,
+) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(audio_dir) else {
+        return Vec::new();
+    };
+
+    let mut found: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "wav" {
+                return None;
+            }
+            // Only session files are named by UUID
+            let stem = path.file_stem()?.to_str()?.to_string();
+            Uuid::parse_str(&stem).ok()?;
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, stem, path))
+        })
+        .filter(|(_, stem, _)| !has_history(stem))
+        .collect();
+
+    found.sort_by_key(|(modified, _, _)| *modified);
+    found
+        .into_iter()
+        .map(|(_, stem, path)| (stem, path))
+        .collect()
+}
+
+/// Load the audio of recordings that never reached history back into the
+/// session map so the frontend can transcribe them. Skipped in incognito mode,
+/// where the history save would be dropped anyway.
+#[tauri::command]
+pub async fn recover_sessions(app: AppHandle) -> Result<Vec<RecoveredSession>, String> {
+    if app.state::<RecordingState>().is_incognito() {
+        return Ok(Vec::new());
+    }
+
+    let audio_dir = crate::system::storage::audio_dir(&app)?;
+
+    tokio::task::spawn_blocking(move || {
+        let state = app.state::<RecordingState>();
+        let unfinished = find_unfinished(&audio_dir, |id| {
+            crate::db::history::exists(id).unwrap_or(true)
+                || get_session_audio(&state, id).is_some()
+        });
+
+        let mut recovered = Vec::new();
+        for (session_id, path) in unfinished {
+            let (samples, sample_rate, channels) = match wav::read_wav_file(&path) {
+                Ok(v) => v,
+                Err(e) => {
+                    log::warn!("Failed to read unfinished session {:?}: {}", path, e);
+                    continue;
+                }
+            };
+            let processed = processing::preprocess(&samples, channels, sample_rate);
+            let duration_ms = (processed.len() as f64 / 16000.0 * 1000.0) as u64;
+            insert_session_audio(&state, &session_id, processed);
+            recovered.push(RecoveredSession {
+                session_id,
+                duration_ms,
+            });
+        }
+
+        if !recovered.is_empty() {
+            log::info!("Recovered {} unfinished session(s)", recovered.len());
+        }
+        recovered
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Drop a session that produced nothing worth keeping (or a secret): frees
+/// its audio, forgets its transcript and deletes its WAVs, unless a history
+/// entry still points at it.
+#[tauri::command]
+pub async fn discard_session(
+    app: AppHandle,
+    state: State<'_, RecordingState>,
+    session_id: String,
+) -> Result<(), String> {
+    take_session_audio(&state, &session_id);
+    app.state::<crate::commands::jobs::JobManager>().forget_results(&session_id);
+
+    if Uuid::parse_str(&session_id).is_err() {
+        return Err(format!("Invalid session id: {}", session_id));
+    }
+    let id = session_id.clone();
+    let has_history = tokio::task::spawn_blocking(move || crate::db::history::exists(&id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    if has_history {
+        return Ok(());
+    }
+
+    let audio_dir = crate::system::storage::audio_dir(&app)?;
+    remove_session_files(&audio_dir, &session_id).map_err(|e| e.to_string())
+}
+
+/// Delete a session's WAV and kept original from `audio_dir`, if present.
+pub fn remove_session_files(audio_dir: &Path, session_id: &str) -> std::io::Result<()> {
+    for path in [
+        audio_dir.join(format!("{}.wav", session_id)),
+        original_wav_path(audio_dir, session_id),
+    ] {
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mic_verdicts() {
+        let verdict = |samples: &[f32]| MicTestResult::analyze(samples, 16000, 1, 0).verdict;
+
+        assert_eq!(verdict(&[0.001; 1000]), MicVerdict::TooQuiet);
+        assert_eq!(verdict(&[]), MicVerdict::TooQuiet);
+
+        let speech: Vec<f32> = (0..1000).map(|i| (i as f32 * 0.05).sin() * 0.3).collect();
+        let result = MicTestResult::analyze(&speech, 16000, 1, 0);
+        assert_eq!(result.verdict, MicVerdict::Ok);
+        assert!(result.rms > MIC_QUIET_RMS);
+        assert!(result.peak <= 0.3);
+        assert_eq!(result.clipped_ratio, 0.0);
+
+        let mut clipping = speech.clone();
+        clipping[..10].fill(1.0);
+        assert_eq!(verdict(&clipping), MicVerdict::Clipping);
+    }
+
+    #[test]
+    fn bar_origin_placements() {
+        // 1920x1080 work area at the origin, 1x scale
+        let area = (0, 0, 1920, 1080);
+        assert_eq!(bar_origin("bottom-center", area, 1.0), (835, 1004));
+        assert_eq!(bar_origin("top-center", area, 1.0), (835, 32));
+        assert_eq!(bar_origin("top-left", area, 1.0), (32, 32));
+        assert_eq!(bar_origin("bottom-right", area, 1.0), (1638, 1004));
+        // Unknown values fall back to bottom-center
+        assert_eq!(bar_origin("middle", area, 1.0), (835, 1004));
+
+        // Second monitor to the right with 2x scale
+        let area = (1920, 0, 2880, 1800);
+        assert_eq!(
+            bar_origin("bottom-center", area, 2.0),
+            (1920 + 1190, 1800 - 88 - 64)
+        );
+        assert_eq!(
+            bar_origin("top-right", area, 2.0),
+            (1920 + 2880 - 500 - 64, 64)
+        );
+    }
+
+    #[test]
+    fn insert_get_and_release_session() {
+        let state = RecordingState::new();
+        insert_session_audio(&state, "s1", vec![0.1, 0.2]);
+        assert_eq!(get_session_audio(&state, "s1"), Some(vec![0.1, 0.2]));
+        // get clones, the session stays
+        assert!(get_session_audio(&state, "s1").is_some());
+        assert_eq!(take_session_audio(&state, "s1"), Some(vec![0.1, 0.2]));
+        assert!(get_session_audio(&state, "s1").is_none());
+    }
+
+    #[test]
+    fn prune_drops_only_expired_sessions() {
+        let start = Instant::now();
+        let ttl = Duration::from_secs(60);
+        let mut sessions = HashMap::new();
+        sessions.insert(
+            "old".to_string(),
+            SessionAudio {
+                samples: vec![0.0],
+                created_at: start,
+            },
+        );
+        sessions.insert(
+            "new".to_string(),
+            SessionAudio {
+                samples: vec![0.0],
+                created_at: start + Duration::from_secs(115),
+            },
+        );
+        prune_expired(&mut sessions, start + Duration::from_secs(120), ttl);
+        assert!(!sessions.contains_key("old"));
+        assert!(sessions.contains_key("new"));
+    }
+
+    #[test]
+    fn find_unfinished_skips_known_and_foreign_files() {
+        let dir = std::env::temp_dir().join("sobotta_test_unfinished");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let orphan = Uuid::new_v4().to_string();
+        let saved = Uuid::new_v4().to_string();
+        for name in [
+            format!("{}.wav", orphan),
+            format!("{}.wav", saved),
+            format!("{}.original.wav", orphan),
+            "notes.wav".to_string(),
+            format!("{}.txt", Uuid::new_v4()),
+        ] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let found = find_unfinished(&dir, |id| id == saved);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, orphan);
+        assert_eq!(found[0].1, dir.join(format!("{}.wav", orphan)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn deleted_history_audio_is_not_recovered() {
+        let dir = std::env::temp_dir().join("sobotta_test_unfinished_deleted");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let id = Uuid::new_v4().to_string();
+        std::fs::write(dir.join(format!("{}.wav", id)), b"").unwrap();
+        std::fs::write(original_wav_path(&dir, &id), b"").unwrap();
+
+        // The history row is gone, so only removing the files keeps it from coming back
+        remove_session_files(&dir, &id).unwrap();
+        assert!(find_unfinished(&dir, |_| false).is_empty());
+        assert!(!original_wav_path(&dir, &id).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn find_unfinished_missing_dir_is_empty() {
+        let dir = std::env::temp_dir().join("sobotta_test_unfinished_missing");
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(find_unfinished(&dir, |_| false).is_empty());
+    }
 }
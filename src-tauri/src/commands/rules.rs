@@ -0,0 +1,286 @@
+use crate::db;
+use crate::db::rules::{self, RuleSetting};
+use crate::rules::custom;
+use serde::{Deserialize, Serialize};
+
+/// Identifies a shared rules file.
+const LIBRARY_FORMAT: &str = "sobottaai-rules";
+/// Version of the sharing format this build writes; newer files are rejected.
+const LIBRARY_VERSION: u32 = 1;
+
+/// Portable file of custom regex rules, in the order they run:
+/// `{ "format": "sobottaai-rules", "version": 1, "rules": [...] }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleLibrary {
+    pub format: String,
+    pub version: u32,
+    pub rules: Vec<SharedRule>,
+}
+
+/// One rule in a library. Ids aren't shared; importing assigns new ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedRule {
+    pub name: String,
+    pub pattern: String,
+    #[serde(default)]
+    pub replacement: String,
+    /// See [`custom::FLAGS`].
+    #[serde(default)]
+    pub flags: String,
+    /// Rules in files that don't say are imported enabled.
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl RuleLibrary {
+    fn new(rules: &[RuleSetting]) -> Self {
+        Self {
+            format: LIBRARY_FORMAT.into(),
+            version: LIBRARY_VERSION,
+            rules: rules
+                .iter()
+                .filter(|r| !r.is_builtin)
+                .map(|r| SharedRule {
+                    name: r.name.clone(),
+                    pattern: r.pattern.clone().unwrap_or_default(),
+                    replacement: r.replacement.clone().unwrap_or_default(),
+                    flags: r.flags.clone().unwrap_or_default(),
+                    enabled: r.enabled,
+                })
+                .collect(),
+        }
+    }
+
+    fn parse(json: &str) -> Result<Self, String> {
+        let mut library: Self =
+            serde_json::from_str(json).map_err(|e| format!("Not a rules file: {}", e))?;
+        if library.format != LIBRARY_FORMAT {
+            return Err(format!("Not a rules file (format '{}')", library.format));
+        }
+        if library.version > LIBRARY_VERSION {
+            return Err(format!(
+                "Rules file version {} needs a newer version of SobottaAI",
+                library.version
+            ));
+        }
+        for rule in &mut library.rules {
+            custom::validate(&rule.pattern, &rule.flags, &rule.replacement)
+                .map_err(|e| format!("Rule '{}' is invalid: {}", rule.name, e))?;
+            rule.flags = custom::normalize_flags(&rule.flags)?;
+        }
+        Ok(library)
+    }
+
+    /// Rules to add, skipping any with the same pattern, flags and
+    /// replacement as one in `existing` or earlier in the file.
+    fn new_rules(self, existing: &[RuleSetting]) -> Vec<SharedRule> {
+        let mut seen: Vec<(String, String, String)> = existing
+            .iter()
+            .filter_map(|r| {
+                Some((
+                    r.pattern.clone()?,
+                    r.flags.clone().unwrap_or_default(),
+                    r.replacement.clone().unwrap_or_default(),
+                ))
+            })
+            .collect();
+        let mut added = Vec::new();
+        for rule in self.rules {
+            let key = (
+                rule.pattern.clone(),
+                rule.flags.clone(),
+                rule.replacement.clone(),
+            );
+            if seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+            let name = match rule.name.trim() {
+                "" => rule.pattern.clone(),
+                name => name.to_string(),
+            };
+            added.push(SharedRule { name, ..rule });
+        }
+        added
+    }
+}
+
+/// The text rules with their saved enabled state, in order.
+#[tauri::command]
+pub async fn list_rules() -> Result<Vec<RuleSetting>, String> {
+    db::run_blocking(rules::list)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_rule_enabled(id: String, enabled: bool) -> Result<(), String> {
+    db::run_blocking(move || rules::set_enabled(&id, enabled))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Reorder the rules; `rule_ids` lists all of them in the new order.
+#[tauri::command]
+pub async fn reorder_rules(rule_ids: Vec<String>) -> Result<(), String> {
+    db::run_blocking(move || rules::reorder(&rule_ids))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Add an enabled custom regex rule after the existing ones. `replacement`
+/// may refer to capture groups (`$1`, `${name}`); `flags` is any of "ims".
+#[tauri::command]
+pub async fn add_rule(
+    name: String,
+    pattern: String,
+    replacement: String,
+    flags: Option<String>,
+) -> Result<Vec<RuleSetting>, String> {
+    let flags = custom::normalize_flags(&flags.unwrap_or_default())?;
+    custom::validate(&pattern, &flags, &replacement)?;
+    let id = format!("custom-{}", uuid::Uuid::new_v4());
+    let name = match name.trim() {
+        "" => pattern.clone(),
+        name => name.to_string(),
+    };
+    db::run_blocking(move || {
+        rules::add_custom(&id, &name, &pattern, &replacement, &flags, true)?;
+        rules::list()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_rule(id: String) -> Result<(), String> {
+    db::run_blocking(move || rules::delete_custom(&id))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Write the custom rules to `path` in the sharing format. Returns how many
+/// were written.
+#[tauri::command]
+pub async fn export_rules(path: String) -> Result<usize, String> {
+    let library = RuleLibrary::new(
+        &db::run_blocking(rules::list)
+            .await
+            .map_err(|e| e.to_string())?,
+    );
+    let json = serde_json::to_string_pretty(&library).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    log::info!("Exported {} rules to {}", library.rules.len(), path);
+    Ok(library.rules.len())
+}
+
+/// Add the rules in a shared file at `path` after the existing ones,
+/// skipping ones already present. Returns how many were added.
+#[tauri::command]
+pub async fn import_rules(path: String) -> Result<usize, String> {
+    let json = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let library = RuleLibrary::parse(&json)?;
+    db::run_blocking(move || {
+        let added = library.new_rules(&rules::list()?);
+        for rule in &added {
+            let id = format!("custom-{}", uuid::Uuid::new_v4());
+            rules::add_custom(
+                &id,
+                &rule.name,
+                &rule.pattern,
+                &rule.replacement,
+                &rule.flags,
+                rule.enabled,
+            )?;
+        }
+        log::info!("Imported {} rules from {}", added.len(), path);
+        Ok(added.len())
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(pattern: &str, replacement: &str) -> RuleSetting {
+        RuleSetting {
+            id: format!("custom-{}", pattern),
+            name: pattern.into(),
+            enabled: true,
+            sort_order: 0,
+            is_builtin: false,
+            pattern: Some(pattern.into()),
+            replacement: Some(replacement.into()),
+            flags: Some(String::new()),
+        }
+    }
+
+    #[test]
+    fn library_round_trips_custom_rules_in_order() {
+        let builtin = RuleSetting {
+            id: "remove-fillers".into(),
+            name: "Remove Filler Words".into(),
+            enabled: true,
+            sort_order: 0,
+            is_builtin: true,
+            pattern: None,
+            replacement: None,
+            flags: None,
+        };
+        let rules = [builtin, custom(r"\bteh\b", "the"), custom(r"\s+,", ",")];
+        let json = serde_json::to_string(&RuleLibrary::new(&rules)).unwrap();
+        let added = RuleLibrary::parse(&json).unwrap().new_rules(&[]);
+        assert_eq!(
+            added.iter().map(|r| r.pattern.as_str()).collect::<Vec<_>>(),
+            vec![r"\bteh\b", r"\s+,"]
+        );
+        assert!(added.iter().all(|r| r.enabled));
+    }
+
+    #[test]
+    fn library_import_skips_existing_and_duplicate_rules() {
+        let json = r#"{"format":"sobottaai-rules","version":1,"rules":[
+            {"name":"Typo","pattern":"teh","replacement":"the"},
+            {"name":"","pattern":"recieve","replacement":"receive"},
+            {"name":"Again","pattern":"recieve","replacement":"receive"}]}"#;
+        let added = RuleLibrary::parse(json)
+            .unwrap()
+            .new_rules(&[custom("teh", "the")]);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].name, "recieve");
+        assert!(added[0].enabled);
+    }
+
+    #[test]
+    fn library_rejects_invalid_files() {
+        assert!(RuleLibrary::parse("[]").is_err());
+        assert!(RuleLibrary::parse(r#"{"format":"other","version":1,"rules":[]}"#).is_err());
+        assert!(
+            RuleLibrary::parse(r#"{"format":"sobottaai-rules","version":2,"rules":[]}"#).is_err()
+        );
+        let bad_regex = r#"{"format":"sobottaai-rules","version":1,
+            "rules":[{"name":"Bad","pattern":"[unclosed"}]}"#;
+        assert!(RuleLibrary::parse(bad_regex)
+            .unwrap_err()
+            .contains("Rule 'Bad' is invalid"));
+        let missing_group = r#"{"format":"sobottaai-rules","version":1,
+            "rules":[{"name":"Issue","pattern":"issue (\\d+)","replacement":"ISSUE-$2"}]}"#;
+        assert!(RuleLibrary::parse(missing_group).is_err());
+    }
+
+    #[test]
+    fn library_keeps_flags() {
+        let json = r#"{"format":"sobottaai-rules","version":1,"rules":[
+            {"name":"Issue","pattern":"issue (\\d+)","replacement":"ISSUE-$1","flags":"mi"}]}"#;
+        let added = RuleLibrary::parse(json).unwrap().new_rules(&[]);
+        assert_eq!(added[0].flags, "im");
+    }
+}
@@ -1,19 +1,31 @@
-use crate::system::hotkey::HotkeyModeState;
+use crate::commands::recording::RecordingState;
+use crate::commands::transcription::SttManager;
+use crate::db::maintenance::MaintenanceReport;
+use crate::stt::parakeet::{self, Provider};
+use crate::system::hotkey::{self, HotkeyAction, HotkeyBackend, HotkeyModeState};
+use crate::system::mouse_trigger::{self, MouseButton};
+use crate::system::storage::{self, MigrationReport};
 use crate::system::tray;
 use serde_json::Value;
+use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
-use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
+/// Settings last saved by the frontend, as a camelCase key/value object.
 #[tauri::command]
 pub fn get_settings() -> Result<Value, String> {
-    // Settings are primarily managed via tauri-plugin-store on the frontend.
-    Ok(serde_json::json!({}))
+    crate::db::settings::get_all()
+        .map(Value::Object)
+        .map_err(|e| e.to_string())
 }
 
+/// Mirror the frontend settings store into the database so hotkey, tray and
+/// transcription code can read them directly.
 #[tauri::command]
 pub fn save_settings(settings: Value) -> Result<(), String> {
-    let _ = settings;
-    Ok(())
+    let Value::Object(map) = settings else {
+        return Err("Settings must be an object".into());
+    };
+    crate::db::settings::save_all(&map).map_err(|e| e.to_string())
 }
 
 /// Sync tray menu check marks with current frontend settings.
@@ -31,31 +43,216 @@ pub fn sync_tray(
 #[tauri::command]
 pub fn update_hotkey(app: AppHandle, hotkey: String) -> Result<(), String> {
     // Parse FIRST — validate before unregistering anything
-    let shortcut: tauri_plugin_global_shortcut::Shortcut = hotkey
-        .parse()
-        .map_err(|e| format!("Invalid hotkey '{}': {:?}", hotkey, e))?;
+    let shortcut = hotkey::parse_hotkey(&hotkey)?;
+    hotkey::set_shortcut(&app, HotkeyAction::Dictation, Some(shortcut))?;
+    log::info!("Global hotkey updated to: {}", hotkey);
+    Ok(())
+}
 
-    let manager = app.global_shortcut();
+/// Set or clear (`None`) the hotkey that rewrites the selected text.
+#[tauri::command]
+pub fn update_rewrite_hotkey(app: AppHandle, hotkey: Option<String>) -> Result<(), String> {
+    update_optional_hotkey(&app, HotkeyAction::RewriteSelection, hotkey)
+}
 
-    // Only unregister after we know the new shortcut is valid
-    manager.unregister_all().map_err(|e| e.to_string())?;
+/// Set or clear (`None`) the hotkey that pastes the last transcript again.
+#[tauri::command]
+pub fn update_paste_last_hotkey(app: AppHandle, hotkey: Option<String>) -> Result<(), String> {
+    update_optional_hotkey(&app, HotkeyAction::PasteLast, hotkey)
+}
 
-    // Register via the shared handler that reads HotkeyModeState
-    crate::system::hotkey::register_shortcut(&app, shortcut).map_err(|e| e.to_string())?;
+/// Set or clear (`None`) the hotkey that deletes the last paste.
+#[tauri::command]
+pub fn update_undo_paste_hotkey(app: AppHandle, hotkey: Option<String>) -> Result<(), String> {
+    update_optional_hotkey(&app, HotkeyAction::UndoPaste, hotkey)
+}
 
-    log::info!("Global hotkey updated to: {}", hotkey);
+fn update_optional_hotkey(
+    app: &AppHandle,
+    action: HotkeyAction,
+    hotkey: Option<String>,
+) -> Result<(), String> {
+    let shortcut = hotkey.as_deref().map(hotkey::parse_hotkey).transpose()?;
+    let id = action.id();
+    hotkey::set_shortcut(app, action, shortcut)?;
+    log::info!("{} hotkey updated to: {:?}", id, hotkey);
+    Ok(())
+}
+
+/// Switch the tray menu and notifications to `language` ("auto" follows the
+/// system language).
+#[tauri::command]
+pub fn set_ui_language(app: AppHandle, language: String) -> Result<(), String> {
+    crate::i18n::set_locale(&language);
+    tray::refresh_language(&app);
+    Ok(())
+}
+
+/// Select how global hotkeys are delivered: "native", "portal" (XDG desktop
+/// portal, for Wayland) or "auto".
+#[tauri::command]
+pub fn set_hotkey_backend(app: AppHandle, backend: String) -> Result<(), String> {
+    let backend = HotkeyBackend::parse(&backend)?;
+    hotkey::set_backend(&app, backend)
+}
+
+/// Trigger dictation with a mouse button: "none", "middle", "back" (M4) or
+/// "forward" (M5).
+#[tauri::command]
+pub fn set_mouse_button(app: AppHandle, button: String) -> Result<(), String> {
+    mouse_trigger::set_button(&app, MouseButton::parse(&button)?);
+    Ok(())
+}
+
+/// Set the inference threads of local models (0 picks automatically) and
+/// low-power mode. Loaded models are dropped so they reload with the new
+/// thread count.
+#[tauri::command]
+pub fn set_inference_threads(app: AppHandle, threads: u32, low_power: bool) {
+    crate::stt::set_threads(threads, low_power);
+    app.state::<SttManager>().evict_all();
+    log::info!(
+        "Inference threads: {} (requested {}), low power: {}",
+        crate::stt::default_threads(),
+        threads,
+        low_power
+    );
+}
+
+/// Execution providers Parakeet can run on in this build, e.g. "cpu" and
+/// "coreml".
+#[tauri::command]
+pub fn parakeet_providers() -> Vec<String> {
+    parakeet::available_providers()
+        .into_iter()
+        .map(|p| p.id().to_string())
+        .collect()
+}
+
+/// Run Parakeet on "auto", "cpu", "coreml", "cuda" or "directml". Loaded
+/// models are dropped so they reload on the new provider.
+#[tauri::command]
+pub fn set_parakeet_provider(app: AppHandle, provider: String) -> Result<(), String> {
+    parakeet::set_provider(Provider::parse(&provider)?);
+    app.state::<SttManager>().evict_all();
+    log::info!("Parakeet execution provider: {}", provider);
     Ok(())
 }
 
-/// Update the recording mode (push-to-talk or toggle).
+/// Update the recording mode (push-to-talk, toggle or hands-free).
 #[tauri::command]
 pub fn update_recording_mode(app: AppHandle, mode: String) -> Result<(), String> {
     let state = app.state::<HotkeyModeState>();
     *state.mode.lock().unwrap() = mode.clone();
+    tray::set_recording_mode_checked(&app, &mode);
     log::info!("Recording mode updated to: {}", mode);
     Ok(())
 }
 
+/// Enable or disable incognito mode (no WAV files, no history).
+#[tauri::command]
+pub fn set_incognito(app: AppHandle, enabled: bool) -> Result<(), String> {
+    app.state::<RecordingState>().set_incognito(enabled);
+    tray::set_incognito_checked(&app, enabled);
+    log::info!("Incognito mode: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_incognito(app: AppHandle) -> Result<bool, String> {
+    Ok(app.state::<RecordingState>().is_incognito())
+}
+
+/// Enable or disable launching the app at login.
+#[tauri::command]
+pub fn set_autostart(app: AppHandle, enabled: bool) -> Result<(), String> {
+    crate::system::autostart::set_enabled(&app, enabled).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_autostart(app: AppHandle) -> Result<bool, String> {
+    crate::system::autostart::is_enabled(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_database_encryption() -> Result<bool, String> {
+    Ok(crate::db::is_encrypted())
+}
+
+/// Encrypt (or decrypt) the history database at rest. The key lives in the
+/// OS keychain.
+#[tauri::command]
+pub async fn set_database_encryption(enabled: bool) -> Result<(), String> {
+    crate::db::run_blocking(move || crate::db::set_encryption(enabled))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Check the database's integrity and shrink the file after history was
+/// deleted.
+#[tauri::command]
+pub async fn maintain_database() -> Result<MaintenanceReport, String> {
+    crate::db::run_blocking(crate::db::maintenance::run)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Where audio and models are stored.
+#[tauri::command]
+pub fn get_storage_dir(app: AppHandle) -> Result<String, String> {
+    storage::root(&app).map(|dir| dir.to_string_lossy().to_string())
+}
+
+/// Change where audio and models are stored (`None` restores the app data
+/// dir). Existing files are moved along unless `move_files` is false, and
+/// history rows are pointed at the new WAV locations.
+#[tauri::command]
+pub async fn set_storage_dir(
+    app: AppHandle,
+    path: Option<String>,
+    move_files: Option<bool>,
+) -> Result<MigrationReport, String> {
+    if app.state::<RecordingState>().is_recording() {
+        return Err("Can't change storage location while recording".into());
+    }
+
+    let from = storage::root(&app)?;
+    let to = match &path {
+        Some(path) => PathBuf::from(path),
+        None => app.path().app_data_dir().map_err(|e| e.to_string())?,
+    };
+    storage::validate_target(&from, &to)?;
+    std::fs::create_dir_all(&to)
+        .map_err(|e| format!("Can't use {}: {}", to.display(), e))?;
+
+    let move_files = move_files.unwrap_or(true) && from != to;
+    tokio::task::spawn_blocking(move || {
+        let mut report = MigrationReport::default();
+        if move_files {
+            report = storage::move_dirs(&from, &to)?;
+            report.history_updated =
+                crate::db::history::relocate_audio(&report.moved)?;
+        }
+
+        let mut setting = serde_json::Map::new();
+        setting.insert("storageDir".into(), path.map_or(Value::Null, Value::String));
+        crate::db::settings::save_all(&setting)?;
+
+        log::info!(
+            "Storage moved from {:?} to {:?}: {} file(s), {} bytes, {} history row(s)",
+            from,
+            to,
+            report.files_moved,
+            report.bytes_moved,
+            report.history_updated
+        );
+        Ok::<_, anyhow::Error>(report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn restart_app(app: AppHandle) -> Result<(), String> {
     app.restart();
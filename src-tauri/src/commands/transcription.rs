@@ -1,36 +1,69 @@
+use crate::commands::error::CommandError;
+use crate::commands::jobs::{self, JobManager, JobStatus};
 use crate::commands::recording::{self, RecordingState};
+use crate::db::settings::AppSettings;
 use crate::models;
+use crate::stt::abort::{self, AbortSignal};
+use crate::stt::cloud_http::RequestPolicy;
+use crate::stt::queue::{Priority, TranscriptionQueue};
 use crate::stt::parakeet::ParakeetEngine;
 use crate::stt::whisper::WhisperEngine;
 use crate::stt::whisper_onnx::WhisperOnnxEngine;
-use crate::stt::{SttEngine, TranscriptionOptions, TranscriptionResult};
+use crate::stt::{
+    InferenceMetrics, ProgressFn, SttEngine, TranscriptionOptions, TranscriptionResult,
+};
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Manager, State};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// How often the live transcript is refreshed while recording.
+const PARTIAL_INTERVAL: Duration = Duration::from_millis(1500);
+/// Only the most recent audio is re-transcribed for the live transcript.
+const PARTIAL_WINDOW_SECS: u32 = 20;
+
+/// Payload of `transcription-progress`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionProgress {
+    pub session_id: String,
+    pub job_id: Option<String>,
+    /// 0–100.
+    pub percent: u8,
+}
+
+/// Payload of `recording-partial-text`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialText {
+    pub text: String,
+}
 
 /// Manages loaded STT engine instances (cached to avoid re-loading models).
 /// Also serializes transcription calls to prevent concurrent Whisper inference.
 pub struct SttManager {
     engines: Mutex<HashMap<String, Arc<dyn SttEngine>>>,
-    /// Serializes transcription to prevent multiple concurrent Whisper calls
-    transcription_lock: Arc<Mutex<()>>,
+    /// Runs one local transcription at a time, dictations first
+    pub(crate) queue: Arc<TranscriptionQueue>,
 }
 
 impl SttManager {
     pub fn new() -> Self {
         Self {
             engines: Mutex::new(HashMap::new()),
-            transcription_lock: Arc::new(Mutex::new(())),
+            queue: Arc::new(TranscriptionQueue::new()),
         }
     }
 
     /// Get or load a local STT engine for the given model.
-    fn get_or_load(
+    pub(crate) fn get_or_load(
         &self,
         model_id: &str,
-        app_data_dir: &Path,
-    ) -> Result<Arc<dyn SttEngine>, String> {
+        storage_dir: &Path,
+    ) -> Result<Arc<dyn SttEngine>, CommandError> {
         let mut engines = self.engines.lock().unwrap();
 
         if let Some(engine) = engines.get(model_id) {
@@ -41,13 +74,13 @@ impl SttManager {
         let model_info = catalog
             .iter()
             .find(|m| m.id == model_id)
-            .ok_or_else(|| format!("Unknown model: {}", model_id))?;
+            .ok_or_else(|| CommandError::UnknownModel(model_id.to_string()))?;
 
-        if !models::is_model_downloaded(app_data_dir, model_info) {
-            return Err(format!("Model '{}' is not downloaded", model_id));
+        if !models::is_model_downloaded(storage_dir, model_info) {
+            return Err(CommandError::ModelNotDownloaded(model_id.to_string()));
         }
 
-        let model_dir = models::model_path(app_data_dir, model_id);
+        let model_dir = models::model_path(storage_dir, model_id);
 
         let engine: Arc<dyn SttEngine> = match model_info.engine {
             models::Engine::Whisper => {
@@ -92,30 +125,203 @@ impl SttManager {
     pub fn evict(&self, model_id: &str) {
         self.engines.lock().unwrap().remove(model_id);
     }
+
+    /// Clear every cached engine, so they're loaded again with new settings.
+    pub fn evict_all(&self) {
+        self.engines.lock().unwrap().clear();
+    }
 }
 
 /// Determine the engine type for a model ID.
 fn engine_for_model(model_id: &str) -> Option<models::Engine> {
-    models::full_catalog()
-        .into_iter()
-        .find(|m| m.id == model_id)
-        .map(|m| m.engine)
+    models::find(model_id).map(|m| m.engine)
 }
 
+/// Start a transcription job for a dictation. Returns the job id
+/// immediately; progress and the final result arrive as `job-updated` events
+/// and can be polled with `get_job_status`. Model, language and initial
+/// prompt default to the saved settings when omitted. Local models take it
+/// before any queued file jobs.
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub async fn transcribe(
     app: AppHandle,
-    recording_state: State<'_, RecordingState>,
-    stt_manager: State<'_, SttManager>,
+    job_manager: State<'_, JobManager>,
     session_id: String,
-    model_id: String,
+    model_id: Option<String>,
     language: Option<String>,
     // Cloud STT needs API key from frontend
     api_key: Option<String>,
     cloud_model: Option<String>,
-) -> Result<TranscriptionResult, String> {
-    let audio = recording::get_session_audio(&recording_state, &session_id)
+    initial_prompt: Option<String>,
+) -> Result<String, CommandError> {
+    let request = JobRequest {
+        session_id,
+        model_id,
+        language,
+        api_key,
+        cloud_model,
+        initial_prompt,
+    };
+    start_job(app, &job_manager, request, Priority::Interactive)
+}
+
+/// What a `transcribe` or `transcribe_file` call asked for.
+struct JobRequest {
+    session_id: String,
+    model_id: Option<String>,
+    language: Option<String>,
+    api_key: Option<String>,
+    cloud_model: Option<String>,
+    initial_prompt: Option<String>,
+}
+
+/// Start a transcription job running in the background and return its id.
+fn start_job(
+    app: AppHandle,
+    job_manager: &JobManager,
+    request: JobRequest,
+    priority: Priority,
+) -> Result<String, CommandError> {
+    let JobRequest {
+        session_id,
+        model_id,
+        language,
+        api_key,
+        cloud_model,
+        initial_prompt,
+    } = request;
+    let (model_id, language) = match model_id {
+        Some(model_id) => (model_id, language),
+        None => {
+            let settings = crate::db::settings::load();
+            let language = language.or_else(|| settings.language());
+            (settings.selected_model, language)
+        }
+    };
+    let (job, cancelled) = job_manager.create(&session_id);
+    let job_id = job.id.clone();
+    jobs::emit_job(&app, &job);
+
+    tauri::async_runtime::spawn(async move {
+        let manager = app.state::<JobManager>();
+        if let Some(info) = manager.update(&job.id, |j| j.status = JobStatus::Running) {
+            jobs::emit_job(&app, &info);
+        }
+
+        let outcome = run_transcription(
+            &app,
+            &session_id,
+            Some(&job.id),
+            &model_id,
+            language,
+            api_key,
+            cloud_model,
+            initial_prompt,
+            priority,
+            cancelled,
+        )
+        .await;
+
+        let updated = manager.update(&job.id, |j| {
+            j.progress = 1.0;
+            match outcome {
+                Ok(result) => {
+                    j.status = JobStatus::Completed;
+                    j.result = Some(result);
+                }
+                Err(e) => {
+                    log::error!("Transcription job {} failed: {}", j.id, e);
+                    j.status = JobStatus::Failed;
+                    j.error = Some(e);
+                }
+            }
+        });
+        if let Some(info) = updated {
+            jobs::emit_job(&app, &info);
+            if let Some(result) = &info.result {
+                crate::system::notifications::transcription_finished(&app, &result.text);
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+/// API key for a cloud model from the saved provider settings.
+pub(crate) fn cloud_api_key(settings: &AppSettings, model_id: &str) -> Option<String> {
+    match engine_for_model(model_id)? {
+        models::Engine::CloudOpenAI => settings.api_key("openai"),
+        models::Engine::CloudGroq => settings.api_key("groq"),
+        _ => None,
+    }
+}
+
+/// API key and model for an OpenAI transcription. A custom server gets its
+/// own key (none if unset) so the OpenAI key never leaves for a third party,
+/// and names its own models.
+fn openai_credentials(
+    settings: &AppSettings,
+    custom_server: bool,
+    api_key: Option<String>,
+    cloud_model: Option<String>,
+) -> Result<(String, Option<String>), CommandError> {
+    if custom_server {
+        let model = Some(settings.openai_stt_model.clone())
+            .filter(|m| !m.is_empty())
+            .or(cloud_model);
+        return Ok((settings.openai_stt_api_key.trim().to_string(), model));
+    }
+    let key = api_key
+        .ok_or_else(|| CommandError::ApiKeyMissing("cloud OpenAI transcription".into()))?;
+    Ok((key, cloud_model))
+}
+
+/// Progress reporter for a local transcription: emits
+/// `transcription-progress` and, for a job, updates its progress too.
+fn progress_reporter(app: &AppHandle, session_id: &str, job_id: Option<&str>) -> ProgressFn {
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    let job_id = job_id.map(str::to_string);
+    Arc::new(move |progress: f32| {
+        let _ = app.emit(
+            "transcription-progress",
+            TranscriptionProgress {
+                session_id: session_id.clone(),
+                job_id: job_id.clone(),
+                percent: (progress.clamp(0.0, 1.0) * 100.0).round() as u8,
+            },
+        );
+        if let Some(job_id) = &job_id {
+            let manager = app.state::<JobManager>();
+            // Left at 1.0 only once the job has its result
+            if let Some(info) = manager.update(job_id, |j| j.progress = progress.min(0.99)) {
+                jobs::emit_job(&app, &info);
+            }
+        }
+    })
+}
+
+/// The transcription pipeline behind a job: silence check, vocabulary, then
+/// the cloud or local engine. Local engines report progress as
+/// `transcription-progress` events.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn run_transcription(
+    app: &AppHandle,
+    session_id: &str,
+    job_id: Option<&str>,
+    model_id: &str,
+    language: Option<String>,
+    api_key: Option<String>,
+    cloud_model: Option<String>,
+    initial_prompt: Option<String>,
+    priority: Priority,
+    cancelled: Arc<AtomicBool>,
+) -> Result<TranscriptionResult, CommandError> {
+    let recording_state = app.state::<RecordingState>();
+    let stt_manager = app.state::<SttManager>();
+
+    let audio = recording::get_session_audio(&recording_state, session_id)
         .ok_or("Session not found")?;
 
     if audio.is_empty() {
@@ -133,15 +339,31 @@ pub async fn transcribe(
             language: None,
             segments: vec![],
             duration_ms: 0,
+            metrics: None,
         });
     }
 
-    // Load vocabulary terms from database to improve transcription accuracy
-    let vocabulary = crate::db::vocabulary::get_terms().unwrap_or_default();
-
+    let settings = crate::db::settings::load();
+    let initial_prompt = initial_prompt.or(settings.initial_prompt.clone());
+    // Load vocabulary terms for this language to improve transcription accuracy
+    let vocabulary = prompt_vocabulary(&settings, language.as_deref(), initial_prompt.as_deref());
+    let sent_terms = vocabulary.clone();
+    let openai_base_url = settings
+        .openai_stt_base_url
+        .clone()
+        .filter(|url| !url.trim().is_empty());
+    let policy = RequestPolicy::new(
+        settings.cloud_stt_timeout_secs,
+        settings.cloud_stt_max_retries,
+    );
     let options = TranscriptionOptions {
         language,
         vocabulary,
+        initial_prompt,
+        decoding: settings.decoding_options.clone(),
+        code_switching: settings.code_switching,
+        code_switch_languages: settings.code_switch_languages.clone(),
+        code_switch_cuts: vec![],
     };
 
     log::info!(
@@ -150,61 +372,356 @@ pub async fn transcribe(
         model_id
     );
 
-    let engine_type = engine_for_model(&model_id)
-        .ok_or_else(|| format!("Unknown model: {}", model_id))?;
+    let model = models::find(model_id)
+        .ok_or_else(|| CommandError::UnknownModel(model_id.to_string()))?;
+    // An English-only model would transcribe Spanish as English gibberish
+    if let Some(language) = options.language.as_deref() {
+        if !model.supports_language(language) {
+            return Err(CommandError::UnsupportedLanguage {
+                model: model.name,
+                language: language.to_string(),
+            });
+        }
+    }
+    let engine_type = model.engine;
+
+    // An explicit cloud model overrides the catalog variant's
+    let cloud_model = cloud_model
+        .filter(|m| !m.is_empty())
+        .or_else(|| models::cloud_api_model(model_id).map(String::from));
 
-    match engine_type {
+    let sample_count = audio.len();
+    let (mut result, engine_name, threads) = match engine_type {
         models::Engine::CloudOpenAI => {
-            let key = api_key.ok_or("API key required for cloud OpenAI transcription")?;
-            crate::stt::cloud_openai::transcribe(&audio, &options, &key)
-                .await
-                .map_err(|e| format!("Cloud OpenAI transcription failed: {}", e))
+            let (key, model) =
+                openai_credentials(&settings, openai_base_url.is_some(), api_key, cloud_model)?;
+            let model = model.as_deref().unwrap_or("whisper-1");
+            let result = crate::stt::cloud_openai::transcribe(
+                &audio,
+                &options,
+                &key,
+                model,
+                openai_base_url.as_deref(),
+                &policy,
+            )
+            .await
+            .map_err(|e| CommandError::cloud_stt(e, "Cloud OpenAI transcription failed"))?;
+            (result, "cloud-openai".to_string(), None)
         }
         models::Engine::CloudGroq => {
-            let key = api_key.ok_or("API key required for cloud Groq transcription")?;
+            let key = api_key.ok_or_else(|| {
+                CommandError::ApiKeyMissing("cloud Groq transcription".into())
+            })?;
             let model = cloud_model.as_deref().unwrap_or("whisper-large-v3-turbo");
-            crate::stt::cloud_groq::transcribe(&audio, &options, &key, model)
-                .await
-                .map_err(|e| format!("Cloud Groq transcription failed: {}", e))
+            let result =
+                crate::stt::cloud_groq::transcribe(&audio, &options, &key, model, &policy)
+                    .await
+                    .map_err(|e| CommandError::cloud_stt(e, "Cloud Groq transcription failed"))?;
+            (result, "cloud-groq".to_string(), None)
         }
         _ => {
             // Local model (Whisper or Parakeet)
-            let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-            let engine = stt_manager.get_or_load(&model_id, &app_data_dir)?;
-            let transcription_lock = stt_manager.transcription_lock.clone();
+            let storage_dir = crate::system::storage::root(app)?;
+            let whisper = matches!(engine_type, models::Engine::Whisper | models::Engine::WhisperOnnx);
+            let vad_model = Some(crate::stt::vad::model_path(&storage_dir))
+                .filter(|path| whisper && settings.vad_enabled && path.exists());
+            let punctuation_model = Some(crate::stt::punctuation::model_path(&storage_dir))
+                .filter(|path| {
+                    matches!(engine_type, models::Engine::Parakeet)
+                        && settings.punctuation_enabled
+                        && path.exists()
+                });
+            let engine = stt_manager.get_or_load(model_id, &storage_dir)?;
+            let engine_name = engine.engine_name().to_string();
+            let threads = engine.threads();
+            let queue = stt_manager.queue.clone();
+            let on_progress = progress_reporter(app, session_id, job_id);
+            let was_cancelled = cancelled.clone();
+            let audio_secs = sample_count as u64 / 16000;
+            let limit = abort::inference_limit(settings.max_inference_secs, audio_secs);
+            let handle = app.clone();
+            let model_id = model_id.to_string();
 
-            tokio::task::spawn_blocking(move || {
-                let _guard = transcription_lock.lock().unwrap();
-                engine.transcribe(&audio, &options)
+            let result = tokio::task::spawn_blocking(move || {
+                let _turn = queue.acquire(priority);
+                // The job may have been cancelled while queued behind another one
+                if cancelled.load(Ordering::SeqCst) {
+                    anyhow::bail!("cancelled");
+                }
+                // The time limit starts once this job has the engine to itself
+                let abort = AbortSignal::new(cancelled, limit);
+                let work_abort = abort.clone();
+                let outcome = abort::run_abortable(&abort, move || {
+                    let mut result = transcribe_local(
+                        engine.as_ref(),
+                        &audio,
+                        &options,
+                        &on_progress,
+                        vad_model.as_deref(),
+                        &work_abort,
+                    )?;
+                    if let Some(model) = punctuation_model {
+                        crate::stt::punctuation::apply(&model, &mut result)?;
+                    }
+                    Ok(result)
+                })
+                .unwrap_or_else(|| {
+                    // Still busy with the abandoned run, so load a fresh copy next time
+                    log::warn!("Abandoned a transcription that didn't stop on {}", model_id);
+                    handle.state::<SttManager>().evict(&model_id);
+                    Err(anyhow::anyhow!("stopped"))
+                });
+                if outcome.is_err() && abort.timed_out() {
+                    let secs = limit.map_or(0, |limit| limit.as_secs());
+                    anyhow::bail!(abort::InferenceTimedOut(secs));
+                }
+                outcome
             })
             .await
             .map_err(|e| format!("Transcription task failed: {}", e))?
-            .map_err(|e| format!("Transcription failed: {}", e))
+            .map_err(|e| {
+                if was_cancelled.load(Ordering::SeqCst) {
+                    CommandError::Cancelled
+                } else if e.is::<abort::InferenceTimedOut>() {
+                    CommandError::Timeout(e.to_string())
+                } else {
+                    CommandError::Other(format!("Transcription failed: {}", e))
+                }
+            })?;
+            (result, engine_name, threads)
         }
+    };
+
+    // Engines report their own inference time (request time for cloud APIs)
+    let metrics = InferenceMetrics::new(&engine_name, threads, result.duration_ms, sample_count);
+    log::info!(
+        "Transcribed with {} in {}ms (RTF {:.2})",
+        metrics.engine,
+        metrics.inference_ms,
+        metrics.real_time_factor
+    );
+    result.metrics = Some(metrics);
+    // Usage counts would leak what an incognito dictation said
+    if !recording_state.is_incognito() {
+        if let Err(e) = crate::db::vocabulary::record_usage(&sent_terms, &result.text) {
+            log::warn!("Failed to record vocabulary usage: {}", e);
+        }
+    }
+    Ok(result)
+}
+
+/// Run a local engine, on just the speech regions when a VAD model is given,
+/// until `abort` is raised.
+fn transcribe_local(
+    engine: &dyn SttEngine,
+    audio: &[f32],
+    options: &TranscriptionOptions,
+    on_progress: &ProgressFn,
+    vad_model: Option<&Path>,
+    abort: &AbortSignal,
+) -> anyhow::Result<TranscriptionResult> {
+    let Some(vad_model) = vad_model else {
+        return engine.transcribe_with_progress(audio, options, on_progress, abort);
+    };
+
+    // Only transcribe the speech, then put timestamps back in place
+    let regions = crate::stt::vad::speech_regions(vad_model, audio)?;
+    let speech = crate::stt::vad::SpeechAudio::new(audio, regions);
+    log::info!(
+        "VAD kept {:.1}s of {:.1}s audio",
+        speech.samples.len() as f64 / 16000.0,
+        audio.len() as f64 / 16000.0,
+    );
+    if speech.samples.is_empty() {
+        return Ok(TranscriptionResult {
+            text: String::new(),
+            language: None,
+            segments: vec![],
+            duration_ms: 0,
+            metrics: None,
+        });
+    }
+    let options = TranscriptionOptions {
+        code_switch_cuts: speech.seams(),
+        ..options.clone()
+    };
+    let mut result =
+        engine.transcribe_with_progress(&speech.samples, &options, on_progress, abort)?;
+    speech.remap(&mut result);
+    Ok(result)
+}
+
+/// Vocabulary for the prompt: terms for `language`, most relevant first, cut
+/// to what fits in the token budget after the initial prompt.
+fn prompt_vocabulary(
+    settings: &AppSettings,
+    language: Option<&str>,
+    initial_prompt: Option<&str>,
+) -> Vec<String> {
+    let terms = crate::db::vocabulary::get_terms(language).unwrap_or_default();
+    let budget = settings
+        .prompt_token_budget
+        .saturating_sub(initial_prompt.map_or(0, crate::stt::estimate_tokens));
+    let total = terms.len();
+    let terms = crate::stt::terms_within_budget(terms, budget);
+    if terms.len() < total {
+        log::info!(
+            "Vocabulary trimmed to {} of {} terms for a {}-token prompt budget",
+            terms.len(),
+            total,
+            settings.prompt_token_budget
+        );
     }
+    terms
+}
+
+/// Start index of the last `secs` seconds of interleaved audio, aligned to
+/// a whole frame.
+fn tail_start(len: usize, sample_rate: u32, channels: u16, secs: u32) -> usize {
+    let channels = channels.max(1) as usize;
+    let window = sample_rate as usize * channels * secs as usize;
+    let start = len.saturating_sub(window);
+    start - start % channels
+}
+
+/// Re-transcribe the tail of an in-progress recording every
+/// `PARTIAL_INTERVAL` and emit it as `recording-partial-text` until `stop`
+/// fires. Only local models are used, and a partial is skipped rather than
+/// queued when another transcription holds the engine.
+pub(crate) fn spawn_partials(
+    app: AppHandle,
+    samples: Arc<Mutex<Vec<f32>>>,
+    sample_rate: u32,
+    channels: u16,
+    stop: Receiver<()>,
+) {
+    std::thread::spawn(move || {
+        let settings = crate::db::settings::load();
+        let model_id = settings.selected_model.clone();
+        match engine_for_model(&model_id) {
+            Some(models::Engine::CloudOpenAI | models::Engine::CloudGroq) | None => {
+                log::info!("Live transcript needs a local model, '{}' is not", model_id);
+                return;
+            }
+            _ => {}
+        }
+        let language = settings.language();
+        let options = TranscriptionOptions {
+            vocabulary: prompt_vocabulary(
+                &settings,
+                language.as_deref(),
+                settings.initial_prompt.as_deref(),
+            ),
+            language,
+            initial_prompt: settings.initial_prompt.clone(),
+            decoding: settings.decoding_options.clone(),
+            // Per-chunk detection is too slow for a rolling preview
+            code_switching: false,
+            code_switch_languages: vec![],
+            code_switch_cuts: vec![],
+        };
+
+        let stt_manager = app.state::<SttManager>();
+        let engine = match crate::system::storage::root(&app)
+            .map_err(CommandError::from)
+            .and_then(|dir| stt_manager.get_or_load(&model_id, &dir))
+        {
+            Ok(engine) => engine,
+            Err(e) => {
+                log::warn!("Live transcript unavailable: {}", e);
+                return;
+            }
+        };
+
+        let mut last_len = 0;
+        // Any message or a dropped sender ends the loop
+        while let Err(RecvTimeoutError::Timeout) = stop.recv_timeout(PARTIAL_INTERVAL) {
+            let raw = {
+                let buf = samples.lock().unwrap();
+                if buf.len() == last_len {
+                    continue;
+                }
+                last_len = buf.len();
+                buf[tail_start(buf.len(), sample_rate, channels, PARTIAL_WINDOW_SECS)..].to_vec()
+            };
+            let audio = crate::audio::processing::preprocess(&raw, channels, sample_rate);
+            if crate::audio::processing::rms_energy(&audio) < 0.01 {
+                continue;
+            }
+
+            let Some(_turn) = stt_manager.queue.try_acquire() else {
+                continue;
+            };
+            match engine.transcribe(&audio, &options) {
+                Ok(result) => {
+                    let text = result.text.trim().to_string();
+                    let _ = app.emit("recording-partial-text", PartialText { text });
+                }
+                Err(e) => log::warn!("Partial transcription failed: {}", e),
+            }
+        }
+    });
 }
 
+/// Like `transcribe`, for an imported file: on a local model it waits
+/// behind dictations.
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub async fn transcribe_file(
     app: AppHandle,
-    recording_state: State<'_, RecordingState>,
-    stt_manager: State<'_, SttManager>,
+    job_manager: State<'_, JobManager>,
     session_id: String,
-    model_id: String,
+    model_id: Option<String>,
     language: Option<String>,
     api_key: Option<String>,
     cloud_model: Option<String>,
-) -> Result<TranscriptionResult, String> {
-    transcribe(
-        app,
-        recording_state,
-        stt_manager,
+    initial_prompt: Option<String>,
+) -> Result<String, CommandError> {
+    let request = JobRequest {
         session_id,
         model_id,
         language,
         api_key,
         cloud_model,
-    )
-    .await
+        initial_prompt,
+    };
+    start_job(app, &job_manager, request, Priority::Background)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_start_keeps_recent_whole_frames() {
+        // Shorter than the window: everything
+        assert_eq!(tail_start(1000, 16000, 1, 20), 0);
+        // Mono: exactly the last 20s
+        assert_eq!(tail_start(16000 * 30, 16000, 1, 20), 16000 * 10);
+        // Stereo: aligned to a frame boundary
+        let start = tail_start(48000 * 2 * 25 + 1, 48000, 2, 20);
+        assert_eq!(start % 2, 0);
+        assert_eq!(start, 48000 * 2 * 5);
+    }
+
+    #[test]
+    fn custom_server_never_gets_the_openai_key() {
+        let openai_key = Some("sk-openai".to_string());
+        let mut settings = AppSettings {
+            openai_stt_model: "Systran/faster-whisper-small".into(),
+            ..Default::default()
+        };
+        let (key, model) =
+            openai_credentials(&settings, true, openai_key.clone(), None).unwrap();
+        assert_eq!(key, "");
+        assert_eq!(model.as_deref(), Some("Systran/faster-whisper-small"));
+
+        settings.openai_stt_api_key = "server-key".into();
+        let (key, _) = openai_credentials(&settings, true, openai_key.clone(), None).unwrap();
+        assert_eq!(key, "server-key");
+
+        let (key, _) = openai_credentials(&settings, false, openai_key, None).unwrap();
+        assert_eq!(key, "sk-openai");
+        assert!(openai_credentials(&settings, false, None, None).is_err());
+    }
 }
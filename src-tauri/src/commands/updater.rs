@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+const STABLE_ENDPOINT: &str =
+    "https://github.com/dotyigit/sobottaai/releases/latest/download/latest.json";
+/// Pre-releases never become GitHub's "latest", so beta builds publish
+/// their manifest to a rolling `beta` tag instead.
+const BETA_ENDPOINT: &str =
+    "https://github.com/dotyigit/sobottaai/releases/download/beta/latest.json";
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "stable" => Ok(Self::Stable),
+            "beta" => Ok(Self::Beta),
+            other => Err(format!("Unknown update channel '{}'", other)),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+        }
+    }
+
+    fn endpoint(self) -> &'static str {
+        match self {
+            Self::Stable => STABLE_ENDPOINT,
+            Self::Beta => BETA_ENDPOINT,
+        }
+    }
+
+    /// The saved channel, defaulting to stable.
+    fn saved() -> Self {
+        Self::parse(&crate::db::settings::load().update_channel).unwrap_or(Self::Stable)
+    }
+}
+
+/// Update found by `check_for_updates`, kept until it is installed.
+pub struct UpdaterState {
+    pending: Mutex<Option<Update>>,
+}
+
+impl UpdaterState {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    /// RFC 3339 release date.
+    pub date: Option<String>,
+    pub body: Option<String>,
+    pub channel: UpdateChannel,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateProgress {
+    downloaded_bytes: u64,
+    content_length: Option<u64>,
+}
+
+#[tauri::command]
+pub fn get_update_channel() -> Result<UpdateChannel, String> {
+    Ok(UpdateChannel::saved())
+}
+
+/// Switch between stable and beta releases. Takes effect on the next check.
+#[tauri::command]
+pub fn set_update_channel(
+    updater_state: State<'_, UpdaterState>,
+    channel: String,
+) -> Result<(), String> {
+    let channel = UpdateChannel::parse(&channel)?;
+    let mut setting = serde_json::Map::new();
+    setting.insert("updateChannel".into(), channel.as_str().into());
+    crate::db::settings::save_all(&setting).map_err(|e| e.to_string())?;
+    // An update found on the other channel no longer applies
+    *updater_state.pending.lock().unwrap() = None;
+    log::info!("Update channel set to {}", channel.as_str());
+    Ok(())
+}
+
+/// Check the selected channel's release manifest. Returns `None` when the
+/// app is up to date.
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let channel = UpdateChannel::saved();
+    let endpoint = Url::parse(channel.endpoint()).map_err(|e| e.to_string())?;
+    let update = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .map_err(|e| e.to_string())?
+        .timeout(CHECK_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let info = update.as_ref().map(|u| UpdateInfo {
+        version: u.version.clone(),
+        current_version: u.current_version.clone(),
+        date: u
+            .date
+            .and_then(|d| chrono::DateTime::from_timestamp(d.unix_timestamp(), 0))
+            .map(|d| d.to_rfc3339()),
+        body: u.body.clone(),
+        channel,
+    });
+    log::info!(
+        "Update check ({}): {}",
+        channel.as_str(),
+        info.as_ref().map_or("up to date", |i| i.version.as_str())
+    );
+    *app.state::<UpdaterState>().pending.lock().unwrap() = update;
+    Ok(info)
+}
+
+/// Download and install the update found by the last `check_for_updates`,
+/// emitting `update-download-progress`. Restart to finish. A failed install
+/// keeps the update so it can be retried.
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<(), String> {
+    let update = app
+        .state::<UpdaterState>()
+        .pending
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("No update available — check for updates first")?;
+
+    let mut downloaded_bytes = 0u64;
+    let installed = update
+        .download_and_install(
+            |chunk_length, content_length| {
+                downloaded_bytes += chunk_length as u64;
+                let _ = app.emit(
+                    "update-download-progress",
+                    UpdateProgress {
+                        downloaded_bytes,
+                        content_length,
+                    },
+                );
+            },
+            || log::info!("Update downloaded, installing"),
+        )
+        .await;
+    if let Err(e) = installed {
+        // Keep it for a retry, unless a newer check replaced it meanwhile
+        let state = app.state::<UpdaterState>();
+        state.pending.lock().unwrap().get_or_insert(update);
+        return Err(e.to_string());
+    }
+
+    log::info!("Update {} installed", update.version);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_parse_round_trips() {
+        for channel in [UpdateChannel::Stable, UpdateChannel::Beta] {
+            assert_eq!(UpdateChannel::parse(channel.as_str()), Ok(channel));
+        }
+        assert!(UpdateChannel::parse("nightly").is_err());
+    }
+
+    #[test]
+    fn channel_endpoints_are_valid_urls() {
+        for channel in [UpdateChannel::Stable, UpdateChannel::Beta] {
+            assert!(Url::parse(channel.endpoint()).is_ok());
+        }
+        assert_ne!(
+            UpdateChannel::Stable.endpoint(),
+            UpdateChannel::Beta.endpoint()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::db;
+use crate::db::corrections::{self, VocabularySuggestion};
 use crate::db::vocabulary::{self, VocabularyTerm};
 
 #[tauri::command]
@@ -9,10 +11,21 @@ pub async fn get_vocabulary() -> Result<Vec<VocabularyTerm>, String> {
 }
 
 #[tauri::command]
-pub async fn add_term(term: String, replacement: Option<String>) -> Result<(), String> {
+pub async fn add_term(
+    term: String,
+    replacement: Option<String>,
+    category: Option<String>,
+    language: Option<String>,
+) -> Result<(), String> {
     let id = uuid::Uuid::new_v4().to_string();
     tokio::task::spawn_blocking(move || {
-        vocabulary::add(&id, &term, replacement.as_deref())
+        vocabulary::add(
+            &id,
+            &term,
+            replacement.as_deref(),
+            category.as_deref(),
+            language.as_deref(),
+        )
     })
     .await
     .map_err(|e| e.to_string())?
@@ -26,3 +39,16 @@ pub async fn delete_term(id: String) -> Result<(), String> {
         .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())
 }
+
+/// Corrections made in edited transcripts at least `min_count` times (2 by
+/// default) that aren't vocabulary terms or replacements yet. Each can be
+/// added as a term or as a replacement of `original` with `term`.
+#[tauri::command]
+pub async fn suggest_vocabulary(
+    min_count: Option<u32>,
+) -> Result<Vec<VocabularySuggestion>, String> {
+    let min_count = min_count.unwrap_or(2).max(1);
+    db::run_blocking(move || corrections::suggest(min_count))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,48 @@
+use crate::db;
+use crate::db::watch_folders::{self, WatchFolder};
+use crate::system::folder_watch;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn list_watch_folders() -> Result<Vec<WatchFolder>, String> {
+    db::run_blocking(watch_folders::list)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create (empty `id`) or update a watched folder and restart watching.
+/// Returns the saved folder.
+#[tauri::command]
+pub async fn save_watch_folder(
+    app: AppHandle,
+    mut folder: WatchFolder,
+) -> Result<WatchFolder, String> {
+    folder.path = folder.path.trim().to_string();
+    if !std::path::Path::new(&folder.path).is_dir() {
+        return Err(format!("Not a folder: {}", folder.path));
+    }
+    folder.model_id = folder.model_id.filter(|id| !id.is_empty());
+    if folder.id.is_empty() {
+        folder.id = uuid::Uuid::new_v4().to_string();
+    }
+    let id = folder.id.clone();
+    let saved = db::run_blocking(move || {
+        watch_folders::upsert(&folder)?;
+        watch_folders::list()?
+            .into_iter()
+            .find(|f| f.id == id)
+            .ok_or_else(|| anyhow::anyhow!("Watched folder not found: {}", id))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    folder_watch::restart(&app)?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_watch_folder(app: AppHandle, id: String) -> Result<(), String> {
+    db::run_blocking(move || watch_folders::delete(&id))
+        .await
+        .map_err(|e| e.to_string())?;
+    folder_watch::restart(&app)
+}
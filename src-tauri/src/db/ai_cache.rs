@@ -0,0 +1,60 @@
+//! AI function outputs keyed by function, model and input, so running the
+//! same function on the same transcript again is instant and isn't billed
+//! twice. Entries expire after the configured TTL.
+
+use crate::db;
+use rusqlite::OptionalExtension;
+use sha2::{Digest, Sha256};
+
+/// Cache key for a request: a hash of everything that shapes the output.
+pub fn key(function_id: &str, model: &str, system_prompt: &str, input: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [function_id, model, system_prompt, input] {
+        // Length prefixes keep ("ab", "c") and ("a", "bc") apart
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Cached output for `key` if it was stored less than `ttl_secs` ago.
+pub fn get(key: &str, ttl_secs: u64) -> anyhow::Result<Option<String>> {
+    let conn = db::get_conn()?;
+    let output = conn
+        .query_row(
+            "SELECT output FROM ai_cache
+             WHERE key = ?1 AND created_at > datetime('now', ?2)",
+            rusqlite::params![key, format!("-{} seconds", ttl_secs)],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(output)
+}
+
+/// Store an output, replacing any earlier one for the same key, and drop
+/// entries older than `ttl_secs`.
+pub fn put(
+    key: &str,
+    function_id: &str,
+    model: &str,
+    output: &str,
+    ttl_secs: u64,
+) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO ai_cache (key, function_id, model, output, created_at)
+         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)",
+        rusqlite::params![key, function_id, model, output],
+    )?;
+    conn.execute(
+        "DELETE FROM ai_cache WHERE created_at <= datetime('now', ?1)",
+        rusqlite::params![format!("-{} seconds", ttl_secs)],
+    )?;
+    Ok(())
+}
+
+/// Remove every cached output, returning how many there were.
+pub fn clear() -> anyhow::Result<usize> {
+    let conn = db::get_conn()?;
+    Ok(conn.execute("DELETE FROM ai_cache", [])?)
+}
@@ -1,5 +1,7 @@
 use crate::db;
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiFunctionRow {
@@ -12,7 +14,7 @@ pub struct AiFunctionRow {
 }
 
 pub fn insert(item: &AiFunctionRow) -> anyhow::Result<()> {
-    let conn = db::get_conn().lock().unwrap();
+    let conn = db::get_conn()?;
     conn.execute(
         "INSERT OR REPLACE INTO ai_functions (id, name, prompt, provider, model, is_builtin)
          VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
@@ -29,7 +31,7 @@ pub fn insert(item: &AiFunctionRow) -> anyhow::Result<()> {
 }
 
 pub fn list() -> anyhow::Result<Vec<AiFunctionRow>> {
-    let conn = db::get_conn().lock().unwrap();
+    let conn = db::get_conn()?;
     let mut stmt = conn.prepare(
         "SELECT id, name, prompt, provider, model, is_builtin FROM ai_functions WHERE is_builtin = FALSE",
     )?;
@@ -51,10 +53,90 @@ pub fn list() -> anyhow::Result<Vec<AiFunctionRow>> {
 }
 
 pub fn delete(id: &str) -> anyhow::Result<()> {
-    let conn = db::get_conn().lock().unwrap();
-    conn.execute(
+    let conn = db::get_conn()?;
+    let deleted = conn.execute(
         "DELETE FROM ai_functions WHERE id = ?1 AND is_builtin = FALSE",
         rusqlite::params![id],
     )?;
+    if deleted > 0 {
+        conn.execute(
+            "DELETE FROM ai_function_output WHERE function_id = ?1",
+            rusqlite::params![id],
+        )?;
+    }
+    Ok(())
+}
+
+/// How often and how recently a function (built-in or custom) has been run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Usage {
+    pub count: u32,
+    pub last_used_at: Option<String>,
+}
+
+/// Count a run of the function with `id`.
+pub fn record_use(id: &str) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "INSERT INTO ai_function_usage (function_id, use_count, last_used_at)
+         VALUES (?1, 1, ?2)
+         ON CONFLICT(function_id) DO UPDATE SET
+             use_count = use_count + 1,
+             last_used_at = excluded.last_used_at",
+        rusqlite::params![id, chrono::Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Usage of every function that has been run, keyed by function id.
+pub fn usage() -> anyhow::Result<HashMap<String, Usage>> {
+    let conn = db::get_conn()?;
+    let mut stmt =
+        conn.prepare("SELECT function_id, use_count, last_used_at FROM ai_function_usage")?;
+    let usage = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                Usage {
+                    count: row.get(1)?,
+                    last_used_at: row.get(2)?,
+                },
+            ))
+        })?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(usage)
+}
+
+/// The id of the function (built-in or custom) that was run most recently.
+pub fn last_used() -> anyhow::Result<Option<String>> {
+    let conn = db::get_conn()?;
+    let id = conn
+        .query_row(
+            "SELECT function_id FROM ai_function_usage ORDER BY last_used_at DESC LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+/// Output actions chosen for functions, as JSON, keyed by function id.
+/// Functions without one paste their output.
+pub fn output_actions() -> anyhow::Result<HashMap<String, String>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare("SELECT function_id, action FROM ai_function_output")?;
+    let actions = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(actions)
+}
+
+pub fn set_output_action(id: &str, action: &str) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "INSERT INTO ai_function_output (function_id, action) VALUES (?1, ?2)
+         ON CONFLICT(function_id) DO UPDATE SET action = excluded.action",
+        rusqlite::params![id, action],
+    )?;
     Ok(())
 }
@@ -0,0 +1,74 @@
+//! Per-app paste adjustments, matched by the name of the app in front when
+//! pasting: chat apps that send on Enter and terminals that run each line.
+
+use crate::db;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppProfile {
+    pub id: String,
+    /// App name as the system reports it ("Slack", "Terminal"; the `.exe`
+    /// name without extension on Windows), matched ignoring case.
+    pub app_name: String,
+    /// "keep", "shiftEnter" (type Shift+Enter between lines) or "space"
+    /// (join lines with spaces).
+    pub newline_mode: String,
+    /// Drop line breaks at the end of the text.
+    pub strip_trailing_newline: bool,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+pub fn list() -> anyhow::Result<Vec<AppProfile>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, app_name, newline_mode, strip_trailing_newline, created_at
+         FROM app_profiles ORDER BY created_at ASC, id ASC",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(AppProfile {
+                id: row.get(0)?,
+                app_name: row.get(1)?,
+                newline_mode: row.get(2)?,
+                strip_trailing_newline: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// The profile for `app_name`, if there is one.
+pub fn find(app_name: &str) -> anyhow::Result<Option<AppProfile>> {
+    Ok(list()?
+        .into_iter()
+        .find(|p| p.app_name.trim().eq_ignore_ascii_case(app_name.trim())))
+}
+
+pub fn upsert(profile: &AppProfile) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "INSERT INTO app_profiles (id, app_name, newline_mode, strip_trailing_newline)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(id) DO UPDATE SET
+            app_name = ?2, newline_mode = ?3, strip_trailing_newline = ?4",
+        rusqlite::params![
+            profile.id,
+            profile.app_name,
+            profile.newline_mode,
+            profile.strip_trailing_newline
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn delete(id: &str) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "DELETE FROM app_profiles WHERE id = ?1",
+        rusqlite::params![id],
+    )?;
+    Ok(())
+}
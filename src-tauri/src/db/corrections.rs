@@ -0,0 +1,172 @@
+//! Word-level corrections the user made to transcripts. Fixes that keep
+//! recurring are offered as vocabulary so the model gets them right next time.
+
+use crate::db;
+use serde::Serialize;
+
+/// Longest phrase, in words, treated as a correction. Longer edits are
+/// rewrites rather than misrecognitions.
+const MAX_PHRASE_WORDS: usize = 3;
+
+/// Skip the diff when the changed region is larger than this many word pairs.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VocabularySuggestion {
+    /// What the model transcribed.
+    pub original: String,
+    /// What the user changed it to; the term to add.
+    pub term: String,
+    /// How many times this correction was made.
+    pub count: u32,
+}
+
+/// Pairs of `(original, corrected)` phrases where `edited` replaced words of
+/// `original`. Insertions, deletions and punctuation-only changes are ignored.
+pub fn word_corrections(original: &str, edited: &str) -> Vec<(String, String)> {
+    let a: Vec<&str> = original.split_whitespace().collect();
+    let b: Vec<&str> = edited.split_whitespace().collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let a = &a[prefix..a.len() - suffix];
+    let b = &b[prefix..b.len() - suffix];
+    if a.is_empty() || b.is_empty() || a.len() * b.len() > MAX_DIFF_CELLS {
+        return vec![];
+    }
+
+    // Longest common subsequence lengths of every suffix pair
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut corrections = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        // Collect the changed run on both sides up to the next match
+        let (start_i, start_j) = (i, j);
+        while i < a.len() || j < b.len() {
+            if i < a.len() && j < b.len() && a[i] == b[j] {
+                break;
+            }
+            if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        if let Some(pair) = correction(&a[start_i..i], &b[start_j..j]) {
+            corrections.push(pair);
+        }
+    }
+    corrections
+}
+
+fn correction(from: &[&str], to: &[&str]) -> Option<(String, String)> {
+    let phrase = 1..=MAX_PHRASE_WORDS;
+    if !phrase.contains(&from.len()) || !phrase.contains(&to.len()) {
+        return None;
+    }
+    let trim = |words: &[&str]| {
+        words
+            .join(" ")
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_string()
+    };
+    let (from, to) = (trim(from), trim(to));
+    (!from.is_empty() && !to.is_empty() && from != to).then_some((from, to))
+}
+
+/// Store the corrections made to a recording's transcript.
+pub fn record(recording_id: &str, corrections: &[(String, String)]) -> anyhow::Result<()> {
+    let mut conn = db::get_conn()?;
+    let tx = conn.transaction()?;
+    for (original, corrected) in corrections {
+        tx.execute(
+            "INSERT INTO corrections (recording_id, original, corrected) VALUES (?1, ?2, ?3)",
+            rusqlite::params![recording_id, original, corrected],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Corrections made at least `min_count` times that the vocabulary doesn't
+/// cover yet, neither as a term nor as a replacement for the original,
+/// most frequent first.
+pub fn suggest(min_count: u32) -> anyhow::Result<Vec<VocabularySuggestion>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(
+        "SELECT original, corrected, COUNT(*) AS n FROM corrections c
+         WHERE NOT EXISTS (
+             SELECT 1 FROM vocabulary v
+             WHERE lower(v.term) IN (lower(c.corrected), lower(c.original))
+         )
+         GROUP BY original, corrected
+         HAVING n >= ?1
+         ORDER BY n DESC, corrected ASC",
+    )?;
+    let suggestions = stmt
+        .query_map(rusqlite::params![min_count], |row| {
+            Ok(VocabularySuggestion {
+                original: row.get(0)?,
+                term: row.get(1)?,
+                count: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(original: &str, edited: &str) -> Vec<(String, String)> {
+        word_corrections(original, edited)
+    }
+
+    #[test]
+    fn finds_replaced_words() {
+        assert_eq!(
+            pairs(
+                "I deployed it to cooper netties yesterday.",
+                "I deployed it to Kubernetes yesterday."
+            ),
+            vec![("cooper netties".into(), "Kubernetes".into())]
+        );
+        assert_eq!(
+            pairs("ask sobotta about it", "ask Sobotta about it"),
+            vec![("sobotta".into(), "Sobotta".into())]
+        );
+    }
+
+    #[test]
+    fn ignores_insertions_punctuation_and_rewrites() {
+        assert!(pairs("hello world", "hello big world").is_empty());
+        assert!(pairs("hello world", "hello world.").is_empty());
+        assert!(pairs(
+            "this sentence was said one way",
+            "completely different words were typed instead here"
+        )
+        .is_empty());
+    }
+}
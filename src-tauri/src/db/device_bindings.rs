@@ -0,0 +1,77 @@
+//! Buttons on USB HID devices, such as transcription foot pedals, bound to a
+//! hotkey action. A button is a bit of the device's input report.
+
+use crate::db;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceBinding {
+    pub id: String,
+    /// Device name shown in settings.
+    pub name: String,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    /// Bit of the input report that is set while the button is held.
+    pub button: u32,
+    /// Hotkey action id: "dictation", "paste-last", "undo-paste" or
+    /// "binding:<id>".
+    pub action: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+pub fn list() -> anyhow::Result<Vec<DeviceBinding>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, name, vendor_id, product_id, button, action, enabled, created_at
+         FROM device_bindings ORDER BY created_at ASC, id ASC",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(DeviceBinding {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                vendor_id: row.get(2)?,
+                product_id: row.get(3)?,
+                button: row.get(4)?,
+                action: row.get(5)?,
+                enabled: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+pub fn upsert(binding: &DeviceBinding) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "INSERT INTO device_bindings
+            (id, name, vendor_id, product_id, button, action, enabled)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET
+            name = ?2, vendor_id = ?3, product_id = ?4, button = ?5, action = ?6,
+            enabled = ?7",
+        rusqlite::params![
+            binding.id,
+            binding.name,
+            binding.vendor_id,
+            binding.product_id,
+            binding.button,
+            binding.action,
+            binding.enabled
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn delete(id: &str) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "DELETE FROM device_bindings WHERE id = ?1",
+        rusqlite::params![id],
+    )?;
+    Ok(())
+}
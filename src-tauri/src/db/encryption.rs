@@ -0,0 +1,95 @@
+//! Optional at-rest encryption of sobottaai.db via SQLCipher.
+//!
+//! The key is a random 256-bit value kept in the OS keychain (Keychain on
+//! macOS, Credential Manager on Windows, Secret Service on Linux) and is
+//! never written to disk. Whether the database is encrypted is read from
+//! the file itself, so there is no separate flag to get out of sync.
+
+use rusqlite::Connection;
+use std::io::Read;
+use std::path::Path;
+
+pub(crate) const KEYCHAIN_SERVICE: &str = "ai.sobotta.app";
+const KEYCHAIN_USER: &str = "database-key";
+
+/// Every plaintext SQLite file starts with this header; a SQLCipher file is
+/// indistinguishable from random bytes.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// True if `path` exists and is not a plaintext SQLite file.
+pub fn is_encrypted_file(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    match std::fs::File::open(path).and_then(|mut f| f.read_exact(&mut header)) {
+        Ok(()) => &header != SQLITE_HEADER,
+        // Missing or empty files are created as plaintext
+        Err(_) => false,
+    }
+}
+
+/// Generate a random 256-bit key as 64 hex characters.
+pub fn generate_key() -> anyhow::Result<String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| anyhow::anyhow!("Failed to generate database key: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// SQLCipher raw-key literal: skips passphrase derivation since the key is
+/// already random.
+fn raw_key(key: &str) -> String {
+    format!("x'{}'", key)
+}
+
+/// Unlock an encrypted connection. Fails if the key is wrong.
+pub fn apply_key(conn: &Connection, key: &str) -> anyhow::Result<()> {
+    conn.pragma_update(None, "key", raw_key(key))?;
+    // SQLCipher only validates the key on first read
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|e| anyhow::anyhow!("Failed to unlock database: {}", e))?;
+    Ok(())
+}
+
+/// Copy the open database to `dest`, encrypted with `key` or as plaintext
+/// when `key` is `None`.
+pub fn export_to(conn: &Connection, dest: &Path, key: Option<&str>) -> anyhow::Result<()> {
+    let key = key.map(raw_key).unwrap_or_default();
+    conn.execute(
+        "ATTACH DATABASE ?1 AS export KEY ?2",
+        rusqlite::params![dest.to_string_lossy(), key],
+    )?;
+    let result = conn.query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()));
+    conn.execute("DETACH DATABASE export", [])?;
+    result?;
+    Ok(())
+}
+
+fn keychain_entry() -> anyhow::Result<keyring::Entry> {
+    Ok(keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_USER)?)
+}
+
+/// Read the database key from the OS keychain.
+pub fn load_key() -> anyhow::Result<Option<String>> {
+    match keychain_entry()?.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Return the existing keychain key, or create and store a new one.
+pub fn load_or_create_key() -> anyhow::Result<String> {
+    if let Some(key) = load_key()? {
+        return Ok(key);
+    }
+    let key = generate_key()?;
+    keychain_entry()?.set_password(&key)?;
+    Ok(key)
+}
+
+/// Remove the key from the keychain after decrypting the database.
+pub fn delete_key() -> anyhow::Result<()> {
+    match keychain_entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
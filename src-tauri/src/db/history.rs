@@ -1,120 +1,244 @@
 use crate::db;
+use crate::stt::InferenceMetrics;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct HistoryItem {
     pub id: String,
     pub audio_path: Option<String>,
+    /// Raw engine output, exactly as spoken.
     pub transcript: String,
+    /// Final text after rules and the AI function.
     pub processed_text: Option<String>,
+    /// Intermediate text after rules only (no AI function), when the
+    /// pipeline was configured to keep it.
+    #[serde(default)]
+    pub rules_text: Option<String>,
     pub model_id: String,
     pub language: Option<String>,
     pub ai_function: Option<String>,
     pub duration_ms: Option<i64>,
+    /// Engine, inference time and real-time factor of the transcription.
+    #[serde(default)]
+    pub metrics: Option<InferenceMetrics>,
     pub created_at: String,
 }
 
+/// Structured history filters. All fields are optional and combined with AND.
+/// Dates are any SQLite-parsable time string (`2025-01-31`, RFC 3339, ...);
+/// `to` is inclusive of the whole day when only a date is given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryFilter {
+    pub query: Option<String>,
+    pub model_id: Option<String>,
+    pub language: Option<String>,
+    pub ai_function: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+const COLUMNS: &str = "id, audio_path, transcript, processed_text, rules_text, model_id, language, ai_function, duration_ms, created_at, engine, inference_ms, real_time_factor, threads";
+
+fn map_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryItem> {
+    Ok(HistoryItem {
+        id: row.get(0)?,
+        audio_path: row.get(1)?,
+        transcript: row.get(2)?,
+        processed_text: row.get(3)?,
+        rules_text: row.get(4)?,
+        model_id: row.get(5)?,
+        language: row.get(6)?,
+        ai_function: row.get(7)?,
+        duration_ms: row.get(8)?,
+        created_at: row.get(9)?,
+        // Rows from before metrics were recorded have no engine
+        metrics: row
+            .get::<_, Option<String>>(10)?
+            .map(|engine| -> rusqlite::Result<InferenceMetrics> {
+                Ok(InferenceMetrics {
+                    engine,
+                    inference_ms: row.get::<_, Option<i64>>(11)?.unwrap_or(0) as u64,
+                    real_time_factor: row.get::<_, Option<f64>>(12)?.unwrap_or(0.0),
+                    threads: row.get(13)?,
+                })
+            })
+            .transpose()?,
+    })
+}
+
 pub fn insert(item: &HistoryItem) -> anyhow::Result<()> {
-    let conn = db::get_conn().lock().unwrap();
+    let conn = db::get_conn()?;
     conn.execute(
-        "INSERT INTO recordings (id, audio_path, transcript, processed_text, model_id, language, ai_function, duration_ms)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        "INSERT INTO recordings (id, audio_path, transcript, processed_text, rules_text, model_id, language, ai_function, duration_ms, engine, inference_ms, real_time_factor, threads)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
         rusqlite::params![
             item.id,
             item.audio_path,
             item.transcript,
             item.processed_text,
+            item.rules_text,
             item.model_id,
             item.language,
             item.ai_function,
             item.duration_ms,
+            item.metrics.as_ref().map(|m| m.engine.clone()),
+            item.metrics.as_ref().map(|m| m.inference_ms as i64),
+            item.metrics.as_ref().map(|m| m.real_time_factor),
+            item.metrics.as_ref().and_then(|m| m.threads),
         ],
     )?;
     Ok(())
 }
 
 pub fn list(limit: usize, offset: usize) -> anyhow::Result<Vec<HistoryItem>> {
-    let conn = db::get_conn().lock().unwrap();
-    let mut stmt = conn.prepare(
-        "SELECT id, audio_path, transcript, processed_text, model_id, language, ai_function, duration_ms, created_at
-         FROM recordings ORDER BY created_at DESC LIMIT ?1 OFFSET ?2",
-    )?;
-
-    let items = stmt
-        .query_map(rusqlite::params![limit, offset], |row| {
-            Ok(HistoryItem {
-                id: row.get(0)?,
-                audio_path: row.get(1)?,
-                transcript: row.get(2)?,
-                processed_text: row.get(3)?,
-                model_id: row.get(4)?,
-                language: row.get(5)?,
-                ai_function: row.get(6)?,
-                duration_ms: row.get(7)?,
-                created_at: row.get(8)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-
-    Ok(items)
+    list_filtered(&HistoryFilter::default(), limit, offset)
 }
 
 pub fn search(query: &str) -> anyhow::Result<Vec<HistoryItem>> {
-    let conn = db::get_conn().lock().unwrap();
-    let pattern = format!("%{}%", query);
-    let mut stmt = conn.prepare(
-        "SELECT id, audio_path, transcript, processed_text, model_id, language, ai_function, duration_ms, created_at
-         FROM recordings WHERE transcript LIKE ?1 OR processed_text LIKE ?1
-         ORDER BY created_at DESC LIMIT 100",
-    )?;
+    let filter = HistoryFilter {
+        query: Some(query.to_string()),
+        ..Default::default()
+    };
+    list_filtered(&filter, 100, 0)
+}
+
+/// List history newest-first, narrowed by `filter`.
+pub fn list_filtered(
+    filter: &HistoryFilter,
+    limit: usize,
+    offset: usize,
+) -> anyhow::Result<Vec<HistoryItem>> {
+    let mut clauses: Vec<&str> = Vec::new();
+    let mut params: Vec<rusqlite::types::Value> = Vec::new();
+
+    if let Some(query) = filter.query.as_deref().filter(|q| !q.is_empty()) {
+        clauses.push("(transcript LIKE ? OR processed_text LIKE ? OR rules_text LIKE ?)");
+        let pattern = format!("%{}%", query);
+        for _ in 0..3 {
+            params.push(pattern.clone().into());
+        }
+    }
+    if let Some(model_id) = &filter.model_id {
+        clauses.push("model_id = ?");
+        params.push(model_id.clone().into());
+    }
+    if let Some(language) = &filter.language {
+        clauses.push("language = ?");
+        params.push(language.clone().into());
+    }
+    if let Some(ai_function) = &filter.ai_function {
+        clauses.push("ai_function = ?");
+        params.push(ai_function.clone().into());
+    }
+    // created_at is filled by SQLite as "YYYY-MM-DD HH:MM:SS", so bring the
+    // bounds to that format and compare the bare column, which keeps
+    // idx_recordings_created usable.
+    if let Some(from) = &filter.from {
+        clauses.push("created_at >= datetime(?)");
+        params.push(from.clone().into());
+    }
+    if let Some(to) = &filter.to {
+        if to.len() == 10 {
+            clauses.push("created_at < datetime(?, '+1 day')");
+        } else {
+            clauses.push("created_at <= datetime(?)");
+        }
+        params.push(to.clone().into());
+    }
+
+    let where_sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", clauses.join(" AND "))
+    };
+    params.push((limit as i64).into());
+    params.push((offset as i64).into());
+
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM recordings {} ORDER BY created_at DESC LIMIT ? OFFSET ?",
+        COLUMNS, where_sql
+    ))?;
 
     let items = stmt
-        .query_map(rusqlite::params![pattern], |row| {
-            Ok(HistoryItem {
-                id: row.get(0)?,
-                audio_path: row.get(1)?,
-                transcript: row.get(2)?,
-                processed_text: row.get(3)?,
-                model_id: row.get(4)?,
-                language: row.get(5)?,
-                ai_function: row.get(6)?,
-                duration_ms: row.get(7)?,
-                created_at: row.get(8)?,
-            })
-        })?
+        .query_map(rusqlite::params_from_iter(params), map_row)?
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(items)
 }
 
+/// Delete a recording along with the corrections made to its transcript.
 pub fn delete(id: &str) -> anyhow::Result<()> {
-    let conn = db::get_conn().lock().unwrap();
-    conn.execute("DELETE FROM recordings WHERE id = ?1", rusqlite::params![id])?;
+    let mut conn = db::get_conn()?;
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM corrections WHERE recording_id = ?1", rusqlite::params![id])?;
+    tx.execute("DELETE FROM recordings WHERE id = ?1", rusqlite::params![id])?;
+    tx.commit()?;
     Ok(())
 }
 
-pub fn get(id: &str) -> anyhow::Result<Option<HistoryItem>> {
-    let conn = db::get_conn().lock().unwrap();
-    let mut stmt = conn.prepare(
-        "SELECT id, audio_path, transcript, processed_text, model_id, language, ai_function, duration_ms, created_at
-         FROM recordings WHERE id = ?1",
+/// Replace a recording's verbatim transcript after the user corrected it.
+pub fn update_transcript(id: &str, transcript: &str) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    let updated = conn.execute(
+        "UPDATE recordings SET transcript = ?1 WHERE id = ?2",
+        rusqlite::params![transcript, id],
     )?;
+    if updated == 0 {
+        anyhow::bail!("History item not found: {}", id);
+    }
+    Ok(())
+}
+
+/// Point `audio_path` rows at the new location of files that moved, given
+/// as (old path, new path). Rows whose file stayed put keep their path.
+/// Returns the number of rows updated.
+pub fn relocate_audio(moved: &[(PathBuf, PathBuf)]) -> anyhow::Result<usize> {
+    let moved: HashMap<&Path, &Path> = moved
+        .iter()
+        .map(|(from, to)| (from.as_path(), to.as_path()))
+        .collect();
+    let mut conn = db::get_conn()?;
+    let tx = conn.transaction()?;
+    let rows: Vec<(String, String)> = tx
+        .prepare("SELECT id, audio_path FROM recordings WHERE audio_path IS NOT NULL")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    let mut updated = 0;
+    for (id, path) in rows {
+        let Some(new_path) = moved.get(Path::new(&path)) else {
+            continue;
+        };
+        tx.execute(
+            "UPDATE recordings SET audio_path = ?1 WHERE id = ?2",
+            rusqlite::params![new_path.to_string_lossy(), id],
+        )?;
+        updated += 1;
+    }
+    tx.commit()?;
+    Ok(updated)
+}
+
+/// Whether a history row exists for the given id (session id).
+pub fn exists(id: &str) -> anyhow::Result<bool> {
+    let conn = db::get_conn()?;
+    let found = conn
+        .prepare("SELECT 1 FROM recordings WHERE id = ?1")?
+        .exists(rusqlite::params![id])?;
+    Ok(found)
+}
+
+pub fn get(id: &str) -> anyhow::Result<Option<HistoryItem>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM recordings WHERE id = ?1", COLUMNS))?;
 
     let mut items = stmt
-        .query_map(rusqlite::params![id], |row| {
-            Ok(HistoryItem {
-                id: row.get(0)?,
-                audio_path: row.get(1)?,
-                transcript: row.get(2)?,
-                processed_text: row.get(3)?,
-                model_id: row.get(4)?,
-                language: row.get(5)?,
-                ai_function: row.get(6)?,
-                duration_ms: row.get(7)?,
-                created_at: row.get(8)?,
-            })
-        })?
+        .query_map(rusqlite::params![id], map_row)?
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(items.pop())
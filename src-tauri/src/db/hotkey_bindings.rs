@@ -0,0 +1,86 @@
+use crate::db;
+use serde::{Deserialize, Serialize};
+
+/// A hotkey that dictates with its own preset. `None` fields fall back to
+/// the current model, language, AI function and pipeline settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyBinding {
+    pub id: String,
+    pub hotkey: String,
+    pub model_id: Option<String>,
+    pub language: Option<String>,
+    pub ai_function: Option<String>,
+    #[serde(default)]
+    pub pipeline_id: Option<String>,
+}
+
+const COLUMNS: &str = "id, hotkey, model_id, language, ai_function, pipeline_id";
+
+fn map_row(row: &rusqlite::Row) -> rusqlite::Result<HotkeyBinding> {
+    Ok(HotkeyBinding {
+        id: row.get(0)?,
+        hotkey: row.get(1)?,
+        model_id: row.get(2)?,
+        language: row.get(3)?,
+        ai_function: row.get(4)?,
+        pipeline_id: row.get(5)?,
+    })
+}
+
+pub fn list() -> anyhow::Result<Vec<HotkeyBinding>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM hotkey_bindings ORDER BY created_at ASC, id ASC",
+        COLUMNS
+    ))?;
+
+    let items = stmt
+        .query_map([], map_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+pub fn get(id: &str) -> anyhow::Result<Option<HotkeyBinding>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM hotkey_bindings WHERE id = ?1",
+        COLUMNS
+    ))?;
+
+    let mut items = stmt
+        .query_map(rusqlite::params![id], map_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items.pop())
+}
+
+/// Insert or update a binding, keeping its position when it already exists.
+pub fn upsert(item: &HotkeyBinding) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "INSERT INTO hotkey_bindings (id, hotkey, model_id, language, ai_function, pipeline_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(id) DO UPDATE SET hotkey = ?2, model_id = ?3, language = ?4,
+             ai_function = ?5, pipeline_id = ?6",
+        rusqlite::params![
+            item.id,
+            item.hotkey,
+            item.model_id,
+            item.language,
+            item.ai_function,
+            item.pipeline_id,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn delete(id: &str) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "DELETE FROM hotkey_bindings WHERE id = ?1",
+        rusqlite::params![id],
+    )?;
+    Ok(())
+}
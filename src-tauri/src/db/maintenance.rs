@@ -0,0 +1,129 @@
+//! Integrity check and space reclamation. Deleting history only frees pages
+//! inside the file; vacuuming gives them back to the filesystem.
+
+use crate::db;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::path::Path;
+
+/// `PRAGMA auto_vacuum` value for incremental mode.
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceReport {
+    pub integrity_ok: bool,
+    /// Problems reported by `integrity_check`; empty when it passed.
+    pub integrity_errors: Vec<String>,
+    /// Database plus WAL file size, before and after.
+    pub size_before: u64,
+    pub size_after: u64,
+    pub reclaimed_bytes: u64,
+}
+
+/// Check the live database, reclaim free pages and truncate the WAL.
+pub fn run() -> anyhow::Result<MaintenanceReport> {
+    let path = db::path().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let size_before = file_size(&path);
+
+    let conn = db::get_conn()?;
+    let integrity_errors = maintain(&conn)?;
+    drop(conn);
+
+    let size_after = file_size(&path);
+    log::info!(
+        "Database maintenance: {} integrity errors, {} -> {} bytes",
+        integrity_errors.len(),
+        size_before,
+        size_after
+    );
+    Ok(MaintenanceReport {
+        integrity_ok: integrity_errors.is_empty(),
+        integrity_errors,
+        size_before,
+        size_after,
+        reclaimed_bytes: size_before.saturating_sub(size_after),
+    })
+}
+
+/// Run the maintenance steps on `conn`, returning integrity problems. A
+/// database created before incremental auto-vacuum gets one full `VACUUM`
+/// to switch it over; later runs only release free pages.
+fn maintain(conn: &Connection) -> anyhow::Result<Vec<String>> {
+    let integrity: Vec<String> = conn
+        .prepare("PRAGMA integrity_check")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    let integrity_errors: Vec<String> = integrity.into_iter().filter(|r| r != "ok").collect();
+    if !integrity_errors.is_empty() {
+        // Rewriting a damaged file could lose more; leave it for a backup restore
+        log::warn!("Database integrity check failed: {:?}", integrity_errors);
+        return Ok(integrity_errors);
+    }
+
+    let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+    if auto_vacuum == AUTO_VACUUM_INCREMENTAL {
+        // Pages are released one step at a time
+        let mut stmt = conn.prepare("PRAGMA incremental_vacuum")?;
+        let mut rows = stmt.query([])?;
+        while rows.next()?.is_some() {}
+    } else {
+        conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")?;
+    }
+    // Returns (busy, log, checkpointed) rather than failing when readers are active
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    Ok(integrity_errors)
+}
+
+/// Size of the database file and its WAL, if any.
+fn file_size(path: &Path) -> u64 {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    [path.to_path_buf(), wal.into()]
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maintain_shrinks_file_after_deletes() {
+        let path =
+            std::env::temp_dir().join(format!("sobottaai-vacuum-{}.db", uuid::Uuid::new_v4()));
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch("PRAGMA journal_mode=WAL;").unwrap();
+        db::create_schema(&conn).unwrap();
+        let padding = "x".repeat(4000);
+        for i in 0..200 {
+            conn.execute(
+                "INSERT INTO recordings (id, transcript, model_id) VALUES (?1, ?2, 'm')",
+                rusqlite::params![format!("r{}", i), padding],
+            )
+            .unwrap();
+        }
+        conn.execute_batch("DELETE FROM recordings; PRAGMA wal_checkpoint(TRUNCATE);")
+            .unwrap();
+        let before = file_size(&path);
+
+        assert!(maintain(&conn).unwrap().is_empty());
+        assert!(file_size(&path) < before);
+        let auto_vacuum: i64 = conn
+            .query_row("PRAGMA auto_vacuum", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(auto_vacuum, AUTO_VACUUM_INCREMENTAL);
+
+        // Later runs take the incremental path
+        assert!(maintain(&conn).unwrap().is_empty());
+
+        drop(conn);
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = path.as_os_str().to_owned();
+            file.push(suffix);
+            let _ = std::fs::remove_file(std::path::PathBuf::from(file));
+        }
+    }
+}
@@ -1,25 +1,207 @@
+pub mod ai_cache;
 pub mod ai_functions;
+pub mod app_profiles;
+pub mod corrections;
+pub mod device_bindings;
+pub mod encryption;
 pub mod history;
+pub mod hotkey_bindings;
+pub mod maintenance;
+pub mod notes;
+pub mod pipelines;
+pub mod rules;
 pub mod settings;
 pub mod vocabulary;
+pub mod watch_folders;
 
 use once_cell::sync::OnceCell;
 use rusqlite::Connection;
-use std::path::Path;
-use std::sync::Mutex;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock, RwLockReadGuard};
+use std::time::Duration;
 
-static DB: OnceCell<Mutex<Connection>> = OnceCell::new();
+/// Idle connections kept open for reuse; more are opened under load.
+const MAX_IDLE: usize = 4;
+/// How long a write waits for another connection's write to finish.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+static POOL: OnceCell<Pool> = OnceCell::new();
+
+/// Connections to the database file, one per caller. With WAL, readers
+/// don't wait on each other or on the writer, so a long history export no
+/// longer blocks saving a dictation.
+struct Pool {
+    /// Write-locked by `set_encryption` while it swaps the file, which waits
+    /// for every checked-out connection to be returned.
+    source: RwLock<Source>,
+    idle: Mutex<Vec<Connection>>,
+}
+
+/// What new connections open.
+struct Source {
+    path: PathBuf,
+    key: Option<String>,
+}
+
+/// A connection checked out of the pool, returned to it on drop.
+pub struct PooledConnection {
+    conn: Option<Connection>,
+    _source: RwLockReadGuard<'static, Source>,
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection taken")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn.as_mut().expect("connection taken")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let (Some(conn), Some(pool)) = (self.conn.take(), POOL.get()) {
+            let mut idle = pool.idle.lock().unwrap();
+            if idle.len() < MAX_IDLE {
+                idle.push(conn);
+            }
+        }
+    }
+}
 
 pub fn initialize(db_path: &Path) -> anyhow::Result<()> {
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
+    let key = if encryption::is_encrypted_file(db_path) {
+        let key = encryption::load_key()?.ok_or_else(|| {
+            anyhow::anyhow!("Database is encrypted but no key was found in the OS keychain")
+        })?;
+        Some(key)
+    } else {
+        None
+    };
+    let conn = open(db_path, key.as_deref())?;
+
+    let pool = Pool {
+        source: RwLock::new(Source {
+            path: db_path.to_path_buf(),
+            key,
+        }),
+        idle: Mutex::new(vec![conn]),
+    };
+    POOL.set(pool)
+        .map_err(|_| anyhow::anyhow!("Database already initialized"))?;
+
+    Ok(())
+}
+
+/// Open (and unlock, if `key` is given) the database file and bring its
+/// schema up to date.
+fn open(db_path: &Path, key: Option<&str>) -> anyhow::Result<Connection> {
+    let conn = connect(db_path, key)?;
+    // Only takes effect on a new file; maintenance converts older ones
+    conn.execute_batch("PRAGMA auto_vacuum=INCREMENTAL; PRAGMA journal_mode=WAL;")?;
+    create_schema(&conn)?;
+    Ok(conn)
+}
+
+/// Open another connection to an already set up database.
+fn connect(db_path: &Path, key: Option<&str>) -> anyhow::Result<Connection> {
     let conn = Connection::open(db_path)?;
+    if let Some(key) = key {
+        encryption::apply_key(&conn, key)?;
+    }
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    // Per-connection, unlike journal_mode
+    conn.execute_batch("PRAGMA foreign_keys=ON;")?;
+    Ok(conn)
+}
+
+/// Location of the database file, once initialized.
+pub fn path() -> Option<PathBuf> {
+    POOL.get().map(|pool| pool.source.read().unwrap().path.clone())
+}
+
+/// Whether the on-disk database is encrypted.
+pub fn is_encrypted() -> bool {
+    POOL.get()
+        .map(|pool| encryption::is_encrypted_file(&pool.source.read().unwrap().path))
+        .unwrap_or(false)
+}
+
+/// Encrypt or decrypt the live database in place. The data is exported to a
+/// temporary file, which then replaces the original, once every pooled
+/// connection has been returned and closed.
+pub fn set_encryption(enabled: bool) -> anyhow::Result<()> {
+    let pool = POOL
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let mut source = pool.source.write().unwrap();
+    let path = source.path.clone();
+
+    if encryption::is_encrypted_file(&path) == enabled {
+        return Ok(());
+    }
+
+    let key = if enabled {
+        Some(encryption::load_or_create_key()?)
+    } else {
+        None
+    };
 
+    let tmp_path = path.with_extension("db.tmp");
+    let _ = std::fs::remove_file(&tmp_path);
+    let conn = connect(&path, source.key.as_deref())?;
+    encryption::export_to(&conn, &tmp_path, key.as_deref())?;
+
+    // Close every connection before swapping files underneath them
+    drop(conn);
+    pool.idle.lock().unwrap().clear();
+
+    let swap = (|| -> anyhow::Result<()> {
+        for suffix in ["-wal", "-shm"] {
+            let mut sidecar = path.as_os_str().to_owned();
+            sidecar.push(suffix);
+            let _ = std::fs::remove_file(PathBuf::from(sidecar));
+        }
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(())
+    })();
+
+    match swap {
+        Ok(()) => {
+            let conn = open(&path, key.as_deref())?;
+            pool.idle.lock().unwrap().push(conn);
+            source.key = key;
+            if !enabled {
+                if let Err(e) = encryption::delete_key() {
+                    log::warn!("Failed to remove database key from keychain: {}", e);
+                }
+            }
+            log::info!("Database encryption {}", if enabled { "enabled" } else { "disabled" });
+            Ok(())
+        }
+        Err(e) => {
+            // The original is untouched; new connections reopen it as before
+            let _ = std::fs::remove_file(&tmp_path);
+            Err(e)
+        }
+    }
+}
+
+/// Create all tables and apply additive column migrations. Idempotent, so it
+/// runs on every startup against both fresh and existing databases.
+pub(crate) fn create_schema(conn: &Connection) -> anyhow::Result<()> {
     conn.execute_batch(
         "
-        PRAGMA journal_mode=WAL;
         PRAGMA foreign_keys=ON;
 
         CREATE TABLE IF NOT EXISTS recordings (
@@ -41,6 +223,14 @@ pub fn initialize(db_path: &Path) -> anyhow::Result<()> {
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP
         );
 
+        CREATE TABLE IF NOT EXISTS corrections (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            recording_id TEXT,
+            original TEXT NOT NULL,
+            corrected TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
         CREATE TABLE IF NOT EXISTS ai_functions (
             id TEXT PRIMARY KEY,
             name TEXT NOT NULL,
@@ -51,6 +241,25 @@ pub fn initialize(db_path: &Path) -> anyhow::Result<()> {
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP
         );
 
+        CREATE TABLE IF NOT EXISTS ai_function_usage (
+            function_id TEXT PRIMARY KEY,
+            use_count INTEGER NOT NULL DEFAULT 0,
+            last_used_at DATETIME
+        );
+
+        CREATE TABLE IF NOT EXISTS ai_function_output (
+            function_id TEXT PRIMARY KEY,
+            action TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS ai_cache (
+            key TEXT PRIMARY KEY,
+            function_id TEXT NOT NULL,
+            model TEXT NOT NULL,
+            output TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
         CREATE TABLE IF NOT EXISTS rules (
             id TEXT PRIMARY KEY,
             name TEXT NOT NULL,
@@ -62,18 +271,140 @@ pub fn initialize(db_path: &Path) -> anyhow::Result<()> {
             sort_order INTEGER DEFAULT 0
         );
 
+        CREATE TABLE IF NOT EXISTS hotkey_bindings (
+            id TEXT PRIMARY KEY,
+            hotkey TEXT NOT NULL UNIQUE,
+            model_id TEXT,
+            language TEXT,
+            ai_function TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS pipelines (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            steps TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS watch_folders (
+            id TEXT PRIMARY KEY,
+            path TEXT NOT NULL,
+            model_id TEXT,
+            write_txt BOOLEAN NOT NULL DEFAULT FALSE,
+            write_srt BOOLEAN NOT NULL DEFAULT FALSE,
+            enabled BOOLEAN NOT NULL DEFAULT TRUE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS device_bindings (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            vendor_id INTEGER NOT NULL,
+            product_id INTEGER NOT NULL,
+            button INTEGER NOT NULL,
+            action TEXT NOT NULL,
+            enabled BOOLEAN NOT NULL DEFAULT TRUE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS app_profiles (
+            id TEXT PRIMARY KEY,
+            app_name TEXT NOT NULL,
+            newline_mode TEXT NOT NULL DEFAULT 'keep',
+            strip_trailing_newline BOOLEAN NOT NULL DEFAULT FALSE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS notes (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS note_recordings (
+            note_id TEXT NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            recording_id TEXT NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+            position INTEGER NOT NULL,
+            PRIMARY KEY (note_id, recording_id)
+        );
+
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+
         CREATE INDEX IF NOT EXISTS idx_recordings_created ON recordings(created_at DESC);
+        CREATE INDEX IF NOT EXISTS idx_recordings_model ON recordings(model_id, created_at DESC);
+        CREATE INDEX IF NOT EXISTS idx_recordings_language ON recordings(language, created_at DESC);
+        CREATE INDEX IF NOT EXISTS idx_recordings_ai_function ON recordings(ai_function, created_at DESC);
         ",
     )?;
 
-    DB.set(Mutex::new(conn))
-        .map_err(|_| anyhow::anyhow!("Database already initialized"))?;
+    add_column_if_missing(conn, "recordings", "rules_text", "TEXT")?;
+    add_column_if_missing(conn, "recordings", "engine", "TEXT")?;
+    add_column_if_missing(conn, "recordings", "inference_ms", "INTEGER")?;
+    add_column_if_missing(conn, "recordings", "real_time_factor", "REAL")?;
+    add_column_if_missing(conn, "recordings", "threads", "INTEGER")?;
+    add_column_if_missing(conn, "vocabulary", "category", "TEXT")?;
+    add_column_if_missing(conn, "vocabulary", "language", "TEXT")?;
+    add_column_if_missing(conn, "vocabulary", "use_count", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "vocabulary", "last_used_at", "DATETIME")?;
+    add_column_if_missing(conn, "hotkey_bindings", "pipeline_id", "TEXT")?;
+    add_column_if_missing(conn, "rules", "flags", "TEXT")?;
 
     Ok(())
 }
 
-pub fn get_conn() -> &'static Mutex<Connection> {
-    DB.get().expect("Database not initialized")
+/// `ALTER TABLE ... ADD COLUMN` for databases created before the column existed.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    decl: &str,
+) -> anyhow::Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {};",
+            table, column, decl
+        ))?;
+    }
+    Ok(())
+}
+
+/// Check out a connection, opening a new one if none are idle.
+pub fn get_conn() -> anyhow::Result<PooledConnection> {
+    let pool = POOL
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+    let source = pool.source.read().unwrap();
+    let idle = pool.idle.lock().unwrap().pop();
+    let conn = match idle {
+        Some(conn) => conn,
+        None => connect(&source.path, source.key.as_deref())?,
+    };
+    Ok(PooledConnection {
+        conn: Some(conn),
+        _source: source,
+    })
+}
+
+/// Run blocking database work on Tokio's blocking pool, so async commands
+/// don't stall the runtime while a query runs.
+pub async fn run_blocking<T, F>(f: F) -> anyhow::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await?
 }
 
 #[cfg(test)]
@@ -83,63 +414,37 @@ pub mod tests {
 
     static INIT: Once = Once::new();
 
-    /// Initialize the test database (in-memory). Safe to call from multiple tests.
+    /// Initialize the test database, a fresh file per test run so pooled
+    /// connections share it. Safe to call from multiple tests.
     pub fn init_test_db() {
         INIT.call_once(|| {
-            let conn = Connection::open_in_memory().unwrap();
-            conn.execute_batch(
-                "
-                PRAGMA foreign_keys=ON;
-
-                CREATE TABLE IF NOT EXISTS recordings (
-                    id TEXT PRIMARY KEY,
-                    audio_path TEXT,
-                    transcript TEXT NOT NULL,
-                    processed_text TEXT,
-                    model_id TEXT NOT NULL,
-                    language TEXT,
-                    ai_function TEXT,
-                    duration_ms INTEGER,
-                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-                );
-
-                CREATE TABLE IF NOT EXISTS vocabulary (
-                    id TEXT PRIMARY KEY,
-                    term TEXT NOT NULL UNIQUE,
-                    replacement TEXT,
-                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-                );
-
-                CREATE TABLE IF NOT EXISTS ai_functions (
-                    id TEXT PRIMARY KEY,
-                    name TEXT NOT NULL,
-                    prompt TEXT NOT NULL,
-                    provider TEXT NOT NULL,
-                    model TEXT,
-                    is_builtin BOOLEAN DEFAULT FALSE,
-                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-                );
-
-                CREATE TABLE IF NOT EXISTS rules (
-                    id TEXT PRIMARY KEY,
-                    name TEXT NOT NULL,
-                    type TEXT NOT NULL,
-                    prompt TEXT,
-                    pattern TEXT,
-                    replacement TEXT,
-                    enabled BOOLEAN DEFAULT TRUE,
-                    sort_order INTEGER DEFAULT 0
-                );
-
-                CREATE INDEX IF NOT EXISTS idx_recordings_created ON recordings(created_at DESC);
-                ",
-            )
-            .unwrap();
-            DB.set(Mutex::new(conn))
-                .expect("Failed to set test DB");
+            let path = std::env::temp_dir()
+                .join(format!("sobottaai-test-{}.db", std::process::id()));
+            for suffix in ["", "-wal", "-shm"] {
+                let mut file = path.as_os_str().to_owned();
+                file.push(suffix);
+                let _ = std::fs::remove_file(PathBuf::from(file));
+            }
+            initialize(&path).expect("Failed to set test DB");
         });
     }
 
+    #[test]
+    fn readers_do_not_block_writers() {
+        init_test_db();
+        // An open read transaction, like a long export
+        let reader = get_conn().unwrap();
+        reader.execute_batch("BEGIN;").unwrap();
+        let _: i64 = reader
+            .query_row("SELECT count(*) FROM recordings", [], |row| row.get(0))
+            .unwrap();
+
+        vocabulary::add("vocab-concurrent", "Concurrent", None, None, None).unwrap();
+        assert!(vocabulary::get_terms(None).unwrap().contains(&"Concurrent".to_string()));
+
+        reader.execute_batch("ROLLBACK;").unwrap();
+    }
+
     // ── History CRUD ─────────────────────────────────────────
 
     #[test]
@@ -150,10 +455,12 @@ pub mod tests {
             audio_path: Some("/tmp/test.wav".into()),
             transcript: "Hello world".into(),
             processed_text: Some("Hello, world.".into()),
+            rules_text: None,
             model_id: "whisper-base".into(),
             language: Some("en".into()),
             ai_function: None,
             duration_ms: Some(5000),
+            metrics: None,
             created_at: String::new(), // DB fills this
         };
         history::insert(&item).unwrap();
@@ -167,6 +474,31 @@ pub mod tests {
         assert_eq!(r.model_id, "whisper-base");
         assert_eq!(r.language, Some("en".into()));
         assert_eq!(r.duration_ms, Some(5000));
+        assert_eq!(r.metrics, None);
+    }
+
+    #[test]
+    fn history_stores_inference_metrics() {
+        init_test_db();
+        let metrics = crate::stt::InferenceMetrics::new("whisper", Some(8), 1500, 16000 * 6);
+        assert_eq!(metrics.real_time_factor, 0.25);
+        let item = history::HistoryItem {
+            id: "hist-metrics".into(),
+            audio_path: None,
+            transcript: "Fast enough".into(),
+            processed_text: None,
+            rules_text: None,
+            model_id: "whisper-base".into(),
+            language: None,
+            ai_function: None,
+            duration_ms: Some(6000),
+            metrics: Some(metrics.clone()),
+            created_at: String::new(),
+        };
+        history::insert(&item).unwrap();
+
+        let r = history::get("hist-metrics").unwrap().unwrap();
+        assert_eq!(r.metrics, Some(metrics));
     }
 
     #[test]
@@ -177,10 +509,12 @@ pub mod tests {
             audio_path: None,
             transcript: "Test transcript for listing".into(),
             processed_text: None,
+            rules_text: None,
             model_id: "whisper-tiny".into(),
             language: None,
             ai_function: None,
             duration_ms: None,
+            metrics: None,
             created_at: String::new(),
         };
         history::insert(&item).unwrap();
@@ -198,10 +532,12 @@ pub mod tests {
             audio_path: None,
             transcript: "unique_search_term_xyz123".into(),
             processed_text: None,
+            rules_text: None,
             model_id: "whisper-base".into(),
             language: None,
             ai_function: None,
             duration_ms: None,
+            metrics: None,
             created_at: String::new(),
         };
         history::insert(&item).unwrap();
@@ -226,10 +562,12 @@ pub mod tests {
             audio_path: None,
             transcript: "To be deleted".into(),
             processed_text: None,
+            rules_text: None,
             model_id: "whisper-base".into(),
             language: None,
             ai_function: None,
             duration_ms: None,
+            metrics: None,
             created_at: String::new(),
         };
         history::insert(&item).unwrap();
@@ -246,6 +584,73 @@ pub mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn history_exists() {
+        init_test_db();
+        let item = history::HistoryItem {
+            id: "hist-exists".into(),
+            audio_path: None,
+            transcript: "Present".into(),
+            processed_text: None,
+            rules_text: None,
+            model_id: "whisper-base".into(),
+            language: None,
+            ai_function: None,
+            duration_ms: None,
+            metrics: None,
+            created_at: String::new(),
+        };
+        history::insert(&item).unwrap();
+        assert!(history::exists("hist-exists").unwrap());
+        assert!(!history::exists("hist-missing-12345").unwrap());
+    }
+
+    #[test]
+    fn history_relocate_audio() {
+        init_test_db();
+        for (id, path) in [
+            ("hist-reloc-1", Some("/reloc-old/audio/one.wav")),
+            ("hist-reloc-2", Some("/elsewhere/two.wav")),
+            ("hist-reloc-3", None),
+            ("hist-reloc-4", Some("/reloc-old/audio/four.wav")),
+        ] {
+            let item = history::HistoryItem {
+                id: id.into(),
+                audio_path: path.map(String::from),
+                transcript: "Relocated".into(),
+                processed_text: None,
+                rules_text: None,
+                model_id: "whisper-base".into(),
+                language: None,
+                ai_function: None,
+                duration_ms: None,
+                metrics: None,
+                created_at: String::new(),
+            };
+            history::insert(&item).unwrap();
+        }
+
+        let moved = [
+            (
+                PathBuf::from("/reloc-old/audio/one.wav"),
+                PathBuf::from("/reloc-new/audio/one.wav"),
+            ),
+            (
+                PathBuf::from("/reloc-old/audio/gone.wav"),
+                PathBuf::from("/reloc-new/audio/gone.wav"),
+            ),
+        ];
+        assert_eq!(history::relocate_audio(&moved).unwrap(), 1);
+
+        let relocated = history::get("hist-reloc-1").unwrap().unwrap();
+        assert_eq!(relocated.audio_path.as_deref(), Some("/reloc-new/audio/one.wav"));
+        let untouched = history::get("hist-reloc-2").unwrap().unwrap();
+        assert_eq!(untouched.audio_path.as_deref(), Some("/elsewhere/two.wav"));
+        // A file left in the old folder keeps its old path
+        let skipped = history::get("hist-reloc-4").unwrap().unwrap();
+        assert_eq!(skipped.audio_path.as_deref(), Some("/reloc-old/audio/four.wav"));
+    }
+
     #[test]
     fn history_list_pagination() {
         init_test_db();
@@ -255,10 +660,12 @@ pub mod tests {
                 audio_path: None,
                 transcript: format!("Paginated item {}", i),
                 processed_text: None,
+                rules_text: None,
                 model_id: "whisper-base".into(),
                 language: None,
                 ai_function: None,
                 duration_ms: None,
+                metrics: None,
                 created_at: String::new(),
             };
             history::insert(&item).unwrap();
@@ -280,10 +687,12 @@ pub mod tests {
             audio_path: None,
             transcript: "original text".into(),
             processed_text: Some("unique_processed_zyx789".into()),
+            rules_text: None,
             model_id: "whisper-base".into(),
             language: None,
             ai_function: None,
             duration_ms: None,
+            metrics: None,
             created_at: String::new(),
         };
         history::insert(&item).unwrap();
@@ -292,12 +701,224 @@ pub mod tests {
         assert!(!results.is_empty());
     }
 
+    #[test]
+    fn history_keeps_verbatim_rules_and_processed_text() {
+        init_test_db();
+        let item = history::HistoryItem {
+            id: "hist-dual-1".into(),
+            audio_path: None,
+            transcript: "um so the the meeting is at three".into(),
+            processed_text: Some("The meeting is at 3 PM.".into()),
+            rules_text: Some("so the meeting is at three".into()),
+            model_id: "whisper-base".into(),
+            language: Some("en".into()),
+            ai_function: Some("email".into()),
+            duration_ms: Some(2000),
+            metrics: None,
+            created_at: String::new(),
+        };
+        history::insert(&item).unwrap();
+
+        let r = history::get("hist-dual-1").unwrap().unwrap();
+        assert_eq!(r.transcript, "um so the the meeting is at three");
+        assert_eq!(r.rules_text, Some("so the meeting is at three".into()));
+        assert_eq!(r.processed_text, Some("The meeting is at 3 PM.".into()));
+    }
+
+    #[test]
+    fn history_filter_by_model_language_and_function() {
+        init_test_db();
+        for (id, lang, func) in [
+            ("hist-filter-1", "en", Some("email")),
+            ("hist-filter-2", "de", Some("email")),
+            ("hist-filter-3", "en", None),
+        ] {
+            history::insert(&history::HistoryItem {
+                id: id.into(),
+                audio_path: None,
+                transcript: format!("filter test {}", id),
+                processed_text: None,
+                rules_text: None,
+                model_id: "filter-model".into(),
+                language: Some(lang.into()),
+                ai_function: func.map(String::from),
+                duration_ms: None,
+                metrics: None,
+                created_at: String::new(),
+            })
+            .unwrap();
+        }
+
+        let by_model = history::HistoryFilter {
+            model_id: Some("filter-model".into()),
+            ..Default::default()
+        };
+        assert_eq!(history::list_filtered(&by_model, 100, 0).unwrap().len(), 3);
+
+        let en_email = history::HistoryFilter {
+            model_id: Some("filter-model".into()),
+            language: Some("en".into()),
+            ai_function: Some("email".into()),
+            ..Default::default()
+        };
+        let results = history::list_filtered(&en_email, 100, 0).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "hist-filter-1");
+
+        let with_query = history::HistoryFilter {
+            query: Some("hist-filter-2".into()),
+            model_id: Some("filter-model".into()),
+            ..Default::default()
+        };
+        assert_eq!(history::list_filtered(&with_query, 100, 0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn history_filter_by_date_range() {
+        init_test_db();
+        {
+            let conn = get_conn().unwrap();
+            conn.execute(
+                "INSERT INTO recordings (id, transcript, model_id, created_at) VALUES
+                 ('hist-date-1', 'a', 'date-model', '2024-01-15 10:00:00'),
+                 ('hist-date-2', 'b', 'date-model', '2024-02-20 08:30:00'),
+                 ('hist-date-3', 'c', 'date-model', '2024-03-01 00:00:00')",
+                [],
+            )
+            .unwrap();
+        }
+
+        let feb = history::HistoryFilter {
+            model_id: Some("date-model".into()),
+            from: Some("2024-02-01".into()),
+            to: Some("2024-02-29".into()),
+            ..Default::default()
+        };
+        let results = history::list_filtered(&feb, 100, 0).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "hist-date-2");
+
+        // Date-only `to` includes the whole day
+        let through_mar_1 = history::HistoryFilter {
+            model_id: Some("date-model".into()),
+            from: Some("2024-02-01".into()),
+            to: Some("2024-03-01".into()),
+            ..Default::default()
+        };
+        assert_eq!(history::list_filtered(&through_mar_1, 100, 0).unwrap().len(), 2);
+
+        // RFC 3339 bounds are normalized to the stored format
+        let from_rfc3339 = history::HistoryFilter {
+            model_id: Some("date-model".into()),
+            from: Some("2024-02-20T08:30:00+00:00".into()),
+            ..Default::default()
+        };
+        assert_eq!(history::list_filtered(&from_rfc3339, 100, 0).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn history_date_filter_uses_created_index() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        let sql = "EXPLAIN QUERY PLAN SELECT id FROM recordings WHERE created_at >= datetime(?)";
+        let plan: Vec<String> = conn
+            .prepare(sql)
+            .unwrap()
+            .query_map(["2024-02-01"], |row| row.get(3))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(plan.iter().any(|step| step.contains("idx_recordings_created")));
+    }
+
+    // ── Encryption ───────────────────────────────────────────
+
+    #[test]
+    fn encryption_export_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("sobottaai-enc-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let plain = dir.join("plain.db");
+        let enc = dir.join("enc.db");
+        let back = dir.join("back.db");
+
+        let conn = Connection::open(&plain).unwrap();
+        create_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO vocabulary (id, term) VALUES ('v', 'secret-term')",
+            [],
+        )
+        .unwrap();
+        assert!(!encryption::is_encrypted_file(&plain));
+
+        let key = encryption::generate_key().unwrap();
+        assert_eq!(key.len(), 64);
+        encryption::export_to(&conn, &enc, Some(&key)).unwrap();
+        assert!(encryption::is_encrypted_file(&enc));
+
+        // Correct key reads the data back
+        let enc_conn = Connection::open(&enc).unwrap();
+        encryption::apply_key(&enc_conn, &key).unwrap();
+        let term: String = enc_conn
+            .query_row("SELECT term FROM vocabulary WHERE id = 'v'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(term, "secret-term");
+
+        // Decrypting produces a plaintext file again
+        encryption::export_to(&enc_conn, &back, None).unwrap();
+        assert!(!encryption::is_encrypted_file(&back));
+
+        // Wrong key is rejected
+        let wrong = Connection::open(&enc).unwrap();
+        let other_key = encryption::generate_key().unwrap();
+        assert!(encryption::apply_key(&wrong, &other_key).is_err());
+
+        drop((conn, enc_conn, wrong));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn encryption_missing_file_is_not_encrypted() {
+        assert!(!encryption::is_encrypted_file(Path::new(
+            "/nonexistent/sobottaai-missing.db"
+        )));
+    }
+
+    #[test]
+    fn schema_migration_adds_missing_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE recordings (
+                id TEXT PRIMARY KEY,
+                audio_path TEXT,
+                transcript TEXT NOT NULL,
+                processed_text TEXT,
+                model_id TEXT NOT NULL,
+                language TEXT,
+                ai_function TEXT,
+                duration_ms INTEGER,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );",
+        )
+        .unwrap();
+
+        create_schema(&conn).unwrap();
+        // Second run must be a no-op
+        create_schema(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO recordings (id, transcript, model_id, rules_text, engine, inference_ms, real_time_factor, threads)
+             VALUES ('m', 't', 'x', 'r', 'whisper', 100, 0.5, 4)",
+            [],
+        )
+        .unwrap();
+    }
+
     // ── Vocabulary CRUD ──────────────────────────────────────
 
     #[test]
     fn vocabulary_add_and_list() {
         init_test_db();
-        vocabulary::add("vocab-1", "SobottaAI", None).unwrap();
+        vocabulary::add("vocab-1", "SobottaAI", None, None, None).unwrap();
 
         let terms = vocabulary::list().unwrap();
         assert!(terms.iter().any(|t| t.term == "SobottaAI"));
@@ -306,18 +927,21 @@ pub mod tests {
     #[test]
     fn vocabulary_add_with_replacement() {
         init_test_db();
-        vocabulary::add("vocab-2", "gpt4", Some("GPT-4")).unwrap();
+        vocabulary::add("vocab-2", "gpt4", Some("GPT-4"), None, None).unwrap();
 
         let terms = vocabulary::list().unwrap();
         let found = terms.iter().find(|t| t.term == "gpt4");
         assert!(found.is_some());
         assert_eq!(found.unwrap().replacement, Some("GPT-4".into()));
+        assert!(vocabulary::replacements()
+            .unwrap()
+            .contains(&("gpt4".to_string(), "GPT-4".to_string())));
     }
 
     #[test]
     fn vocabulary_delete() {
         init_test_db();
-        vocabulary::add("vocab-del-1", "DeleteMe", None).unwrap();
+        vocabulary::add("vocab-del-1", "DeleteMe", None, None, None).unwrap();
         vocabulary::delete("vocab-del-1").unwrap();
 
         let terms = vocabulary::list().unwrap();
@@ -327,17 +951,62 @@ pub mod tests {
     #[test]
     fn vocabulary_get_terms_returns_strings() {
         init_test_db();
-        vocabulary::add("vocab-terms-1", "MyTerm", None).unwrap();
+        vocabulary::add("vocab-terms-1", "MyTerm", None, None, None).unwrap();
 
-        let terms = vocabulary::get_terms().unwrap();
+        let terms = vocabulary::get_terms(None).unwrap();
         assert!(terms.contains(&"MyTerm".to_string()));
     }
 
+    #[test]
+    fn vocabulary_get_terms_scoped_by_language() {
+        init_test_db();
+        vocabulary::add("vocab-lang-de", "Herzinsuffizienz", None, Some("Medical"), Some("de"))
+            .unwrap();
+        vocabulary::add("vocab-lang-any", "Kubernetes", None, None, None).unwrap();
+
+        let english = vocabulary::get_terms(Some("en")).unwrap();
+        assert!(!english.contains(&"Herzinsuffizienz".to_string()));
+        assert!(english.contains(&"Kubernetes".to_string()));
+
+        let german = vocabulary::get_terms(Some("de")).unwrap();
+        assert!(german.contains(&"Herzinsuffizienz".to_string()));
+        assert!(german.contains(&"Kubernetes".to_string()));
+
+        let auto = vocabulary::get_terms(Some("auto")).unwrap();
+        assert!(auto.contains(&"Herzinsuffizienz".to_string()));
+
+        let found = vocabulary::list()
+            .unwrap()
+            .into_iter()
+            .find(|t| t.id == "vocab-lang-de")
+            .unwrap();
+        assert_eq!(found.category.as_deref(), Some("Medical"));
+        assert_eq!(found.language.as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn vocabulary_get_terms_ranks_scoped_and_used_terms_first() {
+        init_test_db();
+        vocabulary::add("vocab-rank-fi", "Sauna", None, None, Some("fi")).unwrap();
+        vocabulary::add("vocab-rank-used", "Tokio", None, None, None).unwrap();
+        vocabulary::add("vocab-rank-unused", "Axum", None, None, None).unwrap();
+        vocabulary::record_usage(
+            &["Tokio".to_string(), "Axum".to_string()],
+            "we moved the service to tokio",
+        )
+        .unwrap();
+
+        let terms = vocabulary::get_terms(Some("fi")).unwrap();
+        let position = |term: &str| terms.iter().position(|t| t == term).unwrap();
+        assert_eq!(position("Sauna"), 0);
+        assert!(position("Tokio") < position("Axum"));
+    }
+
     #[test]
     fn vocabulary_upsert_replaces_existing() {
         init_test_db();
-        vocabulary::add("vocab-upsert", "original", None).unwrap();
-        vocabulary::add("vocab-upsert", "updated", Some("Updated Term")).unwrap();
+        vocabulary::add("vocab-upsert", "original", None, None, None).unwrap();
+        vocabulary::add("vocab-upsert", "updated", Some("Updated Term"), None, None).unwrap();
 
         let terms = vocabulary::list().unwrap();
         let found = terms.iter().find(|t| t.id == "vocab-upsert");
@@ -345,6 +1014,467 @@ pub mod tests {
         assert_eq!(found.unwrap().term, "updated");
     }
 
+    // ── Corrections ──────────────────────────────────────────
+
+    #[test]
+    fn corrections_suggest_recurring_fixes() {
+        init_test_db();
+        let fix = vec![("tow ree".to_string(), "Tauri".to_string())];
+        corrections::record("rec-fix-1", &fix).unwrap();
+        let once = corrections::suggest(2).unwrap();
+        assert!(!once.iter().any(|s| s.term == "Tauri"));
+
+        corrections::record("rec-fix-2", &fix).unwrap();
+        let twice = corrections::suggest(2).unwrap();
+        let found = twice.iter().find(|s| s.term == "Tauri").unwrap();
+        assert_eq!(found.original, "tow ree");
+        assert!(found.count >= 2);
+
+        // Already in the vocabulary, so no longer suggested
+        vocabulary::add("vocab-tauri", "tauri", None, None, None).unwrap();
+        assert!(!corrections::suggest(2)
+            .unwrap()
+            .iter()
+            .any(|s| s.term == "Tauri"));
+    }
+
+    #[test]
+    fn corrections_covered_by_a_replacement_are_not_suggested() {
+        init_test_db();
+        let fix = vec![("cube cuddle".to_string(), "kubectl".to_string())];
+        corrections::record("rec-kube-1", &fix).unwrap();
+        corrections::record("rec-kube-2", &fix).unwrap();
+        assert!(corrections::suggest(2).unwrap().iter().any(|s| s.term == "kubectl"));
+
+        vocabulary::add("vocab-kube", "cube cuddle", Some("kubectl"), None, None).unwrap();
+        assert!(!corrections::suggest(2).unwrap().iter().any(|s| s.term == "kubectl"));
+    }
+
+    #[test]
+    fn deleting_a_recording_deletes_its_corrections() {
+        init_test_db();
+        let fix = vec![("sobota".to_string(), "Sobotta".to_string())];
+        corrections::record("rec-gone-1", &fix).unwrap();
+        corrections::record("rec-gone-2", &fix).unwrap();
+        assert!(corrections::suggest(2).unwrap().iter().any(|s| s.term == "Sobotta"));
+
+        history::delete("rec-gone-1").unwrap();
+        assert!(!corrections::suggest(2).unwrap().iter().any(|s| s.term == "Sobotta"));
+    }
+
+    // ── AI response cache ────────────────────────────────────
+
+    #[test]
+    fn ai_cache_returns_fresh_outputs_only() {
+        init_test_db();
+        let key = ai_cache::key("summarize", "gpt-4o-mini", "Summarize.", "cache test input");
+        assert_ne!(
+            key,
+            ai_cache::key("summarize", "gpt-4o-mini", "Summarize.", "other input")
+        );
+        assert_eq!(ai_cache::get(&key, 3600).unwrap(), None);
+
+        ai_cache::put(&key, "summarize", "gpt-4o-mini", "Short.", 3600).unwrap();
+        assert_eq!(ai_cache::get(&key, 3600).unwrap().as_deref(), Some("Short."));
+        // Stored this second, so older than a zero TTL allows
+        assert_eq!(ai_cache::get(&key, 0).unwrap(), None);
+
+        assert!(ai_cache::clear().unwrap() >= 1);
+        assert_eq!(ai_cache::get(&key, 3600).unwrap(), None);
+    }
+
+    // ── AI function usage ────────────────────────────────────
+
+    #[test]
+    fn ai_function_usage_counts_runs() {
+        init_test_db();
+        ai_functions::record_use("usage-test-fn").unwrap();
+        ai_functions::record_use("usage-test-fn").unwrap();
+
+        let usage = ai_functions::usage().unwrap();
+        let used = usage.get("usage-test-fn").unwrap();
+        assert_eq!(used.count, 2);
+        assert!(used.last_used_at.is_some());
+        assert!(!usage.contains_key("never-run-fn"));
+        assert!(ai_functions::last_used().unwrap().is_some());
+    }
+
+    #[test]
+    fn ai_function_output_action_follows_custom_function() {
+        init_test_db();
+        ai_functions::insert(&ai_functions::AiFunctionRow {
+            id: "output-fn".into(),
+            name: "Output".into(),
+            prompt: "p".into(),
+            provider: "default".into(),
+            model: None,
+            is_builtin: false,
+        })
+        .unwrap();
+        ai_functions::set_output_action("output-fn", r#"{"type":"copy"}"#).unwrap();
+        ai_functions::set_output_action("output-fn", r#"{"type":"preview"}"#).unwrap();
+        ai_functions::set_output_action("output-builtin-fn", r#"{"type":"copy"}"#).unwrap();
+        let actions = ai_functions::output_actions().unwrap();
+        assert_eq!(actions["output-fn"], r#"{"type":"preview"}"#);
+
+        ai_functions::delete("output-fn").unwrap();
+        // Built-ins have no row to delete, so their action stays
+        ai_functions::delete("output-builtin-fn").unwrap();
+        let actions = ai_functions::output_actions().unwrap();
+        assert!(!actions.contains_key("output-fn"));
+        assert_eq!(actions["output-builtin-fn"], r#"{"type":"copy"}"#);
+    }
+
+    // ── Hotkey bindings ──────────────────────────────────────
+
+    #[test]
+    fn hotkey_binding_upsert_get_and_delete() {
+        init_test_db();
+        let mut binding = hotkey_bindings::HotkeyBinding {
+            id: "binding-1".into(),
+            hotkey: "CommandOrControl+Shift+KeyE".into(),
+            model_id: None,
+            language: Some("en".into()),
+            ai_function: Some("email".into()),
+            pipeline_id: None,
+        };
+        hotkey_bindings::upsert(&binding).unwrap();
+
+        binding.model_id = Some("whisper-small".into());
+        hotkey_bindings::upsert(&binding).unwrap();
+        assert_eq!(hotkey_bindings::get("binding-1").unwrap(), Some(binding.clone()));
+        assert_eq!(
+            hotkey_bindings::list()
+                .unwrap()
+                .iter()
+                .filter(|b| b.id == "binding-1")
+                .count(),
+            1
+        );
+
+        hotkey_bindings::delete("binding-1").unwrap();
+        assert!(hotkey_bindings::get("binding-1").unwrap().is_none());
+    }
+
+    #[test]
+    fn hotkey_binding_rejects_duplicate_hotkey() {
+        init_test_db();
+        let binding = hotkey_bindings::HotkeyBinding {
+            id: "binding-dup-1".into(),
+            hotkey: "Alt+KeyD".into(),
+            model_id: None,
+            language: None,
+            ai_function: None,
+            pipeline_id: None,
+        };
+        hotkey_bindings::upsert(&binding).unwrap();
+        let clash = hotkey_bindings::HotkeyBinding {
+            id: "binding-dup-2".into(),
+            ..binding
+        };
+        assert!(hotkey_bindings::upsert(&clash).is_err());
+    }
+
+    // ── Rules ────────────────────────────────────────────────
+
+    #[test]
+    fn rule_state_and_order_persist() {
+        init_test_db();
+        let ids = |rules: Vec<rules::RuleSetting>| -> Vec<String> {
+            rules.into_iter().map(|r| r.id).collect()
+        };
+        rules::set_enabled("smart-punctuation", true).unwrap();
+        rules::reorder(&["smart-punctuation".into(), "remove-fillers".into()]).unwrap();
+        assert_eq!(
+            ids(rules::list().unwrap()),
+            vec!["smart-punctuation", "remove-fillers"]
+        );
+        assert_eq!(rules::enabled_ids().unwrap(), vec!["smart-punctuation"]);
+
+        assert!(rules::set_enabled("no-such-rule", true).is_err());
+        assert!(rules::reorder(&["remove-fillers".into()]).is_err());
+
+        rules::set_enabled("smart-punctuation", false).unwrap();
+        rules::reorder(&["remove-fillers".into(), "smart-punctuation".into()]).unwrap();
+        assert!(rules::enabled_ids().unwrap().is_empty());
+
+        // Custom rules run after the built-in ones until reordered
+        rules::add_custom("custom-rule-1", "Typo", r"\bteh\b", "the", "", true).unwrap();
+        rules::add_custom("custom-rule-2", "Issue", r"issue (\d+)", "ISSUE-$1", "i", true)
+            .unwrap();
+        let loaded = rules::load().unwrap();
+        assert_eq!(loaded.last().unwrap().id, "custom-rule-2");
+        assert_eq!(
+            crate::rules::apply_regex_rules("teh Issue 12", &loaded),
+            "the ISSUE-12"
+        );
+        assert!(rules::delete_custom("remove-fillers").is_err());
+        rules::delete_custom("custom-rule-1").unwrap();
+        rules::delete_custom("custom-rule-2").unwrap();
+        assert_eq!(rules::list().unwrap().len(), 2);
+    }
+
+    // ── Pipelines ────────────────────────────────────────────
+
+    #[test]
+    fn pipeline_round_trip_and_delete_clears_presets() {
+        init_test_db();
+        let mut pipeline = pipelines::Pipeline {
+            id: "pipeline-1".into(),
+            name: "Email".into(),
+            steps: vec![
+                pipelines::PipelineStep::Rules {
+                    rule_ids: vec!["remove-fillers".into()],
+                },
+                pipelines::PipelineStep::AiFunction {
+                    function_id: "email".into(),
+                },
+                pipelines::PipelineStep::Redact,
+            ],
+            created_at: String::new(),
+        };
+        pipelines::upsert(&pipeline).unwrap();
+        pipeline.name = "Email draft".into();
+        pipelines::upsert(&pipeline).unwrap();
+        let saved = pipelines::get("pipeline-1").unwrap().unwrap();
+        assert_eq!(saved.name, "Email draft");
+        assert_eq!(saved.steps, pipeline.steps);
+
+        hotkey_bindings::upsert(&hotkey_bindings::HotkeyBinding {
+            id: "pipeline-binding".into(),
+            hotkey: "Alt+KeyP".into(),
+            model_id: None,
+            language: None,
+            ai_function: None,
+            pipeline_id: Some("pipeline-1".into()),
+        })
+        .unwrap();
+        pipelines::delete("pipeline-1").unwrap();
+        assert!(pipelines::get("pipeline-1").unwrap().is_none());
+        let binding = hotkey_bindings::get("pipeline-binding").unwrap().unwrap();
+        assert_eq!(binding.pipeline_id, None);
+    }
+
+    // ── Watched folders ──────────────────────────────────────
+
+    #[test]
+    fn watch_folder_round_trip() {
+        init_test_db();
+        let mut folder = watch_folders::WatchFolder {
+            id: "watch-1".into(),
+            path: "/tmp/recorder".into(),
+            model_id: None,
+            write_txt: true,
+            write_srt: false,
+            enabled: true,
+            created_at: String::new(),
+        };
+        watch_folders::upsert(&folder).unwrap();
+        folder.model_id = Some("whisper-base".into());
+        folder.write_srt = true;
+        watch_folders::upsert(&folder).unwrap();
+
+        let find = || {
+            watch_folders::list()
+                .unwrap()
+                .into_iter()
+                .find(|f| f.id == "watch-1")
+        };
+        let saved = find().unwrap();
+        assert_eq!(saved.model_id.as_deref(), Some("whisper-base"));
+        assert!(saved.write_txt && saved.write_srt && saved.enabled);
+
+        watch_folders::delete("watch-1").unwrap();
+        assert!(find().is_none());
+    }
+
+    // ── Device bindings ──────────────────────────────────────
+
+    #[test]
+    fn device_binding_round_trip() {
+        init_test_db();
+        let mut binding = device_bindings::DeviceBinding {
+            id: "pedal-1".into(),
+            name: "VEC USB Footpedal".into(),
+            vendor_id: 0x05f3,
+            product_id: 0x00ff,
+            button: 1,
+            action: "dictation".into(),
+            enabled: true,
+            created_at: String::new(),
+        };
+        device_bindings::upsert(&binding).unwrap();
+        binding.button = 2;
+        binding.action = "paste-last".into();
+        device_bindings::upsert(&binding).unwrap();
+
+        let find = || {
+            device_bindings::list()
+                .unwrap()
+                .into_iter()
+                .find(|b| b.id == "pedal-1")
+        };
+        let saved = find().unwrap();
+        assert_eq!((saved.vendor_id, saved.product_id), (0x05f3, 0x00ff));
+        assert_eq!(saved.button, 2);
+        assert_eq!(saved.action, "paste-last");
+
+        device_bindings::delete("pedal-1").unwrap();
+        assert!(find().is_none());
+    }
+
+    // ── App profiles ─────────────────────────────────────────
+
+    #[test]
+    fn app_profile_round_trip_and_lookup() {
+        init_test_db();
+        let mut profile = app_profiles::AppProfile {
+            id: "profile-1".into(),
+            app_name: "Slack".into(),
+            newline_mode: "keep".into(),
+            strip_trailing_newline: false,
+            created_at: String::new(),
+        };
+        app_profiles::upsert(&profile).unwrap();
+        profile.newline_mode = "shiftEnter".into();
+        profile.strip_trailing_newline = true;
+        app_profiles::upsert(&profile).unwrap();
+
+        let saved = app_profiles::find("slack").unwrap().unwrap();
+        assert_eq!(saved.newline_mode, "shiftEnter");
+        assert!(saved.strip_trailing_newline);
+        assert!(app_profiles::find("Terminal").unwrap().is_none());
+
+        app_profiles::delete("profile-1").unwrap();
+        assert!(app_profiles::find("Slack").unwrap().is_none());
+    }
+
+    // ── Notes ────────────────────────────────────────────────
+
+    #[test]
+    fn notes_attach_reorder_and_merge() {
+        init_test_db();
+        for (id, transcript, processed) in [
+            ("note-rec-1", "first part", None),
+            ("note-rec-2", "second part", Some("Second part.")),
+            ("note-rec-3", "third part", None),
+        ] {
+            history::insert(&history::HistoryItem {
+                id: id.into(),
+                audio_path: None,
+                transcript: transcript.into(),
+                processed_text: processed.map(Into::into),
+                rules_text: None,
+                model_id: "whisper-base".into(),
+                language: None,
+                ai_function: None,
+                duration_ms: None,
+                metrics: None,
+                created_at: String::new(),
+            })
+            .unwrap();
+        }
+        let note = notes::create("note-1", "Meeting").unwrap();
+        assert!(note.recording_ids.is_empty());
+
+        for id in ["note-rec-1", "note-rec-2", "note-rec-3", "note-rec-1"] {
+            notes::attach("note-1", id).unwrap();
+        }
+        assert_eq!(
+            notes::merged_text("note-1").unwrap(),
+            "first part\n\nSecond part.\n\nthird part"
+        );
+
+        let order: Vec<String> = ["note-rec-3", "note-rec-1", "note-rec-2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        notes::reorder("note-1", &order).unwrap();
+        assert_eq!(notes::get("note-1").unwrap().unwrap().recording_ids, order);
+        assert!(notes::reorder("note-1", &order[..2]).is_err());
+
+        // Deleting a recording drops it from the note
+        history::delete("note-rec-1").unwrap();
+        notes::detach("note-1", "note-rec-2").unwrap();
+        assert_eq!(notes::merged_text("note-1").unwrap(), "third part");
+
+        notes::rename("note-1", "Standup").unwrap();
+        let listed = notes::list().unwrap();
+        let note = listed.iter().find(|n| n.id == "note-1").unwrap();
+        assert_eq!(note.title, "Standup");
+        assert_eq!(note.recording_ids, vec!["note-rec-3".to_string()]);
+
+        notes::delete("note-1").unwrap();
+        assert!(notes::get("note-1").unwrap().is_none());
+        assert!(history::exists("note-rec-3").unwrap());
+        assert!(notes::attach("note-1", "note-rec-3").is_err());
+    }
+
+    // ── Settings ─────────────────────────────────────────────
+
+    #[test]
+    fn settings_save_and_get_all() {
+        init_test_db();
+        let mut map = serde_json::Map::new();
+        map.insert("testSettingA".into(), serde_json::json!("value"));
+        map.insert("testSettingB".into(), serde_json::json!({ "nested": [1, 2] }));
+        settings::save_all(&map).unwrap();
+
+        let mut update = serde_json::Map::new();
+        update.insert("testSettingA".into(), serde_json::json!(42));
+        settings::save_all(&update).unwrap();
+
+        let stored = settings::get_all().unwrap();
+        assert_eq!(stored["testSettingA"], serde_json::json!(42));
+        assert_eq!(stored["testSettingB"], serde_json::json!({ "nested": [1, 2] }));
+    }
+
+    #[test]
+    fn app_settings_defaults_and_bad_types() {
+        let mut map = serde_json::Map::new();
+        map.insert("selectedModel".into(), serde_json::json!("parakeet-tdt-0.6b-v3"));
+        map.insert("selectedLanguage".into(), serde_json::json!("de"));
+        map.insert("incognito".into(), serde_json::json!("not a bool"));
+        map.insert("theme".into(), serde_json::json!("dark"));
+        map.insert(
+            "providerConfigs".into(),
+            serde_json::json!({
+                "openai": { "apiKey": "sk-test", "model": "gpt-4o-mini", "timeoutSecs": 30 },
+                "groq": { "apiKey": "", "model": "llama-3.3-70b-versatile" }
+            }),
+        );
+
+        let s = settings::AppSettings::from_map(&map);
+        assert_eq!(s.selected_model, "parakeet-tdt-0.6b-v3");
+        assert_eq!(s.language().as_deref(), Some("de"));
+        assert!(!s.incognito);
+        assert_eq!(s.default_hotkey, "Alt+Space");
+        assert_eq!(s.recording_mode, "push-to-talk");
+        assert_eq!(s.min_recording_ms, 300);
+        assert_eq!(s.hands_free_silence_secs, 2.0);
+        assert_eq!(s.decoding_options, crate::stt::DecodingOptions::default());
+        assert_eq!(s.api_key("openai").as_deref(), Some("sk-test"));
+        assert_eq!(s.api_key("groq"), None);
+        assert_eq!(s.api_key("anthropic"), None);
+        assert_eq!(settings::AppSettings::default().language(), None);
+    }
+
+    #[test]
+    fn app_settings_partial_decoding_options() {
+        let mut map = serde_json::Map::new();
+        map.insert(
+            "decodingOptions".into(),
+            serde_json::json!({ "beamSize": 0, "temperatureInc": 0.0 }),
+        );
+
+        let decoding = settings::AppSettings::from_map(&map).decoding_options;
+        assert_eq!(decoding.beam_size, 0);
+        assert_eq!(decoding.temperature_inc, 0.0);
+        assert_eq!(decoding.entropy_thold, 2.4);
+        // A beam of 0 from a bad setting still decodes greedily
+        assert_eq!(decoding.sanitized().beam_size, 1);
+    }
+
     // ── AI Functions CRUD ────────────────────────────────────
 
     #[test]
@@ -416,7 +1546,7 @@ pub mod tests {
         ai_functions::delete("builtin-nodelete").unwrap();
         // Verify it's still there (not in list because list filters builtins,
         // but the row should still exist)
-        let conn = get_conn().lock().unwrap();
+        let conn = get_conn().unwrap();
         let count: i32 = conn
             .query_row(
                 "SELECT COUNT(*) FROM ai_functions WHERE id = ?1",
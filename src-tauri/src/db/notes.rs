@@ -0,0 +1,185 @@
+//! Notes: named documents made of history recordings in a chosen order.
+//! Each recording is a snippet of the note; deleting the recording removes
+//! it from every note it was attached to.
+
+use crate::db;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub id: String,
+    pub title: String,
+    /// Attached recordings, in document order.
+    pub recording_ids: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Separates snippets in the merged text.
+const SNIPPET_SEPARATOR: &str = "\n\n";
+
+fn recording_ids(conn: &Connection, note_id: &str) -> rusqlite::Result<Vec<String>> {
+    conn.prepare(
+        "SELECT recording_id FROM note_recordings WHERE note_id = ?1 ORDER BY position ASC",
+    )?
+    .query_map(rusqlite::params![note_id], |row| row.get(0))?
+    .collect()
+}
+
+fn touch(conn: &Connection, note_id: &str) -> anyhow::Result<()> {
+    let updated = conn.execute(
+        "UPDATE notes SET updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        rusqlite::params![note_id],
+    )?;
+    if updated == 0 {
+        anyhow::bail!("Note not found: {}", note_id);
+    }
+    Ok(())
+}
+
+pub fn create(id: &str, title: &str) -> anyhow::Result<Note> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "INSERT INTO notes (id, title) VALUES (?1, ?2)",
+        rusqlite::params![id, title],
+    )?;
+    drop(conn);
+    get(id)?.ok_or_else(|| anyhow::anyhow!("Note not found: {}", id))
+}
+
+pub fn get(id: &str) -> anyhow::Result<Option<Note>> {
+    let conn = db::get_conn()?;
+    let note = conn
+        .query_row(
+            "SELECT id, title, created_at, updated_at FROM notes WHERE id = ?1",
+            rusqlite::params![id],
+            |row| {
+                Ok(Note {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    recording_ids: Vec::new(),
+                    created_at: row.get(2)?,
+                    updated_at: row.get(3)?,
+                })
+            },
+        )
+        .optional()?;
+    let Some(mut note) = note else {
+        return Ok(None);
+    };
+    note.recording_ids = recording_ids(&conn, id)?;
+    Ok(Some(note))
+}
+
+/// All notes, most recently changed first.
+pub fn list() -> anyhow::Result<Vec<Note>> {
+    let conn = db::get_conn()?;
+    let mut notes = conn
+        .prepare(
+            "SELECT id, title, created_at, updated_at FROM notes
+             ORDER BY updated_at DESC, created_at DESC",
+        )?
+        .query_map([], |row| {
+            Ok(Note {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                recording_ids: Vec::new(),
+                created_at: row.get(2)?,
+                updated_at: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for note in &mut notes {
+        note.recording_ids = recording_ids(&conn, &note.id)?;
+    }
+    Ok(notes)
+}
+
+pub fn rename(id: &str, title: &str) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "UPDATE notes SET title = ?1 WHERE id = ?2",
+        rusqlite::params![title, id],
+    )?;
+    touch(&conn, id)
+}
+
+/// Delete a note. Its recordings stay in history.
+pub fn delete(id: &str) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute("DELETE FROM notes WHERE id = ?1", rusqlite::params![id])?;
+    Ok(())
+}
+
+/// Append a recording to the end of a note. Attaching one that's already in
+/// the note leaves it where it is.
+pub fn attach(note_id: &str, recording_id: &str) -> anyhow::Result<()> {
+    let mut conn = db::get_conn()?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT OR IGNORE INTO note_recordings (note_id, recording_id, position)
+         SELECT ?1, ?2, COALESCE(MAX(position) + 1, 0)
+         FROM note_recordings WHERE note_id = ?1",
+        rusqlite::params![note_id, recording_id],
+    )?;
+    touch(&tx, note_id)?;
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn detach(note_id: &str, recording_id: &str) -> anyhow::Result<()> {
+    let mut conn = db::get_conn()?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "DELETE FROM note_recordings WHERE note_id = ?1 AND recording_id = ?2",
+        rusqlite::params![note_id, recording_id],
+    )?;
+    touch(&tx, note_id)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Put a note's snippets in the given order. `recording_ids` must list
+/// exactly the recordings attached to the note.
+pub fn reorder(note_id: &str, recording_ids_in_order: &[String]) -> anyhow::Result<()> {
+    let mut conn = db::get_conn()?;
+    let tx = conn.transaction()?;
+    let mut current = recording_ids(&tx, note_id)?;
+    let mut requested = recording_ids_in_order.to_vec();
+    current.sort();
+    requested.sort();
+    if current != requested {
+        anyhow::bail!("Reorder must list every recording in the note exactly once");
+    }
+    for (position, recording_id) in recording_ids_in_order.iter().enumerate() {
+        tx.execute(
+            "UPDATE note_recordings SET position = ?1 WHERE note_id = ?2 AND recording_id = ?3",
+            rusqlite::params![position as i64, note_id, recording_id],
+        )?;
+    }
+    touch(&tx, note_id)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// The note's snippets joined in order, each recording's final text: the
+/// AI output if there is one, else the rules text, else the transcript.
+pub fn merged_text(note_id: &str) -> anyhow::Result<String> {
+    let conn = db::get_conn()?;
+    let snippets: Vec<String> = conn
+        .prepare(
+            "SELECT COALESCE(r.processed_text, r.rules_text, r.transcript)
+             FROM note_recordings n JOIN recordings r ON r.id = n.recording_id
+             WHERE n.note_id = ?1 ORDER BY n.position ASC",
+        )?
+        .query_map(rusqlite::params![note_id], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(snippets
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(SNIPPET_SEPARATOR))
+}
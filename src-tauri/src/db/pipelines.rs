@@ -0,0 +1,94 @@
+//! Post-processing pipelines: named, ordered lists of steps a transcript
+//! goes through before it's pasted. Steps are stored as JSON.
+
+use crate::db;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PipelineStep {
+    /// Built-in text rules such as filler removal, in the given order.
+    #[serde(rename_all = "camelCase")]
+    Rules { rule_ids: Vec<String> },
+    /// Vocabulary terms swapped for their replacements.
+    Vocabulary,
+    #[serde(rename_all = "camelCase")]
+    AiFunction { function_id: String },
+    /// Email addresses, card and phone numbers masked.
+    Redact,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pipeline {
+    pub id: String,
+    pub name: String,
+    pub steps: Vec<PipelineStep>,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+type PipelineRow = (String, String, String, String);
+
+fn from_row((id, name, steps, created_at): PipelineRow) -> anyhow::Result<Pipeline> {
+    Ok(Pipeline {
+        id,
+        name,
+        steps: serde_json::from_str(&steps)?,
+        created_at,
+    })
+}
+
+fn select(sql: &str, params: impl rusqlite::Params) -> anyhow::Result<Vec<Pipeline>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<Result<Vec<PipelineRow>, _>>()?;
+    rows.into_iter().map(from_row).collect()
+}
+
+pub fn list() -> anyhow::Result<Vec<Pipeline>> {
+    select(
+        "SELECT id, name, steps, created_at FROM pipelines ORDER BY created_at ASC, id ASC",
+        [],
+    )
+}
+
+pub fn get(id: &str) -> anyhow::Result<Option<Pipeline>> {
+    let mut items = select(
+        "SELECT id, name, steps, created_at FROM pipelines WHERE id = ?1",
+        rusqlite::params![id],
+    )?;
+    Ok(items.pop())
+}
+
+/// Insert or update a pipeline, keeping its position when it already exists.
+pub fn upsert(pipeline: &Pipeline) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "INSERT INTO pipelines (id, name, steps) VALUES (?1, ?2, ?3)
+         ON CONFLICT(id) DO UPDATE SET name = ?2, steps = ?3",
+        rusqlite::params![
+            pipeline.id,
+            pipeline.name,
+            serde_json::to_string(&pipeline.steps)?
+        ],
+    )?;
+    Ok(())
+}
+
+/// Delete a pipeline; hotkey presets using it fall back to the default.
+pub fn delete(id: &str) -> anyhow::Result<()> {
+    let mut conn = db::get_conn()?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE hotkey_bindings SET pipeline_id = NULL WHERE pipeline_id = ?1",
+        rusqlite::params![id],
+    )?;
+    tx.execute("DELETE FROM pipelines WHERE id = ?1", rusqlite::params![id])?;
+    tx.commit()?;
+    Ok(())
+}
@@ -0,0 +1,219 @@
+//! Enabled state and order of the text rules: the built-in ones plus custom
+//! regex rules. A built-in rule without a row keeps its defaults (disabled,
+//! built-in position).
+
+use crate::db;
+use crate::rules::{self, Rule, RuleType};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleSetting {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub sort_order: i32,
+    pub is_builtin: bool,
+    /// Regex of a custom rule.
+    pub pattern: Option<String>,
+    pub replacement: Option<String>,
+    pub flags: Option<String>,
+}
+
+/// Rule state the frontend kept before it was stored here, mirrored into
+/// the settings table under "rules".
+#[derive(Deserialize)]
+struct FrontendRule {
+    id: String,
+    enabled: bool,
+}
+
+/// Add a row for every built-in rule that doesn't have one yet. An empty
+/// table is seeded from the rules in the frontend settings.
+fn ensure_rows(conn: &Connection) -> anyhow::Result<()> {
+    let empty: bool = conn.query_row("SELECT COUNT(*) = 0 FROM rules", [], |row| row.get(0))?;
+    let mut seeded: Vec<FrontendRule> = vec![];
+    if empty {
+        let saved: Option<String> = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'rules'",
+                [],
+                |row| row.get(0),
+            )
+            .ok();
+        seeded = saved
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+    }
+
+    for rule in rules::builtin_rules() {
+        let position = seeded.iter().position(|r| r.id == rule.id);
+        let enabled = position.is_some_and(|i| seeded[i].enabled);
+        let sort_order = position.map_or(rule.sort_order, |i| i as i32);
+        conn.execute(
+            "INSERT OR IGNORE INTO rules (id, name, type, enabled, sort_order)
+             VALUES (?1, ?2, 'regex_replace', ?3, ?4)",
+            rusqlite::params![rule.id, rule.name, enabled, sort_order],
+        )?;
+    }
+    Ok(())
+}
+
+struct StoredRule {
+    id: String,
+    name: String,
+    enabled: bool,
+    sort_order: i32,
+    pattern: Option<String>,
+    replacement: Option<String>,
+    flags: Option<String>,
+}
+
+/// Every rule with its stored state, in order.
+pub fn load() -> anyhow::Result<Vec<Rule>> {
+    Ok(list()?
+        .into_iter()
+        .map(|r| Rule {
+            rule_type: match (r.is_builtin, r.pattern) {
+                (false, Some(pattern)) => RuleType::RegexReplace {
+                    pattern,
+                    replacement: r.replacement.unwrap_or_default(),
+                    flags: r.flags.unwrap_or_default(),
+                },
+                _ => builtin_type(&r.id),
+            },
+            id: r.id,
+            name: r.name,
+            enabled: r.enabled,
+            sort_order: r.sort_order,
+        })
+        .collect())
+}
+
+fn builtin_type(id: &str) -> RuleType {
+    rules::builtin_rules()
+        .into_iter()
+        .find(|r| r.id == id)
+        .map(|r| r.rule_type)
+        .unwrap_or(RuleType::RegexReplace {
+            pattern: String::new(),
+            replacement: String::new(),
+            flags: String::new(),
+        })
+}
+
+pub fn list() -> anyhow::Result<Vec<RuleSetting>> {
+    let conn = db::get_conn()?;
+    ensure_rows(&conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, name, enabled, sort_order, pattern, replacement, flags FROM rules
+         ORDER BY sort_order ASC, id ASC",
+    )?;
+    let stored = stmt
+        .query_map([], |row| {
+            Ok(StoredRule {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                enabled: row.get(2)?,
+                sort_order: row.get(3)?,
+                pattern: row.get(4)?,
+                replacement: row.get(5)?,
+                flags: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let builtin = rules::builtin_rules();
+    Ok(stored
+        .into_iter()
+        .filter_map(|r| {
+            let is_builtin = builtin.iter().any(|b| b.id == r.id);
+            // Rows that are neither built in nor a regex are left from old versions
+            (is_builtin || r.pattern.is_some()).then(|| RuleSetting {
+                is_builtin,
+                pattern: r.pattern.filter(|_| !is_builtin),
+                replacement: r.replacement.filter(|_| !is_builtin),
+                flags: r.flags.filter(|_| !is_builtin),
+                id: r.id,
+                name: r.name,
+                enabled: r.enabled,
+                sort_order: r.sort_order,
+            })
+        })
+        .collect())
+}
+
+/// Ids of the enabled rules, in order.
+pub fn enabled_ids() -> anyhow::Result<Vec<String>> {
+    Ok(load()?
+        .into_iter()
+        .filter(|r| r.enabled)
+        .map(|r| r.id)
+        .collect())
+}
+
+pub fn set_enabled(id: &str, enabled: bool) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    ensure_rows(&conn)?;
+    let updated = conn.execute(
+        "UPDATE rules SET enabled = ?2 WHERE id = ?1",
+        rusqlite::params![id, enabled],
+    )?;
+    if updated == 0 {
+        anyhow::bail!("Unknown rule: {}", id);
+    }
+    Ok(())
+}
+
+/// Add a custom regex rule after the existing ones.
+pub fn add_custom(
+    id: &str,
+    name: &str,
+    pattern: &str,
+    replacement: &str,
+    flags: &str,
+    enabled: bool,
+) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    ensure_rows(&conn)?;
+    conn.execute(
+        "INSERT INTO rules (id, name, type, pattern, replacement, flags, enabled, sort_order)
+         VALUES (?1, ?2, 'regex_replace', ?3, ?4, ?5, ?6,
+                 (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM rules))",
+        rusqlite::params![id, name, pattern, replacement, flags, enabled],
+    )?;
+    Ok(())
+}
+
+/// Delete a custom rule. Built-in rules can only be disabled.
+pub fn delete_custom(id: &str) -> anyhow::Result<()> {
+    if rules::builtin_rules().iter().any(|r| r.id == id) {
+        anyhow::bail!("Built-in rules can't be deleted");
+    }
+    let conn = db::get_conn()?;
+    conn.execute("DELETE FROM rules WHERE id = ?1", rusqlite::params![id])?;
+    Ok(())
+}
+
+/// Set the rule order; `ids` must list every rule exactly once.
+pub fn reorder(ids: &[String]) -> anyhow::Result<()> {
+    let mut current: Vec<String> = list()?.into_iter().map(|r| r.id).collect();
+    let mut sorted = ids.to_vec();
+    current.sort();
+    sorted.sort();
+    if sorted != current {
+        anyhow::bail!("Rule order must list every rule once");
+    }
+
+    let mut conn = db::get_conn()?;
+    let tx = conn.transaction()?;
+    for (position, id) in ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE rules SET sort_order = ?2 WHERE id = ?1",
+            rusqlite::params![id, position as i32],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
@@ -1,3 +1,291 @@
-// Settings persistence via tauri-plugin-store (key-value)
-// This module provides helpers for structured settings that don't fit key-value.
-// Most settings use the Tauri store directly from the frontend.
+//! Frontend settings mirrored into SQLite so backend code (hotkeys, tray,
+//! transcription) can read user preferences without the frontend passing
+//! them on every call. Values are stored as JSON, keyed by the camelCase
+//! field names of the frontend store.
+
+use crate::db;
+use crate::stt::DecodingOptions;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Typed view of the settings the backend acts on. Missing keys fall back
+/// to the same defaults as the frontend store.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub selected_model: String,
+    pub selected_language: String,
+    pub selected_ai_function: Option<String>,
+    pub recording_mode: String,
+    pub default_hotkey: String,
+    pub rewrite_hotkey: Option<String>,
+    pub paste_last_hotkey: Option<String>,
+    pub undo_paste_hotkey: Option<String>,
+    /// "auto", "native" or "portal".
+    pub hotkey_backend: String,
+    /// Mouse button that triggers dictation like the hotkey: "none",
+    /// "middle", "back" (M4) or "forward" (M5).
+    pub mouse_button: String,
+    /// Double-pressing the dictation hotkey dictates and runs the last used
+    /// AI function on the transcript.
+    pub double_press_ai: bool,
+    /// Recordings shorter than this are discarded; 0 keeps everything.
+    pub min_recording_ms: u64,
+    /// Trailing silence that ends a hands-free recording.
+    pub hands_free_silence_secs: f64,
+    /// Show a rolling transcript in the recording bar while recording.
+    pub live_transcript: bool,
+    /// Emit `audio-spectrum` band levels for the recording bar.
+    pub spectrum_visualizer: bool,
+    pub paste_source: String,
+    /// How pasted text reaches the clipboard: "plain" (Markdown removed),
+    /// "markdown" (as-is) or "rich" (HTML with a plain-text fallback).
+    pub paste_format: String,
+    /// What happens to a finished dictation: "auto" (paste it), "copy"
+    /// (clipboard only) or "confirm" (preview window with a Paste button).
+    pub paste_mode: String,
+    /// Wait before the paste keystroke, for apps slow to take focus.
+    pub paste_delay_ms: u64,
+    /// Re-activate the app that was in front when recording started before
+    /// pasting the result.
+    pub lock_paste_target: bool,
+    /// What auto-paste does when a password field has focus: "block" or
+    /// "warn" (paste anyway and report it).
+    pub secure_field_paste: String,
+    pub incognito: bool,
+    /// Also save the raw capture next to the 16 kHz mono WAV.
+    pub keep_original_audio: bool,
+    /// Accept `sobottaai://` automation URLs. Off by default since any web
+    /// page or document can open them.
+    pub automation_urls_enabled: bool,
+    /// Record from the built-in mic instead of a Bluetooth headset.
+    pub prefer_built_in_mic: bool,
+    /// Recording bar placement, e.g. "bottom-center" or "top-right".
+    pub recording_bar_position: String,
+    /// "primary", "cursor" (display under the mouse) or a monitor name.
+    pub recording_bar_monitor: String,
+    /// Whisper beam size, temperature fallback and thresholds.
+    pub decoding_options: DecodingOptions,
+    /// CPU threads for local models; 0 uses all cores up to 8.
+    pub inference_threads: u32,
+    /// Half the threads and greedy decoding, to keep laptops cool and quiet.
+    pub low_power_mode: bool,
+    /// Longest a local transcription may run before it's stopped (0 for no
+    /// limit); recordings get at least twice their duration.
+    pub max_inference_secs: u64,
+    /// Execution provider for Parakeet: "auto", "cpu", "coreml", "cuda" or
+    /// "directml".
+    pub parakeet_provider: String,
+    /// Context passed as the initial prompt to Whisper and cloud STT.
+    pub initial_prompt: Option<String>,
+    /// Approximate token cap for the prompt (initial prompt plus vocabulary).
+    /// Whisper only keeps the last 224 prompt tokens, so lower-ranked terms
+    /// are left out rather than truncated arbitrarily.
+    pub prompt_token_budget: usize,
+    /// Detect the language per pause-separated chunk for mixed-language speech.
+    pub code_switching: bool,
+    /// Candidate languages for code-switching; empty allows any.
+    pub code_switch_languages: Vec<String>,
+    /// Cut local Whisper input down to speech with Silero VAD, once downloaded.
+    pub vad_enabled: bool,
+    /// Add punctuation to unpunctuated Parakeet output, once the model is
+    /// downloaded.
+    pub punctuation_enabled: bool,
+    /// Per-request timeout for cloud transcription.
+    pub cloud_stt_timeout_secs: u64,
+    /// Retries of a cloud transcription request after a rate limit or server error.
+    pub cloud_stt_max_retries: u32,
+    /// OpenAI-compatible server to send OpenAI transcriptions to instead,
+    /// e.g. a self-hosted faster-whisper-server.
+    pub openai_stt_base_url: Option<String>,
+    /// Model name for that server; unused without a base URL.
+    pub openai_stt_model: String,
+    /// API key for that server, if it needs one. The OpenAI key is never
+    /// sent there.
+    pub openai_stt_api_key: String,
+    /// Custom location for audio and models; `None` uses the app data dir.
+    pub storage_dir: Option<String>,
+    /// Per-provider API keys and models, keyed by provider id.
+    pub provider_configs: HashMap<String, ProviderSettings>,
+    /// LLM provider for AI functions that don't name their own.
+    pub llm_provider: String,
+    /// Providers to try, in order, when the AI function's provider fails.
+    pub llm_fallback_providers: Vec<String>,
+    /// User context (name, style, sign-off) prepended to AI function prompts.
+    pub system_prompt_prefix: String,
+    /// Extra AI function input: "none", "clipboard" or "selection".
+    pub ai_context_source: String,
+    /// How long identical AI function requests reuse the cached output; 0
+    /// disables the cache.
+    pub ai_cache_ttl_hours: u64,
+    /// "stable" or "beta".
+    pub update_channel: String,
+    /// Language of the tray menu and notifications; "auto" follows the system.
+    pub ui_language: String,
+    /// Notify with a transcript preview when a transcription finishes while
+    /// the main window is hidden.
+    pub notify_on_transcription: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            selected_model: "whisper-base".into(),
+            selected_language: "auto".into(),
+            selected_ai_function: None,
+            recording_mode: "push-to-talk".into(),
+            default_hotkey: "Alt+Space".into(),
+            rewrite_hotkey: None,
+            paste_last_hotkey: None,
+            undo_paste_hotkey: None,
+            hotkey_backend: "auto".into(),
+            mouse_button: "none".into(),
+            double_press_ai: false,
+            min_recording_ms: 300,
+            hands_free_silence_secs: 2.0,
+            live_transcript: false,
+            spectrum_visualizer: false,
+            paste_source: "processed".into(),
+            paste_format: "markdown".into(),
+            paste_mode: "auto".into(),
+            paste_delay_ms: 0,
+            lock_paste_target: false,
+            secure_field_paste: "block".into(),
+            incognito: false,
+            keep_original_audio: false,
+            automation_urls_enabled: false,
+            prefer_built_in_mic: false,
+            recording_bar_position: "bottom-center".into(),
+            recording_bar_monitor: "primary".into(),
+            decoding_options: DecodingOptions::default(),
+            inference_threads: 0,
+            low_power_mode: false,
+            max_inference_secs: 300,
+            parakeet_provider: "auto".into(),
+            initial_prompt: None,
+            prompt_token_budget: 200,
+            code_switching: false,
+            code_switch_languages: vec![],
+            vad_enabled: false,
+            punctuation_enabled: false,
+            cloud_stt_timeout_secs: 120,
+            cloud_stt_max_retries: 2,
+            openai_stt_base_url: None,
+            openai_stt_model: String::new(),
+            openai_stt_api_key: String::new(),
+            storage_dir: None,
+            provider_configs: HashMap::new(),
+            llm_provider: "openai".into(),
+            llm_fallback_providers: vec![],
+            system_prompt_prefix: String::new(),
+            ai_context_source: "none".into(),
+            ai_cache_ttl_hours: 24,
+            update_channel: "stable".into(),
+            ui_language: "auto".into(),
+            notify_on_transcription: true,
+        }
+    }
+}
+
+/// The parts of a frontend `ProviderConfig` the backend uses.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderSettings {
+    pub api_key: String,
+    pub model: String,
+    pub base_url: Option<String>,
+    pub timeout_secs: Option<u64>,
+    /// Ollama only: context window, temperature and keep_alive.
+    pub num_ctx: Option<u32>,
+    pub temperature: Option<f32>,
+    pub keep_alive: Option<String>,
+}
+
+impl AppSettings {
+    /// Build from stored key/values. Keys with an unexpected type are
+    /// ignored rather than discarding the whole map.
+    pub fn from_map(map: &Map<String, Value>) -> Self {
+        let mut clean = Map::new();
+        for (key, value) in map {
+            let mut single = Map::new();
+            single.insert(key.clone(), value.clone());
+            if serde_json::from_value::<AppSettings>(Value::Object(single)).is_ok() {
+                clean.insert(key.clone(), value.clone());
+            }
+        }
+        serde_json::from_value(Value::Object(clean)).unwrap_or_default()
+    }
+
+    /// Saved API key for a provider, if one is set.
+    pub fn api_key(&self, provider: &str) -> Option<String> {
+        self.provider_configs
+            .get(provider)
+            .map(|c| c.api_key.clone())
+            .filter(|key| !key.is_empty())
+    }
+
+    /// Language hint for the STT engine; `None` means auto-detect.
+    pub fn language(&self) -> Option<String> {
+        match self.selected_language.as_str() {
+            "" | "auto" => None,
+            lang => Some(lang.to_string()),
+        }
+    }
+}
+
+/// API key for a provider stored in the OS keychain as `{provider}-api-key`,
+/// for keys kept out of the settings table.
+pub fn keychain_api_key(provider: &str) -> Option<String> {
+    keyring::Entry::new(
+        db::encryption::KEYCHAIN_SERVICE,
+        &format!("{}-api-key", provider),
+    )
+    .and_then(|entry| entry.get_password())
+    .ok()
+    .filter(|key| !key.is_empty())
+}
+
+pub fn get_all() -> anyhow::Result<Map<String, Value>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut map = Map::new();
+    for (key, value) in rows {
+        match serde_json::from_str(&value) {
+            Ok(value) => {
+                map.insert(key, value);
+            }
+            Err(e) => log::warn!("Ignoring unreadable setting '{}': {}", key, e),
+        }
+    }
+    Ok(map)
+}
+
+/// Upsert every key in `settings`. Keys not present are left untouched.
+pub fn save_all(settings: &Map<String, Value>) -> anyhow::Result<()> {
+    let mut conn = db::get_conn()?;
+    let tx = conn.transaction()?;
+    for (key, value) in settings {
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            rusqlite::params![key, value.to_string()],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Current settings, or defaults if the database can't be read.
+pub fn load() -> AppSettings {
+    match get_all() {
+        Ok(map) => AppSettings::from_map(&map),
+        Err(e) => {
+            log::warn!("Failed to load settings, using defaults: {}", e);
+            AppSettings::default()
+        }
+    }
+}
@@ -7,13 +7,18 @@ pub struct VocabularyTerm {
     pub id: String,
     pub term: String,
     pub replacement: Option<String>,
+    /// Free-form grouping such as "Medical" or "Names".
+    pub category: Option<String>,
+    /// Language code the term applies to; `None` applies to every language.
+    pub language: Option<String>,
     pub created_at: String,
 }
 
 pub fn list() -> anyhow::Result<Vec<VocabularyTerm>> {
-    let conn = db::get_conn().lock().unwrap();
+    let conn = db::get_conn()?;
     let mut stmt = conn.prepare(
-        "SELECT id, term, replacement, created_at FROM vocabulary ORDER BY term ASC",
+        "SELECT id, term, replacement, category, language, created_at
+         FROM vocabulary ORDER BY term ASC",
     )?;
 
     let items = stmt
@@ -22,7 +27,9 @@ pub fn list() -> anyhow::Result<Vec<VocabularyTerm>> {
                 id: row.get(0)?,
                 term: row.get(1)?,
                 replacement: row.get(2)?,
-                created_at: row.get(3)?,
+                category: row.get(3)?,
+                language: row.get(4)?,
+                created_at: row.get(5)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -30,17 +37,24 @@ pub fn list() -> anyhow::Result<Vec<VocabularyTerm>> {
     Ok(items)
 }
 
-pub fn add(id: &str, term: &str, replacement: Option<&str>) -> anyhow::Result<()> {
-    let conn = db::get_conn().lock().unwrap();
+pub fn add(
+    id: &str,
+    term: &str,
+    replacement: Option<&str>,
+    category: Option<&str>,
+    language: Option<&str>,
+) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
     conn.execute(
-        "INSERT OR REPLACE INTO vocabulary (id, term, replacement) VALUES (?1, ?2, ?3)",
-        rusqlite::params![id, term, replacement],
+        "INSERT OR REPLACE INTO vocabulary (id, term, replacement, category, language)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![id, term, replacement, category, language],
     )?;
     Ok(())
 }
 
 pub fn delete(id: &str) -> anyhow::Result<()> {
-    let conn = db::get_conn().lock().unwrap();
+    let conn = db::get_conn()?;
     conn.execute(
         "DELETE FROM vocabulary WHERE id = ?1",
         rusqlite::params![id],
@@ -48,11 +62,59 @@ pub fn delete(id: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn get_terms() -> anyhow::Result<Vec<String>> {
-    let conn = db::get_conn().lock().unwrap();
-    let mut stmt = conn.prepare("SELECT term FROM vocabulary ORDER BY term ASC")?;
+/// Terms to bias transcription in `language`: those without a language plus
+/// those scoped to it. With auto-detect the language isn't known up front, so
+/// every term is returned. Most relevant first: terms scoped to a language,
+/// then those recognized most often and most recently, then the newest.
+pub fn get_terms(language: Option<&str>) -> anyhow::Result<Vec<String>> {
+    let language = language.filter(|l| !l.is_empty() && *l != "auto");
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(
+        "SELECT term FROM vocabulary
+         WHERE ?1 IS NULL OR language IS NULL OR language = ?1
+         ORDER BY language IS NOT NULL DESC, use_count DESC, last_used_at DESC,
+                  created_at DESC, term ASC",
+    )?;
     let terms = stmt
-        .query_map([], |row| row.get::<_, String>(0))?
+        .query_map([language], |row| row.get::<_, String>(0))?
         .collect::<Result<Vec<_>, _>>()?;
     Ok(terms)
 }
+
+/// `(term, replacement)` for every term that has a replacement, longest
+/// term first so "gpt four turbo" wins over "gpt four".
+pub fn replacements() -> anyhow::Result<Vec<(String, String)>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(
+        "SELECT term, replacement FROM vocabulary
+         WHERE replacement IS NOT NULL AND replacement != ''
+         ORDER BY length(term) DESC, term ASC",
+    )?;
+    let pairs = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(pairs)
+}
+
+/// Count a use of each of `terms` that appears in `text` as a whole word, so
+/// terms the speaker actually says rank higher next time.
+pub fn record_usage(terms: &[String], text: &str) -> anyhow::Result<()> {
+    let used: Vec<&String> = terms
+        .iter()
+        .filter(|term| crate::rules::vocabulary::contains_term(text, term))
+        .collect();
+    if used.is_empty() {
+        return Ok(());
+    }
+    let mut conn = db::get_conn()?;
+    let tx = conn.transaction()?;
+    for term in used {
+        tx.execute(
+            "UPDATE vocabulary SET use_count = use_count + 1, last_used_at = CURRENT_TIMESTAMP
+             WHERE term = ?1",
+            rusqlite::params![term],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
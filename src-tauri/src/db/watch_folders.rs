@@ -0,0 +1,72 @@
+//! Folders watched for new audio files, e.g. a voice recorder's sync folder.
+//! Each new file is transcribed into history and, optionally, sidecar
+//! `.txt`/`.srt` files next to it.
+
+use crate::db;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchFolder {
+    pub id: String,
+    pub path: String,
+    /// Model to transcribe with; `None` uses the selected model.
+    pub model_id: Option<String>,
+    /// Write `<name>.txt` with the transcript next to the audio file.
+    pub write_txt: bool,
+    /// Write `<name>.srt` subtitles next to the audio file.
+    pub write_srt: bool,
+    pub enabled: bool,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+pub fn list() -> anyhow::Result<Vec<WatchFolder>> {
+    let conn = db::get_conn()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, path, model_id, write_txt, write_srt, enabled, created_at
+         FROM watch_folders ORDER BY created_at ASC, id ASC",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(WatchFolder {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                model_id: row.get(2)?,
+                write_txt: row.get(3)?,
+                write_srt: row.get(4)?,
+                enabled: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+pub fn upsert(folder: &WatchFolder) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "INSERT INTO watch_folders (id, path, model_id, write_txt, write_srt, enabled)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(id) DO UPDATE SET
+            path = ?2, model_id = ?3, write_txt = ?4, write_srt = ?5, enabled = ?6",
+        rusqlite::params![
+            folder.id,
+            folder.path,
+            folder.model_id,
+            folder.write_txt,
+            folder.write_srt,
+            folder.enabled
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn delete(id: &str) -> anyhow::Result<()> {
+    let conn = db::get_conn()?;
+    conn.execute(
+        "DELETE FROM watch_folders WHERE id = ?1",
+        rusqlite::params![id],
+    )?;
+    Ok(())
+}
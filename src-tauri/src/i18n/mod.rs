@@ -0,0 +1,134 @@
+//! Translations for the strings the backend shows itself: tray menu labels
+//! and notifications. Bundles are flat JSON maps from key to text, embedded
+//! at build time; `{name}` placeholders are filled in by [`t_with`]. Missing
+//! keys fall back to English.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Supported languages, English first.
+pub const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("locales/en.json")),
+    ("de", include_str!("locales/de.json")),
+    ("es", include_str!("locales/es.json")),
+    ("fr", include_str!("locales/fr.json")),
+    ("tr", include_str!("locales/tr.json")),
+];
+
+static BUNDLES: Lazy<HashMap<&'static str, HashMap<String, String>>> = Lazy::new(|| {
+    LOCALES
+        .iter()
+        .map(|(code, json)| {
+            let bundle = serde_json::from_str(json)
+                .unwrap_or_else(|e| panic!("invalid {} translations: {}", code, e));
+            (*code, bundle)
+        })
+        .collect()
+});
+
+static LOCALE: RwLock<&'static str> = RwLock::new("en");
+
+/// Switch to `language` ("auto" follows the system language), falling back
+/// to English when it isn't translated. Returns the language now in use.
+pub fn set_locale(language: &str) -> &'static str {
+    let requested = match language {
+        "auto" | "" => sys_locale::get_locale().unwrap_or_default(),
+        other => other.to_string(),
+    };
+    let locale = resolve(&requested);
+    *LOCALE.write().unwrap() = locale;
+    log::info!("UI language: {} (setting '{}')", locale, language);
+    locale
+}
+
+/// The supported locale for a BCP 47 tag such as "de-AT" or "pt_BR".
+fn resolve(tag: &str) -> &'static str {
+    let primary = tag
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    LOCALES
+        .iter()
+        .map(|(code, _)| *code)
+        .find(|code| *code == primary)
+        .unwrap_or("en")
+}
+
+/// The text for `key` in the current language.
+pub fn t(key: &str) -> String {
+    lookup(*LOCALE.read().unwrap(), key)
+}
+
+/// The text for `key` with each `{name}` replaced by its value.
+pub fn t_with(key: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(t(key), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+fn lookup(locale: &str, key: &str) -> String {
+    [locale, "en"]
+        .iter()
+        .find_map(|code| BUNDLES.get(code)?.get(key))
+        .cloned()
+        .unwrap_or_else(|| {
+            log::warn!("Missing translation: {}", key);
+            key.to_string()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn every_bundle_translates_every_key() {
+        let en = &BUNDLES["en"];
+        for (code, _) in LOCALES {
+            let bundle = &BUNDLES[code];
+            for (key, text) in en {
+                let translated = bundle
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{} is missing {}", code, key));
+                assert_eq!(
+                    placeholders(translated),
+                    placeholders(text),
+                    "{} {}",
+                    code,
+                    key
+                );
+            }
+            assert_eq!(bundle.len(), en.len(), "{} has keys English doesn't", code);
+        }
+    }
+
+    #[test]
+    fn resolves_regional_tags_and_falls_back_to_english() {
+        assert_eq!(resolve("de-AT"), "de");
+        assert_eq!(resolve("tr_TR"), "tr");
+        assert_eq!(resolve("FR"), "fr");
+        assert_eq!(resolve("ja-JP"), "en");
+        assert_eq!(resolve(""), "en");
+    }
+
+    #[test]
+    fn lookup_fills_in_and_falls_back() {
+        assert_eq!(lookup("de", "tray.quit"), "SobottaAI beenden");
+        assert_eq!(lookup("xx", "tray.quit"), "Quit SobottaAI");
+        assert_eq!(lookup("en", "no.such.key"), "no.such.key");
+        let text = lookup("en", "notification.fileTranscribed").replace("{name}", "memo.wav");
+        assert_eq!(text, "Transcribed memo.wav");
+    }
+}
@@ -1,17 +1,22 @@
 mod audio;
 mod commands;
 mod db;
+mod i18n;
 mod llm;
 mod models;
 mod rules;
 mod stt;
 mod system;
 
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Must come first: a second launch hands its arguments to this one and exits
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            system::automation::handle_second_instance(app, args, cwd);
+        }))
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
@@ -30,8 +35,20 @@ pub fn run() {
         )
         .manage(commands::recording::RecordingState::new())
         .manage(commands::transcription::SttManager::new())
+        .manage(commands::jobs::JobManager::new())
         .manage(system::tray::TrayMenuState::new())
         .manage(system::hotkey::HotkeyModeState::new())
+        .manage(commands::updater::UpdaterState::new())
+        .manage(commands::ai_functions::AiConversation::new())
+        .manage(commands::ai_functions::AiCancel::new())
+        .manage(commands::compose::ComposeState::new())
+        .manage(commands::output::PreviewState::new())
+        .manage(system::folder_watch::FolderWatchState::new())
+        .manage(system::hid_input::HidInputState::new())
+        .manage(system::notifications::CompletionNotice::new())
+        .manage(commands::clipboard::RecentOutputs::new())
+        .manage(commands::clipboard::LastPaste::new())
+        .manage(system::focus_target::TargetLock::new())
         .setup(|app| {
             let app_handle = app.handle().clone();
 
@@ -43,6 +60,11 @@ pub fn run() {
                 .join("sobottaai.db");
             db::initialize(&db_path).expect("failed to initialize database");
 
+            let settings = db::settings::load();
+            app.state::<commands::recording::RecordingState>()
+                .set_incognito(settings.incognito);
+            i18n::set_locale(&settings.ui_language);
+
             // Setup system tray
             system::tray::setup_tray(&app_handle)?;
 
@@ -52,17 +74,63 @@ pub fn run() {
                 log::error!("Failed to pre-create recording bar: {}", e);
             }
 
+            // Tell the frontend when an AI request waits behind others
+            let queue_handle = app_handle.clone();
+            llm::set_queue_listener(move |position| {
+                let _ = queue_handle.emit("llm-queue", position);
+            });
+
+            stt::set_threads(settings.inference_threads, settings.low_power_mode);
+            match stt::parakeet::Provider::parse(&settings.parakeet_provider) {
+                Ok(provider) => stt::parakeet::set_provider(provider),
+                Err(e) => log::warn!("Ignoring Parakeet provider setting: {}", e),
+            }
+
             // Register global hotkey for push-to-talk
             if let Err(e) = system::hotkey::register_hotkey(&app_handle) {
                 log::error!("Failed to register global hotkey: {:?}", e);
             }
 
+            match system::mouse_trigger::MouseButton::parse(&settings.mouse_button) {
+                Ok(Some(button)) => system::mouse_trigger::set_button(&app_handle, Some(button)),
+                Ok(None) => {}
+                Err(e) => log::warn!("Ignoring mouse button setting: {}", e),
+            }
+
+            if let Err(e) = system::folder_watch::restart(&app_handle) {
+                log::error!("Failed to watch folders: {}", e);
+            }
+
+            if let Err(e) = system::hid_input::restart(&app_handle) {
+                log::error!("Failed to listen to HID devices: {}", e);
+            }
+
+            // Automation URLs and audio files passed at launch; macOS sends
+            // them as RunEvent::Opened
+            let cwd = std::env::current_dir().unwrap_or_default();
+            let urls = system::automation::urls_from_args(std::env::args().skip(1), &cwd);
+            system::automation::handle_urls(&app_handle, &urls);
+
             log::info!("SobottaAI started successfully");
             Ok(())
         })
-        // Intercept window close: hide instead of destroying the window.
-        // This lets the app keep running in the system tray.
         .on_window_event(|window, event| {
+            // Audio files dropped on the main window are imported and transcribed
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                if window.label() == "main" {
+                    commands::audio_import::import_dropped(window.app_handle(), paths.clone());
+                }
+            }
+
+            // Opening the window soon after a completion notification shows History
+            if let tauri::WindowEvent::Focused(true) = event {
+                if window.label() == "main" {
+                    system::notifications::main_window_shown(window.app_handle());
+                }
+            }
+
+            // Intercept window close: hide instead of destroying the window.
+            // This lets the app keep running in the system tray.
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 if window.label() == "main" {
                     api.prevent_close();
@@ -83,44 +151,164 @@ pub fn run() {
             // Recording
             commands::recording::start_recording,
             commands::recording::stop_recording,
+            commands::recording::test_microphone,
             commands::recording::show_recording_bar,
             commands::recording::hide_recording_bar,
+            commands::recording::set_recording_bar_position,
+            commands::recording::list_monitors,
+            commands::recording::release_session,
+            commands::recording::recover_sessions,
+            commands::recording::discard_session,
             // Transcription
             commands::transcription::transcribe,
             commands::transcription::transcribe_file,
+            commands::jobs::get_job_status,
+            commands::jobs::cancel_job,
             // Models
             commands::models::list_models,
+            commands::models::is_vad_model_downloaded,
+            commands::models::download_vad_model,
+            commands::models::is_punctuation_model_downloaded,
+            commands::models::download_punctuation_model,
             commands::models::download_model,
             commands::models::delete_model,
+            commands::models::benchmark_model,
+            commands::models::model_disk_usage,
             // AI Functions & Rules
             commands::ai_functions::list_ai_functions,
             commands::ai_functions::execute_ai_function,
+            commands::ai_functions::rewrite_selection,
             commands::ai_functions::save_ai_function,
+            commands::ai_functions::refine_last_output,
+            commands::ai_functions::clear_ai_cache,
+            commands::ai_functions::cancel_ai_function,
+            commands::ai_functions::set_ai_function_output,
+            commands::output::deliver_output,
+            commands::output::get_output_preview,
+            commands::output::paste_output_preview,
+            commands::compose::start_compose,
+            commands::compose::get_compose,
+            commands::compose::compose_append,
+            commands::compose::finish_compose,
+            commands::compose::discard_compose,
+            commands::notes::list_notes,
+            commands::notes::create_note,
+            commands::notes::rename_note,
+            commands::notes::delete_note,
+            commands::notes::attach_to_note,
+            commands::notes::detach_from_note,
+            commands::notes::reorder_note,
+            commands::notes::get_note_text,
+            commands::notes::export_note,
+            commands::pipelines::list_pipelines,
+            commands::pipelines::save_pipeline,
+            commands::pipelines::delete_pipeline,
+            commands::pipelines::run_pipeline,
+            commands::ai_functions::export_ai_functions,
+            commands::ai_functions::import_ai_functions,
             commands::ai_functions::delete_ai_function,
             commands::ai_functions::apply_rules,
+            commands::rules::list_rules,
+            commands::rules::set_rule_enabled,
+            commands::rules::reorder_rules,
+            commands::rules::add_rule,
+            commands::rules::delete_rule,
+            commands::rules::export_rules,
+            commands::rules::import_rules,
+            // Providers
+            commands::providers::list_ollama_models,
+            commands::providers::test_llm_provider,
+            commands::providers::test_stt_provider,
             // History
             commands::history::get_history,
             commands::history::search_history,
             commands::history::get_history_item,
             commands::history::delete_history_item,
+            commands::history::edit_history_transcript,
             commands::history::save_history_item,
+            commands::history::get_waveform_peaks,
             // Settings
             commands::settings::get_settings,
             commands::settings::save_settings,
             commands::settings::update_hotkey,
+            commands::settings::update_rewrite_hotkey,
+            commands::settings::update_paste_last_hotkey,
+            commands::settings::update_undo_paste_hotkey,
             commands::settings::update_recording_mode,
+            commands::settings::set_hotkey_backend,
+            commands::settings::set_mouse_button,
+            commands::settings::set_inference_threads,
+            commands::settings::parakeet_providers,
+            commands::settings::set_parakeet_provider,
+            commands::settings::set_ui_language,
             commands::settings::sync_tray,
+            commands::settings::set_incognito,
+            commands::settings::get_incognito,
+            commands::settings::set_autostart,
+            commands::settings::get_autostart,
+            commands::settings::get_storage_dir,
+            commands::settings::set_storage_dir,
+            commands::settings::get_database_encryption,
+            commands::settings::set_database_encryption,
+            commands::settings::maintain_database,
             commands::settings::restart_app,
+            commands::diagnostics::collect_diagnostics,
+            // Updater
+            commands::updater::get_update_channel,
+            commands::updater::set_update_channel,
+            commands::updater::check_for_updates,
+            commands::updater::install_update,
+            // Permissions
+            commands::permissions::check_permissions,
+            commands::permissions::request_permissions,
+            // Hotkey bindings
+            commands::hotkey_bindings::list_hotkey_bindings,
+            commands::hotkey_bindings::save_hotkey_binding,
+            commands::hotkey_bindings::delete_hotkey_binding,
             // Vocabulary
             commands::vocabulary::get_vocabulary,
             commands::vocabulary::add_term,
             commands::vocabulary::delete_term,
+            commands::vocabulary::suggest_vocabulary,
             // Clipboard
             commands::clipboard::paste_text,
+            commands::clipboard::get_recent_outputs,
+            commands::clipboard::undo_last_paste,
             // Audio Import
             commands::audio_import::import_audio_file,
+            // Watched folders
+            commands::watch_folders::list_watch_folders,
+            commands::watch_folders::save_watch_folder,
+            commands::watch_folders::delete_watch_folder,
+            // Device bindings
+            commands::device_bindings::list_device_bindings,
+            commands::device_bindings::save_device_binding,
+            commands::device_bindings::delete_device_binding,
+            commands::device_bindings::list_hid_devices,
+            commands::device_bindings::capture_hid_button,
+            // App profiles
+            commands::app_profiles::list_app_profiles,
+            commands::app_profiles::save_app_profile,
+            commands::app_profiles::delete_app_profile,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_app_handle, _event| {});
+        .run(|_app_handle, _event| {
+            // sobottaai:// URLs from Shortcuts, AppleScript and `open`, and
+            // audio files opened with the app
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = &_event {
+                system::automation::handle_urls(_app_handle, urls);
+            }
+
+            // Clicking the dock icon, or a notification, with the window hidden
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Reopen {
+                has_visible_windows: false,
+                ..
+            } = &_event
+            {
+                system::tray::show_main_window(_app_handle);
+            }
+        });
 }
@@ -1,10 +1,14 @@
-use super::{LlmConfig, LlmProvider};
+use super::{sse, ChatMessage, LlmConfig, LlmProvider, OnDelta};
 use reqwest::Client;
 use serde_json::json;
 use std::time::Duration;
 
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
 pub struct AnthropicProvider {
     client: Client,
+    /// Limit on a whole reply, or on the wait for each chunk when streaming.
+    timeout: Duration,
     api_key: String,
     model: String,
 }
@@ -13,37 +17,64 @@ impl AnthropicProvider {
     pub fn new(config: &LlmConfig) -> Self {
         Self {
             client: Client::builder()
-                .timeout(Duration::from_secs(30))
+                .connect_timeout(super::CONNECT_TIMEOUT)
                 .build()
                 .unwrap_or_else(|_| Client::new()),
+            timeout: config.timeout(DEFAULT_TIMEOUT_SECS),
             api_key: config.api_key.clone().unwrap_or_default(),
             model: config.model.clone(),
         }
     }
+
+    fn request(&self, body: serde_json::Value) -> reqwest::RequestBuilder {
+        self.client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&body)
+    }
+}
+
+/// What one streamed Messages API event means for the reply.
+#[derive(Debug, PartialEq)]
+enum StreamEvent<'a> {
+    Text(&'a str),
+    Stop,
+    Other,
+}
+
+fn stream_event(event: &serde_json::Value) -> anyhow::Result<StreamEvent<'_>> {
+    Ok(match event["type"].as_str() {
+        Some("content_block_delta") => match event["delta"]["text"].as_str() {
+            Some(text) => StreamEvent::Text(text),
+            None => StreamEvent::Other,
+        },
+        Some("message_stop") => StreamEvent::Stop,
+        Some("error") => anyhow::bail!(
+            "Anthropic API error: {}",
+            event["error"]["message"].as_str().unwrap_or("Unknown error")
+        ),
+        _ => StreamEvent::Other,
+    })
 }
 
 #[async_trait::async_trait]
 impl LlmProvider for AnthropicProvider {
-    async fn complete(
+    async fn chat(
         &self,
         system_prompt: &str,
-        user_message: &str,
+        messages: &[ChatMessage],
     ) -> anyhow::Result<String> {
         log::info!("Anthropic: calling model={}", self.model);
 
         let response = self
-            .client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .json(&json!({
+            .request(json!({
                 "model": self.model,
                 "max_tokens": 4096,
                 "system": system_prompt,
-                "messages": [
-                    { "role": "user", "content": user_message }
-                ]
+                "messages": messages
             }))
+            .timeout(self.timeout)
             .send()
             .await?;
 
@@ -65,4 +96,79 @@ impl LlmProvider for AnthropicProvider {
         log::info!("Anthropic: response received ({} chars)", text.len());
         Ok(text)
     }
+
+    async fn chat_stream(
+        &self,
+        system_prompt: &str,
+        messages: &[ChatMessage],
+        on_delta: &OnDelta<'_>,
+    ) -> anyhow::Result<String> {
+        log::info!("Anthropic: streaming model={}", self.model);
+
+        let request = self.request(json!({
+            "model": self.model,
+            "max_tokens": 4096,
+            "system": system_prompt,
+            "messages": messages,
+            "stream": true
+        }));
+        let response = sse::send(request, self.timeout).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let err_msg = body["error"]["message"]
+                .as_str()
+                .unwrap_or("Unknown error");
+            anyhow::bail!("Anthropic API error ({}): {}", status, err_msg);
+        }
+
+        let mut text = String::new();
+        sse::read_data(response, self.timeout, |data| {
+            let event: serde_json::Value = serde_json::from_str(data)?;
+            match stream_event(&event)? {
+                StreamEvent::Text(delta) => {
+                    on_delta(delta);
+                    text.push_str(delta);
+                }
+                StreamEvent::Stop => return Ok(false),
+                StreamEvent::Other => {}
+            }
+            Ok(true)
+        })
+        .await?;
+
+        log::info!("Anthropic: stream finished ({} chars)", text.len());
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_event_reads_text_stop_and_errors() {
+        let delta = json!({
+            "type": "content_block_delta",
+            "index": 0,
+            "delta": { "type": "text_delta", "text": "Hi" }
+        });
+        assert_eq!(stream_event(&delta).unwrap(), StreamEvent::Text("Hi"));
+        assert_eq!(
+            stream_event(&json!({ "type": "message_stop" })).unwrap(),
+            StreamEvent::Stop
+        );
+        assert_eq!(
+            stream_event(&json!({ "type": "ping" })).unwrap(),
+            StreamEvent::Other
+        );
+
+        let error = json!({
+            "type": "error",
+            "error": { "type": "overloaded_error", "message": "Overloaded" }
+        });
+        let err = stream_event(&error).unwrap_err();
+        assert_eq!(err.to_string(), "Anthropic API error: Overloaded");
+    }
 }
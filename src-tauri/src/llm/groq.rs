@@ -1,7 +1,8 @@
-use super::{LlmConfig, LlmProvider};
+use super::{chat_messages, ChatMessage, LlmConfig, LlmProvider};
 use reqwest::Client;
 use serde_json::json;
-use std::time::Duration;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
 pub struct GroqProvider {
     client: Client,
@@ -13,7 +14,7 @@ impl GroqProvider {
     pub fn new(config: &LlmConfig) -> Self {
         Self {
             client: Client::builder()
-                .timeout(Duration::from_secs(30))
+                .timeout(config.timeout(DEFAULT_TIMEOUT_SECS))
                 .build()
                 .unwrap_or_else(|_| Client::new()),
             api_key: config.api_key.clone().unwrap_or_default(),
@@ -24,10 +25,10 @@ impl GroqProvider {
 
 #[async_trait::async_trait]
 impl LlmProvider for GroqProvider {
-    async fn complete(
+    async fn chat(
         &self,
         system_prompt: &str,
-        user_message: &str,
+        messages: &[ChatMessage],
     ) -> anyhow::Result<String> {
         log::info!("Groq: calling model={}", self.model);
 
@@ -37,10 +38,7 @@ impl LlmProvider for GroqProvider {
             .header("Authorization", format!("Bearer {}", self.api_key))
             .json(&json!({
                 "model": self.model,
-                "messages": [
-                    { "role": "system", "content": system_prompt },
-                    { "role": "user", "content": user_message }
-                ]
+                "messages": chat_messages(system_prompt, messages)
             }))
             .send()
             .await?;
@@ -2,8 +2,19 @@ pub mod anthropic;
 pub mod groq;
 pub mod ollama;
 pub mod openai;
+pub mod sse;
 
+use once_cell::sync::{Lazy, OnceCell};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// How long cloud providers get to accept a connection. Replies themselves
+/// are limited by the configured timeout instead.
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmConfig {
@@ -11,6 +22,23 @@ pub struct LlmConfig {
     pub api_key: Option<String>,
     pub model: String,
     pub base_url: Option<String>,
+    /// Request timeout in seconds; `None` keeps the provider's default.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Ollama-only generation options and `keep_alive`; ignored by cloud providers.
+    #[serde(default)]
+    pub ollama: Option<ollama::OllamaOptions>,
+}
+
+impl LlmConfig {
+    /// Configured request timeout, or `default_secs` when unset or zero.
+    pub fn timeout(&self, default_secs: u64) -> Duration {
+        Duration::from_secs(
+            self.timeout_secs
+                .filter(|&secs| secs > 0)
+                .unwrap_or(default_secs),
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,24 +49,337 @@ pub enum LlmProviderType {
     Ollama,
 }
 
+impl LlmProviderType {
+    /// Provider id as used in settings, e.g. `"openai"`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::OpenAI => "openai",
+            Self::Anthropic => "anthropic",
+            Self::Groq => "groq",
+            Self::Ollama => "ollama",
+        }
+    }
+
+    /// Model to use when none is configured; matches the settings page.
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            Self::OpenAI => "gpt-4o-mini",
+            Self::Anthropic => "claude-sonnet-4-5-20250929",
+            Self::Groq => "llama-3.3-70b-versatile",
+            Self::Ollama => "llama3.2",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+/// One turn of a conversation after the system prompt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::Assistant,
+            content: content.into(),
+        }
+    }
+}
+
+/// `messages` for chat-completions style APIs: the system prompt first, then
+/// the conversation.
+pub fn chat_messages(system_prompt: &str, messages: &[ChatMessage]) -> serde_json::Value {
+    std::iter::once(serde_json::json!({ "role": "system", "content": system_prompt }))
+        .chain(messages.iter().map(|m| serde_json::json!(m)))
+        .collect()
+}
+
+/// Receives each piece of a streamed reply.
+pub type OnDelta<'a> = dyn Fn(&str) + Send + Sync + 'a;
+
 #[async_trait::async_trait]
 pub trait LlmProvider: Send + Sync {
+    /// Reply to a conversation that ends with a user message.
+    async fn chat(
+        &self,
+        system_prompt: &str,
+        messages: &[ChatMessage],
+    ) -> anyhow::Result<String>;
+
+    /// Like `chat`, but calls `on_delta` with each piece of the reply as it
+    /// arrives. Providers without streaming deliver the reply in one piece.
+    async fn chat_stream(
+        &self,
+        system_prompt: &str,
+        messages: &[ChatMessage],
+        on_delta: &OnDelta<'_>,
+    ) -> anyhow::Result<String> {
+        let text = self.chat(system_prompt, messages).await?;
+        on_delta(&text);
+        Ok(text)
+    }
+
+    /// Single-turn shorthand for `chat`.
     async fn complete(
         &self,
         system_prompt: &str,
         user_message: &str,
-    ) -> anyhow::Result<String>;
+    ) -> anyhow::Result<String> {
+        self.chat(system_prompt, &[ChatMessage::user(user_message)]).await
+    }
 }
 
+/// Build a provider whose requests wait their turn in the provider's queue.
 pub fn create_provider(config: &LlmConfig) -> Box<dyn LlmProvider> {
-    match config.provider {
+    let inner: Box<dyn LlmProvider> = match config.provider {
         LlmProviderType::OpenAI => Box::new(openai::OpenAiProvider::new(config)),
         LlmProviderType::Anthropic => Box::new(anthropic::AnthropicProvider::new(config)),
         LlmProviderType::Groq => Box::new(groq::GroqProvider::new(config)),
         LlmProviderType::Ollama => Box::new(ollama::OllamaProvider::new(config)),
+    };
+    Box::new(Queued {
+        provider: config.provider.id(),
+        inner,
+    })
+}
+
+/// Requests sent to one provider at the same time. Further requests wait,
+/// so a burst of AI functions doesn't run into the provider's rate limits.
+const MAX_CONCURRENT_REQUESTS: usize = 2;
+
+/// A waiting request's place in its provider's queue, sent to the queue
+/// listener whenever it changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuePosition {
+    pub provider: &'static str,
+    pub request_id: u64,
+    /// 1 for the next request to run; 0 once the request has started.
+    pub position: usize,
+}
+
+type QueueListener = Box<dyn Fn(&QueuePosition) + Send + Sync>;
+
+static QUEUE_LISTENER: OnceCell<QueueListener> = OnceCell::new();
+static QUEUES: Lazy<Mutex<HashMap<&'static str, Arc<ProviderQueue>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Receive queue position changes, e.g. to forward them to the frontend.
+/// Only the first listener set is kept.
+pub fn set_queue_listener(listener: impl Fn(&QueuePosition) + Send + Sync + 'static) {
+    let _ = QUEUE_LISTENER.set(Box::new(listener));
+}
+
+/// Limits the requests in flight to one provider, first come first served.
+struct ProviderQueue {
+    permits: Arc<Semaphore>,
+    /// Ids of the requests waiting for a permit, oldest first.
+    waiting: Mutex<VecDeque<u64>>,
+}
+
+impl ProviderQueue {
+    fn new(max_concurrent: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            waiting: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn for_provider(provider: &'static str) -> Arc<Self> {
+        QUEUES
+            .lock()
+            .unwrap()
+            .entry(provider)
+            .or_insert_with(|| Arc::new(Self::new(MAX_CONCURRENT_REQUESTS)))
+            .clone()
+    }
+
+    /// Wait for a free slot. The permit holds it until dropped.
+    async fn acquire(&self, provider: &'static str) -> OwnedSemaphorePermit {
+        if let Ok(permit) = self.permits.clone().try_acquire_owned() {
+            return permit;
+        }
+        let request_id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        self.waiting.lock().unwrap().push_back(request_id);
+        // Leaves the queue even if the caller gives up while waiting
+        let _waiting = Waiting {
+            queue: self,
+            provider,
+            request_id,
+        };
+        self.notify(provider);
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("provider queue semaphore is never closed");
+        notify_listener(&QueuePosition {
+            provider,
+            request_id,
+            position: 0,
+        });
+        permit
+    }
+
+    /// Positions of the waiting requests, next to run first.
+    fn positions(&self, provider: &'static str) -> Vec<QueuePosition> {
+        self.waiting
+            .lock()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(i, &request_id)| QueuePosition {
+                provider,
+                request_id,
+                position: i + 1,
+            })
+            .collect()
+    }
+
+    fn notify(&self, provider: &'static str) {
+        for position in self.positions(provider) {
+            notify_listener(&position);
+        }
+    }
+}
+
+/// Removes a request from the waiting list when it starts or is dropped,
+/// moving everyone behind it up.
+struct Waiting<'a> {
+    queue: &'a ProviderQueue,
+    provider: &'static str,
+    request_id: u64,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.queue
+            .waiting
+            .lock()
+            .unwrap()
+            .retain(|&id| id != self.request_id);
+        self.queue.notify(self.provider);
+    }
+}
+
+fn notify_listener(position: &QueuePosition) {
+    if let Some(listener) = QUEUE_LISTENER.get() {
+        listener(position);
+    }
+}
+
+/// Waits for a slot in the provider's queue before each request.
+struct Queued {
+    provider: &'static str,
+    inner: Box<dyn LlmProvider>,
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for Queued {
+    async fn chat(
+        &self,
+        system_prompt: &str,
+        messages: &[ChatMessage],
+    ) -> anyhow::Result<String> {
+        let _permit = ProviderQueue::for_provider(self.provider)
+            .acquire(self.provider)
+            .await;
+        self.inner.chat(system_prompt, messages).await
+    }
+
+    async fn chat_stream(
+        &self,
+        system_prompt: &str,
+        messages: &[ChatMessage],
+        on_delta: &OnDelta<'_>,
+    ) -> anyhow::Result<String> {
+        let _permit = ProviderQueue::for_provider(self.provider)
+            .acquire(self.provider)
+            .await;
+        self.inner.chat_stream(system_prompt, messages, on_delta).await
     }
 }
 
+/// Tries each provider in order and returns the first non-empty reply, so a
+/// stopped Ollama or a failing cloud call falls through to the next one.
+pub struct FallbackProvider {
+    providers: Vec<(String, Box<dyn LlmProvider>)>,
+}
+
+impl FallbackProvider {
+    pub fn new(providers: Vec<(String, Box<dyn LlmProvider>)>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for FallbackProvider {
+    async fn chat(
+        &self,
+        system_prompt: &str,
+        messages: &[ChatMessage],
+    ) -> anyhow::Result<String> {
+        self.chat_stream(system_prompt, messages, &|_| {}).await
+    }
+
+    /// Streams from each provider in turn. Deltas already sent by a provider
+    /// that then fails are not taken back.
+    async fn chat_stream(
+        &self,
+        system_prompt: &str,
+        messages: &[ChatMessage],
+        on_delta: &OnDelta<'_>,
+    ) -> anyhow::Result<String> {
+        let mut errors = Vec::new();
+        for (label, provider) in &self.providers {
+            match provider.chat_stream(system_prompt, messages, on_delta).await {
+                Ok(text) if !text.trim().is_empty() => return Ok(text),
+                Ok(_) => errors.push(format!("{}: empty response", label)),
+                Err(e) => {
+                    log::warn!("LLM fallback: {} failed: {}", label, e);
+                    errors.push(format!("{}: {}", label, e));
+                }
+            }
+        }
+        match errors.len() {
+            0 => anyhow::bail!("No LLM provider configured"),
+            1 => anyhow::bail!("{}", errors.remove(0)),
+            _ => anyhow::bail!("All providers failed — {}", errors.join("; ")),
+        }
+    }
+}
+
+/// Build a provider for an ordered chain of configs. A single config is used
+/// directly; more are wrapped in a `FallbackProvider`.
+pub fn create_provider_chain(configs: &[LlmConfig]) -> Box<dyn LlmProvider> {
+    if let [config] = configs {
+        return create_provider(config);
+    }
+    Box::new(FallbackProvider::new(
+        configs
+            .iter()
+            .map(|c| (format!("{:?} ({})", c.provider, c.model), create_provider(c)))
+            .collect(),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,6 +390,8 @@ mod tests {
             api_key: Some("test-key".into()),
             model: "test-model".into(),
             base_url: None,
+            timeout_secs: None,
+            ollama: None,
         }
     }
 
@@ -83,6 +426,8 @@ mod tests {
             api_key: None,
             model: "llama3".into(),
             base_url: Some("http://custom:8080".into()),
+            timeout_secs: None,
+            ollama: None,
         };
         let _provider = create_provider(&config);
     }
@@ -94,10 +439,115 @@ mod tests {
             api_key: None,
             model: "gpt-4".into(),
             base_url: None,
+            timeout_secs: None,
+            ollama: None,
         };
         let _provider = create_provider(&config); // should not panic
     }
 
+    /// Test double that returns a canned reply or error.
+    struct StubProvider(Result<&'static str, &'static str>);
+
+    #[async_trait::async_trait]
+    impl LlmProvider for StubProvider {
+        async fn chat(&self, _: &str, _: &[ChatMessage]) -> anyhow::Result<String> {
+            self.0
+                .map(str::to_string)
+                .map_err(|e| anyhow::anyhow!(e))
+        }
+    }
+
+    fn stub(
+        label: &str,
+        reply: Result<&'static str, &'static str>,
+    ) -> (String, Box<dyn LlmProvider>) {
+        (label.to_string(), Box::new(StubProvider(reply)))
+    }
+
+    #[tokio::test]
+    async fn fallback_uses_first_success() {
+        let chain = FallbackProvider::new(vec![
+            stub("ollama", Err("connection refused")),
+            stub("groq", Ok("from groq")),
+            stub("openai", Ok("from openai")),
+        ]);
+        assert_eq!(chain.complete("sys", "hi").await.unwrap(), "from groq");
+    }
+
+    #[tokio::test]
+    async fn fallback_skips_empty_responses() {
+        let chain = FallbackProvider::new(vec![
+            stub("ollama", Ok("  ")),
+            stub("openai", Ok("from openai")),
+        ]);
+        assert_eq!(chain.complete("sys", "hi").await.unwrap(), "from openai");
+    }
+
+    #[tokio::test]
+    async fn fallback_reports_every_failure() {
+        let chain = FallbackProvider::new(vec![
+            stub("ollama", Err("connection refused")),
+            stub("groq", Err("429 rate limited")),
+        ]);
+        let err = chain.complete("sys", "hi").await.unwrap_err().to_string();
+        assert!(err.contains("ollama: connection refused"));
+        assert!(err.contains("groq: 429 rate limited"));
+    }
+
+    #[tokio::test]
+    async fn fallback_without_providers_errors() {
+        let chain = FallbackProvider::new(vec![]);
+        assert!(chain.complete("sys", "hi").await.is_err());
+    }
+
+    #[test]
+    fn chat_messages_put_system_prompt_first() {
+        let messages = chat_messages(
+            "Be brief.",
+            &[
+                ChatMessage::user("hello there"),
+                ChatMessage::assistant("Hi."),
+                ChatMessage::user("more formal"),
+            ],
+        );
+        assert_eq!(messages[0]["role"], "system");
+        assert_eq!(messages[0]["content"], "Be brief.");
+        assert_eq!(messages[2]["role"], "assistant");
+        assert_eq!(messages[3]["content"], "more formal");
+        assert_eq!(messages.as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn timeout_uses_default_when_unset() {
+        let config = make_config(LlmProviderType::OpenAI);
+        assert_eq!(config.timeout(30), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn timeout_uses_configured_value() {
+        let config = LlmConfig {
+            timeout_secs: Some(300),
+            ..make_config(LlmProviderType::Ollama)
+        };
+        assert_eq!(config.timeout(60), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn timeout_zero_falls_back_to_default() {
+        let config = LlmConfig {
+            timeout_secs: Some(0),
+            ..make_config(LlmProviderType::Groq)
+        };
+        assert_eq!(config.timeout(30), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn llm_config_without_timeout_deserializes() {
+        let json = r#"{"provider":"Ollama","api_key":null,"model":"llama3","base_url":null}"#;
+        let config: LlmConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.timeout_secs, None);
+    }
+
     #[test]
     fn llm_config_serialization_roundtrip() {
         let config = make_config(LlmProviderType::OpenAI);
@@ -124,4 +574,31 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn provider_queue_runs_in_order_and_drops_cancelled() {
+        let queue = Arc::new(ProviderQueue::new(1));
+        let first = queue.acquire("test").await;
+
+        let waiter = |queue: Arc<ProviderQueue>| {
+            tokio::spawn(async move {
+                let _permit = queue.acquire("test").await;
+            })
+        };
+        let second = waiter(queue.clone());
+        tokio::task::yield_now().await;
+        let third = waiter(queue.clone());
+        tokio::task::yield_now().await;
+        let positions: Vec<usize> = queue.positions("test").iter().map(|p| p.position).collect();
+        assert_eq!(positions, [1, 2]);
+
+        // A request that gives up leaves the queue
+        second.abort();
+        let _ = second.await;
+        assert_eq!(queue.positions("test").len(), 1);
+
+        drop(first);
+        third.await.unwrap();
+        assert!(queue.positions("test").is_empty());
+    }
 }
@@ -1,50 +1,69 @@
-use super::{LlmConfig, LlmProvider};
+use super::{chat_messages, ChatMessage, LlmConfig, LlmProvider};
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::time::Duration;
 
+pub const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+/// Local generation on CPU can be slow, so Ollama gets a longer default.
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// Ollama request tuning passed through from settings. Unset fields keep
+/// the server's defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaOptions {
+    /// Context window in tokens (`options.num_ctx`).
+    pub num_ctx: Option<u32>,
+    /// Sampling temperature (`options.temperature`).
+    pub temperature: Option<f32>,
+    /// How long the model stays loaded after a request: a duration like
+    /// `"30m"`, or seconds where `-1` keeps it loaded indefinitely.
+    pub keep_alive: Option<String>,
+}
+
 pub struct OllamaProvider {
     client: Client,
     base_url: String,
     model: String,
+    options: OllamaOptions,
 }
 
 impl OllamaProvider {
     pub fn new(config: &LlmConfig) -> Self {
         Self {
             client: Client::builder()
-                .timeout(Duration::from_secs(60))
+                .timeout(config.timeout(DEFAULT_TIMEOUT_SECS))
                 .build()
                 .unwrap_or_else(|_| Client::new()),
             base_url: config
                 .base_url
                 .clone()
-                .unwrap_or_else(|| "http://localhost:11434".to_string()),
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
             model: config.model.clone(),
+            options: config.ollama.clone().unwrap_or_default(),
         }
     }
 }
 
 #[async_trait::async_trait]
 impl LlmProvider for OllamaProvider {
-    async fn complete(
+    async fn chat(
         &self,
         system_prompt: &str,
-        user_message: &str,
+        messages: &[ChatMessage],
     ) -> anyhow::Result<String> {
         log::info!("Ollama: calling model={} at {}", self.model, self.base_url);
 
         let response = self
             .client
             .post(format!("{}/api/chat", self.base_url))
-            .json(&json!({
-                "model": self.model,
-                "stream": false,
-                "messages": [
-                    { "role": "system", "content": system_prompt },
-                    { "role": "user", "content": user_message }
-                ]
-            }))
+            .json(&chat_body(
+                &self.model,
+                &self.options,
+                system_prompt,
+                messages,
+            ))
             .send()
             .await?;
 
@@ -67,3 +86,139 @@ impl LlmProvider for OllamaProvider {
         Ok(text)
     }
 }
+
+/// Build the `/api/chat` request, adding `options` and `keep_alive` only
+/// when configured.
+fn chat_body(
+    model: &str,
+    options: &OllamaOptions,
+    system_prompt: &str,
+    messages: &[ChatMessage],
+) -> serde_json::Value {
+    let mut body = json!({
+        "model": model,
+        "stream": false,
+        "messages": chat_messages(system_prompt, messages),
+    });
+
+    let mut opts = serde_json::Map::new();
+    if let Some(num_ctx) = options.num_ctx {
+        opts.insert("num_ctx".into(), json!(num_ctx));
+    }
+    if let Some(temperature) = options.temperature {
+        opts.insert("temperature".into(), json!(temperature));
+    }
+    if !opts.is_empty() {
+        body["options"] = serde_json::Value::Object(opts);
+    }
+
+    if let Some(keep_alive) = options.keep_alive.as_deref().map(str::trim) {
+        if !keep_alive.is_empty() {
+            // Ollama treats a bare number as seconds but rejects it as a string
+            body["keep_alive"] = match keep_alive.parse::<i64>() {
+                Ok(secs) => json!(secs),
+                Err(_) => json!(keep_alive),
+            };
+        }
+    }
+
+    body
+}
+
+/// List the models installed on an Ollama server via `GET /api/tags`.
+pub async fn list_models(base_url: Option<&str>) -> anyhow::Result<Vec<String>> {
+    let base_url = base_url
+        .map(|u| u.trim().trim_end_matches('/'))
+        .filter(|u| !u.is_empty())
+        .unwrap_or(DEFAULT_BASE_URL);
+
+    let response = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()?
+        .get(format!("{}/api/tags", base_url))
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("Could not reach Ollama at {}: {}", base_url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("Ollama API error ({})", status);
+    }
+
+    let body: serde_json::Value = response.json().await?;
+    Ok(parse_model_names(&body))
+}
+
+/// Extract sorted model names from an `/api/tags` response.
+fn parse_model_names(body: &serde_json::Value) -> Vec<String> {
+    let mut names: Vec<String> = body["models"]
+        .as_array()
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|m| m["name"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chat_body_omits_unset_options() {
+        let body = chat_body("llama3.2", &OllamaOptions::default(), "sys", &[ChatMessage::user("hi")]);
+        assert_eq!(body["model"], "llama3.2");
+        assert_eq!(body["messages"][1]["content"], "hi");
+        assert!(body.get("options").is_none());
+        assert!(body.get("keep_alive").is_none());
+    }
+
+    #[test]
+    fn chat_body_includes_options_and_keep_alive() {
+        let options = OllamaOptions {
+            num_ctx: Some(8192),
+            temperature: Some(0.5),
+            keep_alive: Some("30m".into()),
+        };
+        let body = chat_body("llama3.2", &options, "sys", &[ChatMessage::user("hi")]);
+        assert_eq!(body["options"]["num_ctx"], 8192);
+        assert_eq!(body["options"]["temperature"], 0.5);
+        assert!(body["options"].get("top_p").is_none());
+        assert_eq!(body["keep_alive"], "30m");
+    }
+
+    #[test]
+    fn chat_body_numeric_keep_alive_sent_as_number() {
+        let options = OllamaOptions {
+            keep_alive: Some("-1".into()),
+            ..Default::default()
+        };
+        let body = chat_body("llama3.2", &options, "sys", &[ChatMessage::user("hi")]);
+        assert_eq!(body["keep_alive"], -1);
+    }
+
+    #[test]
+    fn parse_model_names_sorted() {
+        let body = json!({
+            "models": [
+                { "name": "qwen2.5:7b", "size": 4683087332u64 },
+                { "name": "llama3.2:latest", "size": 2019393189u64 }
+            ]
+        });
+        assert_eq!(
+            parse_model_names(&body),
+            vec!["llama3.2:latest", "qwen2.5:7b"]
+        );
+    }
+
+    #[test]
+    fn parse_model_names_empty_or_malformed() {
+        assert!(parse_model_names(&json!({ "models": [] })).is_empty());
+        assert!(parse_model_names(&json!({})).is_empty());
+        assert!(parse_model_names(&json!({ "models": [{ "size": 1 }] })).is_empty());
+    }
+}
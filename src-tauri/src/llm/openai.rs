@@ -1,10 +1,14 @@
-use super::{LlmConfig, LlmProvider};
+use super::{chat_messages, sse, ChatMessage, LlmConfig, LlmProvider, OnDelta};
 use reqwest::Client;
 use serde_json::json;
 use std::time::Duration;
 
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
 pub struct OpenAiProvider {
     client: Client,
+    /// Limit on a whole reply, or on the wait for each chunk when streaming.
+    timeout: Duration,
     api_key: String,
     model: String,
 }
@@ -13,35 +17,43 @@ impl OpenAiProvider {
     pub fn new(config: &LlmConfig) -> Self {
         Self {
             client: Client::builder()
-                .timeout(Duration::from_secs(30))
+                .connect_timeout(super::CONNECT_TIMEOUT)
                 .build()
                 .unwrap_or_else(|_| Client::new()),
+            timeout: config.timeout(DEFAULT_TIMEOUT_SECS),
             api_key: config.api_key.clone().unwrap_or_default(),
             model: config.model.clone(),
         }
     }
+
+    fn request(&self, body: serde_json::Value) -> reqwest::RequestBuilder {
+        self.client
+            .post("https://api.openai.com/v1/chat/completions")
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&body)
+    }
+}
+
+/// Text added by one streamed chat-completions chunk, if any.
+fn stream_delta(chunk: &serde_json::Value) -> Option<&str> {
+    chunk["choices"][0]["delta"]["content"].as_str()
 }
 
 #[async_trait::async_trait]
 impl LlmProvider for OpenAiProvider {
-    async fn complete(
+    async fn chat(
         &self,
         system_prompt: &str,
-        user_message: &str,
+        messages: &[ChatMessage],
     ) -> anyhow::Result<String> {
         log::info!("OpenAI: calling model={}", self.model);
 
         let response = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&json!({
+            .request(json!({
                 "model": self.model,
-                "messages": [
-                    { "role": "system", "content": system_prompt },
-                    { "role": "user", "content": user_message }
-                ]
+                "messages": chat_messages(system_prompt, messages)
             }))
+            .timeout(self.timeout)
             .send()
             .await?;
 
@@ -63,4 +75,60 @@ impl LlmProvider for OpenAiProvider {
         log::info!("OpenAI: response received ({} chars)", text.len());
         Ok(text)
     }
+
+    async fn chat_stream(
+        &self,
+        system_prompt: &str,
+        messages: &[ChatMessage],
+        on_delta: &OnDelta<'_>,
+    ) -> anyhow::Result<String> {
+        log::info!("OpenAI: streaming model={}", self.model);
+
+        let request = self.request(json!({
+            "model": self.model,
+            "messages": chat_messages(system_prompt, messages),
+            "stream": true
+        }));
+        let response = sse::send(request, self.timeout).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let err_msg = body["error"]["message"]
+                .as_str()
+                .unwrap_or("Unknown error");
+            anyhow::bail!("OpenAI API error ({}): {}", status, err_msg);
+        }
+
+        let mut text = String::new();
+        sse::read_data(response, self.timeout, |data| {
+            if data == "[DONE]" {
+                return Ok(false);
+            }
+            let chunk: serde_json::Value = serde_json::from_str(data)?;
+            if let Some(delta) = stream_delta(&chunk) {
+                on_delta(delta);
+                text.push_str(delta);
+            }
+            Ok(true)
+        })
+        .await?;
+
+        log::info!("OpenAI: stream finished ({} chars)", text.len());
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_delta_reads_content_chunks() {
+        let chunk = json!({ "choices": [{ "delta": { "content": "Hel" } }] });
+        assert_eq!(stream_delta(&chunk), Some("Hel"));
+        // The first chunk only carries the role, the last one finish_reason
+        let chunk = json!({ "choices": [{ "delta": { "role": "assistant" } }] });
+        assert_eq!(stream_delta(&chunk), None);
+    }
 }
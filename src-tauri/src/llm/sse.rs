@@ -0,0 +1,125 @@
+//! Minimal server-sent events reader for streamed LLM responses. A stream
+//! has no overall deadline, since a long answer can take minutes; instead
+//! each wait for the response or its next chunk is limited.
+
+use futures_util::{Stream, StreamExt};
+use std::time::Duration;
+
+/// Splits a byte stream into the `data:` payloads of its events. Chunks may
+/// end anywhere, including inside a UTF-8 character.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    /// Feed a chunk and return the data payloads of the lines it completes.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut data = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+            if let Some(payload) = line.strip_prefix("data:") {
+                data.push(payload.strip_prefix(' ').unwrap_or(payload).to_string());
+            }
+        }
+        data
+    }
+}
+
+/// Send a streaming request, waiting at most `idle` for the response headers.
+pub async fn send(
+    request: reqwest::RequestBuilder,
+    idle: Duration,
+) -> anyhow::Result<reqwest::Response> {
+    tokio::time::timeout(idle, request.send())
+        .await
+        .map_err(|_| anyhow::anyhow!("No response after {}s", idle.as_secs()))?
+        .map_err(Into::into)
+}
+
+/// Read `response` as an event stream, passing each data payload to
+/// `on_data` until it returns `false` or the stream ends. Fails when no
+/// chunk arrives for `idle`.
+pub async fn read_data(
+    response: reqwest::Response,
+    idle: Duration,
+    on_data: impl FnMut(&str) -> anyhow::Result<bool>,
+) -> anyhow::Result<()> {
+    read_chunks(response.bytes_stream(), idle, on_data).await
+}
+
+async fn read_chunks<B, E>(
+    stream: impl Stream<Item = Result<B, E>>,
+    idle: Duration,
+    mut on_data: impl FnMut(&str) -> anyhow::Result<bool>,
+) -> anyhow::Result<()>
+where
+    B: AsRef<[u8]>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut parser = SseParser::default();
+    let mut stream = std::pin::pin!(stream);
+    loop {
+        let chunk = tokio::time::timeout(idle, stream.next())
+            .await
+            .map_err(|_| anyhow::anyhow!("Stream stalled for {}s", idle.as_secs()))?;
+        let Some(chunk) = chunk else {
+            return Ok(());
+        };
+        for data in parser.push(chunk?.as_ref()) {
+            if !on_data(&data)? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser_joins_split_lines_and_skips_other_fields() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"event: ping\r\ndata: {\"a\"").is_empty());
+        assert_eq!(
+            parser.push(b":1}\r\n\r\ndata:[DONE]\n"),
+            ["{\"a\":1}", "[DONE]"]
+        );
+    }
+
+    #[test]
+    fn parser_keeps_multibyte_characters_split_across_chunks() {
+        let mut parser = SseParser::default();
+        let line = "data: héllo\n".as_bytes();
+        assert!(parser.push(&line[..8]).is_empty());
+        assert_eq!(parser.push(&line[8..]), ["héllo"]);
+    }
+
+    #[tokio::test]
+    async fn slow_stream_runs_past_the_idle_limit_but_a_stall_fails() {
+        let chunk = |text: &'static str| Ok::<_, std::io::Error>(text.as_bytes());
+        let idle = Duration::from_millis(50);
+        // Each chunk arrives within the limit, though all of them take longer
+        let slow = futures_util::stream::iter(["data: a\n", "data: b\n", "data: c\n"])
+            .then(|text| async move {
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                chunk(text)
+            });
+        let mut seen = Vec::new();
+        read_chunks(slow, idle, |data| {
+            seen.push(data.to_string());
+            Ok(true)
+        })
+        .await
+        .unwrap();
+        assert_eq!(seen, ["a", "b", "c"]);
+
+        let stalled = futures_util::stream::iter([chunk("data: a\n")])
+            .chain(futures_util::stream::pending());
+        assert!(read_chunks(stalled, idle, |_| Ok(true)).await.is_err());
+    }
+}
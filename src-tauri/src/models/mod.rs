@@ -17,6 +17,21 @@ pub struct ModelInfo {
     pub description: String,
 }
 
+impl ModelInfo {
+    /// Whether the model transcribes `language`, an ISO 639-1 code such as
+    /// "es". "auto" (detection) is always allowed.
+    pub fn supports_language(&self, language: &str) -> bool {
+        match (&self.languages, &self.engine) {
+            _ if language == "auto" => true,
+            (LanguageSupport::English, _) => language == "en",
+            (LanguageSupport::Multilingual(_), Engine::Parakeet) => {
+                parakeet_models::V3_LANGUAGES.contains(&language)
+            }
+            (LanguageSupport::Multilingual(_), _) => true,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Engine {
     Whisper,
@@ -49,15 +64,22 @@ pub enum DownloadStatus {
     Failed(String),
 }
 
-pub fn models_dir(app_data_dir: &Path) -> PathBuf {
-    app_data_dir.join("models")
+/// Directory of the Silero VAD model, which isn't a transcription model and
+/// so isn't in the catalog.
+pub const VAD_MODEL_ID: &str = "silero-vad";
+/// Directory of the punctuation model used after Parakeet, likewise not in
+/// the catalog.
+pub const PUNCTUATION_MODEL_ID: &str = "ct-punctuation";
+
+pub fn models_dir(storage_dir: &Path) -> PathBuf {
+    storage_dir.join("models")
 }
 
-pub fn model_path(app_data_dir: &Path, model_id: &str) -> PathBuf {
-    models_dir(app_data_dir).join(model_id)
+pub fn model_path(storage_dir: &Path, model_id: &str) -> PathBuf {
+    models_dir(storage_dir).join(model_id)
 }
 
-pub fn is_model_downloaded(app_data_dir: &Path, model: &ModelInfo) -> bool {
+pub fn is_model_downloaded(storage_dir: &Path, model: &ModelInfo) -> bool {
     // Cloud models don't need downloads
     if matches!(model.engine, Engine::CloudOpenAI | Engine::CloudGroq) {
         return true;
@@ -66,33 +88,182 @@ pub fn is_model_downloaded(app_data_dir: &Path, model: &ModelInfo) -> bool {
     if model.files.is_empty() {
         return false;
     }
-    let dir = model_path(app_data_dir, &model.id);
+    let dir = model_path(storage_dir, &model.id);
     model.files.iter().all(|f| dir.join(f).exists())
 }
 
+/// Free space left over after a download so a model never fills the disk.
+pub const DISK_SPACE_MARGIN_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Bytes still to download: the catalog size minus files already on disk.
+pub fn remaining_download_bytes(storage_dir: &Path, model: &ModelInfo) -> u64 {
+    let dir = model_path(storage_dir, &model.id);
+    let present: u64 = model
+        .files
+        .iter()
+        .filter_map(|f| std::fs::metadata(dir.join(f)).ok())
+        .map(|m| m.len())
+        .sum();
+    model.size_bytes.saturating_sub(present)
+}
+
+/// Refuse a download that wouldn't fit in `available` bytes with the margin.
+pub fn check_disk_space(model_name: &str, needed: u64, available: u64) -> Result<(), String> {
+    if needed.saturating_add(DISK_SPACE_MARGIN_BYTES) <= available {
+        return Ok(());
+    }
+    Err(format!(
+        "Not enough disk space to download {}: needs {} MB, only {} MB free",
+        model_name,
+        needed.div_ceil(1_000_000),
+        available / 1_000_000
+    ))
+}
+
+/// Disk usage of one entry in the models directory.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDiskUsage {
+    pub model_id: String,
+    /// Catalog name; `None` for orphaned entries.
+    pub name: Option<String>,
+    pub size_bytes: u64,
+    /// Not in the catalog (renamed or removed model, or a stray file).
+    pub orphaned: bool,
+}
+
+/// Total size of a file or directory tree. Unreadable entries count as zero.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| dir_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Size of every entry under the models directory, largest first, flagging
+/// entries that don't match a catalog model.
+pub fn disk_usage(storage_dir: &Path, catalog: &[ModelInfo]) -> Vec<ModelDiskUsage> {
+    let Ok(entries) = std::fs::read_dir(models_dir(storage_dir)) else {
+        return vec![];
+    };
+    let mut usage: Vec<ModelDiskUsage> = entries
+        .flatten()
+        .map(|entry| {
+            let model_id = entry.file_name().to_string_lossy().into_owned();
+            let model = catalog.iter().find(|m| m.id == model_id);
+            let auxiliary = match model_id.as_str() {
+                VAD_MODEL_ID => Some("Silero VAD"),
+                PUNCTUATION_MODEL_ID => Some("Punctuation"),
+                _ => None,
+            };
+            ModelDiskUsage {
+                size_bytes: dir_size(&entry.path()),
+                name: model
+                    .map(|m| m.name.clone())
+                    .or_else(|| auxiliary.map(str::to_string)),
+                orphaned: model.is_none() && auxiliary.is_none(),
+                model_id,
+            }
+        })
+        .collect();
+    usage.sort_by_key(|u| std::cmp::Reverse(u.size_bytes));
+    usage
+}
+
+/// A cloud STT catalog entry and the model name sent to the provider's API.
+struct CloudVariant {
+    id: &'static str,
+    name: &'static str,
+    engine: Engine,
+    api_model: &'static str,
+    english_only: bool,
+    description: &'static str,
+}
+
+const CLOUD_VARIANTS: &[CloudVariant] = &[
+    CloudVariant {
+        id: "cloud-openai-whisper",
+        name: "OpenAI Whisper (Cloud)",
+        engine: Engine::CloudOpenAI,
+        api_model: "whisper-1",
+        english_only: false,
+        description: "OpenAI's cloud Whisper API. Requires API key.",
+    },
+    CloudVariant {
+        id: "cloud-openai-gpt-4o-transcribe",
+        name: "OpenAI GPT-4o Transcribe (Cloud)",
+        engine: Engine::CloudOpenAI,
+        api_model: "gpt-4o-transcribe",
+        english_only: false,
+        description: "OpenAI's GPT-4o speech model - more accurate than Whisper. Requires API key.",
+    },
+    CloudVariant {
+        id: "cloud-openai-gpt-4o-mini-transcribe",
+        name: "OpenAI GPT-4o Mini Transcribe (Cloud)",
+        engine: Engine::CloudOpenAI,
+        api_model: "gpt-4o-mini-transcribe",
+        english_only: false,
+        description: "Smaller GPT-4o speech model - half the price of Whisper. Requires API key.",
+    },
+    CloudVariant {
+        id: "cloud-groq-whisper",
+        name: "Groq Whisper (Cloud)",
+        engine: Engine::CloudGroq,
+        api_model: "whisper-large-v3-turbo",
+        english_only: false,
+        description: "Groq's fast cloud Whisper Large V3 Turbo. Requires API key.",
+    },
+    CloudVariant {
+        id: "cloud-groq-whisper-large-v3",
+        name: "Groq Whisper Large V3 (Cloud)",
+        engine: Engine::CloudGroq,
+        api_model: "whisper-large-v3",
+        english_only: false,
+        description:
+            "Full Whisper Large V3 on Groq - slower than Turbo, most accurate. Requires API key.",
+    },
+    CloudVariant {
+        id: "cloud-groq-distil-whisper",
+        name: "Groq Distil-Whisper (Cloud, EN)",
+        engine: Engine::CloudGroq,
+        api_model: "distil-whisper-large-v3-en",
+        english_only: true,
+        description:
+            "Distilled Whisper on Groq - English only, fastest and cheapest. Requires API key.",
+    },
+];
+
 pub fn cloud_models() -> Vec<ModelInfo> {
-    vec![
-        ModelInfo {
-            id: "cloud-openai-whisper".into(),
-            name: "OpenAI Whisper (Cloud)".into(),
-            engine: Engine::CloudOpenAI,
+    CLOUD_VARIANTS
+        .iter()
+        .map(|v| ModelInfo {
+            id: v.id.into(),
+            name: v.name.into(),
+            engine: v.engine.clone(),
             size_bytes: 0,
             download_urls: vec![],
             files: vec![],
-            languages: LanguageSupport::Multilingual(99),
-            description: "OpenAI's cloud Whisper API. Requires API key.".into(),
-        },
-        ModelInfo {
-            id: "cloud-groq-whisper".into(),
-            name: "Groq Whisper (Cloud)".into(),
-            engine: Engine::CloudGroq,
-            size_bytes: 0,
-            download_urls: vec![],
-            files: vec![],
-            languages: LanguageSupport::Multilingual(99),
-            description: "Groq's fast cloud Whisper API. Requires API key.".into(),
-        },
-    ]
+            languages: if v.english_only {
+                LanguageSupport::English
+            } else {
+                LanguageSupport::Multilingual(99)
+            },
+            description: v.description.into(),
+        })
+        .collect()
+}
+
+/// Provider model name for a cloud catalog id, e.g. "whisper-large-v3".
+pub fn cloud_api_model(model_id: &str) -> Option<&'static str> {
+    CLOUD_VARIANTS
+        .iter()
+        .find(|v| v.id == model_id)
+        .map(|v| v.api_model)
 }
 
 pub fn full_catalog() -> Vec<ModelInfo> {
@@ -102,10 +273,14 @@ pub fn full_catalog() -> Vec<ModelInfo> {
     catalog
 }
 
+pub fn find(model_id: &str) -> Option<ModelInfo> {
+    full_catalog().into_iter().find(|m| m.id == model_id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::{Path, PathBuf};
+    use std::path::PathBuf;
 
     #[test]
     fn full_catalog_contains_all_models() {
@@ -157,9 +332,38 @@ mod tests {
     #[test]
     fn cloud_models_have_correct_engines() {
         let models = cloud_models();
-        assert_eq!(models.len(), 2);
-        assert_eq!(models[0].engine, Engine::CloudOpenAI);
-        assert_eq!(models[1].engine, Engine::CloudGroq);
+        assert_eq!(models.len(), 6);
+        assert!(models[..3].iter().all(|m| m.engine == Engine::CloudOpenAI));
+        assert!(models[3..].iter().all(|m| m.engine == Engine::CloudGroq));
+    }
+
+    #[test]
+    fn cloud_models_map_to_api_models() {
+        assert_eq!(cloud_api_model("cloud-openai-whisper"), Some("whisper-1"));
+        assert_eq!(
+            cloud_api_model("cloud-openai-gpt-4o-mini-transcribe"),
+            Some("gpt-4o-mini-transcribe")
+        );
+        assert_eq!(
+            cloud_api_model("cloud-groq-whisper"),
+            Some("whisper-large-v3-turbo")
+        );
+        assert_eq!(
+            cloud_api_model("cloud-groq-whisper-large-v3"),
+            Some("whisper-large-v3")
+        );
+        assert_eq!(
+            cloud_api_model("cloud-groq-distil-whisper"),
+            Some("distil-whisper-large-v3-en")
+        );
+        assert_eq!(cloud_api_model("whisper-base"), None);
+        for model in cloud_models() {
+            assert!(
+                cloud_api_model(&model.id).is_some(),
+                "{} has no API model",
+                model.id
+            );
+        }
     }
 
     #[test]
@@ -199,6 +403,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn coreml_encoders_match_ggml_files() {
+        let encoder = whisper_models::coreml_encoder("whisper-large-v3-turbo").unwrap();
+        assert_eq!(encoder.dir, "ggml-large-v3-turbo-encoder.mlmodelc");
+        assert!(encoder
+            .url
+            .ends_with("/ggml-large-v3-turbo-encoder.mlmodelc.zip"));
+        assert!(whisper_models::coreml_encoder("parakeet-tdt-0.6b-v3").is_none());
+    }
+
+    #[test]
+    fn coreml_encoder_zip_is_installed_as_a_directory() {
+        use std::io::Write;
+        let dir = std::env::temp_dir().join("sobotta_test_coreml");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let encoder = whisper_models::coreml_encoder("whisper-tiny").unwrap();
+
+        let zip_path = dir.join("encoder.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file(format!("{}/model.mil", encoder.dir), options)
+            .unwrap();
+        zip.write_all(b"program").unwrap();
+        zip.finish().unwrap();
+
+        whisper_models::install_coreml_encoder(&zip_path, &dir, &encoder).unwrap();
+        let installed = dir.join(&encoder.dir).join("model.mil");
+        assert_eq!(std::fs::read(installed).unwrap(), b"program");
+        assert!(!dir.join(format!("{}.extract", encoder.dir)).exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn models_dir_appends_models_subdir() {
         let base = PathBuf::from("/app/data");
@@ -279,6 +516,79 @@ mod tests {
         assert!(!is_model_downloaded(&base, &model));
     }
 
+    #[test]
+    fn check_disk_space_allows_download_with_margin() {
+        let needed = 500_000_000;
+        assert!(check_disk_space("Whisper Base", needed, needed + DISK_SPACE_MARGIN_BYTES).is_ok());
+    }
+
+    #[test]
+    fn check_disk_space_refuses_when_too_small() {
+        let err = check_disk_space("Whisper Large", 3_000_000_000, 1_000_000_000).unwrap_err();
+        assert!(err.contains("Whisper Large"));
+        assert!(err.contains("3000 MB"));
+        assert!(err.contains("1000 MB"));
+    }
+
+    #[test]
+    fn remaining_download_bytes_subtracts_existing_files() {
+        let dir = std::env::temp_dir().join("sobotta_test_models_remaining");
+        let model = &parakeet_models::catalog()[0];
+        let model_dir = model_path(&dir, &model.id);
+        std::fs::create_dir_all(&model_dir).unwrap();
+        assert_eq!(remaining_download_bytes(&dir, model), model.size_bytes);
+
+        std::fs::write(model_dir.join(&model.files[0]), vec![0u8; 1000]).unwrap();
+        assert_eq!(remaining_download_bytes(&dir, model), model.size_bytes - 1000);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn disk_usage_reports_sizes_and_orphans() {
+        let dir = std::env::temp_dir().join("sobotta_test_models_usage");
+        std::fs::remove_dir_all(&dir).ok();
+        let catalog = full_catalog();
+        let known = &catalog[0];
+
+        let known_dir = model_path(&dir, &known.id);
+        std::fs::create_dir_all(&known_dir).unwrap();
+        std::fs::write(known_dir.join("model.bin"), vec![0u8; 300]).unwrap();
+
+        let orphan_dir = model_path(&dir, "whisper-old-name");
+        std::fs::create_dir_all(orphan_dir.join("nested")).unwrap();
+        std::fs::write(orphan_dir.join("nested/a.onnx"), vec![0u8; 100]).unwrap();
+
+        let vad_dir = model_path(&dir, VAD_MODEL_ID);
+        std::fs::create_dir_all(&vad_dir).unwrap();
+        std::fs::write(vad_dir.join("silero_vad.onnx"), vec![0u8; 10]).unwrap();
+
+        let punctuation_dir = model_path(&dir, PUNCTUATION_MODEL_ID);
+        std::fs::create_dir_all(&punctuation_dir).unwrap();
+        std::fs::write(punctuation_dir.join("model.int8.onnx"), vec![0u8; 5]).unwrap();
+
+        let usage = disk_usage(&dir, &catalog);
+        assert_eq!(usage.len(), 4);
+        assert_eq!(usage[0].model_id, known.id);
+        assert_eq!(usage[0].size_bytes, 300);
+        assert!(!usage[0].orphaned);
+        assert_eq!(usage[0].name.as_deref(), Some(known.name.as_str()));
+        assert_eq!(usage[1].model_id, "whisper-old-name");
+        assert_eq!(usage[1].size_bytes, 100);
+        assert!(usage[1].orphaned);
+        assert_eq!(usage[2].model_id, VAD_MODEL_ID);
+        assert!(!usage[2].orphaned);
+        assert_eq!(usage[3].model_id, PUNCTUATION_MODEL_ID);
+        assert!(!usage[3].orphaned);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn disk_usage_missing_models_dir_is_empty() {
+        let base = PathBuf::from("/nonexistent/path");
+        assert!(disk_usage(&base, &full_catalog()).is_empty());
+    }
+
     #[test]
     fn whisper_models_are_all_multilingual() {
         for model in whisper_models::catalog() {
@@ -302,5 +612,28 @@ mod tests {
             models[1].languages,
             LanguageSupport::Multilingual(25)
         ));
+        assert_eq!(
+            parakeet_models::V3_LANGUAGES.len(),
+            25,
+            "V3_LANGUAGES should match the catalog's language count"
+        );
+    }
+
+    #[test]
+    fn supported_languages_follow_the_model() {
+        let v2 = find("parakeet-tdt-0.6b-v2").unwrap();
+        assert!(v2.supports_language("en"));
+        assert!(v2.supports_language("auto"));
+        assert!(!v2.supports_language("es"));
+
+        let v3 = find("parakeet-tdt-0.6b-v3").unwrap();
+        assert!(v3.supports_language("es"));
+        assert!(v3.supports_language("uk"));
+        assert!(!v3.supports_language("ja"));
+
+        assert!(find("whisper-base").unwrap().supports_language("ja"));
+        assert!(!find("cloud-groq-distil-whisper")
+            .unwrap()
+            .supports_language("fr"));
     }
 }
@@ -5,6 +5,12 @@ const HF_TDT_V2: &str =
 const HF_TDT_V3: &str =
     "https://huggingface.co/csukuangfj/sherpa-onnx-nemo-parakeet-tdt-0.6b-v3-int8/resolve/main";
 
+/// ISO 639-1 codes of the 25 European languages Parakeet TDT v3 transcribes.
+pub const V3_LANGUAGES: &[&str] = &[
+    "bg", "cs", "da", "de", "el", "en", "es", "et", "fi", "fr", "hr", "hu", "it", "lt", "lv", "mt",
+    "nl", "pl", "pt", "ro", "ru", "sk", "sl", "sv", "uk",
+];
+
 pub fn catalog() -> Vec<ModelInfo> {
     vec![
         ModelInfo {
@@ -1,17 +1,17 @@
-use super::{Engine, LanguageSupport, ModelInfo};
+//! Unified Whisper model catalog.
+//!
+//! On **macOS** we use GGML models with whisper.cpp (Metal GPU acceleration).
+//! On **Windows/Linux** we use ONNX int8 models with sherpa-onnx (optimized CPU kernels).
+//!
+//! The same model IDs are used on all platforms so the frontend sees a single
+//! set of models regardless of OS.  The backend picks the right engine and files
+//! via `#[cfg(target_os)]` conditional compilation.
 
-/// Unified Whisper model catalog.
-///
-/// On **macOS** we use GGML models with whisper.cpp (Metal GPU acceleration).
-/// On **Windows/Linux** we use ONNX int8 models with sherpa-onnx (optimized CPU kernels).
-///
-/// The same model IDs are used on all platforms so the frontend sees a single
-/// set of models regardless of OS.  The backend picks the right engine and files
-/// via `#[cfg(target_os)]` conditional compilation.
+use super::{Engine, LanguageSupport, ModelInfo};
+use std::path::Path;
 
 // ── macOS: GGML models (whisper.cpp + Metal) ────────────────────────────────
 
-#[cfg(target_os = "macos")]
 const HF_BASE: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
 
 #[cfg(target_os = "macos")]
@@ -70,6 +70,61 @@ pub fn catalog() -> Vec<ModelInfo> {
     ]
 }
 
+// ── macOS: CoreML encoders (Apple Neural Engine) ────────────────────────────
+
+/// CoreML build of a GGML model's encoder. whisper.cpp looks for it next to
+/// the model file (`ggml-base.bin` → `ggml-base-encoder.mlmodelc`) and runs
+/// the encoder through CoreML when it's there, otherwise on Metal. It's
+/// published as a zip of the `.mlmodelc` directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoremlEncoder {
+    pub url: String,
+    /// Directory name inside the model directory.
+    pub dir: String,
+    pub size_bytes: u64,
+}
+
+/// Approximate size of each model's zipped CoreML encoder.
+const COREML_ENCODER_SIZES: &[(&str, u64)] = &[
+    ("whisper-tiny", 15_000_000),
+    ("whisper-base", 38_000_000),
+    ("whisper-small", 163_000_000),
+    ("whisper-medium", 568_000_000),
+    ("whisper-large-v3-turbo", 1_180_000_000),
+];
+
+/// The CoreML encoder matching a GGML Whisper model, if one is published.
+pub fn coreml_encoder(model_id: &str) -> Option<CoremlEncoder> {
+    let (_, size_bytes) = COREML_ENCODER_SIZES.iter().find(|(id, _)| *id == model_id)?;
+    let name = model_id.strip_prefix("whisper-")?;
+    Some(CoremlEncoder {
+        url: format!("{}/ggml-{}-encoder.mlmodelc.zip", HF_BASE, name),
+        dir: format!("ggml-{}-encoder.mlmodelc", name),
+        size_bytes: *size_bytes,
+    })
+}
+
+/// Unpack a downloaded CoreML encoder zip into `model_dir`. The archive
+/// holds the `.mlmodelc` directory; it's extracted next to it first so an
+/// interrupted unpack never leaves a partial encoder for whisper.cpp to load.
+pub fn install_coreml_encoder(
+    zip_path: &Path,
+    model_dir: &Path,
+    encoder: &CoremlEncoder,
+) -> anyhow::Result<()> {
+    let staging = model_dir.join(format!("{}.extract", encoder.dir));
+    let _ = std::fs::remove_dir_all(&staging);
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(zip_path)?)?;
+    archive.extract(&staging)?;
+
+    // Some archives hold the directory's contents rather than the directory
+    let nested = staging.join(&encoder.dir);
+    let source = if nested.is_dir() { nested } else { staging.clone() };
+    std::fs::rename(&source, model_dir.join(&encoder.dir))?;
+    let _ = std::fs::remove_dir_all(&staging);
+    Ok(())
+}
+
 // ── Windows & Linux: ONNX int8 models (sherpa-onnx, fast CPU) ───────────────
 
 #[cfg(not(target_os = "macos"))]
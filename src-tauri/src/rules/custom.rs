@@ -0,0 +1,140 @@
+//! User-written regex rules.
+//!
+//! Replacements may refer to capture groups as `$1`, `${1}` or `$name`
+//! (`$0` is the whole match, `$$` a literal `$`), so `(?i)issue (\d+)` with
+//! `ISSUE-$1` turns "issue 123" into "ISSUE-123". Flags: `i` ignores case,
+//! `m` makes `^` and `$` match at every line, `s` lets `.` match newlines.
+
+use regex::{Regex, RegexBuilder};
+
+/// Flags a rule may set, in the order they're stored.
+pub const FLAGS: &str = "ims";
+
+/// Sort and de-duplicate `flags`, rejecting unknown ones.
+pub fn normalize_flags(flags: &str) -> Result<String, String> {
+    if let Some(unknown) = flags.chars().find(|c| !FLAGS.contains(*c)) {
+        return Err(format!("Unknown flag '{}' (use i, m or s)", unknown));
+    }
+    Ok(FLAGS.chars().filter(|c| flags.contains(*c)).collect())
+}
+
+pub fn compile(pattern: &str, flags: &str) -> Result<Regex, String> {
+    normalize_flags(flags)?;
+    RegexBuilder::new(pattern)
+        .case_insensitive(flags.contains('i'))
+        .multi_line(flags.contains('m'))
+        .dot_matches_new_line(flags.contains('s'))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Check a rule before it's saved: the pattern must compile and every group
+/// the replacement refers to must exist.
+pub fn validate(pattern: &str, flags: &str, replacement: &str) -> Result<(), String> {
+    if pattern.is_empty() {
+        return Err("Pattern is empty".into());
+    }
+    let re = compile(pattern, flags)?;
+    for name in group_refs(replacement) {
+        let exists = match name.parse::<usize>() {
+            Ok(index) => index < re.captures_len(),
+            Err(_) => re.capture_names().flatten().any(|n| n == name),
+        };
+        if exists {
+            continue;
+        }
+        // "$1st" refers to a group named "1st", not group 1
+        let hint = match name.find(|c: char| !c.is_ascii_digit()) {
+            Some(split) if split > 0 => format!(
+                "; write ${{{}}}{} to follow group {} with text",
+                &name[..split],
+                &name[split..],
+                &name[..split]
+            ),
+            _ => String::new(),
+        };
+        return Err(format!(
+            "Replacement refers to group '{}', which the pattern doesn't have{}",
+            name, hint
+        ));
+    }
+    Ok(())
+}
+
+/// Group names and numbers referred to in `replacement`, following the
+/// `regex` crate's syntax: `$` then `{name}` or the longest run of
+/// `[0-9A-Za-z_]`. A `$` followed by anything else is literal.
+fn group_refs(replacement: &str) -> Vec<&str> {
+    let mut refs = vec![];
+    let mut rest = replacement;
+    while let Some(i) = rest.find('$') {
+        rest = &rest[i + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            rest = after;
+        } else if let Some(braced) = rest.strip_prefix('{') {
+            if let Some(end) = braced.find('}') {
+                refs.push(&braced[..end]);
+                rest = &braced[end + 1..];
+            }
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            if end > 0 {
+                refs.push(&rest[..end]);
+            }
+            rest = &rest[end..];
+        }
+    }
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(pattern: &str, flags: &str, replacement: &str, text: &str) -> String {
+        validate(pattern, flags, replacement).unwrap();
+        compile(pattern, flags)
+            .unwrap()
+            .replace_all(text, replacement)
+            .to_string()
+    }
+
+    #[test]
+    fn replacement_expands_capture_groups() {
+        assert_eq!(
+            replace(r"issue (\d+)", "i", "ISSUE-$1", "see Issue 123 and issue 7"),
+            "see ISSUE-123 and ISSUE-7"
+        );
+        assert_eq!(
+            replace(
+                r"(?P<last>\w+), (?P<first>\w+)",
+                "",
+                "$first ${last}s",
+                "Doe, Jane"
+            ),
+            "Jane Does"
+        );
+        assert_eq!(replace(r"(\d+) dollars", "", "$$$1", "5 dollars"), "$5");
+    }
+
+    #[test]
+    fn multi_line_flag_anchors_each_line() {
+        assert_eq!(replace(r"^- ", "m", "• ", "- one\n- two"), "• one\n• two");
+        assert_eq!(replace(r"^- ", "", "• ", "- one\n- two"), "• one\n- two");
+        assert_eq!(replace(r"a.b", "s", "x", "a\nb"), "x");
+    }
+
+    #[test]
+    fn validate_rejects_missing_groups_and_bad_flags() {
+        assert!(validate("", "", "x").is_err());
+        assert!(validate(r"(\d+)", "", "$2").is_err());
+        assert!(validate(r"(\d+)", "", "$missing").is_err());
+        let err = validate(r"(\d+)", "", "$1st").unwrap_err();
+        assert!(err.contains("${1}st"), "{}", err);
+        assert!(validate(r"(\d+)", "q", "$1").is_err());
+        assert!(validate(r"(\d+)", "", "${1}st costs $ 5").is_ok());
+        assert_eq!(normalize_flags("smi").unwrap(), "ims");
+    }
+}
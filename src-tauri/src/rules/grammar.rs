@@ -0,0 +1,5 @@
+/// System prompt for the "Fix Grammar" AI function. Corrects grammar,
+/// spelling and punctuation while keeping the speaker's wording and tone.
+pub const GRAMMAR_SYSTEM_PROMPT: &str = "Fix the grammar, spelling and punctuation of the following dictated text. \
+Keep the original wording, meaning, tone and language as much as possible — do not rephrase, summarize or add content. \
+Output only the corrected text.";
@@ -1,5 +1,9 @@
+pub mod custom;
 pub mod filler;
+pub mod grammar;
 pub mod punctuation;
+pub mod redact;
+pub mod vocabulary;
 
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +21,9 @@ pub enum RuleType {
     RegexReplace {
         pattern: String,
         replacement: String,
+        /// Flags of a custom rule; see [`custom`].
+        #[serde(default)]
+        flags: String,
     },
 }
 
@@ -28,6 +35,7 @@ pub fn builtin_rules() -> Vec<Rule> {
             rule_type: RuleType::RegexReplace {
                 pattern: filler::FILLER_PATTERN.into(),
                 replacement: "".into(),
+                flags: String::new(),
             },
             enabled: false,
             sort_order: 0,
@@ -38,6 +46,7 @@ pub fn builtin_rules() -> Vec<Rule> {
             rule_type: RuleType::RegexReplace {
                 pattern: String::new(),
                 replacement: String::new(),
+                flags: String::new(),
             },
             enabled: false,
             sort_order: 1,
@@ -60,8 +69,9 @@ pub fn apply_regex_rules(text: &str, rules: &[Rule]) -> String {
             RuleType::RegexReplace {
                 pattern,
                 replacement,
+                flags,
             } => {
-                if let Ok(re) = regex::Regex::new(pattern) {
+                if let Ok(re) = custom::compile(pattern, flags) {
                     result = re.replace_all(&result, replacement.as_str()).to_string();
                 }
             }
@@ -85,7 +95,11 @@ mod tests {
     fn builtin_rules_all_disabled_by_default() {
         let rules = builtin_rules();
         for rule in &rules {
-            assert!(!rule.enabled, "Rule '{}' should be disabled by default", rule.name);
+            assert!(
+                !rule.enabled,
+                "Rule '{}' should be disabled by default",
+                rule.name
+            );
         }
     }
 
@@ -152,6 +166,7 @@ mod tests {
             rule_type: RuleType::RegexReplace {
                 pattern: r"foo".into(),
                 replacement: "qux".into(),
+                flags: String::new(),
             },
             enabled: true,
             sort_order: 0,
@@ -169,6 +184,7 @@ mod tests {
             rule_type: RuleType::RegexReplace {
                 pattern: r"[invalid".into(), // invalid regex
                 replacement: "x".into(),
+                flags: String::new(),
             },
             enabled: true,
             sort_order: 0,
@@ -0,0 +1,109 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+static EMAIL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b").unwrap());
+
+/// 13–19 digits, optionally grouped by spaces or dashes.
+static CARD: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").unwrap());
+
+static PHONE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\+?\(?\b\d[\d ().-]{5,}\d\b").unwrap());
+
+/// Digit groups shaped like a date (2024-01-15, 15.01.2024) or an IPv4
+/// address, which `PHONE` would otherwise catch.
+static NOT_PHONE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"^(?:\d{4}[-./]\d{1,2}[-./]\d{1,2}",
+        r"|\d{1,2}[-./]\d{1,2}[-./]\d{4}",
+        r"|\d{1,3}(?:\.\d{1,3}){3})$",
+    ))
+    .unwrap()
+});
+
+/// Replace email addresses, card numbers and phone numbers with placeholders.
+pub fn redact(text: &str) -> String {
+    let result = EMAIL.replace_all(text, "[email]");
+    let result = CARD.replace_all(&result, |caps: &Captures| {
+        if luhn_valid(&caps[0]) {
+            "[card]".to_string()
+        } else {
+            caps[0].to_string()
+        }
+    });
+    PHONE
+        .replace_all(&result, |caps: &Captures| {
+            if looks_like_phone(&caps[0]) {
+                "[phone]".to_string()
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .to_string()
+}
+
+/// 7–15 digits (the E.164 limit) written with a leading `+` or separators.
+/// A bare run of digits is more likely an order or account number, fewer
+/// digits a time or amount.
+fn looks_like_phone(candidate: &str) -> bool {
+    let digits = candidate.chars().filter(char::is_ascii_digit).count();
+    let grouped = candidate.starts_with('+') || digits < candidate.len();
+    (7..=15).contains(&digits) && grouped && !NOT_PHONE.is_match(candidate)
+}
+
+fn luhn_valid(number: &str) -> bool {
+    let sum: u32 = number
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| match (i % 2 == 1, d * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => d,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_email_addresses() {
+        assert_eq!(
+            redact("write to jane.doe@example.com today"),
+            "write to [email] today"
+        );
+    }
+
+    #[test]
+    fn redacts_card_numbers() {
+        assert_eq!(
+            redact("card 4111 1111 1111 1111 please"),
+            "card [card] please"
+        );
+        assert_eq!(redact("card 4111-1111-1111-1111"), "card [card]");
+    }
+
+    #[test]
+    fn redacts_phone_numbers() {
+        assert_eq!(redact("call +1 (555) 123-4567 now"), "call [phone] now");
+        assert_eq!(redact("order 12345 at 10.30"), "order 12345 at 10.30");
+        assert_eq!(redact("ring 555.123.4567"), "ring [phone]");
+    }
+
+    #[test]
+    fn leaves_dates_addresses_and_order_numbers() {
+        for text in [
+            "due 2024-01-15 at noon",
+            "due 15.01.2024 at noon",
+            "host 192.168.1.10 is down",
+            "order 1234567890123 shipped",
+            "ref ab1234567 here",
+        ] {
+            assert_eq!(redact(text), text);
+        }
+    }
+}
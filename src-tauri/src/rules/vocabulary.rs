@@ -0,0 +1,81 @@
+use regex::Regex;
+
+/// Replace each vocabulary term with its replacement, e.g. "gpt four" with
+/// "GPT-4". Terms match case-insensitively as whole words.
+pub fn apply_replacements(text: &str, replacements: &[(String, String)]) -> String {
+    let mut result = text.to_string();
+    for (term, replacement) in replacements {
+        if let Some(re) = term_regex(term) {
+            result = re
+                .replace_all(&result, regex::NoExpand(replacement))
+                .to_string();
+        }
+    }
+    result
+}
+
+/// Whether `term` appears in `text` as a whole word, ignoring case.
+pub fn contains_term(text: &str, term: &str) -> bool {
+    term_regex(term).is_some_and(|re| re.is_match(text))
+}
+
+/// Case-insensitive whole-word pattern for `term`; `None` for a blank term.
+fn term_regex(term: &str) -> Option<Regex> {
+    let term = term.trim();
+    if term.is_empty() {
+        return None;
+    }
+    // \b only makes sense next to a word character ("C++" can't end in one)
+    let boundary = |c: Option<char>| {
+        if c.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            r"\b"
+        } else {
+            ""
+        }
+    };
+    let pattern = format!(
+        "(?i){}{}{}",
+        boundary(term.chars().next()),
+        regex::escape(term),
+        boundary(term.chars().last())
+    );
+    Regex::new(&pattern).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(t, r)| (t.to_string(), r.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn replaces_whole_words_case_insensitively() {
+        let replacements = pairs(&[("gpt four", "GPT-4"), ("sobotta", "SobottaAI")]);
+        assert_eq!(
+            apply_replacements("Ask GPT four about sobotta, not sobottas", &replacements),
+            "Ask GPT-4 about SobottaAI, not sobottas"
+        );
+    }
+
+    #[test]
+    fn replacement_is_literal() {
+        let replacements = pairs(&[("c plus plus", "C++ ($1)")]);
+        assert_eq!(
+            apply_replacements("I write c plus plus", &replacements),
+            "I write C++ ($1)"
+        );
+    }
+
+    #[test]
+    fn contains_term_matches_whole_words_only() {
+        assert!(contains_term("We moved to TOKIO today", "tokio"));
+        assert!(contains_term("I write C++ daily", "C++"));
+        assert!(!contains_term("tokiofied", "tokio"));
+        assert!(!contains_term("anything", " "));
+    }
+}
@@ -0,0 +1,134 @@
+//! Stopping local inference that's been cancelled or is taking too long, so
+//! one bad recording can't hold the transcription lock and block every
+//! dictation after it.
+//!
+//! Whisper checks the signal between decoding steps and stops by itself.
+//! The sherpa-onnx engines can't be interrupted mid-call, so a run that
+//! ignores the signal is abandoned on its thread and its result dropped.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long an engine gets to stop by itself once aborted before it's
+/// abandoned.
+const GRACE: Duration = Duration::from_secs(2);
+/// How often a running transcription is checked for an abort.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Cancellation and the time limit of one local transcription.
+#[derive(Debug, Clone)]
+pub struct AbortSignal {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl AbortSignal {
+    /// A signal raised by `cancelled` or once `limit` has passed from now.
+    pub fn new(cancelled: Arc<AtomicBool>, limit: Option<Duration>) -> Self {
+        Self {
+            cancelled,
+            deadline: limit.map(|limit| Instant::now() + limit),
+        }
+    }
+
+    pub fn timed_out(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst) || self.timed_out()
+    }
+}
+
+/// A transcription stopped for running past its time limit.
+#[derive(Debug, thiserror::Error)]
+#[error("Transcription took longer than {0}s and was stopped")]
+pub struct InferenceTimedOut(pub u64);
+
+/// Time limit for transcribing `audio_secs` of audio: `max_secs` (0 for
+/// none), but at least twice the audio's duration so long recordings still
+/// get to finish.
+pub fn inference_limit(max_secs: u64, audio_secs: u64) -> Option<Duration> {
+    (max_secs > 0).then(|| Duration::from_secs(max_secs.max(audio_secs * 2)))
+}
+
+/// Run `work` on its own thread until it finishes or `abort` is raised.
+/// Returns `None` when the work had to be abandoned, which leaves whatever
+/// it holds in use until it eventually returns.
+pub fn run_abortable<T: Send + 'static>(
+    abort: &AbortSignal,
+    work: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+) -> Option<anyhow::Result<T>> {
+    run_with_grace(abort, GRACE, work)
+}
+
+fn run_with_grace<T: Send + 'static>(
+    abort: &AbortSignal,
+    grace: Duration,
+    work: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+) -> Option<anyhow::Result<T>> {
+    let (tx, rx) = mpsc::channel();
+    let spawned = std::thread::Builder::new()
+        .name("stt-inference".into())
+        .spawn(move || {
+            let _ = tx.send(work());
+        });
+    if let Err(e) = spawned {
+        return Some(Err(anyhow::anyhow!("Failed to start transcription: {}", e)));
+    }
+
+    let mut aborted_at: Option<Instant> = None;
+    loop {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(result) => return Some(result),
+            Err(RecvTimeoutError::Disconnected) => {
+                return Some(Err(anyhow::anyhow!("Transcription thread panicked")))
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+        match aborted_at {
+            Some(at) if at.elapsed() >= grace => return None,
+            None if abort.is_aborted() => aborted_at = Some(Instant::now()),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_leaves_room_for_long_audio() {
+        assert_eq!(inference_limit(0, 30), None);
+        assert_eq!(inference_limit(300, 30), Some(Duration::from_secs(300)));
+        assert_eq!(inference_limit(300, 3600), Some(Duration::from_secs(7200)));
+    }
+
+    #[test]
+    fn finished_work_returns_its_result() {
+        let abort = AbortSignal::new(Arc::new(AtomicBool::new(false)), None);
+        let result = run_with_grace(&abort, Duration::ZERO, || Ok(42));
+        assert_eq!(result.unwrap().unwrap(), 42);
+    }
+
+    #[test]
+    fn work_ignoring_the_signal_is_abandoned() {
+        let abort = AbortSignal::new(Arc::new(AtomicBool::new(true)), None);
+        let result = run_with_grace(&abort, Duration::ZERO, || {
+            std::thread::sleep(Duration::from_secs(5));
+            Ok(())
+        });
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn deadline_raises_the_signal() {
+        let abort = AbortSignal::new(Arc::new(AtomicBool::new(false)), Some(Duration::ZERO));
+        assert!(abort.timed_out());
+        assert!(abort.is_aborted());
+    }
+}
@@ -0,0 +1,74 @@
+//! Helpers for `benchmark_model`: word error rate against a reference text.
+
+/// Lowercase words with surrounding punctuation stripped, so "Hello," and
+/// "hello" compare equal.
+fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Word error rate: word-level edit distance (substitutions, insertions,
+/// deletions) divided by the number of reference words. Rough by design —
+/// no number or contraction normalization. `None` for an empty reference.
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> Option<f32> {
+    let reference = normalize_words(reference);
+    let hypothesis = normalize_words(hypothesis);
+    if reference.is_empty() {
+        return None;
+    }
+
+    // Single-row Levenshtein over words
+    let mut row: Vec<usize> = (0..=hypothesis.len()).collect();
+    for (i, ref_word) in reference.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, hyp_word) in hypothesis.iter().enumerate() {
+            let cost = usize::from(ref_word != hyp_word);
+            let next = (row[j] + 1).min(row[j + 1] + 1).min(diagonal + cost);
+            diagonal = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+
+    Some(row[hypothesis.len()] as f32 / reference.len() as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_text_is_zero() {
+        assert_eq!(word_error_rate("the quick brown fox", "the quick brown fox"), Some(0.0));
+    }
+
+    #[test]
+    fn ignores_case_and_punctuation() {
+        assert_eq!(word_error_rate("Hello, world!", "hello world"), Some(0.0));
+    }
+
+    #[test]
+    fn counts_substitution_insertion_deletion() {
+        // one substitution out of four words
+        assert_eq!(word_error_rate("the quick brown fox", "the quick red fox"), Some(0.25));
+        // one deletion
+        assert_eq!(word_error_rate("the quick brown fox", "the brown fox"), Some(0.25));
+        // one insertion
+        assert_eq!(word_error_rate("the brown fox", "the big brown fox"), Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn empty_hypothesis_is_full_error() {
+        assert_eq!(word_error_rate("one two", ""), Some(1.0));
+    }
+
+    #[test]
+    fn empty_reference_is_none() {
+        assert_eq!(word_error_rate("  ", "anything"), None);
+    }
+}
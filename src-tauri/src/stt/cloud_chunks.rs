@@ -0,0 +1,140 @@
+//! Splitting long recordings to fit cloud STT upload limits, and merging the
+//! per-chunk transcripts back into one result.
+
+use super::{Segment, TranscriptionResult};
+use crate::audio::processing::rms_energy;
+use std::ops::Range;
+
+/// OpenAI and Groq reject uploads over 25 MB; leave headroom for the header.
+const MAX_UPLOAD_BYTES: usize = 24 * 1024 * 1024;
+/// Bytes per sample of the 16-bit PCM WAV the cloud modules upload.
+const BYTES_PER_SAMPLE: usize = 2;
+/// How far back from a chunk's size limit to look for a quiet place to cut.
+const CUT_SEARCH_MS: usize = 5000;
+const FRAME_MS: usize = 50;
+
+/// Sample ranges of `audio` that each encode to an uploadable file.
+pub fn upload_chunks(audio: &[f32], sample_rate: u32) -> Vec<Range<usize>> {
+    chunk_ranges(audio, sample_rate, MAX_UPLOAD_BYTES / BYTES_PER_SAMPLE)
+}
+
+/// Split into ranges of at most `max_samples`, cutting each in the quietest
+/// frame shortly before its limit so words aren't split.
+fn chunk_ranges(audio: &[f32], sample_rate: u32, max_samples: usize) -> Vec<Range<usize>> {
+    let per_ms = sample_rate as usize / 1000;
+    let frame = (FRAME_MS * per_ms).max(1);
+    let search = (CUT_SEARCH_MS * per_ms).min(max_samples / 2);
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while audio.len() - start > max_samples {
+        let limit = start + max_samples;
+        let cut = (limit - search..=limit.saturating_sub(frame))
+            .step_by(frame)
+            .min_by(|&a, &b| {
+                rms_energy(&audio[a..a + frame]).total_cmp(&rms_energy(&audio[b..b + frame]))
+            })
+            .map_or(limit, |quietest| quietest + frame / 2);
+        ranges.push(start..cut);
+        start = cut;
+    }
+    ranges.push(start..audio.len());
+    ranges
+}
+
+/// Join per-chunk results, each with its start sample, shifting segment
+/// timestamps onto the whole recording.
+pub fn merge(
+    parts: Vec<(usize, TranscriptionResult)>,
+    sample_rate: u32,
+    duration_ms: u64,
+) -> TranscriptionResult {
+    let mut text = String::new();
+    let mut language = None;
+    let mut segments = Vec::new();
+    for (start, part) in parts {
+        let offset_ms = start as u64 * 1000 / sample_rate as u64;
+        let part_text = part.text.trim();
+        if !part_text.is_empty() {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(part_text);
+        }
+        language = language.or(part.language);
+        segments.extend(part.segments.into_iter().map(|s| Segment {
+            start_ms: s.start_ms + offset_ms,
+            end_ms: s.end_ms + offset_ms,
+            text: s.text,
+        }));
+    }
+
+    TranscriptionResult {
+        text,
+        language,
+        segments,
+        duration_ms,
+        metrics: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_audio_is_one_chunk() {
+        assert_eq!(chunk_ranges(&[0.5; 1000], 16000, 1000), vec![0..1000]);
+        assert_eq!(chunk_ranges(&[], 16000, 1000), vec![0..0]);
+    }
+
+    #[test]
+    fn long_audio_is_cut_in_a_pause_before_the_limit() {
+        // 30s of tone with a pause at 17.0s..17.2s; limit is 20s per chunk
+        let mut audio = vec![0.5f32; 30 * 16000];
+        audio[17 * 16000..17 * 16000 + 3200].fill(0.0);
+        let ranges = chunk_ranges(&audio, 16000, 20 * 16000);
+
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(ranges[0].start, 0);
+        assert_eq!(ranges[0].end, ranges[1].start);
+        assert_eq!(ranges[1].end, audio.len());
+        assert!(ranges[0].end > 17 * 16000 && ranges[0].end < 17 * 16000 + 3200);
+    }
+
+    #[test]
+    fn chunks_never_exceed_the_limit() {
+        let audio = vec![0.5f32; 100 * 16000];
+        let ranges = chunk_ranges(&audio, 16000, 20 * 16000);
+        assert!(ranges.iter().all(|r| r.len() <= 20 * 16000));
+        assert_eq!(ranges.iter().map(|r| r.len()).sum::<usize>(), audio.len());
+    }
+
+    #[test]
+    fn merge_offsets_segments() {
+        let part = |text: &str, language: Option<&str>| TranscriptionResult {
+            text: text.into(),
+            language: language.map(String::from),
+            segments: vec![Segment {
+                start_ms: 100,
+                end_ms: 900,
+                text: text.into(),
+            }],
+            duration_ms: 50,
+            metrics: None,
+        };
+        let merged = merge(
+            vec![
+                (0, part(" Hello.", None)),
+                (32000, part("World. ", Some("en"))),
+            ],
+            16000,
+            120,
+        );
+        assert_eq!(merged.text, "Hello. World.");
+        assert_eq!(merged.language.as_deref(), Some("en"));
+        assert_eq!(merged.segments[1].start_ms, 2100);
+        assert_eq!(merged.segments[1].end_ms, 2900);
+        assert_eq!(merged.duration_ms, 120);
+    }
+}
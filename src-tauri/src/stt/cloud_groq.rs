@@ -1,3 +1,4 @@
+use super::cloud_http::{self, RequestPolicy};
 use super::{Segment, TranscriptionOptions, TranscriptionResult};
 use crate::audio::wav;
 use serde::Deserialize;
@@ -21,20 +22,43 @@ struct GroqSegment {
     text: String,
 }
 
-/// Transcribe audio using the Groq Whisper API.
+/// Transcribe audio using the Groq Whisper API, in several requests when
+/// it's too long for one upload.
 pub async fn transcribe(
     audio: &[f32],
     options: &TranscriptionOptions,
     api_key: &str,
     model: &str,
+    policy: &RequestPolicy,
 ) -> anyhow::Result<TranscriptionResult> {
     let start = std::time::Instant::now();
+    let chunks = super::cloud_chunks::upload_chunks(audio, 16000);
+    if chunks.len() == 1 {
+        return transcribe_upload(audio, options, api_key, model, policy).await;
+    }
+
+    log::info!("Uploading {} chunks to Groq", chunks.len());
+    let mut parts = Vec::with_capacity(chunks.len());
+    for range in chunks {
+        let part =
+            transcribe_upload(&audio[range.clone()], options, api_key, model, policy).await?;
+        parts.push((range.start, part));
+    }
+    let duration_ms = start.elapsed().as_millis() as u64;
+    Ok(super::cloud_chunks::merge(parts, 16000, duration_ms))
+}
 
-    let wav_bytes = wav::encode_wav_to_bytes(audio, 16000)?;
+/// Transcribe audio that fits in a single upload.
+async fn transcribe_upload(
+    audio: &[f32],
+    options: &TranscriptionOptions,
+    api_key: &str,
+    model: &str,
+    policy: &RequestPolicy,
+) -> anyhow::Result<TranscriptionResult> {
+    let start = std::time::Instant::now();
 
-    let file_part = reqwest::multipart::Part::bytes(wav_bytes)
-        .file_name("audio.wav")
-        .mime_str("audio/wav")?;
+    let wav_bytes = wav::encode_wav_pcm16_to_bytes(audio, 16000)?;
 
     let groq_model = if model.is_empty() {
         "whisper-large-v3-turbo"
@@ -42,34 +66,32 @@ pub async fn transcribe(
         model
     };
 
-    let mut form = reqwest::multipart::Form::new()
-        .part("file", file_part)
-        .text("model", groq_model.to_string())
-        .text("response_format", "verbose_json");
+    let resp = cloud_http::send("Groq", policy, |client| {
+        let file_part = reqwest::multipart::Part::bytes(wav_bytes.clone())
+            .file_name("audio.wav")
+            .mime_str("audio/wav")?;
 
-    if let Some(ref lang) = options.language {
-        if lang != "auto" {
-            form = form.text("language", lang.clone());
+        let mut form = reqwest::multipart::Form::new()
+            .part("file", file_part)
+            .text("model", groq_model.to_string())
+            .text("response_format", "verbose_json");
+
+        if let Some(ref lang) = options.language {
+            if lang != "auto" {
+                form = form.text("language", lang.clone());
+            }
         }
-    }
 
-    if !options.vocabulary.is_empty() {
-        form = form.text("prompt", options.vocabulary.join(", "));
-    }
+        if let Some(prompt) = options.prompt() {
+            form = form.text("prompt", prompt);
+        }
 
-    let client = reqwest::Client::new();
-    let resp = client
-        .post("https://api.groq.com/openai/v1/audio/transcriptions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .multipart(form)
-        .send()
-        .await?;
-
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        anyhow::bail!("Groq API error {}: {}", status, body);
-    }
+        Ok(client
+            .post("https://api.groq.com/openai/v1/audio/transcriptions")
+            .header("Authorization", format!("Bearer {}", api_key))
+            .multipart(form))
+    })
+    .await?;
 
     let result: GroqTranscription = resp.json().await?;
     let inference_ms = start.elapsed().as_millis() as u64;
@@ -90,5 +112,6 @@ pub async fn transcribe(
         language: None, // Groq doesn't return detected language in the same way
         segments,
         duration_ms: inference_ms,
+        metrics: None,
     })
 }
@@ -0,0 +1,221 @@
+//! Request policy shared by the cloud STT modules: a timeout per request,
+//! retries with backoff on rate limits and server errors, and failures
+//! sorted into categories.
+
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::time::Duration;
+
+/// Longest wait between retries, whatever `Retry-After` asks for.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Timeout and retry settings for cloud transcription requests.
+#[derive(Debug, Clone)]
+pub struct RequestPolicy {
+    pub timeout: Duration,
+    /// Retries after the first attempt; 0 disables retrying.
+    pub max_retries: u32,
+    /// First backoff delay, doubled on every further retry.
+    pub base_delay: Duration,
+}
+
+impl RequestPolicy {
+    pub fn new(timeout_secs: u64, max_retries: u32) -> Self {
+        Self {
+            timeout: Duration::from_secs(timeout_secs.max(1)),
+            max_retries,
+            base_delay: Duration::from_secs(1),
+        }
+    }
+
+    /// Delay before retry number `attempt` (1-based), preferring the
+    /// server's `Retry-After`.
+    fn backoff(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| self.base_delay.saturating_mul(1 << (attempt - 1).min(16)))
+            .min(MAX_BACKOFF)
+    }
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self::new(120, 2)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Missing, invalid or unauthorized API key (401/403).
+    Auth,
+    /// Too many requests or quota exhausted (429).
+    RateLimited,
+    /// The provider failed (5xx).
+    Server,
+    /// No response within the policy's timeout.
+    Timeout,
+    /// Couldn't connect or the connection dropped.
+    Network,
+    /// Any other rejected request, e.g. an unsupported model or file (4xx).
+    Rejected,
+}
+
+impl ErrorKind {
+    fn from_status(status: StatusCode) -> Self {
+        match status.as_u16() {
+            401 | 403 => Self::Auth,
+            429 => Self::RateLimited,
+            500..=599 => Self::Server,
+            _ => Self::Rejected,
+        }
+    }
+
+    fn is_retryable(self) -> bool {
+        matches!(self, Self::RateLimited | Self::Server | Self::Network)
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Auth => "rejected the API key",
+            Self::RateLimited => "rate limit exceeded",
+            Self::Server => "server error",
+            Self::Timeout => "request timed out",
+            Self::Network => "network error",
+            Self::Rejected => "rejected the request",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{provider} {}: {message}", kind.describe())]
+pub struct CloudSttError {
+    pub kind: ErrorKind,
+    pub provider: &'static str,
+    pub message: String,
+}
+
+/// Send the request built by `build` until it succeeds, retrying rate
+/// limits, server errors and dropped connections per `policy`. `build` is
+/// called once per attempt since multipart bodies can't be reused.
+pub async fn send(
+    provider: &'static str,
+    policy: &RequestPolicy,
+    build: impl Fn(&Client) -> Result<RequestBuilder, reqwest::Error>,
+) -> Result<Response, CloudSttError> {
+    let client = Client::builder()
+        .timeout(policy.timeout)
+        .build()
+        .unwrap_or_else(|_| Client::new());
+    let error = |kind, message: String| CloudSttError {
+        kind,
+        provider,
+        message,
+    };
+
+    let mut attempt = 0;
+    loop {
+        let request = build(&client).map_err(|e| error(ErrorKind::Rejected, e.to_string()))?;
+        let (failure, retry_after) = match request.send().await {
+            Ok(resp) if resp.status().is_success() => return Ok(resp),
+            Ok(resp) => {
+                let status = resp.status();
+                let retry_after = resp
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.trim().parse::<u64>().ok())
+                    .map(Duration::from_secs);
+                let body = resp.text().await.unwrap_or_default();
+                let failure = error(
+                    ErrorKind::from_status(status),
+                    format!("{} {}", status, body),
+                );
+                (failure, retry_after)
+            }
+            Err(e) if e.is_timeout() => {
+                let secs = policy.timeout.as_secs();
+                return Err(error(
+                    ErrorKind::Timeout,
+                    format!("no response after {}s", secs),
+                ));
+            }
+            Err(e) => (error(ErrorKind::Network, e.to_string()), None),
+        };
+
+        attempt += 1;
+        if !failure.kind.is_retryable() || attempt > policy.max_retries {
+            return Err(failure);
+        }
+        let delay = policy.backoff(attempt, retry_after);
+        log::warn!(
+            "{}; retrying in {:.1}s ({}/{})",
+            failure,
+            delay.as_secs_f32(),
+            attempt,
+            policy.max_retries
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_categories() {
+        assert_eq!(
+            ErrorKind::from_status(StatusCode::UNAUTHORIZED),
+            ErrorKind::Auth
+        );
+        assert_eq!(
+            ErrorKind::from_status(StatusCode::FORBIDDEN),
+            ErrorKind::Auth
+        );
+        assert_eq!(
+            ErrorKind::from_status(StatusCode::TOO_MANY_REQUESTS),
+            ErrorKind::RateLimited
+        );
+        assert_eq!(
+            ErrorKind::from_status(StatusCode::BAD_GATEWAY),
+            ErrorKind::Server
+        );
+        assert_eq!(
+            ErrorKind::from_status(StatusCode::PAYLOAD_TOO_LARGE),
+            ErrorKind::Rejected
+        );
+
+        assert!(ErrorKind::RateLimited.is_retryable());
+        assert!(ErrorKind::Server.is_retryable());
+        assert!(!ErrorKind::Auth.is_retryable());
+        assert!(!ErrorKind::Rejected.is_retryable());
+        assert!(!ErrorKind::Timeout.is_retryable());
+    }
+
+    #[test]
+    fn backoff_doubles_and_honors_retry_after() {
+        let policy = RequestPolicy::default();
+        assert_eq!(policy.backoff(1, None), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3, None), Duration::from_secs(4));
+        assert_eq!(policy.backoff(10, None), MAX_BACKOFF);
+        assert_eq!(
+            policy.backoff(1, Some(Duration::from_secs(7))),
+            Duration::from_secs(7)
+        );
+        assert_eq!(
+            policy.backoff(1, Some(Duration::from_secs(600))),
+            MAX_BACKOFF
+        );
+    }
+
+    #[test]
+    fn error_message_names_provider_and_category() {
+        let err = CloudSttError {
+            kind: ErrorKind::RateLimited,
+            provider: "Groq",
+            message: "429 Too Many Requests".into(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Groq rate limit exceeded: 429 Too Many Requests"
+        );
+    }
+}
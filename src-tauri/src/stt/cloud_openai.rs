@@ -1,7 +1,12 @@
+use super::cloud_http::{self, RequestPolicy};
 use super::{Segment, TranscriptionOptions, TranscriptionResult};
 use crate::audio::wav;
 use serde::Deserialize;
 
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// `verbose_json` response of whisper-1; the gpt-4o models only return
+/// `json`, which has just the text.
 #[derive(Debug, Deserialize)]
 struct OpenAiTranscription {
     text: String,
@@ -16,69 +21,199 @@ struct OpenAiSegment {
     text: String,
 }
 
-/// Transcribe audio using the OpenAI Whisper API.
+/// Richest response format `model` supports: the gpt-4o models only
+/// return text, while whisper-1 and self-hosted Whisper servers also return
+/// segments and the detected language.
+fn response_format(model: &str) -> &'static str {
+    if model.starts_with("gpt-4o") {
+        "json"
+    } else {
+        "verbose_json"
+    }
+}
+
+/// Transcriptions endpoint under `base_url`, e.g. `http://localhost:8000/v1`
+/// for a self-hosted OpenAI-compatible server, or OpenAI's own.
+fn transcriptions_url(base_url: Option<&str>) -> String {
+    let base = base_url
+        .map(|url| url.trim().trim_end_matches('/'))
+        .filter(|url| !url.is_empty())
+        .unwrap_or(DEFAULT_BASE_URL);
+    format!("{}/audio/transcriptions", base)
+}
+
+fn into_result(
+    transcription: OpenAiTranscription,
+    audio_ms: u64,
+    inference_ms: u64,
+) -> TranscriptionResult {
+    let segments = match transcription.segments {
+        Some(segments) => segments
+            .into_iter()
+            .map(|s| Segment {
+                start_ms: (s.start * 1000.0) as u64,
+                end_ms: (s.end * 1000.0) as u64,
+                text: s.text,
+            })
+            .collect(),
+        // Without timestamps, the text spans the whole clip
+        None if !transcription.text.trim().is_empty() => vec![Segment {
+            start_ms: 0,
+            end_ms: audio_ms,
+            text: transcription.text.clone(),
+        }],
+        None => vec![],
+    };
+
+    TranscriptionResult {
+        text: transcription.text,
+        language: transcription.language,
+        segments,
+        duration_ms: inference_ms,
+        metrics: None,
+    }
+}
+
+/// Transcribe audio using the OpenAI transcription API with `model`, e.g.
+/// "whisper-1" or "gpt-4o-transcribe", in several requests when it's too
+/// long for one upload. `base_url` points at an OpenAI-compatible server
+/// instead, such as faster-whisper-server, speaches or LocalAI, which may
+/// not need an API key.
 pub async fn transcribe(
     audio: &[f32],
     options: &TranscriptionOptions,
     api_key: &str,
+    model: &str,
+    base_url: Option<&str>,
+    policy: &RequestPolicy,
 ) -> anyhow::Result<TranscriptionResult> {
     let start = std::time::Instant::now();
+    let chunks = super::cloud_chunks::upload_chunks(audio, 16000);
+    if chunks.len() == 1 {
+        return transcribe_upload(audio, options, api_key, model, base_url, policy).await;
+    }
+
+    log::info!("Uploading {} chunks to OpenAI", chunks.len());
+    let mut parts = Vec::with_capacity(chunks.len());
+    for range in chunks {
+        let part = transcribe_upload(
+            &audio[range.clone()],
+            options,
+            api_key,
+            model,
+            base_url,
+            policy,
+        )
+        .await?;
+        parts.push((range.start, part));
+    }
+    let duration_ms = start.elapsed().as_millis() as u64;
+    Ok(super::cloud_chunks::merge(parts, 16000, duration_ms))
+}
 
-    // Encode audio as WAV bytes (16kHz mono)
-    let wav_bytes = wav::encode_wav_to_bytes(audio, 16000)?;
+/// Transcribe audio that fits in a single upload.
+async fn transcribe_upload(
+    audio: &[f32],
+    options: &TranscriptionOptions,
+    api_key: &str,
+    model: &str,
+    base_url: Option<&str>,
+    policy: &RequestPolicy,
+) -> anyhow::Result<TranscriptionResult> {
+    let start = std::time::Instant::now();
 
-    // Build multipart form
-    let file_part = reqwest::multipart::Part::bytes(wav_bytes)
-        .file_name("audio.wav")
-        .mime_str("audio/wav")?;
+    // Encode audio as 16-bit WAV bytes (16kHz mono)
+    let wav_bytes = wav::encode_wav_pcm16_to_bytes(audio, 16000)?;
+    let url = transcriptions_url(base_url);
 
-    let mut form = reqwest::multipart::Form::new()
-        .part("file", file_part)
-        .text("model", "whisper-1")
-        .text("response_format", "verbose_json");
+    let resp = cloud_http::send("OpenAI", policy, |client| {
+        let file_part = reqwest::multipart::Part::bytes(wav_bytes.clone())
+            .file_name("audio.wav")
+            .mime_str("audio/wav")?;
 
-    if let Some(ref lang) = options.language {
-        if lang != "auto" {
-            form = form.text("language", lang.clone());
+        let mut form = reqwest::multipart::Form::new()
+            .part("file", file_part)
+            .text("model", model.to_string())
+            .text("response_format", response_format(model));
+
+        if let Some(ref lang) = options.language {
+            if lang != "auto" {
+                form = form.text("language", lang.clone());
+            }
+        }
+
+        if let Some(prompt) = options.prompt() {
+            form = form.text("prompt", prompt);
         }
-    }
 
-    if !options.vocabulary.is_empty() {
-        form = form.text("prompt", options.vocabulary.join(", "));
+        let mut request = client.post(&url).multipart(form);
+        if !api_key.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+        Ok(request)
+    })
+    .await?;
+
+    let result: OpenAiTranscription = resp.json().await?;
+    let inference_ms = start.elapsed().as_millis() as u64;
+    let audio_ms = audio.len() as u64 * 1000 / 16000;
+
+    Ok(into_result(result, audio_ms, inference_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_format_per_model() {
+        assert_eq!(response_format("whisper-1"), "verbose_json");
+        assert_eq!(response_format("gpt-4o-transcribe"), "json");
+        assert_eq!(response_format("gpt-4o-mini-transcribe"), "json");
+        assert_eq!(
+            response_format("Systran/faster-whisper-small"),
+            "verbose_json"
+        );
     }
 
-    let client = reqwest::Client::new();
-    let resp = client
-        .post("https://api.openai.com/v1/audio/transcriptions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .multipart(form)
-        .send()
-        .await?;
+    #[test]
+    fn transcriptions_url_uses_custom_base() {
+        assert_eq!(
+            transcriptions_url(None),
+            "https://api.openai.com/v1/audio/transcriptions"
+        );
+        assert_eq!(
+            transcriptions_url(Some(" http://localhost:8000/v1/ ")),
+            "http://localhost:8000/v1/audio/transcriptions"
+        );
+        assert_eq!(
+            transcriptions_url(Some("")),
+            "https://api.openai.com/v1/audio/transcriptions"
+        );
+    }
 
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        anyhow::bail!("OpenAI API error {}: {}", status, body);
+    #[test]
+    fn verbose_response_keeps_segments() {
+        let body = r#"{"text":"Hello there.","language":"english","segments":[
+            {"id":0,"start":0.0,"end":1.2,"text":"Hello there."}]}"#;
+        let result = into_result(serde_json::from_str(body).unwrap(), 2000, 300);
+        assert_eq!(result.language.as_deref(), Some("english"));
+        assert_eq!(result.segments.len(), 1);
+        assert_eq!(result.segments[0].end_ms, 1200);
+        assert_eq!(result.duration_ms, 300);
     }
 
-    let result: OpenAiTranscription = resp.json().await?;
-    let inference_ms = start.elapsed().as_millis() as u64;
+    #[test]
+    fn json_response_spans_the_clip() {
+        let body = r#"{"text":"Hello there.","usage":{"type":"tokens","total_tokens":12}}"#;
+        let result = into_result(serde_json::from_str(body).unwrap(), 2000, 300);
+        assert_eq!(result.text, "Hello there.");
+        assert_eq!(result.language, None);
+        assert_eq!(result.segments.len(), 1);
+        assert_eq!(result.segments[0].start_ms, 0);
+        assert_eq!(result.segments[0].end_ms, 2000);
 
-    let segments = result
-        .segments
-        .unwrap_or_default()
-        .into_iter()
-        .map(|s| Segment {
-            start_ms: (s.start * 1000.0) as u64,
-            end_ms: (s.end * 1000.0) as u64,
-            text: s.text,
-        })
-        .collect();
-
-    Ok(TranscriptionResult {
-        text: result.text,
-        language: result.language,
-        segments,
-        duration_ms: inference_ms,
-    })
+        let empty = into_result(serde_json::from_str(r#"{"text":""}"#).unwrap(), 2000, 300);
+        assert!(empty.segments.is_empty());
+    }
 }
@@ -1,10 +1,23 @@
+pub mod abort;
+pub mod benchmark;
+pub mod cloud_chunks;
 pub mod cloud_groq;
+pub mod cloud_http;
 pub mod cloud_openai;
 pub mod parakeet;
+pub mod punctuation;
+pub mod queue;
+pub mod vad;
 pub mod whisper;
 pub mod whisper_onnx;
 
+use abort::AbortSignal;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// Receives inference progress from 0.0 to 1.0.
+pub type ProgressFn = Arc<dyn Fn(f32) + Send + Sync>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptionResult {
@@ -12,6 +25,34 @@ pub struct TranscriptionResult {
     pub language: Option<String>,
     pub segments: Vec<Segment>,
     pub duration_ms: u64,
+    /// Filled in by the transcription pipeline, not by the engines.
+    #[serde(default)]
+    pub metrics: Option<InferenceMetrics>,
+}
+
+/// How fast a transcription ran, kept with the history entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InferenceMetrics {
+    pub engine: String,
+    pub inference_ms: u64,
+    /// Inference time / audio duration; below 1.0 is faster than real time.
+    pub real_time_factor: f64,
+    /// CPU threads used by a local engine; `None` for cloud APIs.
+    pub threads: Option<u32>,
+}
+
+impl InferenceMetrics {
+    /// Metrics for `inference_ms` spent on `samples` of 16 kHz audio.
+    pub fn new(engine: &str, threads: Option<u32>, inference_ms: u64, samples: usize) -> Self {
+        let audio_ms = (samples as u64 * 1000 / 16000).max(1);
+        Self {
+            engine: engine.to_string(),
+            inference_ms,
+            real_time_factor: inference_ms as f64 / audio_ms as f64,
+            threads,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +66,108 @@ pub struct Segment {
 pub struct TranscriptionOptions {
     pub language: Option<String>,
     pub vocabulary: Vec<String>,
+    /// Free-form context to bias recognition, e.g. "A cardiology dictation".
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+    #[serde(default)]
+    pub decoding: DecodingOptions,
+    /// Split at pauses and detect the language of each chunk (Whisper only).
+    #[serde(default)]
+    pub code_switching: bool,
+    /// Languages code-switching may pick from; empty allows any.
+    #[serde(default)]
+    pub code_switch_languages: Vec<String>,
+    /// Sample offsets code-switching splits at; empty splits at detected
+    /// pauses. VAD-joined audio passes the seams between its speech regions,
+    /// whose padding leaves pauses too short to detect.
+    #[serde(skip)]
+    pub code_switch_cuts: Vec<usize>,
+}
+
+impl TranscriptionOptions {
+    /// Prompt text for the engine: the initial prompt followed by the
+    /// vocabulary terms, or `None` when both are empty.
+    pub fn prompt(&self) -> Option<String> {
+        let initial = self
+            .initial_prompt
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty());
+        let vocabulary = (!self.vocabulary.is_empty()).then(|| self.vocabulary.join(", "));
+        match (initial, vocabulary) {
+            (Some(initial), Some(vocabulary)) => Some(format!("{} {}", initial, vocabulary)),
+            (Some(initial), None) => Some(initial.to_string()),
+            (None, vocabulary) => vocabulary,
+        }
+    }
+}
+
+/// Rough token count of `text` for prompt budgeting. Whisper's tokenizer
+/// splits names and jargon finely, so this errs high at three characters
+/// per token.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(3)
+}
+
+/// Keep the terms, in order, that fit in `budget` tokens once joined with
+/// ", ". A term too long for the remaining room is skipped so shorter ones
+/// after it can still fit.
+pub fn terms_within_budget(terms: Vec<String>, budget: usize) -> Vec<String> {
+    let mut used = 0;
+    terms
+        .into_iter()
+        .filter(|term| {
+            // One token for the separator
+            let cost = estimate_tokens(term) + 1;
+            let fits = used + cost <= budget;
+            if fits {
+                used += cost;
+            }
+            fits
+        })
+        .collect()
+}
+
+/// Whisper decoding and anti-hallucination parameters. The defaults match
+/// what the app has always used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DecodingOptions {
+    /// 1 decodes greedily; larger values use beam search.
+    pub beam_size: u32,
+    /// Initial sampling temperature.
+    pub temperature: f32,
+    /// Added to the temperature on each fallback retry; 0 disables fallback.
+    pub temperature_inc: f32,
+    /// Segments more likely than this to be silence are dropped.
+    pub no_speech_thold: f32,
+    /// Segments with higher token entropy are retried or dropped.
+    pub entropy_thold: f32,
+}
+
+impl Default for DecodingOptions {
+    fn default() -> Self {
+        Self {
+            beam_size: 5,
+            temperature: 0.0,
+            temperature_inc: 0.2,
+            no_speech_thold: 0.6,
+            entropy_thold: 2.4,
+        }
+    }
+}
+
+impl DecodingOptions {
+    /// Clamp values from settings into ranges whisper.cpp handles.
+    pub fn sanitized(&self) -> Self {
+        Self {
+            beam_size: self.beam_size.clamp(1, 10),
+            temperature: self.temperature.clamp(0.0, 1.0),
+            temperature_inc: self.temperature_inc.clamp(0.0, 1.0),
+            no_speech_thold: self.no_speech_thold.clamp(0.0, 1.0),
+            entropy_thold: self.entropy_thold.max(0.0),
+        }
+    }
 }
 
 pub trait SttEngine: Send + Sync {
@@ -34,5 +177,122 @@ pub trait SttEngine: Send + Sync {
         options: &TranscriptionOptions,
     ) -> anyhow::Result<TranscriptionResult>;
 
+    /// Like `transcribe`, reporting progress along the way and stopping
+    /// early once `abort` is raised, where the engine supports it. Engines
+    /// that don't just transcribe.
+    fn transcribe_with_progress(
+        &self,
+        audio: &[f32],
+        options: &TranscriptionOptions,
+        on_progress: &ProgressFn,
+        abort: &AbortSignal,
+    ) -> anyhow::Result<TranscriptionResult> {
+        let _ = (on_progress, abort);
+        self.transcribe(audio, options)
+    }
+
     fn engine_name(&self) -> &str;
+
+    /// CPU threads used for inference, if the engine runs locally.
+    fn threads(&self) -> Option<u32> {
+        None
+    }
+}
+
+/// Threads used when none are configured: all cores up to this many.
+const MAX_AUTO_THREADS: usize = 8;
+
+/// `inferenceThreads` setting; 0 picks automatically.
+static REQUESTED_THREADS: AtomicU32 = AtomicU32::new(0);
+static LOW_POWER: AtomicBool = AtomicBool::new(false);
+
+/// Configure the inference threads of engines loaded from now on (and of
+/// each Whisper run). `threads` of 0 picks automatically.
+pub fn set_threads(threads: u32, low_power: bool) {
+    REQUESTED_THREADS.store(threads, Ordering::Relaxed);
+    LOW_POWER.store(low_power, Ordering::Relaxed);
+}
+
+/// Whether low-power mode is on: fewer threads and greedy Whisper decoding.
+pub fn low_power() -> bool {
+    LOW_POWER.load(Ordering::Relaxed)
+}
+
+/// Inference threads for local engines, as configured with [`set_threads`].
+pub fn default_threads() -> i32 {
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+    thread_count(
+        REQUESTED_THREADS.load(Ordering::Relaxed),
+        low_power(),
+        cores,
+    )
+}
+
+/// `requested` threads (at most one per core), or all cores capped at
+/// [`MAX_AUTO_THREADS`] when that's 0. Low-power mode uses half as many.
+fn thread_count(requested: u32, low_power: bool, cores: usize) -> i32 {
+    let threads = match requested as usize {
+        0 => cores.min(MAX_AUTO_THREADS),
+        n => n.min(cores),
+    };
+    let threads = if low_power { threads / 2 } else { threads };
+    threads.max(1) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thread_count_follows_settings() {
+        assert_eq!(thread_count(0, false, 16), 8);
+        assert_eq!(thread_count(0, false, 4), 4);
+        assert_eq!(thread_count(12, false, 16), 12);
+        assert_eq!(thread_count(12, false, 6), 6);
+        assert_eq!(thread_count(0, true, 16), 4);
+        assert_eq!(thread_count(1, true, 16), 1);
+    }
+
+    #[test]
+    fn prompt_combines_initial_prompt_and_vocabulary() {
+        let mut options = TranscriptionOptions {
+            language: None,
+            vocabulary: vec![],
+            initial_prompt: Some("  ".into()),
+            decoding: DecodingOptions::default(),
+            code_switching: false,
+            code_switch_languages: vec![],
+            code_switch_cuts: vec![],
+        };
+        assert_eq!(options.prompt(), None);
+
+        options.vocabulary = vec!["Tauri".into(), "Zustand".into()];
+        assert_eq!(options.prompt().as_deref(), Some("Tauri, Zustand"));
+
+        options.initial_prompt = Some(" A talk about desktop apps. ".into());
+        assert_eq!(
+            options.prompt().as_deref(),
+            Some("A talk about desktop apps. Tauri, Zustand")
+        );
+
+        options.vocabulary.clear();
+        assert_eq!(options.prompt().as_deref(), Some("A talk about desktop apps."));
+    }
+
+    #[test]
+    fn terms_within_budget_keeps_ranked_terms_that_fit() {
+        let terms: Vec<String> = ["Tauri", "Herzinsuffizienz", "Rust", "Zustand"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        // Tauri 2+1, Herzinsuffizienz 6+1 (skipped), Rust 2+1, Zustand 3+1
+        assert_eq!(
+            terms_within_budget(terms.clone(), 8),
+            vec!["Tauri".to_string(), "Rust".into()]
+        );
+        assert_eq!(terms_within_budget(terms.clone(), 100), terms);
+        assert!(terms_within_budget(terms, 0).is_empty());
+    }
 }
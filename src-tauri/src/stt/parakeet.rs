@@ -1,10 +1,104 @@
 use super::{Segment, SttEngine, TranscriptionOptions, TranscriptionResult};
 use sherpa_rs::transducer::{TransducerConfig, TransducerRecognizer};
 use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::Mutex;
 
+/// ONNX Runtime execution provider Parakeet runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Provider {
+    Cpu = 1,
+    /// Apple Neural Engine and GPU, on macOS.
+    CoreMl = 2,
+    /// NVIDIA GPUs, in builds with the `cuda` feature.
+    Cuda = 3,
+    /// Any DirectX 12 GPU, on Windows.
+    DirectMl = 4,
+}
+
+impl Provider {
+    pub const ALL: [Self; 4] = [Self::Cpu, Self::CoreMl, Self::Cuda, Self::DirectMl];
+
+    /// Parse the `parakeetProvider` setting; "auto" picks the best available.
+    pub fn parse(value: &str) -> Result<Option<Self>, String> {
+        match value {
+            "auto" | "" => Ok(None),
+            other => Self::ALL
+                .into_iter()
+                .find(|p| p.id() == other)
+                .map(Some)
+                .ok_or_else(|| format!("Unknown execution provider '{}'", other)),
+        }
+    }
+
+    /// Name of the provider in settings and in sherpa-onnx.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu",
+            Self::CoreMl => "coreml",
+            Self::Cuda => "cuda",
+            Self::DirectMl => "directml",
+        }
+    }
+
+    /// Whether this build of sherpa-onnx includes the provider.
+    pub fn is_available(self) -> bool {
+        match self {
+            Self::Cpu => true,
+            Self::CoreMl => cfg!(target_os = "macos"),
+            Self::Cuda => cfg!(feature = "cuda"),
+            Self::DirectMl => cfg!(target_os = "windows"),
+        }
+    }
+}
+
+/// The chosen provider as its `u8` value; 0 picks automatically.
+static REQUESTED_PROVIDER: AtomicU8 = AtomicU8::new(0);
+
+/// Run Parakeet models loaded from now on with `provider`, or the best
+/// available one with `None`.
+pub fn set_provider(provider: Option<Provider>) {
+    REQUESTED_PROVIDER.store(provider.map_or(0, |p| p as u8), Ordering::Relaxed);
+}
+
+/// Providers this build can run Parakeet with.
+pub fn available_providers() -> Vec<Provider> {
+    Provider::ALL
+        .into_iter()
+        .filter(|p| p.is_available())
+        .collect()
+}
+
+/// The provider to load with, as configured with [`set_provider`].
+fn provider() -> Provider {
+    let requested = Provider::ALL
+        .into_iter()
+        .find(|p| *p as u8 == REQUESTED_PROVIDER.load(Ordering::Relaxed));
+    resolve_provider(requested, Provider::is_available)
+}
+
+/// `requested` if it's available, else the fastest available provider:
+/// a GPU one when there is one, the CPU otherwise.
+fn resolve_provider(requested: Option<Provider>, available: impl Fn(Provider) -> bool) -> Provider {
+    if let Some(provider) = requested {
+        if available(provider) {
+            return provider;
+        }
+        log::warn!(
+            "Execution provider {} isn't in this build, picking another",
+            provider.id()
+        );
+    }
+    [Provider::Cuda, Provider::DirectMl, Provider::CoreMl]
+        .into_iter()
+        .find(|p| available(*p))
+        .unwrap_or(Provider::Cpu)
+}
+
 pub struct ParakeetEngine {
     recognizer: Mutex<TransducerRecognizer>,
+    threads: i32,
 }
 
 impl ParakeetEngine {
@@ -30,15 +124,8 @@ impl ParakeetEngine {
             .ok_or_else(|| anyhow::anyhow!("Invalid tokens path"))?
             .to_string();
 
-        let n_threads = std::thread::available_parallelism()
-            .map(|n| n.get().clamp(1, 8) as i32)
-            .unwrap_or(4);
-
-        // Use CoreML on macOS for GPU acceleration, CPU elsewhere
-        #[cfg(target_os = "macos")]
-        let provider = Some("coreml".to_string());
-        #[cfg(not(target_os = "macos"))]
-        let provider = None;
+        let n_threads = super::default_threads();
+        let provider = provider();
 
         let config = TransducerConfig {
             encoder,
@@ -49,16 +136,16 @@ impl ParakeetEngine {
             sample_rate: 16_000,
             feature_dim: 80,
             model_type: "nemo_transducer".to_string(),
-            provider,
+            provider: Some(provider.id().to_string()),
             debug: false,
             ..Default::default()
         };
 
         log::info!(
-            "Parakeet engine loading: {:?} (threads={}, provider={:?})",
+            "Parakeet engine loading: {:?} (threads={}, provider={})",
             model_dir,
             n_threads,
-            config.provider
+            provider.id()
         );
 
         let start = std::time::Instant::now();
@@ -71,6 +158,7 @@ impl ParakeetEngine {
 
         Ok(Self {
             recognizer: Mutex::new(recognizer),
+            threads: n_threads,
         })
     }
 }
@@ -79,7 +167,7 @@ impl SttEngine for ParakeetEngine {
     fn transcribe(
         &self,
         audio: &[f32],
-        _options: &TranscriptionOptions,
+        options: &TranscriptionOptions,
     ) -> anyhow::Result<TranscriptionResult> {
         log::info!(
             "Parakeet inference starting: {} samples ({:.1}s audio)",
@@ -112,15 +200,53 @@ impl SttEngine for ParakeetEngine {
             vec![]
         };
 
+        // The transducer takes no language input (v3 detects it), so the hint
+        // is only reported, which lets punctuation skip unsupported languages
+        let language = options.language.clone().filter(|l| l != "auto");
+
         Ok(TranscriptionResult {
             text,
-            language: None,
+            language,
             segments,
             duration_ms: inference_ms,
+            metrics: None,
         })
     }
 
     fn engine_name(&self) -> &str {
         "parakeet"
     }
+
+    fn threads(&self) -> Option<u32> {
+        Some(self.threads as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_providers() {
+        assert_eq!(Provider::parse("auto"), Ok(None));
+        assert_eq!(Provider::parse("coreml"), Ok(Some(Provider::CoreMl)));
+        assert_eq!(Provider::parse("directml"), Ok(Some(Provider::DirectMl)));
+        assert!(Provider::parse("tensorrt").is_err());
+    }
+
+    #[test]
+    fn unavailable_provider_falls_back() {
+        let macos = |p: Provider| matches!(p, Provider::Cpu | Provider::CoreMl);
+        assert_eq!(resolve_provider(None, macos), Provider::CoreMl);
+        assert_eq!(resolve_provider(Some(Provider::Cpu), macos), Provider::Cpu);
+        assert_eq!(
+            resolve_provider(Some(Provider::Cuda), macos),
+            Provider::CoreMl
+        );
+        let cpu_only = |p: Provider| p == Provider::Cpu;
+        assert_eq!(
+            resolve_provider(Some(Provider::DirectMl), cpu_only),
+            Provider::Cpu
+        );
+    }
 }
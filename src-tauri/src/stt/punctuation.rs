@@ -0,0 +1,112 @@
+//! CT-Transformer punctuation for engines that return bare words: adds
+//! commas and sentence ends to English or Chinese text, then capitalizes the
+//! sentences. Text that already has punctuation is left alone.
+
+use sherpa_rs::punctuate::{Punctuation, PunctuationConfig};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const MODEL_FILE: &str = "model.int8.onnx";
+pub const MODEL_URL: &str = "https://huggingface.co/csukuangfj/sherpa-onnx-punct-ct-transformer-zh-en-vocab272727-2024-04-12-int8/resolve/main/model.int8.onnx";
+
+/// Languages the model was trained on; others would get Chinese-style
+/// punctuation.
+const LANGUAGES: &[&str] = &["en", "zh"];
+
+/// The loaded model, kept between transcriptions since loading takes a
+/// second or two.
+static LOADED: Mutex<Option<(PathBuf, Punctuation)>> = Mutex::new(None);
+
+pub fn model_path(storage_dir: &Path) -> PathBuf {
+    crate::models::model_path(storage_dir, crate::models::PUNCTUATION_MODEL_ID).join(MODEL_FILE)
+}
+
+/// Punctuate a result's text and segments if it has none and its language
+/// (when known) is one the model handles.
+pub fn apply(model: &Path, result: &mut super::TranscriptionResult) -> anyhow::Result<()> {
+    let language = result.language.as_deref();
+    if !needs_punctuation(&result.text) || language.is_some_and(|l| !LANGUAGES.contains(&l)) {
+        return Ok(());
+    }
+
+    let mut loaded = LOADED.lock().unwrap();
+    if !matches!(loaded.as_ref(), Some((path, _)) if path == model) {
+        let config = PunctuationConfig {
+            model: model
+                .to_str()
+                .ok_or_else(|| anyhow::anyhow!("Invalid punctuation model path"))?
+                .to_string(),
+            num_threads: Some(1),
+            ..Default::default()
+        };
+        let punctuation = Punctuation::new(config)
+            .map_err(|e| anyhow::anyhow!("Failed to load punctuation model: {}", e))?;
+        *loaded = Some((model.to_path_buf(), punctuation));
+    }
+    let Some((_, punctuation)) = loaded.as_mut() else {
+        return Ok(());
+    };
+
+    result.text = capitalize_sentences(&punctuation.add_punctuation(&result.text));
+    if let [segment] = result.segments.as_mut_slice() {
+        segment.text = result.text.clone();
+    } else {
+        for segment in &mut result.segments {
+            segment.text = capitalize_sentences(&punctuation.add_punctuation(&segment.text));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `text` has words but no sentence punctuation at all.
+fn needs_punctuation(text: &str) -> bool {
+    text.chars().any(char::is_alphanumeric)
+        && !text
+            .chars()
+            .any(|c| matches!(c, '.' | ',' | '?' | '!' | ';' | '。' | '，' | '？' | '！'))
+}
+
+/// Upper-case the first letter of the text and of each sentence after it.
+fn capitalize_sentences(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut sentence_start = true;
+    for c in text.chars() {
+        if sentence_start && c.is_alphabetic() {
+            out.extend(c.to_uppercase());
+            sentence_start = false;
+            continue;
+        }
+        if matches!(c, '.' | '?' | '!') {
+            sentence_start = true;
+        } else if !c.is_whitespace() {
+            sentence_start = false;
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_bare_text_needs_punctuation() {
+        assert!(needs_punctuation("hello how are you"));
+        assert!(!needs_punctuation("Hello, how are you?"));
+        assert!(!needs_punctuation("你好。"));
+        assert!(!needs_punctuation("  "));
+    }
+
+    #[test]
+    fn capitalizes_each_sentence() {
+        assert_eq!(
+            capitalize_sentences("hello, how are you? fine. ok"),
+            "Hello, how are you? Fine. Ok"
+        );
+        assert_eq!(
+            capitalize_sentences("version 2.0 is out"),
+            "Version 2.0 is out"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Turn-taking for local inference: one transcription runs at a time, and
+//! dictations waiting for a turn go before file and folder jobs, so a batch
+//! import doesn't make push-to-talk wait for every file ahead of it.
+
+use std::sync::{Condvar, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// A dictation someone is waiting to paste.
+    Interactive,
+    /// File imports, watched folders and benchmarks.
+    Background,
+}
+
+#[derive(Default)]
+struct QueueState {
+    running: bool,
+    waiting_interactive: usize,
+}
+
+pub struct TranscriptionQueue {
+    state: Mutex<QueueState>,
+    turn_ended: Condvar,
+}
+
+impl TranscriptionQueue {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(QueueState::default()),
+            turn_ended: Condvar::new(),
+        }
+    }
+
+    /// Block until nothing else is running and, for background work, no
+    /// dictation is waiting.
+    pub fn acquire(&self, priority: Priority) -> Turn<'_> {
+        let interactive = priority == Priority::Interactive;
+        let mut state = self.state.lock().unwrap();
+        if interactive {
+            state.waiting_interactive += 1;
+        }
+        while state.running || (!interactive && state.waiting_interactive > 0) {
+            state = self.turn_ended.wait(state).unwrap();
+        }
+        if interactive {
+            state.waiting_interactive -= 1;
+        }
+        state.running = true;
+        Turn { queue: self }
+    }
+
+    /// Take a turn only if nothing is running or waiting, for work that can
+    /// be skipped like live partial transcripts.
+    pub fn try_acquire(&self) -> Option<Turn<'_>> {
+        let mut state = self.state.lock().unwrap();
+        if state.running || state.waiting_interactive > 0 {
+            return None;
+        }
+        state.running = true;
+        Some(Turn { queue: self })
+    }
+}
+
+/// The running transcription's turn; the next one starts when it's dropped.
+pub struct Turn<'a> {
+    queue: &'a TranscriptionQueue,
+}
+
+impl Drop for Turn<'_> {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().running = false;
+        self.queue.turn_ended.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn dictation_goes_before_queued_background_work() {
+        let queue = Arc::new(TranscriptionQueue::new());
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let running = queue.acquire(Priority::Background);
+
+        let spawn = |priority: Priority| {
+            let (queue, order) = (queue.clone(), order.clone());
+            std::thread::spawn(move || {
+                let _turn = queue.acquire(priority);
+                order.lock().unwrap().push(priority);
+            })
+        };
+        let background = spawn(Priority::Background);
+        std::thread::sleep(Duration::from_millis(50));
+        let interactive = spawn(Priority::Interactive);
+        std::thread::sleep(Duration::from_millis(50));
+
+        drop(running);
+        background.join().unwrap();
+        interactive.join().unwrap();
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec![Priority::Interactive, Priority::Background]
+        );
+    }
+
+    #[test]
+    fn try_acquire_only_when_idle() {
+        let queue = TranscriptionQueue::new();
+        let turn = queue.try_acquire();
+        assert!(turn.is_some());
+        assert!(queue.try_acquire().is_none());
+        drop(turn);
+        assert!(queue.try_acquire().is_some());
+    }
+}
@@ -0,0 +1,158 @@
+//! Silero VAD pre-segmentation: local Whisper only sees the speech in a
+//! recording, and segment timestamps are mapped back to the original audio.
+
+use sherpa_rs::silero_vad::{SileroVad, SileroVadConfig};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+pub const MODEL_FILE: &str = "silero_vad.onnx";
+pub const MODEL_URL: &str =
+    "https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/silero_vad.onnx";
+
+/// Samples per millisecond of the 16kHz audio the engines receive.
+const SAMPLES_PER_MS: usize = 16;
+/// Samples per VAD inference window.
+const WINDOW_SIZE: usize = 512;
+/// Audio kept either side of each speech region so word edges survive.
+const PAD_MS: usize = 200;
+
+pub fn model_path(storage_dir: &Path) -> PathBuf {
+    crate::models::model_path(storage_dir, crate::models::VAD_MODEL_ID).join(MODEL_FILE)
+}
+
+/// Padded sample ranges of 16kHz mono `audio` that contain speech.
+pub fn speech_regions(model: &Path, audio: &[f32]) -> anyhow::Result<Vec<Range<usize>>> {
+    let config = SileroVadConfig {
+        model: model
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("Invalid VAD model path"))?
+            .to_string(),
+        min_silence_duration: 0.5,
+        min_speech_duration: 0.25,
+        max_speech_duration: 30.0,
+        threshold: 0.5,
+        sample_rate: 16000,
+        window_size: WINDOW_SIZE as i32,
+        provider: None,
+        num_threads: Some(1),
+        debug: false,
+    };
+    let buffer_secs = (audio.len() / 16000 + 1) as f32;
+    let mut vad = SileroVad::new(config, buffer_secs)
+        .map_err(|e| anyhow::anyhow!("Failed to load VAD model: {}", e))?;
+
+    for window in audio.chunks(WINDOW_SIZE) {
+        vad.accept_waveform(window.to_vec());
+    }
+    vad.flush();
+
+    let mut regions = Vec::new();
+    while !vad.is_empty() {
+        let segment = vad.front();
+        let start = segment.start.max(0) as usize;
+        regions.push(start..start + segment.samples.len());
+        vad.pop();
+    }
+    Ok(pad_and_merge(regions, PAD_MS * SAMPLES_PER_MS, audio.len()))
+}
+
+/// Widen each region by `pad` samples within `0..len` and merge regions
+/// that then touch.
+fn pad_and_merge(mut regions: Vec<Range<usize>>, pad: usize, len: usize) -> Vec<Range<usize>> {
+    regions.sort_by_key(|r| r.start);
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for region in regions {
+        let padded = region.start.saturating_sub(pad)..(region.end + pad).min(len);
+        match merged.last_mut() {
+            Some(last) if padded.start <= last.end => last.end = last.end.max(padded.end),
+            _ => merged.push(padded),
+        }
+    }
+    merged
+}
+
+/// Speech regions of a recording joined into one buffer, remembering where
+/// each came from.
+pub struct SpeechAudio {
+    pub samples: Vec<f32>,
+    regions: Vec<Range<usize>>,
+}
+
+impl SpeechAudio {
+    pub fn new(audio: &[f32], regions: Vec<Range<usize>>) -> Self {
+        let samples = regions
+            .iter()
+            .flat_map(|r| audio[r.clone()].iter().copied())
+            .collect();
+        Self { samples, regions }
+    }
+
+    /// Map a time in the joined speech back to the original recording.
+    pub fn original_ms(&self, ms: u64) -> u64 {
+        let pos = ms as usize * SAMPLES_PER_MS;
+        let mut offset = 0;
+        for region in &self.regions {
+            if pos < offset + region.len() {
+                return ((region.start + pos - offset) / SAMPLES_PER_MS) as u64;
+            }
+            offset += region.len();
+        }
+        let end = self.regions.last().map_or(0, |r| r.end);
+        ((end + pos - offset) / SAMPLES_PER_MS) as u64
+    }
+
+    /// Offsets in `samples` where one speech region ends and the next begins.
+    pub fn seams(&self) -> Vec<usize> {
+        self.regions
+            .iter()
+            .scan(0, |offset, region| {
+                *offset += region.len();
+                Some(*offset)
+            })
+            .take(self.regions.len().saturating_sub(1))
+            .collect()
+    }
+
+    /// Shift a result's segment timestamps back onto the original recording.
+    pub fn remap(&self, result: &mut super::TranscriptionResult) {
+        for segment in &mut result.segments {
+            segment.start_ms = self.original_ms(segment.start_ms);
+            segment.end_ms = self.original_ms(segment.end_ms);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad_and_merge_joins_close_regions() {
+        let regions = vec![16000..32000, 0..800, 35000..40000];
+        assert_eq!(
+            pad_and_merge(regions, 3200, 41000),
+            vec![0..4000, 12800..41000]
+        );
+        assert!(pad_and_merge(vec![], 3200, 41000).is_empty());
+    }
+
+    #[test]
+    fn original_ms_maps_across_removed_silence() {
+        let audio = vec![0.0; 64000];
+        // 1s..2s and 3s..3.5s of a 4s recording
+        let speech = SpeechAudio::new(&audio, vec![16000..32000, 48000..56000]);
+        assert_eq!(speech.samples.len(), 24000);
+        assert_eq!(speech.original_ms(0), 1000);
+        assert_eq!(speech.original_ms(999), 1999);
+        assert_eq!(speech.original_ms(1000), 3000);
+        assert_eq!(speech.original_ms(1500), 3500);
+    }
+
+    #[test]
+    fn seams_fall_between_regions() {
+        let audio = vec![0.0; 64000];
+        let speech = SpeechAudio::new(&audio, vec![0..16000, 24000..40000, 48000..56000]);
+        assert_eq!(speech.seams(), vec![16000, 32000]);
+        assert!(SpeechAudio::new(&audio, vec![]).seams().is_empty());
+    }
+}
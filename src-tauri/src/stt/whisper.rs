@@ -1,8 +1,14 @@
-use super::{Segment, SttEngine, TranscriptionOptions, TranscriptionResult};
+use super::abort::AbortSignal;
+use super::{ProgressFn, Segment, SttEngine, TranscriptionOptions, TranscriptionResult};
 use std::path::Path;
 use std::sync::Arc;
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
+/// Pauses at least this long may separate phrases in different languages.
+const CODE_SWITCH_SILENCE_MS: u32 = 400;
+/// Shorter chunks are too little audio for reliable language detection.
+const CODE_SWITCH_MIN_CHUNK_MS: u32 = 2000;
+
 pub struct WhisperEngine {
     ctx: Arc<WhisperContext>,
 }
@@ -19,26 +25,155 @@ impl WhisperEngine {
             .map_err(|e| anyhow::anyhow!("Failed to load Whisper model: {:?}", e))?;
 
         log::info!("Whisper model loaded: {:?}", model_path);
+        // whisper.cpp picks up the CoreML encoder next to the model by itself
+        #[cfg(target_os = "macos")]
+        if let Some(stem) = model_path.file_stem().and_then(|s| s.to_str()) {
+            let encoder = model_path.with_file_name(format!("{}-encoder.mlmodelc", stem));
+            if encoder.exists() {
+                log::info!("Encoding with CoreML: {:?}", encoder);
+            } else {
+                log::info!("No CoreML encoder, encoding on Metal");
+            }
+        }
 
         Ok(Self { ctx: Arc::new(ctx) })
     }
-}
 
-impl SttEngine for WhisperEngine {
-    fn transcribe(
+    /// Most likely language of the audio, restricted to `candidates` unless
+    /// that's empty.
+    fn detect_language(
+        &self,
+        audio: &[f32],
+        candidates: &[String],
+    ) -> anyhow::Result<Option<String>> {
+        let mut state = self
+            .ctx
+            .create_state()
+            .map_err(|e| anyhow::anyhow!("Failed to create Whisper state: {:?}", e))?;
+        let threads = super::default_threads() as usize;
+        state
+            .pcm_to_mel(audio, threads)
+            .map_err(|e| anyhow::anyhow!("Failed to compute mel spectrogram: {:?}", e))?;
+        let (detected, probs) = state
+            .lang_detect(0, threads)
+            .map_err(|e| anyhow::anyhow!("Language detection failed: {:?}", e))?;
+
+        let best = candidates
+            .iter()
+            .filter_map(|lang| whisper_rs::get_lang_id(lang))
+            .filter(|&id| (id as usize) < probs.len())
+            .max_by(|a, b| probs[*a as usize].total_cmp(&probs[*b as usize]))
+            .unwrap_or(detected);
+        Ok(whisper_rs::get_lang_str(best).map(|s| s.to_string()))
+    }
+
+    /// Transcribe each pause-separated chunk in its own detected language and
+    /// merge the results. The reported language is that of the longest chunk.
+    fn run_code_switched(
+        &self,
+        audio: &[f32],
+        options: &TranscriptionOptions,
+        on_progress: Option<ProgressFn>,
+        abort: Option<&AbortSignal>,
+    ) -> anyhow::Result<TranscriptionResult> {
+        let start = std::time::Instant::now();
+        let chunks = if options.code_switch_cuts.is_empty() {
+            crate::audio::processing::split_on_silence(
+                audio,
+                16000,
+                CODE_SWITCH_SILENCE_MS,
+                CODE_SWITCH_MIN_CHUNK_MS,
+            )
+        } else {
+            let min_len = (CODE_SWITCH_MIN_CHUNK_MS * 16) as usize;
+            crate::audio::processing::split_at(&options.code_switch_cuts, audio.len(), min_len)
+        };
+        let total = audio.len().max(1) as f32;
+
+        let mut text = String::new();
+        let mut segments = Vec::new();
+        let mut language = None;
+        let mut longest = 0;
+        for range in chunks {
+            let chunk = &audio[range.clone()];
+            let detected = self
+                .detect_language(chunk, &options.code_switch_languages)
+                .unwrap_or_else(|e| {
+                    log::warn!("{}; using the configured language", e);
+                    None
+                });
+            log::info!(
+                "Code-switching chunk at {:.1}s ({:.1}s): lang={:?}",
+                range.start as f64 / 16000.0,
+                chunk.len() as f64 / 16000.0,
+                detected,
+            );
+            let chunk_options = TranscriptionOptions {
+                language: detected.or_else(|| options.language.clone()),
+                code_switching: false,
+                ..options.clone()
+            };
+
+            // Scale the chunk's progress into its share of the whole recording
+            let chunk_progress = on_progress.clone().map(|on_progress| {
+                let (offset, share) = (range.start as f32 / total, chunk.len() as f32 / total);
+                Arc::new(move |p: f32| on_progress(offset + p * share)) as ProgressFn
+            });
+            let result = self.run(chunk, &chunk_options, chunk_progress, abort)?;
+
+            let offset_ms = range.start as u64 * 1000 / 16000;
+            segments.extend(result.segments.into_iter().map(|s| Segment {
+                start_ms: s.start_ms + offset_ms,
+                end_ms: s.end_ms + offset_ms,
+                text: s.text,
+            }));
+            if !result.text.is_empty() {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(&result.text);
+            }
+            if chunk.len() > longest {
+                longest = chunk.len();
+                language = result.language;
+            }
+        }
+
+        Ok(TranscriptionResult {
+            text,
+            language,
+            segments,
+            duration_ms: start.elapsed().as_millis() as u64,
+            metrics: None,
+        })
+    }
+
+    fn run(
         &self,
         audio: &[f32],
         options: &TranscriptionOptions,
+        on_progress: Option<ProgressFn>,
+        abort: Option<&AbortSignal>,
     ) -> anyhow::Result<TranscriptionResult> {
         let mut state = self
             .ctx
             .create_state()
             .map_err(|e| anyhow::anyhow!("Failed to create Whisper state: {:?}", e))?;
 
-        let mut params = FullParams::new(SamplingStrategy::BeamSearch {
-            beam_size: 5,
-            patience: -1.0,
-        });
+        let mut decoding = options.decoding.sanitized();
+        // Beam search costs several times the compute of greedy decoding
+        if super::low_power() {
+            decoding.beam_size = 1;
+        }
+        let strategy = if decoding.beam_size > 1 {
+            SamplingStrategy::BeamSearch {
+                beam_size: decoding.beam_size as i32,
+                patience: -1.0,
+            }
+        } else {
+            SamplingStrategy::Greedy { best_of: 1 }
+        };
+        let mut params = FullParams::new(strategy);
 
         // Language setting
         // NOTE: set_detect_language(true) causes 0 segments with whisper-rs 0.15 + Metal.
@@ -48,13 +183,8 @@ impl SttEngine for WhisperEngine {
             _ => params.set_language(Some("en")),
         }
 
-        // Set initial prompt with vocabulary terms if provided
-        let prompt = if !options.vocabulary.is_empty() {
-            options.vocabulary.join(", ")
-        } else {
-            String::new()
-        };
-        if !prompt.is_empty() {
+        // Set initial prompt from the custom prompt and vocabulary terms if provided
+        if let Some(prompt) = options.prompt() {
             params.set_initial_prompt(&prompt);
         }
 
@@ -68,15 +198,29 @@ impl SttEngine for WhisperEngine {
         // no-speech / entropy thresholds to filter phantom segments.
         params.set_suppress_blank(true);
         params.set_suppress_nst(true);
-        params.set_no_speech_thold(0.6);
-        params.set_entropy_thold(2.4);
+        params.set_no_speech_thold(decoding.no_speech_thold);
+        params.set_entropy_thold(decoding.entropy_thold);
+        params.set_temperature(decoding.temperature);
+        params.set_temperature_inc(decoding.temperature_inc);
 
-        // Use available CPU threads (cap at 8)
-        let n_threads = std::thread::available_parallelism()
-            .map(|n| n.get().clamp(1, 8) as i32)
-            .unwrap_or(4);
+        // Configured threads; all cores up to 8 by default
+        let n_threads = super::default_threads();
         params.set_n_threads(n_threads);
 
+        // whisper.cpp reports whole percentages as it moves through the audio
+        if let Some(on_progress) = on_progress {
+            params.set_progress_callback_safe(move |percent: i32| {
+                on_progress(percent.clamp(0, 100) as f32 / 100.0);
+            });
+        }
+
+        // Checked between decoding steps. Passing the closure already boxed
+        // keeps whisper-rs's trampoline type in line with what it stores.
+        if let Some(abort) = abort.cloned() {
+            let should_abort: Box<dyn FnMut() -> bool> = Box::new(move || abort.is_aborted());
+            params.set_abort_callback_safe::<_, Box<dyn FnMut() -> bool>>(should_abort);
+        }
+
         log::info!(
             "Whisper inference starting: {} samples ({:.1}s audio)",
             audio.len(),
@@ -134,10 +278,42 @@ impl SttEngine for WhisperEngine {
             language,
             segments,
             duration_ms: inference_ms,
+            metrics: None,
         })
     }
+}
+
+impl SttEngine for WhisperEngine {
+    fn transcribe(
+        &self,
+        audio: &[f32],
+        options: &TranscriptionOptions,
+    ) -> anyhow::Result<TranscriptionResult> {
+        if options.code_switching {
+            return self.run_code_switched(audio, options, None, None);
+        }
+        self.run(audio, options, None, None)
+    }
+
+    fn transcribe_with_progress(
+        &self,
+        audio: &[f32],
+        options: &TranscriptionOptions,
+        on_progress: &ProgressFn,
+        abort: &AbortSignal,
+    ) -> anyhow::Result<TranscriptionResult> {
+        let abort = Some(abort);
+        if options.code_switching {
+            return self.run_code_switched(audio, options, Some(on_progress.clone()), abort);
+        }
+        self.run(audio, options, Some(on_progress.clone()), abort)
+    }
 
     fn engine_name(&self) -> &str {
         "whisper"
     }
+
+    fn threads(&self) -> Option<u32> {
+        Some(super::default_threads() as u32)
+    }
 }
@@ -28,9 +28,7 @@ impl WhisperOnnxEngine {
             .ok_or_else(|| anyhow::anyhow!("Invalid tokens path"))?
             .to_string();
 
-        let n_threads = std::thread::available_parallelism()
-            .map(|n| n.get().clamp(1, 8) as i32)
-            .unwrap_or(4);
+        let n_threads = super::default_threads();
 
         // GPU acceleration per platform; falls back to CPU if provider fails
         #[cfg(target_os = "macos")]
@@ -129,10 +127,15 @@ impl SttEngine for WhisperOnnxEngine {
             language: detected_lang,
             segments,
             duration_ms: inference_ms,
+            metrics: None,
         })
     }
 
     fn engine_name(&self) -> &str {
         "whisper-onnx"
     }
+
+    fn threads(&self) -> Option<u32> {
+        Some(super::default_threads() as u32)
+    }
 }
@@ -0,0 +1,167 @@
+//! Applies the app profile of the app in front to pasted text. Slack and
+//! similar chat apps send the message on Enter, so line breaks become typed
+//! Shift+Enter; terminals run each pasted line, so lines can be joined.
+//!
+//! macOS asks System Events for the frontmost process; Windows takes the
+//! executable of the foreground window. Other platforms can't tell, so no
+//! profile applies there.
+
+use crate::db::app_profiles::{self, AppProfile};
+
+/// Name of the app that has the keyboard focus.
+pub fn frontmost_app() -> Option<String> {
+    let name = platform::frontmost_app()?.trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// The profile for `app`, if it has one.
+pub fn profile_for(app: &str) -> Option<AppProfile> {
+    match app_profiles::find(app) {
+        Ok(profile) => {
+            if profile.is_some() {
+                log::info!("Pasting with the app profile for {}", app);
+            }
+            profile
+        }
+        Err(e) => {
+            log::warn!("Failed to load app profile for {}: {}", app, e);
+            None
+        }
+    }
+}
+
+/// `text` adjusted for `profile`, split into the pieces to paste: with
+/// "shiftEnter" Shift+Enter is typed between pieces, otherwise there is one.
+pub fn pieces(text: &str, profile: &AppProfile) -> Vec<String> {
+    let text = text.replace("\r\n", "\n");
+    let text = if profile.strip_trailing_newline {
+        text.trim_end_matches('\n')
+    } else {
+        &text
+    };
+    match profile.newline_mode.as_str() {
+        "shiftEnter" => text.split('\n').map(str::to_string).collect(),
+        "space" => vec![text
+            .split('\n')
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")],
+        _ => vec![text.to_string()],
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    pub fn frontmost_app() -> Option<String> {
+        let output = std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(
+                "tell application \"System Events\" to get name of \
+                 first application process whose frontmost is true",
+            )
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetForegroundWindow() -> isize;
+        fn GetWindowThreadProcessId(hwnd: isize, process_id: *mut u32) -> u32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, process_id: u32) -> isize;
+        fn QueryFullProcessImageNameW(
+            process: isize,
+            flags: u32,
+            name: *mut u16,
+            size: *mut u32,
+        ) -> i32;
+        fn CloseHandle(handle: isize) -> i32;
+    }
+
+    pub fn frontmost_app() -> Option<String> {
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd == 0 {
+                return None;
+            }
+            let mut pid = 0;
+            GetWindowThreadProcessId(hwnd, &mut pid);
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if process == 0 {
+                return None;
+            }
+            let mut path = [0u16; 512];
+            let mut len = path.len() as u32;
+            let ok = QueryFullProcessImageNameW(process, 0, path.as_mut_ptr(), &mut len);
+            CloseHandle(process);
+            if ok == 0 {
+                return None;
+            }
+            let path = String::from_utf16_lossy(&path[..len as usize]);
+            std::path::Path::new(&path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    pub fn frontmost_app() -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(newline_mode: &str, strip_trailing_newline: bool) -> AppProfile {
+        AppProfile {
+            id: "p".into(),
+            app_name: "Slack".into(),
+            newline_mode: newline_mode.into(),
+            strip_trailing_newline,
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn shift_enter_splits_lines() {
+        assert_eq!(
+            pieces("Hi all\r\n\nShipping today\n", &profile("shiftEnter", true)),
+            vec!["Hi all", "", "Shipping today"]
+        );
+        assert_eq!(
+            pieces("One\n", &profile("shiftEnter", false)),
+            vec!["One", ""]
+        );
+    }
+
+    #[test]
+    fn space_joins_lines() {
+        assert_eq!(
+            pieces("git status\n\ngit log\n", &profile("space", false)),
+            vec!["git status git log"]
+        );
+    }
+
+    #[test]
+    fn keep_only_strips_when_asked() {
+        assert_eq!(pieces("a\nb\n\n", &profile("keep", true)), vec!["a\nb"]);
+        assert_eq!(pieces("a\nb\n", &profile("keep", false)), vec!["a\nb\n"]);
+    }
+}
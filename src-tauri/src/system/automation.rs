@@ -0,0 +1,297 @@
+//! Automation through the `sobottaai://` URL scheme, so Shortcuts ("Open
+//! URLs"), AppleScript (`open location "sobottaai://record/toggle"`) and
+//! other tools can drive the app:
+//!
+//! - `sobottaai://record/start`, `record/stop`, `record/toggle`
+//! - `sobottaai://transcribe?path=/Users/me/memo.wav` copies the transcript
+//! - `sobottaai://ai-function/<id>` runs an AI function on the clipboard
+//!   text and copies the result
+//!
+//! On Windows and Linux the same URLs are accepted as launch arguments.
+//! Audio files opened with the app (double-click, `open -a`, or a path on
+//! the command line) are transcribed like `sobottaai://transcribe`.
+//! Launching a second copy forwards its arguments to the running one.
+//!
+//! Transcripts and AI output land on the clipboard and are announced with a
+//! notification, so a Shortcut can pick them up with "Get Clipboard".
+//!
+//! Any web page or document can open these URLs, so they're ignored unless
+//! automation links are turned on, and even then every action but stopping
+//! a recording asks first. Audio files opened with the app need neither.
+
+use crate::commands::audio_import::is_audio_file;
+use crate::commands::recording::RecordingState;
+use crate::i18n::{t, t_with};
+use crate::system::hotkey;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_notification::NotificationExt;
+
+pub const URL_SCHEME: &str = "sobottaai";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AutomationAction {
+    StartRecording,
+    StopRecording,
+    ToggleRecording,
+    TranscribeFile(PathBuf),
+    RunAiFunction(String),
+}
+
+impl AutomationAction {
+    pub fn parse(url: &Url) -> Result<Self, String> {
+        if url.scheme() == "file" {
+            return url
+                .to_file_path()
+                .map(Self::TranscribeFile)
+                .map_err(|_| format!("Not a local file: {}", url));
+        }
+        if url.scheme() != URL_SCHEME {
+            return Err(format!("Not a {} URL: {}", URL_SCHEME, url));
+        }
+        let path = url.path().trim_matches('/');
+        match (url.host_str().unwrap_or_default(), path) {
+            ("record", "start") => Ok(Self::StartRecording),
+            ("record", "stop") => Ok(Self::StopRecording),
+            ("record", "toggle") => Ok(Self::ToggleRecording),
+            ("transcribe", "") => url
+                .query_pairs()
+                .find(|(key, _)| key == "path")
+                .map(|(_, path)| Self::TranscribeFile(PathBuf::from(path.as_ref())))
+                .ok_or_else(|| "transcribe needs a ?path= to an audio file".to_string()),
+            ("ai-function", id) if !id.is_empty() && !id.contains('/') => {
+                Ok(Self::RunAiFunction(id.to_string()))
+            }
+            _ => Err(format!("Unknown automation URL: {}", url)),
+        }
+    }
+
+    /// What to ask before running this action from a URL, or `None` when it
+    /// needs no consent.
+    fn consent_message(&self) -> Option<String> {
+        match self {
+            Self::StopRecording => None,
+            Self::StartRecording | Self::ToggleRecording => Some(t("automation.confirmRecord")),
+            Self::TranscribeFile(path) => Some(t_with(
+                "automation.confirmTranscribe",
+                &[("path", &path.to_string_lossy())],
+            )),
+            Self::RunAiFunction(id) => Some(t_with("automation.confirmAiFunction", &[("id", id)])),
+        }
+    }
+}
+
+/// The automation URLs among command-line arguments, with audio file paths
+/// (relative to `cwd`) turned into `file://` URLs.
+pub fn urls_from_args(args: impl IntoIterator<Item = String>, cwd: &Path) -> Vec<Url> {
+    args.into_iter()
+        .filter_map(|arg| match Url::parse(&arg) {
+            Ok(url) if url.scheme() == URL_SCHEME => Some(url),
+            // "C:\memo.wav" parses as a URL with scheme "c"
+            _ if is_audio_file(Path::new(&arg)) => Url::from_file_path(cwd.join(&arg)).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A second launch of the app: run what it was asked to do here, or just
+/// bring up the main window.
+pub fn handle_second_instance(app: &AppHandle, args: Vec<String>, cwd: String) {
+    let urls = urls_from_args(args.into_iter().skip(1), Path::new(&cwd));
+    log::info!(
+        "Second instance launched with {} automation URLs",
+        urls.len()
+    );
+    if urls.is_empty() {
+        crate::system::tray::show_main_window(app);
+    } else {
+        handle_urls(app, &urls);
+    }
+}
+
+/// Run the actions for URLs the app was opened with. Unknown URLs are
+/// logged and ignored, as are `sobottaai://` URLs while automation links are
+/// off.
+pub fn handle_urls(app: &AppHandle, urls: &[Url]) {
+    for url in urls {
+        let action = match AutomationAction::parse(url) {
+            Ok(action) => action,
+            Err(e) => {
+                log::warn!("Automation: {}", e);
+                continue;
+            }
+        };
+        if url.scheme() != URL_SCHEME {
+            run(app, action);
+        } else if !crate::db::settings::load().automation_urls_enabled {
+            log::warn!("Automation: ignoring {}, automation links are off", url);
+        } else {
+            match action.consent_message() {
+                Some(message) => ask_then_run(app, action, message),
+                None => run(app, action),
+            }
+        }
+    }
+}
+
+/// Run `action` once the user allows it in a dialog.
+fn ask_then_run(app: &AppHandle, action: AutomationAction, message: String) {
+    let handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title(t("automation.confirmTitle"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            t("automation.allow"),
+            t("automation.deny"),
+        ))
+        .show(move |allowed| {
+            if allowed {
+                run(&handle, action);
+            } else {
+                log::info!("Automation: {:?} not allowed", action);
+            }
+        });
+}
+
+fn run(app: &AppHandle, action: AutomationAction) {
+    log::info!("Automation: {:?}", action);
+    let is_recording = app.state::<RecordingState>().is_recording();
+    match action {
+        AutomationAction::StartRecording if !is_recording => hotkey::start_dictation(app, None),
+        AutomationAction::StopRecording if is_recording => hotkey::stop_dictation(app),
+        AutomationAction::ToggleRecording if is_recording => hotkey::stop_dictation(app),
+        AutomationAction::ToggleRecording => hotkey::start_dictation(app, None),
+        AutomationAction::StartRecording | AutomationAction::StopRecording => {
+            log::info!("Automation: recording already in that state");
+        }
+        AutomationAction::TranscribeFile(path) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let outcome =
+                    crate::commands::audio_import::transcribe_to_clipboard(&app, &path).await;
+                notify(&app, "notification.transcriptionCopied", outcome);
+            });
+        }
+        AutomationAction::RunAiFunction(id) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let outcome = run_ai_function_on_clipboard(&app, id).await;
+                notify(&app, "notification.aiResultCopied", outcome);
+            });
+        }
+    }
+}
+
+async fn run_ai_function_on_clipboard(app: &AppHandle, id: String) -> Result<String, String> {
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|e| format!("Failed to read clipboard: {}", e))?;
+    if text.trim().is_empty() {
+        return Err("The clipboard has no text".into());
+    }
+    let output = crate::commands::ai_functions::execute_ai_function(app.clone(), text, id)
+        .await
+        .map_err(|e| e.to_string())?;
+    app.clipboard()
+        .write_text(&output)
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    Ok(output)
+}
+
+/// Announce the outcome; `title_key` is the translation key of the title
+/// on success.
+fn notify(app: &AppHandle, title_key: &str, outcome: Result<String, String>) {
+    let (title, body) = match outcome {
+        Ok(text) => (t(title_key), text),
+        Err(e) => {
+            log::error!("Automation failed: {}", e);
+            (t("notification.automationFailed"), e)
+        }
+    };
+    let _ = app.notification().builder().title(title).body(body).show();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Result<AutomationAction, String> {
+        AutomationAction::parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parses_recording_actions() {
+        assert_eq!(
+            parse("sobottaai://record/start"),
+            Ok(AutomationAction::StartRecording)
+        );
+        assert_eq!(
+            parse("sobottaai://record/stop/"),
+            Ok(AutomationAction::StopRecording)
+        );
+        assert_eq!(
+            parse("sobottaai://record/toggle"),
+            Ok(AutomationAction::ToggleRecording)
+        );
+        assert!(parse("sobottaai://record/pause").is_err());
+        assert!(parse("https://record/start").is_err());
+    }
+
+    #[test]
+    fn parses_file_and_ai_function_actions() {
+        assert_eq!(
+            parse("sobottaai://transcribe?path=%2FUsers%2Fme%2Fmy%20memo.wav"),
+            Ok(AutomationAction::TranscribeFile(PathBuf::from(
+                "/Users/me/my memo.wav"
+            )))
+        );
+        assert!(parse("sobottaai://transcribe").is_err());
+        assert_eq!(
+            parse("sobottaai://ai-function/email"),
+            Ok(AutomationAction::RunAiFunction("email".into()))
+        );
+        assert!(parse("sobottaai://ai-function/").is_err());
+    }
+
+    #[test]
+    fn only_stopping_needs_no_consent() {
+        assert!(AutomationAction::StopRecording.consent_message().is_none());
+        for action in [
+            AutomationAction::StartRecording,
+            AutomationAction::ToggleRecording,
+            AutomationAction::TranscribeFile(PathBuf::from("/tmp/memo.wav")),
+            AutomationAction::RunAiFunction("email".into()),
+        ] {
+            assert!(action.consent_message().is_some(), "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn file_urls_transcribe_the_file() {
+        let path = std::env::temp_dir().join("memo.wav");
+        assert_eq!(
+            AutomationAction::parse(&Url::from_file_path(&path).unwrap()),
+            Ok(AutomationAction::TranscribeFile(path))
+        );
+    }
+
+    #[test]
+    fn urls_from_args_keeps_automation_urls_and_audio_files() {
+        let cwd = std::env::temp_dir();
+        let args = [
+            "--flag",
+            "https://example.com",
+            "sobottaai://record/toggle",
+            "notes.txt",
+            "Memo.WAV",
+        ];
+        let urls = urls_from_args(args.iter().map(|a| a.to_string()), &cwd);
+        assert_eq!(urls.len(), 2);
+        assert_eq!(urls[0].as_str(), "sobottaai://record/toggle");
+        assert_eq!(urls[1].to_file_path().unwrap(), cwd.join("Memo.WAV"));
+    }
+}
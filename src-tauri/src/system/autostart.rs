@@ -1,2 +1,24 @@
-// Autostart is handled via tauri-plugin-autostart.
-// Configuration is managed from the frontend via the plugin's JS API.
+//! Launch at login via tauri-plugin-autostart (LaunchAgent on macOS,
+//! registry on Windows, XDG autostart entry on Linux).
+
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> anyhow::Result<()> {
+    let launcher = app.autolaunch();
+    // Enabling twice rewrites the entry; skip it when nothing changes
+    if launcher.is_enabled()? == enabled {
+        return Ok(());
+    }
+    if enabled {
+        launcher.enable()?;
+    } else {
+        launcher.disable()?;
+    }
+    log::info!("Launch at login {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+pub fn is_enabled(app: &AppHandle) -> anyhow::Result<bool> {
+    Ok(app.autolaunch().is_enabled()?)
+}
@@ -0,0 +1,212 @@
+//! Dictation target lock: remember the app or window in front when a
+//! recording starts and bring it back before the result is pasted, so text
+//! doesn't land in whatever grabbed the focus during transcription.
+//!
+//! macOS remembers the frontmost process and re-activates it through System
+//! Events; Windows remembers the foreground window; X11 reads and requests
+//! `_NET_ACTIVE_WINDOW` from the window manager. Wayland doesn't let apps
+//! see or move the focus, so nothing is locked there.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// Time the re-activated app gets to take the keyboard focus.
+const REFOCUS_DELAY: Duration = Duration::from_millis(150);
+
+pub struct TargetLock {
+    target: Mutex<Option<platform::Target>>,
+}
+
+impl TargetLock {
+    pub fn new() -> Self {
+        Self {
+            target: Mutex::new(None),
+        }
+    }
+}
+
+/// Remember the app in front as the paste target of the recording that is
+/// starting. Looked up on a separate thread so recording isn't delayed.
+pub fn lock(app: &AppHandle) {
+    app.state::<TargetLock>().target.lock().unwrap().take();
+    let app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("target-lock".into())
+        .spawn(move || {
+            let target = platform::focused();
+            log::info!("Paste target locked: {:?}", target);
+            *app.state::<TargetLock>().target.lock().unwrap() = target;
+        });
+    if let Err(e) = spawned {
+        log::warn!("Failed to lock the paste target: {}", e);
+    }
+}
+
+/// Bring the locked target back to the front if something else took the
+/// focus. Each lock is restored once.
+pub async fn restore(app: &AppHandle) {
+    let Some(target) = app.state::<TargetLock>().target.lock().unwrap().take() else {
+        return;
+    };
+    let activated = tokio::task::spawn_blocking(move || {
+        if platform::focused() == Some(target) {
+            return false;
+        }
+        log::info!("Re-activating the paste target {:?}", target);
+        platform::activate(target)
+    })
+    .await
+    .unwrap_or(false);
+    if activated {
+        tokio::time::sleep(REFOCUS_DELAY).await;
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    /// Process id of the frontmost app.
+    pub type Target = i32;
+
+    fn system_events(script: &str) -> Option<String> {
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(format!("tell application \"System Events\" to {}", script))
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    pub fn focused() -> Option<Target> {
+        system_events("get unix id of first application process whose frontmost is true")?
+            .parse()
+            .ok()
+    }
+
+    pub fn activate(target: Target) -> bool {
+        system_events(&format!(
+            "set frontmost of first application process whose unix id is {} to true",
+            target
+        ))
+        .is_some()
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    /// Handle of the foreground window.
+    pub type Target = isize;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetForegroundWindow() -> isize;
+        fn SetForegroundWindow(hwnd: isize) -> i32;
+        fn IsWindow(hwnd: isize) -> i32;
+    }
+
+    pub fn focused() -> Option<Target> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        (hwnd != 0).then_some(hwnd)
+    }
+
+    pub fn activate(target: Target) -> bool {
+        unsafe { IsWindow(target) != 0 && SetForegroundWindow(target) != 0 }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::os::raw::{c_int, c_long, c_uchar, c_ulong};
+    use x11::xlib::{
+        AnyPropertyType, ClientMessage, CurrentTime, Display, False, SubstructureNotifyMask,
+        SubstructureRedirectMask, Window, XClientMessageEvent, XCloseDisplay, XDefaultRootWindow,
+        XEvent, XFlush, XFree, XGetWindowProperty, XInternAtom, XOpenDisplay, XSendEvent,
+    };
+
+    /// The active X11 window.
+    pub type Target = Window;
+
+    /// Source indication for `_NET_ACTIVE_WINDOW` that asks the window
+    /// manager to honour the request like one from a pager or taskbar.
+    const SOURCE_PAGER: c_long = 2;
+
+    /// Run `f` with a connection to the X server, if there is one.
+    fn with_display<T>(f: impl FnOnce(*mut Display, Window, c_ulong) -> Option<T>) -> Option<T> {
+        unsafe {
+            let display = XOpenDisplay(std::ptr::null());
+            if display.is_null() {
+                return None;
+            }
+            let active = XInternAtom(display, c"_NET_ACTIVE_WINDOW".as_ptr(), False);
+            let result = f(display, XDefaultRootWindow(display), active);
+            XCloseDisplay(display);
+            result
+        }
+    }
+
+    pub fn focused() -> Option<Target> {
+        with_display(|display, root, active| unsafe {
+            let mut actual_type = 0;
+            let mut format: c_int = 0;
+            let (mut items, mut remaining): (c_ulong, c_ulong) = (0, 0);
+            let mut data: *mut c_uchar = std::ptr::null_mut();
+            let status = XGetWindowProperty(
+                display,
+                root,
+                active,
+                0,
+                1,
+                False,
+                AnyPropertyType as c_ulong,
+                &mut actual_type,
+                &mut format,
+                &mut items,
+                &mut remaining,
+                &mut data,
+            );
+            if data.is_null() {
+                return None;
+            }
+            // 32-bit properties come back as longs
+            let window = *(data as *const c_ulong);
+            XFree(data.cast());
+            (status == 0 && items == 1 && window != 0).then_some(window)
+        })
+    }
+
+    pub fn activate(target: Target) -> bool {
+        with_display(|display, root, active| unsafe {
+            let mut event: XEvent = std::mem::zeroed();
+            let message: &mut XClientMessageEvent = event.as_mut();
+            message.type_ = ClientMessage;
+            message.window = target;
+            message.message_type = active;
+            message.format = 32;
+            message.data.set_long(0, SOURCE_PAGER);
+            message.data.set_long(1, CurrentTime as c_long);
+            let mask = SubstructureRedirectMask | SubstructureNotifyMask;
+            let sent = XSendEvent(display, root, False, mask, &mut event) != 0;
+            XFlush(display);
+            Some(sent)
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    pub type Target = ();
+
+    pub fn focused() -> Option<Target> {
+        None
+    }
+
+    pub fn activate(_target: Target) -> bool {
+        false
+    }
+}
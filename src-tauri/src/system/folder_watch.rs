@@ -0,0 +1,384 @@
+//! Watches the configured folders and transcribes audio files that appear in
+//! them into history, plus optional `.txt`/`.srt` sidecar files. A file is
+//! picked up once its size stops changing, so a sync client can finish
+//! writing it first. Files already in a folder when watching starts are left
+//! alone.
+
+use crate::commands::audio_import;
+use crate::commands::recording::{self, RecordingState};
+use crate::commands::transcription;
+use crate::db;
+use crate::db::history::{self, HistoryItem};
+use crate::db::watch_folders::{self, WatchFolder};
+use crate::i18n::t_with;
+use crate::stt::queue::Priority;
+use crate::stt::TranscriptionResult;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// How often a new file's size is checked while it's being written.
+const SETTLE_INTERVAL: Duration = Duration::from_secs(2);
+/// Checks before giving up on a file that keeps growing (10 minutes).
+const SETTLE_MAX_CHECKS: u32 = 300;
+
+pub struct FolderWatchState {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    /// Files picked up this session or already there when watching started,
+    /// so repeated events or touching an old file don't transcribe it again.
+    seen: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl FolderWatchState {
+    pub fn new() -> Self {
+        Self {
+            watcher: Mutex::new(None),
+            seen: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+}
+
+/// Watch the enabled folders, replacing any previous watcher. Called at
+/// launch and whenever the folder list changes.
+pub fn restart(app: &AppHandle) -> Result<(), String> {
+    let folders: Vec<WatchFolder> = watch_folders::list()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|f| f.enabled)
+        .collect();
+
+    let state = app.state::<FolderWatchState>();
+    let mut current = state.watcher.lock().unwrap();
+    // Dropping the old watcher stops it
+    *current = None;
+    if folders.is_empty() {
+        return Ok(());
+    }
+
+    {
+        let mut seen = state.seen.lock().unwrap();
+        for folder in &folders {
+            mark_existing(Path::new(&folder.path), &mut seen);
+        }
+    }
+
+    let handler_app = app.clone();
+    let handler_folders = folders.clone();
+    let seen = state.seen.clone();
+    let mut watcher =
+        notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+            Ok(event) => on_event(&handler_app, &handler_folders, &seen, event),
+            Err(e) => log::warn!("Folder watcher error: {}", e),
+        })
+        .map_err(|e| e.to_string())?;
+
+    for folder in &folders {
+        match watcher.watch(Path::new(&folder.path), RecursiveMode::NonRecursive) {
+            Ok(()) => log::info!("Watching {} for audio files", folder.path),
+            Err(e) => log::warn!("Can't watch {}: {}", folder.path, e),
+        }
+    }
+    *current = Some(watcher);
+    Ok(())
+}
+
+fn on_event(
+    app: &AppHandle,
+    folders: &[WatchFolder],
+    seen: &Mutex<HashSet<PathBuf>>,
+    event: notify::Event,
+) {
+    // Sync clients often write to a temporary name and rename it into place
+    if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+        return;
+    }
+    for path in event.paths {
+        if !is_new_audio_file(&path) {
+            continue;
+        }
+        let Some(folder) = folder_for(folders, &path) else {
+            continue;
+        };
+        if !seen.lock().unwrap().insert(seen_key(&path)) {
+            continue;
+        }
+        let app = app.clone();
+        let folder = folder.clone();
+        tauri::async_runtime::spawn(async move {
+            let name = file_name(&path);
+            let (title, body) = match process(&app, &folder, &path).await {
+                Ok(text) => (
+                    t_with("notification.fileTranscribed", &[("name", &name)]),
+                    text,
+                ),
+                Err(e) => {
+                    log::error!("Watched folder: {:?} failed: {}", path, e);
+                    (t_with("notification.fileFailed", &[("name", &name)]), e)
+                }
+            };
+            let _ = app.notification().builder().title(title).body(body).show();
+        });
+    }
+}
+
+/// Add the audio files already in `dir` to `seen`.
+fn mark_existing(dir: &Path, seen: &mut HashSet<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if is_new_audio_file(&path) {
+            seen.insert(seen_key(&path));
+        }
+    }
+}
+
+/// A file's resolved path, matching however the watcher reports it.
+fn seen_key(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Audio files, skipping hidden ones (sync clients' partial downloads).
+fn is_new_audio_file(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map_or(true, |name| name.starts_with('.'));
+    !hidden && audio_import::is_audio_file(path) && path.is_file()
+}
+
+/// The watched folder a file was created directly in.
+fn folder_for<'a>(folders: &'a [WatchFolder], path: &Path) -> Option<&'a WatchFolder> {
+    let parent = path.parent()?;
+    // FSEvents reports resolved paths, e.g. /private/var for /var
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    let parent = canonical(parent);
+    folders
+        .iter()
+        .find(|f| canonical(Path::new(&f.path)) == parent)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Wait until a file's size stops changing.
+async fn wait_until_written(path: &Path) -> Result<(), String> {
+    let mut last_len = None;
+    for _ in 0..SETTLE_MAX_CHECKS {
+        tokio::time::sleep(SETTLE_INTERVAL).await;
+        let len = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+        if len > 0 && last_len == Some(len) {
+            return Ok(());
+        }
+        last_len = Some(len);
+    }
+    Err("The file was still being written after 10 minutes".into())
+}
+
+/// Transcribe a new file in `folder`, save it to history and write the
+/// sidecar files. Returns the transcript.
+async fn process(app: &AppHandle, folder: &WatchFolder, path: &Path) -> Result<String, String> {
+    wait_until_written(path).await?;
+    log::info!("Watched folder: transcribing {:?}", path);
+
+    let settings = db::settings::load();
+    let model_id = folder
+        .model_id
+        .clone()
+        .unwrap_or_else(|| settings.selected_model.clone());
+    let api_key = transcription::cloud_api_key(&settings, &model_id);
+
+    let import_app = app.clone();
+    let import_path = path.to_path_buf();
+    let session_id = tokio::task::spawn_blocking(move || {
+        audio_import::import(&import_app.state::<RecordingState>(), &import_path)
+    })
+    .await
+    .map_err(|e| format!("Import task panicked: {}", e))??;
+
+    let result = transcription::run_transcription(
+        app,
+        &session_id,
+        None,
+        &model_id,
+        settings.language(),
+        api_key,
+        None,
+        None,
+        Priority::Background,
+        Arc::new(AtomicBool::new(false)),
+    )
+    .await;
+    recording::take_session_audio(&app.state::<RecordingState>(), &session_id);
+    let result = result.map_err(|e| e.to_string())?;
+
+    let text = result.text.trim().to_string();
+    if text.is_empty() {
+        return Err("No speech found in the file".into());
+    }
+
+    if folder.write_txt {
+        std::fs::write(path.with_extension("txt"), &text)
+            .map_err(|e| format!("Failed to write .txt: {}", e))?;
+    }
+    if folder.write_srt {
+        std::fs::write(path.with_extension("srt"), to_srt(&result))
+            .map_err(|e| format!("Failed to write .srt: {}", e))?;
+    }
+
+    if app.state::<RecordingState>().is_incognito() {
+        log::info!("Incognito mode: not saving history for {:?}", path);
+        return Ok(text);
+    }
+    let item = HistoryItem {
+        id: session_id,
+        audio_path: Some(path.to_string_lossy().to_string()),
+        transcript: result.text.clone(),
+        processed_text: None,
+        rules_text: None,
+        model_id,
+        language: settings.language(),
+        ai_function: None,
+        duration_ms: Some(result.duration_ms as i64),
+        metrics: result.metrics.clone(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    db::run_blocking(move || history::insert(&item))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(text)
+}
+
+/// SubRip subtitles from the transcript segments, or a single cue covering
+/// the whole file when the engine doesn't report segments.
+fn to_srt(result: &TranscriptionResult) -> String {
+    let cues: Vec<(u64, u64, &str)> = if result.segments.is_empty() {
+        vec![(0, result.duration_ms, result.text.trim())]
+    } else {
+        result
+            .segments
+            .iter()
+            .map(|s| (s.start_ms, s.end_ms, s.text.trim()))
+            .filter(|(_, _, text)| !text.is_empty())
+            .collect()
+    };
+    cues.iter()
+        .enumerate()
+        .map(|(i, (start, end, text))| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                i + 1,
+                srt_time(*start),
+                srt_time(*end),
+                text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `HH:MM:SS,mmm`
+fn srt_time(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stt::Segment;
+
+    fn result(segments: Vec<Segment>) -> TranscriptionResult {
+        TranscriptionResult {
+            text: " Hello there. General Kenobi.".into(),
+            language: None,
+            segments,
+            duration_ms: 3_723_004,
+            metrics: None,
+        }
+    }
+
+    #[test]
+    fn srt_has_a_cue_per_segment() {
+        let srt = to_srt(&result(vec![
+            Segment {
+                start_ms: 0,
+                end_ms: 1500,
+                text: " Hello there.".into(),
+            },
+            Segment {
+                start_ms: 1500,
+                end_ms: 61_250,
+                text: " General Kenobi.".into(),
+            },
+        ]));
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:01,500\nHello there.\n\n\
+             2\n00:00:01,500 --> 00:01:01,250\nGeneral Kenobi.\n"
+        );
+    }
+
+    #[test]
+    fn srt_without_segments_covers_the_whole_file() {
+        assert_eq!(
+            to_srt(&result(vec![])),
+            "1\n00:00:00,000 --> 01:02:03,004\nHello there. General Kenobi.\n"
+        );
+    }
+
+    #[test]
+    fn only_visible_audio_files_are_picked_up() {
+        let dir = std::env::temp_dir().join(format!("sobottaai-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["memo.wav", ".memo.wav", "notes.txt"] {
+            std::fs::write(dir.join(name), b"x").unwrap();
+        }
+        assert!(is_new_audio_file(&dir.join("memo.wav")));
+        assert!(!is_new_audio_file(&dir.join(".memo.wav")));
+        assert!(!is_new_audio_file(&dir.join("notes.txt")));
+        assert!(!is_new_audio_file(&dir.join("missing.wav")));
+
+        let folders = [WatchFolder {
+            id: "f".into(),
+            path: dir.to_string_lossy().to_string(),
+            model_id: None,
+            write_txt: false,
+            write_srt: false,
+            enabled: true,
+            created_at: String::new(),
+        }];
+        assert!(folder_for(&folders, &dir.join("memo.wav")).is_some());
+        assert!(folder_for(&folders, &std::env::temp_dir().join("memo.wav")).is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn existing_audio_files_are_marked_seen() {
+        let dir = std::env::temp_dir().join(format!("sobottaai-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["old.wav", "notes.txt"] {
+            std::fs::write(dir.join(name), b"x").unwrap();
+        }
+        let mut seen = HashSet::new();
+        mark_existing(&dir, &mut seen);
+        assert_eq!(seen.len(), 1);
+        // Touching the old file later finds it already seen
+        assert!(!seen.insert(seen_key(&dir.join("old.wav"))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
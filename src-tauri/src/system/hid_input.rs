@@ -0,0 +1,367 @@
+//! USB foot pedals and other HID buttons as dictation triggers. Each device
+//! with enabled bindings gets a thread that reads its input reports and runs
+//! the bound hotkey action when a button's bit is set (pressed) or cleared
+//! (released), so push-to-talk works like holding a hotkey. An unplugged
+//! device is reopened when it comes back.
+
+use crate::db::device_bindings::{self, DeviceBinding};
+use crate::system::hotkey::{self, HotkeyAction};
+use hidapi::{HidApi, HidDevice};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::ShortcutState;
+
+/// How long a read waits for a report, so stopping is noticed.
+const READ_TIMEOUT_MS: i32 = 250;
+/// How often a missing device is looked for again.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+/// Longest input report read.
+const REPORT_SIZE: usize = 64;
+/// Longest report descriptor hidapi returns.
+const DESCRIPTOR_SIZE: usize = 4096;
+
+/// A connected HID device, for picking one in settings.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HidDeviceInfo {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub name: String,
+}
+
+pub struct HidInputState {
+    /// Set to stop the running listener threads.
+    stop: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl HidInputState {
+    pub fn new() -> Self {
+        Self {
+            stop: Mutex::new(None),
+        }
+    }
+}
+
+/// Listen to the devices of the enabled bindings, replacing the previous
+/// listeners. Called at launch and whenever the bindings change.
+pub fn restart(app: &AppHandle) -> Result<(), String> {
+    let bindings: Vec<DeviceBinding> = device_bindings::list()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|b| b.enabled)
+        .collect();
+
+    let state = app.state::<HidInputState>();
+    let mut current = state.stop.lock().unwrap();
+    if let Some(stop) = current.take() {
+        stop.store(true, Ordering::Relaxed);
+    }
+    if bindings.is_empty() {
+        return Ok(());
+    }
+
+    let mut by_device: HashMap<(u16, u16), Vec<DeviceBinding>> = HashMap::new();
+    for binding in bindings {
+        by_device
+            .entry((binding.vendor_id, binding.product_id))
+            .or_default()
+            .push(binding);
+    }
+    let stop = Arc::new(AtomicBool::new(false));
+    for ((vendor_id, product_id), bindings) in by_device {
+        let app = app.clone();
+        let stop = stop.clone();
+        std::thread::Builder::new()
+            .name(format!("hid-{:04x}:{:04x}", vendor_id, product_id))
+            .spawn(move || listen(&app, vendor_id, product_id, &bindings, &stop))
+            .map_err(|e| e.to_string())?;
+    }
+    *current = Some(stop);
+    Ok(())
+}
+
+/// Connected HID devices, one entry per vendor and product.
+pub fn list_devices() -> Result<Vec<HidDeviceInfo>, String> {
+    let api = HidApi::new().map_err(|e| e.to_string())?;
+    let mut devices: Vec<HidDeviceInfo> = vec![];
+    for info in api.device_list() {
+        let (vendor_id, product_id) = (info.vendor_id(), info.product_id());
+        if devices
+            .iter()
+            .any(|d| d.vendor_id == vendor_id && d.product_id == product_id)
+        {
+            continue;
+        }
+        let name = match (info.manufacturer_string(), info.product_string()) {
+            (_, Some(product)) if !product.trim().is_empty() => product.trim().to_string(),
+            (Some(maker), _) if !maker.trim().is_empty() => maker.trim().to_string(),
+            _ => format!("{:04x}:{:04x}", vendor_id, product_id),
+        };
+        devices.push(HidDeviceInfo {
+            vendor_id,
+            product_id,
+            name,
+        });
+    }
+    devices.sort_by_key(|d| d.name.to_lowercase());
+    Ok(devices)
+}
+
+/// Wait for a button press on the device and return its bit, or `None` when
+/// nothing is pressed within `timeout`.
+pub fn capture_button(
+    vendor_id: u16,
+    product_id: u16,
+    timeout: Duration,
+) -> Result<Option<u32>, String> {
+    let api = HidApi::new().map_err(|e| e.to_string())?;
+    let device = api
+        .open(vendor_id, product_id)
+        .map_err(|e| format!("Couldn't open device: {}", e))?;
+    let deadline = Instant::now() + timeout;
+    let mut reports = Reports::new(has_report_ids(&device));
+    let mut buf = [0u8; REPORT_SIZE];
+    while Instant::now() < deadline {
+        let len = device
+            .read_timeout(&mut buf, READ_TIMEOUT_MS)
+            .map_err(|e| e.to_string())?;
+        if len == 0 {
+            continue;
+        }
+        if let Some((button, _)) = reports
+            .changes(&buf[..len])
+            .into_iter()
+            .find(|(_, pressed)| *pressed)
+        {
+            return Ok(Some(button));
+        }
+    }
+    Ok(None)
+}
+
+/// Read the device until stopped, reopening it after it's unplugged.
+fn listen(
+    app: &AppHandle,
+    vendor_id: u16,
+    product_id: u16,
+    bindings: &[DeviceBinding],
+    stop: &AtomicBool,
+) {
+    let mut announced_missing = false;
+    while !stop.load(Ordering::Relaxed) {
+        let device = HidApi::new().and_then(|api| api.open(vendor_id, product_id));
+        let device = match device {
+            Ok(device) => device,
+            Err(e) => {
+                if !announced_missing {
+                    log::info!(
+                        "HID device {:04x}:{:04x} not available: {}",
+                        vendor_id,
+                        product_id,
+                        e
+                    );
+                    announced_missing = true;
+                }
+                std::thread::sleep(RECONNECT_INTERVAL);
+                continue;
+            }
+        };
+        log::info!(
+            "Listening to HID device {:04x}:{:04x}",
+            vendor_id,
+            product_id
+        );
+        announced_missing = false;
+
+        let mut reports = Reports::new(has_report_ids(&device));
+        let mut buf = [0u8; REPORT_SIZE];
+        while !stop.load(Ordering::Relaxed) {
+            let len = match device.read_timeout(&mut buf, READ_TIMEOUT_MS) {
+                Ok(0) => continue,
+                Ok(len) => len,
+                Err(e) => {
+                    log::warn!(
+                        "HID device {:04x}:{:04x} disconnected: {}",
+                        vendor_id,
+                        product_id,
+                        e
+                    );
+                    break;
+                }
+            };
+            for (button, pressed) in reports.changes(&buf[..len]) {
+                trigger(app, bindings, button, pressed);
+            }
+        }
+        // Don't leave push-to-talk recording when the device goes away
+        for button in reports.held() {
+            trigger(app, bindings, button, false);
+        }
+    }
+}
+
+fn trigger(app: &AppHandle, bindings: &[DeviceBinding], button: u32, pressed: bool) {
+    for binding in bindings.iter().filter(|b| b.button == button) {
+        let Some(action) = HotkeyAction::from_id(&binding.action) else {
+            log::warn!("Unknown action '{}' for {}", binding.action, binding.name);
+            continue;
+        };
+        let shortcut_state = if pressed {
+            ShortcutState::Pressed
+        } else {
+            ShortcutState::Released
+        };
+        log::info!("{} button {} {:?}", binding.name, button, shortcut_state);
+        hotkey::dispatch(app, &action, shortcut_state);
+    }
+}
+
+/// The last input report of each report ID, to find the buttons the next
+/// one changes.
+struct Reports {
+    /// Whether reports start with a report ID byte, which isn't a button.
+    report_ids: bool,
+    last: HashMap<u8, Vec<u8>>,
+}
+
+impl Reports {
+    fn new(report_ids: bool) -> Self {
+        Self {
+            report_ids,
+            last: HashMap::new(),
+        }
+    }
+
+    /// Buttons that `report` changed. The first report of each ID only sets
+    /// the baseline, so bits already set when listening starts (or that
+    /// never change) aren't taken for presses.
+    fn changes(&mut self, report: &[u8]) -> Vec<(u32, bool)> {
+        let (id, payload) = match report.split_first() {
+            Some((&id, payload)) if self.report_ids => (id, payload),
+            _ => (0, report),
+        };
+        match self.last.insert(id, payload.to_vec()) {
+            Some(previous) => changed_buttons(&previous, payload),
+            None => vec![],
+        }
+    }
+
+    /// Buttons pressed in the last reports.
+    fn held(&self) -> Vec<u32> {
+        self.last
+            .values()
+            .flat_map(|report| changed_buttons(report, &[]))
+            .map(|(button, _)| button)
+            .collect()
+    }
+}
+
+/// Whether the device numbers its reports, read from its report descriptor.
+fn has_report_ids(device: &HidDevice) -> bool {
+    let mut buf = [0u8; DESCRIPTOR_SIZE];
+    match device.get_report_descriptor(&mut buf) {
+        Ok(len) => descriptor_has_report_ids(&buf[..len]),
+        Err(e) => {
+            log::warn!("Couldn't read the HID report descriptor: {}", e);
+            false
+        }
+    }
+}
+
+/// Whether a report descriptor declares a Report ID item.
+fn descriptor_has_report_ids(descriptor: &[u8]) -> bool {
+    const REPORT_ID: u8 = 0x84;
+    const LONG_ITEM: u8 = 0xfe;
+    let mut i = 0;
+    while let Some(&prefix) = descriptor.get(i) {
+        if prefix == LONG_ITEM {
+            // Long items carry their data size in the next byte
+            let size = descriptor.get(i + 1).copied().unwrap_or(0) as usize;
+            i += 3 + size;
+            continue;
+        }
+        if prefix & 0xfc == REPORT_ID {
+            return true;
+        }
+        let size = match prefix & 0x03 {
+            3 => 4,
+            size => size as usize,
+        };
+        i += 1 + size;
+    }
+    false
+}
+
+/// Buttons (report bits) that changed between two reports, with whether
+/// each is now pressed. Missing bytes count as released.
+fn changed_buttons(previous: &[u8], report: &[u8]) -> Vec<(u32, bool)> {
+    let len = previous.len().max(report.len());
+    let mut changed = vec![];
+    for i in 0..len {
+        let before = previous.get(i).copied().unwrap_or(0);
+        let after = report.get(i).copied().unwrap_or(0);
+        for bit in 0..8 {
+            let mask = 1 << bit;
+            if (before ^ after) & mask != 0 {
+                changed.push(((i * 8 + bit) as u32, after & mask != 0));
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_presses_and_releases() {
+        // Left pedal down, then middle down, then both up
+        assert_eq!(changed_buttons(&[], &[0b001]), vec![(0, true)]);
+        assert_eq!(changed_buttons(&[0b001], &[0b011]), vec![(1, true)]);
+        assert_eq!(
+            changed_buttons(&[0b011], &[0]),
+            vec![(0, false), (1, false)]
+        );
+        assert!(changed_buttons(&[0b011], &[0b011]).is_empty());
+    }
+
+    #[test]
+    fn bits_count_across_bytes() {
+        assert_eq!(changed_buttons(&[0, 0], &[0, 0b100]), vec![(10, true)]);
+        assert_eq!(changed_buttons(&[0, 0b100], &[]), vec![(10, false)]);
+    }
+
+    #[test]
+    fn first_report_sets_the_baseline() {
+        let mut reports = Reports::new(false);
+        assert!(reports.changes(&[0b101]).is_empty());
+        assert_eq!(reports.changes(&[0b100]), vec![(0, false)]);
+        assert_eq!(reports.held(), vec![2]);
+    }
+
+    #[test]
+    fn report_id_byte_is_not_a_button() {
+        let mut reports = Reports::new(true);
+        assert!(reports.changes(&[3, 0]).is_empty());
+        assert_eq!(reports.changes(&[3, 0b1]), vec![(0, true)]);
+        // Another report ID has its own baseline
+        assert!(reports.changes(&[4, 0b10]).is_empty());
+    }
+
+    #[test]
+    fn finds_report_ids_in_the_descriptor() {
+        // Usage Page (Generic Desktop), Usage (Joystick), Collection, ...
+        let without = [0x05, 0x01, 0x09, 0x04, 0xa1, 0x01, 0x75, 0x01, 0xc0];
+        assert!(!descriptor_has_report_ids(&without));
+        // ... with Report ID (1) after the collection starts
+        let with = [0x05, 0x01, 0x09, 0x04, 0xa1, 0x01, 0x85, 0x01, 0x75, 0x01, 0xc0];
+        assert!(descriptor_has_report_ids(&with));
+        // A 0x85 inside another item's data doesn't count
+        assert!(!descriptor_has_report_ids(&[0x26, 0x85, 0x00]));
+    }
+}
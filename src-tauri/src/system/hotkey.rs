@@ -1,152 +1,666 @@
 use crate::commands::recording::RecordingState;
+use crate::db::hotkey_bindings::HotkeyBinding;
+use std::collections::HashMap;
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
+/// How global hotkeys are delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyBackend {
+    /// OS-level shortcut grab via tauri-plugin-global-shortcut.
+    Native,
+    /// XDG desktop portal GlobalShortcuts (Linux). Works on Wayland
+    /// compositors where native grabs are not allowed.
+    Portal,
+}
+
+impl HotkeyBackend {
+    /// Parse the `hotkeyBackend` setting: "native", "portal" or "auto".
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "auto" => Ok(Self::detect()),
+            "native" => Ok(Self::Native),
+            #[cfg(target_os = "linux")]
+            "portal" => Ok(Self::Portal),
+            #[cfg(not(target_os = "linux"))]
+            "portal" => Err("The portal hotkey backend is only available on Linux".into()),
+            other => Err(format!("Unknown hotkey backend '{}'", other)),
+        }
+    }
+
+    /// Portal on Wayland sessions, native everywhere else.
+    pub fn detect() -> Self {
+        Self::detect_from(
+            std::env::var("XDG_SESSION_TYPE").ok().as_deref(),
+            std::env::var("WAYLAND_DISPLAY").ok().as_deref(),
+        )
+    }
+
+    fn detect_from(session_type: Option<&str>, wayland_display: Option<&str>) -> Self {
+        let wayland = session_type == Some("wayland")
+            || wayland_display.is_some_and(|d| !d.is_empty());
+        if cfg!(target_os = "linux") && wayland {
+            Self::Portal
+        } else {
+            Self::Native
+        }
+    }
+}
+
+/// What a global hotkey does. Every backend routes presses through
+/// `dispatch`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HotkeyAction {
+    /// Start/stop dictation, honouring the recording mode.
+    Dictation,
+    /// Rewrite the selected text with an AI function.
+    RewriteSelection,
+    /// Paste the last transcript again.
+    PasteLast,
+    /// Delete the text of the last paste from the target app.
+    UndoPaste,
+    /// Dictate with the preset of the hotkey binding with this id.
+    Binding(String),
+}
+
+const BINDING_PREFIX: &str = "binding:";
+
+impl HotkeyAction {
+    /// The built-in actions; bindings come from the database.
+    pub const ALL: [Self; 4] = [
+        Self::Dictation,
+        Self::RewriteSelection,
+        Self::PasteLast,
+        Self::UndoPaste,
+    ];
+
+    /// Stable identifier, also used as the portal shortcut id.
+    pub fn id(&self) -> String {
+        match self {
+            Self::Dictation => "dictation".into(),
+            Self::RewriteSelection => "rewrite-selection".into(),
+            Self::PasteLast => "paste-last".into(),
+            Self::UndoPaste => "undo-paste".into(),
+            Self::Binding(id) => format!("{}{}", BINDING_PREFIX, id),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        if let Some(binding) = id.strip_prefix(BINDING_PREFIX) {
+            return Some(Self::Binding(binding.to_string()));
+        }
+        Self::ALL.into_iter().find(|a| a.id() == id)
+    }
+
+    /// Human-readable description (shown by the desktop portal).
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Dictation => "Start or stop dictation",
+            Self::RewriteSelection => "Rewrite selected text",
+            Self::PasteLast => "Paste the last transcript",
+            Self::UndoPaste => "Undo the last paste",
+            Self::Binding(_) => "Dictate with a preset",
+        }
+    }
+}
+
 /// Shared state for the recording mode so the hotkey handler can read it.
 pub struct HotkeyModeState {
-    /// "push-to-talk" or "toggle"
+    /// "push-to-talk", "toggle" or "hands-free"
     pub mode: Mutex<String>,
+    pub backend: Mutex<HotkeyBackend>,
+    /// Currently registered shortcut per action, so one can be swapped
+    /// without touching the others.
+    pub shortcuts: Mutex<HashMap<HotkeyAction, Shortcut>>,
+    /// While paused the shortcuts stay recorded but are not bound, so other
+    /// apps (games, screen sharing) get the key combination.
+    pub paused: Mutex<bool>,
+    /// When the dictation hotkey last started a recording, to spot a
+    /// double-press.
+    pub last_press: Mutex<Option<Instant>>,
+    /// Stops the running portal session when dropped or signalled.
+    #[cfg(target_os = "linux")]
+    pub portal_session: Mutex<Option<tokio::sync::oneshot::Sender<()>>>,
 }
 
 impl HotkeyModeState {
     pub fn new() -> Self {
         Self {
             mode: Mutex::new("push-to-talk".to_string()),
+            shortcuts: Mutex::new(HashMap::new()),
+            paused: Mutex::new(false),
+            last_press: Mutex::new(None),
+            backend: Mutex::new(HotkeyBackend::Native),
+            #[cfg(target_os = "linux")]
+            portal_session: Mutex::new(None),
         }
     }
 }
 
-/// Register the global hotkey with the initial default (Alt+Space).
+/// Register the saved hotkeys and restore the saved recording mode, so they
+/// work before the frontend has loaded. Falls back to Alt+Space.
 pub fn register_hotkey(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let shortcut = Shortcut::new(Some(Modifiers::ALT), Code::Space);
-    register_shortcut(app, shortcut)?;
-    log::info!("Global hotkey registered: Option+Space");
+    let settings = crate::db::settings::load();
+    let state = app.state::<HotkeyModeState>();
+    *state.mode.lock().unwrap() = settings.recording_mode;
+    let backend = HotkeyBackend::parse(&settings.hotkey_backend).unwrap_or_else(|e| {
+        log::warn!("{}, using auto-detection", e);
+        HotkeyBackend::detect()
+    });
+    *state.backend.lock().unwrap() = backend;
+    log::info!("Hotkey backend: {:?}", backend);
+
+    let shortcut = settings
+        .default_hotkey
+        .parse::<Shortcut>()
+        .unwrap_or_else(|e| {
+            log::warn!(
+                "Invalid saved hotkey '{}': {:?}, using Option+Space",
+                settings.default_hotkey,
+                e
+            );
+            Shortcut::new(Some(Modifiers::ALT), Code::Space)
+        });
+    register_shortcut(app, HotkeyAction::Dictation, shortcut)?;
+    log::info!("Global hotkey registered: {}", settings.default_hotkey);
+
+    let optional = [
+        (HotkeyAction::RewriteSelection, settings.rewrite_hotkey),
+        (HotkeyAction::PasteLast, settings.paste_last_hotkey),
+        (HotkeyAction::UndoPaste, settings.undo_paste_hotkey),
+    ];
+    for (action, hotkey) in optional {
+        let Some(hotkey) = hotkey else { continue };
+        match parse_hotkey(&hotkey) {
+            Ok(shortcut) => register_shortcut(app, action, shortcut)?,
+            Err(e) => log::warn!("Saved {} hotkey: {}", action.id(), e),
+        }
+    }
+
+    match crate::db::hotkey_bindings::list() {
+        Ok(bindings) => {
+            for binding in bindings {
+                let registered = parse_hotkey(&binding.hotkey).and_then(|shortcut| {
+                    register_shortcut(app, HotkeyAction::Binding(binding.id), shortcut)
+                        .map_err(|e| e.to_string())
+                });
+                if let Err(e) = registered {
+                    log::warn!("Hotkey binding {} not registered: {}", binding.hotkey, e);
+                }
+            }
+        }
+        Err(e) => log::warn!("Failed to load hotkey bindings: {}", e),
+    }
     Ok(())
 }
 
-/// Core registration logic — used by both initial setup and update_hotkey.
+/// Parse an accelerator string such as `CommandOrControl+Shift+KeyR`.
+pub fn parse_hotkey(hotkey: &str) -> Result<Shortcut, String> {
+    hotkey
+        .parse()
+        .map_err(|e| format!("Invalid hotkey '{}': {:?}", hotkey, e))
+}
+
+/// Core registration logic — used by initial setup and the update commands.
+/// Binds natively unless the portal backend is active or hotkeys are paused,
+/// and records the shortcut so it can be swapped, moved or paused later.
 pub fn register_shortcut(
     app: &AppHandle,
+    action: HotkeyAction,
     shortcut: Shortcut,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    app.global_shortcut()
-        .on_shortcut(shortcut, move |app, _shortcut, event| {
-            let mode = app.state::<HotkeyModeState>().mode.lock().unwrap().clone();
-
-            let rec_state = app.state::<RecordingState>();
-            let is_recording = rec_state.is_recording();
-
-            match mode.as_str() {
-                "toggle" => {
-                    // Toggle mode: only react to Press, ignore Release
-                    if event.state() == ShortcutState::Pressed {
-                        if is_recording {
-                            log::info!("Hotkey pressed (toggle) — stopping recording");
-                            let _ = app.emit("hotkey-released", ());
-                            match crate::commands::recording::stop_recording(app.clone(), rec_state)
-                            {
-                                Ok(result) => {
-                                    // Don't hide bar — RecordingPipeline handles it after processing
-                                    log::info!(
-                                        "Recording stopped via hotkey: session={}, duration={}ms",
-                                        result.session_id,
-                                        result.duration_ms
-                                    );
-                                }
-                                Err(e) => {
-                                    log::warn!("Failed to stop recording from hotkey: {}", e);
-                                    let _ =
-                                        crate::commands::recording::hide_recording_bar(app.clone());
-                                }
-                            }
-                        } else {
-                            log::info!("Hotkey pressed (toggle) — starting recording");
-                            let _ = app.emit("hotkey-pressed", ());
-                            // Emit BEFORE the blocking start_recording() call so the
-                            // frontend can reset state while audio init runs (~50-200ms).
-                            let _ = app.emit("recording-will-start", ());
-                            match crate::commands::recording::start_recording(
-                                app.clone(),
-                                rec_state,
-                            ) {
-                                Ok(()) => {
-                                    let _ =
-                                        crate::commands::recording::show_recording_bar(app.clone());
-                                }
-                                Err(e) => {
-                                    log::warn!("Failed to start recording from hotkey: {}", e);
-                                    let _ = app.emit("recording-error", e.clone());
-                                    // Emit dummy recording-stopped so frontend can reset state
-                                    let _ = app.emit(
-                                        "recording-stopped",
-                                        crate::commands::recording::StopResult {
-                                            session_id: String::new(),
-                                            duration_ms: 0,
-                                            sample_count: 0,
-                                        },
-                                    );
-                                }
-                            }
+    let state = app.state::<HotkeyModeState>();
+    if binds_natively(&state) {
+        let bound = action.clone();
+        app.global_shortcut()
+            .on_shortcut(shortcut, move |app, _shortcut, event| {
+                dispatch(app, &bound, event.state());
+            })?;
+    }
+
+    state.shortcuts.lock().unwrap().insert(action, shortcut);
+    restart_portal_if_active(app);
+    Ok(())
+}
+
+/// Replace (or with `None`, remove) the shortcut for one action, leaving the
+/// other hotkeys registered. A shortcut already used by another action is
+/// rejected.
+pub fn set_shortcut(
+    app: &AppHandle,
+    action: HotkeyAction,
+    shortcut: Option<Shortcut>,
+) -> Result<(), String> {
+    let state = app.state::<HotkeyModeState>();
+    if let Some(shortcut) = shortcut {
+        let shortcuts = state.shortcuts.lock().unwrap();
+        if let Some((other, _)) = shortcuts
+            .iter()
+            .find(|(other, s)| **other != action && **s == shortcut)
+        {
+            return Err(format!(
+                "Hotkey is already assigned to '{}'",
+                other.description()
+            ));
+        }
+    }
+
+    let previous = state.shortcuts.lock().unwrap().remove(&action);
+    if let Some(previous) = previous {
+        unregister(app, previous)?;
+    }
+
+    match shortcut {
+        Some(shortcut) => register_shortcut(app, action, shortcut).map_err(|e| e.to_string()),
+        None => {
+            restart_portal_if_active(app);
+            Ok(())
+        }
+    }
+}
+
+/// Run the action bound to a hotkey. Shared by every hotkey backend.
+pub(crate) fn dispatch(app: &AppHandle, action: &HotkeyAction, shortcut_state: ShortcutState) {
+    match action {
+        HotkeyAction::Dictation => handle_dictation(app, shortcut_state, None),
+        HotkeyAction::RewriteSelection => handle_rewrite(app, shortcut_state),
+        HotkeyAction::PasteLast => handle_paste_last(app, shortcut_state),
+        HotkeyAction::UndoPaste => handle_undo_paste(app, shortcut_state),
+        HotkeyAction::Binding(id) => {
+            let preset = match crate::db::hotkey_bindings::get(id) {
+                Ok(preset) => preset,
+                Err(e) => {
+                    log::warn!("Failed to load hotkey binding {}: {}", id, e);
+                    None
+                }
+            };
+            handle_dictation(app, shortcut_state, preset);
+        }
+    }
+}
+
+/// Start or stop dictation for a hotkey press/release, honouring the
+/// recording mode. A `preset` travels with `recording-will-start` so the
+/// frontend transcribes that session with the binding's settings.
+fn handle_dictation(app: &AppHandle, shortcut_state: ShortcutState, preset: Option<HotkeyBinding>) {
+    let mode = app.state::<HotkeyModeState>().mode.lock().unwrap().clone();
+
+    let rec_state = app.state::<RecordingState>();
+    let is_recording = rec_state.is_recording();
+
+    if shortcut_state == ShortcutState::Pressed && preset.is_none() {
+        if let Some(preset) = double_press_preset(app, &mode, is_recording) {
+            log::info!(
+                "Hotkey double-pressed — dictating with AI function {:?}",
+                preset.ai_function
+            );
+            // The first press's recording is only a tap; stop it so the new
+            // session starts clean. In push-to-talk it already stopped.
+            if is_recording {
+                stop_dictation(app);
+            }
+            start_dictation(app, Some(preset));
+            return;
+        }
+    }
+
+    match mode.as_str() {
+        // Hands-free starts and stops like toggle
+        "toggle" | "hands-free" => {
+            // Toggle mode: only react to Press, ignore Release
+            if shortcut_state == ShortcutState::Pressed {
+                if is_recording {
+                    log::info!("Hotkey pressed (toggle) — stopping recording");
+                    stop_dictation(app);
+                } else {
+                    log::info!("Hotkey pressed (toggle) — starting recording");
+                    start_dictation(app, preset);
+                }
+            }
+            // Release does nothing in toggle mode
+        }
+        _ => {
+            // Push-to-talk: Press → start, Release → stop
+            match shortcut_state {
+                ShortcutState::Pressed => {
+                    log::info!("Hotkey pressed — starting recording");
+                    let _ = app.emit("hotkey-pressed", ());
+                    // Emit BEFORE the blocking start_recording() call so the
+                    // frontend can reset state while audio init runs (~50-200ms).
+                    let _ = app.emit("recording-will-start", &preset);
+                    match crate::commands::recording::start_recording(
+                        app.clone(),
+                        rec_state,
+                    ) {
+                        Ok(()) => {
+                            let _ =
+                                crate::commands::recording::show_recording_bar(app.clone());
+                        }
+                        Err(e) => {
+                            log::warn!("Failed to start recording from hotkey: {}", e);
+                            let _ = app.emit("recording-error", &e);
                         }
                     }
-                    // Release does nothing in toggle mode
                 }
-                _ => {
-                    // Push-to-talk: Press → start, Release → stop
-                    match event.state() {
-                        ShortcutState::Pressed => {
-                            log::info!("Hotkey pressed — starting recording");
-                            let _ = app.emit("hotkey-pressed", ());
-                            // Emit BEFORE the blocking start_recording() call so the
-                            // frontend can reset state while audio init runs (~50-200ms).
-                            let _ = app.emit("recording-will-start", ());
-                            match crate::commands::recording::start_recording(
-                                app.clone(),
-                                rec_state,
-                            ) {
-                                Ok(()) => {
-                                    let _ =
-                                        crate::commands::recording::show_recording_bar(app.clone());
-                                }
-                                Err(e) => {
-                                    log::warn!("Failed to start recording from hotkey: {}", e);
-                                    let _ = app.emit("recording-error", e.clone());
-                                }
-                            }
+                ShortcutState::Released => {
+                    log::info!("Hotkey released — stopping recording");
+                    let _ = app.emit("hotkey-released", ());
+                    match crate::commands::recording::stop_recording(app.clone(), rec_state)
+                    {
+                        Ok(result) => {
+                            // Don't hide bar — RecordingPipeline handles it after processing
+                            log::info!(
+                                "Recording stopped via hotkey: session={}, duration={}ms",
+                                result.session_id,
+                                result.duration_ms
+                            );
                         }
-                        ShortcutState::Released => {
-                            log::info!("Hotkey released — stopping recording");
-                            let _ = app.emit("hotkey-released", ());
-                            match crate::commands::recording::stop_recording(app.clone(), rec_state)
-                            {
-                                Ok(result) => {
-                                    // Don't hide bar — RecordingPipeline handles it after processing
-                                    log::info!(
-                                        "Recording stopped via hotkey: session={}, duration={}ms",
-                                        result.session_id,
-                                        result.duration_ms
-                                    );
-                                }
-                                Err(e) => {
-                                    log::warn!("Failed to stop recording from hotkey: {}", e);
-                                    let _ =
-                                        crate::commands::recording::hide_recording_bar(app.clone());
-                                    let _ = app.emit(
-                                        "recording-stopped",
-                                        crate::commands::recording::StopResult {
-                                            session_id: String::new(),
-                                            duration_ms: 0,
-                                            sample_count: 0,
-                                        },
-                                    );
-                                }
-                            }
+                        Err(e) => {
+                            log::warn!("Failed to stop recording from hotkey: {}", e);
+                            let _ =
+                                crate::commands::recording::hide_recording_bar(app.clone());
+                            let _ = app.emit(
+                                "recording-stopped",
+                                crate::commands::recording::StopResult {
+                                    session_id: String::new(),
+                                    duration_ms: 0,
+                                    sample_count: 0,
+                                },
+                            );
                         }
                     }
                 }
             }
-        })?;
+        }
+    }
+}
+
+/// Presses of the dictation hotkey closer together than this are a
+/// double-press.
+const DOUBLE_PRESS_WINDOW: Duration = Duration::from_millis(400);
+
+/// Whether a press at `now` follows the press at `previous` closely enough
+/// to be a double-press.
+fn is_double_press(previous: Option<Instant>, now: Instant) -> bool {
+    previous.is_some_and(|previous| now.duration_since(previous) <= DOUBLE_PRESS_WINDOW)
+}
+
+/// Track presses of the plain dictation hotkey and, when this one completes
+/// a double-press and `doublePressAi` is on, return a preset that runs the
+/// most recently used AI function on the new dictation. Only presses that
+/// start a recording count, so stopping a toggle recording and quickly
+/// starting the next one isn't a double-press.
+fn double_press_preset(app: &AppHandle, mode: &str, is_recording: bool) -> Option<HotkeyBinding> {
+    let state = app.state::<HotkeyModeState>();
+    let mut last_press = state.last_press.lock().unwrap();
+    let now = Instant::now();
+    // In toggle modes the second press of a double-press is the one that
+    // would stop the recording
+    let starts_recording = !is_recording || !matches!(mode, "toggle" | "hands-free");
+    if !is_double_press(*last_press, now) || !crate::db::settings::load().double_press_ai {
+        *last_press = starts_recording.then_some(now);
+        return None;
+    }
+    // A third press starts over instead of counting as another double-press
+    *last_press = None;
+    let ai_function = match crate::db::ai_functions::last_used() {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            log::info!("Hotkey double-pressed, but no AI function has been run yet");
+            return None;
+        }
+        Err(e) => {
+            log::warn!("Failed to look up the last AI function: {}", e);
+            return None;
+        }
+    };
+    Some(HotkeyBinding {
+        id: String::new(),
+        hotkey: String::new(),
+        model_id: None,
+        language: None,
+        ai_function: Some(ai_function),
+        pipeline_id: None,
+    })
+}
+
+/// Start a dictation session whose transcript the frontend pipeline pastes.
+/// Used by toggle-mode hotkeys and automation.
+pub(crate) fn start_dictation(app: &AppHandle, preset: Option<HotkeyBinding>) {
+    let _ = app.emit("hotkey-pressed", ());
+    // Emit BEFORE the blocking start_recording() call so the
+    // frontend can reset state while audio init runs (~50-200ms).
+    let _ = app.emit("recording-will-start", &preset);
+    match crate::commands::recording::start_recording(
+        app.clone(),
+        app.state::<RecordingState>(),
+    ) {
+        Ok(()) => {
+            let _ = crate::commands::recording::show_recording_bar(app.clone());
+        }
+        Err(e) => {
+            log::warn!("Failed to start recording: {}", e);
+            let _ = app.emit("recording-error", &e);
+            // Emit dummy recording-stopped so frontend can reset state
+            let _ = app.emit(
+                "recording-stopped",
+                crate::commands::recording::StopResult {
+                    session_id: String::new(),
+                    duration_ms: 0,
+                    sample_count: 0,
+                },
+            );
+        }
+    }
+}
+
+/// Stop the dictation session started by [`start_dictation`].
+pub(crate) fn stop_dictation(app: &AppHandle) {
+    let _ = app.emit("hotkey-released", ());
+    match crate::commands::recording::stop_recording(app.clone(), app.state::<RecordingState>()) {
+        Ok(result) => {
+            // Don't hide bar — RecordingPipeline handles it after processing
+            log::info!(
+                "Recording stopped: session={}, duration={}ms",
+                result.session_id,
+                result.duration_ms
+            );
+        }
+        Err(e) => {
+            log::warn!("Failed to stop recording: {}", e);
+            let _ = crate::commands::recording::hide_recording_bar(app.clone());
+        }
+    }
+}
+
+/// The frontend owns the LLM settings, so rewriting only notifies it via
+/// `rewrite-selection-requested`.
+fn handle_rewrite(app: &AppHandle, shortcut_state: ShortcutState) {
+    if shortcut_state == ShortcutState::Pressed {
+        log::info!("Rewrite hotkey pressed");
+        let _ = app.emit("rewrite-selection-requested", ());
+    }
+}
+
+fn handle_paste_last(app: &AppHandle, shortcut_state: ShortcutState) {
+    if shortcut_state != ShortcutState::Pressed {
+        return;
+    }
+    log::info!("Paste-last hotkey pressed");
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(text) = crate::commands::clipboard::last_transcript(&app) else {
+            log::info!("Paste-last: nothing to paste");
+            return;
+        };
+        if let Err(e) = crate::commands::output::deliver_output(app, None, text).await {
+            log::warn!("Paste-last failed: {}", e);
+        }
+    });
+}
 
+fn handle_undo_paste(app: &AppHandle, shortcut_state: ShortcutState) {
+    if shortcut_state != ShortcutState::Pressed {
+        return;
+    }
+    log::info!("Undo-paste hotkey pressed");
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::commands::clipboard::undo_last_paste(app).await {
+            log::info!("Undo-paste: {}", e);
+        }
+    });
+}
+
+/// Release a shortcut previously passed to `register_shortcut`. Portal
+/// shortcuts are rebound when the set changes, so there is nothing to
+/// release there.
+pub fn unregister(app: &AppHandle, shortcut: Shortcut) -> Result<(), String> {
+    if binds_natively(&app.state::<HotkeyModeState>()) {
+        app.global_shortcut()
+            .unregister(shortcut)
+            .map_err(|e| e.to_string())?;
+    }
     Ok(())
 }
+
+/// Switch the hotkey backend at runtime, moving the registered shortcuts
+/// over to it.
+pub fn set_backend(app: &AppHandle, backend: HotkeyBackend) -> Result<(), String> {
+    let state = app.state::<HotkeyModeState>();
+    let previous = *state.backend.lock().unwrap();
+    if previous == backend {
+        return Ok(());
+    }
+
+    let shortcuts: Vec<_> = state.shortcuts.lock().unwrap().drain().collect();
+    for (_, shortcut) in &shortcuts {
+        unregister(app, *shortcut)?;
+    }
+    #[cfg(target_os = "linux")]
+    super::hotkey_portal::stop(app);
+
+    *state.backend.lock().unwrap() = backend;
+    for (action, shortcut) in shortcuts {
+        register_shortcut(app, action, shortcut).map_err(|e| e.to_string())?;
+    }
+    log::info!("Hotkey backend switched from {:?} to {:?}", previous, backend);
+    Ok(())
+}
+
+/// Release (`true`) or restore (`false`) all registered hotkeys without
+/// forgetting them. Not persisted — a restart re-enables them.
+pub fn set_paused(app: &AppHandle, paused: bool) -> Result<(), String> {
+    let state = app.state::<HotkeyModeState>();
+    if *state.paused.lock().unwrap() == paused {
+        return Ok(());
+    }
+
+    let shortcuts: Vec<_> = state
+        .shortcuts
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(action, shortcut)| (action.clone(), *shortcut))
+        .collect();
+    if paused {
+        for (_, shortcut) in &shortcuts {
+            unregister(app, *shortcut)?;
+        }
+        #[cfg(target_os = "linux")]
+        super::hotkey_portal::stop(app);
+        *state.paused.lock().unwrap() = true;
+    } else {
+        *state.paused.lock().unwrap() = false;
+        for (action, shortcut) in shortcuts {
+            register_shortcut(app, action, shortcut).map_err(|e| e.to_string())?;
+        }
+    }
+    log::info!("Global hotkeys {}", if paused { "paused" } else { "resumed" });
+    Ok(())
+}
+
+/// Whether shortcuts are currently bound through the native plugin.
+fn binds_natively(state: &HotkeyModeState) -> bool {
+    *state.backend.lock().unwrap() == HotkeyBackend::Native && !*state.paused.lock().unwrap()
+}
+
+/// Rebind portal shortcuts after one of them changed.
+fn restart_portal_if_active(app: &AppHandle) {
+    #[cfg(target_os = "linux")]
+    {
+        let state = app.state::<HotkeyModeState>();
+        if *state.backend.lock().unwrap() == HotkeyBackend::Portal
+            && !*state.paused.lock().unwrap()
+        {
+            super::hotkey_portal::restart(app);
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn double_press_needs_a_recent_press() {
+        let first = Instant::now();
+        assert!(!is_double_press(None, first));
+        assert!(is_double_press(Some(first), first + Duration::from_millis(250)));
+        assert!(!is_double_press(
+            Some(first),
+            first + DOUBLE_PRESS_WINDOW + Duration::from_millis(1)
+        ));
+    }
+
+    #[test]
+    fn action_ids_round_trip() {
+        for action in HotkeyAction::ALL {
+            assert_eq!(HotkeyAction::from_id(&action.id()), Some(action));
+        }
+        let binding = HotkeyAction::Binding("abc-123".into());
+        assert_eq!(HotkeyAction::from_id(&binding.id()), Some(binding));
+        assert_eq!(HotkeyAction::from_id("bogus"), None);
+    }
+
+    #[test]
+    fn parse_hotkey_reports_invalid_accelerators() {
+        assert!(parse_hotkey("Alt+Space").is_ok());
+        assert!(parse_hotkey("Alt+NotAKey").is_err());
+    }
+
+    #[test]
+    fn backend_parse() {
+        assert_eq!(HotkeyBackend::parse("native"), Ok(HotkeyBackend::Native));
+        assert!(HotkeyBackend::parse("bogus").is_err());
+        assert!(HotkeyBackend::parse("auto").is_ok());
+        #[cfg(target_os = "linux")]
+        assert_eq!(HotkeyBackend::parse("portal"), Ok(HotkeyBackend::Portal));
+        #[cfg(not(target_os = "linux"))]
+        assert!(HotkeyBackend::parse("portal").is_err());
+    }
+
+    #[test]
+    fn backend_detect_is_native_outside_wayland() {
+        assert_eq!(HotkeyBackend::detect_from(None, None), HotkeyBackend::Native);
+        assert_eq!(
+            HotkeyBackend::detect_from(Some("x11"), None),
+            HotkeyBackend::Native
+        );
+        #[cfg(target_os = "linux")]
+        {
+            assert_eq!(
+                HotkeyBackend::detect_from(Some("wayland"), None),
+                HotkeyBackend::Portal
+            );
+            assert_eq!(
+                HotkeyBackend::detect_from(None, Some("wayland-0")),
+                HotkeyBackend::Portal
+            );
+        }
+    }
+}
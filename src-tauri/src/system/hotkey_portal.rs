@@ -0,0 +1,161 @@
+//! Global hotkeys through the XDG desktop portal (`org.freedesktop.portal.GlobalShortcuts`).
+//!
+//! Wayland compositors don't let apps grab keys themselves, so the portal
+//! binds the shortcuts on our behalf. The compositor may ask the user to
+//! confirm or change the trigger; ours is only a preference.
+
+use super::hotkey::{self, HotkeyAction, HotkeyModeState};
+use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+use ashpd::WindowIdentifier;
+use futures_util::StreamExt;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut, ShortcutState};
+use tokio::sync::oneshot;
+
+/// (Re)bind the shortcuts currently recorded in `HotkeyModeState`,
+/// replacing any running portal session.
+pub fn restart(app: &AppHandle) {
+    stop(app);
+
+    let state = app.state::<HotkeyModeState>();
+    let shortcuts: Vec<NewShortcut> = state
+        .shortcuts
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(action, shortcut)| {
+            NewShortcut::new(action.id(), action.description())
+                .preferred_trigger(trigger(shortcut).as_str())
+        })
+        .collect();
+    if shortcuts.is_empty() {
+        return;
+    }
+
+    let (stop_tx, stop_rx) = oneshot::channel();
+    *state.portal_session.lock().unwrap() = Some(stop_tx);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run(&app, shortcuts, stop_rx).await {
+            log::error!("Portal global shortcuts failed: {}", e);
+            let _ = app.emit("hotkey-backend-error", e.to_string());
+        }
+    });
+}
+
+/// End the running portal session, if any.
+pub fn stop(app: &AppHandle) {
+    if let Some(stop_tx) = app
+        .state::<HotkeyModeState>()
+        .portal_session
+        .lock()
+        .unwrap()
+        .take()
+    {
+        let _ = stop_tx.send(());
+    }
+}
+
+async fn run(
+    app: &AppHandle,
+    shortcuts: Vec<NewShortcut>,
+    mut stop_rx: oneshot::Receiver<()>,
+) -> anyhow::Result<()> {
+    let portal = GlobalShortcuts::new().await?;
+    let session = portal.create_session().await?;
+    portal
+        .bind_shortcuts(&session, &shortcuts, &WindowIdentifier::default())
+        .await?
+        .response()?;
+    log::info!("Portal global shortcuts bound ({} shortcuts)", shortcuts.len());
+
+    let mut activated = portal.receive_activated().await?;
+    let mut deactivated = portal.receive_deactivated().await?;
+    loop {
+        tokio::select! {
+            _ = &mut stop_rx => break,
+            Some(event) = activated.next() => {
+                dispatch(app, event.shortcut_id(), ShortcutState::Pressed);
+            }
+            Some(event) = deactivated.next() => {
+                dispatch(app, event.shortcut_id(), ShortcutState::Released);
+            }
+            else => break,
+        }
+    }
+
+    session.close().await?;
+    Ok(())
+}
+
+fn dispatch(app: &AppHandle, id: &str, state: ShortcutState) {
+    match HotkeyAction::from_id(id) {
+        Some(action) => hotkey::dispatch(app, &action, state),
+        None => log::warn!("Unknown portal shortcut '{}'", id),
+    }
+}
+
+/// Format a shortcut as an XDG "shortcuts" spec trigger, e.g. `ALT+space`.
+fn trigger(shortcut: &Shortcut) -> String {
+    let mut parts: Vec<String> = [
+        (Modifiers::CONTROL, "CTRL"),
+        (Modifiers::ALT, "ALT"),
+        (Modifiers::SHIFT, "SHIFT"),
+        (Modifiers::SUPER | Modifiers::META, "LOGO"),
+    ]
+    .iter()
+    .filter(|(mods, _)| shortcut.mods.intersects(*mods))
+    .map(|(_, name)| name.to_string())
+    .collect();
+    parts.push(keysym(shortcut.key));
+    parts.join("+")
+}
+
+/// xkb keysym name for a key code.
+fn keysym(code: Code) -> String {
+    let name = code.to_string();
+    if let Some(letter) = name.strip_prefix("Key") {
+        return letter.to_lowercase();
+    }
+    if let Some(digit) = name.strip_prefix("Digit") {
+        return digit.to_string();
+    }
+    match code {
+        Code::Space => "space".into(),
+        Code::Enter => "Return".into(),
+        Code::Backspace => "BackSpace".into(),
+        Code::Backquote => "grave".into(),
+        Code::Minus => "minus".into(),
+        Code::Equal => "equal".into(),
+        Code::Comma => "comma".into(),
+        Code::Period => "period".into(),
+        Code::Slash => "slash".into(),
+        Code::Semicolon => "semicolon".into(),
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_formats_modifiers_and_key() {
+        let shortcut: Shortcut = "Alt+Space".parse().unwrap();
+        assert_eq!(trigger(&shortcut), "ALT+space");
+
+        let shortcut: Shortcut = "Control+Shift+KeyK".parse().unwrap();
+        assert_eq!(trigger(&shortcut), "CTRL+SHIFT+k");
+
+        let shortcut: Shortcut = "Super+F5".parse().unwrap();
+        assert_eq!(trigger(&shortcut), "LOGO+F5");
+    }
+
+    #[test]
+    fn keysym_digits_and_punctuation() {
+        assert_eq!(keysym(Code::Digit3), "3");
+        assert_eq!(keysym(Code::Enter), "Return");
+        assert_eq!(keysym(Code::Backquote), "grave");
+    }
+}
@@ -1,4 +1,18 @@
+pub mod app_paste;
+pub mod automation;
 pub mod autostart;
+pub mod focus_target;
+pub mod folder_watch;
+pub mod hid_input;
 pub mod hotkey;
+#[cfg(target_os = "linux")]
+pub mod hotkey_portal;
+pub mod mouse_trigger;
+pub mod notifications;
 pub mod paste;
+pub mod paste_format;
+pub mod permissions;
+pub mod secure_input;
+pub mod selection;
+pub mod storage;
 pub mod tray;
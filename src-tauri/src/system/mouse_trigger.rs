@@ -0,0 +1,319 @@
+//! An extra mouse button (middle, back/M4 or forward/M5) as the dictation
+//! trigger, for when keyboard hotkeys clash with other apps. Holding it works
+//! like holding the dictation hotkey.
+//!
+//! A low-level hook sees every press: a `WH_MOUSE_LL` hook on Windows, an
+//! event tap on macOS (needs Accessibility access) and XInput2 raw events on
+//! X11. Windows and macOS swallow the bound button so it doesn't also go
+//! back a page; X11 can only listen. Wayland offers no global hook.
+//!
+//! The hook is installed the first time a button is bound and stays for the
+//! rest of the session; unbinding only stops it from acting. If it couldn't
+//! be installed (no Accessibility access yet, no X11 session), binding a
+//! button again tries once more.
+
+use crate::system::hotkey::{self, HotkeyAction};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::OnceLock;
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::ShortcutState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MouseButton {
+    Middle = 1,
+    /// Side button, M4.
+    Back = 2,
+    /// Side button, M5.
+    Forward = 3,
+}
+
+impl MouseButton {
+    /// Parse the `mouseButton` setting: "none", "middle", "back" or "forward".
+    pub fn parse(value: &str) -> Result<Option<Self>, String> {
+        match value {
+            "none" | "" => Ok(None),
+            "middle" => Ok(Some(Self::Middle)),
+            "back" => Ok(Some(Self::Back)),
+            "forward" => Ok(Some(Self::Forward)),
+            other => Err(format!("Unknown mouse button '{}'", other)),
+        }
+    }
+}
+
+/// The bound button as its `u8` value; 0 when none is bound.
+static BOUND: AtomicU8 = AtomicU8::new(0);
+/// Presses of the bound button, handed from the hook to the dispatch thread
+/// so the hook returns quickly.
+static EVENTS: OnceLock<Sender<bool>> = OnceLock::new();
+/// Whether the hook thread is running. It only returns when the hook failed,
+/// which clears this so the next `set_button` can retry.
+static HOOK_LIVE: AtomicBool = AtomicBool::new(false);
+
+/// Use `button` to trigger dictation, or stop with `None`.
+pub fn set_button(app: &AppHandle, button: Option<MouseButton>) {
+    BOUND.store(button.map_or(0, |b| b as u8), Ordering::Relaxed);
+    log::info!("Mouse push-to-talk button: {:?}", button);
+    if button.is_none() {
+        return;
+    }
+
+    let (tx, rx) = mpsc::channel::<bool>();
+    if EVENTS.set(tx).is_ok() {
+        let app = app.clone();
+        let dispatcher = std::thread::Builder::new()
+            .name("mouse-trigger".into())
+            .spawn(move || {
+                for pressed in rx {
+                    let state = if pressed {
+                        ShortcutState::Pressed
+                    } else {
+                        ShortcutState::Released
+                    };
+                    hotkey::dispatch(&app, &HotkeyAction::Dictation, state);
+                }
+            });
+        if let Err(e) = dispatcher {
+            log::error!("Failed to start the mouse trigger dispatcher: {}", e);
+        }
+    }
+
+    if HOOK_LIVE.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let hook = std::thread::Builder::new()
+        .name("mouse-hook".into())
+        .spawn(|| {
+            platform::run_hook();
+            HOOK_LIVE.store(false, Ordering::SeqCst);
+        });
+    if let Err(e) = hook {
+        HOOK_LIVE.store(false, Ordering::SeqCst);
+        log::error!("Failed to start the mouse hook: {}", e);
+    }
+}
+
+/// Called by the hook for each press or release of an extra button.
+/// Returns whether it's the bound button, which the hook then swallows.
+fn handle(button: MouseButton, pressed: bool) -> bool {
+    if BOUND.load(Ordering::Relaxed) != button as u8 {
+        return false;
+    }
+    if let Some(events) = EVENTS.get() {
+        let _ = events.send(pressed);
+    }
+    true
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{handle, MouseButton};
+
+    const WH_MOUSE_LL: i32 = 14;
+    const WM_MBUTTONDOWN: usize = 0x0207;
+    const WM_MBUTTONUP: usize = 0x0208;
+    const WM_XBUTTONDOWN: usize = 0x020B;
+    const WM_XBUTTONUP: usize = 0x020C;
+    const XBUTTON1: u32 = 1;
+
+    #[repr(C)]
+    struct MsllHookStruct {
+        pt: [i32; 2],
+        mouse_data: u32,
+        flags: u32,
+        time: u32,
+        extra_info: usize,
+    }
+
+    #[repr(C)]
+    #[allow(dead_code)] // Filled in by Windows
+    struct Msg {
+        hwnd: isize,
+        message: u32,
+        wparam: usize,
+        lparam: isize,
+        time: u32,
+        pt: [i32; 2],
+        private: u32,
+    }
+
+    type HookProc = unsafe extern "system" fn(i32, usize, isize) -> isize;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn SetWindowsHookExW(id: i32, hook: HookProc, module: isize, thread_id: u32) -> isize;
+        fn CallNextHookEx(hook: isize, code: i32, wparam: usize, lparam: isize) -> isize;
+        fn GetMessageW(msg: *mut Msg, hwnd: isize, min: u32, max: u32) -> i32;
+    }
+
+    unsafe extern "system" fn mouse_proc(code: i32, wparam: usize, lparam: isize) -> isize {
+        if code >= 0 {
+            let info = &*(lparam as *const MsllHookStruct);
+            let button = match wparam {
+                WM_MBUTTONDOWN | WM_MBUTTONUP => Some(MouseButton::Middle),
+                WM_XBUTTONDOWN | WM_XBUTTONUP if info.mouse_data >> 16 == XBUTTON1 => {
+                    Some(MouseButton::Back)
+                }
+                WM_XBUTTONDOWN | WM_XBUTTONUP => Some(MouseButton::Forward),
+                _ => None,
+            };
+            let pressed = matches!(wparam, WM_MBUTTONDOWN | WM_XBUTTONDOWN);
+            if button.is_some_and(|button| handle(button, pressed)) {
+                return 1;
+            }
+        }
+        CallNextHookEx(0, code, wparam, lparam)
+    }
+
+    /// Install the hook and pump messages for it on this thread.
+    pub fn run_hook() {
+        unsafe {
+            if SetWindowsHookExW(WH_MOUSE_LL, mouse_proc, 0, 0) == 0 {
+                log::error!("Failed to install the mouse hook");
+                return;
+            }
+            let mut msg: Msg = std::mem::zeroed();
+            while GetMessageW(&mut msg, 0, 0, 0) > 0 {}
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{handle, MouseButton};
+    use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop};
+    use core_graphics::event::{
+        CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement, CGEventType,
+        EventField,
+    };
+
+    /// Listen for other-mouse-button events on this thread's run loop.
+    pub fn run_hook() {
+        let tap = CGEventTap::new(
+            CGEventTapLocation::HID,
+            CGEventTapPlacement::HeadInsertEventTap,
+            CGEventTapOptions::Default,
+            vec![CGEventType::OtherMouseDown, CGEventType::OtherMouseUp],
+            |_proxy, event_type, event| {
+                let number = event.get_integer_value_field(EventField::MOUSE_EVENT_BUTTON_NUMBER);
+                let button = match number {
+                    2 => MouseButton::Middle,
+                    3 => MouseButton::Back,
+                    4 => MouseButton::Forward,
+                    _ => return None,
+                };
+                let pressed = matches!(event_type, CGEventType::OtherMouseDown);
+                if handle(button, pressed) {
+                    // A null event is dropped instead of delivered
+                    event.set_type(CGEventType::Null);
+                }
+                None
+            },
+        );
+        let Ok(tap) = tap else {
+            log::error!("Failed to create the mouse event tap (is Accessibility access granted?)");
+            return;
+        };
+        let Ok(source) = tap.mach_port.create_runloop_source(0) else {
+            log::error!("Failed to add the mouse event tap to the run loop");
+            return;
+        };
+        unsafe {
+            CFRunLoop::get_current().add_source(&source, kCFRunLoopCommonModes);
+        }
+        tap.enable();
+        CFRunLoop::run_current();
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{handle, MouseButton};
+    use std::os::raw::c_int;
+    use x11::xinput2::{
+        XIAllMasterDevices, XIEventMask, XIRawEvent, XISelectEvents, XISetMask, XI_RawButtonPress,
+        XI_RawButtonRelease,
+    };
+    use x11::xlib::{
+        GenericEvent, XCloseDisplay, XDefaultRootWindow, XEvent, XFlush, XFreeEventData,
+        XGetEventData, XNextEvent, XOpenDisplay, XQueryExtension,
+    };
+
+    /// Read raw button events from the X server on this thread.
+    pub fn run_hook() {
+        unsafe {
+            let display = XOpenDisplay(std::ptr::null());
+            if display.is_null() {
+                log::warn!("Mouse push-to-talk needs an X11 session");
+                return;
+            }
+            let (mut opcode, mut event, mut error): (c_int, c_int, c_int) = (0, 0, 0);
+            let name = c"XInputExtension";
+            if XQueryExtension(display, name.as_ptr(), &mut opcode, &mut event, &mut error) == 0 {
+                log::warn!("Mouse push-to-talk needs the XInput extension");
+                XCloseDisplay(display);
+                return;
+            }
+
+            let mut mask = [0u8; 4];
+            XISetMask(&mut mask, XI_RawButtonPress);
+            XISetMask(&mut mask, XI_RawButtonRelease);
+            let mut event_mask = XIEventMask {
+                deviceid: XIAllMasterDevices,
+                mask_len: mask.len() as c_int,
+                mask: mask.as_mut_ptr(),
+            };
+            XISelectEvents(display, XDefaultRootWindow(display), &mut event_mask, 1);
+            XFlush(display);
+
+            loop {
+                let mut event: XEvent = std::mem::zeroed();
+                XNextEvent(display, &mut event);
+                let cookie = &mut event.generic_event_cookie;
+                if cookie.type_ != GenericEvent
+                    || cookie.extension != opcode
+                    || XGetEventData(display, cookie) == 0
+                {
+                    continue;
+                }
+                let raw = &*(cookie.data as *const XIRawEvent);
+                let button = match raw.detail {
+                    2 => Some(MouseButton::Middle),
+                    8 => Some(MouseButton::Back),
+                    9 => Some(MouseButton::Forward),
+                    _ => None,
+                };
+                if let Some(button) = button {
+                    handle(button, cookie.evtype == XI_RawButtonPress);
+                }
+                XFreeEventData(display, cookie);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_buttons() {
+        assert_eq!(MouseButton::parse("none"), Ok(None));
+        assert_eq!(MouseButton::parse("back"), Ok(Some(MouseButton::Back)));
+        assert_eq!(
+            MouseButton::parse("forward"),
+            Ok(Some(MouseButton::Forward))
+        );
+        assert!(MouseButton::parse("left").is_err());
+    }
+
+    #[test]
+    fn only_the_bound_button_is_handled() {
+        BOUND.store(MouseButton::Back as u8, Ordering::Relaxed);
+        assert!(handle(MouseButton::Back, true));
+        assert!(!handle(MouseButton::Forward, true));
+        BOUND.store(0, Ordering::Relaxed);
+        assert!(!handle(MouseButton::Back, false));
+    }
+}
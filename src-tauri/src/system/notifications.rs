@@ -0,0 +1,103 @@
+//! A system notification when a transcription finishes while the main window
+//! is hidden, previewing the transcript's first line.
+//!
+//! The desktop notification plugin doesn't report clicks, so instead the
+//! main window opens on History the next time it's shown within a few
+//! minutes of the notification: clicking the notification activates the
+//! app, and the tray or dock icon brings the window up.
+
+use crate::i18n::t;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// How long after a notification showing the main window opens History.
+const OPEN_HISTORY_FOR: Duration = Duration::from_secs(5 * 60);
+/// Longest transcript preview, in characters.
+const PREVIEW_CHARS: usize = 120;
+
+pub struct CompletionNotice {
+    /// When the last notification was posted, until the window opens History.
+    posted_at: Mutex<Option<Instant>>,
+}
+
+impl CompletionNotice {
+    pub fn new() -> Self {
+        Self {
+            posted_at: Mutex::new(None),
+        }
+    }
+}
+
+/// Post a notification for a finished transcription, unless the main window
+/// is showing, notifications are off or nothing was said.
+pub fn transcription_finished(app: &AppHandle, text: &str) {
+    if !crate::db::settings::load().notify_on_transcription || main_window_visible(app) {
+        return;
+    }
+    let Some(body) = preview(text, PREVIEW_CHARS) else {
+        return;
+    };
+    let shown = app
+        .notification()
+        .builder()
+        .title(t("notification.transcriptionDone"))
+        .body(body)
+        .show();
+    if let Err(e) = shown {
+        log::warn!("Failed to show transcription notification: {}", e);
+        return;
+    }
+    *app.state::<CompletionNotice>().posted_at.lock().unwrap() = Some(Instant::now());
+}
+
+/// The main window was shown or focused: open History if a notification was
+/// posted recently.
+pub fn main_window_shown(app: &AppHandle) {
+    let posted_at = app
+        .state::<CompletionNotice>()
+        .posted_at
+        .lock()
+        .unwrap()
+        .take();
+    if posted_at.is_some_and(|at| at.elapsed() < OPEN_HISTORY_FOR) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.emit("navigate", "/history");
+        }
+    }
+}
+
+fn main_window_visible(app: &AppHandle) -> bool {
+    app.get_webview_window("main").is_some_and(|window| {
+        window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false)
+    })
+}
+
+/// The first non-empty line of `text`, shortened to `max_chars`.
+pub(crate) fn preview(text: &str, max_chars: usize) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    if line.chars().count() <= max_chars {
+        return Some(line.to_string());
+    }
+    let cut: String = line.chars().take(max_chars - 1).collect();
+    Some(format!("{}…", cut.trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_is_the_first_line_shortened() {
+        assert_eq!(
+            preview("\n  Hello there.\nSecond line", PREVIEW_CHARS),
+            Some("Hello there.".into())
+        );
+        assert_eq!(preview(" \n ", PREVIEW_CHARS), None);
+        let long = "word ".repeat(40);
+        let shown = preview(&long, PREVIEW_CHARS).unwrap();
+        assert_eq!(shown.chars().count(), PREVIEW_CHARS);
+        assert!(shown.ends_with("word…"), "{}", shown);
+    }
+}
@@ -5,14 +5,104 @@ use std::time::Duration;
 pub fn simulate_paste() -> anyhow::Result<()> {
     // Small delay to ensure clipboard is ready
     thread::sleep(Duration::from_millis(50));
+    send_shortcut('v')?;
+    // Small delay to ensure the keystroke is delivered
+    thread::sleep(Duration::from_millis(50));
+    Ok(())
+}
+
+/// Simulates a copy keystroke (Cmd+C on macOS, Ctrl+C elsewhere).
+pub fn simulate_copy() -> anyhow::Result<()> {
+    send_shortcut('c')?;
+    // The target app needs a moment to write the clipboard
+    thread::sleep(Duration::from_millis(150));
+    Ok(())
+}
 
+/// Types Shift+Enter: a line break in chat apps that send on Enter.
+pub fn simulate_shift_enter() -> anyhow::Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+        // Key code 36 is Return
+        let status = std::process::Command::new("osascript")
+            .arg("-e")
+            .arg("tell application \"System Events\" to key code 36 using shift down")
+            .status()?;
+
+        if !status.success() {
+            anyhow::bail!("osascript exited with status: {}", status);
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+        let mut enigo = Enigo::new(&Settings::default())?;
+        enigo.key(Key::Shift, Direction::Press)?;
+        enigo.key(Key::Return, Direction::Click)?;
+        enigo.key(Key::Shift, Direction::Release)?;
+    }
+
+    thread::sleep(Duration::from_millis(50));
+    Ok(())
+}
+
+/// Selects the `chars` characters before the caret with Shift+Left and
+/// deletes them, e.g. to take back text that was just pasted.
+pub fn simulate_delete_back(chars: usize) -> anyhow::Result<()> {
+    if chars == 0 {
+        return Ok(());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // Key code 123 is Left Arrow, 51 is Delete
+        let status = std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(format!(
+                "tell application \"System Events\"\n\
+                 repeat {} times\n\
+                 key code 123 using shift down\n\
+                 end repeat\n\
+                 key code 51\n\
+                 end tell",
+                chars
+            ))
+            .status()?;
+
+        if !status.success() {
+            anyhow::bail!("osascript exited with status: {}", status);
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+        let mut enigo = Enigo::new(&Settings::default())?;
+        enigo.key(Key::Shift, Direction::Press)?;
+        for _ in 0..chars {
+            enigo.key(Key::LeftArrow, Direction::Click)?;
+        }
+        enigo.key(Key::Shift, Direction::Release)?;
+        enigo.key(Key::Backspace, Direction::Click)?;
+    }
+
+    thread::sleep(Duration::from_millis(50));
+    Ok(())
+}
+
+/// Send Cmd/Ctrl + `key` to the focused application.
+fn send_shortcut(key: char) -> anyhow::Result<()> {
     #[cfg(target_os = "macos")]
     {
         // Use osascript instead of enigo on macOS.
         // enigo's CGEvent posting causes the Tauri app to exit.
         let status = std::process::Command::new("osascript")
             .arg("-e")
-            .arg("tell application \"System Events\" to keystroke \"v\" using command down")
+            .arg(format!(
+                "tell application \"System Events\" to keystroke \"{}\" using command down",
+                key
+            ))
             .status()?;
 
         if !status.success() {
@@ -25,12 +115,9 @@ pub fn simulate_paste() -> anyhow::Result<()> {
         use enigo::{Direction, Enigo, Key, Keyboard, Settings};
         let mut enigo = Enigo::new(&Settings::default())?;
         enigo.key(Key::Control, Direction::Press)?;
-        enigo.key(Key::Unicode('v'), Direction::Click)?;
+        enigo.key(Key::Unicode(key), Direction::Click)?;
         enigo.key(Key::Control, Direction::Release)?;
     }
 
-    // Small delay to ensure the keystroke is delivered
-    thread::sleep(Duration::from_millis(50));
-
     Ok(())
 }
@@ -0,0 +1,138 @@
+//! How pasted text is put on the clipboard. AI functions answer in Markdown,
+//! which reads badly where it isn't rendered (asterisks in an email), so it
+//! can be pasted as plain text, as-is, or as rich text (HTML with a plain
+//! text fallback) for mail clients and document editors.
+
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteFormat {
+    /// Markdown syntax removed.
+    Plain,
+    /// The text unchanged.
+    Markdown,
+    /// Markdown rendered to HTML, plus the plain text for apps without HTML.
+    Rich,
+}
+
+impl PasteFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "plain" => Ok(Self::Plain),
+            "markdown" => Ok(Self::Markdown),
+            "rich" => Ok(Self::Rich),
+            other => Err(format!(
+                "Unknown paste format '{}' (use plain, markdown or rich)",
+                other
+            )),
+        }
+    }
+}
+
+/// Markdown rendered as HTML.
+pub fn to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, Parser::new(markdown));
+    html
+}
+
+/// Markdown as readable plain text: emphasis and headings lose their marks,
+/// list items get bullets or numbers, links keep their URL.
+pub fn to_plain(markdown: &str) -> String {
+    let mut out = String::new();
+    // Next number of each open list; None for bulleted lists
+    let mut lists: Vec<Option<u64>> = vec![];
+    let mut link_urls: Vec<String> = vec![];
+    let mut link_start = 0;
+
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Text(text) | Event::Code(text) => out.push_str(&text),
+            Event::SoftBreak | Event::HardBreak | Event::Rule => out.push('\n'),
+            Event::Start(Tag::List(start)) => lists.push(start),
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+                if lists.is_empty() {
+                    out.push('\n');
+                }
+            }
+            Event::Start(Tag::Item) => {
+                let depth = lists.len().saturating_sub(1);
+                out.push_str(&"  ".repeat(depth));
+                match lists.last_mut() {
+                    Some(Some(n)) => {
+                        out.push_str(&format!("{}. ", n));
+                        *n += 1;
+                    }
+                    _ => out.push_str("• "),
+                }
+            }
+            Event::End(TagEnd::Item) if !out.ends_with('\n') => out.push('\n'),
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                link_urls.push(dest_url.to_string());
+                link_start = out.len();
+            }
+            Event::End(TagEnd::Link) => {
+                let url = link_urls.pop().unwrap_or_default();
+                if !url.is_empty() && out[link_start..] != url {
+                    out.push_str(&format!(" ({})", url));
+                }
+            }
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::CodeBlock)
+                if lists.is_empty() =>
+            {
+                out.push_str("\n\n");
+            }
+            Event::End(TagEnd::Paragraph) => out.push('\n'),
+            _ => {}
+        }
+    }
+    // Paragraphs in list items end in their own line break
+    out.replace("\n\n\n", "\n\n").trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_drops_markdown_syntax() {
+        let markdown = "# Weekly update\n\nHi **team**, the *launch* is on `Monday`.\n\n\
+                        - Ship the [release](https://example.com/r)\n\
+                        - Write notes\n\n\
+                        1. First\n2. Second\n\nThanks";
+        assert_eq!(
+            to_plain(markdown),
+            "Weekly update\n\nHi team, the launch is on Monday.\n\n\
+             • Ship the release (https://example.com/r)\n\
+             • Write notes\n\n\
+             1. First\n2. Second\n\nThanks"
+        );
+    }
+
+    #[test]
+    fn plain_text_leaves_prose_alone() {
+        assert_eq!(
+            to_plain("Just a sentence.\nAnd another."),
+            "Just a sentence.\nAnd another."
+        );
+        assert_eq!(
+            to_plain("See https://example.com"),
+            "See https://example.com"
+        );
+    }
+
+    #[test]
+    fn html_renders_markdown() {
+        assert_eq!(
+            to_html("Hi **team**\n\n- one"),
+            "<p>Hi <strong>team</strong></p>\n<ul>\n<li>one</li>\n</ul>\n"
+        );
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!(PasteFormat::parse("rich"), Ok(PasteFormat::Rich));
+        assert!(PasteFormat::parse("docx").is_err());
+    }
+}
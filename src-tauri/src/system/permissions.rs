@@ -0,0 +1,191 @@
+//! Microphone and accessibility permission checks. Without them recordings
+//! are silent and auto-paste does nothing, with no error from the OS.
+//!
+//! Only macOS gates these per app; other platforms always report `Granted`.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// The user hasn't been asked yet.
+    NotDetermined,
+    /// Blocked by device policy (MDM, parental controls).
+    Restricted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Permissions {
+    pub microphone: PermissionState,
+    /// Needed to simulate the paste/copy keystrokes.
+    pub accessibility: PermissionState,
+}
+
+pub fn check() -> Permissions {
+    Permissions {
+        microphone: microphone_status(),
+        accessibility: accessibility_status(),
+    }
+}
+
+/// Prompt for anything not yet granted. Permissions that were already
+/// denied can only be changed in System Settings, so that pane is opened
+/// instead. Returns immediately; the user answers asynchronously.
+pub fn request() -> Permissions {
+    let current = check();
+    match current.microphone {
+        PermissionState::NotDetermined => request_microphone(),
+        PermissionState::Denied | PermissionState::Restricted => {
+            open_privacy_pane("Privacy_Microphone")
+        }
+        PermissionState::Granted => {}
+    }
+    if current.accessibility != PermissionState::Granted {
+        request_accessibility();
+    }
+    check()
+}
+
+/// Map `AVAuthorizationStatus` to a `PermissionState`.
+#[cfg(any(target_os = "macos", test))]
+fn from_av_status(status: isize) -> PermissionState {
+    match status {
+        0 => PermissionState::NotDetermined,
+        1 => PermissionState::Restricted,
+        2 => PermissionState::Denied,
+        _ => PermissionState::Granted,
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use core_foundation::base::TCFType;
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::string::{CFString, CFStringRef};
+    use objc2::runtime::{AnyObject, Bool};
+    use objc2::{class, msg_send};
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {
+        static AVMediaTypeAudio: &'static AnyObject;
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+        fn AXIsProcessTrustedWithOptions(options: CFDictionaryRef) -> bool;
+        static kAXTrustedCheckOptionPrompt: CFStringRef;
+    }
+
+    pub fn microphone_status() -> isize {
+        unsafe {
+            msg_send![
+                class!(AVCaptureDevice),
+                authorizationStatusForMediaType: AVMediaTypeAudio
+            ]
+        }
+    }
+
+    pub fn request_microphone() {
+        let handler = block2::RcBlock::new(|granted: Bool| {
+            log::info!("Microphone access granted: {}", granted.as_bool());
+        });
+        unsafe {
+            let _: () = msg_send![
+                class!(AVCaptureDevice),
+                requestAccessForMediaType: AVMediaTypeAudio,
+                completionHandler: &*handler
+            ];
+        }
+    }
+
+    pub fn accessibility_trusted() -> bool {
+        unsafe { AXIsProcessTrusted() }
+    }
+
+    /// Shows the system dialog that links to the Accessibility pane.
+    pub fn prompt_accessibility() {
+        let key = unsafe { CFString::wrap_under_get_rule(kAXTrustedCheckOptionPrompt) };
+        let options = CFDictionary::from_CFType_pairs(&[(
+            key.as_CFType(),
+            CFBoolean::true_value().as_CFType(),
+        )]);
+        unsafe {
+            AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef());
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn microphone_status() -> PermissionState {
+    from_av_status(macos::microphone_status())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn microphone_status() -> PermissionState {
+    PermissionState::Granted
+}
+
+#[cfg(target_os = "macos")]
+fn accessibility_status() -> PermissionState {
+    if macos::accessibility_trusted() {
+        PermissionState::Granted
+    } else {
+        PermissionState::Denied
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn accessibility_status() -> PermissionState {
+    PermissionState::Granted
+}
+
+fn request_microphone() {
+    #[cfg(target_os = "macos")]
+    macos::request_microphone();
+}
+
+fn request_accessibility() {
+    #[cfg(target_os = "macos")]
+    macos::prompt_accessibility();
+}
+
+/// Open a Privacy & Security pane, e.g. `Privacy_Microphone`.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+fn open_privacy_pane(anchor: &str) {
+    #[cfg(target_os = "macos")]
+    {
+        let url = format!(
+            "x-apple.systempreferences:com.apple.preference.security?{}",
+            anchor
+        );
+        if let Err(e) = std::process::Command::new("open").arg(&url).status() {
+            log::warn!("Failed to open System Settings: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn av_status_mapping() {
+        assert_eq!(from_av_status(0), PermissionState::NotDetermined);
+        assert_eq!(from_av_status(1), PermissionState::Restricted);
+        assert_eq!(from_av_status(2), PermissionState::Denied);
+        assert_eq!(from_av_status(3), PermissionState::Granted);
+    }
+
+    #[test]
+    fn permission_state_serializes_kebab_case() {
+        assert_eq!(
+            serde_json::to_string(&PermissionState::NotDetermined).unwrap(),
+            "\"not-determined\""
+        );
+    }
+}
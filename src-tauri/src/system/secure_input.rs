@@ -0,0 +1,122 @@
+//! Detects a password field under the keyboard focus. Pasting dictation
+//! there fails silently in most apps, and a spoken secret would end up in
+//! history.
+//!
+//! macOS asks the accessibility API for the focused element's subrole;
+//! Windows checks for a classic edit control with `ES_PASSWORD`. Other
+//! platforms can't tell and always report `false`.
+
+/// Whether the focused text field is a password field.
+pub fn focused_field_is_secure() -> bool {
+    let secure = platform::focused_field_is_secure();
+    if secure {
+        log::info!("Focused field is a password field");
+    }
+    secure
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use core_foundation::base::{CFRelease, CFType, CFTypeRef, TCFType};
+    use core_foundation::string::{CFString, CFStringRef};
+
+    /// Subrole of `NSSecureTextField` and browser password inputs.
+    const SECURE_TEXT_FIELD: &str = "AXSecureTextField";
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateSystemWide() -> CFTypeRef;
+        fn AXUIElementCopyAttributeValue(
+            element: CFTypeRef,
+            attribute: CFStringRef,
+            value: *mut CFTypeRef,
+        ) -> i32;
+    }
+
+    /// Copy an attribute of an accessibility element. The caller owns the result.
+    unsafe fn copy_attribute(element: CFTypeRef, name: &str) -> Option<CFTypeRef> {
+        let name = CFString::new(name);
+        let mut value: CFTypeRef = std::ptr::null();
+        let err = AXUIElementCopyAttributeValue(element, name.as_concrete_TypeRef(), &mut value);
+        (err == 0 && !value.is_null()).then_some(value)
+    }
+
+    pub fn focused_field_is_secure() -> bool {
+        unsafe {
+            let system = AXUIElementCreateSystemWide();
+            if system.is_null() {
+                return false;
+            }
+            let focused = copy_attribute(system, "AXFocusedUIElement");
+            CFRelease(system);
+            let Some(focused) = focused else {
+                return false;
+            };
+            let subrole = copy_attribute(focused, "AXSubrole");
+            CFRelease(focused);
+            subrole
+                .and_then(|s| CFType::wrap_under_create_rule(s).downcast_into::<CFString>())
+                .is_some_and(|s| s.to_string() == SECURE_TEXT_FIELD)
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    const GWL_STYLE: i32 = -16;
+    const ES_PASSWORD: i32 = 0x0020;
+
+    #[repr(C)]
+    #[allow(dead_code)] // Filled in by Windows
+    struct Rect {
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    }
+
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct GuiThreadInfo {
+        cb_size: u32,
+        flags: u32,
+        hwnd_active: isize,
+        hwnd_focus: isize,
+        hwnd_capture: isize,
+        hwnd_menu_owner: isize,
+        hwnd_move_size: isize,
+        hwnd_caret: isize,
+        rc_caret: Rect,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetGUIThreadInfo(thread_id: u32, info: *mut GuiThreadInfo) -> i32;
+        fn GetWindowLongW(hwnd: isize, index: i32) -> i32;
+        fn GetClassNameW(hwnd: isize, name: *mut u16, max_count: i32) -> i32;
+    }
+
+    pub fn focused_field_is_secure() -> bool {
+        unsafe {
+            let mut info: GuiThreadInfo = std::mem::zeroed();
+            info.cb_size = std::mem::size_of::<GuiThreadInfo>() as u32;
+            // Thread 0 is the foreground thread
+            if GetGUIThreadInfo(0, &mut info) == 0 || info.hwnd_focus == 0 {
+                return false;
+            }
+            let mut class = [0u16; 64];
+            let len = GetClassNameW(info.hwnd_focus, class.as_mut_ptr(), class.len() as i32);
+            let class = String::from_utf16_lossy(&class[..len.max(0) as usize]);
+            // Only edit controls define ES_PASSWORD; other classes reuse the bit
+            class.eq_ignore_ascii_case("Edit")
+                && GetWindowLongW(info.hwnd_focus, GWL_STYLE) & ES_PASSWORD != 0
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    pub fn focused_field_is_secure() -> bool {
+        false
+    }
+}
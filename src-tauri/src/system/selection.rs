@@ -0,0 +1,75 @@
+use crate::system::paste;
+use arboard::{Clipboard, ImageData};
+use std::path::PathBuf;
+
+/// What was on the clipboard before the simulated copy, in the richest form
+/// that can be put back.
+enum SavedClipboard {
+    /// Nothing we can read: empty, or only in formats other than these.
+    Empty,
+    Files(Vec<PathBuf>),
+    Image(ImageData<'static>),
+    Html { html: String, text: Option<String> },
+    Text(String),
+}
+
+impl SavedClipboard {
+    fn save(clipboard: &mut Clipboard) -> Self {
+        if let Ok(files) = clipboard.get().file_list() {
+            if !files.is_empty() {
+                return Self::Files(files);
+            }
+        }
+        if let Ok(image) = clipboard.get().image() {
+            return Self::Image(image);
+        }
+        let text = clipboard.get().text().ok();
+        match (clipboard.get().html(), text) {
+            (Ok(html), text) => Self::Html { html, text },
+            (Err(_), Some(text)) => Self::Text(text),
+            (Err(_), None) => Self::Empty,
+        }
+    }
+
+    fn restore(self, clipboard: &mut Clipboard) -> Result<(), arboard::Error> {
+        match self {
+            Self::Empty => clipboard.clear(),
+            Self::Files(files) => clipboard.set().file_list(&files),
+            Self::Image(image) => clipboard.set().image(image),
+            Self::Html { html, text } => clipboard.set().html(html, text),
+            Self::Text(text) => clipboard.set().text(text),
+        }
+    }
+}
+
+/// Read the focused app's selected text by simulating a copy, then put back
+/// what was on the clipboard before: text, HTML, an image or copied files.
+/// Content only in other formats can't be read, so it's lost and the
+/// clipboard is left empty. Returns `None` when nothing is selected.
+///
+/// Reading the selection through the accessibility APIs instead, without
+/// touching the clipboard, isn't implemented yet.
+pub async fn read_selected_text() -> anyhow::Result<Option<String>> {
+    // Off the main thread, where clipboard access can deadlock on Linux
+    tokio::task::spawn_blocking(|| {
+        let mut clipboard = Clipboard::new()?;
+        let saved = SavedClipboard::save(&mut clipboard);
+
+        // Clear first so an empty selection isn't mistaken for the old clipboard
+        clipboard.clear()?;
+        let copied = paste::simulate_copy();
+        let selected = clipboard
+            .get()
+            .text()
+            .ok()
+            .filter(|s| !s.trim().is_empty());
+
+        if let Err(e) = saved.restore(&mut clipboard) {
+            log::warn!("Failed to restore the clipboard: {}", e);
+        }
+        copied?;
+        Ok(selected)
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Copy task panicked: {}", e))?
+}
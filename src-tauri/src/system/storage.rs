@@ -0,0 +1,151 @@
+//! Where recordings and models are kept. Defaults to the app data dir; the
+//! user can move it (e.g. to an external drive) with `set_storage_dir`. The
+//! database always stays in the app data dir.
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Subdirectories that follow the storage location.
+const MOVED_DIRS: [&str; 2] = ["audio", "models"];
+
+/// Current storage root: the configured directory, or the app data dir.
+pub fn root(app: &AppHandle) -> Result<PathBuf, String> {
+    match crate::db::settings::load().storage_dir {
+        Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+        _ => app.path().app_data_dir().map_err(|e| e.to_string()),
+    }
+}
+
+/// Directory holding session WAVs.
+pub fn audio_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    root(app).map(|dir| dir.join("audio"))
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub files_moved: u64,
+    pub bytes_moved: u64,
+    /// Files left in place because the destination already had them.
+    pub files_skipped: u64,
+    pub history_updated: usize,
+    /// Files that moved, as (old path, new path).
+    #[serde(skip)]
+    pub moved: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Reject destinations that would nest the data inside itself.
+pub fn validate_target(from: &Path, to: &Path) -> Result<(), String> {
+    if !to.is_absolute() {
+        return Err("Storage directory must be an absolute path".into());
+    }
+    if let Some(sub) = MOVED_DIRS.iter().find(|sub| to.starts_with(from.join(sub))) {
+        return Err(format!(
+            "Storage directory can't be inside the current {} folder",
+            sub
+        ));
+    }
+    Ok(())
+}
+
+/// Move the audio and models folders from `from` to `to`. Renames where
+/// possible and falls back to copy + delete across drives.
+pub fn move_dirs(from: &Path, to: &Path) -> std::io::Result<MigrationReport> {
+    let mut report = MigrationReport::default();
+    for sub in MOVED_DIRS {
+        move_tree(&from.join(sub), &to.join(sub), &mut report)?;
+    }
+    Ok(report)
+}
+
+fn move_tree(src: &Path, dst: &Path, report: &mut MigrationReport) -> std::io::Result<()> {
+    if !src.is_dir() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(dst)?;
+
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            move_tree(&path, &target, report)?;
+        } else if target.exists() {
+            log::warn!("Not overwriting {:?}, leaving {:?} in place", target, path);
+            report.files_skipped += 1;
+        } else {
+            let size = entry.metadata()?.len();
+            if std::fs::rename(&path, &target).is_err() {
+                std::fs::copy(&path, &target)?;
+                std::fs::remove_file(&path)?;
+            }
+            report.files_moved += 1;
+            report.bytes_moved += size;
+            report.moved.push((path, target));
+        }
+    }
+
+    // Only succeeds once everything moved
+    let _ = std::fs::remove_dir(src);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_dirs_moves_audio_and_models() {
+        let base = std::env::temp_dir().join("sobotta_test_storage_move");
+        let _ = std::fs::remove_dir_all(&base);
+        let (from, to) = (base.join("from"), base.join("to"));
+        std::fs::create_dir_all(from.join("audio")).unwrap();
+        std::fs::create_dir_all(from.join("models/whisper-base")).unwrap();
+        std::fs::write(from.join("audio/a.wav"), b"1234").unwrap();
+        std::fs::write(from.join("models/whisper-base/model.bin"), b"12").unwrap();
+        std::fs::write(from.join("sobottaai.db"), b"db").unwrap();
+
+        let report = move_dirs(&from, &to).unwrap();
+        assert_eq!(report.files_moved, 2);
+        assert_eq!(report.bytes_moved, 6);
+        assert!(report
+            .moved
+            .contains(&(from.join("audio/a.wav"), to.join("audio/a.wav"))));
+        assert!(to.join("audio/a.wav").exists());
+        assert!(to.join("models/whisper-base/model.bin").exists());
+        assert!(!from.join("audio").exists());
+        assert!(!from.join("models").exists());
+        // Only the storage folders move
+        assert!(from.join("sobottaai.db").exists());
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn move_dirs_keeps_existing_destination_files() {
+        let base = std::env::temp_dir().join("sobotta_test_storage_skip");
+        let _ = std::fs::remove_dir_all(&base);
+        let (from, to) = (base.join("from"), base.join("to"));
+        std::fs::create_dir_all(from.join("audio")).unwrap();
+        std::fs::create_dir_all(to.join("audio")).unwrap();
+        std::fs::write(from.join("audio/a.wav"), b"new").unwrap();
+        std::fs::write(to.join("audio/a.wav"), b"old").unwrap();
+
+        let report = move_dirs(&from, &to).unwrap();
+        assert_eq!(report.files_moved, 0);
+        assert_eq!(report.files_skipped, 1);
+        assert!(report.moved.is_empty());
+        assert_eq!(std::fs::read(to.join("audio/a.wav")).unwrap(), b"old");
+        assert!(from.join("audio/a.wav").exists());
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn validate_target_rejects_nested_and_relative() {
+        let from = PathBuf::from("/data/app");
+        assert!(validate_target(&from, Path::new("/mnt/external")).is_ok());
+        assert!(validate_target(&from, Path::new("/data/app/audio/x")).is_err());
+        assert!(validate_target(&from, Path::new("relative/dir")).is_err());
+    }
+}
@@ -1,9 +1,21 @@
+use crate::commands::clipboard::RecentOutputs;
+use crate::commands::compose::ComposeState;
+use crate::commands::recording::RecordingState;
+use crate::i18n::{t, t_with};
+use crate::system::hotkey::{self, HotkeyModeState};
 use std::sync::Mutex;
 use tauri::{
     menu::{CheckMenuItem, Menu, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager,
 };
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_notification::NotificationExt;
+
+const TRAY_ID: &str = "main";
+/// Longest recent output shown in the tray, in characters.
+const RECENT_OUTPUT_CHARS: usize = 48;
 
 /// Models available in the tray (matches frontend model-selector.tsx).
 const LOCAL_MODELS: &[(&str, &str)] = &[
@@ -18,7 +30,11 @@ const LOCAL_MODELS: &[(&str, &str)] = &[
 
 const CLOUD_MODELS: &[(&str, &str)] = &[
     ("cloud-openai-whisper", "OpenAI Whisper (Cloud)"),
+    ("cloud-openai-gpt-4o-transcribe", "OpenAI GPT-4o Transcribe (Cloud)"),
+    ("cloud-openai-gpt-4o-mini-transcribe", "OpenAI GPT-4o Mini Transcribe (Cloud)"),
     ("cloud-groq-whisper", "Groq Whisper (Cloud)"),
+    ("cloud-groq-whisper-large-v3", "Groq Whisper Large V3 (Cloud)"),
+    ("cloud-groq-distil-whisper", "Groq Distil-Whisper (Cloud, EN)"),
 ];
 
 /// Languages available in the tray (matches frontend language-selector.tsx).
@@ -41,14 +57,12 @@ const LANGUAGES: &[(&str, &str)] = &[
     ("sv", "Swedish"),
 ];
 
-/// AI functions (matches builtin list from ai_functions.rs).
-const AI_FUNCTIONS: &[(&str, &str)] = &[
-    ("none", "None"),
-    ("email", "Professional Email"),
-    ("code-prompt", "Code Prompt"),
-    ("summarize", "Summarize"),
-    ("casual", "Casual Rewrite"),
-    ("translate", "Translate to English"),
+/// Recording modes (matches frontend hotkeys settings page), with the
+/// translation keys of their labels.
+const RECORDING_MODES: &[(&str, &str)] = &[
+    ("push-to-talk", "tray.mode.pushToTalk"),
+    ("toggle", "tray.mode.toggle"),
+    ("hands-free", "tray.mode.handsFree"),
 ];
 
 /// Holds references to tray submenus so we can update check marks later.
@@ -56,6 +70,14 @@ pub struct TrayMenuState {
     model_submenu: Mutex<Option<Submenu<tauri::Wry>>>,
     lang_submenu: Mutex<Option<Submenu<tauri::Wry>>>,
     ai_fn_submenu: Mutex<Option<Submenu<tauri::Wry>>>,
+    /// Model whose languages the language submenu lists.
+    lang_model: Mutex<String>,
+    mode_submenu: Mutex<Option<Submenu<tauri::Wry>>>,
+    recent_submenu: Mutex<Option<Submenu<tauri::Wry>>>,
+    incognito_item: Mutex<Option<CheckMenuItem<tauri::Wry>>>,
+    pause_hotkey_item: Mutex<Option<CheckMenuItem<tauri::Wry>>>,
+    compose_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    discard_compose_item: Mutex<Option<MenuItem<tauri::Wry>>>,
 }
 
 impl TrayMenuState {
@@ -64,18 +86,28 @@ impl TrayMenuState {
             model_submenu: Mutex::new(None),
             lang_submenu: Mutex::new(None),
             ai_fn_submenu: Mutex::new(None),
+            lang_model: Mutex::new(String::new()),
+            mode_submenu: Mutex::new(None),
+            recent_submenu: Mutex::new(None),
+            incognito_item: Mutex::new(None),
+            pause_hotkey_item: Mutex::new(None),
+            compose_item: Mutex::new(None),
+            discard_compose_item: Mutex::new(None),
         }
     }
 }
 
-pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
-    let default_model = "whisper-base";
-    let default_lang = "auto";
-    let default_ai_fn = "none";
+/// Build the tray menu in the current UI language and keep handles to the
+/// items whose state changes later.
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let settings = crate::db::settings::load();
+    let default_model = settings.selected_model.as_str();
+    let default_lang = settings.selected_language.as_str();
+    let default_ai_fn = settings.selected_ai_function.as_deref().unwrap_or("none");
 
     // ── Model submenu ──
     let model_submenu = {
-        let sub = Submenu::with_id(app, "model-menu", "Model", true)?;
+        let sub = Submenu::with_id(app, "model-menu", t("tray.model"), true)?;
         for (id, name) in LOCAL_MODELS {
             sub.append(&CheckMenuItem::with_id(
                 app,
@@ -102,70 +134,137 @@ pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
 
     // ── Language submenu ──
     let lang_submenu = {
-        let sub = Submenu::with_id(app, "lang-menu", "Language", true)?;
-        for (code, name) in LANGUAGES {
-            sub.append(&CheckMenuItem::with_id(
-                app,
-                format!("lang:{}", code),
-                *name,
-                true,
-                *code == default_lang,
-                None::<&str>,
-            )?)?;
-        }
+        let sub = Submenu::with_id(app, "lang-menu", t("tray.language"), true)?;
+        append_language_items(app, &sub, default_model, default_lang)?;
         sub
     };
 
     // ── AI Function submenu ──
     let ai_fn_submenu = {
-        let sub = Submenu::with_id(app, "ai-fn-menu", "AI Function", true)?;
-        for (id, name) in AI_FUNCTIONS {
+        let sub = Submenu::with_id(app, "ai-fn-menu", t("tray.aiFunction"), true)?;
+        append_ai_fn_items(app, &sub, default_ai_fn)?;
+        sub
+    };
+
+    // ── Recording mode submenu ──
+    let mode_submenu = {
+        let sub = Submenu::with_id(app, "mode-menu", t("tray.recordingMode"), true)?;
+        for (id, key) in RECORDING_MODES {
             sub.append(&CheckMenuItem::with_id(
                 app,
-                format!("ai-fn:{}", id),
-                *name,
+                format!("mode:{}", id),
+                t(key),
                 true,
-                *id == default_ai_fn,
+                *id == settings.recording_mode,
                 None::<&str>,
             )?)?;
         }
         sub
     };
 
+    // ── Recent outputs submenu ──
+    let recent_submenu = {
+        let sub = Submenu::with_id(app, "recent-menu", t("tray.recentOutputs"), true)?;
+        append_recent_items(app, &sub)?;
+        sub
+    };
+
+    let incognito_item = CheckMenuItem::with_id(
+        app,
+        "incognito",
+        t("tray.incognito"),
+        true,
+        app.state::<RecordingState>().is_incognito(),
+        None::<&str>,
+    )?;
+
+    let pause_hotkey_item = CheckMenuItem::with_id(
+        app,
+        "pause-hotkey",
+        t("tray.pauseHotkey"),
+        true,
+        *app.state::<HotkeyModeState>().paused.lock().unwrap(),
+        None::<&str>,
+    )?;
+
+    let compose_parts = app.state::<ComposeState>().parts();
+    let compose_item = MenuItem::with_id(
+        app,
+        "compose",
+        compose_label(compose_parts),
+        true,
+        None::<&str>,
+    )?;
+    let discard_compose_item = MenuItem::with_id(
+        app,
+        "discard-compose",
+        t("tray.discardCompose"),
+        compose_parts.is_some(),
+        None::<&str>,
+    )?;
+
     // Store submenu handles so we can update check marks later
     {
         let state = app.state::<TrayMenuState>();
         *state.model_submenu.lock().unwrap() = Some(model_submenu.clone());
         *state.lang_submenu.lock().unwrap() = Some(lang_submenu.clone());
+        *state.lang_model.lock().unwrap() = default_model.to_string();
         *state.ai_fn_submenu.lock().unwrap() = Some(ai_fn_submenu.clone());
+        *state.mode_submenu.lock().unwrap() = Some(mode_submenu.clone());
+        *state.recent_submenu.lock().unwrap() = Some(recent_submenu.clone());
+        *state.incognito_item.lock().unwrap() = Some(incognito_item.clone());
+        *state.pause_hotkey_item.lock().unwrap() = Some(pause_hotkey_item.clone());
+        *state.compose_item.lock().unwrap() = Some(compose_item.clone());
+        *state.discard_compose_item.lock().unwrap() = Some(discard_compose_item.clone());
     }
 
     // ── App controls ──
-    let show_item = MenuItem::with_id(app, "show", "Show SobottaAI", true, None::<&str>)?;
-    let settings_item = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit SobottaAI", true, None::<&str>)?;
+    let show_item = MenuItem::with_id(app, "show", t("tray.show"), true, None::<&str>)?;
+    let transcribe_file_item = MenuItem::with_id(
+        app,
+        "transcribe-file",
+        t("tray.transcribeFile"),
+        true,
+        None::<&str>,
+    )?;
+    let settings_item =
+        MenuItem::with_id(app, "settings", t("tray.settings"), true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", t("tray.quit"), true, None::<&str>)?;
 
     // ── Build the menu ──
-    let menu = Menu::with_items(
+    Menu::with_items(
         app,
         &[
             &model_submenu,
             &lang_submenu,
             &ai_fn_submenu,
             &PredefinedMenuItem::separator(app)?,
+            &mode_submenu,
+            &incognito_item,
+            &pause_hotkey_item,
+            &PredefinedMenuItem::separator(app)?,
+            &transcribe_file_item,
+            &recent_submenu,
+            &compose_item,
+            &discard_compose_item,
+            &PredefinedMenuItem::separator(app)?,
             &show_item,
             &settings_item,
             &PredefinedMenuItem::separator(app)?,
             &quit_item,
         ],
-    )?;
+    )
+}
+
+pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app)?;
 
     let tray_icon = {
         let icon_bytes = include_bytes!("../../icons/tray-icon@2x.png");
         tauri::image::Image::from_bytes(icon_bytes).expect("failed to load tray icon")
     };
 
-    TrayIconBuilder::new()
+    TrayIconBuilder::with_id(TRAY_ID)
         .icon(tray_icon)
         .icon_as_template(true)
         .menu(&menu)
@@ -175,6 +274,8 @@ pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
 
             if let Some(model_id) = id.strip_prefix("model:") {
                 update_submenu_checks(app, "model", model_id);
+                let settings = crate::db::settings::load();
+                refresh_languages(app, model_id, &settings.selected_language);
                 let _ = app.emit("tray-model-changed", model_id.to_string());
                 log::info!("Tray: model → {}", model_id);
                 return;
@@ -194,7 +295,62 @@ pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
                 return;
             }
 
+            if let Some(index) = id.strip_prefix("recent:") {
+                copy_recent_output(app, index);
+                return;
+            }
+
+            if let Some(mode) = id.strip_prefix("mode:") {
+                // Applies immediately and is saved, without needing the main window
+                let _ = crate::commands::settings::update_recording_mode(
+                    app.clone(),
+                    mode.to_string(),
+                );
+                let mut setting = serde_json::Map::new();
+                setting.insert("recordingMode".into(), mode.into());
+                if let Err(e) = crate::db::settings::save_all(&setting) {
+                    log::warn!("Failed to save recording mode: {}", e);
+                }
+                let _ = app.emit("tray-recording-mode-changed", mode.to_string());
+                log::info!("Tray: recording mode → {}", mode);
+                return;
+            }
+
             match id {
+                "incognito" => {
+                    let recording_state = app.state::<RecordingState>();
+                    let enabled = !recording_state.is_incognito();
+                    recording_state.set_incognito(enabled);
+                    set_incognito_checked(app, enabled);
+                    let _ = app.emit("tray-incognito-changed", enabled);
+                    log::info!("Tray: incognito → {}", enabled);
+                }
+                "pause-hotkey" => {
+                    let state = app.state::<HotkeyModeState>();
+                    let paused = !*state.paused.lock().unwrap();
+                    if let Err(e) = hotkey::set_paused(app, paused) {
+                        log::warn!("Failed to pause hotkey: {}", e);
+                    }
+                    set_hotkey_paused_checked(app, *state.paused.lock().unwrap());
+                    log::info!("Tray: pause hotkey → {}", paused);
+                }
+                "transcribe-file" => transcribe_file(app),
+                "compose" => {
+                    // Finishing needs the frontend's AI function and paste settings
+                    if app.state::<ComposeState>().parts().is_some() {
+                        let _ = app.emit("compose-finish-requested", ());
+                    } else {
+                        crate::commands::compose::start_compose(app.clone());
+                    }
+                }
+                "discard-compose" => {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = crate::commands::compose::discard_compose(app).await {
+                            log::warn!("Failed to discard compose session: {}", e);
+                        }
+                    });
+                }
                 "show" => {
                     show_main_window(app);
                 }
@@ -223,13 +379,161 @@ pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
     Ok(())
 }
 
-/// Update check marks in a submenu group. `group` is "model", "lang", or "ai-fn".
+/// Fill the AI function submenu: "None", then builtin and custom functions.
+fn append_ai_fn_items(
+    app: &AppHandle,
+    sub: &Submenu<tauri::Wry>,
+    selected: &str,
+) -> tauri::Result<()> {
+    let mut functions = crate::commands::ai_functions::list_ai_functions().unwrap_or_default();
+    // Most used first; unused ones keep their built-in-then-custom order
+    functions.sort_by_key(|f| std::cmp::Reverse(f.use_count));
+    let items = std::iter::once(("none".to_string(), t("tray.aiFunctionNone")))
+        .chain(functions.into_iter().map(|f| (f.id, f.name)));
+    for (id, name) in items {
+        sub.append(&CheckMenuItem::with_id(
+            app,
+            format!("ai-fn:{}", id),
+            name,
+            true,
+            id == selected,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(())
+}
+
+/// Fill the language submenu with the languages `model_id` can transcribe,
+/// or all of them for an unknown model.
+fn append_language_items(
+    app: &AppHandle,
+    sub: &Submenu<tauri::Wry>,
+    model_id: &str,
+    selected: &str,
+) -> tauri::Result<()> {
+    let model = crate::models::find(model_id);
+    let supported = LANGUAGES
+        .iter()
+        .filter(|(code, _)| model.as_ref().map_or(true, |m| m.supports_language(code)));
+    for (code, name) in supported {
+        let name = match *code {
+            "auto" => t("tray.languageAuto"),
+            _ => name.to_string(),
+        };
+        sub.append(&CheckMenuItem::with_id(
+            app,
+            format!("lang:{}", code),
+            name,
+            true,
+            *code == selected,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(())
+}
+
+/// Rebuild the language submenu when the model changes, so an English-only
+/// model only offers English; otherwise just move the check mark.
+fn refresh_languages(app: &AppHandle, model_id: &str, selected: &str) {
+    let state = app.state::<TrayMenuState>();
+    let mut lang_model = state.lang_model.lock().unwrap();
+    if *lang_model == model_id {
+        drop(lang_model);
+        update_submenu_checks(app, "lang", selected);
+        return;
+    }
+    let guard = state.lang_submenu.lock().unwrap();
+    let Some(sub) = guard.as_ref() else {
+        return;
+    };
+    let result =
+        clear_submenu(sub).and_then(|()| append_language_items(app, sub, model_id, selected));
+    match result {
+        Ok(()) => *lang_model = model_id.to_string(),
+        Err(e) => log::warn!("Failed to refresh tray languages: {}", e),
+    }
+}
+
+/// Fill the recent outputs submenu, newest first, or say there are none.
+fn append_recent_items(app: &AppHandle, sub: &Submenu<tauri::Wry>) -> tauri::Result<()> {
+    let outputs = app.state::<RecentOutputs>().list();
+    if outputs.is_empty() {
+        let empty = t("tray.recentOutputsEmpty");
+        sub.append(&MenuItem::with_id(app, "recent-empty", empty, false, None::<&str>)?)?;
+    }
+    for (index, output) in outputs.iter().enumerate() {
+        let label = crate::system::notifications::preview(&output.text, RECENT_OUTPUT_CHARS)
+            .unwrap_or_default();
+        sub.append(&MenuItem::with_id(
+            app,
+            format!("recent:{}", index),
+            label,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(())
+}
+
+/// Rebuild the recent outputs submenu after something was pasted.
+pub fn refresh_recent_outputs(app: &AppHandle) {
+    let state = app.state::<TrayMenuState>();
+    let guard = state.recent_submenu.lock().unwrap();
+    let Some(sub) = guard.as_ref() else {
+        return;
+    };
+    let result = clear_submenu(sub).and_then(|()| append_recent_items(app, sub));
+    if let Err(e) = result {
+        log::warn!("Failed to refresh tray recent outputs: {}", e);
+    }
+}
+
+/// Put a recent output back on the clipboard.
+fn copy_recent_output(app: &AppHandle, index: &str) {
+    let text = index
+        .parse()
+        .ok()
+        .and_then(|index| app.state::<RecentOutputs>().get(index));
+    let Some(text) = text else {
+        return;
+    };
+    match app.clipboard().write_text(text) {
+        Ok(()) => log::info!("Tray: copied recent output {}", index),
+        Err(e) => log::warn!("Failed to copy recent output: {}", e),
+    }
+}
+
+/// Rebuild the AI function submenu after custom functions were saved or
+/// deleted, keeping the saved selection checked.
+pub fn refresh_ai_functions(app: &AppHandle) {
+    let state = app.state::<TrayMenuState>();
+    let guard = state.ai_fn_submenu.lock().unwrap();
+    let Some(sub) = guard.as_ref() else {
+        return;
+    };
+
+    let settings = crate::db::settings::load();
+    let selected = settings.selected_ai_function.as_deref().unwrap_or("none");
+    let result = clear_submenu(sub).and_then(|()| append_ai_fn_items(app, sub, selected));
+    if let Err(e) = result {
+        log::warn!("Failed to refresh tray AI functions: {}", e);
+    }
+}
+
+fn clear_submenu(sub: &Submenu<tauri::Wry>) -> tauri::Result<()> {
+    while sub.remove_at(0)?.is_some() {}
+    Ok(())
+}
+
+/// Update check marks in a submenu group. `group` is "model", "lang", "ai-fn"
+/// or "mode".
 fn update_submenu_checks(app: &AppHandle, group: &str, selected: &str) {
     let state = app.state::<TrayMenuState>();
     let submenu_lock = match group {
         "model" => &state.model_submenu,
         "lang" => &state.lang_submenu,
         "ai-fn" => &state.ai_fn_submenu,
+        "mode" => &state.mode_submenu,
         _ => return,
     };
 
@@ -255,12 +559,96 @@ fn update_submenu_checks(app: &AppHandle, group: &str, selected: &str) {
 /// Update tray check marks from the frontend (called when settings are loaded/changed).
 pub fn update_tray_selection(app: &AppHandle, model: &str, language: &str, ai_function: Option<&str>) {
     update_submenu_checks(app, "model", model);
-    update_submenu_checks(app, "lang", language);
+    refresh_languages(app, model, language);
     update_submenu_checks(app, "ai-fn", ai_function.unwrap_or("none"));
 }
 
+/// Update the recording mode check marks.
+pub fn set_recording_mode_checked(app: &AppHandle, mode: &str) {
+    update_submenu_checks(app, "mode", mode);
+}
+
+/// Update the incognito check mark.
+pub fn set_incognito_checked(app: &AppHandle, enabled: bool) {
+    let state = app.state::<TrayMenuState>();
+    let guard = state.incognito_item.lock().unwrap();
+    if let Some(item) = guard.as_ref() {
+        let _ = item.set_checked(enabled);
+    }
+}
+
+/// Update the pause hotkey check mark.
+pub fn set_hotkey_paused_checked(app: &AppHandle, paused: bool) {
+    let state = app.state::<TrayMenuState>();
+    let guard = state.pause_hotkey_item.lock().unwrap();
+    if let Some(item) = guard.as_ref() {
+        let _ = item.set_checked(paused);
+    }
+}
+
+/// Reflect the compose session in the tray: the menu items and, where the
+/// platform shows one, a part count next to the icon. `None` when not composing.
+pub fn set_compose_status(app: &AppHandle, parts: Option<usize>) {
+    let state = app.state::<TrayMenuState>();
+    if let Some(item) = state.compose_item.lock().unwrap().as_ref() {
+        let _ = item.set_text(compose_label(parts));
+    }
+    if let Some(item) = state.discard_compose_item.lock().unwrap().as_ref() {
+        let _ = item.set_enabled(parts.is_some());
+    }
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_title(parts.map(|n| format!("✎ {}", n)));
+    }
+}
+
+fn compose_label(parts: Option<usize>) -> String {
+    match parts {
+        Some(n) => t_with("tray.finishCompose", &[("count", &n.to_string())]),
+        None => t("tray.startCompose"),
+    }
+}
+
+/// Switch the tray to the current UI language by rebuilding its menu.
+pub fn refresh_language(app: &AppHandle) {
+    let result = build_menu(app).and_then(|menu| match app.tray_by_id(TRAY_ID) {
+        Some(tray) => tray.set_menu(Some(menu)),
+        None => Ok(()),
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to rebuild tray menu: {}", e);
+    }
+}
+
+/// Pick an audio file, transcribe it in the background and copy the text,
+/// reporting the outcome with a notification.
+fn transcribe_file(app: &AppHandle) {
+    let app = app.clone();
+    app.dialog()
+        .file()
+        .add_filter(t("tray.wavFilter"), &["wav"])
+        .pick_file(move |file| {
+            let Some(path) = file.and_then(|f| f.into_path().ok()) else {
+                return;
+            };
+            tauri::async_runtime::spawn(async move {
+                log::info!("Tray: transcribing {:?}", path);
+                let (title, body) =
+                    match crate::commands::audio_import::transcribe_to_clipboard(&app, &path)
+                        .await
+                    {
+                        Ok(text) => (t("notification.transcriptionCopied"), text),
+                        Err(e) => {
+                            log::error!("Tray file transcription failed: {}", e);
+                            (t("notification.transcriptionFailed"), e)
+                        }
+                    };
+                let _ = app.notification().builder().title(title).body(body).show();
+            });
+        });
+}
+
 /// Show the main window and restore dock icon on macOS.
-fn show_main_window(app: &AppHandle) {
+pub fn show_main_window(app: &AppHandle) {
     // macOS: restore dock icon before showing the window
     #[cfg(target_os = "macos")]
     let _ = app.set_activation_policy(tauri::ActivationPolicy::Regular);
@@ -1,5 +1,8 @@
 import { describe, it, expect, beforeEach } from "vitest";
-import { useSettingsStore } from "@/stores/settings-store";
+import {
+  useSettingsStore,
+  ollamaOptionsFor,
+} from "@/stores/settings-store";
 
 // Reset the zustand store between tests
 beforeEach(() => {
@@ -7,6 +10,7 @@ beforeEach(() => {
     selectedModel: "whisper-base",
     selectedLanguage: "auto",
     selectedAiFunction: null,
+    pipelineId: null,
     recordingMode: "push-to-talk",
     rules: [
       { id: "remove-fillers", name: "Remove Filler Words", enabled: false },
@@ -23,6 +27,58 @@ beforeEach(() => {
       groq: { apiKey: "", model: "llama-3.3-70b-versatile" },
       ollama: { apiKey: "", model: "llama3.2", baseUrl: "http://localhost:11434" },
     },
+    llmFallbackProviders: [],
+    keepRulesOnlyText: false,
+    pasteSource: "processed",
+    pasteFormat: "markdown",
+    pasteMode: "auto",
+    pasteDelayMs: 0,
+    lockPasteTarget: false,
+    secureFieldPaste: "block",
+    incognito: false,
+    keepOriginalAudio: false,
+    automationUrlsEnabled: false,
+    notifyOnTranscription: true,
+    preferBuiltInMic: false,
+    recordingBarPosition: "bottom-center",
+    recordingBarMonitor: "primary",
+    spectrumVisualizer: false,
+    systemPromptPrefix: "",
+    aiContextSource: "none",
+    aiCacheTtlHours: 24,
+    rewriteHotkey: null,
+    rewriteAiFunction: "grammar",
+    pasteLastHotkey: null,
+    undoPasteHotkey: null,
+    hotkeyBackend: "auto",
+    mouseButton: "none",
+    doublePressAi: false,
+    minRecordingMs: 300,
+    handsFreeSilenceSecs: 2,
+    liveTranscript: false,
+    initialPrompt: "",
+    promptTokenBudget: 200,
+    codeSwitching: false,
+    codeSwitchLanguages: [],
+    vadEnabled: false,
+    punctuationEnabled: false,
+    cloudSttTimeoutSecs: 120,
+    cloudSttMaxRetries: 2,
+    openaiSttBaseUrl: "",
+    openaiSttModel: "",
+    openaiSttApiKey: "",
+    uiLanguage: "auto",
+    decodingOptions: {
+      beamSize: 5,
+      temperature: 0,
+      temperatureInc: 0.2,
+      noSpeechThold: 0.6,
+      entropyThold: 2.4,
+    },
+    inferenceThreads: 0,
+    lowPowerMode: false,
+    maxInferenceSecs: 300,
+    parakeetProvider: "auto",
     onboardingComplete: false,
     _hydrated: false,
   });
@@ -106,11 +162,23 @@ describe("settings-store", () => {
       expect(useSettingsStore.getState().selectedAiFunction).toBeNull();
     });
 
+    it("setPipelineId selects a pipeline", () => {
+      useSettingsStore.getState().setPipelineId("p1");
+      expect(useSettingsStore.getState().pipelineId).toBe("p1");
+      useSettingsStore.getState().setPipelineId(null);
+      expect(useSettingsStore.getState().pipelineId).toBeNull();
+    });
+
     it("setRecordingMode updates mode", () => {
       useSettingsStore.getState().setRecordingMode("toggle");
       expect(useSettingsStore.getState().recordingMode).toBe("toggle");
     });
 
+    it("setRecordingMode accepts hands-free", () => {
+      useSettingsStore.getState().setRecordingMode("hands-free");
+      expect(useSettingsStore.getState().recordingMode).toBe("hands-free");
+    });
+
     it("setDefaultHotkey updates hotkey", () => {
       useSettingsStore.getState().setDefaultHotkey("CommandOrControl+Shift+Space");
       expect(useSettingsStore.getState().defaultHotkey).toBe("CommandOrControl+Shift+Space");
@@ -135,6 +203,236 @@ describe("settings-store", () => {
       useSettingsStore.getState().setOnboardingComplete(true);
       expect(useSettingsStore.getState().onboardingComplete).toBe(true);
     });
+
+    it("setPasteSource updates paste source", () => {
+      useSettingsStore.getState().setPasteSource("verbatim");
+      expect(useSettingsStore.getState().pasteSource).toBe("verbatim");
+    });
+
+    it("setPasteFormat updates paste format", () => {
+      useSettingsStore.getState().setPasteFormat("rich");
+      expect(useSettingsStore.getState().pasteFormat).toBe("rich");
+    });
+
+    it("setPasteMode updates paste mode", () => {
+      useSettingsStore.getState().setPasteMode("confirm");
+      expect(useSettingsStore.getState().pasteMode).toBe("confirm");
+    });
+
+    it("setPasteDelayMs updates paste delay", () => {
+      useSettingsStore.getState().setPasteDelayMs(250);
+      expect(useSettingsStore.getState().pasteDelayMs).toBe(250);
+    });
+
+    it("setLockPasteTarget toggles the target lock", () => {
+      useSettingsStore.getState().setLockPasteTarget(true);
+      expect(useSettingsStore.getState().lockPasteTarget).toBe(true);
+    });
+
+    it("setSecureFieldPaste updates password field behavior", () => {
+      useSettingsStore.getState().setSecureFieldPaste("warn");
+      expect(useSettingsStore.getState().secureFieldPaste).toBe("warn");
+    });
+
+    it("setSystemPromptPrefix updates prefix", () => {
+      useSettingsStore.getState().setSystemPromptPrefix("I am Ada.");
+      expect(useSettingsStore.getState().systemPromptPrefix).toBe("I am Ada.");
+    });
+
+    it("setAiContextSource updates source", () => {
+      useSettingsStore.getState().setAiContextSource("selection");
+      expect(useSettingsStore.getState().aiContextSource).toBe("selection");
+    });
+
+    it("setAiCacheTtlHours updates the cache lifetime", () => {
+      useSettingsStore.getState().setAiCacheTtlHours(0);
+      expect(useSettingsStore.getState().aiCacheTtlHours).toBe(0);
+    });
+
+    it("setRewriteHotkey sets and clears the hotkey", () => {
+      useSettingsStore.getState().setRewriteHotkey("CommandOrControl+Shift+KeyR");
+      expect(useSettingsStore.getState().rewriteHotkey).toBe("CommandOrControl+Shift+KeyR");
+      useSettingsStore.getState().setRewriteHotkey(null);
+      expect(useSettingsStore.getState().rewriteHotkey).toBeNull();
+    });
+
+    it("setPasteLastHotkey sets and clears the hotkey", () => {
+      useSettingsStore.getState().setPasteLastHotkey("CommandOrControl+Shift+KeyV");
+      expect(useSettingsStore.getState().pasteLastHotkey).toBe("CommandOrControl+Shift+KeyV");
+      useSettingsStore.getState().setPasteLastHotkey(null);
+      expect(useSettingsStore.getState().pasteLastHotkey).toBeNull();
+    });
+
+    it("setUndoPasteHotkey sets and clears the hotkey", () => {
+      useSettingsStore.getState().setUndoPasteHotkey("CommandOrControl+Shift+KeyZ");
+      expect(useSettingsStore.getState().undoPasteHotkey).toBe("CommandOrControl+Shift+KeyZ");
+      useSettingsStore.getState().setUndoPasteHotkey(null);
+      expect(useSettingsStore.getState().undoPasteHotkey).toBeNull();
+    });
+
+    it("setRewriteAiFunction updates function", () => {
+      useSettingsStore.getState().setRewriteAiFunction("casual");
+      expect(useSettingsStore.getState().rewriteAiFunction).toBe("casual");
+    });
+
+    it("setHotkeyBackend updates backend", () => {
+      useSettingsStore.getState().setHotkeyBackend("portal");
+      expect(useSettingsStore.getState().hotkeyBackend).toBe("portal");
+    });
+
+    it("setMouseButton updates mouse button", () => {
+      useSettingsStore.getState().setMouseButton("back");
+      expect(useSettingsStore.getState().mouseButton).toBe("back");
+    });
+
+    it("setDoublePressAi toggles double-press AI", () => {
+      useSettingsStore.getState().setDoublePressAi(true);
+      expect(useSettingsStore.getState().doublePressAi).toBe(true);
+    });
+
+    it("setMinRecordingMs updates value", () => {
+      useSettingsStore.getState().setMinRecordingMs(0);
+      expect(useSettingsStore.getState().minRecordingMs).toBe(0);
+    });
+
+    it("setHandsFreeSilenceSecs updates value", () => {
+      useSettingsStore.getState().setHandsFreeSilenceSecs(3);
+      expect(useSettingsStore.getState().handsFreeSilenceSecs).toBe(3);
+    });
+
+    it("setLiveTranscript updates value", () => {
+      useSettingsStore.getState().setLiveTranscript(true);
+      expect(useSettingsStore.getState().liveTranscript).toBe(true);
+    });
+
+    it("setDecodingOptions merges with existing values", () => {
+      useSettingsStore.getState().setDecodingOptions({ beamSize: 1 });
+      const options = useSettingsStore.getState().decodingOptions;
+      expect(options.beamSize).toBe(1);
+      expect(options.entropyThold).toBe(2.4);
+    });
+
+    it("setInferenceThreads updates thread count", () => {
+      useSettingsStore.getState().setInferenceThreads(4);
+      expect(useSettingsStore.getState().inferenceThreads).toBe(4);
+    });
+
+    it("setLowPowerMode toggles low-power mode", () => {
+      useSettingsStore.getState().setLowPowerMode(true);
+      expect(useSettingsStore.getState().lowPowerMode).toBe(true);
+    });
+
+    it("setMaxInferenceSecs updates the time limit", () => {
+      useSettingsStore.getState().setMaxInferenceSecs(0);
+      expect(useSettingsStore.getState().maxInferenceSecs).toBe(0);
+    });
+
+    it("setParakeetProvider updates provider", () => {
+      useSettingsStore.getState().setParakeetProvider("cpu");
+      expect(useSettingsStore.getState().parakeetProvider).toBe("cpu");
+    });
+
+    it("setInitialPrompt updates value", () => {
+      useSettingsStore.getState().setInitialPrompt("A cardiology dictation.");
+      expect(useSettingsStore.getState().initialPrompt).toBe("A cardiology dictation.");
+    });
+
+    it("setPromptTokenBudget updates value", () => {
+      useSettingsStore.getState().setPromptTokenBudget(120);
+      expect(useSettingsStore.getState().promptTokenBudget).toBe(120);
+    });
+
+    it("setCodeSwitching updates value", () => {
+      useSettingsStore.getState().setCodeSwitching(true);
+      expect(useSettingsStore.getState().codeSwitching).toBe(true);
+    });
+
+    it("setCodeSwitchLanguages updates value", () => {
+      useSettingsStore.getState().setCodeSwitchLanguages(["de", "en"]);
+      expect(useSettingsStore.getState().codeSwitchLanguages).toEqual(["de", "en"]);
+    });
+
+    it("setVadEnabled updates value", () => {
+      useSettingsStore.getState().setVadEnabled(true);
+      expect(useSettingsStore.getState().vadEnabled).toBe(true);
+    });
+
+    it("setPunctuationEnabled updates value", () => {
+      useSettingsStore.getState().setPunctuationEnabled(true);
+      expect(useSettingsStore.getState().punctuationEnabled).toBe(true);
+    });
+
+    it("setCloudSttTimeoutSecs updates value", () => {
+      useSettingsStore.getState().setCloudSttTimeoutSecs(300);
+      expect(useSettingsStore.getState().cloudSttTimeoutSecs).toBe(300);
+    });
+
+    it("setCloudSttMaxRetries updates value", () => {
+      useSettingsStore.getState().setCloudSttMaxRetries(0);
+      expect(useSettingsStore.getState().cloudSttMaxRetries).toBe(0);
+    });
+
+    it("setOpenaiSttBaseUrl updates value", () => {
+      useSettingsStore.getState().setOpenaiSttBaseUrl("http://localhost:8000/v1");
+      expect(useSettingsStore.getState().openaiSttBaseUrl).toBe("http://localhost:8000/v1");
+    });
+
+    it("setOpenaiSttModel updates value", () => {
+      useSettingsStore.getState().setOpenaiSttModel("Systran/faster-whisper-small");
+      expect(useSettingsStore.getState().openaiSttModel).toBe("Systran/faster-whisper-small");
+    });
+
+    it("setOpenaiSttApiKey updates value", () => {
+      useSettingsStore.getState().setOpenaiSttApiKey("server-key");
+      expect(useSettingsStore.getState().openaiSttApiKey).toBe("server-key");
+    });
+
+    it("setUiLanguage updates value", () => {
+      useSettingsStore.getState().setUiLanguage("de");
+      expect(useSettingsStore.getState().uiLanguage).toBe("de");
+    });
+
+    it("setIncognito updates value", () => {
+      useSettingsStore.getState().setIncognito(true);
+      expect(useSettingsStore.getState().incognito).toBe(true);
+    });
+
+    it("setKeepOriginalAudio updates value", () => {
+      useSettingsStore.getState().setKeepOriginalAudio(true);
+      expect(useSettingsStore.getState().keepOriginalAudio).toBe(true);
+    });
+
+    it("setAutomationUrlsEnabled updates value", () => {
+      useSettingsStore.getState().setAutomationUrlsEnabled(true);
+      expect(useSettingsStore.getState().automationUrlsEnabled).toBe(true);
+    });
+
+    it("setNotifyOnTranscription updates value", () => {
+      useSettingsStore.getState().setNotifyOnTranscription(false);
+      expect(useSettingsStore.getState().notifyOnTranscription).toBe(false);
+    });
+
+    it("setPreferBuiltInMic updates value", () => {
+      useSettingsStore.getState().setPreferBuiltInMic(true);
+      expect(useSettingsStore.getState().preferBuiltInMic).toBe(true);
+    });
+
+    it("setRecordingBarPosition and setRecordingBarMonitor update values", () => {
+      useSettingsStore.getState().setRecordingBarPosition("top-right");
+      useSettingsStore.getState().setRecordingBarMonitor("cursor");
+      expect(useSettingsStore.getState().recordingBarPosition).toBe("top-right");
+      expect(useSettingsStore.getState().recordingBarMonitor).toBe("cursor");
+    });
+
+    it("setSpectrumVisualizer updates value", () => {
+      useSettingsStore.getState().setSpectrumVisualizer(true);
+      expect(useSettingsStore.getState().spectrumVisualizer).toBe(true);
+    });
+
+    it("setKeepRulesOnlyText updates value", () => {
+      useSettingsStore.getState().setKeepRulesOnlyText(true);
+      expect(useSettingsStore.getState().keepRulesOnlyText).toBe(true);
+    });
   });
 
   // ── Rules ─────────────────────────────────────────────────
@@ -161,6 +459,14 @@ describe("settings-store", () => {
       expect(rules.find((r) => r.id === "fix-grammar")?.enabled).toBe(false);
     });
 
+    it("moveRule reorders rules and stops at the ends", () => {
+      useSettingsStore.getState().moveRule("remove-fillers", 1);
+      expect(useSettingsStore.getState().rules[1].id).toBe("remove-fillers");
+      const before = useSettingsStore.getState().rules;
+      useSettingsStore.getState().moveRule(before[before.length - 1].id, 1);
+      expect(useSettingsStore.getState().rules).toEqual(before);
+    });
+
     it("toggleRule with unknown id is safe (no-op)", () => {
       const before = useSettingsStore.getState().rules;
       useSettingsStore.getState().toggleRule("nonexistent");
@@ -198,6 +504,29 @@ describe("settings-store", () => {
       expect(config.baseUrl).toBe("http://custom:8080");
     });
 
+    it("setProviderConfig updates ollama tuning", () => {
+      useSettingsStore.getState().setProviderConfig("ollama", {
+        numCtx: 8192,
+        keepAlive: "30m",
+      });
+      const config = useSettingsStore.getState().providerConfigs["ollama"];
+      expect(config.numCtx).toBe(8192);
+      expect(config.keepAlive).toBe("30m");
+    });
+
+    it("ollamaOptionsFor maps ollama config and skips other providers", () => {
+      const configs = useSettingsStore.getState().providerConfigs;
+      expect(ollamaOptionsFor("openai", configs["openai"])).toBeNull();
+      expect(
+        ollamaOptionsFor("ollama", { ...configs["ollama"], temperature: 0.2, keepAlive: " " }),
+      ).toEqual({ numCtx: null, temperature: 0.2, keepAlive: null });
+    });
+
+    it("setLlmFallbackProviders stores order", () => {
+      useSettingsStore.getState().setLlmFallbackProviders(["groq", "openai"]);
+      expect(useSettingsStore.getState().llmFallbackProviders).toEqual(["groq", "openai"]);
+    });
+
     it("setProviderConfig for different providers is independent", () => {
       useSettingsStore.getState().setProviderConfig("openai", { apiKey: "key-openai" });
       useSettingsStore.getState().setProviderConfig("anthropic", { apiKey: "key-anthropic" });
@@ -14,6 +14,31 @@ beforeEach(async () => {
 });
 
 describe("tauri-commands", () => {
+  // ── Errors ────────────────────────────────────────────────
+
+  describe("command errors", () => {
+    it("recognizes structured errors", () => {
+      const err = { code: "modelNotDownloaded", message: "Model 'base' is not downloaded" };
+      expect(cmds.isCommandError(err)).toBe(true);
+      expect(cmds.isCommandError("Session not found")).toBe(false);
+      expect(cmds.isCommandError(null)).toBe(false);
+    });
+
+    it("errorMessage reads structured, Error and string rejections", () => {
+      expect(cmds.errorMessage({ code: "noAudio", message: "No audio data captured" })).toBe(
+        "No audio data captured",
+      );
+      expect(cmds.errorMessage(new Error("boom"))).toBe("boom");
+      expect(cmds.errorMessage("plain")).toBe("plain");
+    });
+
+    it("startRecording rejects with the backend error", async () => {
+      const err = { code: "micPermissionDenied", message: "Microphone access denied" };
+      mockInvoke.mockRejectedValue(err);
+      await expect(cmds.startRecording()).rejects.toEqual(err);
+    });
+  });
+
   // ── Recording ─────────────────────────────────────────────
 
   describe("recording commands", () => {
@@ -33,6 +58,36 @@ describe("tauri-commands", () => {
       expect(result.sessionId).toBe("s1");
     });
 
+    it("releaseSession sends session id", async () => {
+      mockInvoke.mockResolvedValue(true);
+      const result = await cmds.releaseSession("s1");
+      expect(mockInvoke).toHaveBeenCalledWith("release_session", { sessionId: "s1" });
+      expect(result).toBe(true);
+    });
+
+    it("recoverSessions returns recovered sessions", async () => {
+      const sessions = [{ sessionId: "s1", durationMs: 1200 }];
+      mockInvoke.mockResolvedValue(sessions);
+      const result = await cmds.recoverSessions();
+      expect(mockInvoke).toHaveBeenCalledWith("recover_sessions", undefined);
+      expect(result).toEqual(sessions);
+    });
+
+    it("discardSession sends session id", async () => {
+      await cmds.discardSession("s1");
+      expect(mockInvoke).toHaveBeenCalledWith("discard_session", { sessionId: "s1" });
+    });
+
+    it("testMicrophone sends duration and level streaming flag", async () => {
+      mockInvoke.mockResolvedValue({ verdict: "ok", rms: 0.05, peak: 0.4 });
+      const result = await cmds.testMicrophone(true, 2000);
+      expect(mockInvoke).toHaveBeenCalledWith("test_microphone", {
+        durationMs: 2000,
+        emitLevels: true,
+      });
+      expect(result.verdict).toBe("ok");
+    });
+
     it("showRecordingBar calls correct command", async () => {
       await cmds.showRecordingBar();
       expect(mockInvoke).toHaveBeenCalledWith("show_recording_bar", undefined);
@@ -42,34 +97,41 @@ describe("tauri-commands", () => {
       await cmds.hideRecordingBar();
       expect(mockInvoke).toHaveBeenCalledWith("hide_recording_bar", undefined);
     });
+
+    it("listMonitors returns connected displays", async () => {
+      mockInvoke.mockResolvedValue([
+        { name: "Built-in", width: 2880, height: 1800, primary: true },
+      ]);
+      const result = await cmds.listMonitors();
+      expect(mockInvoke).toHaveBeenCalledWith("list_monitors", undefined);
+      expect(result[0].primary).toBe(true);
+    });
+
+    it("setRecordingBarPosition sends position and monitor", async () => {
+      await cmds.setRecordingBarPosition("top-right", "cursor");
+      expect(mockInvoke).toHaveBeenCalledWith("set_recording_bar_position", {
+        position: "top-right",
+        monitor: "cursor",
+      });
+    });
   });
 
   // ── Transcription ─────────────────────────────────────────
 
   describe("transcription commands", () => {
-    it("transcribe sends correct params", async () => {
-      mockInvoke.mockResolvedValue({
-        text: "Hello",
-        language: "en",
-        segments: [],
-        durationMs: 1000,
-      });
-      const result = await cmds.transcribe("session-1", "whisper-base", "en");
+    it("transcribe sends correct params and returns a job id", async () => {
+      mockInvoke.mockResolvedValue("job-1");
+      const jobId = await cmds.transcribe("session-1", "whisper-base", "en");
       expect(mockInvoke).toHaveBeenCalledWith("transcribe", {
         sessionId: "session-1",
         modelId: "whisper-base",
         language: "en",
       });
-      expect(result.text).toBe("Hello");
+      expect(jobId).toBe("job-1");
     });
 
     it("transcribe works without language param", async () => {
-      mockInvoke.mockResolvedValue({
-        text: "Hi",
-        language: null,
-        segments: [],
-        durationMs: 500,
-      });
+      mockInvoke.mockResolvedValue("job-2");
       await cmds.transcribe("s1", "whisper-tiny");
       expect(mockInvoke).toHaveBeenCalledWith("transcribe", {
         sessionId: "s1",
@@ -78,13 +140,19 @@ describe("tauri-commands", () => {
       });
     });
 
-    it("transcribeFile sends correct params", async () => {
-      mockInvoke.mockResolvedValue({
-        text: "File text",
+    it("transcribe passes an initial prompt override", async () => {
+      mockInvoke.mockResolvedValue("job-4");
+      await cmds.transcribe("s1", "whisper-base", "en", "A cardiology dictation.");
+      expect(mockInvoke).toHaveBeenCalledWith("transcribe", {
+        sessionId: "s1",
+        modelId: "whisper-base",
         language: "en",
-        segments: [],
-        durationMs: 2000,
+        initialPrompt: "A cardiology dictation.",
       });
+    });
+
+    it("transcribeFile sends correct params", async () => {
+      mockInvoke.mockResolvedValue("job-3");
       await cmds.transcribeFile("/path/to/file.wav", "whisper-base", "en");
       expect(mockInvoke).toHaveBeenCalledWith("transcribe_file", {
         path: "/path/to/file.wav",
@@ -94,6 +162,24 @@ describe("tauri-commands", () => {
     });
   });
 
+  // ── Jobs ──────────────────────────────────────────────────
+
+  describe("job commands", () => {
+    it("getJobStatus sends job id", async () => {
+      mockInvoke.mockResolvedValue({ id: "job-1", status: "running" });
+      const job = await cmds.getJobStatus("job-1");
+      expect(mockInvoke).toHaveBeenCalledWith("get_job_status", { jobId: "job-1" });
+      expect(job?.status).toBe("running");
+    });
+
+    it("cancelJob sends job id", async () => {
+      mockInvoke.mockResolvedValue(true);
+      const cancelled = await cmds.cancelJob("job-1");
+      expect(mockInvoke).toHaveBeenCalledWith("cancel_job", { jobId: "job-1" });
+      expect(cancelled).toBe(true);
+    });
+  });
+
   // ── Clipboard ─────────────────────────────────────────────
 
   describe("clipboard commands", () => {
@@ -103,6 +189,40 @@ describe("tauri-commands", () => {
         text: "Hello world",
       });
     });
+
+    it("pasteText sends an explicit format", async () => {
+      await cmds.pasteText("**Hi**", "rich");
+      expect(mockInvoke).toHaveBeenCalledWith("paste_text", {
+        text: "**Hi**",
+        format: "rich",
+      });
+    });
+
+    it("pasteText rejects for a password field", async () => {
+      const err = {
+        code: "secureField",
+        message: "Not pasted: the focused field is a password field",
+      };
+      mockInvoke.mockRejectedValue(err);
+      await expect(cmds.pasteText("hunter2")).rejects.toEqual(err);
+    });
+
+    it("getRecentOutputs returns the newest first", async () => {
+      const outputs = [
+        { text: "Second", pastedAt: "2026-01-01T10:01:00Z" },
+        { text: "First", pastedAt: "2026-01-01T10:00:00Z" },
+      ];
+      mockInvoke.mockResolvedValue(outputs);
+      const result = await cmds.getRecentOutputs();
+      expect(mockInvoke).toHaveBeenCalledWith("get_recent_outputs", undefined);
+      expect(result[0].text).toBe("Second");
+    });
+
+    it("undoLastPaste calls undo_last_paste", async () => {
+      mockInvoke.mockResolvedValue(undefined);
+      await cmds.undoLastPaste();
+      expect(mockInvoke).toHaveBeenCalledWith("undo_last_paste", undefined);
+    });
   });
 
   // ── Models ────────────────────────────────────────────────
@@ -124,12 +244,67 @@ describe("tauri-commands", () => {
       });
     });
 
+    it("isVadModelDownloaded calls correct command", async () => {
+      mockInvoke.mockResolvedValue(true);
+      const downloaded = await cmds.isVadModelDownloaded();
+      expect(mockInvoke).toHaveBeenCalledWith("is_vad_model_downloaded", undefined);
+      expect(downloaded).toBe(true);
+    });
+
+    it("downloadVadModel calls correct command", async () => {
+      await cmds.downloadVadModel();
+      expect(mockInvoke).toHaveBeenCalledWith("download_vad_model", undefined);
+    });
+
+    it("isPunctuationModelDownloaded calls correct command", async () => {
+      mockInvoke.mockResolvedValue(false);
+      const downloaded = await cmds.isPunctuationModelDownloaded();
+      expect(mockInvoke).toHaveBeenCalledWith("is_punctuation_model_downloaded", undefined);
+      expect(downloaded).toBe(false);
+    });
+
+    it("downloadPunctuationModel calls correct command", async () => {
+      await cmds.downloadPunctuationModel();
+      expect(mockInvoke).toHaveBeenCalledWith("download_punctuation_model", undefined);
+    });
+
+    it("parakeetProviders returns available providers", async () => {
+      mockInvoke.mockResolvedValue(["cpu", "coreml"]);
+      const providers = await cmds.parakeetProviders();
+      expect(mockInvoke).toHaveBeenCalledWith("parakeet_providers", undefined);
+      expect(providers).toEqual(["cpu", "coreml"]);
+    });
+
     it("deleteModel sends model ID", async () => {
       await cmds.deleteModel("whisper-tiny");
       expect(mockInvoke).toHaveBeenCalledWith("delete_model", {
         modelId: "whisper-tiny",
       });
     });
+
+    it("benchmarkModel sends clip and reference text", async () => {
+      mockInvoke.mockResolvedValue({ modelId: "whisper-base", realTimeFactor: 0.2 });
+      const result = await cmds.benchmarkModel({
+        modelId: "whisper-base",
+        audioPath: "/tmp/sample.wav",
+        referenceText: "the quick brown fox",
+      });
+      expect(mockInvoke).toHaveBeenCalledWith("benchmark_model", {
+        modelId: "whisper-base",
+        audioPath: "/tmp/sample.wav",
+        referenceText: "the quick brown fox",
+      });
+      expect(result.realTimeFactor).toBe(0.2);
+    });
+
+    it("modelDiskUsage passes the delete flag", async () => {
+      mockInvoke.mockResolvedValue({ entries: [], totalBytes: 0, freedBytes: 512 });
+      const result = await cmds.modelDiskUsage(true);
+      expect(mockInvoke).toHaveBeenCalledWith("model_disk_usage", {
+        deleteOrphaned: true,
+      });
+      expect(result.freedBytes).toBe(512);
+    });
   });
 
   // ── AI Functions ──────────────────────────────────────────
@@ -144,24 +319,189 @@ describe("tauri-commands", () => {
       expect(result[0].id).toBe("email");
     });
 
-    it("executeAiFunction sends all params", async () => {
-      mockInvoke.mockResolvedValue("Processed text");
-      const result = await cmds.executeAiFunction({
-        functionId: "email",
-        text: "Hello",
-        llmProvider: "openai",
-        llmApiKey: "sk-test",
-        llmModel: "gpt-4",
+    it("sortAiFunctionsByUsage puts most used first and keeps order otherwise", () => {
+      const sorted = cmds.sortAiFunctionsByUsage([
+        { id: "email", useCount: 0 },
+        { id: "summarize", useCount: 5 },
+        { id: "casual" },
+        { id: "slack", useCount: 2 },
+      ]);
+      expect(sorted.map((f) => f.id)).toEqual(["summarize", "slack", "email", "casual"]);
+    });
+
+    it("exportAiFunctions sends path and IDs", async () => {
+      mockInvoke.mockResolvedValue(1);
+      const count = await cmds.exportAiFunctions("/tmp/fns.json", ["custom-1"]);
+      expect(mockInvoke).toHaveBeenCalledWith("export_ai_functions", {
+        path: "/tmp/fns.json",
+        ids: ["custom-1"],
       });
+      expect(count).toBe(1);
+    });
+
+    it("importAiFunctions sends path", async () => {
+      mockInvoke.mockResolvedValue([{ id: "custom-2", name: "Tweet", isBuiltin: false }]);
+      const added = await cmds.importAiFunctions("/tmp/fns.json");
+      expect(mockInvoke).toHaveBeenCalledWith("import_ai_functions", { path: "/tmp/fns.json" });
+      expect(added[0].name).toBe("Tweet");
+    });
+
+    it("executeAiFunction sends function and text only", async () => {
+      mockInvoke.mockResolvedValue("Processed text");
+      const result = await cmds.executeAiFunction({ functionId: "email", text: "Hello" });
       expect(mockInvoke).toHaveBeenCalledWith("execute_ai_function", {
         functionId: "email",
         text: "Hello",
-        llmProvider: "openai",
-        llmApiKey: "sk-test",
-        llmModel: "gpt-4",
       });
       expect(result).toBe("Processed text");
     });
+
+    it("rewriteSelection sends function id", async () => {
+      mockInvoke.mockResolvedValue("Rewritten");
+      const result = await cmds.rewriteSelection("grammar");
+      expect(mockInvoke).toHaveBeenCalledWith("rewrite_selection", { functionId: "grammar" });
+      expect(result).toBe("Rewritten");
+    });
+
+    it("refineLastOutput sends instruction", async () => {
+      mockInvoke.mockResolvedValue("Shorter");
+      const result = await cmds.refineLastOutput("make it shorter");
+      expect(mockInvoke).toHaveBeenCalledWith("refine_last_output", {
+        instruction: "make it shorter",
+      });
+      expect(result).toBe("Shorter");
+    });
+
+    it("cancelAiFunction reports whether a request was running", async () => {
+      mockInvoke.mockResolvedValue(true);
+      const result = await cmds.cancelAiFunction();
+      expect(mockInvoke).toHaveBeenCalledWith("cancel_ai_function");
+      expect(result).toBe(true);
+    });
+
+    it("setAiFunctionOutput passes the action", async () => {
+      await cmds.setAiFunctionOutput("email", { type: "webhook", url: "https://example.com" });
+      expect(mockInvoke).toHaveBeenCalledWith("set_ai_function_output", {
+        functionId: "email",
+        action: { type: "webhook", url: "https://example.com" },
+      });
+    });
+
+    it("deliverOutput passes function and text", async () => {
+      mockInvoke.mockResolvedValue(false);
+      const secure = await cmds.deliverOutput("email", "Hi");
+      expect(mockInvoke).toHaveBeenCalledWith("deliver_output", { functionId: "email", text: "Hi" });
+      expect(secure).toBe(false);
+    });
+
+    it("pasteOutputPreview sends the confirmed text", async () => {
+      mockInvoke.mockResolvedValue(false);
+      await cmds.pasteOutputPreview("Edited");
+      expect(mockInvoke).toHaveBeenCalledWith("paste_output_preview", { text: "Edited" });
+    });
+
+    it("clearAiCache returns removed count", async () => {
+      mockInvoke.mockResolvedValue(3);
+      const result = await cmds.clearAiCache();
+      expect(mockInvoke).toHaveBeenCalledWith("clear_ai_cache");
+      expect(result).toBe(3);
+    });
+  });
+
+  // ── Compose ───────────────────────────────────────────────
+
+  describe("compose", () => {
+    it("startCompose returns the session", async () => {
+      const status = { id: "c1", parts: 0, text: "", durationMs: 0 };
+      mockInvoke.mockResolvedValue(status);
+      const result = await cmds.startCompose();
+      expect(mockInvoke).toHaveBeenCalledWith("start_compose");
+      expect(result).toEqual(status);
+    });
+
+    it("finishCompose passes the AI function and rules option", async () => {
+      await cmds.finishCompose({ aiFunction: "email", keepRulesText: true });
+      expect(mockInvoke).toHaveBeenCalledWith("finish_compose", {
+        aiFunction: "email",
+        keepRulesText: true,
+      });
+    });
+
+    it("getCompose and discardCompose call their commands", async () => {
+      await cmds.getCompose();
+      expect(mockInvoke).toHaveBeenCalledWith("get_compose");
+      await cmds.discardCompose();
+      expect(mockInvoke).toHaveBeenCalledWith("discard_compose");
+    });
+
+    it("composeOutput picks the stage to paste", () => {
+      const result = { transcript: "raw", rulesText: "rules", processedText: "ai" };
+      expect(cmds.composeOutput(result, "processed")).toBe("ai");
+      expect(cmds.composeOutput({ ...result, processedText: null }, "processed")).toBe("rules");
+      expect(cmds.composeOutput(result, "rules")).toBe("rules");
+      expect(cmds.composeOutput(result, "verbatim")).toBe("raw");
+    });
+  });
+
+  // ── Providers ─────────────────────────────────────────────
+
+  describe("provider commands", () => {
+    it("listOllamaModels passes base URL", async () => {
+      mockInvoke.mockResolvedValue(["llama3.2:latest"]);
+      const result = await cmds.listOllamaModels("http://gpu-box:11434");
+      expect(mockInvoke).toHaveBeenCalledWith("list_ollama_models", {
+        baseUrl: "http://gpu-box:11434",
+      });
+      expect(result).toEqual(["llama3.2:latest"]);
+    });
+
+    it("listOllamaModels defaults base URL to null", async () => {
+      mockInvoke.mockResolvedValue([]);
+      await cmds.listOllamaModels();
+      expect(mockInvoke).toHaveBeenCalledWith("list_ollama_models", { baseUrl: null });
+    });
+
+    it("testLlmProvider sends provider config", async () => {
+      const res = { ok: true, latencyMs: 420, message: "Connected" };
+      mockInvoke.mockResolvedValue(res);
+      const result = await cmds.testLlmProvider({
+        provider: "groq",
+        apiKey: "gsk_test",
+        model: "llama-3.3-70b-versatile",
+      });
+      expect(mockInvoke).toHaveBeenCalledWith("test_llm_provider", {
+        provider: "groq",
+        apiKey: "gsk_test",
+        model: "llama-3.3-70b-versatile",
+      });
+      expect(result).toEqual(res);
+    });
+
+    it("testSttProvider sends provider and key", async () => {
+      mockInvoke.mockResolvedValue({ ok: false, latencyMs: 80, message: "401" });
+      const result = await cmds.testSttProvider({ provider: "openai", apiKey: "sk-bad" });
+      expect(mockInvoke).toHaveBeenCalledWith("test_stt_provider", {
+        provider: "openai",
+        apiKey: "sk-bad",
+      });
+      expect(result.ok).toBe(false);
+    });
+
+    it("testSttProvider sends a custom server URL", async () => {
+      mockInvoke.mockResolvedValue({ ok: true, latencyMs: 12, message: "Connected" });
+      await cmds.testSttProvider({
+        provider: "openai",
+        apiKey: "",
+        model: "Systran/faster-whisper-small",
+        baseUrl: "http://localhost:8000/v1",
+      });
+      expect(mockInvoke).toHaveBeenCalledWith("test_stt_provider", {
+        provider: "openai",
+        apiKey: "",
+        model: "Systran/faster-whisper-small",
+        baseUrl: "http://localhost:8000/v1",
+      });
+    });
   });
 
   // ── Rules ─────────────────────────────────────────────────
@@ -176,6 +516,137 @@ describe("tauri-commands", () => {
       });
       expect(result).toBe("Cleaned text");
     });
+
+    it("applyRules without rule IDs uses the saved rules", async () => {
+      mockInvoke.mockResolvedValue("Cleaned text");
+      await cmds.applyRules("um hello");
+      expect(mockInvoke).toHaveBeenCalledWith("apply_rules", {
+        text: "um hello",
+        enabledRuleIds: null,
+      });
+    });
+
+    it("listRules calls correct command", async () => {
+      mockInvoke.mockResolvedValue([
+        {
+          id: "remove-fillers",
+          name: "Remove Filler Words",
+          enabled: true,
+          sortOrder: 0,
+          isBuiltin: true,
+          pattern: null,
+          replacement: null,
+          flags: null,
+        },
+      ]);
+      const result = await cmds.listRules();
+      expect(mockInvoke).toHaveBeenCalledWith("list_rules", undefined);
+      expect(result[0].enabled).toBe(true);
+    });
+
+    it("setRuleEnabled sends ID and state", async () => {
+      await cmds.setRuleEnabled("remove-fillers", true);
+      expect(mockInvoke).toHaveBeenCalledWith("set_rule_enabled", {
+        id: "remove-fillers",
+        enabled: true,
+      });
+    });
+
+    it("reorderRules sends the new order", async () => {
+      await cmds.reorderRules(["smart-punctuation", "remove-fillers"]);
+      expect(mockInvoke).toHaveBeenCalledWith("reorder_rules", {
+        ruleIds: ["smart-punctuation", "remove-fillers"],
+      });
+    });
+
+    it("addRule sends the regex and replacement", async () => {
+      mockInvoke.mockResolvedValue([]);
+      await cmds.addRule("Typo", "\\bteh\\b", "the");
+      expect(mockInvoke).toHaveBeenCalledWith("add_rule", {
+        name: "Typo",
+        pattern: "\\bteh\\b",
+        replacement: "the",
+        flags: "",
+      });
+    });
+
+    it("addRule sends capture group replacements and flags", async () => {
+      mockInvoke.mockResolvedValue([]);
+      await cmds.addRule("Issue", "issue (\\d+)", "ISSUE-$1", "im");
+      expect(mockInvoke).toHaveBeenCalledWith("add_rule", {
+        name: "Issue",
+        pattern: "issue (\\d+)",
+        replacement: "ISSUE-$1",
+        flags: "im",
+      });
+    });
+
+    it("deleteRule sends ID", async () => {
+      await cmds.deleteRule("custom-1");
+      expect(mockInvoke).toHaveBeenCalledWith("delete_rule", { id: "custom-1" });
+    });
+
+    it("exportRules and importRules send the file path", async () => {
+      mockInvoke.mockResolvedValue(2);
+      expect(await cmds.exportRules("/tmp/rules.json")).toBe(2);
+      expect(mockInvoke).toHaveBeenCalledWith("export_rules", { path: "/tmp/rules.json" });
+      expect(await cmds.importRules("/tmp/rules.json")).toBe(2);
+      expect(mockInvoke).toHaveBeenCalledWith("import_rules", { path: "/tmp/rules.json" });
+    });
+  });
+
+  // ── Pipelines ─────────────────────────────────────────────
+
+  describe("pipeline commands", () => {
+    const pipeline = {
+      id: "",
+      name: "Email",
+      steps: [
+        { type: "rules" as const, ruleIds: ["remove-fillers"] },
+        { type: "aiFunction" as const, functionId: "email" },
+        { type: "redact" as const },
+      ],
+      createdAt: "",
+    };
+
+    it("listPipelines calls correct command", async () => {
+      mockInvoke.mockResolvedValue([{ ...pipeline, id: "p1" }]);
+      const result = await cmds.listPipelines();
+      expect(mockInvoke).toHaveBeenCalledWith("list_pipelines", undefined);
+      expect(result[0].steps).toHaveLength(3);
+    });
+
+    it("savePipeline sends the pipeline and returns the saved one", async () => {
+      mockInvoke.mockResolvedValue({ ...pipeline, id: "p1" });
+      const result = await cmds.savePipeline(pipeline);
+      expect(mockInvoke).toHaveBeenCalledWith("save_pipeline", { pipeline });
+      expect(result.id).toBe("p1");
+    });
+
+    it("deletePipeline sends ID", async () => {
+      await cmds.deletePipeline("p1");
+      expect(mockInvoke).toHaveBeenCalledWith("delete_pipeline", { id: "p1" });
+    });
+
+    it("runPipeline sends the request and returns each stage", async () => {
+      const output = {
+        text: "Dear Sam,",
+        rulesText: "dear sam",
+        processedText: "Dear Sam,",
+        aiFunction: "email",
+        aiError: null,
+      };
+      mockInvoke.mockResolvedValue(output);
+      const request = {
+        text: "um dear sam",
+        pipelineId: null,
+        aiFunction: "email",
+        skipAi: false,
+      };
+      const result = await cmds.runPipeline(request);
+      expect(mockInvoke).toHaveBeenCalledWith("run_pipeline", request);
+      expect(result).toEqual(output);
+    });
   });
 
   // ── History ───────────────────────────────────────────────
@@ -207,6 +678,25 @@ describe("tauri-commands", () => {
       });
     });
 
+    it("getHistory sends structured filter", async () => {
+      mockInvoke.mockResolvedValue([]);
+      await cmds.getHistory(100, 0, { modelId: "whisper-base", from: "2025-01-01" });
+      expect(mockInvoke).toHaveBeenCalledWith("get_history", {
+        limit: 100,
+        offset: 0,
+        filter: { modelId: "whisper-base", from: "2025-01-01" },
+      });
+    });
+
+    it("searchHistory sends query with filter", async () => {
+      mockInvoke.mockResolvedValue([]);
+      await cmds.searchHistory("hello", { language: "en" });
+      expect(mockInvoke).toHaveBeenCalledWith("search_history", {
+        query: "hello",
+        filter: { language: "en" },
+      });
+    });
+
     it("getHistoryItem sends ID", async () => {
       mockInvoke.mockResolvedValue({ id: "h1", transcript: "Hi" });
       await cmds.getHistoryItem("h1");
@@ -220,24 +710,36 @@ describe("tauri-commands", () => {
       });
     });
 
+    it("editHistoryTranscript sends ID and transcript", async () => {
+      await cmds.editHistoryTranscript("h1", "Deployed to Kubernetes");
+      expect(mockInvoke).toHaveBeenCalledWith("edit_history_transcript", {
+        id: "h1",
+        transcript: "Deployed to Kubernetes",
+      });
+    });
+
     it("saveHistoryItem sends all params", async () => {
       await cmds.saveHistoryItem({
         sessionId: "s1",
         transcript: "Hello",
         processedText: "Hello!",
+        rulesText: "Hello",
         modelId: "whisper-base",
         language: "en",
         aiFunction: "email",
         durationMs: 5000,
+        metrics: { engine: "whisper", inferenceMs: 1200, realTimeFactor: 0.24, threads: 8 },
       });
       expect(mockInvoke).toHaveBeenCalledWith("save_history_item", {
         sessionId: "s1",
         transcript: "Hello",
         processedText: "Hello!",
+        rulesText: "Hello",
         modelId: "whisper-base",
         language: "en",
         aiFunction: "email",
         durationMs: 5000,
+        metrics: { engine: "whisper", inferenceMs: 1200, realTimeFactor: 0.24, threads: 8 },
       });
     });
 
@@ -253,6 +755,51 @@ describe("tauri-commands", () => {
         modelId: "whisper-base",
       });
     });
+
+    it("getWaveformPeaks sends id and bucket count", async () => {
+      await cmds.getWaveformPeaks("s1", 64);
+      expect(mockInvoke).toHaveBeenCalledWith("get_waveform_peaks", {
+        id: "s1",
+        buckets: 64,
+      });
+    });
+  });
+
+  // ── Notes ─────────────────────────────────────────────────
+
+  describe("notes", () => {
+    it("createNote passes title and starting recordings", async () => {
+      await cmds.createNote("Standup", ["r1", "r2"]);
+      expect(mockInvoke).toHaveBeenCalledWith("create_note", {
+        title: "Standup",
+        recordingIds: ["r1", "r2"],
+      });
+      await cmds.createNote("Empty");
+      expect(mockInvoke).toHaveBeenCalledWith("create_note", {
+        title: "Empty",
+        recordingIds: null,
+      });
+    });
+
+    it("attach, detach and reorder pass ids", async () => {
+      await cmds.attachToNote("n1", "r1");
+      expect(mockInvoke).toHaveBeenCalledWith("attach_to_note", { noteId: "n1", recordingId: "r1" });
+      await cmds.detachFromNote("n1", "r1");
+      expect(mockInvoke).toHaveBeenCalledWith("detach_from_note", {
+        noteId: "n1",
+        recordingId: "r1",
+      });
+      await cmds.reorderNote("n1", ["r2", "r1"]);
+      expect(mockInvoke).toHaveBeenCalledWith("reorder_note", { id: "n1", recordingIds: ["r2", "r1"] });
+    });
+
+    it("getNoteText and exportNote", async () => {
+      mockInvoke.mockResolvedValue("One.\n\nTwo.");
+      expect(await cmds.getNoteText("n1")).toBe("One.\n\nTwo.");
+      expect(mockInvoke).toHaveBeenCalledWith("get_note_text", { id: "n1" });
+      await cmds.exportNote("n1", "/tmp/n1.md");
+      expect(mockInvoke).toHaveBeenCalledWith("export_note", { id: "n1", path: "/tmp/n1.md" });
+    });
   });
 
   // ── Vocabulary ────────────────────────────────────────────
@@ -270,10 +817,108 @@ describe("tauri-commands", () => {
       expect(mockInvoke).toHaveBeenCalledWith("add_term", { term: "GPT-4" });
     });
 
+    it("addTerm sends category and language", async () => {
+      await cmds.addTerm("Herzinsuffizienz", { category: "Medical", language: "de" });
+      expect(mockInvoke).toHaveBeenCalledWith("add_term", {
+        term: "Herzinsuffizienz",
+        category: "Medical",
+        language: "de",
+      });
+    });
+
     it("deleteTerm sends ID", async () => {
       await cmds.deleteTerm("v1");
       expect(mockInvoke).toHaveBeenCalledWith("delete_term", { id: "v1" });
     });
+
+    it("suggestVocabulary sends minimum count", async () => {
+      mockInvoke.mockResolvedValue([{ original: "cooper netties", term: "Kubernetes", count: 3 }]);
+      const result = await cmds.suggestVocabulary(3);
+      expect(mockInvoke).toHaveBeenCalledWith("suggest_vocabulary", { minCount: 3 });
+      expect(result[0].term).toBe("Kubernetes");
+    });
+  });
+
+  // ── Hotkey Bindings ───────────────────────────────────────
+
+  describe("hotkey binding commands", () => {
+    const binding = {
+      id: "",
+      hotkey: "CommandOrControl+Shift+KeyE",
+      modelId: null,
+      language: "en",
+      aiFunction: "email",
+      pipelineId: null,
+    };
+
+    it("listHotkeyBindings calls correct command", async () => {
+      mockInvoke.mockResolvedValue([{ ...binding, id: "b1" }]);
+      const result = await cmds.listHotkeyBindings();
+      expect(mockInvoke).toHaveBeenCalledWith("list_hotkey_bindings", undefined);
+      expect(result[0].aiFunction).toBe("email");
+    });
+
+    it("saveHotkeyBinding sends the binding and returns the saved one", async () => {
+      mockInvoke.mockResolvedValue({ ...binding, id: "b1" });
+      const result = await cmds.saveHotkeyBinding(binding);
+      expect(mockInvoke).toHaveBeenCalledWith("save_hotkey_binding", { binding });
+      expect(result.id).toBe("b1");
+    });
+
+    it("deleteHotkeyBinding sends ID", async () => {
+      await cmds.deleteHotkeyBinding("b1");
+      expect(mockInvoke).toHaveBeenCalledWith("delete_hotkey_binding", { id: "b1" });
+    });
+  });
+
+  // ── Device Bindings ───────────────────────────────────────
+
+  describe("device binding commands", () => {
+    const binding = {
+      id: "",
+      name: "VEC USB Footpedal",
+      vendorId: 0x05f3,
+      productId: 0x00ff,
+      button: 1,
+      action: "dictation",
+      enabled: true,
+      createdAt: "",
+    };
+
+    it("listDeviceBindings calls list_device_bindings", async () => {
+      mockInvoke.mockResolvedValue([]);
+      await cmds.listDeviceBindings();
+      expect(mockInvoke).toHaveBeenCalledWith("list_device_bindings", undefined);
+    });
+
+    it("saveDeviceBinding sends the binding and returns the saved one", async () => {
+      mockInvoke.mockResolvedValue({ ...binding, id: "d1" });
+      const result = await cmds.saveDeviceBinding(binding);
+      expect(mockInvoke).toHaveBeenCalledWith("save_device_binding", { binding });
+      expect(result.id).toBe("d1");
+    });
+
+    it("deleteDeviceBinding sends ID", async () => {
+      await cmds.deleteDeviceBinding("d1");
+      expect(mockInvoke).toHaveBeenCalledWith("delete_device_binding", { id: "d1" });
+    });
+
+    it("listHidDevices calls list_hid_devices", async () => {
+      mockInvoke.mockResolvedValue([{ vendorId: 1523, productId: 255, name: "Pedal" }]);
+      const result = await cmds.listHidDevices();
+      expect(mockInvoke).toHaveBeenCalledWith("list_hid_devices", undefined);
+      expect(result[0].name).toBe("Pedal");
+    });
+
+    it("captureHidButton sends the device and returns the button", async () => {
+      mockInvoke.mockResolvedValue(2);
+      const result = await cmds.captureHidButton(1523, 255);
+      expect(mockInvoke).toHaveBeenCalledWith("capture_hid_button", {
+        vendorId: 1523,
+        productId: 255,
+      });
+      expect(result).toBe(2);
+    });
   });
 
   // ── Audio Import ──────────────────────────────────────────
@@ -288,4 +933,232 @@ describe("tauri-commands", () => {
       expect(result).toBe("session-abc");
     });
   });
+
+  describe("watch folder commands", () => {
+    const folder = {
+      id: "",
+      path: "/Users/me/Recorder",
+      modelId: null,
+      writeTxt: true,
+      writeSrt: false,
+      enabled: true,
+      createdAt: "",
+    };
+
+    it("listWatchFolders calls list_watch_folders", async () => {
+      mockInvoke.mockResolvedValue([]);
+      const result = await cmds.listWatchFolders();
+      expect(mockInvoke).toHaveBeenCalledWith("list_watch_folders", undefined);
+      expect(result).toEqual([]);
+    });
+
+    it("saveWatchFolder sends the folder and returns the saved one", async () => {
+      mockInvoke.mockResolvedValue({ ...folder, id: "w1" });
+      const result = await cmds.saveWatchFolder(folder);
+      expect(mockInvoke).toHaveBeenCalledWith("save_watch_folder", { folder });
+      expect(result.id).toBe("w1");
+    });
+
+    it("deleteWatchFolder sends id", async () => {
+      mockInvoke.mockResolvedValue(undefined);
+      await cmds.deleteWatchFolder("w1");
+      expect(mockInvoke).toHaveBeenCalledWith("delete_watch_folder", {
+        id: "w1",
+      });
+    });
+  });
+
+  // ── App Profiles ──────────────────────────────────────────
+
+  describe("app profile commands", () => {
+    const profile = {
+      id: "",
+      appName: "Slack",
+      newlineMode: "shiftEnter" as const,
+      stripTrailingNewline: true,
+      createdAt: "",
+    };
+
+    it("listAppProfiles calls list_app_profiles", async () => {
+      mockInvoke.mockResolvedValue([]);
+      const result = await cmds.listAppProfiles();
+      expect(mockInvoke).toHaveBeenCalledWith("list_app_profiles", undefined);
+      expect(result).toEqual([]);
+    });
+
+    it("saveAppProfile sends the profile and returns the saved one", async () => {
+      mockInvoke.mockResolvedValue({ ...profile, id: "p1" });
+      const result = await cmds.saveAppProfile(profile);
+      expect(mockInvoke).toHaveBeenCalledWith("save_app_profile", { profile });
+      expect(result.id).toBe("p1");
+    });
+
+    it("deleteAppProfile sends id", async () => {
+      mockInvoke.mockResolvedValue(undefined);
+      await cmds.deleteAppProfile("p1");
+      expect(mockInvoke).toHaveBeenCalledWith("delete_app_profile", {
+        id: "p1",
+      });
+    });
+  });
+
+  // ── Settings ──────────────────────────────────────────────
+
+  describe("settings commands", () => {
+    it("saveSettings sends the settings object", async () => {
+      await cmds.saveSettings({ selectedModel: "whisper-base", incognito: false });
+      expect(mockInvoke).toHaveBeenCalledWith("save_settings", {
+        settings: { selectedModel: "whisper-base", incognito: false },
+      });
+    });
+
+    it("getSettings calls correct command", async () => {
+      mockInvoke.mockResolvedValue({ selectedLanguage: "de" });
+      const result = await cmds.getSettings();
+      expect(mockInvoke).toHaveBeenCalledWith("get_settings", undefined);
+      expect(result.selectedLanguage).toBe("de");
+    });
+
+    it("setIncognito sends enabled flag", async () => {
+      await cmds.setIncognito(true);
+      expect(mockInvoke).toHaveBeenCalledWith("set_incognito", { enabled: true });
+    });
+
+    it("setHotkeyBackend sends backend name", async () => {
+      await cmds.setHotkeyBackend("portal");
+      expect(mockInvoke).toHaveBeenCalledWith("set_hotkey_backend", { backend: "portal" });
+    });
+
+    it("setMouseButton sends button name", async () => {
+      await cmds.setMouseButton("forward");
+      expect(mockInvoke).toHaveBeenCalledWith("set_mouse_button", { button: "forward" });
+    });
+
+    it("setUiLanguage sends language", async () => {
+      await cmds.setUiLanguage("tr");
+      expect(mockInvoke).toHaveBeenCalledWith("set_ui_language", { language: "tr" });
+    });
+
+    it("getIncognito calls correct command", async () => {
+      mockInvoke.mockResolvedValue(false);
+      const result = await cmds.getIncognito();
+      expect(mockInvoke).toHaveBeenCalledWith("get_incognito");
+      expect(result).toBe(false);
+    });
+
+    it("setAutostart sends enabled flag", async () => {
+      await cmds.setAutostart(true);
+      expect(mockInvoke).toHaveBeenCalledWith("set_autostart", { enabled: true });
+    });
+
+    it("getAutostart calls correct command", async () => {
+      mockInvoke.mockResolvedValue(true);
+      const result = await cmds.getAutostart();
+      expect(mockInvoke).toHaveBeenCalledWith("get_autostart");
+      expect(result).toBe(true);
+    });
+
+    it("getStorageDir calls correct command", async () => {
+      mockInvoke.mockResolvedValue("/data/sobotta");
+      const result = await cmds.getStorageDir();
+      expect(mockInvoke).toHaveBeenCalledWith("get_storage_dir");
+      expect(result).toBe("/data/sobotta");
+    });
+
+    it("setStorageDir moves files by default", async () => {
+      await cmds.setStorageDir("/Volumes/External/Sobotta");
+      expect(mockInvoke).toHaveBeenCalledWith("set_storage_dir", {
+        path: "/Volumes/External/Sobotta",
+        moveFiles: true,
+      });
+    });
+
+    it("setStorageDir can reset without moving files", async () => {
+      await cmds.setStorageDir(null, false);
+      expect(mockInvoke).toHaveBeenCalledWith("set_storage_dir", {
+        path: null,
+        moveFiles: false,
+      });
+    });
+
+    it("setDatabaseEncryption sends enabled flag", async () => {
+      await cmds.setDatabaseEncryption(true);
+      expect(mockInvoke).toHaveBeenCalledWith("set_database_encryption", { enabled: true });
+    });
+
+    it("getDatabaseEncryption calls correct command", async () => {
+      mockInvoke.mockResolvedValue(true);
+      const result = await cmds.getDatabaseEncryption();
+      expect(mockInvoke).toHaveBeenCalledWith("get_database_encryption");
+      expect(result).toBe(true);
+    });
+
+    it("maintainDatabase returns the maintenance report", async () => {
+      const report = {
+        integrityOk: true,
+        integrityErrors: [],
+        sizeBefore: 5_000_000,
+        sizeAfter: 1_000_000,
+        reclaimedBytes: 4_000_000,
+      };
+      mockInvoke.mockResolvedValue(report);
+      const result = await cmds.maintainDatabase();
+      expect(mockInvoke).toHaveBeenCalledWith("maintain_database");
+      expect(result).toEqual(report);
+    });
+  });
+
+  // ── Permissions ───────────────────────────────────────────
+
+  describe("permission commands", () => {
+    it("checkPermissions calls correct command", async () => {
+      mockInvoke.mockResolvedValue({ microphone: "granted", accessibility: "denied" });
+      const result = await cmds.checkPermissions();
+      expect(mockInvoke).toHaveBeenCalledWith("check_permissions");
+      expect(result.accessibility).toBe("denied");
+    });
+
+    it("requestPermissions calls correct command", async () => {
+      mockInvoke.mockResolvedValue({ microphone: "not-determined", accessibility: "granted" });
+      await cmds.requestPermissions();
+      expect(mockInvoke).toHaveBeenCalledWith("request_permissions");
+    });
+  });
+
+  // ── Updater ───────────────────────────────────────────────
+
+  describe("updater commands", () => {
+    it("collectDiagnostics passes options and returns path", async () => {
+      mockInvoke.mockResolvedValue("/Users/me/Downloads/sobottaai-diagnostics.zip");
+      const result = await cmds.collectDiagnostics({ includeFailedAudio: true });
+      expect(mockInvoke).toHaveBeenCalledWith("collect_diagnostics", {
+        includeFailedAudio: true,
+      });
+      expect(result).toBe("/Users/me/Downloads/sobottaai-diagnostics.zip");
+    });
+
+    it("setUpdateChannel sends channel", async () => {
+      await cmds.setUpdateChannel("beta");
+      expect(mockInvoke).toHaveBeenCalledWith("set_update_channel", { channel: "beta" });
+    });
+
+    it("getUpdateChannel calls correct command", async () => {
+      mockInvoke.mockResolvedValue("stable");
+      const result = await cmds.getUpdateChannel();
+      expect(mockInvoke).toHaveBeenCalledWith("get_update_channel");
+      expect(result).toBe("stable");
+    });
+
+    it("checkForUpdates returns null when up to date", async () => {
+      mockInvoke.mockResolvedValue(null);
+      const result = await cmds.checkForUpdates();
+      expect(mockInvoke).toHaveBeenCalledWith("check_for_updates");
+      expect(result).toBeNull();
+    });
+
+    it("installUpdate calls correct command", async () => {
+      await cmds.installUpdate();
+      expect(mockInvoke).toHaveBeenCalledWith("install_update");
+    });
+  });
 });
@@ -1,12 +1,32 @@
 "use client";
 
-import { useEffect, useState, useCallback } from "react";
+import { useEffect, useState, useCallback, useMemo } from "react";
 import { toast } from "sonner";
-import { Search, Trash2, Clock, Mic, Copy, Check } from "lucide-react";
+import {
+  Search,
+  Trash2,
+  Clock,
+  Mic,
+  Copy,
+  Check,
+  Gauge,
+  Pencil,
+  NotebookPen,
+  Plus,
+} from "lucide-react";
 import { motion, AnimatePresence } from "motion/react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
+import { Textarea } from "@/components/ui/textarea";
 import { ScrollArea } from "@/components/ui/scroll-area";
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuLabel,
+  DropdownMenuSeparator,
+  DropdownMenuTrigger,
+} from "@/components/ui/dropdown-menu";
 import { AppShell } from "@/components/app-shell";
 
 interface HistoryItem {
@@ -14,10 +34,17 @@ interface HistoryItem {
   audioPath?: string;
   transcript: string;
   processedText?: string;
+  rulesText?: string;
   modelId: string;
   language?: string;
   aiFunction?: string;
   durationMs?: number;
+  metrics?: {
+    engine: string;
+    inferenceMs: number;
+    realTimeFactor: number;
+    threads: number | null;
+  } | null;
   createdAt: string;
 }
 
@@ -48,10 +75,72 @@ function CopyButton({ text }: { text: string }) {
   );
 }
 
+/** Append a recording to an existing note or start a new one with it. */
+function AddToNoteButton({ recordingId }: { recordingId: string }) {
+  const [notes, setNotes] = useState<{ id: string; title: string }[]>([]);
+
+  const attach = async (noteId: string, title: string) => {
+    try {
+      await tauriInvoke("attach_to_note", { noteId, recordingId });
+      toast.success(`Added to "${title}"`);
+    } catch {
+      toast.error("Failed to add to note");
+    }
+  };
+
+  const createNote = async () => {
+    const title = `Note ${new Date().toLocaleDateString()}`;
+    try {
+      await tauriInvoke("create_note", { title, recordingIds: [recordingId] });
+      toast.success(`Added to "${title}"`);
+    } catch {
+      toast.error("Failed to create note");
+    }
+  };
+
+  return (
+    <DropdownMenu
+      onOpenChange={(open) => {
+        if (open) tauriInvoke<typeof notes>("list_notes").then(setNotes).catch(() => {});
+      }}
+    >
+      <DropdownMenuTrigger asChild>
+        <Button variant="ghost" size="icon" title="Add to note">
+          <NotebookPen className="h-4 w-4" />
+        </Button>
+      </DropdownMenuTrigger>
+      <DropdownMenuContent align="end" className="w-48">
+        <DropdownMenuLabel className="text-xs">Add to note</DropdownMenuLabel>
+        {notes.map((note) => (
+          <DropdownMenuItem key={note.id} onSelect={() => attach(note.id, note.title)}>
+            <span className="truncate">{note.title}</span>
+          </DropdownMenuItem>
+        ))}
+        {notes.length > 0 && <DropdownMenuSeparator />}
+        <DropdownMenuItem onSelect={createNote}>
+          <Plus className="h-4 w-4" />
+          New note
+        </DropdownMenuItem>
+      </DropdownMenuContent>
+    </DropdownMenu>
+  );
+}
+
 export default function HistoryPage() {
   const [items, setItems] = useState<HistoryItem[]>([]);
   const [searchQuery, setSearchQuery] = useState("");
+  const [fromDate, setFromDate] = useState("");
+  const [toDate, setToDate] = useState("");
   const [loading, setLoading] = useState(true);
+  const [editing, setEditing] = useState<{ id: string; text: string } | null>(null);
+
+  const filter = useMemo(
+    () => ({
+      from: fromDate || undefined,
+      to: toDate || undefined,
+    }),
+    [fromDate, toDate],
+  );
 
   const loadHistory = useCallback(async () => {
     try {
@@ -59,6 +148,7 @@ export default function HistoryPage() {
       const result = await tauriInvoke<HistoryItem[]>("get_history", {
         limit: 100,
         offset: 0,
+        filter,
       });
       setItems(result);
     } catch {
@@ -66,7 +156,7 @@ export default function HistoryPage() {
     } finally {
       setLoading(false);
     }
-  }, []);
+  }, [filter]);
 
   const searchHistory = useCallback(async (query: string) => {
     if (!query.trim()) {
@@ -77,6 +167,7 @@ export default function HistoryPage() {
       setLoading(true);
       const result = await tauriInvoke<HistoryItem[]>("search_history", {
         query,
+        filter,
       });
       setItems(result);
     } catch {
@@ -84,7 +175,7 @@ export default function HistoryPage() {
     } finally {
       setLoading(false);
     }
-  }, [loadHistory]);
+  }, [loadHistory, filter]);
 
   const deleteItem = useCallback(async (id: string) => {
     try {
@@ -95,6 +186,21 @@ export default function HistoryPage() {
     }
   }, []);
 
+  const saveEdit = useCallback(async () => {
+    if (!editing) return;
+    const transcript = editing.text.trim();
+    if (!transcript) return;
+    try {
+      await tauriInvoke("edit_history_transcript", { id: editing.id, transcript });
+      setItems((prev) =>
+        prev.map((item) => (item.id === editing.id ? { ...item, transcript } : item)),
+      );
+      setEditing(null);
+    } catch {
+      toast.error("Failed to save transcript");
+    }
+  }, [editing]);
+
   useEffect(() => {
     loadHistory();
   }, [loadHistory]);
@@ -161,6 +267,22 @@ export default function HistoryPage() {
               onChange={(e) => setSearchQuery(e.target.value)}
             />
           </div>
+          <div className="flex items-center gap-2 mt-2 text-xs text-muted-foreground">
+            <span>From</span>
+            <Input
+              type="date"
+              className="h-8 w-auto text-xs"
+              value={fromDate}
+              onChange={(e) => setFromDate(e.target.value)}
+            />
+            <span>to</span>
+            <Input
+              type="date"
+              className="h-8 w-auto text-xs"
+              value={toDate}
+              onChange={(e) => setToDate(e.target.value)}
+            />
+          </div>
         </div>
 
         <div className="flex-1 min-h-0">
@@ -208,9 +330,68 @@ export default function HistoryPage() {
                       >
                         <div className="flex items-start justify-between gap-3">
                           <div className="flex-1 min-w-0">
-                            <p className="text-sm leading-relaxed">
-                              {item.processedText || item.transcript}
-                            </p>
+                            {editing?.id === item.id ? (
+                              <div className="space-y-2">
+                                {item.processedText && (
+                                  <p className="text-xs text-muted-foreground">
+                                    Verbatim transcript
+                                  </p>
+                                )}
+                                <Textarea
+                                  value={editing.text}
+                                  onChange={(e) =>
+                                    setEditing({ id: item.id, text: e.target.value })
+                                  }
+                                  onKeyDown={(e) => {
+                                    if (e.key === "Escape") setEditing(null);
+                                    if (e.key === "Enter" && (e.metaKey || e.ctrlKey)) saveEdit();
+                                  }}
+                                  className="text-sm min-h-20"
+                                  autoFocus
+                                />
+                                <div className="flex items-center gap-2">
+                                  <Button size="sm" onClick={saveEdit} className="h-7 text-xs">
+                                    Save
+                                  </Button>
+                                  <Button
+                                    size="sm"
+                                    variant="ghost"
+                                    onClick={() => setEditing(null)}
+                                    className="h-7 text-xs"
+                                  >
+                                    Cancel
+                                  </Button>
+                                  <span className="text-[11px] text-muted-foreground/50">
+                                    Words you fix often are suggested as vocabulary.
+                                  </span>
+                                </div>
+                              </div>
+                            ) : (
+                              <p className="text-sm leading-relaxed">
+                                {item.processedText || item.transcript}
+                              </p>
+                            )}
+                            {(item.processedText || item.rulesText) && (
+                              <details className="mt-2 text-xs text-muted-foreground">
+                                <summary className="cursor-pointer select-none">
+                                  Other versions
+                                </summary>
+                                <div className="mt-1.5 space-y-1.5">
+                                  <div className="flex items-start gap-2">
+                                    <span className="w-16 shrink-0 font-medium">Verbatim</span>
+                                    <p className="flex-1">{item.transcript}</p>
+                                    <CopyButton text={item.transcript} />
+                                  </div>
+                                  {item.rulesText && (
+                                    <div className="flex items-start gap-2">
+                                      <span className="w-16 shrink-0 font-medium">Rules only</span>
+                                      <p className="flex-1">{item.rulesText}</p>
+                                      <CopyButton text={item.rulesText} />
+                                    </div>
+                                  )}
+                                </div>
+                              </details>
+                            )}
                             <div className="flex items-center gap-3 mt-2 text-xs text-muted-foreground">
                               <span>{formatDate(item.createdAt)}</span>
                               {item.durationMs && (
@@ -225,10 +406,30 @@ export default function HistoryPage() {
                               {item.language && (
                                 <span className="uppercase">{item.language}</span>
                               )}
+                              {item.metrics && (
+                                <span
+                                  className="flex items-center gap-1"
+                                  title={`${item.metrics.engine}, ${(item.metrics.inferenceMs / 1000).toFixed(1)}s${
+                                    item.metrics.threads ? `, ${item.metrics.threads} threads` : ""
+                                  }`}
+                                >
+                                  <Gauge className="h-3 w-3" />
+                                  {item.metrics.realTimeFactor.toFixed(2)}× RTF
+                                </span>
+                              )}
                             </div>
                           </div>
                           <div className="flex items-center gap-0.5 opacity-0 group-hover:opacity-100 transition-opacity shrink-0">
                             <CopyButton text={item.processedText || item.transcript} />
+                            <AddToNoteButton recordingId={item.id} />
+                            <Button
+                              variant="ghost"
+                              size="icon"
+                              title="Correct transcript"
+                              onClick={() => setEditing({ id: item.id, text: item.transcript })}
+                            >
+                              <Pencil className="h-4 w-4" />
+                            </Button>
                             <Button
                               variant="ghost"
                               size="icon"
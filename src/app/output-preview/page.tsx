@@ -0,0 +1,89 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { ClipboardPaste, Copy, X } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Textarea } from "@/components/ui/textarea";
+import { errorMessage, getOutputPreview, pasteOutputPreview } from "@/lib/tauri-commands";
+
+/**
+ * Window opened by the "preview" output action, or for every dictation when
+ * the paste mode is "confirm": the output is shown for review and editing
+ * instead of being pasted.
+ */
+export default function OutputPreviewPage() {
+  const [text, setText] = useState("");
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    getOutputPreview()
+      .then((preview) => !cancelled && preview && setText(preview.text))
+      .catch(() => {});
+    import("@tauri-apps/api/event")
+      .then(({ listen }) =>
+        listen<{ text: string }>("output-preview", (event) => setText(event.payload.text)),
+      )
+      .then((fn) => {
+        if (cancelled) fn();
+        else unlisten = fn;
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
+  async function close() {
+    const { getCurrentWindow } = await import("@tauri-apps/api/window");
+    await getCurrentWindow().close();
+  }
+
+  async function copyAndClose() {
+    try {
+      await navigator.clipboard.writeText(text);
+      await close();
+    } catch {
+      toast.error("Failed to copy");
+    }
+  }
+
+  async function paste() {
+    try {
+      await pasteOutputPreview(text);
+    } catch (err) {
+      toast.error("Failed to paste", { description: errorMessage(err) });
+    }
+  }
+
+  return (
+    <div className="flex h-screen flex-col gap-3 p-4">
+      <Textarea
+        value={text}
+        onChange={(e) => setText(e.target.value)}
+        className="flex-1 resize-none text-sm leading-relaxed"
+      />
+      <div className="flex justify-end gap-2">
+        <Button variant="ghost" size="sm" onClick={close} className="text-xs gap-1.5">
+          <X className="h-3.5 w-3.5" />
+          Close
+        </Button>
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={copyAndClose}
+          className="text-xs gap-1.5"
+        >
+          <Copy className="h-3.5 w-3.5" />
+          Copy and Close
+        </Button>
+        <Button size="sm" onClick={paste} className="text-xs gap-1.5">
+          <ClipboardPaste className="h-3.5 w-3.5" />
+          Paste
+        </Button>
+      </div>
+    </div>
+  );
+}
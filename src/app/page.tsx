@@ -8,6 +8,8 @@ import { motion, AnimatePresence } from "motion/react";
 import { AppShell } from "@/components/app-shell";
 import { RecordButton } from "@/components/record-button";
 import { QuickSettings } from "@/components/quick-settings";
+import { RefineOutput } from "@/components/refine-output";
+import { ComposePanel } from "@/components/compose-panel";
 import { Onboarding } from "@/components/onboarding";
 import { useRecording } from "@/hooks/use-recording";
 import { useSettingsStore } from "@/stores/settings-store";
@@ -16,7 +18,8 @@ import { useIsMac, formatHotkeyDisplay } from "@/lib/hotkey-utils";
 export default function Home() {
   const router = useRouter();
   const [mounted, setMounted] = useState(false);
-  const { onboardingComplete, setOnboardingComplete, defaultHotkey, _hydrated } = useSettingsStore();
+  const { onboardingComplete, setOnboardingComplete, defaultHotkey, selectedAiFunction, _hydrated } =
+    useSettingsStore();
   const isMac = useIsMac();
   const {
     isRecording,
@@ -27,6 +30,10 @@ export default function Home() {
     toggleRecording,
   } = useRecording();
 
+  // A refinement of the last output replaces it until the next recording
+  const [refined, setRefined] = useState<{ of: string; text: string } | null>(null);
+  const shownResult = refined?.of === lastResult ? refined.text : lastResult;
+
   useEffect(() => {
     setMounted(true);
     import("@/stores/settings-store").then(({ useSettingsStore }) => {
@@ -132,12 +139,18 @@ export default function Home() {
                     Last transcription
                   </p>
                   <div className="max-h-40 overflow-y-auto pr-1">
-                    <p className="text-sm leading-relaxed">{lastResult}</p>
+                    <p className="text-sm leading-relaxed">{shownResult}</p>
                   </div>
+                  {selectedAiFunction && lastResult && (
+                    <RefineOutput onRefined={(text) => setRefined({ of: lastResult, text })} />
+                  )}
                 </div>
               </motion.div>
             )}
           </AnimatePresence>
+
+          {/* Compose — dictations collected into one document */}
+          <ComposePanel disabled={isRecording || isTranscribing} />
         </main>
 
         {/* Bottom toolbar */}
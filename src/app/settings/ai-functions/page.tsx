@@ -10,13 +10,25 @@ import {
   FileText,
   PenLine,
   Zap,
+  UserRound,
+  Download,
+  Upload,
+  DatabaseZap,
 } from "lucide-react";
 import { motion, AnimatePresence } from "motion/react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Textarea } from "@/components/ui/textarea";
 import { Label } from "@/components/ui/label";
+import { ToggleGroup, ToggleGroupItem } from "@/components/ui/toggle-group";
 import { cn } from "@/lib/utils";
+import {
+  clearAiCache,
+  setAiFunctionOutput,
+  sortAiFunctionsByUsage,
+  type OutputAction,
+} from "@/lib/tauri-commands";
+import { useSettingsStore, type AiContextSource } from "@/stores/settings-store";
 
 interface AiFunction {
   id: string;
@@ -25,6 +37,16 @@ interface AiFunction {
   provider: string;
   model?: string;
   isBuiltin: boolean;
+  outputAction: OutputAction;
+  useCount: number;
+  lastUsedAt: string | null;
+}
+
+function formatLastUsed(iso: string): string {
+  const days = Math.floor((Date.now() - new Date(iso).getTime()) / 86_400_000);
+  if (days <= 0) return "today";
+  if (days === 1) return "yesterday";
+  return `${days} days ago`;
 }
 
 const BUILTIN_ICONS: Record<string, typeof Sparkles> = {
@@ -48,8 +70,31 @@ function FunctionCard({
   index: number;
 }) {
   const [expanded, setExpanded] = useState(false);
+  const [output, setOutput] = useState<OutputAction>(fn.outputAction);
+  const [webhookUrl, setWebhookUrl] = useState(
+    fn.outputAction.type === "webhook" ? fn.outputAction.url : ""
+  );
   const Icon = BUILTIN_ICONS[fn.id] ?? Zap;
 
+  async function saveOutput(action: OutputAction) {
+    try {
+      await setAiFunctionOutput(fn.id, action);
+      setOutput(action);
+    } catch (err) {
+      toast.error("Failed to save output action", { description: String(err) });
+    }
+  }
+
+  function selectOutput(type: string) {
+    if (type === "webhook") {
+      // Saved once a URL is entered
+      setOutput({ type: "webhook", url: webhookUrl });
+      if (webhookUrl.trim()) saveOutput({ type: "webhook", url: webhookUrl.trim() });
+    } else {
+      saveOutput({ type } as OutputAction);
+    }
+  }
+
   return (
     <motion.div
       initial={{ opacity: 0, y: 8 }}
@@ -96,6 +141,13 @@ function FunctionCard({
           <p className="text-xs text-muted-foreground mt-1 line-clamp-2 leading-relaxed">
             {fn.prompt}
           </p>
+          <p className="text-[10px] text-muted-foreground/50 mt-1.5">
+            {fn.useCount > 0
+              ? `Used ${fn.useCount} ${fn.useCount === 1 ? "time" : "times"}${
+                  fn.lastUsedAt ? `, last ${formatLastUsed(fn.lastUsedAt)}` : ""
+                }`
+              : "Never used"}
+          </p>
         </div>
 
         {/* Delete for custom */}
@@ -133,6 +185,53 @@ function FunctionCard({
                   {fn.prompt}
                 </p>
               </div>
+              <div className="pt-4 space-y-2">
+                <Label className="text-[10px] font-medium uppercase tracking-wider text-muted-foreground/50">
+                  Output
+                </Label>
+                <ToggleGroup
+                  type="single"
+                  variant="outline"
+                  size="sm"
+                  value={output.type}
+                  onValueChange={(v) => {
+                    if (v) selectOutput(v);
+                  }}
+                  className="flex-wrap justify-start"
+                >
+                  <ToggleGroupItem value="paste" className="text-xs">
+                    Paste
+                  </ToggleGroupItem>
+                  <ToggleGroupItem value="copy" className="text-xs">
+                    Copy
+                  </ToggleGroupItem>
+                  <ToggleGroupItem
+                    value="pasteKeepClipboard"
+                    aria-label="Paste and restore clipboard"
+                    className="text-xs"
+                  >
+                    Paste &amp; Restore
+                  </ToggleGroupItem>
+                  <ToggleGroupItem value="preview" className="text-xs">
+                    Preview
+                  </ToggleGroupItem>
+                  <ToggleGroupItem value="webhook" className="text-xs">
+                    Webhook
+                  </ToggleGroupItem>
+                </ToggleGroup>
+                {output.type === "webhook" && (
+                  <Input
+                    value={webhookUrl}
+                    onChange={(e) => setWebhookUrl(e.target.value)}
+                    onBlur={() => {
+                      const url = webhookUrl.trim();
+                      if (url) saveOutput({ type: "webhook", url });
+                    }}
+                    placeholder="https://example.com/hook"
+                    className="h-8 text-xs font-mono"
+                  />
+                )}
+              </div>
             </div>
           </motion.div>
         )}
@@ -147,6 +246,14 @@ export default function AiFunctionsSettings() {
   const [showCreate, setShowCreate] = useState(false);
   const [newName, setNewName] = useState("");
   const [newPrompt, setNewPrompt] = useState("");
+  const {
+    systemPromptPrefix,
+    setSystemPromptPrefix,
+    aiContextSource,
+    setAiContextSource,
+    aiCacheTtlHours,
+    setAiCacheTtlHours,
+  } = useSettingsStore();
 
   const loadFunctions = useCallback(async () => {
     try {
@@ -197,19 +304,168 @@ export default function AiFunctionsSettings() {
     }
   }
 
-  const builtinFns = functions.filter((f) => f.isBuiltin);
-  const customFns = functions.filter((f) => !f.isBuiltin);
+  async function clearCache() {
+    try {
+      const removed = await clearAiCache();
+      toast.success(removed === 1 ? "Cleared 1 cached output" : `Cleared ${removed} cached outputs`);
+    } catch (err) {
+      toast.error("Failed to clear AI cache", { description: String(err) });
+    }
+  }
+
+  async function exportFunctions() {
+    try {
+      const { save } = await import("@tauri-apps/plugin-dialog");
+      const path = await save({
+        defaultPath: "ai-functions.json",
+        filters: [{ name: "AI functions", extensions: ["json"] }],
+      });
+      if (!path) return;
+      const count = await invoke<number>("export_ai_functions", { path });
+      toast.success(`Exported ${count} function${count === 1 ? "" : "s"}`);
+    } catch (err) {
+      toast.error("Failed to export AI functions", { description: String(err) });
+    }
+  }
+
+  async function importFunctions() {
+    try {
+      const { open } = await import("@tauri-apps/plugin-dialog");
+      const path = await open({
+        multiple: false,
+        filters: [{ name: "AI functions", extensions: ["json"] }],
+      });
+      if (typeof path !== "string") return;
+      const added = await invoke<AiFunction[]>("import_ai_functions", { path });
+      await loadFunctions();
+      toast.success(
+        added.length === 0
+          ? "No new functions to import"
+          : `Imported ${added.length} function${added.length === 1 ? "" : "s"}`,
+      );
+    } catch (err) {
+      toast.error("Failed to import AI functions", { description: String(err) });
+    }
+  }
+
+  // Most used first within each group
+  const sortedFns = sortAiFunctionsByUsage(functions);
+  const builtinFns = sortedFns.filter((f) => f.isBuiltin);
+  const customFns = sortedFns.filter((f) => !f.isBuiltin);
 
   return (
     <div className="space-y-8">
-      <div>
-        <h3 className="text-lg font-semibold">AI Functions</h3>
-        <p className="text-sm text-muted-foreground">
-          Post-processing with AI. Select a function before recording to automatically
-          transform your transcription.
+      <div className="flex items-start justify-between gap-4">
+        <div>
+          <h3 className="text-lg font-semibold">AI Functions</h3>
+          <p className="text-sm text-muted-foreground">
+            Post-processing with AI. Select a function before recording to automatically
+            transform your transcription.
+          </p>
+        </div>
+        <div className="flex items-center gap-1 shrink-0">
+          <Button variant="ghost" size="sm" onClick={importFunctions} className="text-xs gap-1.5">
+            <Upload className="h-3.5 w-3.5" />
+            Import
+          </Button>
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={exportFunctions}
+            disabled={customFns.length === 0}
+            className="text-xs gap-1.5"
+          >
+            <Download className="h-3.5 w-3.5" />
+            Export
+          </Button>
+        </div>
+      </div>
+
+      {/* Global prefix prepended to every function's system prompt */}
+      <div className="rounded-xl border p-4 space-y-2">
+        <div className="flex items-center gap-2">
+          <UserRound className="h-4 w-4 text-muted-foreground" />
+          <Label className="text-sm">Personal Context</Label>
+        </div>
+        <Textarea
+          placeholder="e.g., My name is Alex. I write in a direct, friendly style and sign off with 'Cheers, Alex'."
+          value={systemPromptPrefix}
+          onChange={(e) => setSystemPromptPrefix(e.target.value)}
+          rows={3}
+          className="text-sm resize-none"
+        />
+        <p className="text-[10px] text-muted-foreground/50">
+          Prepended to the system prompt of every AI function, built-in and custom.
         </p>
       </div>
 
+      {/* Extra context passed to the LLM with the dictation */}
+      <div className="rounded-xl border p-4 flex items-center justify-between gap-4">
+        <div>
+          <Label className="text-sm">Include Context</Label>
+          <p className="text-xs text-muted-foreground mt-0.5">
+            Send the clipboard or selected text along, e.g. to dictate a reply to an email
+          </p>
+        </div>
+        <ToggleGroup
+          type="single"
+          variant="outline"
+          value={aiContextSource}
+          onValueChange={(v) => {
+            if (v) setAiContextSource(v as AiContextSource);
+          }}
+        >
+          <ToggleGroupItem value="none" aria-label="No context">
+            None
+          </ToggleGroupItem>
+          <ToggleGroupItem value="clipboard" aria-label="Clipboard as context">
+            Clipboard
+          </ToggleGroupItem>
+          <ToggleGroupItem value="selection" aria-label="Selected text as context">
+            Selection
+          </ToggleGroupItem>
+        </ToggleGroup>
+      </div>
+
+      {/* Identical requests reuse the earlier output instead of calling the LLM */}
+      <div className="rounded-xl border p-4 flex items-center justify-between gap-4">
+        <div>
+          <div className="flex items-center gap-2">
+            <DatabaseZap className="h-4 w-4 text-muted-foreground" />
+            <Label className="text-sm">Response Cache</Label>
+          </div>
+          <p className="text-xs text-muted-foreground mt-0.5">
+            Re-running a function on the same text returns the saved result without another API call
+          </p>
+        </div>
+        <div className="flex items-center gap-2">
+          <ToggleGroup
+            type="single"
+            variant="outline"
+            value={String(aiCacheTtlHours)}
+            onValueChange={(v) => {
+              if (v) setAiCacheTtlHours(Number(v));
+            }}
+          >
+            <ToggleGroupItem value="0" aria-label="Cache off">
+              Off
+            </ToggleGroupItem>
+            <ToggleGroupItem value="1" aria-label="Cache for 1 hour">
+              1h
+            </ToggleGroupItem>
+            <ToggleGroupItem value="24" aria-label="Cache for 1 day">
+              1d
+            </ToggleGroupItem>
+            <ToggleGroupItem value="168" aria-label="Cache for 1 week">
+              1w
+            </ToggleGroupItem>
+          </ToggleGroup>
+          <Button variant="ghost" size="sm" onClick={clearCache} className="text-xs">
+            Clear
+          </Button>
+        </div>
+      </div>
+
       {loading ? (
         <div className="space-y-3">
           {[1, 2, 3].map((i) => (
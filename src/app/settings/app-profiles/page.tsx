@@ -0,0 +1,204 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { AppWindow, Plus, Trash2 } from "lucide-react";
+import { motion } from "motion/react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import { ToggleGroup, ToggleGroupItem } from "@/components/ui/toggle-group";
+import {
+  deleteAppProfile,
+  listAppProfiles,
+  saveAppProfile,
+  type AppProfile,
+  type NewlineMode,
+} from "@/lib/tauri-commands";
+
+function ProfileCard({
+  profile,
+  index,
+  onSave,
+  onDelete,
+}: {
+  profile: AppProfile;
+  index: number;
+  onSave: (profile: AppProfile) => void;
+  onDelete: () => void;
+}) {
+  return (
+    <motion.div
+      initial={{ opacity: 0, y: 8 }}
+      animate={{ opacity: 1, y: 0 }}
+      transition={{ delay: index * 0.04 }}
+      className="rounded-xl border p-4 space-y-3"
+    >
+      <div className="flex items-center gap-3">
+        <div className="flex items-center justify-center h-8 w-8 rounded-lg shrink-0 bg-primary/10 text-primary">
+          <AppWindow className="h-4 w-4" />
+        </div>
+        <p className="flex-1 min-w-0 truncate text-sm font-medium">{profile.appName}</p>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 w-7 p-0 shrink-0 text-muted-foreground/40 hover:text-destructive"
+          aria-label="Delete app profile"
+          onClick={onDelete}
+        >
+          <Trash2 className="h-3.5 w-3.5" />
+        </Button>
+      </div>
+
+      <div className="flex items-center justify-between gap-4">
+        <div>
+          <Label className="text-sm">Line Breaks</Label>
+          <p className="text-xs text-muted-foreground mt-0.5">
+            Shift+Enter for chat apps that send on Enter, spaces for terminals
+          </p>
+        </div>
+        <ToggleGroup
+          type="single"
+          variant="outline"
+          value={profile.newlineMode}
+          onValueChange={(v) => {
+            if (v) onSave({ ...profile, newlineMode: v as NewlineMode });
+          }}
+        >
+          <ToggleGroupItem value="keep" aria-label="Paste line breaks as-is">
+            Keep
+          </ToggleGroupItem>
+          <ToggleGroupItem value="shiftEnter" aria-label="Type Shift+Enter for line breaks">
+            Shift+Enter
+          </ToggleGroupItem>
+          <ToggleGroupItem value="space" aria-label="Join lines with spaces">
+            Spaces
+          </ToggleGroupItem>
+        </ToggleGroup>
+      </div>
+
+      <div className="flex items-center justify-between gap-4">
+        <div>
+          <Label className="text-sm">Strip Trailing Newline</Label>
+          <p className="text-xs text-muted-foreground mt-0.5">
+            Don&apos;t end the paste with a line break
+          </p>
+        </div>
+        <Switch
+          checked={profile.stripTrailingNewline}
+          onCheckedChange={(checked) => onSave({ ...profile, stripTrailingNewline: checked })}
+        />
+      </div>
+    </motion.div>
+  );
+}
+
+export default function AppProfilesSettings() {
+  const [profiles, setProfiles] = useState<AppProfile[]>([]);
+  const [newApp, setNewApp] = useState("");
+
+  useEffect(() => {
+    listAppProfiles()
+      .then(setProfiles)
+      .catch(() => {
+        // Outside Tauri context
+      });
+  }, []);
+
+  async function save(profile: AppProfile) {
+    try {
+      const saved = await saveAppProfile(profile);
+      setProfiles((prev) =>
+        prev.some((p) => p.id === saved.id)
+          ? prev.map((p) => (p.id === saved.id ? saved : p))
+          : [...prev, saved],
+      );
+    } catch (err) {
+      toast.error("Couldn't save app profile", { description: String(err) });
+    }
+  }
+
+  async function remove(id: string) {
+    try {
+      await deleteAppProfile(id);
+      setProfiles((prev) => prev.filter((p) => p.id !== id));
+    } catch (err) {
+      toast.error("Couldn't delete app profile", { description: String(err) });
+    }
+  }
+
+  async function handleAdd() {
+    const appName = newApp.trim();
+    if (!appName) return;
+    if (profiles.some((p) => p.appName.toLowerCase() === appName.toLowerCase())) {
+      toast.error("That app already has a profile");
+      return;
+    }
+    await save({
+      id: "",
+      appName,
+      newlineMode: "shiftEnter",
+      stripTrailingNewline: true,
+      createdAt: "",
+    });
+    setNewApp("");
+  }
+
+  return (
+    <div className="space-y-8">
+      <div>
+        <h3 className="text-lg font-semibold">App Profiles</h3>
+        <p className="text-sm text-muted-foreground">
+          Adjust pasted text for the app it goes into, e.g. so Slack doesn&apos;t send a
+          message at the first line break.
+        </p>
+      </div>
+
+      <div className="flex items-center gap-2">
+        <Input
+          placeholder="App name, e.g. Slack"
+          value={newApp}
+          onChange={(e) => setNewApp(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+          className="text-sm"
+        />
+        <Button
+          variant="ghost"
+          size="sm"
+          className="text-xs gap-1.5 shrink-0"
+          onClick={handleAdd}
+          disabled={!newApp.trim()}
+        >
+          <Plus className="h-3.5 w-3.5" />
+          Add App
+        </Button>
+      </div>
+
+      {profiles.length === 0 ? (
+        <div className="rounded-xl border border-dashed p-6 text-center">
+          <AppWindow className="h-5 w-5 mx-auto text-muted-foreground/60" />
+          <p className="text-sm text-muted-foreground mt-2">No app profiles yet.</p>
+        </div>
+      ) : (
+        <div className="space-y-3">
+          {profiles.map((profile, i) => (
+            <ProfileCard
+              key={profile.id}
+              profile={profile}
+              index={i}
+              onSave={save}
+              onDelete={() => remove(profile.id)}
+            />
+          ))}
+        </div>
+      )}
+
+      <p className="text-xs text-muted-foreground">
+        Use the name shown in Activity Monitor on macOS, or the program&apos;s .exe name
+        without the extension on Windows. Profiles don&apos;t apply on Linux, where the app in
+        front can&apos;t be detected.
+      </p>
+    </div>
+  );
+}
@@ -0,0 +1,306 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { Footprints, Loader2, Plus, RefreshCw, Trash2 } from "lucide-react";
+import { motion } from "motion/react";
+import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import {
+  captureHidButton,
+  deleteDeviceBinding,
+  listDeviceBindings,
+  listHidDevices,
+  listHotkeyBindings,
+  saveDeviceBinding,
+  type DeviceBinding,
+  type HidDevice,
+  type HotkeyBinding,
+} from "@/lib/tauri-commands";
+
+const BUILT_IN_ACTIONS = [
+  { value: "dictation", label: "Dictation" },
+  { value: "paste-last", label: "Paste last transcript" },
+  { value: "undo-paste", label: "Undo last paste" },
+  { value: "rewrite-selection", label: "Rewrite selection" },
+];
+
+function deviceKey(device: { vendorId: number; productId: number }): string {
+  return `${device.vendorId}:${device.productId}`;
+}
+
+function hex(id: number): string {
+  return id.toString(16).padStart(4, "0");
+}
+
+/** Wait for a press on the device; toasts when nothing was pressed. */
+async function capture(device: { vendorId: number; productId: number }) {
+  const button = await captureHidButton(device.vendorId, device.productId);
+  if (button === null) toast.error("No button press detected");
+  return button;
+}
+
+function BindingCard({
+  binding,
+  actions,
+  index,
+  onSave,
+  onDelete,
+}: {
+  binding: DeviceBinding;
+  actions: { value: string; label: string }[];
+  index: number;
+  onSave: (binding: DeviceBinding) => void;
+  onDelete: () => void;
+}) {
+  const [capturing, setCapturing] = useState(false);
+
+  async function recapture() {
+    setCapturing(true);
+    try {
+      const button = await capture(binding);
+      if (button !== null) onSave({ ...binding, button });
+    } catch (err) {
+      toast.error("Couldn't read the device", { description: String(err) });
+    } finally {
+      setCapturing(false);
+    }
+  }
+
+  return (
+    <motion.div
+      initial={{ opacity: 0, y: 8 }}
+      animate={{ opacity: 1, y: 0 }}
+      transition={{ delay: index * 0.04 }}
+      className="rounded-xl border p-4 space-y-3"
+    >
+      <div className="flex items-center gap-3">
+        <div className="flex items-center justify-center h-8 w-8 rounded-lg shrink-0 bg-primary/10 text-primary">
+          <Footprints className="h-4 w-4" />
+        </div>
+        <div className="flex-1 min-w-0">
+          <p className="truncate text-sm font-medium">{binding.name}</p>
+          <p className="text-xs text-muted-foreground font-mono">
+            {hex(binding.vendorId)}:{hex(binding.productId)} · button {binding.button}
+          </p>
+        </div>
+        <Switch
+          checked={binding.enabled}
+          onCheckedChange={(enabled) => onSave({ ...binding, enabled })}
+          aria-label="Use this button"
+        />
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 w-7 p-0 shrink-0 text-muted-foreground/40 hover:text-destructive"
+          aria-label="Delete device binding"
+          onClick={onDelete}
+        >
+          <Trash2 className="h-3.5 w-3.5" />
+        </Button>
+      </div>
+
+      <div className="flex items-center justify-between gap-4">
+        <Label className="text-sm">Action</Label>
+        <Select value={binding.action} onValueChange={(action) => onSave({ ...binding, action })}>
+          <SelectTrigger className="w-56" aria-label="Action">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent position="popper" className="max-h-60">
+            {actions.map((a) => (
+              <SelectItem key={a.value} value={a.value}>
+                {a.label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+
+      <div className="flex items-center justify-between gap-4">
+        <div>
+          <Label className="text-sm">Button</Label>
+          <p className="text-xs text-muted-foreground mt-0.5">
+            {capturing ? "Press the pedal or button now…" : "Press it again to change which one"}
+          </p>
+        </div>
+        <Button
+          variant="outline"
+          size="sm"
+          className="text-xs gap-1.5"
+          onClick={recapture}
+          disabled={capturing}
+        >
+          {capturing ? (
+            <Loader2 className="h-3.5 w-3.5 animate-spin" />
+          ) : (
+            <RefreshCw className="h-3.5 w-3.5" />
+          )}
+          Detect
+        </Button>
+      </div>
+    </motion.div>
+  );
+}
+
+export default function DevicesSettings() {
+  const [bindings, setBindings] = useState<DeviceBinding[]>([]);
+  const [devices, setDevices] = useState<HidDevice[]>([]);
+  const [presets, setPresets] = useState<HotkeyBinding[]>([]);
+  const [selected, setSelected] = useState<string>("");
+  const [capturing, setCapturing] = useState(false);
+
+  function refreshDevices() {
+    listHidDevices()
+      .then(setDevices)
+      .catch(() => {
+        // Outside Tauri context
+      });
+  }
+
+  useEffect(() => {
+    listDeviceBindings()
+      .then(setBindings)
+      .catch(() => {
+        // Outside Tauri context
+      });
+    listHotkeyBindings()
+      .then(setPresets)
+      .catch(() => {});
+    refreshDevices();
+  }, []);
+
+  const actions = [
+    ...BUILT_IN_ACTIONS,
+    ...presets.map((p) => ({ value: `binding:${p.id}`, label: `Preset (${p.hotkey})` })),
+  ];
+
+  async function save(binding: DeviceBinding) {
+    try {
+      const saved = await saveDeviceBinding(binding);
+      setBindings((prev) =>
+        prev.some((b) => b.id === saved.id)
+          ? prev.map((b) => (b.id === saved.id ? saved : b))
+          : [...prev, saved],
+      );
+    } catch (err) {
+      toast.error("Couldn't save device binding", { description: String(err) });
+    }
+  }
+
+  async function remove(id: string) {
+    try {
+      await deleteDeviceBinding(id);
+      setBindings((prev) => prev.filter((b) => b.id !== id));
+    } catch (err) {
+      toast.error("Couldn't delete device binding", { description: String(err) });
+    }
+  }
+
+  async function handleAdd() {
+    const device = devices.find((d) => deviceKey(d) === selected);
+    if (!device) return;
+    setCapturing(true);
+    try {
+      const button = await capture(device);
+      if (button === null) return;
+      await save({
+        id: "",
+        name: device.name,
+        vendorId: device.vendorId,
+        productId: device.productId,
+        button,
+        action: "dictation",
+        enabled: true,
+        createdAt: "",
+      });
+    } catch (err) {
+      toast.error("Couldn't read the device", { description: String(err) });
+    } finally {
+      setCapturing(false);
+    }
+  }
+
+  return (
+    <div className="space-y-8">
+      <div>
+        <h3 className="text-lg font-semibold">Foot Pedals</h3>
+        <p className="text-sm text-muted-foreground">
+          Use a USB foot pedal or another HID button like a hotkey. Held pedals work as
+          push-to-talk.
+        </p>
+      </div>
+
+      <div className="flex items-center gap-2">
+        <Select value={selected} onValueChange={setSelected}>
+          <SelectTrigger className="flex-1" aria-label="Device">
+            <SelectValue placeholder="Choose a connected device" />
+          </SelectTrigger>
+          <SelectContent position="popper" className="max-h-60">
+            {devices.map((d) => (
+              <SelectItem key={deviceKey(d)} value={deviceKey(d)}>
+                {d.name} ({hex(d.vendorId)}:{hex(d.productId)})
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-8 w-8 p-0 shrink-0"
+          aria-label="Refresh devices"
+          onClick={refreshDevices}
+        >
+          <RefreshCw className="h-3.5 w-3.5" />
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="text-xs gap-1.5 shrink-0"
+          onClick={handleAdd}
+          disabled={!selected || capturing}
+        >
+          {capturing ? (
+            <Loader2 className="h-3.5 w-3.5 animate-spin" />
+          ) : (
+            <Plus className="h-3.5 w-3.5" />
+          )}
+          {capturing ? "Press a pedal…" : "Add Button"}
+        </Button>
+      </div>
+
+      {bindings.length === 0 ? (
+        <div className="rounded-xl border border-dashed p-6 text-center">
+          <Footprints className="h-5 w-5 mx-auto text-muted-foreground/60" />
+          <p className="text-sm text-muted-foreground mt-2">No pedals or buttons set up yet.</p>
+        </div>
+      ) : (
+        <div className="space-y-3">
+          {bindings.map((binding, i) => (
+            <BindingCard
+              key={binding.id}
+              binding={binding}
+              actions={actions}
+              index={i}
+              onSave={save}
+              onDelete={() => remove(binding.id)}
+            />
+          ))}
+        </div>
+      )}
+
+      <p className="text-xs text-muted-foreground">
+        Push-to-talk follows the recording mode, so in toggle mode a press starts and the next
+        one stops. On Linux the device needs a udev rule giving your user access to its hidraw
+        node.
+      </p>
+    </div>
+  );
+}
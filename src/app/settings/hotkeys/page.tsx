@@ -1,14 +1,40 @@
 "use client";
 
 import { useState, useCallback, useEffect, useMemo } from "react";
-import { KeyboardMusic, ToggleLeft, Circle, Check } from "lucide-react";
+import { KeyboardMusic, ToggleLeft, Mic, Circle, Check, X, Plus } from "lucide-react";
 import { motion, AnimatePresence } from "motion/react";
+import { toast } from "sonner";
 import { Button } from "@/components/ui/button";
 import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { Switch } from "@/components/ui/switch";
 import { ToggleGroup, ToggleGroupItem } from "@/components/ui/toggle-group";
 import { cn } from "@/lib/utils";
-import { useSettingsStore } from "@/stores/settings-store";
+import {
+  useSettingsStore,
+  type HotkeyBackend,
+  type MouseButton,
+  type RecordingMode,
+} from "@/stores/settings-store";
 import { useIsMac, parseHotkeyKeys, getHotkeyPresets } from "@/lib/hotkey-utils";
+import {
+  listAiFunctions,
+  listHotkeyBindings,
+  listPipelines,
+  saveHotkeyBinding,
+  deleteHotkeyBinding,
+  type AiFunction,
+  type HotkeyBinding,
+  type Pipeline,
+} from "@/lib/tauri-commands";
+import { LOCAL_MODELS, CLOUD_MODELS } from "@/components/model-selector";
+import { LANGUAGES } from "@/components/language-selector";
 
 /** Render a single keyboard key as a styled badge */
 function Kbd({ children }: { children: React.ReactNode }) {
@@ -19,12 +45,342 @@ function Kbd({ children }: { children: React.ReactNode }) {
   );
 }
 
+const MODIFIER_CODES = ["ControlLeft", "ControlRight", "ShiftLeft", "ShiftRight", "AltLeft", "AltRight", "MetaLeft", "MetaRight"];
+
+/** Build a Tauri accelerator from a keydown, or null for a bare modifier. */
+function shortcutFromEvent(e: KeyboardEvent): string | null {
+  // Skip bare modifier keys — wait for the actual key
+  if (MODIFIER_CODES.includes(e.code)) return null;
+
+  const parts: string[] = [];
+  if (e.metaKey || e.ctrlKey) parts.push("CommandOrControl");
+  if (e.altKey) parts.push("Alt");
+  if (e.shiftKey) parts.push("Shift");
+
+  // Use e.code for the physical key — e.key returns wrong chars
+  // when modifiers are held (e.g. Option+K = ˚ on macOS)
+  // e.code gives: KeyA, Digit1, Space, F1, ArrowUp, Enter, etc.
+  // Tauri's parser accepts these directly
+  parts.push(e.code);
+  return parts.join("+");
+}
+
+/** Record, change or clear an optional global hotkey. */
+function OptionalHotkey({
+  hotkey,
+  onChange,
+  clearLabel,
+}: {
+  hotkey: string | null;
+  onChange: (hotkey: string | null) => void;
+  clearLabel: string;
+}) {
+  const [recording, setRecording] = useState(false);
+  const isMac = useIsMac();
+
+  const handleKeyDown = useCallback(
+    (e: KeyboardEvent) => {
+      e.preventDefault();
+      e.stopPropagation();
+
+      if (e.code === "Escape") {
+        setRecording(false);
+        return;
+      }
+
+      const shortcut = shortcutFromEvent(e);
+      if (!shortcut) return;
+      onChange(shortcut);
+      setRecording(false);
+    },
+    [onChange],
+  );
+
+  useEffect(() => {
+    if (recording) {
+      window.addEventListener("keydown", handleKeyDown, true);
+      return () => window.removeEventListener("keydown", handleKeyDown, true);
+    }
+  }, [recording, handleKeyDown]);
+
+  if (recording) {
+    return (
+      <div className="flex items-center gap-2">
+        <span className="text-xs font-medium text-primary">Press keys...</span>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs"
+          onClick={() => setRecording(false)}
+        >
+          Cancel
+        </Button>
+      </div>
+    );
+  }
+
+  if (!hotkey) {
+    return (
+      <Button
+        variant="outline"
+        size="sm"
+        className="h-7 text-xs"
+        onClick={() => setRecording(true)}
+      >
+        <Circle className="h-3 w-3 mr-1" />
+        Record
+      </Button>
+    );
+  }
+
+  return (
+    <div className="flex items-center gap-2">
+      <div className="flex items-center gap-1.5">
+        {parseHotkeyKeys(hotkey, isMac).map((k, i) => (
+          <span key={i} className="contents">
+            {i > 0 && (
+              <span className="text-[10px] text-muted-foreground/50 font-medium">+</span>
+            )}
+            <Kbd>{k}</Kbd>
+          </span>
+        ))}
+      </div>
+      <Button
+        variant="ghost"
+        size="sm"
+        className="h-7 text-xs"
+        onClick={() => setRecording(true)}
+      >
+        Change
+      </Button>
+      <Button
+        variant="ghost"
+        size="sm"
+        className="h-7 w-7 p-0"
+        aria-label={clearLabel}
+        onClick={() => onChange(null)}
+      >
+        <X className="h-3.5 w-3.5" />
+      </Button>
+    </div>
+  );
+}
+
+/** Select value standing in for "use the current setting" (null). */
+const USE_DEFAULT = "__default";
+
+/** Select for one preset field; `null` keeps the current setting. */
+function PresetSelect({
+  value,
+  onChange,
+  options,
+  label,
+}: {
+  value: string | null;
+  onChange: (value: string | null) => void;
+  options: { id: string; name: string }[];
+  label: string;
+}) {
+  return (
+    <Select
+      value={value ?? USE_DEFAULT}
+      onValueChange={(v) => onChange(v === USE_DEFAULT ? null : v)}
+    >
+      <SelectTrigger className="w-full" aria-label={label}>
+        <SelectValue />
+      </SelectTrigger>
+      <SelectContent position="popper" className="max-h-60">
+        <SelectItem value={USE_DEFAULT}>Current {label.toLowerCase()}</SelectItem>
+        {options.map((o) => (
+          <SelectItem key={o.id} value={o.id}>
+            {o.name}
+          </SelectItem>
+        ))}
+      </SelectContent>
+    </Select>
+  );
+}
+
+const MODEL_OPTIONS = [...LOCAL_MODELS, ...CLOUD_MODELS];
+const LANGUAGE_OPTIONS = LANGUAGES.map((l) => ({ id: l.code, name: l.name }));
+
+/**
+ * Extra dictation hotkeys, each with its own model, language, AI function
+ * and pipeline.
+ */
+function HotkeyBindings({ aiFunctions }: { aiFunctions: AiFunction[] }) {
+  const [bindings, setBindings] = useState<HotkeyBinding[]>([]);
+  const [pipelines, setPipelines] = useState<Pipeline[]>([]);
+  const [adding, setAdding] = useState(false);
+
+  useEffect(() => {
+    listHotkeyBindings()
+      .then(setBindings)
+      .catch(() => {
+        // Outside Tauri context
+      });
+    listPipelines()
+      .then(setPipelines)
+      .catch(() => {});
+  }, []);
+
+  async function save(binding: HotkeyBinding) {
+    try {
+      const saved = await saveHotkeyBinding(binding);
+      setBindings((prev) =>
+        prev.some((b) => b.id === saved.id)
+          ? prev.map((b) => (b.id === saved.id ? saved : b))
+          : [...prev, saved],
+      );
+    } catch (err) {
+      toast.error("Couldn't save preset hotkey", { description: String(err) });
+    }
+  }
+
+  async function remove(id: string) {
+    try {
+      await deleteHotkeyBinding(id);
+      setBindings((prev) => prev.filter((b) => b.id !== id));
+    } catch (err) {
+      toast.error("Couldn't remove preset hotkey", { description: String(err) });
+    }
+  }
+
+  const functionOptions = aiFunctions.map((fn) => ({ id: fn.id, name: fn.name }));
+  const pipelineOptions = pipelines.map((p) => ({ id: p.id, name: p.name }));
+
+  return (
+    <div className="space-y-3">
+      <Label className="text-xs font-medium uppercase tracking-wider text-muted-foreground">
+        Preset Hotkeys
+      </Label>
+      <div className="space-y-4 rounded-xl border p-4">
+        <p className="text-xs text-muted-foreground">
+          Dictate with a different model, language, AI function or pipeline — e.g. one
+          hotkey that formats what you say as an email. Follows the recording mode above.
+        </p>
+        {bindings.map((binding) => (
+          <div key={binding.id} className="space-y-2 rounded-lg border p-3">
+            <div className="flex items-center justify-end">
+              <OptionalHotkey
+                hotkey={binding.hotkey}
+                onChange={(hotkey) =>
+                  hotkey ? save({ ...binding, hotkey }) : remove(binding.id)
+                }
+                clearLabel="Remove preset hotkey"
+              />
+            </div>
+            <div className="grid grid-cols-2 gap-2">
+              <PresetSelect
+                label="Model"
+                value={binding.modelId}
+                onChange={(modelId) => save({ ...binding, modelId })}
+                options={MODEL_OPTIONS}
+              />
+              <PresetSelect
+                label="Language"
+                value={binding.language}
+                onChange={(language) => save({ ...binding, language })}
+                options={LANGUAGE_OPTIONS}
+              />
+              <PresetSelect
+                label="AI function"
+                value={binding.aiFunction}
+                onChange={(aiFunction) => save({ ...binding, aiFunction })}
+                options={functionOptions}
+              />
+              <PresetSelect
+                label="Pipeline"
+                value={binding.pipelineId}
+                onChange={(pipelineId) => save({ ...binding, pipelineId })}
+                options={pipelineOptions}
+              />
+            </div>
+          </div>
+        ))}
+        {adding ? (
+          <div className="flex items-center justify-between gap-4">
+            <p className="text-sm font-medium">New preset hotkey</p>
+            <div className="flex items-center gap-2">
+              <OptionalHotkey
+                hotkey={null}
+                onChange={(hotkey) => {
+                  setAdding(false);
+                  if (hotkey) {
+                    save({
+                      id: "",
+                      hotkey,
+                      modelId: null,
+                      language: null,
+                      aiFunction: null,
+                      pipelineId: null,
+                    });
+                  }
+                }}
+                clearLabel="Cancel"
+              />
+              <Button
+                variant="ghost"
+                size="sm"
+                className="h-7 text-xs"
+                onClick={() => setAdding(false)}
+              >
+                Cancel
+              </Button>
+            </div>
+          </div>
+        ) : (
+          <Button
+            variant="outline"
+            size="sm"
+            className="h-7 text-xs"
+            onClick={() => setAdding(true)}
+          >
+            <Plus className="h-3 w-3 mr-1" />
+            Add preset hotkey
+          </Button>
+        )}
+      </div>
+    </div>
+  );
+}
+
 export default function HotkeySettings() {
-  const { defaultHotkey, setDefaultHotkey, recordingMode, setRecordingMode } =
-    useSettingsStore();
+  const {
+    defaultHotkey,
+    setDefaultHotkey,
+    recordingMode,
+    setRecordingMode,
+    rewriteHotkey,
+    setRewriteHotkey,
+    rewriteAiFunction,
+    setRewriteAiFunction,
+    pasteLastHotkey,
+    setPasteLastHotkey,
+    undoPasteHotkey,
+    setUndoPasteHotkey,
+    hotkeyBackend,
+    setHotkeyBackend,
+    mouseButton,
+    setMouseButton,
+    doublePressAi,
+    setDoublePressAi,
+    minRecordingMs,
+    setMinRecordingMs,
+    handsFreeSilenceSecs,
+    setHandsFreeSilenceSecs,
+    liveTranscript,
+    setLiveTranscript,
+    codeSwitching,
+    setCodeSwitching,
+    codeSwitchLanguages,
+    setCodeSwitchLanguages,
+  } = useSettingsStore();
   const [recording, setRecording] = useState(false);
   const [recordedKeys, setRecordedKeys] = useState<string | null>(null);
+  const [aiFunctions, setAiFunctions] = useState<AiFunction[]>([]);
   const isMac = useIsMac();
+  const isLinux = typeof navigator !== "undefined" && navigator.userAgent.includes("Linux");
   const presets = useMemo(() => getHotkeyPresets(isMac), [isMac]);
 
   const handleKeyDown = useCallback(
@@ -33,27 +389,15 @@ export default function HotkeySettings() {
       e.preventDefault();
       e.stopPropagation();
 
-      // Skip bare modifier keys — wait for the actual key
-      const modifierCodes = ["ControlLeft", "ControlRight", "ShiftLeft", "ShiftRight", "AltLeft", "AltRight", "MetaLeft", "MetaRight"];
-      if (modifierCodes.includes(e.code)) return;
-
       // Escape cancels recording
       if (e.code === "Escape") {
         setRecording(false);
         return;
       }
 
-      const parts: string[] = [];
-      if (e.metaKey || e.ctrlKey) parts.push("CommandOrControl");
-      if (e.altKey) parts.push("Alt");
-      if (e.shiftKey) parts.push("Shift");
-
-      // Use e.code for the physical key — e.key returns wrong chars
-      // when modifiers are held (e.g. Option+K = ˚ on macOS)
-      // e.code gives: KeyA, Digit1, Space, F1, ArrowUp, Enter, etc.
-      // Tauri's parser accepts these directly
-      parts.push(e.code);
-      setRecordedKeys(parts.join("+"));
+      const shortcut = shortcutFromEvent(e);
+      if (!shortcut) return;
+      setRecordedKeys(shortcut);
       setRecording(false);
     },
     [recording],
@@ -66,6 +410,14 @@ export default function HotkeySettings() {
     }
   }, [recording, handleKeyDown]);
 
+  useEffect(() => {
+    listAiFunctions()
+      .then(setAiFunctions)
+      .catch(() => {
+        // Outside Tauri context
+      });
+  }, []);
+
   function applyRecordedHotkey() {
     if (recordedKeys) {
       setDefaultHotkey(recordedKeys);
@@ -281,7 +633,9 @@ export default function HotkeySettings() {
             <p className="text-xs text-muted-foreground mt-0.5">
               {recordingMode === "push-to-talk"
                 ? "Hold the hotkey to record, release to stop"
-                : "Press once to start, press again to stop"}
+                : recordingMode === "hands-free"
+                  ? "Press once to start, stops by itself when you stop talking"
+                  : "Press once to start, press again to stop"}
             </p>
           </div>
           <ToggleGroup
@@ -289,7 +643,7 @@ export default function HotkeySettings() {
             variant="outline"
             value={recordingMode}
             onValueChange={(v) => {
-              if (v) setRecordingMode(v as "push-to-talk" | "toggle");
+              if (v) setRecordingMode(v as RecordingMode);
             }}
           >
             <ToggleGroupItem value="push-to-talk" aria-label="Push to Talk">
@@ -300,9 +654,253 @@ export default function HotkeySettings() {
               <ToggleLeft className="h-4 w-4 mr-1.5" />
               Toggle
             </ToggleGroupItem>
+            <ToggleGroupItem value="hands-free" aria-label="Hands-free">
+              <Mic className="h-4 w-4 mr-1.5" />
+              Hands-free
+            </ToggleGroupItem>
           </ToggleGroup>
         </div>
+        {recordingMode === "hands-free" && (
+          <div className="flex items-center justify-between gap-4 rounded-xl border p-4">
+            <div>
+              <p className="text-sm font-medium">Stop after silence</p>
+              <p className="text-xs text-muted-foreground mt-0.5">
+                How long a pause ends a hands-free recording
+              </p>
+            </div>
+            <Select
+              value={String(handsFreeSilenceSecs)}
+              onValueChange={(v) => setHandsFreeSilenceSecs(Number(v))}
+            >
+              <SelectTrigger className="w-48">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent position="popper">
+                <SelectItem value="1">1 second</SelectItem>
+                <SelectItem value="1.5">1.5 seconds</SelectItem>
+                <SelectItem value="2">2 seconds</SelectItem>
+                <SelectItem value="3">3 seconds</SelectItem>
+                <SelectItem value="5">5 seconds</SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
+        )}
+        {/* Accidental taps */}
+        <div className="flex items-center justify-between gap-4 rounded-xl border p-4">
+          <div>
+            <p className="text-sm font-medium">Ignore short taps</p>
+            <p className="text-xs text-muted-foreground mt-0.5">
+              Recordings shorter than this are discarded instead of transcribed
+            </p>
+          </div>
+          <Select
+            value={String(minRecordingMs)}
+            onValueChange={(v) => setMinRecordingMs(Number(v))}
+          >
+            <SelectTrigger className="w-48">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent position="popper">
+              <SelectItem value="0">Off</SelectItem>
+              <SelectItem value="150">150 ms</SelectItem>
+              <SelectItem value="300">300 ms</SelectItem>
+              <SelectItem value="500">500 ms</SelectItem>
+              <SelectItem value="1000">1 second</SelectItem>
+            </SelectContent>
+          </Select>
+        </div>
+        {/* Double-press */}
+        <div className="flex items-center justify-between gap-4 rounded-xl border p-4">
+          <div>
+            <p className="text-sm font-medium">Double-press for AI</p>
+            <p className="text-xs text-muted-foreground mt-0.5">
+              Press the dictation hotkey twice quickly to dictate and run the AI function you
+              used last. A single press or hold dictates as usual.
+            </p>
+          </div>
+          <Switch checked={doublePressAi} onCheckedChange={setDoublePressAi} />
+        </div>
+        {/* Live transcript */}
+        <div className="flex items-center justify-between gap-4 rounded-xl border p-4">
+          <div>
+            <p className="text-sm font-medium">Live transcript</p>
+            <p className="text-xs text-muted-foreground mt-0.5">
+              Show what&apos;s being recognized in the recording bar as you speak. Uses a
+              local model and extra CPU while recording.
+            </p>
+          </div>
+          <Switch checked={liveTranscript} onCheckedChange={setLiveTranscript} />
+        </div>
+        {/* Code-switching */}
+        <div className="space-y-3 rounded-xl border p-4">
+          <div className="flex items-center justify-between gap-4">
+            <div>
+              <p className="text-sm font-medium">Mixed-language speech</p>
+              <p className="text-xs text-muted-foreground mt-0.5">
+                Detect the language of each phrase between pauses, for switching languages
+                mid-sentence. Local Whisper models only; slower than a fixed language.
+              </p>
+            </div>
+            <Switch checked={codeSwitching} onCheckedChange={setCodeSwitching} />
+          </div>
+          {codeSwitching && (
+            <div className="space-y-1.5">
+              <p className="text-xs text-muted-foreground">
+                Languages you speak (none selected allows any)
+              </p>
+              <ToggleGroup
+                type="multiple"
+                variant="outline"
+                size="sm"
+                value={codeSwitchLanguages}
+                onValueChange={setCodeSwitchLanguages}
+                className="flex-wrap justify-start"
+              >
+                {LANGUAGES.filter((l) => l.code !== "auto").map((lang) => (
+                  <ToggleGroupItem key={lang.code} value={lang.code} className="text-xs px-2">
+                    {lang.name}
+                  </ToggleGroupItem>
+                ))}
+              </ToggleGroup>
+            </div>
+          )}
+        </div>
+      </div>
+
+      {/* Mouse Button */}
+      <div className="space-y-3">
+        <Label className="text-xs font-medium uppercase tracking-wider text-muted-foreground">
+          Mouse Button
+        </Label>
+        <div className="flex items-center justify-between gap-4 rounded-xl border p-4">
+          <div>
+            <p className="text-sm font-medium">Dictate with a mouse button</p>
+            <p className="text-xs text-muted-foreground mt-0.5">
+              Works like the dictation hotkey, for apps where every key combination is
+              taken. {isLinux ? "Needs an X11 session." : "The button stops doing anything else."}
+            </p>
+          </div>
+          <Select value={mouseButton} onValueChange={(v) => setMouseButton(v as MouseButton)}>
+            <SelectTrigger className="w-48">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent position="popper">
+              <SelectItem value="none">Off</SelectItem>
+              <SelectItem value="middle">Middle button</SelectItem>
+              <SelectItem value="back">Back button (M4)</SelectItem>
+              <SelectItem value="forward">Forward button (M5)</SelectItem>
+            </SelectContent>
+          </Select>
+        </div>
       </div>
+
+      {/* Hotkey Backend (Linux: native grabs don't work on most Wayland compositors) */}
+      {isLinux && (
+        <div className="space-y-3">
+          <Label className="text-xs font-medium uppercase tracking-wider text-muted-foreground">
+            Hotkey Backend
+          </Label>
+          <div className="flex items-center justify-between gap-4 rounded-xl border p-4">
+            <div>
+              <p className="text-sm font-medium">How hotkeys are captured</p>
+              <p className="text-xs text-muted-foreground mt-0.5">
+                Use the desktop portal if hotkeys don&apos;t respond under Wayland. Your
+                desktop may ask you to confirm the shortcuts.
+              </p>
+            </div>
+            <Select
+              value={hotkeyBackend}
+              onValueChange={(v) => setHotkeyBackend(v as HotkeyBackend)}
+            >
+              <SelectTrigger className="w-48">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent position="popper">
+                <SelectItem value="auto">Automatic</SelectItem>
+                <SelectItem value="native">Native (X11)</SelectItem>
+                <SelectItem value="portal">Desktop portal (Wayland)</SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
+        </div>
+      )}
+
+      {/* Rewrite Selection */}
+      <div className="space-y-3">
+        <Label className="text-xs font-medium uppercase tracking-wider text-muted-foreground">
+          Rewrite Selection
+        </Label>
+        <div className="space-y-4 rounded-xl border p-4">
+          <div className="flex items-center justify-between gap-4">
+            <div>
+              <p className="text-sm font-medium">Rewrite hotkey</p>
+              <p className="text-xs text-muted-foreground mt-0.5">
+                Runs an AI function on the selected text and pastes the result over it
+              </p>
+            </div>
+            <OptionalHotkey
+              hotkey={rewriteHotkey}
+              onChange={setRewriteHotkey}
+              clearLabel="Clear rewrite hotkey"
+            />
+          </div>
+          <div className="flex items-center justify-between gap-4">
+            <div>
+              <p className="text-sm font-medium">AI function</p>
+              <p className="text-xs text-muted-foreground mt-0.5">
+                Uses the LLM provider configured under AI Functions
+              </p>
+            </div>
+            <Select value={rewriteAiFunction} onValueChange={setRewriteAiFunction}>
+              <SelectTrigger className="w-48">
+                <SelectValue placeholder="Select function" />
+              </SelectTrigger>
+              <SelectContent position="popper" className="max-h-60">
+                {aiFunctions.map((fn) => (
+                  <SelectItem key={fn.id} value={fn.id}>
+                    {fn.name}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+        </div>
+      </div>
+
+      {/* Last Transcript */}
+      <div className="space-y-3">
+        <Label className="text-xs font-medium uppercase tracking-wider text-muted-foreground">
+          Last Transcript
+        </Label>
+        <div className="flex items-center justify-between gap-4 rounded-xl border p-4">
+          <div>
+            <p className="text-sm font-medium">Paste-last hotkey</p>
+            <p className="text-xs text-muted-foreground mt-0.5">
+              Pastes your most recent dictation again into the focused app
+            </p>
+          </div>
+          <OptionalHotkey
+            hotkey={pasteLastHotkey}
+            onChange={setPasteLastHotkey}
+            clearLabel="Clear paste-last hotkey"
+          />
+        </div>
+        <div className="flex items-center justify-between gap-4 rounded-xl border p-4">
+          <div>
+            <p className="text-sm font-medium">Undo-paste hotkey</p>
+            <p className="text-xs text-muted-foreground mt-0.5">
+              Deletes the text you just pasted, for when the transcription came out wrong
+            </p>
+          </div>
+          <OptionalHotkey
+            hotkey={undoPasteHotkey}
+            onChange={setUndoPasteHotkey}
+            clearLabel="Clear undo-paste hotkey"
+          />
+        </div>
+      </div>
+
+      <HotkeyBindings aiFunctions={aiFunctions} />
     </div>
   );
 }
@@ -11,6 +11,10 @@ import {
   BookOpen,
   Key,
   ArrowUpCircle,
+  Workflow,
+  FolderSync,
+  AppWindow,
+  Footprints,
 } from "lucide-react";
 import { motion } from "motion/react";
 import { cn } from "@/lib/utils";
@@ -20,9 +24,13 @@ import { getAppVersion } from "@/lib/tauri-commands";
 const NAV_ITEMS = [
   { href: "/settings", label: "General", icon: Settings },
   { href: "/settings/hotkeys", label: "Hotkeys", icon: Keyboard },
+  { href: "/settings/devices", label: "Foot Pedals", icon: Footprints },
   { href: "/settings/models", label: "Models", icon: Box },
   { href: "/settings/rules", label: "Rules", icon: Wand2 },
   { href: "/settings/ai-functions", label: "AI Functions", icon: Sparkles },
+  { href: "/settings/pipelines", label: "Pipelines", icon: Workflow },
+  { href: "/settings/watch-folders", label: "Watched Folders", icon: FolderSync },
+  { href: "/settings/app-profiles", label: "App Profiles", icon: AppWindow },
   { href: "/settings/vocabulary", label: "Vocabulary", icon: BookOpen },
   { href: "/settings/providers", label: "API Keys", icon: Key },
   { href: "/settings/update", label: "Update", icon: ArrowUpCircle },
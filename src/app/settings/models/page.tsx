@@ -12,10 +12,31 @@ import {
   CheckCircle2,
   HardDrive,
   Zap,
+  Gauge,
+  FileAudio,
+  FolderX,
+  SlidersHorizontal,
+  AudioWaveform,
+  Pilcrow,
 } from "lucide-react";
 import { Button } from "@/components/ui/button";
+import { Switch } from "@/components/ui/switch";
+import { Textarea } from "@/components/ui/textarea";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
 import { cn } from "@/lib/utils";
 import * as commands from "@/lib/tauri-commands";
+import {
+  useSettingsStore,
+  DEFAULT_DECODING_OPTIONS,
+  type DecodingOptions,
+  type ParakeetProvider,
+} from "@/stores/settings-store";
 
 async function tauriListen<T>(
   event: string,
@@ -78,6 +99,595 @@ function formatSize(bytes: number): string {
   return `${(bytes / 1_000_000).toFixed(0)} MB`;
 }
 
+/** Run a reference clip through downloaded models to compare speed and accuracy. */
+function ModelBenchmark({ models }: { models: ModelStatus[] }) {
+  const [modelId, setModelId] = useState(models[0]?.id ?? "");
+  const [audioPath, setAudioPath] = useState<string | null>(null);
+  const [referenceText, setReferenceText] = useState("");
+  const [running, setRunning] = useState(false);
+  const [results, setResults] = useState<commands.BenchmarkResult[]>([]);
+
+  async function pickClip() {
+    try {
+      const { open } = await import("@tauri-apps/plugin-dialog");
+      const selected = await open({
+        multiple: false,
+        filters: [{ name: "WAV audio", extensions: ["wav"] }],
+      });
+      if (typeof selected === "string") setAudioPath(selected);
+    } catch {
+      // Outside Tauri context
+    }
+  }
+
+  async function run() {
+    if (!audioPath || !modelId) return;
+    setRunning(true);
+    try {
+      const result = await commands.benchmarkModel({
+        modelId,
+        audioPath,
+        referenceText: referenceText.trim() || null,
+      });
+      setResults((prev) => [result, ...prev.filter((r) => r.modelId !== result.modelId)]);
+    } catch (err) {
+      toast.error("Benchmark failed", { description: commands.errorMessage(err) });
+    } finally {
+      setRunning(false);
+    }
+  }
+
+  const modelName = (id: string) => models.find((m) => m.id === id)?.name ?? id;
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center gap-2 px-1">
+        <Gauge className="h-3.5 w-3.5 text-muted-foreground/60" />
+        <span className="text-[11px] font-medium uppercase tracking-wider text-muted-foreground/60">
+          Benchmark
+        </span>
+        <div className="flex-1 h-px bg-border/50" />
+      </div>
+      <div className="rounded-xl border p-4 space-y-3">
+        <p className="text-xs text-muted-foreground">
+          Transcribe a reference recording to compare speed and accuracy on this machine.
+          Add the exact words spoken to get a word error rate.
+        </p>
+        <div className="flex items-center gap-2">
+          <Select value={modelId} onValueChange={setModelId}>
+            <SelectTrigger className="w-48 text-xs">
+              <SelectValue placeholder="Select model" />
+            </SelectTrigger>
+            <SelectContent position="popper">
+              {models.map((m) => (
+                <SelectItem key={m.id} value={m.id} className="text-xs">
+                  {m.name}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+          <Button variant="outline" size="sm" className="h-8 text-xs gap-1.5 min-w-0" onClick={pickClip}>
+            <FileAudio className="h-3.5 w-3.5 shrink-0" />
+            <span className="truncate max-w-48">
+              {audioPath ? audioPath.split(/[\\/]/).pop() : "Choose WAV clip"}
+            </span>
+          </Button>
+          <Button
+            size="sm"
+            className="h-8 text-xs ml-auto"
+            disabled={!audioPath || !modelId || running}
+            onClick={run}
+          >
+            {running && <Loader2 className="h-3.5 w-3.5 mr-1 animate-spin" />}
+            Run
+          </Button>
+        </div>
+        <Textarea
+          placeholder="Reference text (optional)"
+          value={referenceText}
+          onChange={(e) => setReferenceText(e.target.value)}
+          className="text-xs min-h-16"
+        />
+        {results.length > 0 && (
+          <table className="w-full text-xs">
+            <thead className="text-muted-foreground">
+              <tr className="text-left">
+                <th className="font-medium py-1">Model</th>
+                <th className="font-medium py-1">Speed</th>
+                <th className="font-medium py-1">Load</th>
+                <th className="font-medium py-1">Memory</th>
+                <th className="font-medium py-1">WER</th>
+              </tr>
+            </thead>
+            <tbody>
+              {results.map((r) => (
+                <tr key={r.modelId} className="border-t border-border/50" title={r.transcript}>
+                  <td className="py-1.5">{modelName(r.modelId)}</td>
+                  <td className="py-1.5 tabular-nums">
+                    {r.realTimeFactor > 0 ? `${(1 / r.realTimeFactor).toFixed(1)}× real time` : "—"}
+                  </td>
+                  <td className="py-1.5 tabular-nums">{(r.loadMs / 1000).toFixed(1)} s</td>
+                  <td className="py-1.5 tabular-nums">
+                    {r.memoryBytes !== null ? formatSize(r.memoryBytes) : "—"}
+                  </td>
+                  <td className="py-1.5 tabular-nums">
+                    {r.wordErrorRate !== null ? `${(r.wordErrorRate * 100).toFixed(1)}%` : "—"}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </div>
+    </div>
+  );
+}
+
+const DECODING_CHOICES: {
+  key: keyof DecodingOptions;
+  label: string;
+  description: string;
+  options: { value: number; label: string }[];
+}[] = [
+  {
+    key: "beamSize",
+    label: "Beam size",
+    description: "Wider beams are more accurate but slower",
+    options: [
+      { value: 1, label: "1 (greedy)" },
+      { value: 2, label: "2" },
+      { value: 5, label: "5" },
+      { value: 8, label: "8" },
+    ],
+  },
+  {
+    key: "temperatureInc",
+    label: "Temperature fallback",
+    description: "Retry uncertain segments with more randomness",
+    options: [
+      { value: 0, label: "Off" },
+      { value: 0.2, label: "0.2 steps" },
+      { value: 0.4, label: "0.4 steps" },
+    ],
+  },
+  {
+    key: "noSpeechThold",
+    label: "No-speech threshold",
+    description: "Lower drops more segments that look like silence",
+    options: [
+      { value: 0.4, label: "0.4 (strict)" },
+      { value: 0.6, label: "0.6" },
+      { value: 0.8, label: "0.8 (lenient)" },
+    ],
+  },
+  {
+    key: "entropyThold",
+    label: "Entropy threshold",
+    description: "Lower rejects more repetitive, hallucinated output",
+    options: [
+      { value: 2.0, label: "2.0 (strict)" },
+      { value: 2.4, label: "2.4" },
+      { value: 2.8, label: "2.8 (lenient)" },
+    ],
+  },
+];
+
+const THREAD_CHOICES = [0, 1, 2, 4, 6, 8, 12, 16];
+
+const TIME_LIMIT_CHOICES = [
+  { value: 60, label: "1 min" },
+  { value: 120, label: "2 min" },
+  { value: 300, label: "5 min" },
+  { value: 600, label: "10 min" },
+  { value: 0, label: "No limit" },
+];
+
+const PROVIDER_LABELS: Record<ParakeetProvider, string> = {
+  auto: "Auto",
+  cpu: "CPU",
+  coreml: "CoreML",
+  cuda: "CUDA",
+  directml: "DirectML",
+};
+
+/** CPU threads and low-power mode for all local models, and Parakeet's provider. */
+function InferencePerformance({ parakeet }: { parakeet: boolean }) {
+  const {
+    inferenceThreads,
+    setInferenceThreads,
+    lowPowerMode,
+    setLowPowerMode,
+    maxInferenceSecs,
+    setMaxInferenceSecs,
+    parakeetProvider,
+    setParakeetProvider,
+  } = useSettingsStore();
+  const [providers, setProviders] = useState<string[]>(["cpu"]);
+
+  useEffect(() => {
+    commands
+      .parakeetProviders()
+      .then(setProviders)
+      .catch(() => {
+        // Outside Tauri context
+      });
+  }, []);
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center gap-2 px-1">
+        <Cpu className="h-3.5 w-3.5 text-muted-foreground/60" />
+        <span className="text-[11px] font-medium uppercase tracking-wider text-muted-foreground/60">
+          Performance
+        </span>
+        <div className="flex-1 h-px bg-border/50" />
+      </div>
+      <div className="rounded-xl border p-4 space-y-3">
+        <div className="flex items-center justify-between gap-4">
+          <div>
+            <p className="text-sm">Threads</p>
+            <p className="text-xs text-muted-foreground">
+              CPU threads for local models; Auto uses all cores up to 8
+            </p>
+          </div>
+          <Select
+            value={String(inferenceThreads)}
+            onValueChange={(v) => setInferenceThreads(Number(v))}
+          >
+            <SelectTrigger className="w-36 text-xs" aria-label="Threads">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent position="popper">
+              {THREAD_CHOICES.map((n) => (
+                <SelectItem key={n} value={String(n)} className="text-xs">
+                  {n === 0 ? "Auto" : String(n)}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+        <div className="flex items-center justify-between gap-4">
+          <div>
+            <p className="text-sm">Low-power mode</p>
+            <p className="text-xs text-muted-foreground">
+              Half the threads and no beam search, so laptops stay cool and quiet. Slower and
+              slightly less accurate.
+            </p>
+          </div>
+          <Switch checked={lowPowerMode} onCheckedChange={setLowPowerMode} />
+        </div>
+        <div className="flex items-center justify-between gap-4">
+          <div>
+            <p className="text-sm">Time limit</p>
+            <p className="text-xs text-muted-foreground">
+              Stop a transcription that runs longer, so a stuck one doesn&apos;t block the next
+              dictation. Long recordings get at least twice their length.
+            </p>
+          </div>
+          <Select
+            value={String(maxInferenceSecs)}
+            onValueChange={(v) => setMaxInferenceSecs(Number(v))}
+          >
+            <SelectTrigger className="w-36 text-xs" aria-label="Time limit">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent position="popper">
+              {TIME_LIMIT_CHOICES.map(({ value, label }) => (
+                <SelectItem key={value} value={String(value)} className="text-xs">
+                  {label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+        {parakeet && (
+          <div className="flex items-center justify-between gap-4">
+            <div>
+              <p className="text-sm">Parakeet acceleration</p>
+              <p className="text-xs text-muted-foreground">
+                Where Parakeet runs; Auto prefers the GPU when this build supports one
+              </p>
+            </div>
+            <Select
+              value={parakeetProvider}
+              onValueChange={(v) => setParakeetProvider(v as ParakeetProvider)}
+            >
+              <SelectTrigger className="w-36 text-xs" aria-label="Parakeet acceleration">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent position="popper">
+                {(["auto", ...providers] as ParakeetProvider[]).map((p) => (
+                  <SelectItem key={p} value={p} className="text-xs">
+                    {PROVIDER_LABELS[p] ?? p}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
+
+/** Whisper decoding parameters; other engines ignore them. */
+function WhisperDecoding() {
+  const { decodingOptions, setDecodingOptions } = useSettingsStore();
+  const isDefault = DECODING_CHOICES.every(
+    ({ key }) => decodingOptions[key] === DEFAULT_DECODING_OPTIONS[key],
+  );
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center gap-2 px-1">
+        <SlidersHorizontal className="h-3.5 w-3.5 text-muted-foreground/60" />
+        <span className="text-[11px] font-medium uppercase tracking-wider text-muted-foreground/60">
+          Whisper Decoding
+        </span>
+        <div className="flex-1 h-px bg-border/50" />
+      </div>
+      <div className="rounded-xl border p-4 space-y-3">
+        <div className="flex items-center justify-between gap-4">
+          <p className="text-xs text-muted-foreground">
+            Tune accuracy, speed and hallucination filtering for Whisper models.
+          </p>
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 text-xs"
+            disabled={isDefault}
+            onClick={() => setDecodingOptions(DEFAULT_DECODING_OPTIONS)}
+          >
+            Reset
+          </Button>
+        </div>
+        {DECODING_CHOICES.map(({ key, label, description, options }) => (
+          <div key={key} className="flex items-center justify-between gap-4">
+            <div>
+              <p className="text-sm">{label}</p>
+              <p className="text-xs text-muted-foreground">{description}</p>
+            </div>
+            <Select
+              value={String(decodingOptions[key])}
+              onValueChange={(v) => setDecodingOptions({ [key]: Number(v) })}
+            >
+              <SelectTrigger className="w-36 text-xs">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent position="popper">
+                {/* Keep a hand-edited value selectable */}
+                {!options.some((o) => o.value === decodingOptions[key]) && (
+                  <SelectItem value={String(decodingOptions[key])} className="text-xs">
+                    {decodingOptions[key]}
+                  </SelectItem>
+                )}
+                {options.map((o) => (
+                  <SelectItem key={o.value} value={String(o.value)} className="text-xs">
+                    {o.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
+
+/** Silero VAD: download the model and toggle skipping silence before Whisper. */
+function VoiceActivityDetection({ progress }: { progress: DownloadProgress | null }) {
+  const { vadEnabled, setVadEnabled } = useSettingsStore();
+  const [downloaded, setDownloaded] = useState<boolean | null>(null);
+  const [downloading, setDownloading] = useState(false);
+
+  useEffect(() => {
+    commands
+      .isVadModelDownloaded()
+      .then(setDownloaded)
+      .catch(() => setDownloaded(false));
+  }, []);
+
+  async function download() {
+    setDownloading(true);
+    try {
+      await commands.downloadVadModel();
+      setDownloaded(true);
+      setVadEnabled(true);
+    } catch (err) {
+      toast.error("Download failed", { description: commands.errorMessage(err) });
+    } finally {
+      setDownloading(false);
+    }
+  }
+
+  const percent = progress?.modelId === "silero-vad" ? Math.round(progress.percentage) : null;
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center gap-2 px-1">
+        <AudioWaveform className="h-3.5 w-3.5 text-muted-foreground/60" />
+        <span className="text-[11px] font-medium uppercase tracking-wider text-muted-foreground/60">
+          Voice Activity Detection
+        </span>
+        <div className="flex-1 h-px bg-border/50" />
+      </div>
+      <div className="flex items-center justify-between gap-4 rounded-xl border p-4">
+        <div>
+          <p className="text-sm font-medium">Skip silence</p>
+          <p className="text-xs text-muted-foreground mt-0.5">
+            Only pass speech to Whisper. Faster on long recordings and avoids phantom
+            phrases during pauses. Uses a small (~2 MB) Silero model.
+          </p>
+        </div>
+        {downloaded ? (
+          <Switch checked={vadEnabled} onCheckedChange={setVadEnabled} />
+        ) : (
+          <Button
+            variant="outline"
+            size="sm"
+            className="h-7 text-xs shrink-0"
+            disabled={downloaded === null || downloading}
+            onClick={download}
+          >
+            {downloading ? (
+              <Loader2 className="h-3.5 w-3.5 mr-1 animate-spin" />
+            ) : (
+              <Download className="h-3.5 w-3.5 mr-1" />
+            )}
+            {downloading && percent !== null ? `${percent}%` : "Download"}
+          </Button>
+        )}
+      </div>
+    </div>
+  );
+}
+
+/** Punctuation model: download it and toggle punctuating bare Parakeet output. */
+function PunctuationModel({ progress }: { progress: DownloadProgress | null }) {
+  const { punctuationEnabled, setPunctuationEnabled } = useSettingsStore();
+  const [downloaded, setDownloaded] = useState<boolean | null>(null);
+  const [downloading, setDownloading] = useState(false);
+
+  useEffect(() => {
+    commands
+      .isPunctuationModelDownloaded()
+      .then(setDownloaded)
+      .catch(() => setDownloaded(false));
+  }, []);
+
+  async function download() {
+    setDownloading(true);
+    try {
+      await commands.downloadPunctuationModel();
+      setDownloaded(true);
+      setPunctuationEnabled(true);
+    } catch (err) {
+      toast.error("Download failed", { description: commands.errorMessage(err) });
+    } finally {
+      setDownloading(false);
+    }
+  }
+
+  const percent = progress?.modelId === "ct-punctuation" ? Math.round(progress.percentage) : null;
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center gap-2 px-1">
+        <Pilcrow className="h-3.5 w-3.5 text-muted-foreground/60" />
+        <span className="text-[11px] font-medium uppercase tracking-wider text-muted-foreground/60">
+          Punctuation
+        </span>
+        <div className="flex-1 h-px bg-border/50" />
+      </div>
+      <div className="flex items-center justify-between gap-4 rounded-xl border p-4">
+        <div>
+          <p className="text-sm font-medium">Punctuate Parakeet output</p>
+          <p className="text-xs text-muted-foreground mt-0.5">
+            Add commas, full stops and capitals when Parakeet returns bare words. English and
+            Chinese only. Uses a ~75 MB CT-Transformer model.
+          </p>
+        </div>
+        {downloaded ? (
+          <Switch checked={punctuationEnabled} onCheckedChange={setPunctuationEnabled} />
+        ) : (
+          <Button
+            variant="outline"
+            size="sm"
+            className="h-7 text-xs shrink-0"
+            disabled={downloaded === null || downloading}
+            onClick={download}
+          >
+            {downloading ? (
+              <Loader2 className="h-3.5 w-3.5 mr-1 animate-spin" />
+            ) : (
+              <Download className="h-3.5 w-3.5 mr-1" />
+            )}
+            {downloading && percent !== null ? `${percent}%` : "Download"}
+          </Button>
+        )}
+      </div>
+    </div>
+  );
+}
+
+/** Disk usage per model directory, with cleanup of entries not in the catalog. */
+function ModelStorage({ models }: { models: ModelStatus[] }) {
+  const [report, setReport] = useState<commands.DiskUsageReport | null>(null);
+  const [cleaning, setCleaning] = useState(false);
+
+  useEffect(() => {
+    commands
+      .modelDiskUsage()
+      .then(setReport)
+      .catch(() => {
+        // Fails outside Tauri
+      });
+  }, [models]);
+
+  async function cleanUp() {
+    setCleaning(true);
+    try {
+      const result = await commands.modelDiskUsage(true);
+      setReport(result);
+      toast.success(`Freed ${formatSize(result.freedBytes)}`);
+    } catch (err) {
+      toast.error("Cleanup failed", { description: commands.errorMessage(err) });
+    } finally {
+      setCleaning(false);
+    }
+  }
+
+  if (!report || report.entries.length === 0) return null;
+  const orphaned = report.entries.filter((e) => e.orphaned);
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center gap-2 px-1">
+        <HardDrive className="h-3.5 w-3.5 text-muted-foreground/60" />
+        <span className="text-[11px] font-medium uppercase tracking-wider text-muted-foreground/60">
+          Storage
+        </span>
+        <div className="flex-1 h-px bg-border/50" />
+      </div>
+      <div className="rounded-xl border p-4 space-y-3">
+        <div className="flex items-center justify-between">
+          <p className="text-xs text-muted-foreground">
+            Models use {formatSize(report.totalBytes)} on disk.
+          </p>
+          {orphaned.length > 0 && (
+            <Button
+              variant="outline"
+              size="sm"
+              className="h-8 text-xs"
+              disabled={cleaning}
+              onClick={cleanUp}
+            >
+              {cleaning ? (
+                <Loader2 className="h-3.5 w-3.5 mr-1 animate-spin" />
+              ) : (
+                <FolderX className="h-3.5 w-3.5 mr-1" />
+              )}
+              Remove unused
+            </Button>
+          )}
+        </div>
+        <div className="space-y-1">
+          {report.entries.map((e) => (
+            <div key={e.modelId} className="flex items-center justify-between text-xs">
+              <span className={cn("truncate", e.orphaned && "text-muted-foreground")}>
+                {e.name ?? e.modelId}
+                {e.orphaned && " (unused)"}
+              </span>
+              <span className="tabular-nums text-muted-foreground">{formatSize(e.sizeBytes)}</span>
+            </div>
+          ))}
+        </div>
+      </div>
+    </div>
+  );
+}
+
 function ModelCard({
   model,
   isDownloading,
@@ -289,7 +899,7 @@ export default function ModelSettings() {
       toast.success("Model downloaded successfully");
       await loadModels();
     } catch (err) {
-      toast.error("Download failed", { description: String(err) });
+      toast.error("Download failed", { description: commands.errorMessage(err) });
     } finally {
       setDownloading(null);
       setProgress(null);
@@ -302,7 +912,7 @@ export default function ModelSettings() {
       toast.success("Model deleted");
       await loadModels();
     } catch (err) {
-      toast.error("Failed to delete model", { description: String(err) });
+      toast.error("Failed to delete model", { description: commands.errorMessage(err) });
     }
   }
 
@@ -380,6 +990,26 @@ export default function ModelSettings() {
             </div>
           )}
 
+          {localModels.some((m) => m.downloaded) && (
+            <ModelBenchmark models={localModels.filter((m) => m.downloaded)} />
+          )}
+
+          {localModels.length > 0 && (
+            <InferencePerformance parakeet={localModels.some((m) => m.engine === "Parakeet")} />
+          )}
+
+          {localModels.some((m) => m.engine === "Whisper") && <WhisperDecoding />}
+
+          {localModels.some((m) => m.engine === "Whisper" || m.engine === "WhisperOnnx") && (
+            <VoiceActivityDetection progress={progress} />
+          )}
+
+          {localModels.some((m) => m.engine === "Parakeet") && (
+            <PunctuationModel progress={progress} />
+          )}
+
+          <ModelStorage models={models} />
+
           {models.length === 0 && (
             <div className="flex flex-col items-center justify-center py-12 text-center">
               <div className="h-12 w-12 rounded-xl bg-muted flex items-center justify-center mb-4">
@@ -1,11 +1,100 @@
 "use client";
 
-import { Monitor, Sun, Moon, Power } from "lucide-react";
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import {
+  Monitor,
+  Languages,
+  Sun,
+  Moon,
+  Power,
+  Bell,
+  ClipboardPaste,
+  ClipboardCheck,
+  Timer,
+  Type,
+  Layers,
+  EyeOff,
+  KeyRound,
+  Lock,
+  LifeBuoy,
+  Loader2,
+  FileAudio,
+  HardDrive,
+  Mic,
+  Headphones,
+  PanelBottom,
+  AudioLines,
+  Wrench,
+  Crosshair,
+  Link,
+} from "lucide-react";
 import { motion } from "motion/react";
+import { Button } from "@/components/ui/button";
 import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
 import { Switch } from "@/components/ui/switch";
 import { ToggleGroup, ToggleGroupItem } from "@/components/ui/toggle-group";
-import { useSettingsStore } from "@/stores/settings-store";
+import {
+  useSettingsStore,
+  type PasteSource,
+  type PasteFormat,
+  type PasteMode,
+  type SecureFieldPaste,
+  type RecordingBarPosition,
+} from "@/stores/settings-store";
+import {
+  collectDiagnostics,
+  errorMessage,
+  getDatabaseEncryption,
+  getStorageDir,
+  listMonitors,
+  maintainDatabase,
+  setDatabaseEncryption,
+  setStorageDir,
+  testMicrophone,
+  type MicVerdict,
+  type MonitorInfo,
+} from "@/lib/tauri-commands";
+
+/** Languages the tray menu and notifications are translated into. */
+const UI_LANGUAGES: { code: string; name: string }[] = [
+  { code: "auto", name: "System" },
+  { code: "en", name: "English" },
+  { code: "de", name: "Deutsch" },
+  { code: "es", name: "Español" },
+  { code: "fr", name: "Français" },
+  { code: "tr", name: "Türkçe" },
+];
+
+const BAR_POSITIONS: { value: RecordingBarPosition; label: string }[] = [
+  { value: "bottom-center", label: "Bottom center" },
+  { value: "top-center", label: "Top center" },
+  { value: "bottom-left", label: "Bottom left" },
+  { value: "bottom-right", label: "Bottom right" },
+  { value: "top-left", label: "Top left" },
+  { value: "top-right", label: "Top right" },
+];
+
+const PASTE_DELAYS = [0, 100, 250, 500, 1000];
+
+const MIC_VERDICTS: Record<MicVerdict, { title: string; description: string }> = {
+  ok: { title: "Microphone sounds good", description: "Levels are fine for dictation" },
+  "too-quiet": {
+    title: "Microphone is too quiet",
+    description: "Move closer, raise the input volume or pick another input device",
+  },
+  clipping: {
+    title: "Microphone is clipping",
+    description: "Lower the input volume or move further from the microphone",
+  },
+};
 
 function SettingRow({
   icon: Icon,
@@ -44,8 +133,162 @@ function SettingRow({
 }
 
 export default function GeneralSettings() {
-  const { theme, setTheme, launchAtLogin, setLaunchAtLogin } =
-    useSettingsStore();
+  const {
+    theme,
+    setTheme,
+    uiLanguage,
+    setUiLanguage,
+    launchAtLogin,
+    setLaunchAtLogin,
+    notifyOnTranscription,
+    setNotifyOnTranscription,
+    keepRulesOnlyText,
+    setKeepRulesOnlyText,
+    pasteSource,
+    setPasteSource,
+    pasteFormat,
+    setPasteFormat,
+    pasteMode,
+    setPasteMode,
+    pasteDelayMs,
+    setPasteDelayMs,
+    lockPasteTarget,
+    setLockPasteTarget,
+    secureFieldPaste,
+    setSecureFieldPaste,
+    incognito,
+    setIncognito,
+    keepOriginalAudio,
+    setKeepOriginalAudio,
+    automationUrlsEnabled,
+    setAutomationUrlsEnabled,
+    preferBuiltInMic,
+    setPreferBuiltInMic,
+    recordingBarPosition,
+    setRecordingBarPosition,
+    recordingBarMonitor,
+    setRecordingBarMonitor,
+    spectrumVisualizer,
+    setSpectrumVisualizer,
+  } = useSettingsStore();
+
+  const [monitors, setMonitors] = useState<MonitorInfo[]>([]);
+
+  useEffect(() => {
+    listMonitors().then(setMonitors).catch(() => {});
+  }, []);
+
+  // Encryption state lives in the database file itself, not the store
+  const [encrypted, setEncrypted] = useState(false);
+  const [encrypting, setEncrypting] = useState(false);
+
+  useEffect(() => {
+    getDatabaseEncryption().then(setEncrypted).catch(() => {});
+  }, []);
+
+  const toggleEncryption = async (enabled: boolean) => {
+    setEncrypting(true);
+    try {
+      await setDatabaseEncryption(enabled);
+      setEncrypted(enabled);
+      toast.success(enabled ? "Database encrypted" : "Database decrypted");
+    } catch (err) {
+      toast.error("Failed to change database encryption", { description: String(err) });
+    } finally {
+      setEncrypting(false);
+    }
+  };
+
+  const [maintaining, setMaintaining] = useState(false);
+
+  const maintain = async () => {
+    setMaintaining(true);
+    try {
+      const report = await maintainDatabase();
+      if (report.integrityOk) {
+        const reclaimedMb = (report.reclaimedBytes / 1_000_000).toFixed(1);
+        toast.success("Database optimized", { description: `Reclaimed ${reclaimedMb} MB` });
+      } else {
+        toast.error("Database integrity check failed", {
+          description: report.integrityErrors.slice(0, 3).join("\n"),
+        });
+      }
+    } catch (err) {
+      toast.error("Database maintenance failed", { description: String(err) });
+    } finally {
+      setMaintaining(false);
+    }
+  };
+
+  const [storageDir, setStorageDirPath] = useState("");
+  const [moving, setMoving] = useState(false);
+
+  useEffect(() => {
+    getStorageDir().then(setStorageDirPath).catch(() => {});
+  }, []);
+
+  const changeStorageDir = async (reset: boolean) => {
+    let path: string | null = null;
+    if (!reset) {
+      try {
+        const { open } = await import("@tauri-apps/plugin-dialog");
+        const selected = await open({ directory: true, multiple: false });
+        if (typeof selected !== "string") return;
+        path = selected;
+      } catch {
+        return; // Outside Tauri context
+      }
+    }
+    setMoving(true);
+    try {
+      const report = await setStorageDir(path);
+      setStorageDirPath(await getStorageDir());
+      toast.success("Storage location changed", {
+        description: `Moved ${report.filesMoved} file${report.filesMoved === 1 ? "" : "s"}`,
+      });
+    } catch (err) {
+      toast.error("Failed to change storage location", { description: String(err) });
+    } finally {
+      setMoving(false);
+    }
+  };
+
+  const [micLevel, setMicLevel] = useState<number | null>(null);
+
+  const runMicTest = async () => {
+    setMicLevel(0);
+    let unlisten: (() => void) | undefined;
+    try {
+      const { listen } = await import("@tauri-apps/api/event");
+      unlisten = await listen<number>("mic-test-level", (e) => setMicLevel(e.payload));
+      const result = await testMicrophone(true);
+      const { title, description } = MIC_VERDICTS[result.verdict];
+      if (result.verdict === "ok") {
+        toast.success(title, { description });
+      } else {
+        toast.warning(title, { description });
+      }
+    } catch (err) {
+      toast.error("Microphone test failed", { description: errorMessage(err) });
+    } finally {
+      unlisten?.();
+      setMicLevel(null);
+    }
+  };
+
+  const [collecting, setCollecting] = useState(false);
+
+  const exportDiagnostics = async () => {
+    setCollecting(true);
+    try {
+      const path = await collectDiagnostics({ includeFailedAudio: false });
+      toast.success("Diagnostics saved", { description: path });
+    } catch (err) {
+      toast.error("Failed to collect diagnostics", { description: String(err) });
+    } finally {
+      setCollecting(false);
+    }
+  };
 
   return (
     <div className="space-y-8">
@@ -86,14 +329,370 @@ export default function GeneralSettings() {
           </ToggleGroup>
         </SettingRow>
 
+        <SettingRow
+          icon={Languages}
+          label="App Language"
+          description="Language of the menu bar menu and notifications"
+          index={1}
+        >
+          <Select value={uiLanguage} onValueChange={setUiLanguage}>
+            <SelectTrigger className="w-36" aria-label="App language">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent position="popper">
+              {UI_LANGUAGES.map(({ code, name }) => (
+                <SelectItem key={code} value={code}>
+                  {name}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </SettingRow>
+
         <SettingRow
           icon={Power}
           label="Launch at Login"
           description="Automatically start SobottaAI when you log in"
-          index={1}
+          index={2}
         >
           <Switch checked={launchAtLogin} onCheckedChange={setLaunchAtLogin} />
         </SettingRow>
+
+        <SettingRow
+          icon={Bell}
+          label="Completion Notifications"
+          description="Show the transcript in a notification when the window is hidden; opening the window then shows History"
+          index={3}
+        >
+          <Switch
+            checked={notifyOnTranscription}
+            onCheckedChange={setNotifyOnTranscription}
+          />
+        </SettingRow>
+
+        <SettingRow
+          icon={Layers}
+          label="Keep Rules-Only Version"
+          description="Also save the text after rules, before the AI function"
+          index={4}
+        >
+          <Switch checked={keepRulesOnlyText} onCheckedChange={setKeepRulesOnlyText} />
+        </SettingRow>
+
+        <SettingRow
+          icon={ClipboardPaste}
+          label="Paste Output"
+          description="Which version of the text is pasted after dictation"
+          index={5}
+        >
+          <ToggleGroup
+            type="single"
+            variant="outline"
+            value={pasteSource}
+            onValueChange={(v) => {
+              if (v) setPasteSource(v as PasteSource);
+            }}
+          >
+            <ToggleGroupItem value="processed" aria-label="Paste processed text">
+              Processed
+            </ToggleGroupItem>
+            <ToggleGroupItem value="rules" aria-label="Paste rules-only text">
+              Rules
+            </ToggleGroupItem>
+            <ToggleGroupItem value="verbatim" aria-label="Paste verbatim transcript">
+              Verbatim
+            </ToggleGroupItem>
+          </ToggleGroup>
+        </SettingRow>
+
+        <SettingRow
+          icon={ClipboardCheck}
+          label="After Dictation"
+          description="Paste right away, only copy, or confirm in a preview first"
+          index={6}
+        >
+          <ToggleGroup
+            type="single"
+            variant="outline"
+            value={pasteMode}
+            onValueChange={(v) => {
+              if (v) setPasteMode(v as PasteMode);
+            }}
+          >
+            <ToggleGroupItem value="auto" aria-label="Paste automatically">
+              Paste
+            </ToggleGroupItem>
+            <ToggleGroupItem value="copy" aria-label="Copy to the clipboard only">
+              Copy
+            </ToggleGroupItem>
+            <ToggleGroupItem value="confirm" aria-label="Confirm before pasting">
+              Confirm
+            </ToggleGroupItem>
+          </ToggleGroup>
+        </SettingRow>
+
+        <SettingRow
+          icon={Timer}
+          label="Paste Delay"
+          description="Wait before pasting, for apps that are slow to take focus"
+          index={7}
+        >
+          <Select
+            value={String(pasteDelayMs)}
+            onValueChange={(v) => setPasteDelayMs(Number(v))}
+          >
+            <SelectTrigger className="w-36" aria-label="Paste delay">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent position="popper">
+              {PASTE_DELAYS.map((ms) => (
+                <SelectItem key={ms} value={String(ms)}>
+                  {ms === 0 ? "None" : `${ms} ms`}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </SettingRow>
+
+        <SettingRow
+          icon={Crosshair}
+          label="Return to Original App"
+          description="Paste into the app you started dictating in, even if another one took focus"
+          index={8}
+        >
+          <Switch checked={lockPasteTarget} onCheckedChange={setLockPasteTarget} />
+        </SettingRow>
+
+        <SettingRow
+          icon={Type}
+          label="Paste Format"
+          description="Strip Markdown, keep it, or paste it formatted for mail and documents"
+          index={9}
+        >
+          <ToggleGroup
+            type="single"
+            variant="outline"
+            value={pasteFormat}
+            onValueChange={(v) => {
+              if (v) setPasteFormat(v as PasteFormat);
+            }}
+          >
+            <ToggleGroupItem value="plain" aria-label="Paste plain text">
+              Plain
+            </ToggleGroupItem>
+            <ToggleGroupItem value="markdown" aria-label="Paste Markdown">
+              Markdown
+            </ToggleGroupItem>
+            <ToggleGroupItem value="rich" aria-label="Paste rich text">
+              Rich
+            </ToggleGroupItem>
+          </ToggleGroup>
+        </SettingRow>
+
+        <SettingRow
+          icon={KeyRound}
+          label="Password Fields"
+          description="Dictation pasted into a password field isn't saved to history either way"
+          index={10}
+        >
+          <ToggleGroup
+            type="single"
+            variant="outline"
+            value={secureFieldPaste}
+            onValueChange={(v) => {
+              if (v) setSecureFieldPaste(v as SecureFieldPaste);
+            }}
+          >
+            <ToggleGroupItem value="block" aria-label="Don't paste into password fields">
+              Block
+            </ToggleGroupItem>
+            <ToggleGroupItem value="warn" aria-label="Paste into password fields with a warning">
+              Warn
+            </ToggleGroupItem>
+          </ToggleGroup>
+        </SettingRow>
+
+        <SettingRow
+          icon={EyeOff}
+          label="Incognito Mode"
+          description="Don't save history or audio — for passwords and sensitive notes"
+          index={11}
+        >
+          <Switch checked={incognito} onCheckedChange={setIncognito} />
+        </SettingRow>
+
+        <SettingRow
+          icon={FileAudio}
+          label="Keep Original Audio"
+          description="Also save the full-quality recording for archiving or re-transcribing later. Uses more disk space."
+          index={12}
+        >
+          <Switch checked={keepOriginalAudio} onCheckedChange={setKeepOriginalAudio} />
+        </SettingRow>
+
+        <SettingRow
+          icon={Lock}
+          label="Encrypt Database"
+          description="Encrypt history at rest with a key stored in your OS keychain"
+          index={13}
+        >
+          <Switch
+            checked={encrypted}
+            disabled={encrypting}
+            onCheckedChange={toggleEncryption}
+          />
+        </SettingRow>
+
+        <SettingRow
+          icon={Wrench}
+          label="Optimize Database"
+          description="Check the history database for damage and reclaim space freed by deleted recordings"
+          index={14}
+        >
+          <Button variant="outline" size="sm" disabled={maintaining} onClick={maintain}>
+            {maintaining && <Loader2 className="h-3.5 w-3.5 mr-1.5 animate-spin" />}
+            Optimize
+          </Button>
+        </SettingRow>
+
+        <SettingRow
+          icon={HardDrive}
+          label="Storage Location"
+          description={storageDir || "Where recordings and models are kept"}
+          index={15}
+        >
+          <div className="flex gap-2">
+            <Button
+              variant="ghost"
+              size="sm"
+              disabled={moving}
+              onClick={() => changeStorageDir(true)}
+            >
+              Reset
+            </Button>
+            <Button
+              variant="outline"
+              size="sm"
+              disabled={moving}
+              onClick={() => changeStorageDir(false)}
+            >
+              {moving && <Loader2 className="h-3.5 w-3.5 mr-1 animate-spin" />}
+              Change
+            </Button>
+          </div>
+        </SettingRow>
+
+        <SettingRow
+          icon={Mic}
+          label="Microphone Test"
+          description="Record a few seconds to check your input levels before dictating"
+          index={16}
+        >
+          <div className="flex items-center gap-3">
+            {micLevel !== null && (
+              <div className="h-1.5 w-24 overflow-hidden rounded-full bg-muted">
+                <div
+                  className="h-full bg-primary transition-[width] duration-75"
+                  style={{ width: `${Math.min(100, micLevel * 400)}%` }}
+                />
+              </div>
+            )}
+            <Button
+              variant="outline"
+              size="sm"
+              disabled={micLevel !== null}
+              onClick={runMicTest}
+            >
+              {micLevel !== null && <Loader2 className="h-3.5 w-3.5 mr-1 animate-spin" />}
+              Test
+            </Button>
+          </div>
+        </SettingRow>
+
+        <SettingRow
+          icon={Headphones}
+          label="Prefer Built-in Microphone"
+          description="Record from the computer's mic when a Bluetooth headset is connected, so its audio quality doesn't drop"
+          index={17}
+        >
+          <Switch checked={preferBuiltInMic} onCheckedChange={setPreferBuiltInMic} />
+        </SettingRow>
+
+        <SettingRow
+          icon={PanelBottom}
+          label="Recording Bar"
+          description="Where the recording indicator appears, and on which display"
+          index={18}
+        >
+          <div className="flex items-center gap-2">
+            <Select
+              value={recordingBarPosition}
+              onValueChange={(v) => setRecordingBarPosition(v as RecordingBarPosition)}
+            >
+              <SelectTrigger className="w-36" aria-label="Recording bar position">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {BAR_POSITIONS.map((p) => (
+                  <SelectItem key={p.value} value={p.value}>
+                    {p.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <Select value={recordingBarMonitor} onValueChange={setRecordingBarMonitor}>
+              <SelectTrigger className="w-40" aria-label="Recording bar display">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="primary">Primary display</SelectItem>
+                <SelectItem value="cursor">Follow mouse</SelectItem>
+                {monitors.map((m) => (
+                  <SelectItem key={m.name} value={m.name}>
+                    {m.name} ({m.width}×{m.height})
+                  </SelectItem>
+                ))}
+                {recordingBarMonitor !== "primary" &&
+                  recordingBarMonitor !== "cursor" &&
+                  !monitors.some((m) => m.name === recordingBarMonitor) && (
+                    <SelectItem value={recordingBarMonitor}>
+                      {recordingBarMonitor} (disconnected)
+                    </SelectItem>
+                  )}
+              </SelectContent>
+            </Select>
+          </div>
+        </SettingRow>
+
+        <SettingRow
+          icon={AudioLines}
+          label="Spectrum Visualizer"
+          description="Show frequency bands in the recording bar instead of simple level bars"
+          index={19}
+        >
+          <Switch checked={spectrumVisualizer} onCheckedChange={setSpectrumVisualizer} />
+        </SettingRow>
+
+        <SettingRow
+          icon={Link}
+          label="Automation Links"
+          description="Let Shortcuts, AppleScript and sobottaai:// links start recordings, transcribe files and run AI functions. Each action asks first."
+          index={20}
+        >
+          <Switch checked={automationUrlsEnabled} onCheckedChange={setAutomationUrlsEnabled} />
+        </SettingRow>
+
+        <SettingRow
+          icon={LifeBuoy}
+          label="Diagnostics"
+          description="Save logs and system info to your Downloads folder for a bug report. API keys are removed."
+          index={21}
+        >
+          <Button variant="outline" size="sm" disabled={collecting} onClick={exportDiagnostics}>
+            {collecting && <Loader2 className="h-3.5 w-3.5 mr-1 animate-spin" />}
+            Export
+          </Button>
+        </SettingRow>
       </div>
     </div>
   );
@@ -0,0 +1,384 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import {
+  ArrowDown,
+  ArrowUp,
+  BookOpen,
+  EyeOff,
+  Plus,
+  Sparkles,
+  Trash2,
+  Wand2,
+  Workflow,
+  X,
+} from "lucide-react";
+import { motion } from "motion/react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuTrigger,
+} from "@/components/ui/dropdown-menu";
+import { ToggleGroup, ToggleGroupItem } from "@/components/ui/toggle-group";
+import {
+  deletePipeline,
+  listAiFunctions,
+  listPipelines,
+  savePipeline,
+  type AiFunction,
+  type Pipeline,
+  type PipelineStep,
+} from "@/lib/tauri-commands";
+import { useSettingsStore } from "@/stores/settings-store";
+
+const STEP_META: Record<PipelineStep["type"], { label: string; icon: typeof Wand2 }> = {
+  rules: { label: "Rules", icon: Wand2 },
+  vocabulary: { label: "Vocabulary Replacements", icon: BookOpen },
+  aiFunction: { label: "AI Function", icon: Sparkles },
+  redact: { label: "Redact Personal Data", icon: EyeOff },
+};
+
+/** Select value standing in for the built-in order (null). */
+const DEFAULT_PIPELINE = "__default";
+
+function StepRow({
+  step,
+  index,
+  count,
+  aiFunctions,
+  onChange,
+  onMove,
+  onRemove,
+}: {
+  step: PipelineStep;
+  index: number;
+  count: number;
+  aiFunctions: AiFunction[];
+  onChange: (step: PipelineStep) => void;
+  onMove: (offset: number) => void;
+  onRemove: () => void;
+}) {
+  const rules = useSettingsStore((s) => s.rules);
+  const meta = STEP_META[step.type];
+  const Icon = meta.icon;
+
+  return (
+    <div className="flex items-start gap-3 rounded-lg border p-3">
+      <div className="flex items-center justify-center h-8 w-8 rounded-lg shrink-0 bg-primary/10 text-primary">
+        <Icon className="h-4 w-4" />
+      </div>
+      <div className="flex-1 min-w-0 space-y-2">
+        <div className="flex items-center gap-2 pt-1.5">
+          <Label className="text-sm">{meta.label}</Label>
+          <span className="text-[10px] font-medium tabular-nums px-1.5 py-0.5 rounded bg-muted/50 text-muted-foreground">
+            Step {index + 1}
+          </span>
+        </div>
+        {step.type === "rules" && (
+          <ToggleGroup
+            type="multiple"
+            variant="outline"
+            size="sm"
+            value={step.ruleIds}
+            onValueChange={(ids) =>
+              // Keep the order of the rules list
+              onChange({
+                type: "rules",
+                ruleIds: rules.map((r) => r.id).filter((id) => ids.includes(id)),
+              })
+            }
+            className="flex-wrap justify-start"
+          >
+            {rules.map((rule) => (
+              <ToggleGroupItem key={rule.id} value={rule.id} className="text-xs">
+                {rule.name}
+              </ToggleGroupItem>
+            ))}
+          </ToggleGroup>
+        )}
+        {step.type === "aiFunction" && (
+          <Select
+            value={step.functionId}
+            onValueChange={(functionId) => onChange({ type: "aiFunction", functionId })}
+          >
+            <SelectTrigger className="w-56" aria-label="AI function">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent position="popper" className="max-h-60">
+              {aiFunctions.map((fn) => (
+                <SelectItem key={fn.id} value={fn.id}>
+                  {fn.name}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        )}
+      </div>
+      <div className="flex items-center gap-0.5 shrink-0">
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 w-7 p-0"
+          aria-label="Move step up"
+          disabled={index === 0}
+          onClick={() => onMove(-1)}
+        >
+          <ArrowUp className="h-3.5 w-3.5" />
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 w-7 p-0"
+          aria-label="Move step down"
+          disabled={index === count - 1}
+          onClick={() => onMove(1)}
+        >
+          <ArrowDown className="h-3.5 w-3.5" />
+        </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 w-7 p-0 text-muted-foreground/60 hover:text-destructive"
+          aria-label="Remove step"
+          onClick={onRemove}
+        >
+          <X className="h-3.5 w-3.5" />
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+function PipelineCard({
+  pipeline,
+  aiFunctions,
+  index,
+  onSave,
+  onDelete,
+}: {
+  pipeline: Pipeline;
+  aiFunctions: AiFunction[];
+  index: number;
+  onSave: (pipeline: Pipeline) => void;
+  onDelete: () => void;
+}) {
+  const rules = useSettingsStore((s) => s.rules);
+  const [name, setName] = useState(pipeline.name);
+
+  function setSteps(steps: PipelineStep[]) {
+    onSave({ ...pipeline, steps });
+  }
+
+  function addStep(type: PipelineStep["type"]) {
+    const step: PipelineStep =
+      type === "rules"
+        ? { type, ruleIds: rules.map((r) => r.id) }
+        : type === "aiFunction"
+          ? { type, functionId: aiFunctions[0]?.id ?? "" }
+          : { type };
+    setSteps([...pipeline.steps, step]);
+  }
+
+  function moveStep(from: number, offset: number) {
+    const steps = [...pipeline.steps];
+    const [step] = steps.splice(from, 1);
+    steps.splice(from + offset, 0, step);
+    setSteps(steps);
+  }
+
+  return (
+    <motion.div
+      initial={{ opacity: 0, y: 8 }}
+      animate={{ opacity: 1, y: 0 }}
+      transition={{ delay: index * 0.04 }}
+      className="rounded-xl border p-4 space-y-3"
+    >
+      <div className="flex items-center gap-2">
+        <Input
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+          onBlur={() => {
+            if (name.trim() && name.trim() !== pipeline.name) {
+              onSave({ ...pipeline, name: name.trim() });
+            } else {
+              setName(pipeline.name);
+            }
+          }}
+          aria-label="Pipeline name"
+          className="h-8 text-sm font-medium"
+        />
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 w-7 p-0 shrink-0 text-muted-foreground/40 hover:text-destructive"
+          aria-label="Delete pipeline"
+          onClick={onDelete}
+        >
+          <Trash2 className="h-3.5 w-3.5" />
+        </Button>
+      </div>
+
+      {pipeline.steps.length === 0 ? (
+        <p className="text-xs text-muted-foreground">
+          No steps — the transcript is pasted as spoken.
+        </p>
+      ) : (
+        <div className="space-y-2">
+          {pipeline.steps.map((step, i) => (
+            <StepRow
+              key={i}
+              step={step}
+              index={i}
+              count={pipeline.steps.length}
+              aiFunctions={aiFunctions}
+              onChange={(next) =>
+                setSteps(pipeline.steps.map((s, j) => (j === i ? next : s)))
+              }
+              onMove={(offset) => moveStep(i, offset)}
+              onRemove={() => setSteps(pipeline.steps.filter((_, j) => j !== i))}
+            />
+          ))}
+        </div>
+      )}
+
+      <DropdownMenu>
+        <DropdownMenuTrigger asChild>
+          <Button variant="outline" size="sm" className="h-7 text-xs">
+            <Plus className="h-3 w-3 mr-1" />
+            Add step
+          </Button>
+        </DropdownMenuTrigger>
+        <DropdownMenuContent align="start" className="w-52">
+          {(Object.keys(STEP_META) as PipelineStep["type"][]).map((type) => {
+            const Icon = STEP_META[type].icon;
+            return (
+              <DropdownMenuItem
+                key={type}
+                disabled={type === "aiFunction" && aiFunctions.length === 0}
+                onSelect={() => addStep(type)}
+              >
+                <Icon className="h-4 w-4" />
+                {STEP_META[type].label}
+              </DropdownMenuItem>
+            );
+          })}
+        </DropdownMenuContent>
+      </DropdownMenu>
+    </motion.div>
+  );
+}
+
+export default function PipelinesSettings() {
+  const [pipelines, setPipelines] = useState<Pipeline[]>([]);
+  const [aiFunctions, setAiFunctions] = useState<AiFunction[]>([]);
+  const { pipelineId, setPipelineId } = useSettingsStore();
+
+  useEffect(() => {
+    listPipelines()
+      .then(setPipelines)
+      .catch(() => {
+        // Outside Tauri context
+      });
+    listAiFunctions()
+      .then(setAiFunctions)
+      .catch(() => {});
+  }, []);
+
+  async function save(pipeline: Pipeline) {
+    try {
+      const saved = await savePipeline(pipeline);
+      setPipelines((prev) =>
+        prev.some((p) => p.id === saved.id)
+          ? prev.map((p) => (p.id === saved.id ? saved : p))
+          : [...prev, saved],
+      );
+    } catch (err) {
+      toast.error("Couldn't save pipeline", { description: String(err) });
+    }
+  }
+
+  async function remove(id: string) {
+    try {
+      await deletePipeline(id);
+      setPipelines((prev) => prev.filter((p) => p.id !== id));
+      if (pipelineId === id) setPipelineId(null);
+    } catch (err) {
+      toast.error("Couldn't delete pipeline", { description: String(err) });
+    }
+  }
+
+  return (
+    <div className="space-y-8">
+      <div className="flex items-start justify-between gap-4">
+        <div>
+          <h3 className="text-lg font-semibold">Pipelines</h3>
+          <p className="text-sm text-muted-foreground">
+            Choose which post-processing steps run on a transcript, and in what order.
+          </p>
+        </div>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="text-xs gap-1.5 shrink-0"
+          onClick={() => save({ id: "", name: "New pipeline", steps: [], createdAt: "" })}
+        >
+          <Plus className="h-3.5 w-3.5" />
+          New Pipeline
+        </Button>
+      </div>
+
+      <div className="rounded-xl border p-4 flex items-center justify-between gap-4">
+        <div>
+          <div className="flex items-center gap-2">
+            <Workflow className="h-4 w-4 text-muted-foreground" />
+            <Label className="text-sm">Dictation Pipeline</Label>
+          </div>
+          <p className="text-xs text-muted-foreground mt-0.5">
+            Used unless a preset hotkey picks its own
+          </p>
+        </div>
+        <Select
+          value={pipelineId ?? DEFAULT_PIPELINE}
+          onValueChange={(v) => setPipelineId(v === DEFAULT_PIPELINE ? null : v)}
+        >
+          <SelectTrigger className="w-56" aria-label="Dictation pipeline">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent position="popper" className="max-h-60">
+            <SelectItem value={DEFAULT_PIPELINE}>Rules, then AI function</SelectItem>
+            {pipelines.map((p) => (
+              <SelectItem key={p.id} value={p.id}>
+                {p.name}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+
+      <div className="space-y-3">
+        {pipelines.map((pipeline, i) => (
+          <PipelineCard
+            key={pipeline.id}
+            pipeline={pipeline}
+            aiFunctions={aiFunctions}
+            index={i}
+            onSave={save}
+            onDelete={() => remove(pipeline.id)}
+          />
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -1,6 +1,6 @@
 "use client";
 
-import { useState, useCallback } from "react";
+import { useState, useCallback, useEffect } from "react";
 import {
   Check,
   Eye,
@@ -11,13 +11,36 @@ import {
   Cloud,
   CircleAlert,
   CircleCheck,
+  RefreshCw,
+  Loader2,
+  ChevronUp,
+  ChevronDown,
 } from "lucide-react";
 import { motion, AnimatePresence } from "motion/react";
 import { Label } from "@/components/ui/label";
 import { Input } from "@/components/ui/input";
 import { Button } from "@/components/ui/button";
+import { Switch } from "@/components/ui/switch";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
 import { cn } from "@/lib/utils";
-import { useSettingsStore } from "@/stores/settings-store";
+import {
+  useSettingsStore,
+  ollamaOptionsFor,
+  isProviderUsable,
+  type ProviderConfig,
+} from "@/stores/settings-store";
+import {
+  listOllamaModels,
+  testLlmProvider,
+  testSttProvider,
+  type ProviderTestResult,
+} from "@/lib/tauri-commands";
 
 const PROVIDERS = [
   {
@@ -27,6 +50,7 @@ const PROVIDERS = [
     placeholder: "sk-...",
     defaultModel: "gpt-4o-mini",
     needsKey: true,
+    defaultTimeoutSecs: 30,
     docsUrl: "https://platform.openai.com/api-keys",
     icon: Cloud,
     keyPrefix: "sk-",
@@ -38,6 +62,7 @@ const PROVIDERS = [
     placeholder: "sk-ant-...",
     defaultModel: "claude-sonnet-4-5-20250929",
     needsKey: true,
+    defaultTimeoutSecs: 30,
     docsUrl: "https://console.anthropic.com/settings/keys",
     icon: Cloud,
     keyPrefix: "sk-ant-",
@@ -49,6 +74,7 @@ const PROVIDERS = [
     placeholder: "gsk_...",
     defaultModel: "llama-3.3-70b-versatile",
     needsKey: true,
+    defaultTimeoutSecs: 30,
     docsUrl: "https://console.groq.com/keys",
     icon: Cloud,
     keyPrefix: "gsk_",
@@ -60,6 +86,7 @@ const PROVIDERS = [
     placeholder: "Not required",
     defaultModel: "llama3.2",
     needsKey: false,
+    defaultTimeoutSecs: 60,
     docsUrl: "https://ollama.com",
     icon: Server,
     keyPrefix: "",
@@ -133,10 +160,332 @@ function MaskedKeyInput({
   );
 }
 
-export default function ProviderSettings() {
-  const { llmProvider, setLlmProvider, providerConfigs, setProviderConfig } =
+/** Model picker backed by the Ollama server's installed models. Falls back to
+ *  a free-text input when the server can't be reached. */
+function OllamaModelSelect({
+  value,
+  onChange,
+  baseUrl,
+  placeholder,
+}: {
+  value: string;
+  onChange: (value: string) => void;
+  baseUrl?: string;
+  placeholder: string;
+}) {
+  const [models, setModels] = useState<string[]>([]);
+  const [error, setError] = useState<string | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const refresh = useCallback(async () => {
+    setLoading(true);
+    try {
+      setModels(await listOllamaModels(baseUrl || null));
+      setError(null);
+    } catch (err) {
+      setModels([]);
+      setError(String(err));
+    } finally {
+      setLoading(false);
+    }
+  }, [baseUrl]);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  // Keep the saved model selectable even if it's no longer installed
+  const options = value && !models.includes(value) ? [value, ...models] : models;
+
+  return (
+    <div className="space-y-1.5">
+      <div className="flex items-center justify-between">
+        <Label className="text-xs text-muted-foreground">Model</Label>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-6 px-1.5 text-[10px] text-muted-foreground gap-1"
+          onClick={refresh}
+          disabled={loading}
+        >
+          <RefreshCw className={cn("h-3 w-3", loading && "animate-spin")} />
+          Refresh
+        </Button>
+      </div>
+      {models.length > 0 ? (
+        <Select value={value} onValueChange={onChange}>
+          <SelectTrigger className="w-full text-xs">
+            <SelectValue placeholder={placeholder} />
+          </SelectTrigger>
+          <SelectContent position="popper" className="max-h-60">
+            {options.map((m) => (
+              <SelectItem key={m} value={m} className="text-xs">
+                {m}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      ) : (
+        <Input
+          placeholder={placeholder}
+          value={value}
+          onChange={(e) => onChange(e.target.value)}
+          className="text-xs"
+        />
+      )}
+      <p className="text-[10px] text-muted-foreground/60">
+        {error
+          ? "Ollama server not reachable — enter the model name manually"
+          : models.length === 0 && !loading
+            ? "No models installed — run `ollama pull` first"
+            : `Default: ${placeholder}`}
+      </p>
+    </div>
+  );
+}
+
+/** Providers whose key is also used for cloud transcription. */
+const STT_PROVIDERS = ["openai", "groq"];
+
+function ConnectionTest({
+  providerId,
+  config,
+}: {
+  providerId: string;
+  config: ProviderConfig;
+}) {
+  const { openaiSttBaseUrl, openaiSttModel, openaiSttApiKey } = useSettingsStore();
+  const [testing, setTesting] = useState<"llm" | "stt" | null>(null);
+  const [result, setResult] = useState<ProviderTestResult | null>(null);
+  const customStt = providerId === "openai" && openaiSttBaseUrl.trim() !== "";
+
+  async function run(kind: "llm" | "stt") {
+    setTesting(kind);
+    setResult(null);
+    try {
+      setResult(
+        kind === "llm"
+          ? await testLlmProvider({
+              provider: providerId,
+              apiKey: config.apiKey,
+              model: config.model,
+              baseUrl: config.baseUrl || null,
+              timeoutSecs: config.timeoutSecs ?? null,
+              ollamaOptions: ollamaOptionsFor(providerId, config),
+            })
+          : await testSttProvider({
+              provider: providerId,
+              // A custom server only ever gets its own key
+              apiKey: customStt ? openaiSttApiKey : config.apiKey,
+              model: customStt ? openaiSttModel || null : null,
+              baseUrl: customStt ? openaiSttBaseUrl : null,
+            }),
+      );
+    } catch (err) {
+      setResult({ ok: false, latencyMs: 0, message: String(err) });
+    } finally {
+      setTesting(null);
+    }
+  }
+
+  return (
+    <div className="space-y-1.5">
+      <div className="flex items-center gap-2">
+        <Button
+          variant="outline"
+          size="sm"
+          className="h-7 text-xs"
+          disabled={testing !== null}
+          onClick={() => run("llm")}
+        >
+          {testing === "llm" && <Loader2 className="h-3 w-3 mr-1 animate-spin" />}
+          Test connection
+        </Button>
+        {STT_PROVIDERS.includes(providerId) && (
+          <Button
+            variant="outline"
+            size="sm"
+            className="h-7 text-xs"
+            disabled={testing !== null}
+            onClick={() => run("stt")}
+          >
+            {testing === "stt" && <Loader2 className="h-3 w-3 mr-1 animate-spin" />}
+            Test transcription
+          </Button>
+        )}
+      </div>
+      {result && (
+        <p
+          className={cn(
+            "flex items-center gap-1 text-[11px]",
+            result.ok ? "text-emerald-500" : "text-destructive",
+          )}
+        >
+          {result.ok ? (
+            <CircleCheck className="h-3 w-3 shrink-0" />
+          ) : (
+            <CircleAlert className="h-3 w-3 shrink-0" />
+          )}
+          <span className="break-all">
+            {result.message}
+            {result.latencyMs > 0 && ` (${result.latencyMs} ms)`}
+          </span>
+        </p>
+      )}
+    </div>
+  );
+}
+
+/** Ordered list of providers to try when the default one fails. */
+function FallbackOrder() {
+  const { llmProvider, providerConfigs, llmFallbackProviders, setLlmFallbackProviders } =
     useSettingsStore();
 
+  const enabled = llmFallbackProviders.filter((id) => id !== llmProvider);
+  const others = PROVIDERS.filter((p) => p.id !== llmProvider && !enabled.includes(p.id));
+  const rows = [
+    ...enabled.flatMap((id) => PROVIDERS.filter((p) => p.id === id)),
+    ...others,
+  ];
+
+  function toggle(id: string, on: boolean) {
+    setLlmFallbackProviders(on ? [...enabled, id] : enabled.filter((p) => p !== id));
+  }
+
+  function move(index: number, delta: number) {
+    const next = [...enabled];
+    const [item] = next.splice(index, 1);
+    next.splice(index + delta, 0, item);
+    setLlmFallbackProviders(next);
+  }
+
+  return (
+    <div className="space-y-3">
+      <div>
+        <h4 className="text-sm font-semibold">Fallback Order</h4>
+        <p className="text-xs text-muted-foreground">
+          If the default provider fails or is offline, these are tried in order.
+        </p>
+      </div>
+      <div className="rounded-xl border divide-y">
+        {rows.map((provider) => {
+          const index = enabled.indexOf(provider.id);
+          const isOn = index !== -1;
+          const usable = isProviderUsable(provider.id, providerConfigs[provider.id]);
+          return (
+            <div key={provider.id} className="flex items-center gap-3 px-4 py-2.5">
+              <span className="w-4 text-xs text-muted-foreground tabular-nums">
+                {isOn ? index + 1 : ""}
+              </span>
+              <div className="flex-1 min-w-0">
+                <p className="text-sm">{provider.name}</p>
+                {!usable && (
+                  <p className="text-[10px] text-muted-foreground">
+                    Add an API key to use as fallback
+                  </p>
+                )}
+              </div>
+              {isOn && (
+                <div className="flex items-center">
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    className="h-6 w-6 p-0"
+                    disabled={index === 0}
+                    onClick={() => move(index, -1)}
+                    aria-label={`Move ${provider.name} up`}
+                  >
+                    <ChevronUp className="h-3.5 w-3.5" />
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    className="h-6 w-6 p-0"
+                    disabled={index === enabled.length - 1}
+                    onClick={() => move(index, 1)}
+                    aria-label={`Move ${provider.name} down`}
+                  >
+                    <ChevronDown className="h-3.5 w-3.5" />
+                  </Button>
+                </div>
+              )}
+              <Switch
+                checked={isOn}
+                disabled={!usable && !isOn}
+                onCheckedChange={(v) => toggle(provider.id, v)}
+              />
+            </div>
+          );
+        })}
+      </div>
+    </div>
+  );
+}
+
+/** Timeout and retries for OpenAI and Groq transcription requests. */
+function CloudTranscriptionRequests() {
+  const {
+    cloudSttTimeoutSecs,
+    cloudSttMaxRetries,
+    setCloudSttTimeoutSecs,
+    setCloudSttMaxRetries,
+  } = useSettingsStore();
+
+  return (
+    <div className="space-y-3">
+      <div>
+        <h4 className="text-sm font-semibold">Cloud Transcription</h4>
+        <p className="text-xs text-muted-foreground">
+          Rate limits and server errors are retried with increasing delays.
+        </p>
+      </div>
+      <div className="grid grid-cols-2 gap-3">
+        <div className="space-y-1.5">
+          <Label className="text-xs text-muted-foreground">Timeout (seconds)</Label>
+          <Input
+            type="number"
+            min={1}
+            value={cloudSttTimeoutSecs}
+            onChange={(e) => {
+              const secs = parseInt(e.target.value, 10);
+              if (secs > 0) setCloudSttTimeoutSecs(secs);
+            }}
+            className="text-xs"
+          />
+        </div>
+        <div className="space-y-1.5">
+          <Label className="text-xs text-muted-foreground">Retries</Label>
+          <Input
+            type="number"
+            min={0}
+            max={10}
+            value={cloudSttMaxRetries}
+            onChange={(e) => {
+              const retries = parseInt(e.target.value, 10);
+              if (retries >= 0 && retries <= 10) setCloudSttMaxRetries(retries);
+            }}
+            className="text-xs"
+          />
+        </div>
+      </div>
+    </div>
+  );
+}
+
+export default function ProviderSettings() {
+  const {
+    llmProvider,
+    setLlmProvider,
+    providerConfigs,
+    setProviderConfig,
+    openaiSttBaseUrl,
+    openaiSttModel,
+    openaiSttApiKey,
+    setOpenaiSttBaseUrl,
+    setOpenaiSttModel,
+    setOpenaiSttApiKey,
+  } = useSettingsStore();
+
   const [expandedId, setExpandedId] = useState<string | null>(llmProvider);
 
   const handleSelect = useCallback(
@@ -277,24 +626,33 @@ export default function ProviderSettings() {
                       )}
 
                       {/* Model input */}
-                      <div className="space-y-1.5">
-                        <Label className="text-xs text-muted-foreground">
-                          Model
-                        </Label>
-                        <Input
-                          placeholder={provider.defaultModel}
+                      {provider.id === "ollama" ? (
+                        <OllamaModelSelect
                           value={config.model}
-                          onChange={(e) =>
-                            setProviderConfig(provider.id, {
-                              model: e.target.value,
-                            })
-                          }
-                          className="text-xs"
+                          onChange={(v) => setProviderConfig(provider.id, { model: v })}
+                          baseUrl={config.baseUrl}
+                          placeholder={provider.defaultModel}
                         />
-                        <p className="text-[10px] text-muted-foreground/60">
-                          Default: {provider.defaultModel}
-                        </p>
-                      </div>
+                      ) : (
+                        <div className="space-y-1.5">
+                          <Label className="text-xs text-muted-foreground">
+                            Model
+                          </Label>
+                          <Input
+                            placeholder={provider.defaultModel}
+                            value={config.model}
+                            onChange={(e) =>
+                              setProviderConfig(provider.id, {
+                                model: e.target.value,
+                              })
+                            }
+                            className="text-xs"
+                          />
+                          <p className="text-[10px] text-muted-foreground/60">
+                            Default: {provider.defaultModel}
+                          </p>
+                        </div>
+                      )}
 
                       {/* Ollama base URL */}
                       {provider.id === "ollama" && (
@@ -315,6 +673,143 @@ export default function ProviderSettings() {
                         </div>
                       )}
 
+                      {/* OpenAI-compatible transcription server */}
+                      {provider.id === "openai" && (
+                        <div className="grid grid-cols-2 gap-2">
+                          <div className="space-y-1.5">
+                            <Label className="text-xs text-muted-foreground">
+                              Transcription server
+                            </Label>
+                            <Input
+                              placeholder="https://api.openai.com/v1"
+                              value={openaiSttBaseUrl}
+                              onChange={(e) => setOpenaiSttBaseUrl(e.target.value)}
+                              className="text-xs font-mono"
+                            />
+                          </div>
+                          <div className="space-y-1.5">
+                            <Label className="text-xs text-muted-foreground">
+                              Server model
+                            </Label>
+                            <Input
+                              placeholder="whisper-1"
+                              value={openaiSttModel}
+                              disabled={!openaiSttBaseUrl.trim()}
+                              onChange={(e) => setOpenaiSttModel(e.target.value)}
+                              className="text-xs font-mono"
+                            />
+                          </div>
+                          <div className="col-span-2 space-y-1.5">
+                            <Label className="text-xs text-muted-foreground">
+                              Server API key
+                            </Label>
+                            <Input
+                              type="password"
+                              placeholder="Optional"
+                              value={openaiSttApiKey}
+                              disabled={!openaiSttBaseUrl.trim()}
+                              onChange={(e) => setOpenaiSttApiKey(e.target.value)}
+                              className="text-xs font-mono"
+                            />
+                          </div>
+                          <p className="col-span-2 text-[10px] text-muted-foreground/60">
+                            Point OpenAI transcription at a self-hosted server such as
+                            faster-whisper-server, speaches or LocalAI. Your OpenAI key
+                            is never sent there; enter the server&apos;s own key if it
+                            needs one.
+                          </p>
+                        </div>
+                      )}
+
+                      {/* Ollama tuning */}
+                      {provider.id === "ollama" && (
+                        <div className="grid grid-cols-3 gap-2">
+                          <div className="space-y-1.5">
+                            <Label className="text-xs text-muted-foreground">
+                              Context (tokens)
+                            </Label>
+                            <Input
+                              type="number"
+                              min={512}
+                              step={512}
+                              placeholder="Default"
+                              value={config.numCtx ?? ""}
+                              onChange={(e) => {
+                                const n = parseInt(e.target.value, 10);
+                                setProviderConfig(provider.id, {
+                                  numCtx: n > 0 ? n : undefined,
+                                });
+                              }}
+                              className="text-xs"
+                            />
+                          </div>
+                          <div className="space-y-1.5">
+                            <Label className="text-xs text-muted-foreground">
+                              Temperature
+                            </Label>
+                            <Input
+                              type="number"
+                              min={0}
+                              max={2}
+                              step={0.1}
+                              placeholder="Default"
+                              value={config.temperature ?? ""}
+                              onChange={(e) => {
+                                const t = parseFloat(e.target.value);
+                                setProviderConfig(provider.id, {
+                                  temperature: Number.isNaN(t) ? undefined : t,
+                                });
+                              }}
+                              className="text-xs"
+                            />
+                          </div>
+                          <div className="space-y-1.5">
+                            <Label className="text-xs text-muted-foreground">
+                              Keep alive
+                            </Label>
+                            <Input
+                              placeholder="5m"
+                              value={config.keepAlive ?? ""}
+                              onChange={(e) =>
+                                setProviderConfig(provider.id, {
+                                  keepAlive: e.target.value,
+                                })
+                              }
+                              className="text-xs font-mono"
+                            />
+                          </div>
+                          <p className="col-span-3 text-[10px] text-muted-foreground/60">
+                            Keep alive holds the model in memory between dictations
+                            (e.g. 30m, or -1 for always)
+                          </p>
+                        </div>
+                      )}
+
+                      {/* Request timeout */}
+                      <div className="space-y-1.5">
+                        <Label className="text-xs text-muted-foreground">
+                          Timeout (seconds)
+                        </Label>
+                        <Input
+                          type="number"
+                          min={1}
+                          placeholder={String(provider.defaultTimeoutSecs)}
+                          value={config.timeoutSecs ?? ""}
+                          onChange={(e) => {
+                            const secs = parseInt(e.target.value, 10);
+                            setProviderConfig(provider.id, {
+                              timeoutSecs: secs > 0 ? secs : undefined,
+                            });
+                          }}
+                          className="text-xs w-32"
+                        />
+                        <p className="text-[10px] text-muted-foreground/60">
+                          Raise this for long generations on slow hardware
+                        </p>
+                      </div>
+
+                      <ConnectionTest providerId={provider.id} config={config} />
+
                       {/* Docs link */}
                       <Button
                         variant="ghost"
@@ -337,6 +832,10 @@ export default function ProviderSettings() {
           );
         })}
       </div>
+
+      <FallbackOrder />
+
+      <CloudTranscriptionRequests />
     </div>
   );
 }
@@ -1,12 +1,34 @@
 "use client";
 
-import { Filter, Type } from "lucide-react";
+import { useState } from "react";
+import { toast } from "sonner";
+import {
+  ArrowDown,
+  ArrowUp,
+  Download,
+  Filter,
+  Plus,
+  Regex,
+  Trash2,
+  Type,
+  Upload,
+} from "lucide-react";
 import { motion } from "motion/react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { Switch } from "@/components/ui/switch";
+import { ToggleGroup, ToggleGroupItem } from "@/components/ui/toggle-group";
+import { addRule, deleteRule, exportRules, importRules } from "@/lib/tauri-commands";
 import { cn } from "@/lib/utils";
 import { useSettingsStore } from "@/stores/settings-store";
 
+const FLAG_OPTIONS = [
+  { value: "i", label: "Ignore case" },
+  { value: "m", label: "Multi-line" },
+  { value: "s", label: "Dot matches newline" },
+];
+
 const RULE_META: Record<string, { icon: typeof Filter; description: string }> = {
   "remove-fillers": {
     icon: Filter,
@@ -21,21 +43,105 @@ const RULE_META: Record<string, { icon: typeof Filter; description: string }> =
 };
 
 export default function RulesSettings() {
-  const { rules, toggleRule } = useSettingsStore();
+  const { rules, toggleRule, moveRule, loadRules } = useSettingsStore();
+  const [name, setName] = useState("");
+  const [pattern, setPattern] = useState("");
+  const [replacement, setReplacement] = useState("");
+  const [flags, setFlags] = useState<string[]>([]);
+  const hasCustomRules = rules.some((r) => r.pattern != null);
+
+  async function handleAdd() {
+    if (!pattern) return;
+    try {
+      await addRule(name, pattern, replacement, flags.join(""));
+      await loadRules();
+      setName("");
+      setPattern("");
+      setReplacement("");
+      setFlags([]);
+    } catch (err) {
+      toast.error("Couldn't add rule", { description: String(err) });
+    }
+  }
+
+  async function handleDelete(id: string) {
+    try {
+      await deleteRule(id);
+      await loadRules();
+    } catch (err) {
+      toast.error("Couldn't delete rule", { description: String(err) });
+    }
+  }
+
+  async function handleExport() {
+    try {
+      const { save } = await import("@tauri-apps/plugin-dialog");
+      const path = await save({
+        defaultPath: "rules.json",
+        filters: [{ name: "Rules", extensions: ["json"] }],
+      });
+      if (!path) return;
+      const count = await exportRules(path);
+      toast.success(`Exported ${count} rule${count === 1 ? "" : "s"}`);
+    } catch (err) {
+      toast.error("Failed to export rules", { description: String(err) });
+    }
+  }
+
+  async function handleImport() {
+    try {
+      const { open } = await import("@tauri-apps/plugin-dialog");
+      const path = await open({
+        multiple: false,
+        filters: [{ name: "Rules", extensions: ["json"] }],
+      });
+      if (typeof path !== "string") return;
+      const count = await importRules(path);
+      await loadRules();
+      toast.success(
+        count === 0 ? "No new rules to import" : `Imported ${count} rule${count === 1 ? "" : "s"}`,
+      );
+    } catch (err) {
+      toast.error("Failed to import rules", { description: String(err) });
+    }
+  }
 
   return (
     <div className="space-y-8">
-      <div>
-        <h3 className="text-lg font-semibold">Text Processing Rules</h3>
-        <p className="text-sm text-muted-foreground">
-          Rules apply to every transcription in the order shown below.
-        </p>
+      <div className="flex items-start justify-between gap-4">
+        <div>
+          <h3 className="text-lg font-semibold">Text Processing Rules</h3>
+          <p className="text-sm text-muted-foreground">
+            Rules apply to every transcription in the order shown below, unless a pipeline is
+            selected under Pipelines.
+          </p>
+        </div>
+        <div className="flex items-center gap-1 shrink-0">
+          <Button variant="ghost" size="sm" onClick={handleImport} className="text-xs gap-1.5">
+            <Upload className="h-3.5 w-3.5" />
+            Import
+          </Button>
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={handleExport}
+            disabled={!hasCustomRules}
+            className="text-xs gap-1.5"
+          >
+            <Download className="h-3.5 w-3.5" />
+            Export
+          </Button>
+        </div>
       </div>
 
       <div className="space-y-2">
         {rules.map((rule, index) => {
           const meta = RULE_META[rule.id];
-          const Icon = meta?.icon ?? Filter;
+          const isCustom = rule.pattern != null;
+          const Icon = meta?.icon ?? (isCustom ? Regex : Filter);
+          const description = isCustom
+            ? `/${rule.pattern}/${rule.flags ?? ""} \u2192 ${rule.replacement || "(removed)"}`
+            : meta?.description;
 
           return (
             <motion.div
@@ -84,31 +190,127 @@ export default function RulesSettings() {
                     Step {index + 1}
                   </span>
                 </div>
-                {meta?.description && (
+                {description && (
                   <p
                     className={cn(
-                      "text-xs mt-1 leading-relaxed transition-colors",
+                      "text-xs mt-1 leading-relaxed transition-colors break-all",
+                      isCustom && "font-mono",
                       rule.enabled
                         ? "text-muted-foreground"
                         : "text-muted-foreground/30"
                     )}
                   >
-                    {meta.description}
+                    {description}
                   </p>
                 )}
               </div>
 
-              {/* Switch */}
-              <div className="shrink-0 pt-1">
+              {/* Order and switch */}
+              <div className="flex items-center gap-0.5 shrink-0">
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-7 w-7 p-0"
+                  aria-label={`Move ${rule.name} up`}
+                  disabled={index === 0}
+                  onClick={() => moveRule(rule.id, -1)}
+                >
+                  <ArrowUp className="h-3.5 w-3.5" />
+                </Button>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-7 w-7 p-0 mr-2"
+                  aria-label={`Move ${rule.name} down`}
+                  disabled={index === rules.length - 1}
+                  onClick={() => moveRule(rule.id, 1)}
+                >
+                  <ArrowDown className="h-3.5 w-3.5" />
+                </Button>
                 <Switch
                   checked={rule.enabled}
                   onCheckedChange={() => toggleRule(rule.id)}
                 />
+                {isCustom && (
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    className="h-7 w-7 p-0 ml-1 text-muted-foreground/40 hover:text-destructive"
+                    aria-label={`Delete ${rule.name}`}
+                    onClick={() => handleDelete(rule.id)}
+                  >
+                    <Trash2 className="h-3.5 w-3.5" />
+                  </Button>
+                )}
               </div>
             </motion.div>
           );
         })}
       </div>
+
+      {/* Custom regex rule */}
+      <div className="rounded-xl border p-4 space-y-3">
+        <div>
+          <Label className="text-sm">Custom Rule</Label>
+          <p className="text-xs text-muted-foreground mt-0.5">
+            Replace every match of a regular expression. The replacement can refer to capture
+            groups as $1 or {"${name}"} &mdash; e.g. <code>issue (\d+)</code> with{" "}
+            <code>ISSUE-$1</code> turns &ldquo;issue 123&rdquo; into &ldquo;ISSUE-123&rdquo;. Use{" "}
+            <code>{"${1}"}</code> when text follows a group, and leave the replacement empty to
+            remove matches.
+          </p>
+        </div>
+        <div className="grid grid-cols-3 gap-2">
+          <Input
+            placeholder="Name"
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+            aria-label="Rule name"
+            className="h-8 text-sm"
+          />
+          <Input
+            placeholder="Pattern, e.g. \bteh\b"
+            value={pattern}
+            onChange={(e) => setPattern(e.target.value)}
+            aria-label="Pattern"
+            className="h-8 text-sm font-mono"
+          />
+          <Input
+            placeholder="Replacement"
+            value={replacement}
+            onChange={(e) => setReplacement(e.target.value)}
+            onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+            aria-label="Replacement"
+            className="h-8 text-sm font-mono"
+          />
+        </div>
+        <div className="flex items-center justify-between gap-2">
+          <ToggleGroup
+            type="multiple"
+            variant="outline"
+            size="sm"
+            value={flags}
+            onValueChange={setFlags}
+            aria-label="Flags"
+          >
+            {FLAG_OPTIONS.map((flag) => (
+              <ToggleGroupItem key={flag.value} value={flag.value} className="text-xs">
+                {flag.label}
+              </ToggleGroupItem>
+            ))}
+          </ToggleGroup>
+          <Button
+            variant="outline"
+            size="sm"
+            className="h-7 text-xs shrink-0"
+            disabled={!pattern}
+            onClick={handleAdd}
+          >
+            <Plus className="h-3 w-3 mr-1" />
+            Add rule
+          </Button>
+        </div>
+      </div>
     </div>
   );
 }
@@ -20,11 +20,21 @@ import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/com
 import { Progress } from "@/components/ui/progress";
 import { Separator } from "@/components/ui/separator";
 import {
-  checkForUpdate,
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import {
+  checkForUpdates,
   getAppVersion,
+  getUpdateChannel,
+  installUpdate as installPendingUpdate,
   restartApp,
-  type Update,
-  type UpdaterDownloadEvent,
+  setUpdateChannel,
+  type UpdateChannel,
+  type UpdateProgress,
 } from "@/lib/tauri-commands";
 import { cn } from "@/lib/utils";
 
@@ -53,6 +63,18 @@ function formatDate(date?: string): string {
   return parsed.toLocaleString();
 }
 
+async function tauriListen<T>(
+  event: string,
+  handler: (payload: T) => void,
+): Promise<(() => void) | undefined> {
+  try {
+    const { listen } = await import("@tauri-apps/api/event");
+    return await listen<T>(event, (e) => handler(e.payload));
+  } catch {
+    return undefined;
+  }
+}
+
 function isTauriRuntime(): boolean {
   return typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
 }
@@ -68,7 +90,8 @@ export default function UpdateSettingsPage() {
   const [downloadedBytes, setDownloadedBytes] = useState(0);
   const [contentLength, setContentLength] = useState<number | null>(null);
   const [isRestarting, setIsRestarting] = useState(false);
-  const [pendingUpdate, setPendingUpdate] = useState<Update | null>(null);
+  const [hasPendingUpdate, setHasPendingUpdate] = useState(false);
+  const [channel, setChannel] = useState<UpdateChannel>("stable");
 
   useEffect(() => {
     let mounted = true;
@@ -83,40 +106,47 @@ export default function UpdateSettingsPage() {
         // Outside Tauri (web preview/tests)
       });
 
+    getUpdateChannel()
+      .then((saved) => {
+        if (mounted) {
+          setChannel(saved);
+        }
+      })
+      .catch(() => {
+        // Outside Tauri (web preview/tests)
+      });
+
     return () => {
       mounted = false;
     };
   }, []);
 
   useEffect(() => {
-    return () => {
-      if (pendingUpdate) {
-        void pendingUpdate.close().catch(() => {});
+    let cleanup: (() => void) | undefined;
+    tauriListen<UpdateProgress>("update-download-progress", (payload) => {
+      setContentLength(payload.contentLength);
+      setDownloadedBytes(payload.downloadedBytes);
+      if (payload.contentLength && payload.downloadedBytes >= payload.contentLength) {
+        setState("installing");
+        setStatusMessage("Download complete. Installing update...");
       }
-    };
-  }, [pendingUpdate]);
+    }).then((fn) => {
+      cleanup = fn;
+    });
+    return () => cleanup?.();
+  }, []);
 
   const progress = useMemo(() => {
     if (!contentLength || contentLength <= 0) return 0;
     return Math.min(100, (downloadedBytes / contentLength) * 100);
   }, [downloadedBytes, contentLength]);
 
-  const hasPendingUpdate = !!pendingUpdate;
   const busy =
     state === "checking" ||
     state === "downloading" ||
     state === "installing" ||
     isRestarting;
 
-  const replacePendingUpdate = useCallback(async (next: Update | null) => {
-    setPendingUpdate((previous) => {
-      if (previous && previous !== next) {
-        void previous.close().catch(() => {});
-      }
-      return next;
-    });
-  }, []);
-
   const runCheck = useCallback(async (showToast = true) => {
     if (!isTauriRuntime()) {
       setState("unsupported");
@@ -128,14 +158,14 @@ export default function UpdateSettingsPage() {
     setStatusMessage("Checking for updates on GitHub...");
 
     try {
-      const update = await checkForUpdate({ timeout: 30000 });
+      const update = await checkForUpdates();
       setLastCheckedAt(new Date());
 
       if (update) {
-        await replacePendingUpdate(update);
+        setHasPendingUpdate(true);
         setLatestVersion(update.version);
-        setReleaseDate(update.date);
-        setReleaseNotes(update.body);
+        setReleaseDate(update.date ?? undefined);
+        setReleaseNotes(update.body ?? undefined);
         setState("available");
         setStatusMessage(`Version ${update.version} is available.`);
         if (showToast) {
@@ -144,7 +174,7 @@ export default function UpdateSettingsPage() {
           });
         }
       } else {
-        await replacePendingUpdate(null);
+        setHasPendingUpdate(false);
         setLatestVersion(null);
         setReleaseDate(undefined);
         setReleaseNotes(undefined);
@@ -161,11 +191,22 @@ export default function UpdateSettingsPage() {
         toast.error("Update check failed", { description: String(err) });
       }
     }
-  }, [replacePendingUpdate]);
+  }, []);
+
+  async function changeChannel(next: UpdateChannel) {
+    try {
+      await setUpdateChannel(next);
+      setChannel(next);
+      setHasPendingUpdate(false);
+      setLatestVersion(null);
+      await runCheck(false);
+    } catch (err) {
+      toast.error("Failed to change update channel", { description: String(err) });
+    }
+  }
 
   async function installUpdate() {
-    const update = pendingUpdate;
-    if (!update) {
+    if (!hasPendingUpdate) {
       toast.error("No update selected", {
         description: "Check for updates first.",
       });
@@ -178,11 +219,9 @@ export default function UpdateSettingsPage() {
     setContentLength(null);
 
     try {
-      await update.downloadAndInstall((event: UpdaterDownloadEvent) => {
-        handleDownloadEvent(event);
-      });
+      await installPendingUpdate();
 
-      await replacePendingUpdate(null);
+      setHasPendingUpdate(false);
       setState("installed");
       setStatusMessage("Update installed. Restart SobottaAI to finish.");
       toast.success("Update installed", {
@@ -195,26 +234,6 @@ export default function UpdateSettingsPage() {
     }
   }
 
-  function handleDownloadEvent(event: UpdaterDownloadEvent) {
-    switch (event.event) {
-      case "Started":
-        setState("downloading");
-        setContentLength(event.data.contentLength ?? null);
-        setDownloadedBytes(0);
-        setStatusMessage("Downloading update package...");
-        break;
-      case "Progress":
-        setDownloadedBytes((bytes) => bytes + event.data.chunkLength);
-        break;
-      case "Finished":
-        setState("installing");
-        setStatusMessage("Download complete. Installing update...");
-        break;
-      default:
-        break;
-    }
-  }
-
   async function handleRestartNow() {
     setIsRestarting(true);
     try {
@@ -264,6 +283,28 @@ export default function UpdateSettingsPage() {
               </p>
             )}
 
+            <div className="flex items-center justify-between gap-4 rounded-lg border p-3">
+              <div>
+                <p className="text-sm font-medium">Update channel</p>
+                <p className="text-xs text-muted-foreground mt-0.5">
+                  Beta builds get new features first but may be less stable.
+                </p>
+              </div>
+              <Select
+                value={channel}
+                onValueChange={(v) => void changeChannel(v as UpdateChannel)}
+                disabled={busy}
+              >
+                <SelectTrigger className="w-32">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent position="popper">
+                  <SelectItem value="stable">Stable</SelectItem>
+                  <SelectItem value="beta">Beta</SelectItem>
+                </SelectContent>
+              </Select>
+            </div>
+
             <div className="flex flex-wrap gap-2">
               <Button onClick={() => void runCheck(true)} disabled={busy} className="gap-1.5">
                 {state === "checking" ? (
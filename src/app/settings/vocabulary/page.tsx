@@ -2,19 +2,41 @@
 
 import { useState, useEffect, useCallback } from "react";
 import { toast } from "sonner";
-import { X, Loader2, BookOpen, CornerDownLeft } from "lucide-react";
+import { X, Loader2, BookOpen, CornerDownLeft, Plus, Replace, Sparkles } from "lucide-react";
 import { motion, AnimatePresence } from "motion/react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
-
+import { Textarea } from "@/components/ui/textarea";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { LANGUAGES } from "@/components/language-selector";
+import { useSettingsStore } from "@/stores/settings-store";
 
 interface VocabularyTerm {
   id: string;
   term: string;
   replacement?: string;
+  category: string | null;
+  language: string | null;
   createdAt: string;
 }
 
+interface VocabularySuggestion {
+  original: string;
+  term: string;
+  count: number;
+}
+
+/** Select value for terms that apply to every language. */
+const ANY_LANGUAGE = "any";
+/** Filter value that shows every category. */
+const ALL_CATEGORIES = "";
+
 async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   const { invoke: tauriInvoke } = await import("@tauri-apps/api/core");
   return tauriInvoke<T>(cmd, args);
@@ -22,13 +44,22 @@ async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T
 
 export default function VocabularySettings() {
   const [terms, setTerms] = useState<VocabularyTerm[]>([]);
+  const [suggestions, setSuggestions] = useState<VocabularySuggestion[]>([]);
   const [newTerm, setNewTerm] = useState("");
+  const [newCategory, setNewCategory] = useState("");
+  const [newLanguage, setNewLanguage] = useState(ANY_LANGUAGE);
+  const [categoryFilter, setCategoryFilter] = useState(ALL_CATEGORIES);
   const [loading, setLoading] = useState(true);
+  const initialPrompt = useSettingsStore((s) => s.initialPrompt);
+  const setInitialPrompt = useSettingsStore((s) => s.setInitialPrompt);
+  const promptTokenBudget = useSettingsStore((s) => s.promptTokenBudget);
+  const setPromptTokenBudget = useSettingsStore((s) => s.setPromptTokenBudget);
 
   const loadTerms = useCallback(async () => {
     try {
       const data = await invoke<VocabularyTerm[]>("get_vocabulary");
       setTerms(data);
+      setSuggestions(await invoke<VocabularySuggestion[]>("suggest_vocabulary"));
     } catch {
       toast.error("Failed to load vocabulary");
     } finally {
@@ -48,7 +79,11 @@ export default function VocabularySettings() {
       return;
     }
     try {
-      await invoke("add_term", { term: trimmed });
+      await invoke("add_term", {
+        term: trimmed,
+        category: newCategory.trim() || null,
+        language: newLanguage === ANY_LANGUAGE ? null : newLanguage,
+      });
       setNewTerm("");
       await loadTerms();
     } catch {
@@ -56,6 +91,21 @@ export default function VocabularySettings() {
     }
   }
 
+  /** Add the corrected word as a term, or as a replacement for what was heard. */
+  async function acceptSuggestion(suggestion: VocabularySuggestion, asReplacement: boolean) {
+    try {
+      await invoke(
+        "add_term",
+        asReplacement
+          ? { term: suggestion.original, replacement: suggestion.term }
+          : { term: suggestion.term },
+      );
+      await loadTerms();
+    } catch {
+      toast.error("Failed to add term");
+    }
+  }
+
   async function removeTerm(id: string) {
     try {
       await invoke("delete_term", { id });
@@ -65,6 +115,13 @@ export default function VocabularySettings() {
     }
   }
 
+  const categories = [
+    ...new Set(terms.map((t) => t.category).filter((c): c is string => !!c)),
+  ].sort();
+  const visibleTerms = categoryFilter
+    ? terms.filter((t) => t.category === categoryFilter)
+    : terms;
+
   return (
     <div className="space-y-8">
       <div>
@@ -74,6 +131,7 @@ export default function VocabularySettings() {
         </p>
         <p className="text-[11px] text-muted-foreground/50 mt-1.5">
           Works with Whisper and cloud models. Parakeet models do not support vocabulary hints.
+          Terms scoped to a language are only sent when transcribing in that language.
         </p>
       </div>
 
@@ -101,11 +159,92 @@ export default function VocabularySettings() {
         </div>
       </div>
 
-      {/* Term count */}
+      {/* Category and language for new terms */}
+      <div className="flex items-center gap-2 -mt-5">
+        <Input
+          placeholder="Category (optional)"
+          value={newCategory}
+          onChange={(e) => setNewCategory(e.target.value)}
+          list="vocabulary-categories"
+          className="h-8 text-xs flex-1"
+        />
+        <datalist id="vocabulary-categories">
+          {categories.map((c) => (
+            <option key={c} value={c} />
+          ))}
+        </datalist>
+        <Select value={newLanguage} onValueChange={setNewLanguage}>
+          <SelectTrigger className="h-8 w-44 text-xs" aria-label="Term language">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent position="popper" className="max-h-60">
+            <SelectItem value={ANY_LANGUAGE}>All languages</SelectItem>
+            {LANGUAGES.filter((l) => l.code !== "auto").map((lang) => (
+              <SelectItem key={lang.code} value={lang.code}>
+                {lang.name} only
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+
+      {/* Suggestions learned from transcript edits */}
+      {suggestions.length > 0 && (
+        <div className="space-y-2">
+          <p className="flex items-center gap-1.5 text-xs font-medium text-muted-foreground">
+            <Sparkles className="h-3 w-3" />
+            Suggested from your corrections
+          </p>
+          <div className="flex flex-wrap gap-2">
+            {suggestions.map((s) => (
+              <span
+                key={`${s.original}→${s.term}`}
+                title={`You corrected "${s.original}" to "${s.term}" ${s.count} times`}
+                className="inline-flex items-center gap-1.5 rounded-full border border-dashed border-border py-1 pl-3 pr-1.5 text-sm"
+              >
+                <span className="text-muted-foreground/60 line-through">{s.original}</span>
+                {s.term}
+                <button
+                  onClick={() => acceptSuggestion(s, false)}
+                  title={`Add "${s.term}" as a term`}
+                  className="rounded-full p-0.5 hover:bg-muted/50 transition-colors"
+                >
+                  <Plus className="h-3 w-3 text-muted-foreground" />
+                </button>
+                <button
+                  onClick={() => acceptSuggestion(s, true)}
+                  title={`Always replace "${s.original}" with "${s.term}"`}
+                  className="rounded-full p-0.5 hover:bg-muted/50 transition-colors"
+                >
+                  <Replace className="h-3 w-3 text-muted-foreground" />
+                </button>
+              </span>
+            ))}
+          </div>
+        </div>
+      )}
+
+      {/* Term count and category filter */}
       {!loading && terms.length > 0 && (
-        <p className="text-[11px] text-muted-foreground/50">
-          {terms.length} {terms.length === 1 ? "term" : "terms"}
-        </p>
+        <div className="flex flex-wrap items-center gap-1.5">
+          <p className="text-[11px] text-muted-foreground/50 mr-1">
+            {visibleTerms.length} {visibleTerms.length === 1 ? "term" : "terms"}
+          </p>
+          {categories.length > 0 &&
+            [ALL_CATEGORIES, ...categories].map((c) => (
+              <button
+                key={c || "all"}
+                onClick={() => setCategoryFilter(c)}
+                className={`rounded-full px-2 py-0.5 text-[10px] transition-colors ${
+                  categoryFilter === c
+                    ? "bg-primary text-primary-foreground"
+                    : "bg-muted/50 text-muted-foreground hover:bg-muted"
+                }`}
+              >
+                {c || "All"}
+              </button>
+            ))}
+        </div>
       )}
 
       {/* Terms */}
@@ -131,7 +270,7 @@ export default function VocabularySettings() {
       ) : (
         <div className="flex flex-wrap gap-2">
           <AnimatePresence>
-            {terms.map((term) => (
+            {visibleTerms.map((term) => (
               <motion.span
                 key={term.id}
                 initial={{ scale: 0.8, opacity: 0 }}
@@ -142,6 +281,14 @@ export default function VocabularySettings() {
                 className="group inline-flex items-center gap-1.5 rounded-full border border-border bg-card/50 px-3 py-1.5 text-sm"
               >
                 {term.term}
+                {term.language && (
+                  <span
+                    className="text-[9px] uppercase text-muted-foreground/60"
+                    title="Only used when transcribing this language"
+                  >
+                    {term.language}
+                  </span>
+                )}
                 <button
                   onClick={() => removeTerm(term.id)}
                   className="text-muted-foreground/30 hover:text-destructive transition-colors"
@@ -153,6 +300,48 @@ export default function VocabularySettings() {
           </AnimatePresence>
         </div>
       )}
+
+      {/* Initial prompt */}
+      <div className="space-y-2">
+        <div>
+          <h4 className="text-sm font-medium">Initial Prompt</h4>
+          <p className="text-[11px] text-muted-foreground/50 mt-0.5">
+            Describe what you usually dictate so the model expects its style and topic. Sent
+            before your vocabulary terms.
+          </p>
+        </div>
+        <Textarea
+          placeholder="e.g. This is a medical dictation about cardiology."
+          value={initialPrompt}
+          onChange={(e) => setInitialPrompt(e.target.value)}
+          className="text-sm min-h-16"
+        />
+      </div>
+
+      {/* Prompt budget */}
+      <div className="flex items-center justify-between gap-4">
+        <div>
+          <h4 className="text-sm font-medium">Prompt Budget</h4>
+          <p className="text-[11px] text-muted-foreground/50 mt-0.5">
+            Whisper only reads about 224 tokens of prompt. When your terms don&apos;t fit, those
+            for the current language and those you say most often are kept.
+          </p>
+        </div>
+        <Select
+          value={String(promptTokenBudget)}
+          onValueChange={(v) => setPromptTokenBudget(Number(v))}
+        >
+          <SelectTrigger className="w-36 shrink-0" aria-label="Prompt budget">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent position="popper">
+            <SelectItem value="100">100 tokens</SelectItem>
+            <SelectItem value="150">150 tokens</SelectItem>
+            <SelectItem value="200">200 tokens</SelectItem>
+            <SelectItem value="224">224 tokens (max)</SelectItem>
+          </SelectContent>
+        </Select>
+      </div>
     </div>
   );
 }
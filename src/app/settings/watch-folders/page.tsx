@@ -0,0 +1,240 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { FolderOpen, FolderSync, Plus, Trash2 } from "lucide-react";
+import { motion } from "motion/react";
+import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import {
+  deleteWatchFolder,
+  listModels,
+  listWatchFolders,
+  saveWatchFolder,
+  type ModelInfo,
+  type WatchFolder,
+} from "@/lib/tauri-commands";
+import { useSettingsStore } from "@/stores/settings-store";
+
+/** Select value standing in for the selected model (null). */
+const SELECTED_MODEL = "__selected";
+
+function isCloudEngine(engine: string): boolean {
+  if (typeof engine === "string") {
+    return engine === "CloudOpenAI" || engine === "CloudGroq";
+  }
+  const obj = engine as Record<string, unknown>;
+  return "CloudOpenAI" in obj || "CloudGroq" in obj;
+}
+
+function FolderCard({
+  folder,
+  models,
+  index,
+  onSave,
+  onDelete,
+}: {
+  folder: WatchFolder;
+  models: ModelInfo[];
+  index: number;
+  onSave: (folder: WatchFolder) => void;
+  onDelete: () => void;
+}) {
+  const toggles: { key: "writeTxt" | "writeSrt"; label: string; hint: string }[] = [
+    { key: "writeTxt", label: "Write .txt", hint: "Transcript next to the audio file" },
+    { key: "writeSrt", label: "Write .srt", hint: "Subtitles with timestamps" },
+  ];
+
+  return (
+    <motion.div
+      initial={{ opacity: 0, y: 8 }}
+      animate={{ opacity: 1, y: 0 }}
+      transition={{ delay: index * 0.04 }}
+      className="rounded-xl border p-4 space-y-3"
+    >
+      <div className="flex items-center gap-3">
+        <div className="flex items-center justify-center h-8 w-8 rounded-lg shrink-0 bg-primary/10 text-primary">
+          <FolderOpen className="h-4 w-4" />
+        </div>
+        <p className="flex-1 min-w-0 truncate text-sm font-mono" title={folder.path}>
+          {folder.path}
+        </p>
+        <Switch
+          checked={folder.enabled}
+          onCheckedChange={(enabled) => onSave({ ...folder, enabled })}
+          aria-label="Watch this folder"
+        />
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 w-7 p-0 shrink-0 text-muted-foreground/40 hover:text-destructive"
+          aria-label="Stop watching folder"
+          onClick={onDelete}
+        >
+          <Trash2 className="h-3.5 w-3.5" />
+        </Button>
+      </div>
+
+      <div className="flex items-center justify-between gap-4">
+        <Label className="text-sm">Model</Label>
+        <Select
+          value={folder.modelId ?? SELECTED_MODEL}
+          onValueChange={(v) =>
+            onSave({ ...folder, modelId: v === SELECTED_MODEL ? null : v })
+          }
+        >
+          <SelectTrigger className="w-56" aria-label="Model">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent position="popper" className="max-h-60">
+            <SelectItem value={SELECTED_MODEL}>Selected model</SelectItem>
+            {models.map((m) => (
+              <SelectItem key={m.id} value={m.id}>
+                {m.name}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+
+      {toggles.map(({ key, label, hint }) => (
+        <div key={key} className="flex items-center justify-between gap-4">
+          <div>
+            <Label className="text-sm">{label}</Label>
+            <p className="text-xs text-muted-foreground mt-0.5">{hint}</p>
+          </div>
+          <Switch
+            checked={folder[key]}
+            onCheckedChange={(checked) => onSave({ ...folder, [key]: checked })}
+          />
+        </div>
+      ))}
+    </motion.div>
+  );
+}
+
+export default function WatchFoldersSettings() {
+  const [folders, setFolders] = useState<WatchFolder[]>([]);
+  const [models, setModels] = useState<ModelInfo[]>([]);
+  const providerConfigs = useSettingsStore((s) => s.providerConfigs);
+
+  useEffect(() => {
+    listWatchFolders()
+      .then(setFolders)
+      .catch(() => {
+        // Outside Tauri context
+      });
+    listModels()
+      .then(setModels)
+      .catch(() => {});
+  }, []);
+
+  // Downloaded local models, and cloud ones with an API key
+  const hasOpenAiKey = !!providerConfigs["openai"]?.apiKey?.trim();
+  const hasGroqKey = !!providerConfigs["groq"]?.apiKey?.trim();
+  const available = models.filter((m) => {
+    if (!isCloudEngine(m.engine)) return m.downloaded;
+    return m.id.includes("openai") ? hasOpenAiKey : m.id.includes("groq") && hasGroqKey;
+  });
+
+  async function save(folder: WatchFolder) {
+    try {
+      const saved = await saveWatchFolder(folder);
+      setFolders((prev) =>
+        prev.some((f) => f.id === saved.id)
+          ? prev.map((f) => (f.id === saved.id ? saved : f))
+          : [...prev, saved],
+      );
+    } catch (err) {
+      toast.error("Couldn't save folder", { description: String(err) });
+    }
+  }
+
+  async function remove(id: string) {
+    try {
+      await deleteWatchFolder(id);
+      setFolders((prev) => prev.filter((f) => f.id !== id));
+    } catch (err) {
+      toast.error("Couldn't remove folder", { description: String(err) });
+    }
+  }
+
+  async function handleAdd() {
+    try {
+      const { open } = await import("@tauri-apps/plugin-dialog");
+      const path = await open({ directory: true, multiple: false });
+      if (typeof path !== "string") return;
+      if (folders.some((f) => f.path === path)) {
+        toast.error("That folder is already watched");
+        return;
+      }
+      await save({
+        id: "",
+        path,
+        modelId: null,
+        writeTxt: true,
+        writeSrt: false,
+        enabled: true,
+        createdAt: "",
+      });
+    } catch (err) {
+      toast.error("Couldn't add folder", { description: String(err) });
+    }
+  }
+
+  return (
+    <div className="space-y-8">
+      <div className="flex items-start justify-between gap-4">
+        <div>
+          <h3 className="text-lg font-semibold">Watched Folders</h3>
+          <p className="text-sm text-muted-foreground">
+            New audio files in these folders, e.g. a voice recorder&apos;s sync folder, are
+            transcribed into history automatically.
+          </p>
+        </div>
+        <Button
+          variant="ghost"
+          size="sm"
+          className="text-xs gap-1.5 shrink-0"
+          onClick={handleAdd}
+        >
+          <Plus className="h-3.5 w-3.5" />
+          Add Folder
+        </Button>
+      </div>
+
+      {folders.length === 0 ? (
+        <div className="rounded-xl border border-dashed p-6 text-center">
+          <FolderSync className="h-5 w-5 mx-auto text-muted-foreground/60" />
+          <p className="text-sm text-muted-foreground mt-2">No folders watched yet.</p>
+        </div>
+      ) : (
+        <div className="space-y-3">
+          {folders.map((folder, i) => (
+            <FolderCard
+              key={folder.id}
+              folder={folder}
+              models={available}
+              index={i}
+              onSave={save}
+              onDelete={() => remove(folder.id)}
+            />
+          ))}
+        </div>
+      )}
+
+      <p className="text-xs text-muted-foreground">
+        Files already in a folder are left alone. A file is picked up once it has finished
+        copying; the transcript and sidecar files use the same name as the audio.
+      </p>
+    </div>
+  );
+}
@@ -8,12 +8,14 @@ import {
   SelectTrigger,
   SelectValue,
 } from "@/components/ui/select";
+import { sortAiFunctionsByUsage } from "@/lib/tauri-commands";
 import { useSettingsStore } from "@/stores/settings-store";
 
 interface AiFunction {
   id: string;
   name: string;
   isBuiltin: boolean;
+  useCount?: number;
 }
 
 export function AiFunctionPicker() {
@@ -23,7 +25,7 @@ export function AiFunctionPicker() {
   useEffect(() => {
     import("@tauri-apps/api/core")
       .then(({ invoke }) => invoke<AiFunction[]>("list_ai_functions"))
-      .then(setFunctions)
+      .then((fns) => setFunctions(sortAiFunctionsByUsage(fns)))
       .catch(() => {
         // Fallback to built-in list outside Tauri
         setFunctions([
@@ -32,6 +34,10 @@ export function AiFunctionPicker() {
           { id: "summarize", name: "Summarize", isBuiltin: true },
           { id: "casual", name: "Casual Rewrite", isBuiltin: true },
           { id: "translate", name: "Translate to English", isBuiltin: true },
+          { id: "grammar", name: "Fix Grammar", isBuiltin: true },
+          { id: "bullets", name: "Bullet Points", isBuiltin: true },
+          { id: "meeting-notes", name: "Meeting Notes", isBuiltin: true },
+          { id: "slack", name: "Slack Message", isBuiltin: true },
         ]);
       });
   }, []);
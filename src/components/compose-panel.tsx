@@ -0,0 +1,122 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { Check, Loader2, NotebookPen, Trash2 } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import {
+  composeOutput,
+  discardCompose,
+  errorMessage,
+  finishCompose,
+  getCompose,
+  startCompose,
+  type ComposeStatus,
+} from "@/lib/tauri-commands";
+import { useRecordingStore } from "@/stores/recording-store";
+import { useSettingsStore } from "@/stores/settings-store";
+
+/**
+ * Compose mode on the home screen: while active, recordings are appended to
+ * one document shown here instead of being pasted. Finishing runs the AI
+ * function once and copies the result to the clipboard.
+ */
+export function ComposePanel({ disabled }: { disabled: boolean }) {
+  const { selectedAiFunction, keepRulesOnlyText, pasteSource } = useSettingsStore();
+  const [status, setStatus] = useState<ComposeStatus | null>(null);
+  const [finishing, setFinishing] = useState(false);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    getCompose()
+      .then((s) => !cancelled && setStatus(s))
+      .catch(() => {});
+    import("@tauri-apps/api/event")
+      .then(({ listen }) =>
+        listen<ComposeStatus | null>("compose-changed", (event) => setStatus(event.payload)),
+      )
+      .then((fn) => {
+        if (cancelled) fn();
+        else unlisten = fn;
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
+  async function finish() {
+    setFinishing(true);
+    try {
+      const result = await finishCompose({
+        aiFunction: selectedAiFunction,
+        keepRulesText: keepRulesOnlyText,
+      });
+      const text = composeOutput(result, pasteSource);
+      await navigator.clipboard.writeText(text);
+      useRecordingStore.getState().setLastResult(text);
+      toast.success("Document copied to clipboard");
+    } catch (err) {
+      toast.error("Finishing compose failed", { description: errorMessage(err) });
+    } finally {
+      setFinishing(false);
+    }
+  }
+
+  if (!status) {
+    return (
+      <Button
+        variant="ghost"
+        size="sm"
+        onClick={() => startCompose().catch(() => {})}
+        disabled={disabled}
+        className="text-xs gap-1.5 text-muted-foreground"
+      >
+        <NotebookPen className="h-3.5 w-3.5" />
+        Compose across recordings
+      </Button>
+    );
+  }
+
+  return (
+    <div className="w-full max-w-lg rounded-lg border border-primary/30 bg-card/50 backdrop-blur-sm p-4">
+      <p className="text-[11px] font-medium uppercase tracking-wider text-muted-foreground mb-1.5">
+        Composing · {status.parts} {status.parts === 1 ? "dictation" : "dictations"}
+      </p>
+      <div className="max-h-40 overflow-y-auto pr-1">
+        <p className="text-sm leading-relaxed">
+          {status.text || (
+            <span className="text-muted-foreground">Record to add to the document.</span>
+          )}
+        </p>
+      </div>
+      <div className="mt-3 flex justify-end gap-2">
+        <Button
+          variant="ghost"
+          size="sm"
+          onClick={() => discardCompose().catch(() => {})}
+          disabled={finishing}
+          className="text-xs gap-1.5"
+        >
+          <Trash2 className="h-3.5 w-3.5" />
+          Discard
+        </Button>
+        <Button
+          size="sm"
+          onClick={finish}
+          disabled={disabled || finishing || !status.text}
+          className="text-xs gap-1.5"
+        >
+          {finishing ? (
+            <Loader2 className="h-3.5 w-3.5 animate-spin" />
+          ) : (
+            <Check className="h-3.5 w-3.5" />
+          )}
+          Finish
+        </Button>
+      </div>
+    </div>
+  );
+}
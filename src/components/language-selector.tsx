@@ -9,7 +9,7 @@ import {
 } from "@/components/ui/select";
 import { useSettingsStore } from "@/stores/settings-store";
 
-const LANGUAGES = [
+export const LANGUAGES = [
   { code: "auto", name: "Auto-detect" },
   { code: "en", name: "English" },
   { code: "es", name: "Spanish" },
@@ -11,7 +11,7 @@ import {
 } from "@/components/ui/select";
 import { useSettingsStore } from "@/stores/settings-store";
 
-const LOCAL_MODELS = [
+export const LOCAL_MODELS = [
   { id: "whisper-tiny", name: "Whisper Tiny" },
   { id: "whisper-base", name: "Whisper Base" },
   { id: "whisper-small", name: "Whisper Small" },
@@ -21,11 +21,28 @@ const LOCAL_MODELS = [
   { id: "parakeet-tdt-0.6b-v3", name: "Parakeet TDT v3 (Multi)" },
 ];
 
-const CLOUD_MODELS = [
-  { id: "cloud-openai-whisper", name: "OpenAI Whisper (Cloud)" },
-  { id: "cloud-groq-whisper", name: "Groq Whisper (Cloud)" },
+export const CLOUD_MODELS = [
+  { id: "cloud-openai-whisper", name: "OpenAI Whisper (Cloud)", provider: "openai" },
+  {
+    id: "cloud-openai-gpt-4o-transcribe",
+    name: "OpenAI GPT-4o Transcribe (Cloud)",
+    provider: "openai",
+  },
+  {
+    id: "cloud-openai-gpt-4o-mini-transcribe",
+    name: "OpenAI GPT-4o Mini Transcribe (Cloud)",
+    provider: "openai",
+  },
+  { id: "cloud-groq-whisper", name: "Groq Whisper (Cloud)", provider: "groq" },
+  { id: "cloud-groq-whisper-large-v3", name: "Groq Whisper Large V3 (Cloud)", provider: "groq" },
+  { id: "cloud-groq-distil-whisper", name: "Groq Distil-Whisper (Cloud, EN)", provider: "groq" },
 ];
 
+/** Provider whose API key a cloud model needs, or null for local models. */
+export function cloudProvider(modelId: string): string | null {
+  return CLOUD_MODELS.find((m) => m.id === modelId)?.provider ?? null;
+}
+
 export function ModelSelector() {
   const { selectedModel, setSelectedModel } = useSettingsStore();
 
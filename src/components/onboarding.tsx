@@ -1,11 +1,21 @@
 "use client";
 
-import { useState, useMemo } from "react";
-import { Mic, Download, Keyboard, Sparkles, ArrowRight, Check, Loader2 } from "lucide-react";
+import { useState, useMemo, useEffect } from "react";
+import {
+  Mic,
+  Download,
+  Keyboard,
+  Sparkles,
+  ArrowRight,
+  Check,
+  Loader2,
+  ShieldCheck,
+} from "lucide-react";
 import { motion, AnimatePresence } from "motion/react";
 import { Button } from "@/components/ui/button";
 import { useSettingsStore } from "@/stores/settings-store";
 import { useIsMac } from "@/lib/hotkey-utils";
+import * as commands from "@/lib/tauri-commands";
 
 interface OnboardingProps {
   onComplete: () => void;
@@ -15,24 +25,40 @@ function getSteps(isMac: boolean) {
   const hotkeyName = isMac ? "Option+Space" : "Alt+Space";
   return [
     {
+      id: "welcome",
       icon: Mic,
       title: "Welcome to SobottaAI",
       description:
         "Open-source voice-to-text with local AI. Your voice data stays on your device — no cloud required.",
     },
+    // Only macOS gates microphone and keystroke access per app
+    ...(isMac
+      ? [
+          {
+            id: "permissions",
+            icon: ShieldCheck,
+            title: "Grant Permissions",
+            description:
+              "SobottaAI needs microphone access to record and Accessibility access to paste into other apps.",
+          },
+        ]
+      : []),
     {
+      id: "model",
       icon: Download,
       title: "Download a Model",
       description:
         "You need a speech-to-text model to transcribe. We recommend starting with Whisper Base (~150 MB).",
     },
     {
+      id: "hotkey",
       icon: Keyboard,
       title: "Your Hotkey",
       description:
         `Press and hold ${hotkeyName} to record. Release to transcribe and paste.`,
     },
     {
+      id: "done",
       icon: Sparkles,
       title: "You're All Set!",
       description:
@@ -46,6 +72,74 @@ async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Prom
   return invoke<T>(cmd, args);
 }
 
+const PERMISSION_ROWS: { key: keyof commands.Permissions; label: string }[] = [
+  { key: "microphone", label: "Microphone" },
+  { key: "accessibility", label: "Accessibility" },
+];
+
+/** Live permission status; polls while shown so grants in System Settings show up. */
+function PermissionsStep() {
+  const [permissions, setPermissions] = useState<commands.Permissions | null>(null);
+  const [requesting, setRequesting] = useState(false);
+
+  useEffect(() => {
+    const refresh = () =>
+      commands
+        .checkPermissions()
+        .then(setPermissions)
+        .catch(() => {
+          // Outside Tauri context
+        });
+    refresh();
+    const interval = setInterval(refresh, 1500);
+    return () => clearInterval(interval);
+  }, []);
+
+  async function request() {
+    setRequesting(true);
+    try {
+      setPermissions(await commands.requestPermissions());
+    } catch (err) {
+      console.error("Permission request failed:", err);
+    } finally {
+      setRequesting(false);
+    }
+  }
+
+  const allGranted =
+    permissions !== null &&
+    PERMISSION_ROWS.every(({ key }) => permissions[key] === "granted");
+
+  return (
+    <div className="space-y-3">
+      <div className="rounded-lg border divide-y text-sm">
+        {PERMISSION_ROWS.map(({ key, label }) => {
+          const granted = permissions?.[key] === "granted";
+          return (
+            <div key={key} className="flex items-center justify-between px-4 py-2.5">
+              <span>{label}</span>
+              {granted ? (
+                <span className="flex items-center gap-1 text-green-600">
+                  <Check className="h-4 w-4" />
+                  Granted
+                </span>
+              ) : (
+                <span className="text-muted-foreground">Not granted</span>
+              )}
+            </div>
+          );
+        })}
+      </div>
+      {!allGranted && (
+        <Button variant="outline" onClick={request} disabled={requesting} className="w-full">
+          {requesting && <Loader2 className="h-4 w-4 mr-2 animate-spin" />}
+          Grant Access
+        </Button>
+      )}
+    </div>
+  );
+}
+
 export function Onboarding({ onComplete }: OnboardingProps) {
   const [step, setStep] = useState(0);
   const [direction, setDirection] = useState(1); // 1 = forward
@@ -58,7 +152,7 @@ export function Onboarding({ onComplete }: OnboardingProps) {
   const current = steps[step];
   const Icon = current.icon;
   const isLast = step === steps.length - 1;
-  const isModelStep = step === 1;
+  const isModelStep = current.id === "model";
 
   async function downloadBaseModel() {
     setDownloading(true);
@@ -127,6 +221,8 @@ export function Onboarding({ onComplete }: OnboardingProps) {
               <p className="text-muted-foreground">{current.description}</p>
             </div>
 
+            {current.id === "permissions" && <PermissionsStep />}
+
             {isModelStep && (
               <div className="space-y-3">
                 {downloaded ? (
@@ -5,6 +5,7 @@ import { useRouter } from "next/navigation";
 import { Box, Globe, Sparkles, Download, AlertCircle } from "lucide-react";
 import { motion, LayoutGroup } from "motion/react";
 import { cn } from "@/lib/utils";
+import { sortAiFunctionsByUsage } from "@/lib/tauri-commands";
 import { useSettingsStore } from "@/stores/settings-store";
 import { ComboboxPicker, type ComboboxOption } from "@/components/combobox-picker";
 import {
@@ -44,6 +45,7 @@ interface AiFunction {
   id: string;
   name: string;
   isBuiltin: boolean;
+  useCount?: number;
 }
 
 function PillButton({
@@ -165,7 +167,7 @@ export function QuickSettings() {
   useEffect(() => {
     import("@tauri-apps/api/core")
       .then(({ invoke }) => invoke<AiFunction[]>("list_ai_functions"))
-      .then(setFunctions)
+      .then((fns) => setFunctions(sortAiFunctionsByUsage(fns)))
       .catch(() => {
         setFunctions([
           { id: "email", name: "Email", isBuiltin: true },
@@ -175,7 +177,7 @@ export function QuickSettings() {
       });
   }, []);
 
-  // Show first 3 as inline pills
+  // Show the 3 most used as inline pills
   const topFunctions = functions.slice(0, 3);
 
   return (
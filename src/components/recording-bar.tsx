@@ -2,7 +2,9 @@
 
 import { useEffect, useRef, useState } from "react";
 import { motion, AnimatePresence, LayoutGroup } from "motion/react";
+import { X } from "lucide-react";
 import { cn } from "@/lib/utils";
+import { cancelAiFunction } from "@/lib/tauri-commands";
 
 async function tauriListen<T>(
   event: string,
@@ -77,6 +79,22 @@ function SoundBars({ level }: { level: number }) {
   );
 }
 
+// ── Spectrum bars (recording state, when band levels are emitted) ──
+
+function SpectrumBars({ bands }: { bands: number[] }) {
+  return (
+    <div className="flex items-end gap-[2px] h-4">
+      {bands.map((level, i) => (
+        <div
+          key={i}
+          className="w-[2px] rounded-full bg-recording transition-[height] duration-75"
+          style={{ height: `${MIN_HEIGHT + level * (MAX_HEIGHT - MIN_HEIGHT)}px` }}
+        />
+      ))}
+    </div>
+  );
+}
+
 // ── Transcribing bars (loading-style bouncing) ──
 
 const TRANSCRIBE_BAR_COUNT = 5;
@@ -164,12 +182,19 @@ function ProcessingWave() {
 
 type PipelineState = "idle" | "recording" | "transcribing" | "ai-processing" | "complete";
 
+const PARTIAL_MAX_CHARS = 18;
+
 export function RecordingBar() {
   // Default to "recording" — the bar is only shown during active recording,
   // so the first render should already display the recording state.
   const [pipelineState, setPipelineState] = useState<PipelineState>("recording");
   const [duration, setDuration] = useState(0);
   const [audioLevel, setAudioLevel] = useState(0);
+  const [partialText, setPartialText] = useState("");
+  const [bands, setBands] = useState<number[]>([]);
+  const [progress, setProgress] = useState<number | null>(null);
+  // Place in the provider queue while the AI request waits; 0 once it runs
+  const [queuePosition, setQueuePosition] = useState(0);
   const smoothedRef = useRef(0);
 
   useEffect(() => {
@@ -183,6 +208,10 @@ export function RecordingBar() {
           setPipelineState("recording");
           setDuration(0);
           setAudioLevel(0);
+          setPartialText("");
+          setBands([]);
+          setProgress(null);
+          setQueuePosition(0);
           smoothedRef.current = 0;
         }),
       );
@@ -191,6 +220,7 @@ export function RecordingBar() {
           setPipelineState("recording");
           setDuration(0);
           setAudioLevel(0);
+          setPartialText("");
           smoothedRef.current = 0;
         }),
       );
@@ -208,6 +238,27 @@ export function RecordingBar() {
           setAudioLevel(smoothed);
         }),
       );
+      cleanups.push(
+        await tauriListen<number[]>("audio-spectrum", (levels) => {
+          // Fall slowly so the bars don't flicker between frames
+          setBands((prev) => levels.map((l, i) => Math.max(l, (prev[i] ?? 0) * 0.8)));
+        }),
+      );
+      cleanups.push(
+        await tauriListen<{ percent: number }>("transcription-progress", ({ percent }) => {
+          setProgress(percent);
+        }),
+      );
+      cleanups.push(
+        await tauriListen<{ text: string }>("recording-partial-text", ({ text }) => {
+          setPartialText(text);
+        }),
+      );
+      cleanups.push(
+        await tauriListen<{ position: number }>("llm-queue", ({ position }) => {
+          setQueuePosition(position);
+        }),
+      );
       cleanups.push(
         await tauriListen<string>("pipeline-state", (state) => {
           setPipelineState((prev) => {
@@ -248,12 +299,22 @@ export function RecordingBar() {
         ? "transcribing"
         : "idle";
 
+  // Live transcript replaces the label; only the latest words fit
+  const partialTail =
+    partialText.length > PARTIAL_MAX_CHARS
+      ? "…" + partialText.slice(-PARTIAL_MAX_CHARS)
+      : partialText;
+
   const statusLabel = isRecording
-    ? "Recording"
+    ? partialTail || "Recording"
     : isTranscribing
-      ? "Transcribing..."
+      ? progress !== null
+        ? `Transcribing ${progress}%`
+        : "Transcribing..."
       : isAiProcessing
-        ? "Processing..."
+        ? queuePosition > 0
+          ? `Queued #${queuePosition}`
+          : "Processing..."
         : "Done";
 
   return (
@@ -278,7 +339,11 @@ export function RecordingBar() {
             className="flex items-end"
           >
             {isRecording ? (
-              <SoundBars level={audioLevel} />
+              bands.length > 0 ? (
+                <SpectrumBars bands={bands} />
+              ) : (
+                <SoundBars level={audioLevel} />
+              )
             ) : isAiProcessing ? (
               <ProcessingWave />
             ) : isTranscribing ? (
@@ -318,6 +383,18 @@ export function RecordingBar() {
             {statusLabel}
           </motion.span>
         </AnimatePresence>
+
+        {/* Cancel — the unprocessed text is pasted instead */}
+        {isAiProcessing && (
+          <button
+            type="button"
+            onClick={() => cancelAiFunction()}
+            aria-label="Cancel AI processing"
+            className="-mr-1.5 rounded-full p-0.5 text-muted-foreground hover:text-foreground"
+          >
+            <X className="h-3.5 w-3.5" />
+          </button>
+        )}
       </motion.div>
     </LayoutGroup>
   );
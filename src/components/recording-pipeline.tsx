@@ -4,6 +4,16 @@ import { useCallback, useEffect, useRef, useState } from "react";
 import { toast } from "sonner";
 import { useRecordingStore } from "@/stores/recording-store";
 import { useSettingsStore } from "@/stores/settings-store";
+import {
+  composeOutput,
+  errorMessage,
+  finishCompose,
+  isCommandError,
+  type ComposeStatus,
+  type HotkeyBinding,
+  type PipelineOutput,
+} from "@/lib/tauri-commands";
+import { cloudProvider } from "@/components/model-selector";
 
 /**
  * Headless component that manages the global recording pipeline.
@@ -11,8 +21,14 @@ import { useSettingsStore } from "@/stores/settings-store";
  * Only activates in the "main" window — the recording-bar window skips it.
  *
  * Listens for Tauri events:
+ *   recording-will-start → resets state, remembers the hotkey binding preset
  *   recording-started  → updates store, starts timer
- *   recording-stopped  → stops timer, runs transcribe → rules → AI → paste → save
+ *   recording-stopped  → stops timer, runs transcribe → pipeline → paste → save
+ *   recording-too-short → tells the user an accidental tap was discarded
+ *   recording-device-changed → warns that the microphone disconnected mid-recording
+ *   recording-input-degraded → offers the built-in mic over a Bluetooth headset in HFP
+ *   rewrite-selection-requested → runs the rewrite AI function on the selected text
+ *   compose-finish-requested → finishes the compose document and pastes it
  */
 
 async function tauriListen<T>(
@@ -78,6 +94,71 @@ interface TranscriptionResult {
   language: string | null;
   segments: { startMs: number; endMs: number; text: string }[];
   durationMs: number;
+  metrics?: {
+    engine: string;
+    inferenceMs: number;
+    realTimeFactor: number;
+    threads: number | null;
+  } | null;
+}
+
+interface JobInfo {
+  id: string;
+  status: "queued" | "running" | "completed" | "failed" | "cancelled";
+  progress: number;
+  result: TranscriptionResult | null;
+  error: string | null;
+}
+
+/** Payload of `audio-import`: a file dropped on the main window. */
+interface AudioImportEvent {
+  path: string;
+  stage: "importing" | "transcribing" | "failed";
+  sessionId: string | null;
+  jobId: string | null;
+  modelId: string | null;
+  error: string | null;
+}
+
+function fileName(path: string): string {
+  return path.split(/[\\/]/).pop() ?? path;
+}
+
+/**
+ * Start a transcription job and resolve once its `job-updated` event reports
+ * a terminal state. The listener is attached before the job starts so a fast
+ * job can't finish before we know its id — early events are buffered.
+ */
+function runTranscriptionJob(args: Record<string, unknown>): Promise<TranscriptionResult> {
+  return new Promise((resolve, reject) => {
+    let jobId: string | null = null;
+    let unlisten: (() => void) | undefined;
+    const early: JobInfo[] = [];
+
+    const settle = (job: JobInfo) => {
+      if (job.status === "completed" && job.result) {
+        unlisten?.();
+        resolve(job.result);
+      } else if (job.status === "failed" || job.status === "cancelled") {
+        unlisten?.();
+        reject(job.error ?? `Transcription ${job.status}`);
+      }
+    };
+
+    tauriListen<JobInfo>("job-updated", (job) => {
+      if (jobId === null) early.push(job);
+      else if (job.id === jobId) settle(job);
+    }).then(async (fn) => {
+      unlisten = fn;
+      try {
+        jobId = await tauriInvoke<string>("transcribe", args);
+        early.filter((job) => job.id === jobId).forEach(settle);
+      } catch (err) {
+        unlisten?.();
+        reject(err);
+      }
+    });
+  });
 }
 
 export function RecordingPipeline() {
@@ -109,9 +190,12 @@ export function RecordingPipeline() {
     selectedModel,
     selectedLanguage,
     selectedAiFunction,
-    rules,
-    llmProvider,
+    pipelineId,
     providerConfigs,
+    keepRulesOnlyText,
+    pasteSource,
+    rewriteAiFunction,
+    _hydrated,
   } = useSettingsStore();
 
   const timerRef = useRef<ReturnType<typeof setInterval> | null>(null);
@@ -122,20 +206,30 @@ export function RecordingPipeline() {
   // a newer recording cycle (e.g. hiding the bar, emitting "complete").
   const generationRef = useRef(0);
 
+  // Unfinished sessions from a previous run are recovered once per launch
+  const recoveredRef = useRef(false);
+
+  // Preset of the hotkey binding that started the current recording, if any
+  const presetRef = useRef<HotkeyBinding | null>(null);
+
   // Keep refs current so the event handlers always use latest settings
   const selectedModelRef = useRef(selectedModel);
   const selectedLanguageRef = useRef(selectedLanguage);
   const selectedAiFunctionRef = useRef(selectedAiFunction);
-  const rulesRef = useRef(rules);
-  const llmProviderRef = useRef(llmProvider);
+  const pipelineIdRef = useRef(pipelineId);
   const providerConfigsRef = useRef(providerConfigs);
+  const keepRulesOnlyTextRef = useRef(keepRulesOnlyText);
+  const pasteSourceRef = useRef(pasteSource);
+  const rewriteAiFunctionRef = useRef(rewriteAiFunction);
 
   useEffect(() => { selectedModelRef.current = selectedModel; }, [selectedModel]);
   useEffect(() => { selectedLanguageRef.current = selectedLanguage; }, [selectedLanguage]);
   useEffect(() => { selectedAiFunctionRef.current = selectedAiFunction; }, [selectedAiFunction]);
-  useEffect(() => { rulesRef.current = rules; }, [rules]);
-  useEffect(() => { llmProviderRef.current = llmProvider; }, [llmProvider]);
+  useEffect(() => { pipelineIdRef.current = pipelineId; }, [pipelineId]);
   useEffect(() => { providerConfigsRef.current = providerConfigs; }, [providerConfigs]);
+  useEffect(() => { keepRulesOnlyTextRef.current = keepRulesOnlyText; }, [keepRulesOnlyText]);
+  useEffect(() => { pasteSourceRef.current = pasteSource; }, [pasteSource]);
+  useEffect(() => { rewriteAiFunctionRef.current = rewriteAiFunction; }, [rewriteAiFunction]);
 
   const startTimer = useCallback(() => {
     if (timerRef.current) clearInterval(timerRef.current);
@@ -162,29 +256,22 @@ export function RecordingPipeline() {
       setIsTranscribing(true);
       tauriEmit("pipeline-state", "transcribing");
       try {
-        const lang = selectedLanguageRef.current;
-        const modelId = selectedModelRef.current;
-        const aiFunctionId = selectedAiFunctionRef.current;
-        const enabledRules = rulesRef.current
-          .filter((r) => r.enabled)
-          .map((r) => r.id);
-
-        // Cloud model API keys
-        let transcribeApiKey: string | null = null;
-        let transcribeCloudModel: string | null = null;
-        if (modelId === "cloud-openai-whisper") {
-          transcribeApiKey = providerConfigsRef.current["openai"]?.apiKey ?? null;
-        } else if (modelId === "cloud-groq-whisper") {
-          transcribeApiKey = providerConfigsRef.current["groq"]?.apiKey ?? null;
-          transcribeCloudModel = providerConfigsRef.current["groq"]?.model ?? null;
-        }
-
-        const result = await tauriInvoke<TranscriptionResult>("transcribe", {
+        const preset = presetRef.current;
+        const lang = preset?.language ?? selectedLanguageRef.current;
+        const modelId = preset?.modelId ?? selectedModelRef.current;
+        const aiFunctionId = preset?.aiFunction ?? selectedAiFunctionRef.current;
+
+        // Cloud model API key; the backend maps the model id to the provider's model
+        const provider = cloudProvider(modelId);
+        const transcribeApiKey = provider
+          ? (providerConfigsRef.current[provider]?.apiKey ?? null)
+          : null;
+
+        const result = await runTranscriptionJob({
           sessionId: sid,
           modelId,
           language: lang === "auto" ? null : lang,
           apiKey: transcribeApiKey,
-          cloudModel: transcribeCloudModel,
         });
 
         const trimmed = result.text.trim();
@@ -193,52 +280,97 @@ export function RecordingPipeline() {
           return;
         }
 
-        let finalText = result.text;
-
-        // Apply regex rules
-        if (enabledRules.length > 0) {
-          finalText = await tauriInvoke<string>("apply_rules", {
-            text: finalText,
-            enabledRuleIds: enabledRules,
+        // While composing, the dictation is appended to the document; AI,
+        // paste and history happen once when the document is finished
+        const composing =
+          (await tauriInvoke<ComposeStatus | null>("get_compose").catch(() => null)) !== null;
+
+        // Rules, vocabulary, AI functions and redaction in the order of the
+        // chosen pipeline — without one, the saved rules and then the AI
+        // function. The backend resolves the AI function's provider and key.
+        const pipelineId = preset?.pipelineId ?? pipelineIdRef.current;
+        const output = await tauriInvoke<PipelineOutput>("run_pipeline", {
+          text: result.text,
+          pipelineId,
+          aiFunction: aiFunctionId,
+          skipAi: composing,
+        });
+        const rulesText = output.rulesText;
+
+        if (composing) {
+          const composed = await tauriInvoke<ComposeStatus | null>("compose_append", {
+            part: {
+              sessionId: sid,
+              transcript: result.text,
+              rulesText,
+              modelId,
+              language: lang === "auto" ? null : lang,
+              durationMs: recordingDurationMs ?? result.durationMs,
+            },
           });
+          if (composed) {
+            if (isCurrent()) setLastResult(null);
+            return;
+          }
         }
 
-        // Apply AI function
-        let processedText: string | null = null;
-        const activeProvider = llmProviderRef.current;
-        const activeConfig = providerConfigsRef.current[activeProvider];
-        console.log("[pipeline] AI function check:", {
-          aiFunctionId,
-          activeProvider,
-          hasApiKey: !!activeConfig?.apiKey,
-          model: activeConfig?.model,
-        });
-        if (aiFunctionId && (activeConfig?.apiKey || activeProvider === "ollama")) {
-          console.log("[pipeline] Starting AI processing with:", activeProvider, activeConfig?.model);
-          if (isCurrent()) tauriEmit("pipeline-state", "ai-processing");
+        // A failed AI function is skipped — the text is pasted without it.
+        // Missing keys and cancellation are skipped silently by the backend.
+        if (output.aiError) {
+          console.error("[pipeline] AI function failed:", output.aiError);
+          if (isCurrent()) {
+            toast.error("AI function failed", { description: output.aiError.message });
+          }
+        }
+        const processedText = output.processedText;
+        const finalText = output.text;
+
+        // Which stage gets pasted is configurable — the full pipeline output
+        // by default, or the rules-only / verbatim text for exact wording.
+        const pasteText =
+          pasteSourceRef.current === "verbatim"
+            ? result.text
+            : pasteSourceRef.current === "rules"
+              ? rulesText
+              : finalText;
+
+        if (isCurrent()) setLastResult(pasteText);
+
+        // Paste (or the AI function's own output action) — only if this is
+        // still the active cycle
+        let secureField = false;
+        if (isCurrent()) {
           try {
-            processedText = await tauriInvoke<string>("execute_ai_function", {
-              text: finalText,
-              functionId: aiFunctionId,
-              llmProvider: activeProvider,
-              llmApiKey: activeConfig?.apiKey ?? "",
-              llmModel: activeConfig?.model ?? "",
+            secureField = await tauriInvoke<boolean>("deliver_output", {
+              functionId: output.aiFunction,
+              text: pasteText,
             });
-            console.log("[pipeline] AI function returned:", processedText?.length, "chars");
-            finalText = processedText;
+            if (secureField) {
+              setLastResult(null);
+              toast.warning("Pasted into a password field", {
+                description: "The dictation wasn't saved to history",
+              });
+            }
           } catch (err) {
-            console.error("[pipeline] AI function failed:", err);
-            if (isCurrent()) toast.error("AI function failed", { description: String(err) });
+            if (isCommandError(err) && err.code === "secureField") {
+              secureField = true;
+              setLastResult(null);
+              toast.warning("Not pasted into a password field", {
+                description: "The dictation wasn't saved to history",
+              });
+            } else {
+              // A failed paste or webhook still leaves the text in history
+              console.error("[pipeline] Output failed:", err);
+              toast.error("Output failed", { description: errorMessage(err) });
+            }
           }
-        } else if (aiFunctionId) {
-          console.warn("[pipeline] AI function selected but no API key configured for provider:", activeProvider);
         }
 
-        if (isCurrent()) setLastResult(finalText);
-
-        // Paste — only if this is still the active cycle
-        if (isCurrent()) {
-          await tauriInvoke("paste_text", { text: finalText });
+        // A password field means the dictation may be a secret: keep it out
+        // of history and drop its audio and transcript
+        if (secureField) {
+          await tauriInvoke("discard_session", { sessionId: sid }).catch(() => {});
+          return;
         }
 
         // Save to history — always save, even if a new recording started
@@ -246,20 +378,24 @@ export function RecordingPipeline() {
           sessionId: sid,
           transcript: result.text,
           processedText,
+          rulesText: keepRulesOnlyTextRef.current ? rulesText : null,
           modelId,
           language: lang === "auto" ? null : lang,
-          aiFunction: aiFunctionId,
+          aiFunction: pipelineId ? output.aiFunction : aiFunctionId,
           durationMs: recordingDurationMs ?? result.durationMs,
+          metrics: result.metrics ?? null,
         }).catch((err: unknown) => {
           console.error("Failed to save history:", err);
         });
       } catch (err) {
         console.error("Transcription failed:", err);
         if (isCurrent()) {
-          toast.error("Transcription failed", { description: String(err) });
+          toast.error("Transcription failed", { description: errorMessage(err) });
           setLastResult(null);
         }
       } finally {
+        // The audio is on disk (or deliberately not, in incognito); free the buffer
+        tauriInvoke("release_session", { sessionId: sid }).catch(() => {});
         // Only clean up UI if this is still the active cycle.
         // If a new recording started, its own cycle owns the UI now.
         if (isCurrent()) {
@@ -273,6 +409,151 @@ export function RecordingPipeline() {
     [setIsTranscribing, setLastResult],
   );
 
+  // Recordings left without a history entry by a crash or quit mid-pipeline
+  // are transcribed and saved (never pasted) once settings are loaded.
+  useEffect(() => {
+    if (!isMainWindow || !_hydrated || recoveredRef.current) return;
+    recoveredRef.current = true;
+
+    (async () => {
+      const sessions = await tauriInvoke<{ sessionId: string; durationMs: number }[]>(
+        "recover_sessions",
+      ).catch(() => []);
+      let saved = 0;
+      for (const { sessionId, durationMs } of sessions) {
+        try {
+          const lang = selectedLanguageRef.current;
+          const modelId = selectedModelRef.current;
+          const provider = cloudProvider(modelId);
+          const result = await runTranscriptionJob({
+            sessionId,
+            modelId,
+            language: lang === "auto" ? null : lang,
+            apiKey: provider ? (providerConfigsRef.current[provider]?.apiKey ?? null) : null,
+          });
+          const trimmed = result.text.trim();
+          if (!trimmed || isHallucination(trimmed)) {
+            await tauriInvoke("discard_session", { sessionId });
+            continue;
+          }
+          await tauriInvoke("save_history_item", {
+            sessionId,
+            transcript: result.text,
+            processedText: null,
+            rulesText: null,
+            modelId,
+            language: lang === "auto" ? null : lang,
+            aiFunction: null,
+            durationMs,
+            metrics: result.metrics ?? null,
+          });
+          saved++;
+        } catch (err) {
+          // Left on disk — it will be offered again on the next launch
+          console.error("Failed to recover session", sessionId, err);
+        } finally {
+          tauriInvoke("release_session", { sessionId }).catch(() => {});
+        }
+      }
+      if (saved > 0) {
+        toast.success(
+          `Recovered ${saved} unfinished recording${saved === 1 ? "" : "s"}`,
+          { description: "Saved to history" },
+        );
+      }
+    })();
+  }, [isMainWindow, _hydrated]);
+
+  // Audio files dropped on the main window: the backend imports them and
+  // starts a transcription job each; the results are saved to history here.
+  useEffect(() => {
+    if (!isMainWindow) return;
+
+    let cancelled = false;
+    const cleanups: ((() => void) | undefined)[] = [];
+    const imports = new Map<string, AudioImportEvent>();
+
+    const finish = async (job: JobInfo) => {
+      const imported = imports.get(job.id);
+      if (!imported || !imported.sessionId) return;
+      imports.delete(job.id);
+      const name = fileName(imported.path);
+      try {
+        if (job.status !== "completed" || !job.result) {
+          throw job.error ?? `Transcription ${job.status}`;
+        }
+        if (!job.result.text.trim()) throw "No speech found in the file";
+        const lang = selectedLanguageRef.current;
+        await tauriInvoke("save_history_item", {
+          sessionId: imported.sessionId,
+          transcript: job.result.text,
+          processedText: null,
+          rulesText: null,
+          modelId: imported.modelId,
+          language: lang === "auto" ? null : lang,
+          aiFunction: null,
+          durationMs: job.result.durationMs,
+          metrics: job.result.metrics ?? null,
+        });
+        toast.success(`Imported ${name}`, { id: imported.path, description: "Saved to history" });
+      } catch (err) {
+        toast.error(`Couldn't import ${name}`, {
+          id: imported.path,
+          description: errorMessage(err),
+        });
+      } finally {
+        tauriInvoke("release_session", { sessionId: imported.sessionId }).catch(() => {});
+      }
+    };
+
+    const onJob = (job: JobInfo) => {
+      const imported = imports.get(job.id);
+      if (!imported) return;
+      if (job.status === "completed" || job.status === "failed" || job.status === "cancelled") {
+        finish(job);
+      } else {
+        toast.loading(`Transcribing ${fileName(imported.path)}`, {
+          id: imported.path,
+          description: `${Math.round(job.progress * 100)}%`,
+        });
+      }
+    };
+
+    const setup = async () => {
+      if (cancelled) return;
+
+      cleanups.push(
+        await tauriListen<AudioImportEvent>("audio-import", async (event) => {
+          const name = fileName(event.path);
+          if (event.stage === "importing") {
+            toast.loading(`Importing ${name}`, { id: event.path });
+          } else if (event.stage === "failed") {
+            toast.error(`Couldn't import ${name}`, {
+              id: event.path,
+              description: event.error ?? undefined,
+            });
+          } else if (event.jobId) {
+            imports.set(event.jobId, event);
+            toast.loading(`Transcribing ${name}`, { id: event.path });
+            // A short file may have finished before this event arrived
+            const job = await tauriInvoke<JobInfo | null>("get_job_status", {
+              jobId: event.jobId,
+            }).catch(() => null);
+            if (job) onJob(job);
+          }
+        }),
+      );
+
+      cleanups.push(await tauriListen<JobInfo>("job-updated", onJob));
+    };
+
+    setup();
+    return () => {
+      cancelled = true;
+      cleanups.forEach((fn) => fn?.());
+    };
+  }, [isMainWindow]);
+
   // Global event listeners — only active in the main window
   useEffect(() => {
     if (!isMainWindow) return;
@@ -287,11 +568,13 @@ export function RecordingPipeline() {
       // init in Rust (~50-200ms).  This resets the store immediately so the UI
       // never shows stale "transcribing" state from the previous cycle.
       // Incrementing the generation invalidates any in-flight transcription's
-      // cleanup (hide bar, emit "complete", etc.).
+      // cleanup (hide bar, emit "complete", etc.). Binding hotkeys send their
+      // preset along; other starts carry none.
       cleanups.push(
-        await tauriListen("recording-will-start", () => {
+        await tauriListen<HotkeyBinding | null>("recording-will-start", (preset) => {
           if (cancelled) return;
           generationRef.current++;
+          presetRef.current = preset ?? null;
           beginRecording();
         }),
       );
@@ -322,6 +605,100 @@ export function RecordingPipeline() {
         }),
       );
 
+      // Below the minimum duration — the backend already discarded the audio
+      // and sent an empty recording-stopped, so only explain what happened
+      cleanups.push(
+        await tauriListen<{ durationMs: number; minDurationMs: number }>(
+          "recording-too-short",
+          (payload) => {
+            if (cancelled) return;
+            toast("Recording too short", {
+              description: `Recordings under ${payload.minDurationMs} ms are ignored`,
+            });
+          },
+        ),
+      );
+
+      // Microphone vanished mid-recording — capture fails over to the default input
+      cleanups.push(
+        await tauriListen<{ error: string; device: string | null }>(
+          "recording-device-changed",
+          (payload) => {
+            if (cancelled) return;
+            if (payload.device) {
+              toast.warning("Microphone disconnected", {
+                description: `Recording continues on ${payload.device}`,
+              });
+            } else {
+              toast.error("Microphone disconnected", {
+                description: "No other input device is available",
+              });
+            }
+          },
+        ),
+      );
+
+      // Bluetooth headset dropped to hands-free (HFP) capture — offer the built-in mic
+      cleanups.push(
+        await tauriListen<{ device: string; sampleRate: number }>(
+          "recording-input-degraded",
+          (payload) => {
+            if (cancelled) return;
+            toast.warning("Low-quality Bluetooth microphone", {
+              description: `${payload.device} records at ${payload.sampleRate / 1000} kHz, which hurts accuracy`,
+              action: {
+                label: "Use built-in mic",
+                onClick: () => useSettingsStore.getState().setPreferBuiltInMic(true),
+              },
+            });
+          },
+        ),
+      );
+
+      // Rewrite hotkey — no dictation, the selected text is the input
+      cleanups.push(
+        await tauriListen("rewrite-selection-requested", async () => {
+          if (cancelled) return;
+          try {
+            await tauriInvoke<string>("rewrite_selection", {
+              functionId: rewriteAiFunctionRef.current,
+            });
+          } catch (err) {
+            console.error("[pipeline] Rewrite selection failed:", err);
+            toast.error("Rewrite failed", { description: errorMessage(err) });
+          }
+        }),
+      );
+
+      // Tray "Finish Compose" — paste the document into the focused app
+      cleanups.push(
+        await tauriListen("compose-finish-requested", async () => {
+          if (cancelled) return;
+          try {
+            const result = await finishCompose({
+              aiFunction: selectedAiFunctionRef.current,
+              keepRulesText: keepRulesOnlyTextRef.current,
+            });
+            const text = composeOutput(result, pasteSourceRef.current);
+            setLastResult(text);
+            // Same output path as a dictation, so the paste mode applies
+            const secureField = await tauriInvoke<boolean>("deliver_output", {
+              functionId: selectedAiFunctionRef.current,
+              text,
+            });
+            if (secureField) setLastResult(null);
+          } catch (err) {
+            if (isCommandError(err) && err.code === "secureField") {
+              setLastResult(null);
+              toast.warning("Not pasted into a password field");
+              return;
+            }
+            console.error("[pipeline] Finishing compose failed:", err);
+            toast.error("Finishing compose failed", { description: errorMessage(err) });
+          }
+        }),
+      );
+
       // Audio level meter — smooth and forward to store
       let smoothed = 0;
       cleanups.push(
@@ -348,6 +725,7 @@ export function RecordingPipeline() {
     setSessionId,
     setDurationMs,
     setAudioLevel,
+    setLastResult,
     startTimer,
     stopTimer,
     transcribeAndPaste,
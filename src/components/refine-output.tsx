@@ -0,0 +1,80 @@
+"use client";
+
+import { useState } from "react";
+import { toast } from "sonner";
+import { Loader2, WandSparkles, X } from "lucide-react";
+import { Input } from "@/components/ui/input";
+import {
+  cancelAiFunction,
+  errorMessage,
+  isCommandError,
+  refineLastOutput,
+} from "@/lib/tauri-commands";
+import { isProviderUsable, useSettingsStore } from "@/stores/settings-store";
+
+/**
+ * Follow-up instruction for the last AI function output ("make it shorter").
+ * The revised text is passed to `onRefined` as it streams in, then copied
+ * to the clipboard.
+ */
+export function RefineOutput({ onRefined }: { onRefined: (text: string) => void }) {
+  const { llmProvider, providerConfigs } = useSettingsStore();
+  const [instruction, setInstruction] = useState("");
+  const [refining, setRefining] = useState(false);
+
+  async function refine() {
+    const trimmed = instruction.trim();
+    if (!trimmed || refining) return;
+    setRefining(true);
+    let unlisten: (() => void) | undefined;
+    try {
+      const { listen } = await import("@tauri-apps/api/event");
+      let streamed = "";
+      unlisten = await listen<{ delta: string }>("ai-output-delta", (event) => {
+        streamed += event.payload.delta;
+        onRefined(streamed);
+      });
+      const text = await refineLastOutput(trimmed);
+      await navigator.clipboard.writeText(text);
+      onRefined(text);
+      setInstruction("");
+      toast.success("Refined and copied to clipboard");
+    } catch (err) {
+      if (isCommandError(err) && err.code === "cancelled") {
+        toast("Refinement cancelled");
+      } else {
+        toast.error("Refinement failed", { description: errorMessage(err) });
+      }
+    } finally {
+      unlisten?.();
+      setRefining(false);
+    }
+  }
+
+  if (!isProviderUsable(llmProvider, providerConfigs[llmProvider])) return null;
+
+  return (
+    <div className="relative mt-3">
+      <WandSparkles className="absolute left-2.5 top-1/2 -translate-y-1/2 h-3.5 w-3.5 text-muted-foreground/50" />
+      <Input
+        placeholder="Refine: make it shorter, more formal..."
+        value={instruction}
+        onChange={(e) => setInstruction(e.target.value)}
+        onKeyDown={(e) => e.key === "Enter" && refine()}
+        disabled={refining}
+        className="h-8 pl-8 text-xs"
+      />
+      {refining && (
+        <button
+          type="button"
+          onClick={() => cancelAiFunction()}
+          aria-label="Cancel refinement"
+          className="group absolute right-2 top-1/2 -translate-y-1/2 p-0.5 text-muted-foreground hover:text-foreground"
+        >
+          <Loader2 className="h-3.5 w-3.5 animate-spin group-hover:hidden" />
+          <X className="hidden h-3.5 w-3.5 group-hover:block" />
+        </button>
+      )}
+    </div>
+  );
+}
@@ -1,7 +1,7 @@
 "use client";
 
 import { useEffect, useRef } from "react";
-import { useSettingsStore } from "@/stores/settings-store";
+import { useSettingsStore, type RecordingMode } from "@/stores/settings-store";
 
 async function tauriListen<T>(
   event: string,
@@ -38,6 +38,8 @@ export function TraySync() {
     setSelectedModel,
     setSelectedLanguage,
     setSelectedAiFunction,
+    setRecordingMode,
+    setIncognito,
     _hydrated,
   } = useSettingsStore();
 
@@ -75,6 +77,20 @@ export function TraySync() {
           setSelectedAiFunction(fn === "none" ? null : fn);
         }),
       );
+
+      cleanups.push(
+        await tauriListen<RecordingMode>("tray-recording-mode-changed", (mode) => {
+          if (cancelled) return;
+          setRecordingMode(mode);
+        }),
+      );
+
+      cleanups.push(
+        await tauriListen<boolean>("tray-incognito-changed", (enabled) => {
+          if (cancelled) return;
+          setIncognito(enabled);
+        }),
+      );
     };
 
     setup();
@@ -82,7 +98,13 @@ export function TraySync() {
       cancelled = true;
       cleanups.forEach((fn) => fn?.());
     };
-  }, [setSelectedModel, setSelectedLanguage, setSelectedAiFunction]);
+  }, [
+    setSelectedModel,
+    setSelectedLanguage,
+    setSelectedAiFunction,
+    setRecordingMode,
+    setIncognito,
+  ]);
 
   // Store → Tray: sync check marks when settings change
   useEffect(() => {
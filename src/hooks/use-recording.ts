@@ -1,6 +1,7 @@
 import { useCallback } from "react";
 import { toast } from "sonner";
 import { useRecordingStore } from "@/stores/recording-store";
+import { errorMessage, isCommandError, requestPermissions } from "@/lib/tauri-commands";
 
 /**
  * Lightweight hook for UI components that need recording controls.
@@ -50,7 +51,14 @@ export function useRecording() {
       await tauriInvoke("show_recording_bar").catch(() => {});
     } catch (err) {
       console.error("Failed to start recording:", err);
-      toast.error("Failed to start recording", { description: String(err) });
+      if (isCommandError(err) && err.code === "micPermissionDenied") {
+        toast.error("Microphone access denied", {
+          description: "Allow SobottaAI to use the microphone, then try again.",
+          action: { label: "Open Settings", onClick: () => void requestPermissions() },
+        });
+      } else {
+        toast.error("Failed to start recording", { description: errorMessage(err) });
+      }
       reset();
     }
   }, [isRecording, beginRecording, reset]);
@@ -63,7 +71,7 @@ export function useRecording() {
       // after transcription (+ AI processing if active) completes.
     } catch (err) {
       console.error("Failed to stop recording:", err);
-      toast.error("Failed to stop recording", { description: String(err) });
+      toast.error("Failed to stop recording", { description: errorMessage(err) });
       reset();
       await tauriInvoke("hide_recording_bar").catch(() => {});
     }
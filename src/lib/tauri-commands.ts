@@ -1,14 +1,57 @@
-import type {
-  CheckOptions,
-  DownloadEvent as UpdaterDownloadEvent,
-  Update,
-} from "@tauri-apps/plugin-updater";
-
 async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   const { invoke: tauriInvoke } = await import("@tauri-apps/api/core");
   return tauriInvoke<T>(cmd, args);
 }
 
+// ── Errors ─────────────────────────────────────────────────
+
+/** Error codes returned by recording, transcription, model and AI commands. */
+export type CommandErrorCode =
+  | "alreadyRecording"
+  | "notRecording"
+  | "micPermissionDenied"
+  | "audioDevice"
+  | "noAudio"
+  | "unknownModel"
+  | "modelNotDownloaded"
+  | "unsupportedLanguage"
+  | "insufficientDiskSpace"
+  | "downloadFailed"
+  | "apiKeyMissing"
+  | "apiKeyInvalid"
+  | "rateLimited"
+  | "timeout"
+  | "network"
+  | "cancelled"
+  | "noTextSelected"
+  | "aiFunctionNotFound"
+  | "noAiOutput"
+  | "nothingToUndo"
+  | "pasteTargetChanged"
+  | "aiFailed"
+  | "other";
+
+export interface CommandError {
+  code: CommandErrorCode;
+  message: string;
+}
+
+export function isCommandError(err: unknown): err is CommandError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    typeof (err as CommandError).code === "string" &&
+    typeof (err as CommandError).message === "string"
+  );
+}
+
+/** Readable message for a rejected command, structured or not. */
+export function errorMessage(err: unknown): string {
+  if (isCommandError(err)) return err.message;
+  if (err instanceof Error) return err.message;
+  return String(err);
+}
+
 // ── Recording ──────────────────────────────────────────────
 
 export interface StopResult {
@@ -33,6 +76,67 @@ export function hideRecordingBar(): Promise<void> {
   return invoke("hide_recording_bar");
 }
 
+export interface MonitorInfo {
+  name: string;
+  width: number;
+  height: number;
+  primary: boolean;
+}
+
+/** Connected displays the recording bar can be pinned to. */
+export function listMonitors(): Promise<MonitorInfo[]> {
+  return invoke("list_monitors");
+}
+
+/**
+ * Move the recording bar. `position` is e.g. "bottom-center" or "top-right";
+ * `monitor` is "primary", "cursor" or a monitor name.
+ */
+export function setRecordingBarPosition(position: string, monitor: string): Promise<void> {
+  return invoke("set_recording_bar_position", { position, monitor });
+}
+
+/** Free a session's in-memory audio once the pipeline is done with it. */
+export function releaseSession(sessionId: string): Promise<boolean> {
+  return invoke("release_session", { sessionId });
+}
+
+export interface RecoveredSession {
+  sessionId: string;
+  durationMs: number;
+}
+
+/** Load recordings that never reached history (app quit mid-pipeline) back into memory. */
+export function recoverSessions(): Promise<RecoveredSession[]> {
+  return invoke("recover_sessions");
+}
+
+/** Drop a session's audio and its WAV, unless a history entry references it. */
+export function discardSession(sessionId: string): Promise<void> {
+  return invoke("discard_session", { sessionId });
+}
+
+export type MicVerdict = "too-quiet" | "clipping" | "ok";
+
+export interface MicTestResult {
+  rms: number;
+  peak: number;
+  /** Fraction of samples at full scale. */
+  clippedRatio: number;
+  verdict: MicVerdict;
+  sampleRate: number;
+  channels: number;
+  durationMs: number;
+}
+
+/**
+ * Record a short clip (3 s by default) from the default input and report its
+ * levels. With `emitLevels`, live RMS readings arrive as `mic-test-level` events.
+ */
+export function testMicrophone(emitLevels = false, durationMs?: number): Promise<MicTestResult> {
+  return invoke("test_microphone", { durationMs, emitLevels });
+}
+
 // ── Transcription ──────────────────────────────────────────
 
 export interface TranscriptionResult {
@@ -40,28 +144,89 @@ export interface TranscriptionResult {
   language: string | null;
   segments: { startMs: number; endMs: number; text: string }[];
   durationMs: number;
+  metrics?: InferenceMetrics | null;
 }
 
+/** How fast a transcription ran; `realTimeFactor` below 1 is faster than real time. */
+export interface InferenceMetrics {
+  engine: string;
+  inferenceMs: number;
+  realTimeFactor: number;
+  /** CPU threads of a local engine; null for cloud APIs. */
+  threads: number | null;
+}
+
+/**
+ * Starts a transcription job; resolves with the job id immediately.
+ * `initialPrompt` overrides the saved prompt for this job only.
+ */
 export function transcribe(
   sessionId: string,
   modelId: string,
   language?: string,
-): Promise<TranscriptionResult> {
-  return invoke("transcribe", { sessionId, modelId, language });
+  initialPrompt?: string,
+): Promise<string> {
+  return invoke("transcribe", { sessionId, modelId, language, initialPrompt });
 }
 
 export function transcribeFile(
   path: string,
   modelId: string,
   language?: string,
-): Promise<TranscriptionResult> {
+): Promise<string> {
   return invoke("transcribe_file", { path, modelId, language });
 }
 
+// ── Jobs ───────────────────────────────────────────────────
+
+export type JobStatus = "queued" | "running" | "completed" | "failed" | "cancelled";
+
+/** Payload of the `job-updated` event and `get_job_status`. */
+export interface JobInfo {
+  id: string;
+  sessionId: string;
+  status: JobStatus;
+  progress: number;
+  result: TranscriptionResult | null;
+  error: CommandError | null;
+  createdAt: string;
+}
+
+export function getJobStatus(jobId: string): Promise<JobInfo | null> {
+  return invoke("get_job_status", { jobId });
+}
+
+export function cancelJob(jobId: string): Promise<boolean> {
+  return invoke("cancel_job", { jobId });
+}
+
 // ── Clipboard ──────────────────────────────────────────────
 
-export function pasteText(text: string): Promise<void> {
-  return invoke("paste_text", { text });
+/**
+ * Paste into the focused app. Rejects with the "secureField" code when a
+ * password field has focus, unless `secureFieldPaste` is "warn"; then it
+ * pastes and resolves to true.
+ */
+/** How pasted text goes on the clipboard; defaults to the saved paste format. */
+export type PasteFormat = "plain" | "markdown" | "rich";
+
+export function pasteText(text: string, format?: PasteFormat): Promise<boolean> {
+  return invoke("paste_text", { text, format });
+}
+
+export interface RecentOutput {
+  text: string;
+  pastedAt: string;
+}
+
+/** The last few pasted outputs, newest first (kept in memory until quit). */
+export function getRecentOutputs(): Promise<RecentOutput[]> {
+  return invoke("get_recent_outputs");
+}
+
+/** Delete the text of the last paste from the app it went to. */
+export function undoLastPaste(): Promise<void> {
+  return invoke("undo_last_paste");
 }
 
 // ── Audio Import ───────────────────────────────────────────
@@ -70,6 +235,59 @@ export function importAudioFile(path: string): Promise<string> {
   return invoke("import_audio_file", { path });
 }
 
+// ── Watched Folders ────────────────────────────────────────
+
+export interface WatchFolder {
+  id: string;
+  path: string;
+  /** Model to transcribe with; null uses the selected model. */
+  modelId: string | null;
+  writeTxt: boolean;
+  writeSrt: boolean;
+  enabled: boolean;
+  createdAt: string;
+}
+
+export function listWatchFolders(): Promise<WatchFolder[]> {
+  return invoke("list_watch_folders");
+}
+
+/** Add or update a folder; an empty id adds a new one. */
+export function saveWatchFolder(folder: WatchFolder): Promise<WatchFolder> {
+  return invoke("save_watch_folder", { folder });
+}
+
+export function deleteWatchFolder(id: string): Promise<void> {
+  return invoke("delete_watch_folder", { id });
+}
+
+// ── App Profiles ───────────────────────────────────────────
+
+/** How line breaks are pasted: as-is, typed as Shift+Enter, or joined with spaces. */
+export type NewlineMode = "keep" | "shiftEnter" | "space";
+
+export interface AppProfile {
+  id: string;
+  /** App name as the system reports it, matched ignoring case. */
+  appName: string;
+  newlineMode: NewlineMode;
+  stripTrailingNewline: boolean;
+  createdAt: string;
+}
+
+export function listAppProfiles(): Promise<AppProfile[]> {
+  return invoke("list_app_profiles");
+}
+
+/** Add or update a profile; an empty id adds a new one. */
+export function saveAppProfile(profile: AppProfile): Promise<AppProfile> {
+  return invoke("save_app_profile", { profile });
+}
+
+export function deleteAppProfile(id: string): Promise<void> {
+  return invoke("delete_app_profile", { id });
+}
+
 // ── Models ─────────────────────────────────────────────────
 
 export interface ModelInfo {
@@ -93,8 +311,87 @@ export function deleteModel(modelId: string): Promise<void> {
   return invoke("delete_model", { modelId });
 }
 
+/** Whether the Silero VAD model used to skip silence before Whisper is on disk. */
+export function isVadModelDownloaded(): Promise<boolean> {
+  return invoke("is_vad_model_downloaded");
+}
+
+/** Downloads the VAD model; progress arrives as `model-download-progress` for `silero-vad`. */
+export function downloadVadModel(): Promise<void> {
+  return invoke("download_vad_model");
+}
+
+/** Whether the punctuation model used after Parakeet is on disk. */
+export function isPunctuationModelDownloaded(): Promise<boolean> {
+  return invoke("is_punctuation_model_downloaded");
+}
+
+/** Downloads the punctuation model; progress arrives for `ct-punctuation`. */
+export function downloadPunctuationModel(): Promise<void> {
+  return invoke("download_punctuation_model");
+}
+
+/** Execution providers Parakeet can run on in this build, e.g. `["cpu", "coreml"]`. */
+export function parakeetProviders(): Promise<string[]> {
+  return invoke("parakeet_providers");
+}
+
+export interface BenchmarkResult {
+  modelId: string;
+  audioDurationMs: number;
+  loadMs: number;
+  inferenceMs: number;
+  /** Inference time / audio duration; below 1 is faster than real time. */
+  realTimeFactor: number;
+  memoryBytes: number | null;
+  memoryDeltaBytes: number | null;
+  transcript: string;
+  /** 0–1, only when a reference text was given. */
+  wordErrorRate: number | null;
+}
+
+/** Transcribe a reference WAV clip with a local model and report speed, memory and WER. */
+export function benchmarkModel(params: {
+  modelId: string;
+  audioPath: string;
+  referenceText?: string | null;
+  language?: string | null;
+}): Promise<BenchmarkResult> {
+  return invoke("benchmark_model", params);
+}
+
+export interface ModelDiskUsage {
+  modelId: string;
+  /** Catalog name; null for orphaned entries. */
+  name: string | null;
+  sizeBytes: number;
+  orphaned: boolean;
+}
+
+export interface DiskUsageReport {
+  entries: ModelDiskUsage[];
+  totalBytes: number;
+  freedBytes: number;
+}
+
+/** Size of each model directory; pass deleteOrphaned to remove entries not in the catalog. */
+export function modelDiskUsage(deleteOrphaned?: boolean): Promise<DiskUsageReport> {
+  return invoke("model_disk_usage", { deleteOrphaned });
+}
+
 // ── AI Functions ───────────────────────────────────────────
 
+/** What happens with an AI function's output after a dictation. */
+export type OutputAction =
+  | { type: "paste" }
+  | { type: "copy" }
+  /** Paste, then put back what was on the clipboard before. */
+  | { type: "pasteKeepClipboard" }
+  /** Show in a preview window to review first. */
+  | { type: "preview" }
+  /** POST `{ functionId, text }` as JSON to `url`. */
+  | { type: "webhook"; url: string };
+
 export interface AiFunction {
   id: string;
   name: string;
@@ -102,26 +399,317 @@ export interface AiFunction {
   provider: string;
   model?: string;
   isBuiltin: boolean;
+  outputAction: OutputAction;
+  /** Times the function has been run. */
+  useCount: number;
+  /** RFC 3339 time of the last run, or null if never run. */
+  lastUsedAt: string | null;
 }
 
 export function listAiFunctions(): Promise<AiFunction[]> {
   return invoke("list_ai_functions");
 }
 
-export function executeAiFunction(params: {
-  functionId: string;
-  text: string;
-  llmProvider: string;
-  llmApiKey: string;
-  llmModel: string;
-}): Promise<string> {
+/** Most used first; functions never run keep their original order. */
+export function sortAiFunctionsByUsage<T extends { useCount?: number }>(functions: T[]): T[] {
+  return [...functions].sort((a, b) => (b.useCount ?? 0) - (a.useCount ?? 0));
+}
+
+/**
+ * Write custom AI functions (or only `ids`) to a shareable JSON file at `path`.
+ * Returns how many were written.
+ */
+export function exportAiFunctions(path: string, ids?: string[]): Promise<number> {
+  return invoke("export_ai_functions", { path, ids });
+}
+
+/** Add the functions from a shared file as custom functions; returns those added. */
+export function importAiFunctions(path: string): Promise<AiFunction[]> {
+  return invoke("import_ai_functions", { path });
+}
+
+/** Ollama-only request tuning; null fields keep the server defaults. */
+export interface OllamaOptions {
+  numCtx: number | null;
+  temperature: number | null;
+  /** e.g. "30m", or seconds where "-1" keeps the model loaded. */
+  keepAlive: string | null;
+}
+
+/**
+ * Run an AI function over `text`. The backend picks the provider, model and
+ * key from the function and the saved provider settings, and reads the
+ * prompt prefix, context source and fallback order from settings.
+ */
+export function executeAiFunction(params: { functionId: string; text: string }): Promise<string> {
   return invoke("execute_ai_function", params);
 }
 
+/** Copy the focused app's selection, run an AI function on it and paste the result back. */
+export function rewriteSelection(functionId: string): Promise<string> {
+  return invoke("rewrite_selection", { functionId });
+}
+
+/**
+ * Revise the last AI function output with a follow-up instruction such as
+ * "make it shorter", continuing the same conversation.
+ */
+export function refineLastOutput(instruction: string): Promise<string> {
+  return invoke("refine_last_output", { instruction });
+}
+
+/**
+ * Abort the running AI function or refinement; it rejects with the
+ * "cancelled" code. Resolves to false if nothing was running. Output
+ * streams in as `ai-output-delta` events with `{ functionId, delta }`.
+ */
+export function cancelAiFunction(): Promise<boolean> {
+  return invoke("cancel_ai_function");
+}
+
+/** Set a function's output action; built-in functions can be changed too. */
+export function setAiFunctionOutput(functionId: string, action: OutputAction): Promise<void> {
+  return invoke("set_ai_function_output", { functionId, action });
+}
+
+/**
+ * Deliver text through the output action of `functionId`, or paste it when
+ * null. Resolves to whether it was pasted into a password field.
+ */
+export function deliverOutput(functionId: string | null, text: string): Promise<boolean> {
+  return invoke("deliver_output", { functionId, text });
+}
+
+/** Text last sent to the preview window by the "preview" output action. */
+export function getOutputPreview(): Promise<{ functionId: string | null; text: string } | null> {
+  return invoke("get_output_preview");
+}
+
+/**
+ * Close the preview window and paste the confirmed text into the app that
+ * was in front. Resolves to whether it was pasted into a password field.
+ */
+export function pasteOutputPreview(text: string): Promise<boolean> {
+  return invoke("paste_output_preview", { text });
+}
+
+/** Drop all cached AI function outputs; resolves to the number removed. */
+export function clearAiCache(): Promise<number> {
+  return invoke("clear_ai_cache");
+}
+
+// ── Compose ────────────────────────────────────────────────
+
+/** The document collected so far while composing across recordings. */
+export interface ComposeStatus {
+  id: string;
+  /** Number of dictations appended. */
+  parts: number;
+  text: string;
+  durationMs: number;
+}
+
+/** The finished document at each pipeline stage. */
+export interface ComposeResult {
+  transcript: string;
+  rulesText: string;
+  /** AI function output, or null when none was run. */
+  processedText: string | null;
+}
+
+/**
+ * Start appending dictations to one document instead of pasting each one.
+ * Changes are broadcast as `compose-changed` with the status, or null once
+ * finished or discarded.
+ */
+export function startCompose(): Promise<ComposeStatus> {
+  return invoke("start_compose");
+}
+
+/** The active compose session, or null when not composing. */
+export function getCompose(): Promise<ComposeStatus | null> {
+  return invoke("get_compose");
+}
+
+/**
+ * Run the AI function once over the whole document and save it as a single
+ * history item. On an AI error the session is kept so it can be retried.
+ */
+export function finishCompose(params: {
+  aiFunction: string | null;
+  keepRulesText: boolean;
+}): Promise<ComposeResult> {
+  return invoke("finish_compose", params);
+}
+
+/** Drop the compose session and its recordings. */
+export function discardCompose(): Promise<void> {
+  return invoke("discard_compose");
+}
+
+/** The stage of a finished document that the paste source setting picks. */
+export function composeOutput(
+  result: ComposeResult,
+  pasteSource: "processed" | "rules" | "verbatim",
+): string {
+  if (pasteSource === "verbatim") return result.transcript;
+  if (pasteSource === "rules") return result.rulesText;
+  return result.processedText ?? result.rulesText;
+}
+
+// ── Providers ─────────────────────────────────────────────
+
+/** Names of the models installed on the Ollama server (default localhost:11434). */
+export function listOllamaModels(baseUrl?: string | null): Promise<string[]> {
+  return invoke("list_ollama_models", { baseUrl: baseUrl ?? null });
+}
+
+export interface ProviderTestResult {
+  ok: boolean;
+  latencyMs: number;
+  message: string;
+}
+
+/** Send a minimal completion to validate an LLM provider's key, model and URL. */
+export function testLlmProvider(params: {
+  provider: string;
+  apiKey: string;
+  model: string;
+  baseUrl?: string | null;
+  timeoutSecs?: number | null;
+  ollamaOptions?: OllamaOptions | null;
+}): Promise<ProviderTestResult> {
+  return invoke("test_llm_provider", params);
+}
+
+/** Validate a cloud STT key ("openai" or "groq") without transcribing anything. */
+export function testSttProvider(params: {
+  provider: string;
+  apiKey: string;
+  model?: string | null;
+  baseUrl?: string | null;
+}): Promise<ProviderTestResult> {
+  return invoke("test_stt_provider", params);
+}
+
 // ── Rules ─────────────────────────────────────────────────
 
-export function applyRules(text: string, enabledRuleIds: string[]): Promise<string> {
-  return invoke("apply_rules", { text, enabledRuleIds });
+export interface RuleSetting {
+  id: string;
+  name: string;
+  enabled: boolean;
+  sortOrder: number;
+  isBuiltin: boolean;
+  /** Regex of a custom rule; null for built-in rules. */
+  pattern: string | null;
+  /** May refer to capture groups as `$1`, `${1}` or `$name`. */
+  replacement: string | null;
+  /** Any of "i" (ignore case), "m" (multi-line `^`/`$`), "s" (`.` matches newlines). */
+  flags: string | null;
+}
+
+/** Apply the saved rules, or only `enabledRuleIds` when given. */
+export function applyRules(text: string, enabledRuleIds?: string[]): Promise<string> {
+  return invoke("apply_rules", { text, enabledRuleIds: enabledRuleIds ?? null });
+}
+
+/** The text rules with their saved enabled state, in order. */
+export function listRules(): Promise<RuleSetting[]> {
+  return invoke("list_rules");
+}
+
+export function setRuleEnabled(id: string, enabled: boolean): Promise<void> {
+  return invoke("set_rule_enabled", { id, enabled });
+}
+
+/** Reorder the rules; `ruleIds` lists all of them in the new order. */
+export function reorderRules(ruleIds: string[]): Promise<void> {
+  return invoke("reorder_rules", { ruleIds });
+}
+
+/**
+ * Add an enabled custom regex rule. Rejects when the pattern doesn't compile
+ * or the replacement refers to a group the pattern lacks. Resolves to all
+ * rules, in order.
+ */
+export function addRule(
+  name: string,
+  pattern: string,
+  replacement: string,
+  flags = "",
+): Promise<RuleSetting[]> {
+  return invoke("add_rule", { name, pattern, replacement, flags });
+}
+
+/** Delete a custom rule; built-in rules can only be disabled. */
+export function deleteRule(id: string): Promise<void> {
+  return invoke("delete_rule", { id });
+}
+
+/** Write the custom rules to a shareable JSON file. Resolves to how many were written. */
+export function exportRules(path: string): Promise<number> {
+  return invoke("export_rules", { path });
+}
+
+/** Add the rules in a shared JSON file. Resolves to how many were added. */
+export function importRules(path: string): Promise<number> {
+  return invoke("import_rules", { path });
+}
+
+// ── Pipelines ──────────────────────────────────────────────
+
+export type PipelineStep =
+  /** Built-in rules, in the given order. */
+  | { type: "rules"; ruleIds: string[] }
+  /** Vocabulary terms swapped for their replacements. */
+  | { type: "vocabulary" }
+  | { type: "aiFunction"; functionId: string }
+  /** Email addresses, card and phone numbers masked. */
+  | { type: "redact" };
+
+export interface Pipeline {
+  id: string;
+  name: string;
+  steps: PipelineStep[];
+  createdAt: string;
+}
+
+export interface PipelineOutput {
+  text: string;
+  /** Text before the first AI function step. */
+  rulesText: string;
+  /** Final text when an AI function step ran. */
+  processedText: string | null;
+  /** The last AI function that ran. */
+  aiFunction: string | null;
+  /** A failed AI function step; the rest of the pipeline still ran. */
+  aiError: CommandError | null;
+}
+
+export function listPipelines(): Promise<Pipeline[]> {
+  return invoke("list_pipelines");
+}
+
+/** Create (empty `id`) or update a pipeline. */
+export function savePipeline(pipeline: Pipeline): Promise<Pipeline> {
+  return invoke("save_pipeline", { pipeline });
+}
+
+export function deletePipeline(id: string): Promise<void> {
+  return invoke("delete_pipeline", { id });
+}
+
+/**
+ * Run text through `pipelineId`, or through the saved rules and then
+ * `aiFunction` when null. `skipAi` leaves out AI function steps.
+ */
+export function runPipeline(request: {
+  text: string;
+  pipelineId: string | null;
+  aiFunction: string | null;
+  skipAi?: boolean;
+}): Promise<PipelineOutput> {
+  return invoke("run_pipeline", request);
 }
 
 // ── History ────────────────────────────────────────────────
@@ -131,19 +719,37 @@ export interface HistoryItem {
   audioPath?: string;
   transcript: string;
   processedText?: string;
+  rulesText?: string;
   modelId: string;
   language?: string;
   aiFunction?: string;
   durationMs?: number;
+  metrics?: InferenceMetrics | null;
   createdAt: string;
 }
 
-export function getHistory(limit = 100, offset = 0): Promise<HistoryItem[]> {
-  return invoke("get_history", { limit, offset });
+/** Structured history filters; all fields optional and combined with AND. */
+export interface HistoryFilter {
+  query?: string;
+  modelId?: string;
+  language?: string;
+  aiFunction?: string;
+  /** Inclusive lower bound, e.g. "2025-01-01" or an ISO timestamp. */
+  from?: string;
+  /** Inclusive upper bound; a bare date covers the whole day. */
+  to?: string;
+}
+
+export function getHistory(
+  limit = 100,
+  offset = 0,
+  filter?: HistoryFilter,
+): Promise<HistoryItem[]> {
+  return invoke("get_history", { limit, offset, filter });
 }
 
-export function searchHistory(query: string): Promise<HistoryItem[]> {
-  return invoke("search_history", { query });
+export function searchHistory(query: string, filter?: HistoryFilter): Promise<HistoryItem[]> {
+  return invoke("search_history", { query, filter });
 }
 
 export function getHistoryItem(id: string): Promise<HistoryItem> {
@@ -154,34 +760,323 @@ export function deleteHistoryItem(id: string): Promise<void> {
   return invoke("delete_history_item", { id });
 }
 
+/** Save a corrected verbatim transcript; the corrected words feed `suggestVocabulary`. */
+export function editHistoryTranscript(id: string, transcript: string): Promise<void> {
+  return invoke("edit_history_transcript", { id, transcript });
+}
+
 export function saveHistoryItem(params: {
   sessionId: string;
   transcript: string;
   processedText?: string | null;
+  rulesText?: string | null;
   modelId: string;
   language?: string | null;
   aiFunction?: string | null;
   durationMs?: number | null;
+  metrics?: InferenceMetrics | null;
 }): Promise<void> {
   return invoke("save_history_item", params);
 }
 
+export interface WaveformPeaks {
+  peaks: { min: number; max: number }[];
+  durationMs: number;
+}
+
+export function getWaveformPeaks(id: string, buckets = 200): Promise<WaveformPeaks> {
+  return invoke("get_waveform_peaks", { id, buckets });
+}
+
+// ── Notes ──────────────────────────────────────────────────
+
+/** A named document made of history recordings in order. */
+export interface Note {
+  id: string;
+  title: string;
+  /** Attached recordings, in document order. */
+  recordingIds: string[];
+  createdAt: string;
+  updatedAt: string;
+}
+
+/** All notes, most recently changed first. */
+export function listNotes(): Promise<Note[]> {
+  return invoke("list_notes");
+}
+
+export function createNote(title: string, recordingIds?: string[]): Promise<Note> {
+  return invoke("create_note", { title, recordingIds: recordingIds ?? null });
+}
+
+export function renameNote(id: string, title: string): Promise<void> {
+  return invoke("rename_note", { id, title });
+}
+
+/** Delete a note; its recordings stay in history. */
+export function deleteNote(id: string): Promise<void> {
+  return invoke("delete_note", { id });
+}
+
+/** Append a history recording to a note; one already attached keeps its place. */
+export function attachToNote(noteId: string, recordingId: string): Promise<void> {
+  return invoke("attach_to_note", { noteId, recordingId });
+}
+
+export function detachFromNote(noteId: string, recordingId: string): Promise<void> {
+  return invoke("detach_from_note", { noteId, recordingId });
+}
+
+/** Reorder snippets; `recordingIds` must list every recording in the note. */
+export function reorderNote(id: string, recordingIds: string[]): Promise<void> {
+  return invoke("reorder_note", { id, recordingIds });
+}
+
+/** Snippets merged in order, each recording's final text separated by a blank line. */
+export function getNoteText(id: string): Promise<string> {
+  return invoke("get_note_text", { id });
+}
+
+/** Write the merged text to `path`; `.md` files get the title as a heading. */
+export function exportNote(id: string, path: string): Promise<void> {
+  return invoke("export_note", { id, path });
+}
+
 // ── Vocabulary ─────────────────────────────────────────────
 
-export function getVocabulary(): Promise<{ id: string; term: string }[]> {
+export interface VocabularyTerm {
+  id: string;
+  term: string;
+  replacement: string | null;
+  category: string | null;
+  /** Language code the term applies to; null applies to every language. */
+  language: string | null;
+  createdAt: string;
+}
+
+export function getVocabulary(): Promise<VocabularyTerm[]> {
   return invoke("get_vocabulary");
 }
 
-export function addTerm(term: string): Promise<void> {
-  return invoke("add_term", { term });
+export function addTerm(
+  term: string,
+  scope: { category?: string | null; language?: string | null } = {},
+): Promise<void> {
+  return invoke("add_term", { term, ...scope });
 }
 
 export function deleteTerm(id: string): Promise<void> {
   return invoke("delete_term", { id });
 }
 
+/** A correction the user keeps making in edited transcripts. */
+export interface VocabularySuggestion {
+  /** What the model transcribed. */
+  original: string;
+  /** What the user changed it to. */
+  term: string;
+  count: number;
+}
+
+/** Recurring transcript corrections (made at least `minCount` times) not yet in the vocabulary. */
+export function suggestVocabulary(minCount?: number): Promise<VocabularySuggestion[]> {
+  return invoke("suggest_vocabulary", { minCount });
+}
+
+// ── Hotkey Bindings ────────────────────────────────────────
+
+/** A hotkey that dictates with its own preset; null fields use the current settings. */
+export interface HotkeyBinding {
+  id: string;
+  hotkey: string;
+  modelId: string | null;
+  language: string | null;
+  aiFunction: string | null;
+  pipelineId: string | null;
+}
+
+export function listHotkeyBindings(): Promise<HotkeyBinding[]> {
+  return invoke("list_hotkey_bindings");
+}
+
+/** Create (empty `id`) or update a binding and register its hotkey. */
+export function saveHotkeyBinding(binding: HotkeyBinding): Promise<HotkeyBinding> {
+  return invoke("save_hotkey_binding", { binding });
+}
+
+export function deleteHotkeyBinding(id: string): Promise<void> {
+  return invoke("delete_hotkey_binding", { id });
+}
+
+// ── Device Bindings ────────────────────────────────────────
+
+/** A button on a HID device (e.g. a foot pedal) that runs a hotkey action. */
+export interface DeviceBinding {
+  id: string;
+  name: string;
+  vendorId: number;
+  productId: number;
+  /** Bit of the device's input report set while the button is held. */
+  button: number;
+  /** "dictation", "paste-last", "undo-paste" or "binding:<hotkey binding id>". */
+  action: string;
+  enabled: boolean;
+  createdAt: string;
+}
+
+export interface HidDevice {
+  vendorId: number;
+  productId: number;
+  name: string;
+}
+
+export function listDeviceBindings(): Promise<DeviceBinding[]> {
+  return invoke("list_device_bindings");
+}
+
+/** Create (empty `id`) or update a binding and restart listening. */
+export function saveDeviceBinding(binding: DeviceBinding): Promise<DeviceBinding> {
+  return invoke("save_device_binding", { binding });
+}
+
+export function deleteDeviceBinding(id: string): Promise<void> {
+  return invoke("delete_device_binding", { id });
+}
+
+export function listHidDevices(): Promise<HidDevice[]> {
+  return invoke("list_hid_devices");
+}
+
+/** Wait up to 10 seconds for a button press; resolves to its bit, or null. */
+export function captureHidButton(vendorId: number, productId: number): Promise<number | null> {
+  return invoke("capture_hid_button", { vendorId, productId });
+}
+
+// ── Settings ───────────────────────────────────────────────
+
+/** Settings mirrored into the backend database, keyed like the settings store. */
+export function getSettings(): Promise<Record<string, unknown>> {
+  return invoke("get_settings");
+}
+
+export function saveSettings(settings: Record<string, unknown>): Promise<void> {
+  return invoke("save_settings", { settings });
+}
+
+/** Incognito mode: no WAV files are written and history is not saved. */
+export function setIncognito(enabled: boolean): Promise<void> {
+  return invoke("set_incognito", { enabled });
+}
+
+/** "native", "portal" (XDG desktop portal, for Wayland) or "auto". */
+export function setHotkeyBackend(backend: string): Promise<void> {
+  return invoke("set_hotkey_backend", { backend });
+}
+
+/** Mouse button that triggers dictation: "none", "middle", "back" (M4) or "forward" (M5). */
+export function setMouseButton(button: string): Promise<void> {
+  return invoke("set_mouse_button", { button });
+}
+
+/** Language of the tray menu and notifications, e.g. "de", or "auto". */
+export function setUiLanguage(language: string): Promise<void> {
+  return invoke("set_ui_language", { language });
+}
+
+export function getIncognito(): Promise<boolean> {
+  return invoke("get_incognito");
+}
+
+/** Launch the app at login. */
+export function setAutostart(enabled: boolean): Promise<void> {
+  return invoke("set_autostart", { enabled });
+}
+
+export function getAutostart(): Promise<boolean> {
+  return invoke("get_autostart");
+}
+
+/** Folder holding recordings and models. */
+export function getStorageDir(): Promise<string> {
+  return invoke("get_storage_dir");
+}
+
+export interface StorageMigrationReport {
+  filesMoved: number;
+  bytesMoved: number;
+  /** Files left in place because the destination already had them. */
+  filesSkipped: number;
+  historyUpdated: number;
+}
+
+/**
+ * Move recordings and models to `path` (`null` restores the default). Existing
+ * files are moved along unless `moveFiles` is false.
+ */
+export function setStorageDir(
+  path: string | null,
+  moveFiles = true,
+): Promise<StorageMigrationReport> {
+  return invoke("set_storage_dir", { path, moveFiles });
+}
+
+/** Whether the history database is encrypted at rest (SQLCipher). */
+export function getDatabaseEncryption(): Promise<boolean> {
+  return invoke("get_database_encryption");
+}
+
+/** Encrypt or decrypt the history database; the key lives in the OS keychain. */
+export function setDatabaseEncryption(enabled: boolean): Promise<void> {
+  return invoke("set_database_encryption", { enabled });
+}
+
+export interface MaintenanceReport {
+  integrityOk: boolean;
+  integrityErrors: string[];
+  sizeBefore: number;
+  sizeAfter: number;
+  reclaimedBytes: number;
+}
+
+/** Run an integrity check and reclaim space left by deleted history. */
+export function maintainDatabase(): Promise<MaintenanceReport> {
+  return invoke("maintain_database");
+}
+
+// ── Permissions ────────────────────────────────────────────
+
+export type PermissionState = "granted" | "denied" | "not-determined" | "restricted";
+
+export interface Permissions {
+  microphone: PermissionState;
+  /** Needed to simulate paste/copy keystrokes. */
+  accessibility: PermissionState;
+}
+
+/** macOS microphone and accessibility permissions; always granted elsewhere. */
+export function checkPermissions(): Promise<Permissions> {
+  return invoke("check_permissions");
+}
+
+/** Prompt for missing permissions, or open System Settings for denied ones. */
+export function requestPermissions(): Promise<Permissions> {
+  return invoke("request_permissions");
+}
+
 // ── App / Updater ───────────────────────────────────────────
 
+/**
+ * Zip recent logs, system info, models and redacted settings for a bug report.
+ * Saved to `dest` or the Downloads folder; returns the bundle path.
+ */
+export function collectDiagnostics(params: {
+  dest?: string | null;
+  /** Include the recording of the last failed transcription. */
+  includeFailedAudio?: boolean;
+}): Promise<string> {
+  return invoke("collect_diagnostics", params);
+}
+
 export function restartApp(): Promise<void> {
   return invoke("restart_app");
 }
@@ -191,8 +1086,38 @@ export async function getAppVersion(): Promise<string> {
   return getVersion();
 }
 
-export function checkForUpdate(options?: CheckOptions): Promise<Update | null> {
-  return import("@tauri-apps/plugin-updater").then(({ check }) => check(options));
+export type UpdateChannel = "stable" | "beta";
+
+export interface UpdateInfo {
+  version: string;
+  currentVersion: string;
+  /** RFC 3339 release date. */
+  date: string | null;
+  body: string | null;
+  channel: UpdateChannel;
+}
+
+/** Payload of the `update-download-progress` event. */
+export interface UpdateProgress {
+  downloadedBytes: number;
+  contentLength: number | null;
+}
+
+export function getUpdateChannel(): Promise<UpdateChannel> {
+  return invoke("get_update_channel");
 }
 
-export type { Update, UpdaterDownloadEvent };
+/** Opt into pre-release builds ("beta") or back to "stable". */
+export function setUpdateChannel(channel: UpdateChannel): Promise<void> {
+  return invoke("set_update_channel", { channel });
+}
+
+/** Check the selected channel; null when up to date. */
+export function checkForUpdates(): Promise<UpdateInfo | null> {
+  return invoke("check_for_updates");
+}
+
+/** Install the update found by the last check; progress arrives as `update-download-progress`. */
+export function installUpdate(): Promise<void> {
+  return invoke("install_update");
+}
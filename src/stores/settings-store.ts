@@ -1,41 +1,256 @@
 import { create } from "zustand";
+import type { OllamaOptions } from "@/lib/tauri-commands";
 
 interface Rule {
   id: string;
   name: string;
   enabled: boolean;
+  /** Regex and replacement of a custom rule; absent for built-in rules. */
+  pattern?: string | null;
+  replacement?: string | null;
+  flags?: string | null;
 }
 
-interface ProviderConfig {
+export interface ProviderConfig {
   apiKey: string;
   model: string;
   baseUrl?: string;
+  /** Request timeout in seconds; unset uses the provider default. */
+  timeoutSecs?: number;
+  /** Ollama only: context window, temperature and keep_alive. */
+  numCtx?: number;
+  temperature?: number;
+  keepAlive?: string;
 }
 
+/** Ollama request options for a provider config, or null for other providers. */
+export function ollamaOptionsFor(
+  provider: string,
+  config: ProviderConfig | undefined,
+): OllamaOptions | null {
+  if (provider !== "ollama" || !config) return null;
+  return {
+    numCtx: config.numCtx ?? null,
+    temperature: config.temperature ?? null,
+    keepAlive: config.keepAlive?.trim() || null,
+  };
+}
+
+/** True if the provider can be called: cloud providers need an API key. */
+export function isProviderUsable(provider: string, config: ProviderConfig | undefined): boolean {
+  return provider === "ollama" ? !!config : !!config?.apiKey;
+}
+
+/** Which stage of the pipeline output gets pasted after a recording. */
+export type PasteSource = "processed" | "rules" | "verbatim";
+
+/** How pasted text goes on the clipboard: Markdown stripped, as-is, or rendered. */
+export type PasteFormat = "plain" | "markdown" | "rich";
+
+/** What happens to a finished dictation: paste it, only copy it, or confirm first. */
+export type PasteMode = "auto" | "copy" | "confirm";
+
+/** Auto-paste into a password field: refuse, or paste and warn. */
+export type SecureFieldPaste = "block" | "warn";
+
+/** Extra context handed to AI functions alongside the dictation. */
+export type AiContextSource = "none" | "clipboard" | "selection";
+
+/** How the dictation hotkey controls recording. */
+export type RecordingMode = "push-to-talk" | "toggle" | "hands-free";
+
+/** How global hotkeys are delivered; "portal" is the XDG desktop portal for Wayland. */
+export type HotkeyBackend = "auto" | "native" | "portal";
+
+/** Mouse button that triggers dictation like the hotkey; "back" is M4, "forward" M5. */
+export type MouseButton = "none" | "middle" | "back" | "forward";
+
+export type ParakeetProvider = "auto" | "cpu" | "coreml" | "cuda" | "directml";
+
+/** Whisper decoding and anti-hallucination parameters. */
+export interface DecodingOptions {
+  /** 1 decodes greedily; larger values use beam search. */
+  beamSize: number;
+  temperature: number;
+  /** Temperature step for fallback retries; 0 disables fallback. */
+  temperatureInc: number;
+  noSpeechThold: number;
+  entropyThold: number;
+}
+
+export const DEFAULT_DECODING_OPTIONS: DecodingOptions = {
+  beamSize: 5,
+  temperature: 0,
+  temperatureInc: 0.2,
+  noSpeechThold: 0.6,
+  entropyThold: 2.4,
+};
+
+/** Where the recording bar sits on its monitor. */
+export type RecordingBarPosition =
+  | "bottom-center"
+  | "top-center"
+  | "bottom-left"
+  | "bottom-right"
+  | "top-left"
+  | "top-right";
+
 interface SettingsState {
   selectedModel: string;
   selectedLanguage: string;
   selectedAiFunction: string | null;
-  recordingMode: "push-to-talk" | "toggle";
+  /** Post-processing pipeline; null runs the enabled rules, then the AI function. */
+  pipelineId: string | null;
+  recordingMode: RecordingMode;
   rules: Rule[];
   defaultHotkey: string;
   theme: "light" | "dark" | "system";
   launchAtLogin: boolean;
   llmProvider: string;
   providerConfigs: Record<string, ProviderConfig>;
+  /** Providers tried in order when the default LLM provider fails. */
+  llmFallbackProviders: string[];
+  keepRulesOnlyText: boolean;
+  pasteSource: PasteSource;
+  pasteFormat: PasteFormat;
+  pasteMode: PasteMode;
+  /** Wait before the paste keystroke, in milliseconds. */
+  pasteDelayMs: number;
+  /** Bring back the app that was in front when recording started before pasting. */
+  lockPasteTarget: boolean;
+  /** What auto-paste does when a password field has focus. */
+  secureFieldPaste: SecureFieldPaste;
+  incognito: boolean;
+  /** Also save the raw capture (original rate/channels) next to the 16 kHz copy. */
+  keepOriginalAudio: boolean;
+  /** Accept sobottaai:// automation links; each action still asks first. */
+  automationUrlsEnabled: boolean;
+  /** Notify with a transcript preview when a transcription finishes while the window is hidden. */
+  notifyOnTranscription: boolean;
+  /** Record from the built-in mic when the default input is a Bluetooth headset. */
+  preferBuiltInMic: boolean;
+  recordingBarPosition: RecordingBarPosition;
+  /** "primary", "cursor" (the display under the mouse) or a monitor name. */
+  recordingBarMonitor: string;
+  /** Frequency-band visualizer in the recording bar instead of the level bars. */
+  spectrumVisualizer: boolean;
+  systemPromptPrefix: string;
+  aiContextSource: AiContextSource;
+  /** Hours an AI function output is reused for the same input; 0 disables the cache. */
+  aiCacheTtlHours: number;
+  rewriteHotkey: string | null;
+  rewriteAiFunction: string;
+  /** Hotkey that pastes the last transcript again. */
+  pasteLastHotkey: string | null;
+  /** Hotkey that deletes the last paste from the app it went to. */
+  undoPasteHotkey: string | null;
+  hotkeyBackend: HotkeyBackend;
+  mouseButton: MouseButton;
+  /** Double-pressing the dictation hotkey also runs the last used AI function. */
+  doublePressAi: boolean;
+  /** Recordings shorter than this are discarded as accidental taps; 0 keeps all. */
+  minRecordingMs: number;
+  /** Seconds of trailing silence that end a hands-free recording. */
+  handsFreeSilenceSecs: number;
+  /** Show a rolling transcript in the recording bar (local models only). */
+  liveTranscript: boolean;
+  decodingOptions: DecodingOptions;
+  /** CPU threads for local models; 0 uses all cores up to 8. */
+  inferenceThreads: number;
+  /** Half the threads and greedy decoding, so dictation doesn't spin up the fans. */
+  lowPowerMode: boolean;
+  /** Seconds a local transcription may run before it's stopped; 0 for no limit. */
+  maxInferenceSecs: number;
+  /** Execution provider Parakeet runs on; "auto" picks the fastest this build has. */
+  parakeetProvider: ParakeetProvider;
+  /** Free-form context that biases Whisper and cloud STT, e.g. the topic. */
+  initialPrompt: string;
+  /** Approximate token cap for the initial prompt plus vocabulary; least relevant terms are dropped. */
+  promptTokenBudget: number;
+  /** Detect the language per pause-separated chunk, for mixed-language speech. */
+  codeSwitching: boolean;
+  /** Languages code-switching picks from; empty allows any. */
+  codeSwitchLanguages: string[];
+  /** Skip silence with Silero VAD before local Whisper, once its model is downloaded. */
+  vadEnabled: boolean;
+  /** Punctuate bare Parakeet output, once the punctuation model is downloaded. */
+  punctuationEnabled: boolean;
+  /** Per-request timeout for cloud transcription. */
+  cloudSttTimeoutSecs: number;
+  /** Retries of a cloud transcription request after a rate limit or server error. */
+  cloudSttMaxRetries: number;
+  /** OpenAI-compatible server for OpenAI transcriptions; empty uses OpenAI. */
+  openaiSttBaseUrl: string;
+  /** Model name on that server; empty uses the selected model's. */
+  openaiSttModel: string;
+  /** API key for that server; the OpenAI key is never sent there. */
+  openaiSttApiKey: string;
+  /** Language of the tray menu and notifications; "auto" follows the system. */
+  uiLanguage: string;
   onboardingComplete: boolean;
   _hydrated: boolean;
   setOnboardingComplete: (value: boolean) => void;
   setSelectedModel: (model: string) => void;
   setSelectedLanguage: (lang: string) => void;
   setSelectedAiFunction: (fn: string | null) => void;
-  setRecordingMode: (mode: "push-to-talk" | "toggle") => void;
+  setPipelineId: (id: string | null) => void;
+  setRecordingMode: (mode: RecordingMode) => void;
   toggleRule: (ruleId: string) => void;
+  /** Move a rule up (-1) or down (1) in the order rules run. */
+  moveRule: (ruleId: string, offset: number) => void;
+  /** Reload rules from the backend after adding, importing or deleting some. */
+  loadRules: () => Promise<void>;
   setTheme: (theme: "light" | "dark" | "system") => void;
   setLaunchAtLogin: (value: boolean) => void;
   setDefaultHotkey: (hotkey: string) => void;
   setLlmProvider: (provider: string) => void;
   setProviderConfig: (provider: string, config: Partial<ProviderConfig>) => void;
+  setLlmFallbackProviders: (providers: string[]) => void;
+  setKeepRulesOnlyText: (value: boolean) => void;
+  setPasteSource: (source: PasteSource) => void;
+  setPasteFormat: (format: PasteFormat) => void;
+  setPasteMode: (mode: PasteMode) => void;
+  setPasteDelayMs: (ms: number) => void;
+  setLockPasteTarget: (value: boolean) => void;
+  setSecureFieldPaste: (value: SecureFieldPaste) => void;
+  setIncognito: (value: boolean) => void;
+  setKeepOriginalAudio: (value: boolean) => void;
+  setAutomationUrlsEnabled: (value: boolean) => void;
+  setNotifyOnTranscription: (value: boolean) => void;
+  setPreferBuiltInMic: (value: boolean) => void;
+  setRecordingBarPosition: (position: RecordingBarPosition) => void;
+  setRecordingBarMonitor: (monitor: string) => void;
+  setSpectrumVisualizer: (value: boolean) => void;
+  setSystemPromptPrefix: (prefix: string) => void;
+  setAiContextSource: (source: AiContextSource) => void;
+  setAiCacheTtlHours: (hours: number) => void;
+  setRewriteHotkey: (hotkey: string | null) => void;
+  setRewriteAiFunction: (fn: string) => void;
+  setPasteLastHotkey: (hotkey: string | null) => void;
+  setUndoPasteHotkey: (hotkey: string | null) => void;
+  setHotkeyBackend: (backend: HotkeyBackend) => void;
+  setMouseButton: (button: MouseButton) => void;
+  setDoublePressAi: (value: boolean) => void;
+  setMinRecordingMs: (ms: number) => void;
+  setHandsFreeSilenceSecs: (secs: number) => void;
+  setLiveTranscript: (value: boolean) => void;
+  setDecodingOptions: (options: Partial<DecodingOptions>) => void;
+  setInferenceThreads: (threads: number) => void;
+  setLowPowerMode: (value: boolean) => void;
+  setMaxInferenceSecs: (secs: number) => void;
+  setParakeetProvider: (provider: ParakeetProvider) => void;
+  setInitialPrompt: (prompt: string) => void;
+  setPromptTokenBudget: (tokens: number) => void;
+  setCodeSwitching: (value: boolean) => void;
+  setCodeSwitchLanguages: (languages: string[]) => void;
+  setVadEnabled: (value: boolean) => void;
+  setPunctuationEnabled: (value: boolean) => void;
+  setCloudSttTimeoutSecs: (secs: number) => void;
+  setCloudSttMaxRetries: (retries: number) => void;
+  setOpenaiSttBaseUrl: (url: string) => void;
+  setOpenaiSttModel: (model: string) => void;
+  setOpenaiSttApiKey: (key: string) => void;
+  setUiLanguage: (language: string) => void;
   // Convenience getters for the active provider
   get llmApiKey(): string;
   get llmModel(): string;
@@ -52,31 +267,37 @@ const DEFAULT_PROVIDER_CONFIGS: Record<string, ProviderConfig> = {
 const STORE_KEY = "settings";
 
 async function persistSettings(state: Partial<SettingsState>) {
+  const data: Record<string, unknown> = {};
+  for (const [key, value] of Object.entries(state)) {
+    if (typeof value !== "function" && key !== "_hydrated") {
+      data[key] = value;
+    }
+  }
   try {
     const { load } = await import("@tauri-apps/plugin-store");
     const store = await load("settings.json");
-    const data: Record<string, unknown> = {};
-    for (const [key, value] of Object.entries(state)) {
-      if (typeof value !== "function" && key !== "_hydrated") {
-        data[key] = value;
-      }
-    }
     await store.set(STORE_KEY, data);
     await store.save();
   } catch {
     // Outside Tauri context
   }
+  await saveSettingsBackend(data);
+}
+
+/** Mirror settings into the backend database so hotkeys, tray and transcription can read them. */
+async function saveSettingsBackend(settings: Record<string, unknown>) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("save_settings", { settings });
+  } catch {
+    // Outside Tauri context
+  }
 }
 
 async function setAutostart(enabled: boolean) {
   try {
-    if (enabled) {
-      const { enable } = await import("@tauri-apps/plugin-autostart");
-      await enable();
-    } else {
-      const { disable } = await import("@tauri-apps/plugin-autostart");
-      await disable();
-    }
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_autostart", { enabled });
   } catch {
     // Outside Tauri context
   }
@@ -97,6 +318,149 @@ async function updateHotkeyBackend(hotkey: string): Promise<boolean> {
   }
 }
 
+async function updateRewriteHotkeyBackend(hotkey: string | null) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("update_rewrite_hotkey", { hotkey });
+  } catch (err) {
+    console.error("Failed to update rewrite hotkey:", err);
+    import("sonner").then(({ toast }) => {
+      toast.error("Failed to set rewrite hotkey", { description: String(err) });
+    }).catch(() => {});
+  }
+}
+
+async function updatePasteLastHotkeyBackend(hotkey: string | null) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("update_paste_last_hotkey", { hotkey });
+  } catch (err) {
+    console.error("Failed to update paste-last hotkey:", err);
+    import("sonner").then(({ toast }) => {
+      toast.error("Failed to set paste-last hotkey", { description: String(err) });
+    }).catch(() => {});
+  }
+}
+
+async function updateUndoPasteHotkeyBackend(hotkey: string | null) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("update_undo_paste_hotkey", { hotkey });
+  } catch (err) {
+    console.error("Failed to update undo-paste hotkey:", err);
+    import("sonner").then(({ toast }) => {
+      toast.error("Failed to set undo-paste hotkey", { description: String(err) });
+    }).catch(() => {});
+  }
+}
+
+// The backend reads the saved language itself on startup, so this only runs on change
+async function updateUiLanguageBackend(language: string) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_ui_language", { language });
+  } catch (err) {
+    console.error("Failed to switch UI language:", err);
+  }
+}
+
+// The backend reads the saved choice itself on startup, so this only runs on change
+async function updateHotkeyBackendKind(backend: HotkeyBackend) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_hotkey_backend", { backend });
+  } catch (err) {
+    console.error("Failed to switch hotkey backend:", err);
+    import("sonner").then(({ toast }) => {
+      toast.error("Failed to switch hotkey backend", { description: String(err) });
+    }).catch(() => {});
+  }
+}
+
+// The backend reads the saved button itself on startup, so this only runs on change
+async function updateMouseButtonBackend(button: MouseButton) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_mouse_button", { button });
+  } catch (err) {
+    console.error("Failed to set mouse button:", err);
+  }
+}
+
+async function updateInferenceThreadsBackend(threads: number, lowPower: boolean) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_inference_threads", { threads, lowPower });
+  } catch (err) {
+    console.error("Failed to set inference threads:", err);
+  }
+}
+
+// Like the mouse button, the backend applies the saved provider on startup
+async function updateParakeetProviderBackend(provider: ParakeetProvider) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_parakeet_provider", { provider });
+  } catch (err) {
+    console.error("Failed to set Parakeet provider:", err);
+  }
+}
+
+async function updateRecordingBarBackend(position: RecordingBarPosition, monitor: string) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_recording_bar_position", { position, monitor });
+  } catch (err) {
+    console.error("Failed to move recording bar:", err);
+  }
+}
+
+// The backend keeps rule state and order so every run uses them, and seeds
+// it from these mirrored settings the first time
+async function setRuleEnabledBackend(id: string, enabled: boolean) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_rule_enabled", { id, enabled });
+  } catch (err) {
+    console.error("Failed to save rule:", err);
+  }
+}
+
+async function reorderRulesBackend(ruleIds: string[]) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("reorder_rules", { ruleIds });
+  } catch (err) {
+    console.error("Failed to reorder rules:", err);
+  }
+}
+
+async function loadRulesBackend(): Promise<Rule[] | null> {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    const rules = await invoke<Rule[]>("list_rules");
+    return rules.map(({ id, name, enabled, pattern, replacement, flags }) => ({
+      id,
+      name,
+      enabled,
+      pattern,
+      replacement,
+      flags,
+    }));
+  } catch {
+    return null;
+  }
+}
+
+async function updateIncognitoBackend(enabled: boolean) {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_incognito", { enabled });
+  } catch {
+    // Outside Tauri context
+  }
+}
+
 async function updateRecordingModeBackend(mode: string) {
   try {
     const { invoke } = await import("@tauri-apps/api/core");
@@ -110,6 +474,7 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
   selectedModel: "whisper-base",
   selectedLanguage: "auto",
   selectedAiFunction: null,
+  pipelineId: null,
   recordingMode: "push-to-talk",
   rules: [
     { id: "remove-fillers", name: "Remove Filler Words", enabled: false },
@@ -120,6 +485,52 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
   launchAtLogin: false,
   llmProvider: "openai",
   providerConfigs: { ...DEFAULT_PROVIDER_CONFIGS },
+  llmFallbackProviders: [],
+  keepRulesOnlyText: false,
+  pasteSource: "processed",
+  pasteFormat: "markdown",
+  pasteMode: "auto",
+  pasteDelayMs: 0,
+  lockPasteTarget: false,
+  secureFieldPaste: "block",
+  incognito: false,
+  keepOriginalAudio: false,
+  automationUrlsEnabled: false,
+  notifyOnTranscription: true,
+  preferBuiltInMic: false,
+  recordingBarPosition: "bottom-center",
+  recordingBarMonitor: "primary",
+  spectrumVisualizer: false,
+  systemPromptPrefix: "",
+  aiContextSource: "none",
+  aiCacheTtlHours: 24,
+  rewriteHotkey: null,
+  rewriteAiFunction: "grammar",
+  pasteLastHotkey: null,
+  undoPasteHotkey: null,
+  hotkeyBackend: "auto",
+  mouseButton: "none",
+  doublePressAi: false,
+  minRecordingMs: 300,
+  handsFreeSilenceSecs: 2,
+  liveTranscript: false,
+  decodingOptions: { ...DEFAULT_DECODING_OPTIONS },
+  inferenceThreads: 0,
+  lowPowerMode: false,
+  maxInferenceSecs: 300,
+  parakeetProvider: "auto",
+  initialPrompt: "",
+  promptTokenBudget: 200,
+  codeSwitching: false,
+  codeSwitchLanguages: [],
+  vadEnabled: false,
+  punctuationEnabled: false,
+  cloudSttTimeoutSecs: 120,
+  cloudSttMaxRetries: 2,
+  openaiSttBaseUrl: "",
+  openaiSttModel: "",
+  openaiSttApiKey: "",
+  uiLanguage: "auto",
   onboardingComplete: false,
   _hydrated: false,
 
@@ -149,6 +560,10 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     set({ selectedAiFunction: fn });
     persistSettings(get());
   },
+  setPipelineId: (id) => {
+    set({ pipelineId: id });
+    persistSettings(get());
+  },
   setRecordingMode: (mode) => {
     set({ recordingMode: mode });
     updateRecordingModeBackend(mode);
@@ -161,6 +576,25 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
       ),
     }));
     persistSettings(get());
+    const rule = get().rules.find((r) => r.id === ruleId);
+    if (rule) setRuleEnabledBackend(rule.id, rule.enabled);
+  },
+  moveRule: (ruleId, offset) => {
+    const rules = [...get().rules];
+    const from = rules.findIndex((r) => r.id === ruleId);
+    const to = from + offset;
+    if (from < 0 || to < 0 || to >= rules.length) return;
+    const [rule] = rules.splice(from, 1);
+    rules.splice(to, 0, rule);
+    set({ rules });
+    persistSettings(get());
+    reorderRulesBackend(rules.map((r) => r.id));
+  },
+  loadRules: async () => {
+    const rules = await loadRulesBackend();
+    if (!rules) return;
+    set({ rules });
+    persistSettings(get());
   },
   setTheme: (theme) => {
     set({ theme });
@@ -192,6 +626,202 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     }));
     persistSettings(get());
   },
+  setLlmFallbackProviders: (providers) => {
+    set({ llmFallbackProviders: providers });
+    persistSettings(get());
+  },
+  setKeepRulesOnlyText: (value) => {
+    set({ keepRulesOnlyText: value });
+    persistSettings(get());
+  },
+  setPasteSource: (source) => {
+    set({ pasteSource: source });
+    persistSettings(get());
+  },
+  setPasteFormat: (format) => {
+    set({ pasteFormat: format });
+    persistSettings(get());
+  },
+  setPasteMode: (mode) => {
+    set({ pasteMode: mode });
+    persistSettings(get());
+  },
+  setPasteDelayMs: (ms) => {
+    set({ pasteDelayMs: ms });
+    persistSettings(get());
+  },
+  setLockPasteTarget: (value) => {
+    set({ lockPasteTarget: value });
+    persistSettings(get());
+  },
+  setSecureFieldPaste: (value) => {
+    set({ secureFieldPaste: value });
+    persistSettings(get());
+  },
+  setIncognito: (value) => {
+    set({ incognito: value });
+    updateIncognitoBackend(value);
+    persistSettings(get());
+  },
+  setKeepOriginalAudio: (value) => {
+    set({ keepOriginalAudio: value });
+    persistSettings(get());
+  },
+  setAutomationUrlsEnabled: (value) => {
+    set({ automationUrlsEnabled: value });
+    persistSettings(get());
+  },
+  setNotifyOnTranscription: (value) => {
+    set({ notifyOnTranscription: value });
+    persistSettings(get());
+  },
+  setPreferBuiltInMic: (value) => {
+    set({ preferBuiltInMic: value });
+    persistSettings(get());
+  },
+  setRecordingBarPosition: (position) => {
+    set({ recordingBarPosition: position });
+    updateRecordingBarBackend(position, get().recordingBarMonitor);
+    persistSettings(get());
+  },
+  setRecordingBarMonitor: (monitor) => {
+    set({ recordingBarMonitor: monitor });
+    updateRecordingBarBackend(get().recordingBarPosition, monitor);
+    persistSettings(get());
+  },
+  setSpectrumVisualizer: (value) => {
+    set({ spectrumVisualizer: value });
+    persistSettings(get());
+  },
+  setSystemPromptPrefix: (prefix) => {
+    set({ systemPromptPrefix: prefix });
+    persistSettings(get());
+  },
+  setAiContextSource: (source) => {
+    set({ aiContextSource: source });
+    persistSettings(get());
+  },
+  setAiCacheTtlHours: (hours) => {
+    set({ aiCacheTtlHours: hours });
+    persistSettings(get());
+  },
+  setRewriteHotkey: (hotkey) => {
+    set({ rewriteHotkey: hotkey });
+    updateRewriteHotkeyBackend(hotkey);
+    persistSettings(get());
+  },
+  setRewriteAiFunction: (fn) => {
+    set({ rewriteAiFunction: fn });
+    persistSettings(get());
+  },
+  setPasteLastHotkey: (hotkey) => {
+    set({ pasteLastHotkey: hotkey });
+    updatePasteLastHotkeyBackend(hotkey);
+    persistSettings(get());
+  },
+  setUndoPasteHotkey: (hotkey) => {
+    set({ undoPasteHotkey: hotkey });
+    updateUndoPasteHotkeyBackend(hotkey);
+    persistSettings(get());
+  },
+  setHotkeyBackend: (backend) => {
+    set({ hotkeyBackend: backend });
+    updateHotkeyBackendKind(backend);
+    persistSettings(get());
+  },
+  setMouseButton: (button) => {
+    set({ mouseButton: button });
+    updateMouseButtonBackend(button);
+    persistSettings(get());
+  },
+  setDoublePressAi: (value) => {
+    set({ doublePressAi: value });
+    persistSettings(get());
+  },
+  setMinRecordingMs: (ms) => {
+    set({ minRecordingMs: ms });
+    persistSettings(get());
+  },
+  setHandsFreeSilenceSecs: (secs) => {
+    set({ handsFreeSilenceSecs: secs });
+    persistSettings(get());
+  },
+  setLiveTranscript: (value) => {
+    set({ liveTranscript: value });
+    persistSettings(get());
+  },
+  setDecodingOptions: (options) => {
+    set((state) => ({ decodingOptions: { ...state.decodingOptions, ...options } }));
+    persistSettings(get());
+  },
+  setInferenceThreads: (threads) => {
+    set({ inferenceThreads: threads });
+    updateInferenceThreadsBackend(threads, get().lowPowerMode);
+    persistSettings(get());
+  },
+  setLowPowerMode: (value) => {
+    set({ lowPowerMode: value });
+    updateInferenceThreadsBackend(get().inferenceThreads, value);
+    persistSettings(get());
+  },
+  setMaxInferenceSecs: (secs) => {
+    set({ maxInferenceSecs: secs });
+    persistSettings(get());
+  },
+  setParakeetProvider: (provider) => {
+    set({ parakeetProvider: provider });
+    updateParakeetProviderBackend(provider);
+    persistSettings(get());
+  },
+  setInitialPrompt: (prompt) => {
+    set({ initialPrompt: prompt });
+    persistSettings(get());
+  },
+  setPromptTokenBudget: (tokens) => {
+    set({ promptTokenBudget: tokens });
+    persistSettings(get());
+  },
+  setCodeSwitching: (value) => {
+    set({ codeSwitching: value });
+    persistSettings(get());
+  },
+  setCodeSwitchLanguages: (languages) => {
+    set({ codeSwitchLanguages: languages });
+    persistSettings(get());
+  },
+  setVadEnabled: (value) => {
+    set({ vadEnabled: value });
+    persistSettings(get());
+  },
+  setPunctuationEnabled: (value) => {
+    set({ punctuationEnabled: value });
+    persistSettings(get());
+  },
+  setCloudSttTimeoutSecs: (secs) => {
+    set({ cloudSttTimeoutSecs: secs });
+    persistSettings(get());
+  },
+  setCloudSttMaxRetries: (retries) => {
+    set({ cloudSttMaxRetries: retries });
+    persistSettings(get());
+  },
+  setOpenaiSttBaseUrl: (url) => {
+    set({ openaiSttBaseUrl: url });
+    persistSettings(get());
+  },
+  setOpenaiSttModel: (model) => {
+    set({ openaiSttModel: model });
+    persistSettings(get());
+  },
+  setOpenaiSttApiKey: (key) => {
+    set({ openaiSttApiKey: key });
+    persistSettings(get());
+  },
+  setUiLanguage: (language) => {
+    set({ uiLanguage: language });
+    updateUiLanguageBackend(language);
+    persistSettings(get());
+  },
 
   hydrate: async () => {
     try {
@@ -219,7 +849,8 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
           selectedModel: (data.selectedModel as string) ?? "whisper-base",
           selectedLanguage: (data.selectedLanguage as string) ?? "auto",
           selectedAiFunction: (data.selectedAiFunction as string | null) ?? null,
-          recordingMode: (data.recordingMode as "push-to-talk" | "toggle") ?? "push-to-talk",
+          pipelineId: (data.pipelineId as string | null) ?? null,
+          recordingMode: (data.recordingMode as RecordingMode) ?? "push-to-talk",
           rules: ((data.rules as Rule[]) ?? [
             { id: "remove-fillers", name: "Remove Filler Words", enabled: false },
             { id: "smart-punctuation", name: "Smart Punctuation", enabled: false },
@@ -229,15 +860,74 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
           launchAtLogin: (data.launchAtLogin as boolean) ?? false,
           llmProvider: (data.llmProvider as string) ?? "openai",
           providerConfigs: { ...DEFAULT_PROVIDER_CONFIGS, ...providerConfigs },
+          llmFallbackProviders: (data.llmFallbackProviders as string[]) ?? [],
+          keepRulesOnlyText: (data.keepRulesOnlyText as boolean) ?? false,
+          pasteSource: (data.pasteSource as PasteSource) ?? "processed",
+          pasteFormat: (data.pasteFormat as PasteFormat) ?? "markdown",
+          pasteMode: (data.pasteMode as PasteMode) ?? "auto",
+          pasteDelayMs: (data.pasteDelayMs as number) ?? 0,
+          lockPasteTarget: (data.lockPasteTarget as boolean) ?? false,
+          secureFieldPaste: (data.secureFieldPaste as SecureFieldPaste) ?? "block",
+          incognito: (data.incognito as boolean) ?? false,
+          keepOriginalAudio: (data.keepOriginalAudio as boolean) ?? false,
+          automationUrlsEnabled: (data.automationUrlsEnabled as boolean) ?? false,
+          notifyOnTranscription: (data.notifyOnTranscription as boolean) ?? true,
+          preferBuiltInMic: (data.preferBuiltInMic as boolean) ?? false,
+          recordingBarPosition:
+            (data.recordingBarPosition as RecordingBarPosition) ?? "bottom-center",
+          recordingBarMonitor: (data.recordingBarMonitor as string) ?? "primary",
+          spectrumVisualizer: (data.spectrumVisualizer as boolean) ?? false,
+          systemPromptPrefix: (data.systemPromptPrefix as string) ?? "",
+          aiContextSource: (data.aiContextSource as AiContextSource) ?? "none",
+          aiCacheTtlHours: (data.aiCacheTtlHours as number) ?? 24,
+          rewriteHotkey: (data.rewriteHotkey as string | null) ?? null,
+          rewriteAiFunction: (data.rewriteAiFunction as string) ?? "grammar",
+          pasteLastHotkey: (data.pasteLastHotkey as string | null) ?? null,
+          undoPasteHotkey: (data.undoPasteHotkey as string | null) ?? null,
+          hotkeyBackend: (data.hotkeyBackend as HotkeyBackend) ?? "auto",
+          mouseButton: (data.mouseButton as MouseButton) ?? "none",
+          doublePressAi: (data.doublePressAi as boolean) ?? false,
+          minRecordingMs: (data.minRecordingMs as number) ?? 300,
+          handsFreeSilenceSecs: (data.handsFreeSilenceSecs as number) ?? 2,
+          liveTranscript: (data.liveTranscript as boolean) ?? false,
+          decodingOptions: {
+            ...DEFAULT_DECODING_OPTIONS,
+            ...((data.decodingOptions as Partial<DecodingOptions>) ?? {}),
+          },
+          inferenceThreads: (data.inferenceThreads as number) ?? 0,
+          lowPowerMode: (data.lowPowerMode as boolean) ?? false,
+          maxInferenceSecs: (data.maxInferenceSecs as number) ?? 300,
+          parakeetProvider: (data.parakeetProvider as ParakeetProvider) ?? "auto",
+          initialPrompt: (data.initialPrompt as string | null) ?? "",
+          promptTokenBudget: (data.promptTokenBudget as number) ?? 200,
+          codeSwitching: (data.codeSwitching as boolean) ?? false,
+          codeSwitchLanguages: (data.codeSwitchLanguages as string[]) ?? [],
+          vadEnabled: (data.vadEnabled as boolean) ?? false,
+          punctuationEnabled: (data.punctuationEnabled as boolean) ?? false,
+          cloudSttTimeoutSecs: (data.cloudSttTimeoutSecs as number) ?? 120,
+          cloudSttMaxRetries: (data.cloudSttMaxRetries as number) ?? 2,
+          openaiSttBaseUrl: (data.openaiSttBaseUrl as string | null) ?? "",
+          openaiSttModel: (data.openaiSttModel as string) ?? "",
+          openaiSttApiKey: (data.openaiSttApiKey as string) ?? "",
+          uiLanguage: (data.uiLanguage as string) ?? "auto",
           onboardingComplete: (data.onboardingComplete as boolean) ?? false,
           _hydrated: true,
         });
 
-        // Sync saved settings to the backend on startup
+        // Sync saved settings to the backend on startup, then take the rules
+        // from the backend, which owns their state
+        saveSettingsBackend(data).then(() => get().loadRules());
         const savedHotkey = (data.defaultHotkey as string) ?? "Alt+Space";
         const savedMode = (data.recordingMode as string) ?? "push-to-talk";
         updateHotkeyBackend(savedHotkey);
         updateRecordingModeBackend(savedMode);
+        updateIncognitoBackend((data.incognito as boolean) ?? false);
+        const savedRewriteHotkey = (data.rewriteHotkey as string | null) ?? null;
+        if (savedRewriteHotkey) updateRewriteHotkeyBackend(savedRewriteHotkey);
+        const savedPasteLastHotkey = (data.pasteLastHotkey as string | null) ?? null;
+        if (savedPasteLastHotkey) updatePasteLastHotkeyBackend(savedPasteLastHotkey);
+        const savedUndoPasteHotkey = (data.undoPasteHotkey as string | null) ?? null;
+        if (savedUndoPasteHotkey) updateUndoPasteHotkeyBackend(savedUndoPasteHotkey);
         setAutostart((data.launchAtLogin as boolean) ?? false);
       } else {
         set({ _hydrated: true });